@@ -0,0 +1,86 @@
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use proyecto_taller_1::run_redis;
+use std::fs;
+use std::thread;
+use std::time::Duration;
+
+extern crate redis;
+
+/// Cantidad de elementos de la lista grande.
+const LARGE_LIST_LEN: usize = 1_000_000;
+/// Cantidad de elementos que se agregan por cada RPUSH, y de RPUSH por pipeline, al llenar la
+/// lista grande.
+const CHUNK_LEN: usize = 100;
+/// Cantidad de LPUSH que se miden por iteración.
+const PIPELINE_LEN: usize = 1000;
+/// Nombre de los archivos del servidor que se levanta para el benchmark.
+const NAME: &str = "bench_lpush";
+
+/// Levanta un servidor en un puerto propio y devuelve una conexión a él.
+fn start_server() -> redis::Connection {
+    let conf = format!("{}.conf", NAME);
+    fs::write(
+        &conf,
+        format!(
+            "port 8090\ndbfilename {}.rdb\nlogfile {}.log\nloglevel 1\nsave \"\"\n",
+            NAME, NAME
+        ),
+    )
+    .unwrap();
+    thread::spawn(move || {
+        run_redis(vec![conf]).unwrap();
+    });
+    thread::sleep(Duration::from_millis(500));
+
+    redis::Client::open("redis://localhost:8090/")
+        .unwrap()
+        .get_connection()
+        .unwrap()
+}
+
+/// Compara el costo de LPUSH sobre una lista de un elemento y sobre una de un millón. Cada
+/// iteración envía en un pipeline `PIPELINE_LEN` LPUSH seguidos de otros tantos LPOP, para que
+/// las listas no cambien de tamaño y la latencia de la red se reparta entre muchos comandos. Si
+/// LPUSH fuera O(n), la lista grande tardaría órdenes de magnitud más que la chica.
+fn lpush(c: &mut Criterion) {
+    let mut con = start_server();
+    let _: () = redis::cmd("RPUSH")
+        .arg("small")
+        .arg("0")
+        .query(&mut con)
+        .unwrap();
+    // La lista grande se llena de a partes: un único RPUSH de un millón de argumentos tarda
+    // demasiado en decodificarse.
+    let elements: Vec<usize> = (0..LARGE_LIST_LEN).collect();
+    for chunk in elements.chunks(CHUNK_LEN * CHUNK_LEN) {
+        let mut pipe = redis::pipe();
+        for elements in chunk.chunks(CHUNK_LEN) {
+            pipe.cmd("RPUSH").arg("large").arg(elements).ignore();
+        }
+        let _: () = pipe.query(&mut con).unwrap();
+    }
+
+    let mut group = c.benchmark_group("lpush");
+    group.throughput(Throughput::Elements(PIPELINE_LEN as u64));
+    for (name, key) in &[("small_list", "small"), ("1m_list", "large")] {
+        group.bench_function(*name, |b| {
+            b.iter(|| {
+                let mut pipe = redis::pipe();
+                for _ in 0..PIPELINE_LEN {
+                    pipe.cmd("LPUSH").arg(*key).arg("value").ignore();
+                }
+                for _ in 0..PIPELINE_LEN {
+                    pipe.cmd("LPOP").arg(*key).ignore();
+                }
+                let _: () = pipe.query(&mut con).unwrap();
+            })
+        });
+    }
+    group.finish();
+
+    let _ = fs::remove_file(format!("{}.conf", NAME));
+    let _ = fs::remove_file(format!("{}.log", NAME));
+}
+
+criterion_group!(benches, lpush);
+criterion_main!(benches);
@@ -1,3 +1,7 @@
+use crate::entities::append_fsync::AppendFsync;
+use crate::entities::keyspace_events::KeyspaceEvents;
+use crate::entities::maxmemory_policy::MaxmemoryPolicy;
+use crate::entities::save_point::SavePoint;
 use std::fs::File;
 use std::io;
 use std::io::BufRead;
@@ -24,6 +28,36 @@ pub struct Config {
     loglevel: u8,
     /// configfile: guarda en la configuración la ruta del archivo de configuración usado.
     configfile: String,
+    /// appendonly: indica si se registran los comandos de escritura en el archivo AOF.
+    appendonly: bool,
+    /// appendfilename: un string indicando el nombre del archivo AOF.
+    appendfilename: String,
+    /// appendfsync: indica cada cuánto se sincroniza el archivo AOF con el disco.
+    appendfsync: AppendFsync,
+    /// save: reglas de guardado automático de la base de datos, como pares (segundos, cambios).
+    save: Vec<SavePoint>,
+    /// replicaof: host y puerto del master del que el servidor es réplica al iniciar, si lo hay.
+    replicaof: Option<(String, u16)>,
+    /// replica-read-only: indica si una réplica rechaza los comandos de escritura de sus clientes.
+    replica_read_only: bool,
+    /// cluster-enabled: indica si el servidor corre en modo cluster.
+    cluster_enabled: bool,
+    /// cluster-announce-ip: dirección que el nodo informa a los demás nodos del cluster y a los
+    /// clientes en las redirecciones.
+    cluster_announce_ip: String,
+    /// cluster-config-file: un string indicando el nombre del archivo en el cual el nodo guarda su
+    /// configuración del cluster.
+    cluster_config_file: String,
+    /// notify-keyspace-events: clases de eventos de cambios en las claves que se publican por
+    /// PubSub. Por defecto no se publica ninguno.
+    notify_keyspace_events: KeyspaceEvents,
+    /// maxmemory: memoria máxima, en bytes, que pueden ocupar los datos. Si el valor es 0 no hay
+    /// límite.
+    maxmemory: u64,
+    /// maxmemory-policy: qué claves se desalojan al superar `maxmemory`.
+    maxmemory_policy: MaxmemoryPolicy,
+    /// databases: cantidad de bases de datos lógicas, numeradas desde 0.
+    databases: usize,
 }
 
 #[allow(dead_code)]
@@ -38,6 +72,19 @@ impl Config {
             logfile: "log.log".to_string(),
             loglevel: 3,
             configfile: "file.conf".to_string(),
+            appendonly: false,
+            appendfilename: "appendonly.aof".to_string(),
+            appendfsync: AppendFsync::Everysec,
+            save: SavePoint::parse_list("3600 1 300 100 60 10000").unwrap_or_default(),
+            replicaof: None,
+            replica_read_only: true,
+            cluster_enabled: false,
+            cluster_announce_ip: "127.0.0.1".to_string(),
+            cluster_config_file: "nodes.conf".to_string(),
+            notify_keyspace_events: KeyspaceEvents::default(),
+            maxmemory: 0,
+            maxmemory_policy: MaxmemoryPolicy::NoEviction,
+            databases: 16,
         }
     }
 
@@ -48,6 +95,9 @@ impl Config {
         let path = Path::new(&path);
         let file = File::open(path)?;
         let content = BufReader::new(&file);
+        // Las reglas de guardado de cada línea `save` se suman a las anteriores, reemplazando a
+        // las reglas por defecto.
+        let mut save: Option<Vec<SavePoint>> = None;
 
         for line in content.lines() {
             // Remuevo espacios al principio y al final de la línea.
@@ -75,9 +125,35 @@ impl Config {
                 "dbfilename" => config.set_dbfilename(param),
                 "logfile" => config.set_logfile(param),
                 "loglevel" => config.set_loglevel(param),
+                "appendonly" => config.set_appendonly(param),
+                "appendfilename" => config.set_appendfilename(param),
+                "appendfsync" => config.set_appendfsync(param),
+                "replicaof" | "slaveof" => config.set_replicaof(param),
+                "replica-read-only" | "slave-read-only" => config.set_replica_read_only(param),
+                "cluster-enabled" => config.set_cluster_enabled(param),
+                "cluster-announce-ip" => config.set_cluster_announce_ip(param),
+                "cluster-config-file" => config.set_cluster_config_file(param),
+                "notify-keyspace-events" => {
+                    config.set_notify_keyspace_events(param);
+                }
+                "maxmemory" => {
+                    config.set_maxmemory(param);
+                }
+                "maxmemory-policy" => {
+                    config.set_maxmemory_policy(param);
+                }
+                "databases" => config.set_databases(param),
+                "save" => {
+                    if let Some(mut save_points) = SavePoint::parse_list(&param) {
+                        save.get_or_insert_with(Vec::new).append(&mut save_points);
+                    }
+                }
                 _ => (),
             }
         }
+        if let Some(save) = save {
+            config.save = save;
+        }
 
         Ok(config)
     }
@@ -142,6 +218,114 @@ impl Config {
         }
     }
 
+    pub fn set_appendonly(&mut self, appendonly: String) {
+        match appendonly.to_lowercase().as_str() {
+            "yes" => self.appendonly = true,
+            "no" => self.appendonly = false,
+            _ => (),
+        }
+    }
+
+    fn set_appendfilename(&mut self, appendfilename: String) {
+        self.appendfilename = appendfilename;
+    }
+
+    pub fn set_appendfsync(&mut self, appendfsync: String) {
+        if let Some(value) = AppendFsync::parse(&appendfsync) {
+            self.appendfsync = value
+        }
+    }
+
+    /// Reemplaza las reglas de guardado automático. Devuelve false si las reglas son inválidas.
+    pub fn set_save(&mut self, save: String) -> bool {
+        match SavePoint::parse_list(&save) {
+            Some(save_points) => {
+                self.save = save_points;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn set_replicaof(&mut self, replicaof: String) {
+        let parts: Vec<&str> = replicaof.split_whitespace().collect();
+        if let [host, port] = parts.as_slice() {
+            if let Ok(port) = port.parse::<u16>() {
+                self.replicaof = Some((host.to_string(), port));
+            }
+        }
+    }
+
+    pub fn set_replica_read_only(&mut self, replica_read_only: String) {
+        match replica_read_only.to_lowercase().as_str() {
+            "yes" => self.replica_read_only = true,
+            "no" => self.replica_read_only = false,
+            _ => (),
+        }
+    }
+
+    fn set_cluster_enabled(&mut self, cluster_enabled: String) {
+        match cluster_enabled.to_lowercase().as_str() {
+            "yes" => self.cluster_enabled = true,
+            "no" => self.cluster_enabled = false,
+            _ => (),
+        }
+    }
+
+    fn set_cluster_announce_ip(&mut self, cluster_announce_ip: String) {
+        if !cluster_announce_ip.is_empty() {
+            self.cluster_announce_ip = cluster_announce_ip;
+        }
+    }
+
+    fn set_cluster_config_file(&mut self, cluster_config_file: String) {
+        if !cluster_config_file.is_empty() {
+            self.cluster_config_file = cluster_config_file;
+        }
+    }
+
+    /// Reemplaza las clases de eventos que se publican. Devuelve false si el valor es inválido.
+    pub fn set_notify_keyspace_events(&mut self, notify_keyspace_events: String) -> bool {
+        match KeyspaceEvents::parse(&notify_keyspace_events) {
+            Some(events) => {
+                self.notify_keyspace_events = events;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Reemplaza la memoria máxima, indicada en bytes o con una unidad (`k`, `kb`, `m`, `mb`,
+    /// `g`, `gb`). Devuelve false si el valor es inválido.
+    pub fn set_maxmemory(&mut self, maxmemory: String) -> bool {
+        match parse_memory(&maxmemory) {
+            Some(value) => {
+                self.maxmemory = value;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Reemplaza la política de desalojo. Devuelve false si la política es inválida.
+    pub fn set_maxmemory_policy(&mut self, maxmemory_policy: String) -> bool {
+        match MaxmemoryPolicy::parse(&maxmemory_policy) {
+            Some(policy) => {
+                self.maxmemory_policy = policy;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn set_databases(&mut self, databases: String) {
+        if let Ok(databases) = databases.parse::<usize>() {
+            if databases > 0 {
+                self.databases = databases;
+            }
+        }
+    }
+
     pub fn get_port(&self) -> String {
         self.port.to_string()
     }
@@ -169,6 +353,77 @@ impl Config {
     pub fn get_loglevel(&self) -> u8 {
         self.loglevel
     }
+
+    pub fn get_appendonly(&self) -> bool {
+        self.appendonly
+    }
+
+    pub fn get_appendfilename(&self) -> String {
+        self.appendfilename.to_string()
+    }
+
+    pub fn get_appendfsync(&self) -> AppendFsync {
+        self.appendfsync
+    }
+
+    pub fn get_save(&self) -> Vec<SavePoint> {
+        self.save.clone()
+    }
+
+    pub fn get_replicaof(&self) -> Option<(String, u16)> {
+        self.replicaof.clone()
+    }
+
+    pub fn get_replica_read_only(&self) -> bool {
+        self.replica_read_only
+    }
+
+    pub fn get_cluster_enabled(&self) -> bool {
+        self.cluster_enabled
+    }
+
+    pub fn get_cluster_announce_ip(&self) -> String {
+        self.cluster_announce_ip.to_string()
+    }
+
+    pub fn get_cluster_config_file(&self) -> String {
+        self.cluster_config_file.to_string()
+    }
+
+    pub fn get_notify_keyspace_events(&self) -> KeyspaceEvents {
+        self.notify_keyspace_events
+    }
+
+    pub fn get_maxmemory(&self) -> u64 {
+        self.maxmemory
+    }
+
+    pub fn get_maxmemory_policy(&self) -> MaxmemoryPolicy {
+        self.maxmemory_policy
+    }
+
+    pub fn get_databases(&self) -> usize {
+        self.databases
+    }
+}
+
+/// Parsea una cantidad de memoria en bytes, con una unidad opcional: `k` (1000), `kb` (1024),
+/// `m`, `mb`, `g` o `gb`.
+fn parse_memory(value: &str) -> Option<u64> {
+    let value = value.trim().to_lowercase();
+    let units = [
+        ("kb", 1 << 10),
+        ("mb", 1 << 20),
+        ("gb", 1 << 30),
+        ("k", 1_000),
+        ("m", 1_000_000),
+        ("g", 1_000_000_000),
+    ];
+    let (number, unit) = units
+        .iter()
+        .find_map(|(suffix, unit)| Some((value.strip_suffix(suffix)?, *unit)))
+        .unwrap_or((value.as_str(), 1));
+    number.parse::<u64>().ok()?.checked_mul(unit)
 }
 
 fn is_invalid_line(line: &str) -> bool {
@@ -178,7 +433,10 @@ fn is_invalid_line(line: &str) -> bool {
 #[allow(unused_imports)]
 mod test {
     use crate::config::server_config::{is_invalid_line, Config};
+    use crate::entities::append_fsync::AppendFsync;
     use crate::entities::log_level::LogLevel;
+    use crate::entities::maxmemory_policy::MaxmemoryPolicy;
+    use crate::entities::save_point::SavePoint;
     use std::iter::FromIterator;
 
     #[test]
@@ -190,6 +448,139 @@ mod test {
         assert_eq!("dump.rdb".to_string(), config.get_dbfilename());
         assert_eq!("log.log".to_string(), config.get_logfile());
         assert_eq!(3, config.loglevel);
+        assert!(!config.get_appendonly());
+        assert_eq!("appendonly.aof".to_string(), config.get_appendfilename());
+        assert_eq!(AppendFsync::Everysec, config.get_appendfsync());
+    }
+
+    #[test]
+    fn check_append_only_setters() {
+        let mut config = Config::new();
+        config.set_appendonly("yes".to_string());
+        config.set_appendfsync("always".to_string());
+        assert!(config.get_appendonly());
+        assert_eq!(AppendFsync::Always, config.get_appendfsync());
+
+        config.set_appendonly("invalid".to_string());
+        config.set_appendfsync("invalid".to_string());
+        assert!(config.get_appendonly());
+        assert_eq!(AppendFsync::Always, config.get_appendfsync());
+    }
+
+    #[test]
+    fn check_save_setter() {
+        let mut config = Config::new();
+        assert_eq!(3, config.get_save().len());
+
+        assert!(config.set_save("900 1".to_string()));
+        assert_eq!(
+            vec![SavePoint {
+                seconds: 900,
+                changes: 1
+            }],
+            config.get_save()
+        );
+
+        assert!(!config.set_save("900".to_string()));
+        assert_eq!(1, config.get_save().len());
+        assert!(config.set_save("".to_string()));
+        assert!(config.get_save().is_empty());
+    }
+
+    #[test]
+    fn check_save_lines_from_file() {
+        let path = "check_save_lines_from_file.conf";
+        std::fs::write(path, "save 900 1\nsave 300 10 60 100\n").unwrap();
+
+        let config = Config::new_from_file(path.to_string()).unwrap();
+        let _ = std::fs::remove_file(path);
+
+        assert_eq!(
+            "900 1 300 10 60 100",
+            SavePoint::list_to_string(&config.get_save())
+        );
+    }
+
+    #[test]
+    fn check_replication_lines_from_file() {
+        let path = "check_replication_lines_from_file.conf";
+        std::fs::write(path, "replicaof 127.0.0.1 6380\nreplica-read-only no\n").unwrap();
+
+        let config = Config::new_from_file(path.to_string()).unwrap();
+        let _ = std::fs::remove_file(path);
+
+        assert_eq!(
+            Some(("127.0.0.1".to_string(), 6380)),
+            config.get_replicaof()
+        );
+        assert!(!config.get_replica_read_only());
+        assert!(Config::new().get_replica_read_only());
+    }
+
+    #[test]
+    fn check_cluster_lines_from_file() {
+        let path = "check_cluster_lines_from_file.conf";
+        std::fs::write(
+            path,
+            "cluster-enabled yes\ncluster-announce-ip 10.0.0.1\ncluster-config-file node-7000.conf\n",
+        )
+        .unwrap();
+
+        let config = Config::new_from_file(path.to_string()).unwrap();
+        let _ = std::fs::remove_file(path);
+
+        assert!(config.get_cluster_enabled());
+        assert_eq!("10.0.0.1", config.get_cluster_announce_ip());
+        assert_eq!("node-7000.conf", config.get_cluster_config_file());
+        assert!(!Config::new().get_cluster_enabled());
+        assert_eq!("nodes.conf", Config::new().get_cluster_config_file());
+    }
+
+    #[test]
+    fn check_notify_keyspace_events_line_from_file() {
+        let path = "check_notify_keyspace_events_line_from_file.conf";
+        std::fs::write(path, "notify-keyspace-events \"Kx\"\n").unwrap();
+
+        let mut config = Config::new_from_file(path.to_string()).unwrap();
+        let _ = std::fs::remove_file(path);
+
+        assert!(config.get_notify_keyspace_events().notifies('x'));
+        assert!(!config.set_notify_keyspace_events("Kq".to_string()));
+        assert!(config.get_notify_keyspace_events().keyspace());
+        assert!(!Config::new().get_notify_keyspace_events().notifies('x'));
+    }
+
+    #[test]
+    fn check_maxmemory_lines_from_file() {
+        let path = "check_maxmemory_lines_from_file.conf";
+        std::fs::write(path, "maxmemory 100mb\nmaxmemory-policy allkeys-lru\n").unwrap();
+
+        let mut config = Config::new_from_file(path.to_string()).unwrap();
+        let _ = std::fs::remove_file(path);
+
+        assert_eq!(100 * 1024 * 1024, config.get_maxmemory());
+        assert_eq!(MaxmemoryPolicy::AllkeysLru, config.get_maxmemory_policy());
+        assert!(config.set_maxmemory("2k".to_string()));
+        assert_eq!(2000, config.get_maxmemory());
+        assert!(!config.set_maxmemory("lots".to_string()));
+        assert!(!config.set_maxmemory_policy("allkeys-fifo".to_string()));
+        assert_eq!(0, Config::new().get_maxmemory());
+        assert_eq!(
+            MaxmemoryPolicy::NoEviction,
+            Config::new().get_maxmemory_policy()
+        );
+    }
+
+    #[test]
+    fn check_databases_line_from_file() {
+        let path = "check_databases_line_from_file.conf";
+        std::fs::write(path, "databases 4\n").unwrap();
+
+        let config = Config::new_from_file(path.to_string()).unwrap();
+        let _ = std::fs::remove_file(path);
+
+        assert_eq!(4, config.get_databases());
+        assert_eq!(16, Config::new().get_databases());
     }
 
     #[test]
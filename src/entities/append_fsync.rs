@@ -0,0 +1,22 @@
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// AppendFsync: Enum usado para representar cada cuánto se sincroniza el archivo AOF con el disco.
+pub enum AppendFsync {
+    /// Se sincroniza luego de escribir cada comando.
+    Always,
+    /// Se sincroniza una vez por segundo, desde un hilo aparte.
+    Everysec,
+    /// No se sincroniza, se deja que el sistema operativo decida cuándo hacerlo.
+    No,
+}
+
+impl AppendFsync {
+    /// Parsea la política a partir de su nombre: `always`, `everysec` o `no`.
+    pub fn parse(value: &str) -> Option<AppendFsync> {
+        match value.to_lowercase().as_str() {
+            "always" => Some(AppendFsync::Always),
+            "everysec" => Some(AppendFsync::Everysec),
+            "no" => Some(AppendFsync::No),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,37 @@
+use crate::entities::command::Command;
+use crate::entities::reply::Reply;
+use crate::entities::response::Response;
+use std::sync::mpsc::Sender;
+
+#[derive(Debug)]
+/// BlockedClient: cliente bloqueado por BLPOP, BRPOP o BLMOVE hasta que alguna de las listas que
+/// espera tenga elementos.
+pub struct BlockedClient {
+    /// Id del cliente bloqueado.
+    pub client_id: String,
+    /// Base de datos seleccionada por el cliente al bloquearse.
+    pub db: usize,
+    /// Comando bloqueante, que se vuelve a ejecutar cuando alguna de sus listas tiene elementos.
+    pub command: Command,
+    /// Canal por el que se envía la respuesta al cliente.
+    pub sender: Sender<Response>,
+}
+
+impl BlockedClient {
+    /// Devuelve las listas que espera el cliente: cualquiera de ellas lo desbloquea.
+    pub fn keys(&self) -> Vec<&Vec<u8>> {
+        match &self.command {
+            Command::Blpop { keys, .. } | Command::Brpop { keys, .. } => keys.iter().collect(),
+            Command::Blmove { source, .. } => vec![source],
+            _ => vec![],
+        }
+    }
+
+    /// Devuelve la respuesta que recibe el cliente si vence el timeout sin que se desbloquee.
+    pub fn timeout_reply(&self) -> Reply {
+        match &self.command {
+            Command::Blmove { .. } => Reply::NullBulk,
+            _ => Reply::NullArray,
+        }
+    }
+}
@@ -0,0 +1,158 @@
+#[derive(Debug)]
+/// ClusterParam: Enum usado para representar los subcomandos permitidos para el Command::Cluster.
+pub enum ClusterParam {
+    /// Información general del estado del cluster.
+    Info,
+    /// Identificador del nodo.
+    Myid,
+    /// Nodos conocidos, en el formato de CLUSTER NODES.
+    Nodes,
+    /// Rangos de slots asignados y el nodo que atiende cada uno.
+    Slots,
+    /// Slot al que corresponde la clave.
+    Keyslot(Vec<u8>),
+    /// Asigna los slots al nodo (CLUSTER ADDSLOTS y CLUSTER ADDSLOTSRANGE).
+    Addslots(Vec<u16>),
+    /// Agrega al cluster el nodo que atiende en la dirección indicada.
+    Meet(String, u16),
+    /// Cambia el estado de un slot, para migrarlo de un nodo a otro.
+    Setslot(u16, SlotState),
+    /// Cantidad de claves del nodo en el slot.
+    Countkeysinslot(u16),
+    /// Hasta `count` claves del nodo en el slot.
+    Getkeysinslot(u16, usize),
+    /// Mensaje interno con el que los nodos se informan su estado entre sí.
+    Gossip(Gossip),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// SlotState: Estado de un slot indicado con CLUSTER SETSLOT.
+pub enum SlotState {
+    /// El slot se está recibiendo desde el nodo indicado.
+    Importing(String),
+    /// El slot se está enviando al nodo indicado.
+    Migrating(String),
+    /// El slot pasa a ser atendido por el nodo indicado.
+    Node(String),
+    /// El slot deja de estar en migración.
+    Stable,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// Gossip: estado de un nodo, tal como lo informa a los demás nodos del cluster.
+pub struct Gossip {
+    /// Identificador del nodo.
+    pub id: String,
+    /// Dirección en la que atiende a los clientes.
+    pub host: String,
+    /// Puerto en el que atiende a los clientes.
+    pub port: u16,
+    /// Época de configuración del nodo. Ante dos nodos que atienden un mismo slot, se queda el de
+    /// mayor época.
+    pub epoch: u64,
+    /// Rangos (inclusivos) de slots que atiende el nodo.
+    pub slots: Vec<(u16, u16)>,
+    /// Otros nodos que conoce: identificador, dirección y puerto.
+    pub nodes: Vec<(String, String, u16)>,
+}
+
+impl Gossip {
+    /// Devuelve los argumentos del comando `CLUSTER GOSSIP` con el que se envía el mensaje.
+    pub fn to_args(&self) -> Vec<Vec<u8>> {
+        let slots = if self.slots.is_empty() {
+            "-".to_string()
+        } else {
+            self.slots
+                .iter()
+                .map(|(start, end)| format!("{}-{}", start, end))
+                .collect::<Vec<String>>()
+                .join(",")
+        };
+        let mut args = vec![
+            "cluster".to_string(),
+            "gossip".to_string(),
+            self.id.clone(),
+            self.host.clone(),
+            self.port.to_string(),
+            self.epoch.to_string(),
+            slots,
+        ];
+        for (id, host, port) in &self.nodes {
+            args.push(id.clone());
+            args.push(host.clone());
+            args.push(port.to_string());
+        }
+        args.into_iter().map(String::into_bytes).collect()
+    }
+
+    /// Arma el mensaje a partir de los argumentos de `CLUSTER GOSSIP`, sin el nombre del comando
+    /// ni el subcomando.
+    pub fn from_args(params: &[String]) -> Option<Gossip> {
+        if params.len() < 5 || !(params.len() - 5).is_multiple_of(3) {
+            return None;
+        }
+        let slots = if params[4] == "-" {
+            vec![]
+        } else {
+            params[4]
+                .split(',')
+                .map(|range| {
+                    let (start, end) = range.split_once('-')?;
+                    Some((start.parse().ok()?, end.parse().ok()?))
+                })
+                .collect::<Option<Vec<(u16, u16)>>>()?
+        };
+        let nodes = params[5..]
+            .chunks(3)
+            .map(|node| Some((node[0].clone(), node[1].clone(), node[2].parse().ok()?)))
+            .collect::<Option<Vec<(String, String, u16)>>>()?;
+
+        Some(Gossip {
+            id: params[0].clone(),
+            host: params[1].clone(),
+            port: params[2].parse().ok()?,
+            epoch: params[3].parse().ok()?,
+            slots,
+            nodes,
+        })
+    }
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::entities::cluster_param::Gossip;
+
+    #[test]
+    fn test_gossip_args_round_trip() {
+        let gossip = Gossip {
+            id: "a".repeat(40),
+            host: "127.0.0.1".to_string(),
+            port: 7000,
+            epoch: 3,
+            slots: vec![(0, 100), (200, 200)],
+            nodes: vec![("b".repeat(40), "127.0.0.1".to_string(), 7001)],
+        };
+
+        let params: Vec<String> = gossip.to_args()[2..]
+            .iter()
+            .map(|arg| String::from_utf8(arg.clone()).unwrap())
+            .collect();
+
+        assert_eq!(params[4], "0-100,200-200");
+        assert_eq!(Gossip::from_args(&params), Some(gossip));
+    }
+
+    #[test]
+    fn test_gossip_without_slots() {
+        let params: Vec<String> = ["id", "127.0.0.1", "7000", "0", "-"]
+            .iter()
+            .map(|param| param.to_string())
+            .collect();
+
+        let gossip = Gossip::from_args(&params).unwrap();
+
+        assert!(gossip.slots.is_empty());
+        assert!(gossip.nodes.is_empty());
+        assert!(Gossip::from_args(&params[..4]).is_none());
+    }
+}
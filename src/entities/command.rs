@@ -1,15 +1,30 @@
+use crate::entities::cluster_param::ClusterParam;
 use crate::entities::info_param::InfoParam;
 use crate::entities::pubsub_param::PubSubParam;
+use crate::entities::set_param::{SetCondition, SetParam};
+use crate::entities::sort_param::SortParam;
+use crate::entities::sorted_set::ScoreBound;
+use crate::entities::zrange_param::{AggregateParam, ZRangeParam};
+use crate::service::float::format_score;
 use std::collections::HashSet;
 use std::time::{Duration, SystemTime};
 
 #[derive(Debug)]
-#[allow(dead_code)]
+#[allow(dead_code, clippy::enum_variant_names)]
 /// Enum usado para representar los comandos que puede ejecutar nuestra base de datos redis.
 pub enum Command {
     // Server
     Ping,
+    Quit,
+    Select {
+        index: usize,
+    },
+    Swapdb {
+        index1: usize,
+        index2: usize,
+    },
     Flushdb,
+    Flushall,
     Dbsize,
     Monitor,
     Info {
@@ -20,6 +35,27 @@ pub enum Command {
         parameter: String,
         value: String,
     },
+    Bgrewriteaof,
+    Save,
+    Bgsave,
+    Lastsave,
+    Replicaof {
+        master: Option<(String, u16)>,
+    },
+    Role,
+    Replconf {
+        client_id: String,
+        listening_port: Option<u16>,
+    },
+    Psync {
+        replid: String,
+        offset: i64,
+        client_id: String,
+    },
+    Cluster {
+        param: ClusterParam,
+    },
+    Asking,
 
     // System
     Store {
@@ -28,154 +64,460 @@ pub enum Command {
     Load {
         path: String,
     },
+    LoadAof {
+        path: String,
+    },
+    CheckSavePoints,
+    ReplicaFullSync {
+        rdb: Vec<u8>,
+        replid: String,
+        offset: u64,
+        link_id: u64,
+    },
+    MasterCommand {
+        args: Vec<Vec<u8>>,
+        link_id: u64,
+    },
     AddClient,
     RemoveClient,
+    /// Desbloquea al cliente si está bloqueado por BLPOP, BRPOP o BLMOVE, respondiéndole como si
+    /// hubiera vencido el timeout.
+    UnblockClient {
+        client_id: String,
+    },
+    ClusterCron,
+    ActiveExpireCycle,
+    /// Comando enviado por un cliente inmediatamente después de ASKING.
+    AskingCommand {
+        command: Box<Command>,
+    },
+    /// Comando enviado por un cliente, junto a la base de datos que tiene seleccionada.
+    SelectedCommand {
+        db: usize,
+        command: Box<Command>,
+    },
 
     // Strings
     Get {
-        key: String,
+        key: Vec<u8>,
     },
     Set {
-        key: String,
-        value: String,
+        key: Vec<u8>,
+        value: Vec<u8>,
+        param: SetParam,
+    },
+    Setnx {
+        key: Vec<u8>,
+        value: Vec<u8>,
     },
     Incrby {
-        key: String,
-        increment: u32,
+        key: Vec<u8>,
+        increment: i64,
     },
     Decrby {
-        key: String,
-        decrement: u32,
+        key: Vec<u8>,
+        decrement: i64,
+    },
+    Incrbyfloat {
+        key: Vec<u8>,
+        increment: f64,
     },
     Getdel {
-        key: String,
+        key: Vec<u8>,
     },
     Append {
-        key: String,
-        value: String,
+        key: Vec<u8>,
+        value: Vec<u8>,
     },
     Getset {
-        key: String,
-        value: String,
+        key: Vec<u8>,
+        value: Vec<u8>,
     },
     Mget {
-        keys: Vec<String>,
+        keys: Vec<Vec<u8>>,
     },
     Mset {
-        key_values: Vec<(String, String)>,
+        key_values: Vec<(Vec<u8>, Vec<u8>)>,
+    },
+    Msetnx {
+        key_values: Vec<(Vec<u8>, Vec<u8>)>,
     },
     Strlen {
-        key: String,
+        key: Vec<u8>,
     },
 
     // Keys
     Copy {
-        key_origin: String,
-        key_destination: String,
+        key_origin: Vec<u8>,
+        key_destination: Vec<u8>,
     },
     Del {
-        keys: Vec<String>,
+        keys: Vec<Vec<u8>>,
     },
     Exists {
-        keys: Vec<String>,
+        keys: Vec<Vec<u8>>,
     },
     Expire {
-        key: String,
+        key: Vec<u8>,
         ttl: Duration,
     },
     Expireat {
-        key: String,
+        key: Vec<u8>,
         ttl: SystemTime,
     },
     Keys {
-        pattern: String,
+        pattern: Vec<u8>,
+    },
+    Move {
+        key: Vec<u8>,
+        db: usize,
     },
     Persist {
-        key: String,
+        key: Vec<u8>,
     },
     Rename {
-        key_origin: String,
-        key_destination: String,
+        key_origin: Vec<u8>,
+        key_destination: Vec<u8>,
+    },
+    Scan {
+        cursor: u64,
+        pattern: Option<Vec<u8>>,
+        count: usize,
+        value_type: Option<String>,
     },
     Sort {
-        key: String,
+        key: Vec<u8>,
+        param: SortParam,
+    },
+    SortRo {
+        key: Vec<u8>,
+        param: SortParam,
     },
     Touch {
-        keys: Vec<String>,
+        keys: Vec<Vec<u8>>,
     },
     Ttl {
-        key: String,
+        key: Vec<u8>,
     },
     Type {
-        key: String,
+        key: Vec<u8>,
+    },
+    Dump {
+        key: Vec<u8>,
+    },
+    Restore {
+        key: Vec<u8>,
+        /// Expiración en milisegundos (0 si la clave no expira), absoluta si `absttl` es true.
+        ttl: u64,
+        payload: Vec<u8>,
+        replace: bool,
+        absttl: bool,
+        /// Indica si se recibió como RESTORE-ASKING, durante la migración de un slot.
+        asking: bool,
+    },
+    Migrate {
+        host: String,
+        port: u16,
+        keys: Vec<Vec<u8>>,
+        /// Base de datos del nodo destino en la que se guardan las claves.
+        db: usize,
+        timeout: Duration,
+        copy: bool,
+        replace: bool,
     },
 
     // List
+    Blmove {
+        source: Vec<u8>,
+        destination: Vec<u8>,
+        from_left: bool,
+        to_left: bool,
+        timeout: Option<Duration>,
+        client_id: String,
+    },
+    Blpop {
+        keys: Vec<Vec<u8>>,
+        timeout: Option<Duration>,
+        client_id: String,
+    },
+    Brpop {
+        keys: Vec<Vec<u8>>,
+        timeout: Option<Duration>,
+        client_id: String,
+    },
     Lindex {
-        key: String,
+        key: Vec<u8>,
         index: i32,
     },
+    Linsert {
+        key: Vec<u8>,
+        before: bool,
+        pivot: Vec<u8>,
+        element: Vec<u8>,
+    },
     Llen {
-        key: String,
+        key: Vec<u8>,
+    },
+    Lmove {
+        source: Vec<u8>,
+        destination: Vec<u8>,
+        from_left: bool,
+        to_left: bool,
+    },
+    Lmpop {
+        keys: Vec<Vec<u8>>,
+        left: bool,
+        count: usize,
+    },
+    Lpos {
+        key: Vec<u8>,
+        element: Vec<u8>,
+        rank: i64,
+        count: Option<usize>,
+        maxlen: usize,
     },
     Lpush {
-        key: String,
-        value: Vec<String>,
+        key: Vec<u8>,
+        value: Vec<Vec<u8>>,
     },
     Lpushx {
-        key: String,
-        value: Vec<String>,
+        key: Vec<u8>,
+        value: Vec<Vec<u8>>,
     },
     Lpop {
-        key: String,
+        key: Vec<u8>,
         count: usize,
     },
     Lrange {
-        key: String,
+        key: Vec<u8>,
         begin: i32,
         end: i32,
     },
     Lrem {
-        key: String,
+        key: Vec<u8>,
         count: i32,
-        element: String,
+        element: Vec<u8>,
     },
     Lset {
-        key: String,
+        key: Vec<u8>,
         index: i32,
-        element: String,
+        element: Vec<u8>,
+    },
+    Ltrim {
+        key: Vec<u8>,
+        begin: i32,
+        end: i32,
     },
     Rpop {
-        key: String,
+        key: Vec<u8>,
         count: usize,
     },
     Rpush {
-        key: String,
-        value: Vec<String>,
+        key: Vec<u8>,
+        value: Vec<Vec<u8>>,
     },
     Rpushx {
-        key: String,
-        value: Vec<String>,
+        key: Vec<u8>,
+        value: Vec<Vec<u8>>,
     },
 
     // Sets
     Sadd {
-        key: String,
-        values: HashSet<String>,
+        key: Vec<u8>,
+        values: HashSet<Vec<u8>>,
     },
     Scard {
-        key: String,
+        key: Vec<u8>,
+    },
+    Sdiff {
+        keys: Vec<Vec<u8>>,
+    },
+    Sdiffstore {
+        destination: Vec<u8>,
+        keys: Vec<Vec<u8>>,
+    },
+    Sinter {
+        keys: Vec<Vec<u8>>,
+    },
+    Sintercard {
+        keys: Vec<Vec<u8>>,
+        limit: usize,
+    },
+    Sinterstore {
+        destination: Vec<u8>,
+        keys: Vec<Vec<u8>>,
     },
     Sismember {
-        key: String,
-        value: String,
+        key: Vec<u8>,
+        value: Vec<u8>,
     },
     Smembers {
-        key: String,
+        key: Vec<u8>,
+    },
+    Smismember {
+        key: Vec<u8>,
+        members: Vec<Vec<u8>>,
+    },
+    Smove {
+        source: Vec<u8>,
+        destination: Vec<u8>,
+        member: Vec<u8>,
+    },
+    Spop {
+        key: Vec<u8>,
+        count: Option<usize>,
+    },
+    Srandmember {
+        key: Vec<u8>,
+        count: Option<i64>,
+    },
+    Sscan {
+        key: Vec<u8>,
+        cursor: u64,
+        pattern: Option<Vec<u8>>,
+        count: usize,
     },
     Srem {
-        key: String,
-        values: HashSet<String>,
+        key: Vec<u8>,
+        values: HashSet<Vec<u8>>,
+    },
+    Sunion {
+        keys: Vec<Vec<u8>>,
+    },
+    Sunionstore {
+        destination: Vec<u8>,
+        keys: Vec<Vec<u8>>,
+    },
+
+    // Hashes
+    Hdel {
+        key: Vec<u8>,
+        fields: Vec<Vec<u8>>,
+    },
+    Hexists {
+        key: Vec<u8>,
+        field: Vec<u8>,
+    },
+    Hget {
+        key: Vec<u8>,
+        field: Vec<u8>,
+    },
+    Hgetall {
+        key: Vec<u8>,
+    },
+    Hincrby {
+        key: Vec<u8>,
+        field: Vec<u8>,
+        increment: i64,
+    },
+    Hincrbyfloat {
+        key: Vec<u8>,
+        field: Vec<u8>,
+        increment: f64,
+    },
+    Hkeys {
+        key: Vec<u8>,
+    },
+    Hlen {
+        key: Vec<u8>,
+    },
+    Hmget {
+        key: Vec<u8>,
+        fields: Vec<Vec<u8>>,
+    },
+    Hmset {
+        key: Vec<u8>,
+        field_values: Vec<(Vec<u8>, Vec<u8>)>,
+    },
+    Hscan {
+        key: Vec<u8>,
+        cursor: u64,
+        pattern: Option<Vec<u8>>,
+        count: usize,
+    },
+    Hset {
+        key: Vec<u8>,
+        field_values: Vec<(Vec<u8>, Vec<u8>)>,
+    },
+    Hsetnx {
+        key: Vec<u8>,
+        field: Vec<u8>,
+        value: Vec<u8>,
+    },
+    Hstrlen {
+        key: Vec<u8>,
+        field: Vec<u8>,
+    },
+    Hvals {
+        key: Vec<u8>,
+    },
+
+    // Sorted Sets
+    Zadd {
+        key: Vec<u8>,
+        members: Vec<(f64, Vec<u8>)>,
+        nx: bool,
+        xx: bool,
+        gt: bool,
+        lt: bool,
+        ch: bool,
+        incr: bool,
+    },
+    Zcard {
+        key: Vec<u8>,
+    },
+    Zcount {
+        key: Vec<u8>,
+        min: ScoreBound,
+        max: ScoreBound,
+    },
+    Zincrby {
+        key: Vec<u8>,
+        increment: f64,
+        member: Vec<u8>,
+    },
+    Zinterstore {
+        destination: Vec<u8>,
+        keys: Vec<Vec<u8>>,
+        weights: Vec<f64>,
+        aggregate: AggregateParam,
+    },
+    Zpopmax {
+        key: Vec<u8>,
+        count: usize,
+    },
+    Zpopmin {
+        key: Vec<u8>,
+        count: usize,
+    },
+    Zrange {
+        key: Vec<u8>,
+        param: ZRangeParam,
+        rev: bool,
+        limit: Option<(i64, i64)>,
+        with_scores: bool,
+    },
+    Zrank {
+        key: Vec<u8>,
+        member: Vec<u8>,
+    },
+    Zrem {
+        key: Vec<u8>,
+        members: Vec<Vec<u8>>,
+    },
+    Zrevrank {
+        key: Vec<u8>,
+        member: Vec<u8>,
+    },
+    Zscore {
+        key: Vec<u8>,
+        member: Vec<u8>,
+    },
+    Zunionstore {
+        destination: Vec<u8>,
+        keys: Vec<Vec<u8>>,
+        weights: Vec<f64>,
+        aggregate: AggregateParam,
     },
 
     // pubsub
@@ -188,13 +530,39 @@ pub enum Command {
     },
     Publish {
         channel: String,
-        message: String,
+        message: Vec<u8>,
     },
     Unsubscribe {
         channels: Vec<String>,
         client_id: String,
     },
+    Psubscribe {
+        patterns: Vec<String>,
+        client_id: String,
+    },
+    Punsubscribe {
+        patterns: Vec<String>,
+        client_id: String,
+    },
     Command,
+
+    // Transactions
+    Multi,
+    Exec {
+        /// Comandos encolados, o None si la transacción fue descartada por errores al encolar.
+        commands: Option<Vec<Command>>,
+        client_id: String,
+    },
+    Discard {
+        client_id: String,
+    },
+    Watch {
+        keys: Vec<Vec<u8>>,
+        client_id: String,
+    },
+    Unwatch {
+        client_id: String,
+    },
 }
 
 impl Command {
@@ -202,12 +570,26 @@ impl Command {
         match *self {
             // Server
             Command::Ping => "ping",
+            Command::Quit => "quit",
+            Command::Select { .. } => "select",
+            Command::Swapdb { .. } => "swapdb",
             Command::Flushdb => "flushdb",
+            Command::Flushall => "flushall",
             Command::Dbsize => "dbsize",
             Command::Monitor => "monitor",
             Command::Info { .. } => "info",
             Command::ConfigGet => "config get",
             Command::ConfigSet { .. } => "config set",
+            Command::Bgrewriteaof => "bgrewriteaof",
+            Command::Save => "save",
+            Command::Bgsave => "bgsave",
+            Command::Lastsave => "lastsave",
+            Command::Replicaof { .. } => "replicaof",
+            Command::Role => "role",
+            Command::Replconf { .. } => "replconf",
+            Command::Psync { .. } => "psync",
+            Command::Cluster { .. } => "cluster",
+            Command::Asking => "asking",
 
             // Strings
             Command::Append { .. } => "append",
@@ -216,9 +598,12 @@ impl Command {
             Command::Getdel { .. } => "getdel",
             Command::Getset { .. } => "getset",
             Command::Incrby { .. } => "incrby",
+            Command::Incrbyfloat { .. } => "incrbyfloat",
             Command::Mget { .. } => "mget",
             Command::Mset { .. } => "mset",
+            Command::Msetnx { .. } => "msetnx",
             Command::Set { .. } => "set",
+            Command::Setnx { .. } => "setnx",
             Command::Strlen { .. } => "strlen",
 
             // Keys
@@ -227,16 +612,30 @@ impl Command {
             Command::Exists { .. } => "exists",
             Command::Expire { .. } => "expire",
             Command::Expireat { .. } => "expireat",
+            Command::Move { .. } => "move",
             Command::Persist { .. } => "persist",
             Command::Rename { .. } => "rename",
             Command::Keys { .. } => "keys",
+            Command::Scan { .. } => "scan",
+            Command::Sort { .. } => "sort",
+            Command::SortRo { .. } => "sort_ro",
             Command::Touch { .. } => "touch",
             Command::Ttl { .. } => "ttl",
             Command::Type { .. } => "type",
+            Command::Dump { .. } => "dump",
+            Command::Restore { .. } => "restore",
+            Command::Migrate { .. } => "migrate",
 
             // Lists
+            Command::Blmove { .. } => "blmove",
+            Command::Blpop { .. } => "blpop",
+            Command::Brpop { .. } => "brpop",
             Command::Lindex { .. } => "lindex",
+            Command::Linsert { .. } => "linsert",
             Command::Llen { .. } => "llen",
+            Command::Lmove { .. } => "lmove",
+            Command::Lmpop { .. } => "lmpop",
+            Command::Lpos { .. } => "lpos",
             Command::Lpop { .. } => "lpop",
             Command::Lpush { .. } => "lpush",
             Command::Lpushx { .. } => "lpushx",
@@ -250,16 +649,759 @@ impl Command {
             // Sets
             Command::Sadd { .. } => "sadd",
             Command::Scard { .. } => "scard",
+            Command::Sdiff { .. } => "sdiff",
+            Command::Sdiffstore { .. } => "sdiffstore",
+            Command::Sinter { .. } => "sinter",
+            Command::Sintercard { .. } => "sintercard",
+            Command::Sinterstore { .. } => "sinterstore",
             Command::Sismember { .. } => "sismember",
             Command::Smembers { .. } => "smember",
+            Command::Smismember { .. } => "smismember",
+            Command::Smove { .. } => "smove",
+            Command::Spop { .. } => "spop",
+            Command::Srandmember { .. } => "srandmember",
+            Command::Sscan { .. } => "sscan",
             Command::Srem { .. } => "srem",
+            Command::Sunion { .. } => "sunion",
+            Command::Sunionstore { .. } => "sunionstore",
+
+            // Hashes
+            Command::Hdel { .. } => "hdel",
+            Command::Hexists { .. } => "hexists",
+            Command::Hget { .. } => "hget",
+            Command::Hgetall { .. } => "hgetall",
+            Command::Hincrby { .. } => "hincrby",
+            Command::Hincrbyfloat { .. } => "hincrbyfloat",
+            Command::Hkeys { .. } => "hkeys",
+            Command::Hlen { .. } => "hlen",
+            Command::Hmget { .. } => "hmget",
+            Command::Hmset { .. } => "hmset",
+            Command::Hscan { .. } => "hscan",
+            Command::Hset { .. } => "hset",
+            Command::Hsetnx { .. } => "hsetnx",
+            Command::Hstrlen { .. } => "hstrlen",
+            Command::Hvals { .. } => "hvals",
+
+            // Sorted Sets
+            Command::Zadd { .. } => "zadd",
+            Command::Zcard { .. } => "zcard",
+            Command::Zcount { .. } => "zcount",
+            Command::Zincrby { .. } => "zincrby",
+            Command::Zinterstore { .. } => "zinterstore",
+            Command::Zpopmax { .. } => "zpopmax",
+            Command::Zpopmin { .. } => "zpopmin",
+            Command::Zrange { .. } => "zrange",
+            Command::Zrank { .. } => "zrank",
+            Command::Zrem { .. } => "zrem",
+            Command::Zrevrank { .. } => "zrevrank",
+            Command::Zscore { .. } => "zscore",
+            Command::Zunionstore { .. } => "zunionstore",
 
             // Pubsub
             Command::Pubsub { .. } => "pubsub",
             Command::Subscribe { .. } => "subscribe",
             Command::Publish { .. } => "publish",
             Command::Unsubscribe { .. } => "unsubscribe",
+            Command::Psubscribe { .. } => "psubscribe",
+            Command::Punsubscribe { .. } => "punsubscribe",
+
+            // Transactions
+            Command::Multi => "multi",
+            Command::Exec { .. } => "exec",
+            Command::Discard { .. } => "discard",
+            Command::Watch { .. } => "watch",
+            Command::Unwatch { .. } => "unwatch",
             _ => "",
         }
     }
+
+    /// Devuelve los argumentos (nombre del comando incluido) con los que se registra el comando en
+    /// el archivo AOF, o None si el comando no modifica la base de datos.
+    ///
+    /// Las expiraciones se registran como PEXPIREAT con el instante absoluto, para que al
+    /// reproducir el archivo no se vuelvan a contar desde el momento de la carga.
+    pub fn write_args(&self) -> Option<Vec<Vec<u8>>> {
+        let args = match self {
+            Command::Flushdb => vec![b"flushdb".to_vec()],
+            Command::Flushall => vec![b"flushall".to_vec()],
+            Command::Swapdb { index1, index2 } => vec![
+                b"swapdb".to_vec(),
+                index1.to_string().into_bytes(),
+                index2.to_string().into_bytes(),
+            ],
+
+            // Strings
+            Command::Append { key, value } => args("append", &[key, value]),
+            Command::Decrby { key, decrement } => {
+                args("decrby", &[key, &decrement.to_string().into_bytes()])
+            }
+            Command::Getdel { key } => args("getdel", &[key]),
+            Command::Getset { key, value } => args("getset", &[key, value]),
+            Command::Incrby { key, increment } => {
+                args("incrby", &[key, &increment.to_string().into_bytes()])
+            }
+            Command::Incrbyfloat { key, increment } => {
+                args("incrbyfloat", &[key, &increment.to_string().into_bytes()])
+            }
+            Command::Mset { key_values } => with_pairs(vec![b"mset".to_vec()], key_values),
+            Command::Msetnx { key_values } => with_pairs(vec![b"msetnx".to_vec()], key_values),
+            Command::Set { key, value, param } => {
+                // La expiración se registra como PXAT con el instante absoluto, igual que EXPIRE.
+                let mut args = args("set", &[key, value]);
+                match param.condition {
+                    Some(SetCondition::Nx) => args.push(b"nx".to_vec()),
+                    Some(SetCondition::Xx) => args.push(b"xx".to_vec()),
+                    None => {}
+                }
+                if let Some(ttl) = param.expires_at() {
+                    args.push(b"pxat".to_vec());
+                    args.push(unix_millis(ttl));
+                }
+                if param.keep_ttl {
+                    args.push(b"keepttl".to_vec());
+                }
+                args
+            }
+            Command::Setnx { key, value } => args("setnx", &[key, value]),
+
+            // Keys
+            Command::Copy {
+                key_origin,
+                key_destination,
+            } => args("copy", &[key_origin, key_destination]),
+            Command::Del { keys } => with_name("del", keys),
+            Command::Expire { key, ttl } => {
+                args("pexpireat", &[key, &unix_millis(SystemTime::now() + *ttl)])
+            }
+            Command::Expireat { key, ttl } => args("pexpireat", &[key, &unix_millis(*ttl)]),
+            Command::Move { key, db } => args("move", &[key, &db.to_string().into_bytes()]),
+            Command::Persist { key } => args("persist", &[key]),
+            Command::Rename {
+                key_origin,
+                key_destination,
+            } => args("rename", &[key_origin, key_destination]),
+            Command::Sort {
+                key,
+                param: param @ SortParam { store: Some(_), .. },
+            } => {
+                let mut args = args("sort", &[key]);
+                args.extend(param.to_args());
+                args
+            }
+            Command::Restore {
+                key,
+                ttl,
+                payload,
+                absttl,
+                ..
+            } => {
+                // Se registra con la expiración absoluta y reemplazando la clave, para que
+                // reproducir el comando deje siempre el mismo resultado.
+                let ttl = restore_expiration(*ttl, *absttl).map_or(b"0".to_vec(), unix_millis);
+                let mut args = args("restore", &[key, &ttl, payload]);
+                args.push(b"replace".to_vec());
+                args.push(b"absttl".to_vec());
+                args
+            }
+            Command::Migrate { keys, copy, .. } => {
+                if *copy {
+                    return None;
+                }
+                with_name("del", keys)
+            }
+
+            // Lists
+            Command::Lmove {
+                source,
+                destination,
+                from_left,
+                to_left,
+            } => args(
+                "lmove",
+                &[
+                    source,
+                    destination,
+                    &list_side(*from_left),
+                    &list_side(*to_left),
+                ],
+            ),
+            Command::Lpop { key, count } => pop_args("lpop", key, *count),
+            Command::Lpush { key, value } => with_key("lpush", key, value),
+            Command::Lpushx { key, value } => with_key("lpushx", key, value),
+            Command::Lrem {
+                key,
+                count,
+                element,
+            } => args("lrem", &[key, &count.to_string().into_bytes(), element]),
+            Command::Lset {
+                key,
+                index,
+                element,
+            } => args("lset", &[key, &index.to_string().into_bytes(), element]),
+            Command::Ltrim { key, begin, end } => args(
+                "ltrim",
+                &[
+                    key,
+                    &begin.to_string().into_bytes(),
+                    &end.to_string().into_bytes(),
+                ],
+            ),
+            Command::Linsert {
+                key,
+                before,
+                pivot,
+                element,
+            } => {
+                let position = if *before {
+                    b"before"
+                } else {
+                    b"after".as_slice()
+                };
+                args("linsert", &[key, &position.to_vec(), pivot, element])
+            }
+            Command::Lmpop { keys, left, count } => {
+                let mut args = args("lmpop", &[&keys.len().to_string().into_bytes()]);
+                args.extend(keys.iter().cloned());
+                args.push(list_side(*left));
+                args.push(b"count".to_vec());
+                args.push(count.to_string().into_bytes());
+                args
+            }
+            Command::Rpop { key, count } => pop_args("rpop", key, *count),
+            Command::Rpush { key, value } => with_key("rpush", key, value),
+            Command::Rpushx { key, value } => with_key("rpushx", key, value),
+
+            // Sets
+            Command::Sadd { key, values } => {
+                with_key("sadd", key, &values.iter().cloned().collect::<Vec<_>>())
+            }
+            Command::Srem { key, values } => {
+                with_key("srem", key, &values.iter().cloned().collect::<Vec<_>>())
+            }
+            Command::Sdiffstore { destination, keys } => with_key("sdiffstore", destination, keys),
+            Command::Sinterstore { destination, keys } => {
+                with_key("sinterstore", destination, keys)
+            }
+            Command::Sunionstore { destination, keys } => {
+                with_key("sunionstore", destination, keys)
+            }
+            Command::Smove {
+                source,
+                destination,
+                member,
+            } => args("smove", &[source, destination, member]),
+            Command::Spop { key, count } => match count {
+                Some(count) => args("spop", &[key, &count.to_string().into_bytes()]),
+                None => args("spop", &[key]),
+            },
+
+            // Hashes
+            Command::Hdel { key, fields } => with_key("hdel", key, fields),
+            Command::Hincrby {
+                key,
+                field,
+                increment,
+            } => args(
+                "hincrby",
+                &[key, field, &increment.to_string().into_bytes()],
+            ),
+            Command::Hincrbyfloat {
+                key,
+                field,
+                increment,
+            } => args(
+                "hincrbyfloat",
+                &[key, field, &increment.to_string().into_bytes()],
+            ),
+            Command::Hmset { key, field_values } => {
+                with_pairs(vec![b"hmset".to_vec(), key.to_vec()], field_values)
+            }
+            Command::Hset { key, field_values } => {
+                with_pairs(vec![b"hset".to_vec(), key.to_vec()], field_values)
+            }
+            Command::Hsetnx { key, field, value } => args("hsetnx", &[key, field, value]),
+
+            // Sorted Sets
+            Command::Zadd {
+                key,
+                members,
+                nx,
+                xx,
+                gt,
+                lt,
+                ch,
+                incr,
+            } => {
+                let mut args = vec![b"zadd".to_vec(), key.to_vec()];
+                let options = [
+                    (nx, "nx"),
+                    (xx, "xx"),
+                    (gt, "gt"),
+                    (lt, "lt"),
+                    (ch, "ch"),
+                    (incr, "incr"),
+                ];
+                for (enabled, option) in options.iter() {
+                    if **enabled {
+                        args.push(option.as_bytes().to_vec());
+                    }
+                }
+                for (score, member) in members {
+                    args.push(format_score(*score).into_bytes());
+                    args.push(member.to_vec());
+                }
+                args
+            }
+            Command::Zincrby {
+                key,
+                increment,
+                member,
+            } => args(
+                "zincrby",
+                &[key, &increment.to_string().into_bytes(), member],
+            ),
+            Command::Zinterstore {
+                destination,
+                keys,
+                weights,
+                aggregate,
+            } => zstore_args("zinterstore", destination, keys, weights, *aggregate),
+            Command::Zpopmax { key, count } => {
+                args("zpopmax", &[key, &count.to_string().into_bytes()])
+            }
+            Command::Zpopmin { key, count } => {
+                args("zpopmin", &[key, &count.to_string().into_bytes()])
+            }
+            Command::Zrem { key, members } => with_key("zrem", key, members),
+            Command::Zunionstore {
+                destination,
+                keys,
+                weights,
+                aggregate,
+            } => zstore_args("zunionstore", destination, keys, weights, *aggregate),
+            _ => return None,
+        };
+        Some(args)
+    }
+
+    /// Devuelve las claves sobre las que opera el comando. En modo cluster, todas tienen que
+    /// pertenecer a un mismo hash slot atendido por el nodo.
+    pub fn keys(&self) -> Vec<&[u8]> {
+        match self {
+            // Strings
+            Command::Get { key }
+            | Command::Set { key, .. }
+            | Command::Setnx { key, .. }
+            | Command::Incrby { key, .. }
+            | Command::Decrby { key, .. }
+            | Command::Incrbyfloat { key, .. }
+            | Command::Getdel { key }
+            | Command::Append { key, .. }
+            | Command::Getset { key, .. }
+            | Command::Strlen { key }
+            // Keys
+            | Command::Expire { key, .. }
+            | Command::Expireat { key, .. }
+            | Command::Persist { key }
+            | Command::Move { key, .. }
+            | Command::Sort {
+                key,
+                param: SortParam { store: None, .. },
+            }
+            | Command::SortRo { key, .. }
+            | Command::Ttl { key }
+            | Command::Type { key }
+            | Command::Dump { key }
+            | Command::Restore { key, .. }
+            // Lists
+            | Command::Lindex { key, .. }
+            | Command::Llen { key }
+            | Command::Lpush { key, .. }
+            | Command::Lpushx { key, .. }
+            | Command::Lpop { key, .. }
+            | Command::Lrange { key, .. }
+            | Command::Lrem { key, .. }
+            | Command::Lset { key, .. }
+            | Command::Ltrim { key, .. }
+            | Command::Linsert { key, .. }
+            | Command::Lpos { key, .. }
+            | Command::Rpop { key, .. }
+            | Command::Rpush { key, .. }
+            | Command::Rpushx { key, .. }
+            // Sets
+            | Command::Sadd { key, .. }
+            | Command::Scard { key }
+            | Command::Sismember { key, .. }
+            | Command::Smembers { key }
+            | Command::Smismember { key, .. }
+            | Command::Spop { key, .. }
+            | Command::Srandmember { key, .. }
+            | Command::Sscan { key, .. }
+            | Command::Srem { key, .. }
+            // Hashes
+            | Command::Hdel { key, .. }
+            | Command::Hexists { key, .. }
+            | Command::Hget { key, .. }
+            | Command::Hgetall { key }
+            | Command::Hincrby { key, .. }
+            | Command::Hincrbyfloat { key, .. }
+            | Command::Hkeys { key }
+            | Command::Hlen { key }
+            | Command::Hmget { key, .. }
+            | Command::Hmset { key, .. }
+            | Command::Hscan { key, .. }
+            | Command::Hset { key, .. }
+            | Command::Hsetnx { key, .. }
+            | Command::Hstrlen { key, .. }
+            | Command::Hvals { key }
+            // Sorted Sets
+            | Command::Zadd { key, .. }
+            | Command::Zcard { key }
+            | Command::Zcount { key, .. }
+            | Command::Zincrby { key, .. }
+            | Command::Zpopmax { key, .. }
+            | Command::Zpopmin { key, .. }
+            | Command::Zrange { key, .. }
+            | Command::Zrank { key, .. }
+            | Command::Zrem { key, .. }
+            | Command::Zrevrank { key, .. }
+            | Command::Zscore { key, .. } => vec![key],
+
+            Command::Mget { keys }
+            | Command::Del { keys }
+            | Command::Exists { keys }
+            | Command::Touch { keys }
+            | Command::Watch { keys, .. }
+            | Command::Blpop { keys, .. }
+            | Command::Brpop { keys, .. }
+            | Command::Lmpop { keys, .. }
+            | Command::Sdiff { keys }
+            | Command::Sinter { keys }
+            | Command::Sintercard { keys, .. }
+            | Command::Sunion { keys } => keys.iter().map(Vec::as_slice).collect(),
+            Command::Mset { key_values } | Command::Msetnx { key_values } => {
+                key_values.iter().map(|(key, _)| key.as_slice()).collect()
+            }
+            Command::Copy {
+                key_origin,
+                key_destination,
+            }
+            | Command::Rename {
+                key_origin,
+                key_destination,
+            } => vec![key_origin, key_destination],
+            Command::Sort {
+                key,
+                param:
+                    SortParam {
+                        store: Some(destination),
+                        ..
+                    },
+            } => vec![key, destination],
+            Command::Lmove {
+                source,
+                destination,
+                ..
+            }
+            | Command::Blmove {
+                source,
+                destination,
+                ..
+            }
+            | Command::Smove {
+                source,
+                destination,
+                ..
+            } => vec![source, destination],
+            Command::Sdiffstore { destination, keys }
+            | Command::Sinterstore { destination, keys }
+            | Command::Sunionstore { destination, keys } => {
+                let mut all_keys: Vec<&[u8]> = vec![destination];
+                all_keys.extend(keys.iter().map(Vec::as_slice));
+                all_keys
+            }
+            Command::Zinterstore {
+                destination, keys, ..
+            }
+            | Command::Zunionstore {
+                destination, keys, ..
+            } => {
+                let mut all_keys: Vec<&[u8]> = vec![destination];
+                all_keys.extend(keys.iter().map(Vec::as_slice));
+                all_keys
+            }
+            _ => vec![],
+        }
+    }
+
+    /// Indica si el comando puede aumentar la memoria usada, por lo que se rechaza cuando se
+    /// supera `maxmemory` y no se pueden desalojar claves.
+    pub fn denies_oom(&self) -> bool {
+        matches!(
+            self,
+            // Strings
+            Command::Set { .. }
+                | Command::Setnx { .. }
+                | Command::Msetnx { .. }
+                | Command::Getset { .. }
+                | Command::Append { .. }
+                | Command::Incrby { .. }
+                | Command::Decrby { .. }
+                | Command::Incrbyfloat { .. }
+                | Command::Mset { .. }
+                // Lists
+                | Command::Lmove { .. }
+                // Keys
+                | Command::Copy { .. }
+                | Command::Restore { .. }
+                | Command::Sort {
+                    param: SortParam { store: Some(_), .. },
+                    ..
+                }
+                // Lists
+                | Command::Lpush { .. }
+                | Command::Lpushx { .. }
+                | Command::Rpush { .. }
+                | Command::Rpushx { .. }
+                | Command::Lset { .. }
+                | Command::Linsert { .. }
+                // Sets
+                | Command::Sadd { .. }
+                | Command::Sdiffstore { .. }
+                | Command::Sinterstore { .. }
+                | Command::Sunionstore { .. }
+                // Hashes
+                | Command::Hset { .. }
+                | Command::Hmset { .. }
+                | Command::Hsetnx { .. }
+                | Command::Hincrby { .. }
+                | Command::Hincrbyfloat { .. }
+                // Sorted Sets
+                | Command::Zadd { .. }
+                | Command::Zincrby { .. }
+                | Command::Zinterstore { .. }
+                | Command::Zunionstore { .. }
+        )
+    }
+
+    /// Devuelve los eventos de keyspace que puede generar el comando de escritura: la clase del
+    /// evento (ver `KeyspaceEvents`), su nombre y la clave afectada. El evento se notifica sólo si
+    /// el comando modificó la clave.
+    pub fn keyspace_events(&self) -> Vec<(char, &'static str, Vec<u8>)> {
+        let (class, event, keys): (char, &'static str, Vec<&Vec<u8>>) = match self {
+            // Strings
+            Command::Set { key, .. } | Command::Setnx { key, .. } | Command::Getset { key, .. } => {
+                ('$', "set", vec![key])
+            }
+            Command::Incrby { key, .. } => ('$', "incrby", vec![key]),
+            Command::Decrby { key, .. } => ('$', "decrby", vec![key]),
+            Command::Incrbyfloat { key, .. } => ('$', "incrbyfloat", vec![key]),
+            Command::Append { key, .. } => ('$', "append", vec![key]),
+            Command::Mset { key_values } | Command::Msetnx { key_values } => {
+                ('$', "set", key_values.iter().map(|(key, _)| key).collect())
+            }
+
+            // Keys
+            Command::Getdel { key } => ('g', "del", vec![key]),
+            Command::Del { keys } => ('g', "del", keys.iter().collect()),
+            Command::Migrate {
+                keys, copy: false, ..
+            } => ('g', "del", keys.iter().collect()),
+            Command::Expire { key, .. } | Command::Expireat { key, .. } => {
+                ('g', "expire", vec![key])
+            }
+            Command::Persist { key } => ('g', "persist", vec![key]),
+            Command::Move { key, .. } => ('g', "move_from", vec![key]),
+            Command::Restore { key, .. } => ('g', "restore", vec![key]),
+            Command::Copy {
+                key_destination, ..
+            } => ('g', "copy_to", vec![key_destination]),
+            Command::Rename {
+                key_origin,
+                key_destination,
+            } => {
+                return vec![
+                    ('g', "rename_from", key_origin.to_vec()),
+                    ('g', "rename_to", key_destination.to_vec()),
+                ]
+            }
+
+            // Lists
+            Command::Lpush { key, .. } | Command::Lpushx { key, .. } => ('l', "lpush", vec![key]),
+            Command::Rpush { key, .. } | Command::Rpushx { key, .. } => ('l', "rpush", vec![key]),
+            Command::Lpop { key, .. } => ('l', "lpop", vec![key]),
+            Command::Rpop { key, .. } => ('l', "rpop", vec![key]),
+            Command::Lrem { key, .. } => ('l', "lrem", vec![key]),
+            Command::Lset { key, .. } => ('l', "lset", vec![key]),
+            Command::Ltrim { key, .. } => ('l', "ltrim", vec![key]),
+            Command::Linsert { key, .. } => ('l', "linsert", vec![key]),
+            Command::Lmpop { keys, left, .. } => (
+                'l',
+                if *left { "lpop" } else { "rpop" },
+                keys.iter().collect(),
+            ),
+            Command::Lmove {
+                source,
+                destination,
+                from_left,
+                to_left,
+            } => {
+                return vec![
+                    (
+                        'l',
+                        if *from_left { "lpop" } else { "rpop" },
+                        source.to_vec(),
+                    ),
+                    (
+                        'l',
+                        if *to_left { "lpush" } else { "rpush" },
+                        destination.to_vec(),
+                    ),
+                ]
+            }
+            Command::Sort {
+                param:
+                    SortParam {
+                        store: Some(destination),
+                        ..
+                    },
+                ..
+            } => ('l', "sortstore", vec![destination]),
+
+            // Sets
+            Command::Sadd { key, .. } => ('s', "sadd", vec![key]),
+            Command::Srem { key, .. } => ('s', "srem", vec![key]),
+            Command::Spop { key, .. } => ('s', "spop", vec![key]),
+            Command::Sdiffstore { destination, .. } => ('s', "sdiffstore", vec![destination]),
+            Command::Sinterstore { destination, .. } => ('s', "sinterstore", vec![destination]),
+            Command::Sunionstore { destination, .. } => ('s', "sunionstore", vec![destination]),
+            Command::Smove {
+                source,
+                destination,
+                ..
+            } => {
+                return vec![
+                    ('s', "srem", source.to_vec()),
+                    ('s', "sadd", destination.to_vec()),
+                ]
+            }
+
+            // Hashes
+            Command::Hset { key, .. }
+            | Command::Hmset { key, .. }
+            | Command::Hsetnx { key, .. } => ('h', "hset", vec![key]),
+            Command::Hdel { key, .. } => ('h', "hdel", vec![key]),
+            Command::Hincrby { key, .. } => ('h', "hincrby", vec![key]),
+            Command::Hincrbyfloat { key, .. } => ('h', "hincrbyfloat", vec![key]),
+
+            // Sorted Sets
+            Command::Zadd {
+                key, incr: true, ..
+            }
+            | Command::Zincrby { key, .. } => ('z', "zincr", vec![key]),
+            Command::Zadd { key, .. } => ('z', "zadd", vec![key]),
+            Command::Zrem { key, .. } => ('z', "zrem", vec![key]),
+            Command::Zpopmax { key, .. } => ('z', "zpopmax", vec![key]),
+            Command::Zpopmin { key, .. } => ('z', "zpopmin", vec![key]),
+            Command::Zinterstore { destination, .. } => ('z', "zinterstore", vec![destination]),
+            Command::Zunionstore { destination, .. } => ('z', "zunionstore", vec![destination]),
+            _ => return vec![],
+        };
+        keys.into_iter()
+            .map(|key| (class, event, key.to_vec()))
+            .collect()
+    }
+}
+
+/// Arma los argumentos de un comando a partir de su nombre y sus parámetros.
+fn args(name: &str, params: &[&Vec<u8>]) -> Vec<Vec<u8>> {
+    let mut args = vec![name.as_bytes().to_vec()];
+    args.extend(params.iter().map(|param| param.to_vec()));
+    args
+}
+
+/// Devuelve el extremo de la lista indicado en LMOVE: LEFT o RIGHT.
+fn list_side(left: bool) -> Vec<u8> {
+    if left {
+        b"left".to_vec()
+    } else {
+        b"right".to_vec()
+    }
+}
+
+/// Arma los argumentos de un comando que recibe una lista de parámetros.
+fn with_name(name: &str, params: &[Vec<u8>]) -> Vec<Vec<u8>> {
+    let mut args = vec![name.as_bytes().to_vec()];
+    args.extend_from_slice(params);
+    args
+}
+
+/// Arma los argumentos de un comando que recibe una clave y una lista de parámetros.
+fn with_key(name: &str, key: &[u8], params: &[Vec<u8>]) -> Vec<Vec<u8>> {
+    let mut args = vec![name.as_bytes().to_vec(), key.to_vec()];
+    args.extend_from_slice(params);
+    args
+}
+
+/// Agrega pares clave-valor (o campo-valor) a los argumentos de un comando.
+fn with_pairs(mut args: Vec<Vec<u8>>, pairs: &[(Vec<u8>, Vec<u8>)]) -> Vec<Vec<u8>> {
+    for (key, value) in pairs {
+        args.push(key.to_vec());
+        args.push(value.to_vec());
+    }
+    args
+}
+
+/// Arma los argumentos de LPOP y RPOP. Una cantidad 0 indica que el comando no la recibió.
+fn pop_args(name: &str, key: &[u8], count: usize) -> Vec<Vec<u8>> {
+    let mut args = vec![name.as_bytes().to_vec(), key.to_vec()];
+    if count > 0 {
+        args.push(count.to_string().into_bytes());
+    }
+    args
+}
+
+/// Arma los argumentos de ZUNIONSTORE y ZINTERSTORE.
+fn zstore_args(
+    name: &str,
+    destination: &[u8],
+    keys: &[Vec<u8>],
+    weights: &[f64],
+    aggregate: AggregateParam,
+) -> Vec<Vec<u8>> {
+    let mut args = vec![name.as_bytes().to_vec(), destination.to_vec()];
+    args.push(keys.len().to_string().into_bytes());
+    args.extend_from_slice(keys);
+    args.push(b"weights".to_vec());
+    args.extend(weights.iter().map(|weight| weight.to_string().into_bytes()));
+    args.push(b"aggregate".to_vec());
+    args.push(
+        match aggregate {
+            AggregateParam::Sum => "sum",
+            AggregateParam::Min => "min",
+            AggregateParam::Max => "max",
+        }
+        .as_bytes()
+        .to_vec(),
+    );
+    args
+}
+
+/// Devuelve el instante en el que expira una clave restaurada con RESTORE, a partir de su
+/// expiración en milisegundos (0 si no expira), absoluta o relativa al momento actual.
+pub fn restore_expiration(ttl: u64, absttl: bool) -> Option<SystemTime> {
+    match (ttl, absttl) {
+        (0, _) => None,
+        (ttl, true) => Some(SystemTime::UNIX_EPOCH + Duration::from_millis(ttl)),
+        (ttl, false) => Some(SystemTime::now() + Duration::from_millis(ttl)),
+    }
+}
+
+/// Convierte un instante a milisegundos desde el UNIX_EPOCH, como texto.
+pub fn unix_millis(time: SystemTime) -> Vec<u8> {
+    time.duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_else(|_| Duration::from_secs(0))
+        .as_millis()
+        .to_string()
+        .into_bytes()
 }
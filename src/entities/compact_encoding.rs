@@ -0,0 +1,216 @@
+use std::convert::TryInto;
+
+/// Byte que marca el fin de un ziplist o de un listpack.
+const END: u8 = 0xff;
+/// Tamaño del encabezado de un ziplist: bytes totales, offset al último elemento y cantidad.
+const ZIPLIST_HEADER: usize = 10;
+/// Tamaño del encabezado de un listpack: bytes totales y cantidad de elementos.
+const LISTPACK_HEADER: usize = 6;
+/// Tamaño del encabezado de un intset: tamaño de cada entero y cantidad de elementos.
+const INTSET_HEADER: usize = 8;
+
+/// Lee `N` bytes a partir de la posición indicada.
+fn bytes<const N: usize>(blob: &[u8], position: usize) -> Option<[u8; N]> {
+    blob.get(position..position + N)?.try_into().ok()
+}
+
+/// Lee un entero con signo little endian de `len` bytes.
+fn int_le(blob: &[u8], position: usize, len: usize) -> Option<i64> {
+    let bytes = blob.get(position..position + len)?;
+    let mut value: i64 = 0;
+    for (index, byte) in bytes.iter().enumerate() {
+        value |= (*byte as i64) << (8 * index);
+    }
+    // Extiende el signo del entero leído.
+    let unused = 64 - 8 * len as u32;
+    Some((value << unused) >> unused)
+}
+
+/// Devuelve los elementos de un ziplist, como bytes. Los enteros se devuelven en decimal.
+pub fn ziplist_entries(blob: &[u8]) -> Option<Vec<Vec<u8>>> {
+    let mut entries = Vec::new();
+    let mut position = ZIPLIST_HEADER;
+
+    while *blob.get(position)? != END {
+        // Largo del elemento anterior: 1 byte, o 0xfe seguido de 4 bytes.
+        position += if *blob.get(position)? < 0xfe { 1 } else { 5 };
+
+        let encoding = *blob.get(position)?;
+        let (entry, len): (Vec<u8>, usize) = match encoding >> 6 {
+            0b00 => string_at(blob, position + 1, (encoding & 0x3f) as usize)?,
+            0b01 => {
+                let len = (((encoding & 0x3f) as usize) << 8) | *blob.get(position + 1)? as usize;
+                let (entry, len) = string_at(blob, position + 2, len)?;
+                (entry, len + 1)
+            }
+            0b10 => {
+                let len = u32::from_be_bytes(bytes(blob, position + 1)?) as usize;
+                let (entry, len) = string_at(blob, position + 5, len)?;
+                (entry, len + 4)
+            }
+            _ => {
+                let (value, len) = match encoding {
+                    0xc0 => (int_le(blob, position + 1, 2)?, 3),
+                    0xd0 => (int_le(blob, position + 1, 4)?, 5),
+                    0xe0 => (int_le(blob, position + 1, 8)?, 9),
+                    0xf0 => (int_le(blob, position + 1, 3)?, 4),
+                    0xfe => (int_le(blob, position + 1, 1)?, 2),
+                    0xf1..=0xfd => ((encoding & 0x0f) as i64 - 1, 1),
+                    _ => return None,
+                };
+                (value.to_string().into_bytes(), len)
+            }
+        };
+        entries.push(entry);
+        position += len;
+    }
+    Some(entries)
+}
+
+/// Devuelve el string de largo `len` que empieza en `start`, y el largo total del elemento
+/// contando el byte de codificación.
+fn string_at(blob: &[u8], start: usize, len: usize) -> Option<(Vec<u8>, usize)> {
+    Some((blob.get(start..start + len)?.to_vec(), len + 1))
+}
+
+/// Devuelve los elementos de un listpack, como bytes. Los enteros se devuelven en decimal.
+pub fn listpack_entries(blob: &[u8]) -> Option<Vec<Vec<u8>>> {
+    let mut entries = Vec::new();
+    let mut position = LISTPACK_HEADER;
+
+    while *blob.get(position)? != END {
+        let encoding = *blob.get(position)?;
+        let (entry, len): (Vec<u8>, usize) = if encoding & 0x80 == 0 {
+            ((encoding & 0x7f).to_string().into_bytes(), 1)
+        } else if encoding & 0xc0 == 0x80 {
+            string_at(blob, position + 1, (encoding & 0x3f) as usize)?
+        } else if encoding & 0xe0 == 0xc0 {
+            let value = (((encoding & 0x1f) as i64) << 8) | *blob.get(position + 1)? as i64;
+            let value = if value >= 1 << 12 {
+                value - (1 << 13)
+            } else {
+                value
+            };
+            (value.to_string().into_bytes(), 2)
+        } else if encoding & 0xf0 == 0xe0 {
+            let len = (((encoding & 0x0f) as usize) << 8) | *blob.get(position + 1)? as usize;
+            let (entry, len) = string_at(blob, position + 2, len)?;
+            (entry, len + 1)
+        } else {
+            match encoding {
+                0xf0 => {
+                    let len = u32::from_le_bytes(bytes(blob, position + 1)?) as usize;
+                    let (entry, len) = string_at(blob, position + 5, len)?;
+                    (entry, len + 4)
+                }
+                0xf1 => (int_le(blob, position + 1, 2)?.to_string().into_bytes(), 3),
+                0xf2 => (int_le(blob, position + 1, 3)?.to_string().into_bytes(), 4),
+                0xf3 => (int_le(blob, position + 1, 4)?.to_string().into_bytes(), 5),
+                0xf4 => (int_le(blob, position + 1, 8)?.to_string().into_bytes(), 9),
+                _ => return None,
+            }
+        };
+        entries.push(entry);
+        position += len + backlen_size(len);
+    }
+    Some(entries)
+}
+
+/// Devuelve cuántos bytes ocupa el largo hacia atrás que sigue a un elemento de un listpack.
+fn backlen_size(len: usize) -> usize {
+    match len {
+        0..=127 => 1,
+        128..=16382 => 2,
+        16383..=2097150 => 3,
+        2097151..=268435454 => 4,
+        _ => 5,
+    }
+}
+
+/// Devuelve los elementos de un intset, como enteros en decimal.
+pub fn intset_entries(blob: &[u8]) -> Option<Vec<Vec<u8>>> {
+    let encoding = u32::from_le_bytes(bytes(blob, 0)?) as usize;
+    let len = u32::from_le_bytes(bytes(blob, 4)?) as usize;
+    if encoding != 2 && encoding != 4 && encoding != 8 {
+        return None;
+    }
+
+    (0..len)
+        .map(|index| {
+            int_le(blob, INTSET_HEADER + index * encoding, encoding)
+                .map(|value| value.to_string().into_bytes())
+        })
+        .collect()
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::entities::compact_encoding::{intset_entries, listpack_entries, ziplist_entries};
+
+    #[allow(dead_code)]
+    fn texts(entries: Vec<Vec<u8>>) -> Vec<String> {
+        entries
+            .into_iter()
+            .map(|entry| String::from_utf8(entry).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_ziplist_entries() {
+        // Ziplist con "ab", 5 (entero inmediato), -2 (int8) y 1000 (int16).
+        let blob = [
+            0x18, 0x00, 0x00, 0x00, 0x13, 0x00, 0x00, 0x00, 0x04, 0x00, // encabezado
+            0x00, 0x02, b'a', b'b', // "ab"
+            0x04, 0xf6, // 5
+            0x02, 0xfe, 0xfe, // -2
+            0x03, 0xc0, 0xe8, 0x03, // 1000
+            0xff,
+        ];
+
+        assert_eq!(
+            texts(ziplist_entries(&blob).unwrap()),
+            vec!["ab", "5", "-2", "1000"]
+        );
+    }
+
+    #[test]
+    fn test_listpack_entries() {
+        // Listpack con "ab", 5 (7 bits), -2 (13 bits) y 100000 (int24).
+        let blob = [
+            0x15, 0x00, 0x00, 0x00, 0x04, 0x00, // encabezado
+            0x82, b'a', b'b', 0x03, // "ab"
+            0x05, 0x01, // 5
+            0xdf, 0xfe, 0x02, // -2
+            0xf2, 0xa0, 0x86, 0x01, 0x04, // 100000
+            0xff,
+        ];
+
+        assert_eq!(
+            texts(listpack_entries(&blob).unwrap()),
+            vec!["ab", "5", "-2", "100000"]
+        );
+    }
+
+    #[test]
+    fn test_intset_entries() {
+        let blob = [
+            0x02, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, // encabezado
+            0xff, 0xff, 0x01, 0x00, 0x00, 0x01,
+        ];
+
+        assert_eq!(
+            texts(intset_entries(&blob).unwrap()),
+            vec!["-1", "1", "256"]
+        );
+    }
+
+    #[test]
+    fn test_truncated_blob_is_none() {
+        assert!(ziplist_entries(&[
+            0x0b, 0x00, 0x00, 0x00, 0x0a, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x05, b'a'
+        ])
+        .is_none());
+        assert!(listpack_entries(&[0x08, 0x00, 0x00, 0x00, 0x01, 0x00, 0x85]).is_none());
+        assert!(intset_entries(&[0x02, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x01]).is_none());
+    }
+}
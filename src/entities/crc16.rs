@@ -0,0 +1,48 @@
+/// Polinomio de la variante XMODEM del CRC16, la usada por Redis Cluster para asignar las claves a
+/// los hash slots.
+const POLY: u16 = 0x1021;
+
+/// Tabla de restos precalculada para procesar un byte por paso.
+const TABLE: [u16; 256] = table();
+
+const fn table() -> [u16; 256] {
+    let mut table = [0u16; 256];
+    let mut byte = 0;
+    while byte < 256 {
+        let mut crc = (byte as u16) << 8;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ POLY
+            } else {
+                crc << 1
+            };
+            bit += 1;
+        }
+        table[byte] = crc;
+        byte += 1;
+    }
+    table
+}
+
+/// Calcula el CRC16 de los bytes.
+pub fn crc16(bytes: &[u8]) -> u16 {
+    bytes.iter().fold(0, |crc, byte| {
+        TABLE[(((crc >> 8) as u8) ^ *byte) as usize] ^ (crc << 8)
+    })
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::entities::crc16::crc16;
+
+    #[test]
+    fn test_crc16_check_value() {
+        assert_eq!(crc16(b"123456789"), 0x31c3);
+    }
+
+    #[test]
+    fn test_crc16_empty() {
+        assert_eq!(crc16(b""), 0);
+    }
+}
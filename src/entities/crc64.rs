@@ -0,0 +1,49 @@
+/// Polinomio (reflejado) de la variante Jones del CRC64, la usada por Redis en los archivos RDB.
+const POLY: u64 = 0x95ac_9329_ac4b_c9b5;
+
+/// Tabla de restos precalculada para procesar un byte por paso.
+const TABLE: [u64; 256] = table();
+
+const fn table() -> [u64; 256] {
+    let mut table = [0u64; 256];
+    let mut byte = 0;
+    while byte < 256 {
+        let mut crc = byte as u64;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ POLY
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[byte] = crc;
+        byte += 1;
+    }
+    table
+}
+
+/// Calcula el CRC64 de los bytes, partiendo del CRC `crc` (0 para un cálculo nuevo). Es el
+/// checksum que Redis guarda al final de los archivos RDB.
+pub fn crc64(crc: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(crc, |crc, byte| {
+        TABLE[((crc ^ *byte as u64) & 0xff) as usize] ^ (crc >> 8)
+    })
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::entities::crc64::crc64;
+
+    #[test]
+    fn test_crc64_check_value() {
+        assert_eq!(crc64(0, b"123456789"), 0xe9c6_d914_c4b8_d9ca);
+    }
+
+    #[test]
+    fn test_crc64_incremental() {
+        let crc = crc64(0, b"1234");
+        assert_eq!(crc64(crc, b"56789"), crc64(0, b"123456789"));
+    }
+}
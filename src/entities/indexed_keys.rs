@@ -0,0 +1,93 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+#[derive(Debug)]
+/// IndexedKeys: conjunto de claves guardadas en un vector, junto a la posición de cada una. Permite
+/// agregar, borrar y acceder por posición en O(1), por lo que se usa para elegir claves al azar y
+/// para recorrerlas por tramos sin volver a empezar. Al borrar una clave, la última ocupa su lugar.
+pub struct IndexedKeys<K: Eq + Hash> {
+    keys: Vec<K>,
+    positions: HashMap<K, usize>,
+}
+
+impl<K: Clone + Eq + Hash> IndexedKeys<K> {
+    /// Constructor de un conjunto vacío.
+    pub fn new() -> Self {
+        IndexedKeys {
+            keys: Vec::new(),
+            positions: HashMap::new(),
+        }
+    }
+
+    /// Agrega la clave, si no estaba.
+    pub fn insert(&mut self, key: &K) {
+        if !self.positions.contains_key(key) {
+            self.positions.insert(key.clone(), self.keys.len());
+            self.keys.push(key.clone());
+        }
+    }
+
+    /// Borra la clave, moviendo la última a su posición.
+    pub fn remove(&mut self, key: &K) {
+        if let Some(position) = self.positions.remove(key) {
+            self.keys.swap_remove(position);
+            if let Some(moved) = self.keys.get(position) {
+                self.positions.insert(moved.clone(), position);
+            }
+        }
+    }
+
+    /// Borra todas las claves.
+    pub fn clear(&mut self) {
+        self.keys.clear();
+        self.positions.clear();
+    }
+
+    /// Devuelve la cantidad de claves.
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Devuelve hasta `count` claves a partir de la posición `start`.
+    pub fn range(&self, start: usize, count: usize) -> &[K] {
+        let start = start.min(self.keys.len());
+        let end = start.saturating_add(count).min(self.keys.len());
+        &self.keys[start..end]
+    }
+
+    /// Devuelve la clave de la posición indicada, volviendo al principio si la posición supera la
+    /// cantidad de claves. Devuelve None si no hay claves.
+    pub fn get_wrapping(&self, position: usize) -> Option<&K> {
+        match self.keys.len() {
+            0 => None,
+            len => self.keys.get(position % len),
+        }
+    }
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::entities::indexed_keys::IndexedKeys;
+
+    #[test]
+    fn test_indexed_keys_insert_and_remove() {
+        let mut keys: IndexedKeys<&str> = IndexedKeys::new();
+        keys.insert(&"a");
+        keys.insert(&"b");
+        keys.insert(&"c");
+        keys.insert(&"a");
+
+        assert_eq!(keys.len(), 3);
+        keys.remove(&"a");
+        keys.remove(&"missing");
+        assert_eq!(keys.range(0, 10), &["c", "b"]);
+        keys.remove(&"b");
+        assert_eq!(keys.range(0, 10), &["c"]);
+        assert_eq!(keys.get_wrapping(5), Some(&"c"));
+
+        keys.clear();
+        assert_eq!(keys.len(), 0);
+        assert_eq!(keys.get_wrapping(0), None);
+        assert!(keys.range(3, 2).is_empty());
+    }
+}
@@ -13,4 +13,12 @@ pub enum InfoParam {
     ConfigFile,
     /// Utilizado para el parametro ConnectedClients del Comando Info
     ConnectedClients,
+    /// Utilizado para el parametro Persistence del Comando Info
+    Persistence,
+    /// Utilizado para el parametro Stats del Comando Info
+    Stats,
+    /// Utilizado para el parametro Memory del Comando Info
+    Memory,
+    /// Utilizado para el parametro Keyspace del Comando Info
+    Keyspace,
 }
@@ -0,0 +1,125 @@
+/// Clases de eventos que se pueden habilitar, en el orden en el que se muestran:
+///
+/// * `g` - Comandos genéricos, como DEL, EXPIRE o RENAME.
+/// * `$` - Comandos de strings.
+/// * `l` - Comandos de listas.
+/// * `s` - Comandos de sets.
+/// * `h` - Comandos de hashes.
+/// * `z` - Comandos de sorted sets.
+/// * `x` - Claves que expiraron.
+/// * `e` - Claves desalojadas por falta de memoria.
+const CLASSES: &str = "g$lshzxe";
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+/// KeyspaceEvents: notificaciones de cambios en las claves habilitadas con la opción
+/// `notify-keyspace-events`. Los eventos se publican en `__keyspace@<db>__:<clave>` (K) y/o en
+/// `__keyevent@<db>__:<evento>` (E), sólo para las clases habilitadas.
+pub struct KeyspaceEvents {
+    /// Indica si se publica en los canales `__keyspace@<db>__:<clave>`.
+    keyspace: bool,
+    /// Indica si se publica en los canales `__keyevent@<db>__:<evento>`.
+    keyevent: bool,
+    /// Clases habilitadas, un bit por cada caracter de `CLASSES`.
+    classes: u8,
+}
+
+impl KeyspaceEvents {
+    /// Parsea las notificaciones a partir de los caracteres de la opción, como `KEx`. `A` es un
+    /// alias de todas las clases. Un valor vacío (o `""`) deshabilita las notificaciones.
+    pub fn parse(value: &str) -> Option<KeyspaceEvents> {
+        let mut events = KeyspaceEvents::default();
+        for flag in value.trim().trim_matches('"').chars() {
+            match flag {
+                'K' => events.keyspace = true,
+                'E' => events.keyevent = true,
+                'A' => events.classes |= KeyspaceEvents::all_classes(),
+                class => events.classes |= KeyspaceEvents::class_bit(class)?,
+            }
+        }
+        Some(events)
+    }
+
+    /// Indica si se publican los eventos de la clase.
+    pub fn notifies(&self, class: char) -> bool {
+        KeyspaceEvents::class_bit(class)
+            .is_some_and(|bit| (self.keyspace || self.keyevent) && self.classes & bit != 0)
+    }
+
+    /// Indica si se publica en los canales `__keyspace@<db>__:<clave>`.
+    pub fn keyspace(&self) -> bool {
+        self.keyspace
+    }
+
+    /// Indica si se publica en los canales `__keyevent@<db>__:<evento>`.
+    pub fn keyevent(&self) -> bool {
+        self.keyevent
+    }
+
+    fn class_bit(class: char) -> Option<u8> {
+        CLASSES.find(class).map(|index| 1 << index)
+    }
+
+    fn all_classes() -> u8 {
+        // Todas las clases menos la de claves desalojadas, igual que en Redis.
+        KeyspaceEvents::class_bit('e').map_or(0, |evicted| !evicted)
+    }
+}
+
+impl std::fmt::Display for KeyspaceEvents {
+    /// Muestra las notificaciones en el mismo formato que recibe `parse`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let all = KeyspaceEvents::all_classes();
+        let mut value = String::new();
+        if self.classes & all == all {
+            value.push('A');
+        }
+        for (index, class) in CLASSES.chars().enumerate() {
+            let bit = 1 << index;
+            if self.classes & bit != 0 && (all & bit == 0 || self.classes & all != all) {
+                value.push(class);
+            }
+        }
+        if self.keyspace {
+            value.push('K');
+        }
+        if self.keyevent {
+            value.push('E');
+        }
+        write!(f, "{}", value)
+    }
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::entities::keyspace_events::KeyspaceEvents;
+
+    #[test]
+    fn test_parse_keyspace_events() {
+        let events = KeyspaceEvents::parse("Kx$").unwrap();
+
+        assert!(events.keyspace());
+        assert!(!events.keyevent());
+        assert!(events.notifies('x'));
+        assert!(events.notifies('$'));
+        assert!(!events.notifies('g'));
+        assert_eq!(events.to_string(), "$xK");
+        assert!(KeyspaceEvents::parse("Kw").is_none());
+    }
+
+    #[test]
+    fn test_parse_all_classes() {
+        let events = KeyspaceEvents::parse("AE").unwrap();
+        let classes_without_channel = KeyspaceEvents::parse("A").unwrap();
+
+        assert!(events.notifies('g'));
+        assert!(events.notifies('x'));
+        assert!(!events.notifies('e'));
+        assert!(!classes_without_channel.notifies('g'));
+        assert_eq!(events.to_string(), "AE");
+        assert_eq!(KeyspaceEvents::parse("AKEe").unwrap().to_string(), "AeKE");
+        assert_eq!(
+            KeyspaceEvents::parse("\"\"").unwrap(),
+            KeyspaceEvents::default()
+        );
+    }
+}
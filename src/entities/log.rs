@@ -1,5 +1,6 @@
 use crate::entities::log_level::LogLevel;
 use crate::service::timestamp_to_string::timestamp_to_string;
+use std::fmt;
 use std::time::SystemTime;
 
 #[derive(Debug, Clone)]
@@ -35,28 +36,25 @@ impl Log {
     }
 }
 
-impl ToString for Log {
+impl fmt::Display for Log {
     /// Trait: impl usado para poder transformar un Log a tipo String.
-    fn to_string(&self) -> String {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         let level = match self.level {
             LogLevel::Debug => "[DEBUG]",
             LogLevel::Info => "[INFO] ",
             LogLevel::Error => "[ERROR]",
         };
 
-        level.to_owned()
-            + " - "
-            + &timestamp_to_string(SystemTime::now())
-            + " UTC"
-            + " - "
-            + &self.file
-            + " - "
-            + &self.line.to_string()
-            + ":"
-            + &self.col.to_string()
-            + " - "
-            + &self.msg
-            + "\n"
+        writeln!(
+            fmt,
+            "{} - {} UTC - {} - {}:{} - {}",
+            level,
+            timestamp_to_string(SystemTime::now()),
+            self.file,
+            self.line,
+            self.col,
+            self.msg
+        )
     }
 }
 
@@ -0,0 +1,157 @@
+/// Cantidad máxima de literales en una misma secuencia.
+const MAX_LITERALS: usize = 32;
+/// Distancia máxima hacia atrás de una referencia.
+const MAX_OFFSET: usize = 1 << 13;
+/// Longitud máxima de una referencia.
+const MAX_REFERENCE: usize = (1 << 8) + (1 << 3);
+/// Cantidad de bits de la tabla de hash usada para buscar repeticiones.
+const HASH_LOG: u32 = 14;
+
+/// Comprime los bytes con el algoritmo LZF, el usado por Redis para los strings de los archivos RDB.
+///
+/// El resultado es una secuencia de bloques: literales (`000LLLLL` seguido de L+1 bytes) o
+/// referencias a bytes ya escritos (`LLLOOOOO [L] OOOOOOOO`).
+pub fn compress(input: &[u8]) -> Vec<u8> {
+    let mut output = Vec::with_capacity(input.len());
+    let mut literals: Vec<u8> = Vec::with_capacity(MAX_LITERALS);
+    let mut table = vec![0usize; 1 << HASH_LOG];
+    let mut index = 0;
+
+    while index < input.len() {
+        if index + 2 < input.len() {
+            let hash = hash(&input[index..index + 3]);
+            // Las posiciones se guardan desplazadas en uno, para que 0 indique vacío.
+            let candidate = table[hash];
+            table[hash] = index + 1;
+
+            if candidate > 0 && index - candidate < MAX_OFFSET {
+                let reference = candidate - 1;
+                let max = MAX_REFERENCE.min(input.len() - index);
+                let mut len = 0;
+                while len < max && input[reference + len] == input[index + len] {
+                    len += 1;
+                }
+
+                if len >= 3 {
+                    flush_literals(&mut output, &mut literals);
+                    let offset = index - reference - 1;
+                    let encoded_len = len - 2;
+                    if encoded_len < 7 {
+                        output.push(((encoded_len as u8) << 5) | (offset >> 8) as u8);
+                    } else {
+                        output.push((7 << 5) | (offset >> 8) as u8);
+                        output.push((encoded_len - 7) as u8);
+                    }
+                    output.push(offset as u8);
+                    index += len;
+                    continue;
+                }
+            }
+        }
+
+        literals.push(input[index]);
+        if literals.len() == MAX_LITERALS {
+            flush_literals(&mut output, &mut literals);
+        }
+        index += 1;
+    }
+    flush_literals(&mut output, &mut literals);
+    output
+}
+
+/// Descomprime bytes comprimidos con LZF. Devuelve None si los datos están corruptos o si no
+/// ocupan exactamente `len` bytes al descomprimirse.
+pub fn decompress(input: &[u8], len: usize) -> Option<Vec<u8>> {
+    let mut output: Vec<u8> = Vec::with_capacity(len);
+    let mut index = 0;
+
+    while index < input.len() {
+        let ctrl = input[index] as usize;
+        index += 1;
+
+        if ctrl < MAX_LITERALS {
+            let literals = input.get(index..index + ctrl + 1)?;
+            output.extend_from_slice(literals);
+            index += ctrl + 1;
+        } else {
+            let mut reference_len = ctrl >> 5;
+            if reference_len == 7 {
+                reference_len += *input.get(index)? as usize;
+                index += 1;
+            }
+            let offset = ((ctrl & 0x1f) << 8) + *input.get(index)? as usize + 1;
+            index += 1;
+
+            let start = output.len().checked_sub(offset)?;
+            for position in start..start + reference_len + 2 {
+                let byte = output[position];
+                output.push(byte);
+            }
+        }
+
+        if output.len() > len {
+            return None;
+        }
+    }
+
+    if output.len() == len {
+        Some(output)
+    } else {
+        None
+    }
+}
+
+fn hash(bytes: &[u8]) -> usize {
+    let value = ((bytes[0] as u32) << 16) | ((bytes[1] as u32) << 8) | bytes[2] as u32;
+    (value.wrapping_mul(2_654_435_761) >> (32 - HASH_LOG)) as usize
+}
+
+fn flush_literals(output: &mut Vec<u8>, literals: &mut Vec<u8>) {
+    if !literals.is_empty() {
+        output.push((literals.len() - 1) as u8);
+        output.append(literals);
+    }
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::entities::lzf::{compress, decompress};
+
+    #[test]
+    fn test_compress_and_decompress_repetitive_input() {
+        let input = "abcabcabcabcabcabcabcabcabcabcabcabc"
+            .repeat(20)
+            .into_bytes();
+
+        let compressed = compress(&input);
+
+        assert!(compressed.len() < input.len() / 4);
+        assert_eq!(decompress(&compressed, input.len()), Some(input));
+    }
+
+    #[test]
+    fn test_compress_and_decompress_binary_input() {
+        let input: Vec<u8> = (0..1000).map(|i| ((i * 7919) % 251) as u8).collect();
+
+        let compressed = compress(&input);
+
+        assert_eq!(decompress(&compressed, input.len()), Some(input));
+    }
+
+    #[test]
+    fn test_decompress_overlapping_reference() {
+        // Un literal 'a' seguido de una referencia de 29 bytes a distancia 1.
+        let compressed = [0x00, b'a', 0xe0, 0x14, 0x00];
+
+        assert_eq!(
+            decompress(&compressed, 30),
+            Some("a".repeat(30).into_bytes())
+        );
+    }
+
+    #[test]
+    fn test_decompress_corrupt_input() {
+        assert_eq!(decompress(&[0x20, 0x05], 3), None);
+        assert_eq!(decompress(&[0x02, b'a'], 3), None);
+    }
+}
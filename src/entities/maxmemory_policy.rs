@@ -0,0 +1,83 @@
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// MaxmemoryPolicy: Enum usado para representar qué claves se desalojan cuando la memoria usada
+/// supera `maxmemory`.
+pub enum MaxmemoryPolicy {
+    /// No se desaloja ninguna clave: los comandos que pueden usar más memoria fallan.
+    NoEviction,
+    /// Se desalojan las claves usadas hace más tiempo.
+    AllkeysLru,
+    /// Se desalojan las claves con expiración usadas hace más tiempo.
+    VolatileLru,
+    /// Se desalojan las claves usadas con menos frecuencia.
+    AllkeysLfu,
+    /// Se desalojan las claves con expiración usadas con menos frecuencia.
+    VolatileLfu,
+    /// Se desalojan claves al azar.
+    AllkeysRandom,
+    /// Se desalojan claves con expiración al azar.
+    VolatileRandom,
+    /// Se desalojan las claves con expiración más próxima.
+    VolatileTtl,
+}
+
+impl MaxmemoryPolicy {
+    /// Parsea la política a partir de su nombre, como `allkeys-lru`.
+    pub fn parse(value: &str) -> Option<MaxmemoryPolicy> {
+        match value.to_lowercase().as_str() {
+            "noeviction" => Some(MaxmemoryPolicy::NoEviction),
+            "allkeys-lru" => Some(MaxmemoryPolicy::AllkeysLru),
+            "volatile-lru" => Some(MaxmemoryPolicy::VolatileLru),
+            "allkeys-lfu" => Some(MaxmemoryPolicy::AllkeysLfu),
+            "volatile-lfu" => Some(MaxmemoryPolicy::VolatileLfu),
+            "allkeys-random" => Some(MaxmemoryPolicy::AllkeysRandom),
+            "volatile-random" => Some(MaxmemoryPolicy::VolatileRandom),
+            "volatile-ttl" => Some(MaxmemoryPolicy::VolatileTtl),
+            _ => None,
+        }
+    }
+
+    /// Devuelve el nombre de la política, en el mismo formato que recibe `parse`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MaxmemoryPolicy::NoEviction => "noeviction",
+            MaxmemoryPolicy::AllkeysLru => "allkeys-lru",
+            MaxmemoryPolicy::VolatileLru => "volatile-lru",
+            MaxmemoryPolicy::AllkeysLfu => "allkeys-lfu",
+            MaxmemoryPolicy::VolatileLfu => "volatile-lfu",
+            MaxmemoryPolicy::AllkeysRandom => "allkeys-random",
+            MaxmemoryPolicy::VolatileRandom => "volatile-random",
+            MaxmemoryPolicy::VolatileTtl => "volatile-ttl",
+        }
+    }
+
+    /// Indica si la política sólo desaloja claves con expiración.
+    pub fn is_volatile(&self) -> bool {
+        matches!(
+            self,
+            MaxmemoryPolicy::VolatileLru
+                | MaxmemoryPolicy::VolatileLfu
+                | MaxmemoryPolicy::VolatileRandom
+                | MaxmemoryPolicy::VolatileTtl
+        )
+    }
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::entities::maxmemory_policy::MaxmemoryPolicy;
+
+    #[test]
+    fn test_parse_maxmemory_policy() {
+        assert_eq!(
+            MaxmemoryPolicy::parse("ALLKEYS-LRU"),
+            Some(MaxmemoryPolicy::AllkeysLru)
+        );
+        assert_eq!(
+            MaxmemoryPolicy::parse("volatile-ttl").map(|policy| policy.as_str()),
+            Some("volatile-ttl")
+        );
+        assert!(MaxmemoryPolicy::VolatileLfu.is_volatile());
+        assert!(!MaxmemoryPolicy::AllkeysRandom.is_volatile());
+        assert!(MaxmemoryPolicy::parse("lru").is_none());
+    }
+}
@@ -1,8 +1,30 @@
+pub mod append_fsync;
+pub mod blocked_client;
+pub mod cluster_param;
 pub mod command;
+pub mod compact_encoding;
+pub mod crc16;
+pub mod crc64;
+pub mod indexed_keys;
 pub mod info_param;
+pub mod keyspace_events;
 pub mod log;
 pub mod log_level;
+pub mod lzf;
+pub mod maxmemory_policy;
 pub mod pubsub_param;
 pub mod redis_element;
+pub mod redis_hash;
+pub mod redis_set;
+pub mod reply;
 pub mod response;
+pub mod save_point;
+pub mod scan_cursor;
+pub mod set_operation;
+pub mod set_param;
+pub mod skip_list;
+pub mod sort_param;
+pub mod sorted_set;
+pub mod transaction;
 pub mod ttl_hash_map;
+pub mod zrange_param;
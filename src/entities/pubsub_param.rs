@@ -3,10 +3,12 @@
 pub enum PubSubParam {
     /// Representa el Parametro Channels de PubSub con canales vacíos.
     Channels,
-    /// Representa el Parametro Channels de PubSub con canal especifico.
-    ChannelsWithChannel(String),
+    /// Representa el Parametro Channels de PubSub con un patrón: los canales que coinciden con él.
+    ChannelsWithPattern(String),
     /// Representa el Parametro NumSub de PubSub con canales vacíos.
     Numsub,
     /// Representa el Parametro Numsub de PubSub con canales específicos.
     NumsubWithChannels(Vec<String>),
+    /// Representa el Parametro Numpat de PubSub: la cantidad de patrones suscriptos.
+    Numpat,
 }
@@ -1,4 +1,7 @@
-use std::collections::HashSet;
+use crate::entities::redis_hash::RedisHash;
+use crate::entities::redis_set::RedisSet;
+use crate::entities::sorted_set::SortedSet;
+use std::collections::VecDeque;
 use std::fmt;
 
 #[allow(dead_code)]
@@ -6,29 +9,42 @@ use std::fmt;
 /// RedisElement: Enum usado para representar los tipos posibles a ser almacenados en nuestra base de
 /// datos redis.
 pub enum RedisElement {
-    /// Representa los tipos de dato String de Redis
-    String(String),
+    /// Representa los tipos de dato String de Redis. Son binarios, por lo que se guardan como bytes.
+    String(Vec<u8>),
     /// Representa los tipos de dato String especiales de Redis
     SimpleString(String),
     /// Representa los tipos de dato Set de Redis
-    Set(HashSet<String>),
-    /// Representa los tipos de dato List de Redis
-    List(Vec<String>),
-    /// Representa los tipos de dato Nil de Redis
+    Set(RedisSet),
+    /// Representa los tipos de dato List de Redis. Se guardan en un VecDeque para que agregar y
+    /// sacar elementos de ambos extremos no dependa del largo de la lista.
+    List(VecDeque<Vec<u8>>),
+    /// Representa los tipos de dato Hash de Redis
+    Hash(RedisHash),
+    /// Representa los tipos de dato Sorted Set de Redis
+    SortedSet(SortedSet),
+    /// Representa los tipos de dato Nil de Redis (bulk string nulo)
     Nil,
 }
 
+/// Convierte los bytes a un string para mostrarlo, reemplazando los bytes que no son UTF-8
+/// válido y el separador de elementos.
+fn display_bytes(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes).replace(" - ", "-")
+}
+
 impl fmt::Display for RedisElement {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            RedisElement::String(s) => write!(fmt, "{}", s.replace(" - ", "-"))?,
+            RedisElement::String(s) => write!(fmt, "{}", display_bytes(s))?,
             RedisElement::Set(set) => {
                 write!(fmt, "{{")?;
 
                 let mut set = set.iter();
-                write!(fmt, "{}", set.next().unwrap().replace(" - ", "-"))?;
+                if let Some(element) = set.next() {
+                    write!(fmt, "{}", display_bytes(element))?;
+                }
                 for element in set {
-                    write!(fmt, " - {}", element.replace(" - ", "-"))?;
+                    write!(fmt, " - {}", display_bytes(element))?;
                 }
                 write!(fmt, "}}")?;
             }
@@ -39,13 +55,37 @@ impl fmt::Display for RedisElement {
                     write!(fmt, "[")?;
 
                     let mut list = list.iter();
-                    write!(fmt, "{}", list.next().unwrap().replace(" - ", "-"))?;
+                    write!(fmt, "{}", display_bytes(list.next().unwrap()))?;
                     for element in list {
-                        write!(fmt, " - {}", element.replace(" - ", "-"))?;
+                        write!(fmt, " - {}", display_bytes(element))?;
                     }
                     write!(fmt, "]")?;
                 }
             }
+            RedisElement::Hash(hash) => {
+                write!(fmt, "{{")?;
+
+                let mut hash = hash.iter();
+                if let Some((field, value)) = hash.next() {
+                    write!(fmt, "{}: {}", display_bytes(field), display_bytes(value))?;
+                }
+                for (field, value) in hash {
+                    write!(fmt, " - {}: {}", display_bytes(field), display_bytes(value))?;
+                }
+                write!(fmt, "}}")?;
+            }
+            RedisElement::SortedSet(sorted_set) => {
+                write!(fmt, "[")?;
+
+                let mut sorted_set = sorted_set.iter();
+                if let Some((member, score)) = sorted_set.next() {
+                    write!(fmt, "{}: {}", display_bytes(member), score)?;
+                }
+                for (member, score) in sorted_set {
+                    write!(fmt, " - {}: {}", display_bytes(member), score)?;
+                }
+                write!(fmt, "]")?;
+            }
             RedisElement::Nil => {
                 write!(fmt, "(nil)")?;
             }
@@ -55,28 +95,72 @@ impl fmt::Display for RedisElement {
     }
 }
 
+/// Cantidad de elementos de una colección que se miden para estimar su memoria, igual que el valor
+/// por defecto de `MEMORY USAGE` en Redis.
+const MEMORY_SAMPLES: usize = 5;
+/// Memoria aproximada que ocupa cada elemento de una colección, además de su contenido.
+const ELEMENT_OVERHEAD: usize = 16;
+/// Memoria aproximada que ocupa cada valor, además de su contenido.
+const VALUE_OVERHEAD: usize = 16;
+
+impl RedisElement {
+    /// Devuelve la memoria aproximada que ocupa el valor. En las colecciones se miden algunos
+    /// elementos y se extrapola al total, para que el costo no dependa de su tamaño.
+    pub fn memory_usage(&self) -> usize {
+        VALUE_OVERHEAD
+            + match self {
+                RedisElement::String(bytes) => bytes.len(),
+                RedisElement::SimpleString(string) => string.len(),
+                RedisElement::List(list) => sampled_usage(list.len(), list.iter().map(Vec::len)),
+                RedisElement::Set(set) => sampled_usage(set.len(), set.iter().map(Vec::len)),
+                RedisElement::Hash(hash) => sampled_usage(
+                    hash.len(),
+                    hash.iter().map(|(field, value)| field.len() + value.len()),
+                ),
+                RedisElement::SortedSet(sorted_set) => sampled_usage(
+                    sorted_set.len(),
+                    // Cada miembro se guarda en el mapa de puntajes y en el índice ordenado.
+                    sorted_set.iter().map(|(member, _)| 2 * member.len() + 16),
+                ),
+                _ => 0,
+            }
+    }
+}
+
+/// Estima la memoria de una colección de `len` elementos a partir de los tamaños de algunos de
+/// ellos.
+fn sampled_usage(len: usize, sizes: impl Iterator<Item = usize>) -> usize {
+    let (count, total) = sizes
+        .take(MEMORY_SAMPLES)
+        .fold((0, 0), |(count, total), size| (count + 1, total + size));
+    if count == 0 {
+        return 0;
+    }
+    len * (total / count + ELEMENT_OVERHEAD)
+}
+
 impl From<&str> for RedisElement {
     fn from(s: &str) -> Self {
         if s.starts_with("(nil)") {
             RedisElement::Nil
         } else if s.starts_with('{') && s.ends_with('}') {
-            let mut set: HashSet<String> = HashSet::new();
+            let mut set = RedisSet::new();
             let s = s.strip_prefix('{').unwrap().strip_suffix('}').unwrap();
 
             for element in s.split(" - ") {
-                set.insert(element.to_string());
+                set.insert(element.as_bytes().to_vec());
             }
             RedisElement::Set(set)
         } else if s.starts_with('[') && s.ends_with(']') {
-            let mut list: Vec<String> = Vec::new();
+            let mut list: VecDeque<Vec<u8>> = VecDeque::new();
             let s = s.strip_prefix('[').unwrap().strip_suffix(']').unwrap();
 
             for element in s.split(" - ") {
-                list.push(element.to_string());
+                list.push_back(element.as_bytes().to_vec());
             }
             RedisElement::List(list)
         } else {
-            RedisElement::String(s.to_string())
+            RedisElement::String(s.as_bytes().to_vec())
         }
     }
 }
@@ -0,0 +1,106 @@
+use crate::entities::scan_cursor::ScanIndex;
+use std::collections::HashMap;
+use std::iter::FromIterator;
+use std::ops::Deref;
+
+#[derive(Debug, Clone, Default)]
+/// RedisHash: hash de Redis. Los campos y sus valores se guardan en un HashMap, al que se accede
+/// para leerlos, y los campos además en un índice con su posición en el recorrido de HSCAN, que se
+/// actualiza al agregar y borrar campos.
+pub struct RedisHash {
+    fields: HashMap<Vec<u8>, Vec<u8>>,
+    index: ScanIndex,
+}
+
+impl PartialEq for RedisHash {
+    fn eq(&self, other: &Self) -> bool {
+        self.fields == other.fields
+    }
+}
+
+impl Deref for RedisHash {
+    type Target = HashMap<Vec<u8>, Vec<u8>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.fields
+    }
+}
+
+impl From<HashMap<Vec<u8>, Vec<u8>>> for RedisHash {
+    fn from(fields: HashMap<Vec<u8>, Vec<u8>>) -> Self {
+        fields.into_iter().collect()
+    }
+}
+
+impl FromIterator<(Vec<u8>, Vec<u8>)> for RedisHash {
+    fn from_iter<I: IntoIterator<Item = (Vec<u8>, Vec<u8>)>>(fields: I) -> Self {
+        let mut hash = RedisHash::new();
+        for (field, value) in fields {
+            hash.insert(field, value);
+        }
+        hash
+    }
+}
+
+impl IntoIterator for RedisHash {
+    type Item = (Vec<u8>, Vec<u8>);
+    type IntoIter = std::collections::hash_map::IntoIter<Vec<u8>, Vec<u8>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.fields.into_iter()
+    }
+}
+
+impl RedisHash {
+    /// Constructor de un nuevo RedisHash vacío.
+    pub fn new() -> Self {
+        RedisHash::default()
+    }
+
+    /// Guarda el valor del campo. Devuelve el valor anterior, o None si el campo no existía.
+    pub fn insert(&mut self, field: Vec<u8>, value: Vec<u8>) -> Option<Vec<u8>> {
+        if !self.fields.contains_key(&field) {
+            self.index.insert(&field);
+        }
+        self.fields.insert(field, value)
+    }
+
+    /// Borra el campo. Devuelve su valor, o None si no existía.
+    pub fn remove(&mut self, field: &[u8]) -> Option<Vec<u8>> {
+        let value = self.fields.remove(field)?;
+        self.index.remove(field);
+        Some(value)
+    }
+
+    /// Devuelve al menos `count` campos (o todos los que quedan) a partir de la posición `cursor`
+    /// del recorrido de HSCAN, junto al cursor con el que se continúa, que es 0 si terminó.
+    pub fn scan(&self, cursor: u64, count: usize) -> (u64, Vec<&Vec<u8>>) {
+        self.index.page(cursor, count)
+    }
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::entities::redis_hash::RedisHash;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_redis_hash_scans_current_fields() {
+        let mut hash: RedisHash = (0..10)
+            .map(|i| (format!("f{}", i).into_bytes(), b"v".to_vec()))
+            .collect();
+
+        assert_eq!(
+            hash.insert(b"f0".to_vec(), b"w".to_vec()),
+            Some(b"v".to_vec())
+        );
+        assert_eq!(hash.remove(b"f1"), Some(b"v".to_vec()));
+        assert_eq!(hash.remove(b"f1"), None);
+        assert_eq!(hash.len(), 9);
+
+        let (cursor, fields) = hash.scan(0, 100);
+        assert_eq!(cursor, 0);
+        let fields: HashSet<&Vec<u8>> = fields.into_iter().collect();
+        assert_eq!(fields, hash.keys().collect());
+    }
+}
@@ -0,0 +1,108 @@
+use crate::entities::scan_cursor::ScanIndex;
+use std::collections::HashSet;
+use std::iter::FromIterator;
+use std::ops::Deref;
+
+#[derive(Debug, Clone, Default)]
+/// RedisSet: set de Redis. Los miembros se guardan en un HashSet, al que se accede para leerlos,
+/// y además en un índice con su posición en el recorrido de SSCAN, que se actualiza al agregar y
+/// borrar miembros.
+pub struct RedisSet {
+    members: HashSet<Vec<u8>>,
+    index: ScanIndex,
+}
+
+impl PartialEq for RedisSet {
+    fn eq(&self, other: &Self) -> bool {
+        self.members == other.members
+    }
+}
+
+impl Deref for RedisSet {
+    type Target = HashSet<Vec<u8>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.members
+    }
+}
+
+impl From<HashSet<Vec<u8>>> for RedisSet {
+    fn from(members: HashSet<Vec<u8>>) -> Self {
+        members.into_iter().collect()
+    }
+}
+
+impl FromIterator<Vec<u8>> for RedisSet {
+    fn from_iter<I: IntoIterator<Item = Vec<u8>>>(members: I) -> Self {
+        let mut set = RedisSet::new();
+        set.extend(members);
+        set
+    }
+}
+
+impl Extend<Vec<u8>> for RedisSet {
+    fn extend<I: IntoIterator<Item = Vec<u8>>>(&mut self, members: I) {
+        for member in members {
+            self.insert(member);
+        }
+    }
+}
+
+impl IntoIterator for RedisSet {
+    type Item = Vec<u8>;
+    type IntoIter = std::collections::hash_set::IntoIter<Vec<u8>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.members.into_iter()
+    }
+}
+
+impl RedisSet {
+    /// Constructor de un nuevo RedisSet vacío.
+    pub fn new() -> Self {
+        RedisSet::default()
+    }
+
+    /// Agrega el miembro. Devuelve true si no estaba.
+    pub fn insert(&mut self, member: Vec<u8>) -> bool {
+        if self.members.contains(&member) {
+            return false;
+        }
+        self.index.insert(&member);
+        self.members.insert(member)
+    }
+
+    /// Borra el miembro. Devuelve true si estaba.
+    pub fn remove(&mut self, member: &[u8]) -> bool {
+        self.index.remove(member);
+        self.members.remove(member)
+    }
+
+    /// Devuelve al menos `count` miembros (o todos los que quedan) a partir de la posición
+    /// `cursor` del recorrido de SSCAN, junto al cursor con el que se continúa, que es 0 si
+    /// terminó.
+    pub fn scan(&self, cursor: u64, count: usize) -> (u64, Vec<&Vec<u8>>) {
+        self.index.page(cursor, count)
+    }
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::entities::redis_set::RedisSet;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_redis_set_scans_current_members() {
+        let mut set: RedisSet = (0..10).map(|i| format!("m{}", i).into_bytes()).collect();
+
+        assert!(!set.insert(b"m0".to_vec()));
+        assert!(set.remove(b"m1"));
+        assert!(!set.remove(b"m1"));
+        assert_eq!(set.len(), 9);
+
+        let (cursor, members) = set.scan(0, 100);
+        assert_eq!(cursor, 0);
+        let members: HashSet<Vec<u8>> = members.into_iter().cloned().collect();
+        assert_eq!(&members, &*set);
+    }
+}
@@ -0,0 +1,107 @@
+use crate::entities::redis_element::RedisElement;
+use crate::service::float::format_score;
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq)]
+/// Reply: Enum usado para representar las respuestas de los comandos, con los tipos de respuesta
+/// que define el protocolo de Redis.
+pub enum Reply {
+    /// Respuesta de estado, como `OK` o `PONG`.
+    SimpleString(String),
+    /// Respuesta de tipo bulk string. Es binaria, por lo que se guarda como bytes.
+    BulkString(Vec<u8>),
+    /// Respuesta de tipo entero.
+    Integer(i64),
+    /// Respuesta de tipo array, cuyos elementos pueden ser de distintos tipos.
+    Array(Vec<Reply>),
+    /// Bulk string nulo, por ejemplo al leer una clave que no existe.
+    NullBulk,
+    /// Array nulo, por ejemplo cuando vence el timeout de un comando bloqueante.
+    NullArray,
+    /// Respuesta de error.
+    Error(String),
+}
+
+impl Reply {
+    /// Devuelve un array cuyos elementos son los bulk strings recibidos.
+    ///
+    /// # Arguments
+    ///
+    /// * `elements` - Bytes de cada elemento del array.
+    pub fn bulk_strings<I: IntoIterator<Item = Vec<u8>>>(elements: I) -> Reply {
+        Reply::Array(elements.into_iter().map(Reply::BulkString).collect())
+    }
+
+    /// Devuelve la respuesta de los comandos de iteración: el próximo cursor y los elementos
+    /// obtenidos en la iteración.
+    ///
+    /// # Arguments
+    ///
+    /// * `cursor` - Cursor con el que se continúa la iteración.
+    /// * `elements` - Elementos obtenidos.
+    pub fn scan(cursor: String, elements: Vec<Vec<u8>>) -> Reply {
+        Reply::Array(vec![
+            Reply::BulkString(cursor.into_bytes()),
+            Reply::bulk_strings(elements),
+        ])
+    }
+}
+
+impl From<RedisElement> for Reply {
+    /// Convierte un valor almacenado en la respuesta con la que Redis lo devuelve: las colecciones
+    /// como arrays de bulk strings (los hash y sorted sets aplanados) y la ausencia de valor como
+    /// bulk string nulo.
+    fn from(redis_element: RedisElement) -> Self {
+        match redis_element {
+            RedisElement::String(bytes) => Reply::BulkString(bytes),
+            RedisElement::SimpleString(string) => Reply::SimpleString(string),
+            RedisElement::List(list) => Reply::bulk_strings(list),
+            RedisElement::Set(set) => Reply::bulk_strings(set),
+            RedisElement::Hash(hash) => Reply::bulk_strings(
+                hash.into_iter()
+                    .flat_map(|(field, value)| vec![field, value]),
+            ),
+            RedisElement::SortedSet(sorted_set) => {
+                Reply::bulk_strings(sorted_set.iter().flat_map(|(member, score)| {
+                    vec![member.to_vec(), format_score(score).into_bytes()]
+                }))
+            }
+            RedisElement::Nil => Reply::NullBulk,
+        }
+    }
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::entities::redis_element::RedisElement;
+    use crate::entities::reply::Reply;
+    use std::collections::VecDeque;
+
+    #[test]
+    fn test_stored_values_are_replied_as_bulk_strings() {
+        let list: VecDeque<Vec<u8>> = vec![b"a".to_vec(), b"b".to_vec()].into();
+        assert_eq!(
+            Reply::from(RedisElement::List(list)),
+            Reply::Array(vec![
+                Reply::BulkString(b"a".to_vec()),
+                Reply::BulkString(b"b".to_vec())
+            ])
+        );
+        assert_eq!(
+            Reply::from(RedisElement::String(b"value".to_vec())),
+            Reply::BulkString(b"value".to_vec())
+        );
+        assert_eq!(Reply::from(RedisElement::Nil), Reply::NullBulk);
+    }
+
+    #[test]
+    fn test_scan_reply_has_cursor_and_elements() {
+        assert_eq!(
+            Reply::scan("3".to_string(), vec![b"key".to_vec()]),
+            Reply::Array(vec![
+                Reply::BulkString(b"3".to_vec()),
+                Reply::Array(vec![Reply::BulkString(b"key".to_vec())])
+            ])
+        );
+    }
+}
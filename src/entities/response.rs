@@ -1,14 +1,23 @@
-use crate::entities::redis_element::RedisElement;
+use crate::entities::reply::Reply;
 use std::sync::mpsc::Receiver;
+use std::time::Duration;
 
 #[allow(dead_code)]
 #[derive(Debug)]
 /// Tipos de respuesta del servidor
 pub enum Response {
     /// Respuesta de una linea
-    Normal(RedisElement),
+    Normal(Reply),
     /// Respuesta como flujo de datos
-    Stream(Receiver<RedisElement>),
+    Stream(Receiver<Reply>),
+    /// La respuesta se envió por el flujo de datos que el cliente ya recibió en un `Stream`
+    /// anterior, como los mensajes de PubSub.
+    Pushed,
     /// Error de comando
     Error(String),
+    /// Respuestas de cada uno de los comandos ejecutados en una transacción
+    Transaction(Vec<Response>),
+    /// El cliente quedó bloqueado: la respuesta llega por el canal cuando se desbloquea. Si vence
+    /// el timeout (None espera indefinidamente), el cliente tiene que pedir que se lo desbloquee.
+    Blocked(Receiver<Response>, Option<Duration>),
 }
@@ -0,0 +1,96 @@
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// SavePoint: regla de guardado automático. Se guarda la base de datos cuando pasaron al menos
+/// `seconds` segundos desde el último guardado y hubo al menos `changes` cambios.
+pub struct SavePoint {
+    /// Segundos que tienen que pasar desde el último guardado.
+    pub seconds: u64,
+    /// Cantidad mínima de cambios desde el último guardado.
+    pub changes: u64,
+}
+
+impl SavePoint {
+    /// Parsea una lista de reglas a partir de pares `<segundos> <cambios>` separados por espacios,
+    /// como `900 1 300 10`. Una lista vacía (o `""`) deshabilita los guardados automáticos.
+    pub fn parse_list(value: &str) -> Option<Vec<SavePoint>> {
+        let value = value.trim();
+        if value == "\"\"" {
+            return Some(vec![]);
+        }
+
+        let numbers = value
+            .split_whitespace()
+            .map(|number| number.parse::<u64>().ok())
+            .collect::<Option<Vec<u64>>>()?;
+        if !numbers.len().is_multiple_of(2) {
+            return None;
+        }
+        Some(
+            numbers
+                .chunks(2)
+                .map(|pair| SavePoint {
+                    seconds: pair[0],
+                    changes: pair[1],
+                })
+                .collect(),
+        )
+    }
+
+    /// Devuelve las reglas en el mismo formato que recibe `parse_list`.
+    pub fn list_to_string(save_points: &[SavePoint]) -> String {
+        save_points
+            .iter()
+            .map(|save_point| format!("{} {}", save_point.seconds, save_point.changes))
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+
+    /// Indica si corresponde guardar, dados los segundos transcurridos y los cambios realizados
+    /// desde el último guardado.
+    pub fn is_satisfied(&self, elapsed_seconds: u64, changes: u64) -> bool {
+        changes >= self.changes && elapsed_seconds >= self.seconds
+    }
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::entities::save_point::SavePoint;
+
+    #[test]
+    fn test_parse_list() {
+        assert_eq!(
+            SavePoint::parse_list("900 1 300 10"),
+            Some(vec![
+                SavePoint {
+                    seconds: 900,
+                    changes: 1
+                },
+                SavePoint {
+                    seconds: 300,
+                    changes: 10
+                },
+            ])
+        );
+        assert_eq!(SavePoint::parse_list(""), Some(vec![]));
+        assert_eq!(SavePoint::parse_list("\"\""), Some(vec![]));
+        assert_eq!(SavePoint::parse_list("900"), None);
+        assert_eq!(SavePoint::parse_list("900 x"), None);
+    }
+
+    #[test]
+    fn test_is_satisfied() {
+        let save_point = SavePoint {
+            seconds: 60,
+            changes: 10,
+        };
+
+        assert!(save_point.is_satisfied(60, 10));
+        assert!(!save_point.is_satisfied(59, 100));
+        assert!(!save_point.is_satisfied(600, 9));
+    }
+
+    #[test]
+    fn test_list_to_string() {
+        let list = SavePoint::parse_list("3600 1 300 100").unwrap();
+        assert_eq!(SavePoint::list_to_string(&list), "3600 1 300 100");
+    }
+}
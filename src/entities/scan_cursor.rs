@@ -0,0 +1,124 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::BTreeMap;
+use std::hash::{Hash, Hasher};
+
+/// Devuelve la posición del elemento en los recorridos de SCAN, SSCAN y HSCAN: un hash fijo del
+/// elemento. Los elementos se recorren en orden de posición, que no depende del orden interno de
+/// la colección ni cambia al agregar o borrar otros elementos. Por eso, el cursor (la posición
+/// desde la que se continúa) devuelve todos los elementos que existieron durante todo el recorrido.
+pub fn scan_position<T: Hash + ?Sized>(item: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    item.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Devuelve al menos `count` elementos del índice (o todos los que quedan) a partir de la posición
+/// `cursor`, junto al cursor con el que se continúa el recorrido, que es 0 si terminó. Los
+/// elementos con una misma posición se devuelven siempre juntos.
+pub fn scan_page<T>(index: &BTreeMap<u64, Vec<T>>, cursor: u64, count: usize) -> (u64, Vec<&T>) {
+    let mut page = vec![];
+    let mut positions = index.range(cursor..);
+    for (_, items) in positions.by_ref() {
+        page.extend(items.iter());
+        if page.len() >= count {
+            break;
+        }
+    }
+    let next_cursor = positions.next().map_or(0, |(position, _)| *position);
+    (next_cursor, page)
+}
+
+#[derive(Debug, Clone, Default)]
+/// ScanIndex: índice de los elementos de un set o de los campos de un hash, agrupados por su
+/// posición en el recorrido de SSCAN o HSCAN. Se actualiza al agregar y borrar elementos, para que
+/// cada página del recorrido dependa sólo de la cantidad de elementos pedidos.
+pub struct ScanIndex {
+    positions: BTreeMap<u64, Vec<Vec<u8>>>,
+}
+
+impl ScanIndex {
+    /// Agrega el elemento al índice. No verifica si ya estaba.
+    pub fn insert(&mut self, item: &[u8]) {
+        self.positions
+            .entry(scan_position(item))
+            .or_default()
+            .push(item.to_vec());
+    }
+
+    /// Borra el elemento del índice, si estaba.
+    pub fn remove(&mut self, item: &[u8]) {
+        let position = scan_position(item);
+        if let Some(items) = self.positions.get_mut(&position) {
+            items.retain(|other| other != item);
+            if items.is_empty() {
+                self.positions.remove(&position);
+            }
+        }
+    }
+
+    /// Igual que `scan_page`, sobre los elementos del índice.
+    pub fn page(&self, cursor: u64, count: usize) -> (u64, Vec<&Vec<u8>>) {
+        scan_page(&self.positions, cursor, count)
+    }
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::entities::scan_cursor::{scan_page, scan_position, ScanIndex};
+    use std::collections::{BTreeMap, HashSet};
+
+    #[test]
+    fn test_scan_index_returns_every_item_once() {
+        let items: HashSet<Vec<u8>> = (0..50).map(|i| format!("item{}", i).into_bytes()).collect();
+        let mut index = ScanIndex::default();
+        items.iter().for_each(|item| index.insert(item));
+
+        let mut cursor = 0;
+        let mut scanned = vec![];
+        loop {
+            let (next_cursor, page) = index.page(cursor, 7);
+            assert!(page.len() >= 7 || next_cursor == 0);
+            scanned.extend(page.into_iter().cloned());
+            cursor = next_cursor;
+            if cursor == 0 {
+                break;
+            }
+        }
+
+        assert_eq!(scanned.len(), 50);
+        assert_eq!(scanned.into_iter().collect::<HashSet<Vec<u8>>>(), items);
+    }
+
+    #[test]
+    fn test_scan_index_keeps_cursor_after_changes() {
+        let mut index = ScanIndex::default();
+        for i in 0..20 {
+            index.insert(format!("item{}", i).as_bytes());
+        }
+
+        let (cursor, first) = index.page(0, 10);
+        let first: Vec<Vec<u8>> = first.into_iter().cloned().collect();
+        for i in 20..40 {
+            index.insert(format!("item{}", i).as_bytes());
+        }
+        let removed = first[0].clone();
+        index.remove(&removed);
+        let (_, rest) = index.page(cursor, 40);
+
+        let scanned: HashSet<&Vec<u8>> = first.iter().chain(rest).collect();
+        for i in 0..20 {
+            assert!(scanned.contains(&format!("item{}", i).into_bytes()));
+        }
+    }
+
+    #[test]
+    fn test_scan_page_keeps_same_position_together() {
+        let mut index: BTreeMap<u64, Vec<&str>> = BTreeMap::new();
+        index.insert(1, vec!["a", "b"]);
+        index.insert(5, vec!["c"]);
+
+        assert_eq!(scan_page(&index, 0, 1), (5, vec![&"a", &"b"]));
+        assert_eq!(scan_page(&index, 5, 1), (0, vec![&"c"]));
+        assert_eq!(scan_position("key"), scan_position("key"));
+    }
+}
@@ -0,0 +1,74 @@
+use std::collections::HashSet;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// SetOperation: Enum usado para representar la operación entre sets de los comandos SINTER,
+/// SUNION y SDIFF, y de sus variantes *STORE.
+pub enum SetOperation {
+    /// Miembros que están en todos los sets.
+    Inter,
+    /// Miembros que están en alguno de los sets.
+    Union,
+    /// Miembros del primer set que no están en ninguno de los demás.
+    Diff,
+}
+
+impl SetOperation {
+    /// Devuelve el nombre del comando que aplica la operación.
+    pub fn command_name(&self) -> &'static str {
+        match self {
+            SetOperation::Inter => "sinter",
+            SetOperation::Union => "sunion",
+            SetOperation::Diff => "sdiff",
+        }
+    }
+
+    /// Aplica la operación a los sets, en orden. Las claves que no existen se consideran sets
+    /// vacíos.
+    pub fn apply(&self, sets: &[HashSet<Vec<u8>>]) -> HashSet<Vec<u8>> {
+        let (first, others) = match sets.split_first() {
+            Some(split) => split,
+            None => return HashSet::new(),
+        };
+        match self {
+            SetOperation::Inter => first
+                .iter()
+                .filter(|member| others.iter().all(|set| set.contains(*member)))
+                .cloned()
+                .collect(),
+            SetOperation::Union => sets.iter().flatten().cloned().collect(),
+            SetOperation::Diff => first
+                .iter()
+                .filter(|member| !others.iter().any(|set| set.contains(*member)))
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::entities::set_operation::SetOperation;
+    use std::collections::HashSet;
+
+    #[allow(dead_code)]
+    fn set(members: &[&str]) -> HashSet<Vec<u8>> {
+        members
+            .iter()
+            .map(|member| member.as_bytes().to_vec())
+            .collect()
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let sets = vec![set(&["a", "b", "c"]), set(&["b", "c", "d"]), set(&["c"])];
+
+        assert_eq!(SetOperation::Inter.apply(&sets), set(&["c"]));
+        assert_eq!(SetOperation::Union.apply(&sets), set(&["a", "b", "c", "d"]));
+        assert_eq!(SetOperation::Diff.apply(&sets), set(&["a"]));
+        assert_eq!(
+            SetOperation::Inter.apply(&[set(&["a"]), HashSet::new()]),
+            HashSet::new()
+        );
+        assert!(SetOperation::Union.apply(&[]).is_empty());
+    }
+}
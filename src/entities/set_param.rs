@@ -0,0 +1,44 @@
+use std::time::{Duration, SystemTime};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// SetCondition: condición para que el Command::Set guarde el valor.
+pub enum SetCondition {
+    /// Sólo si la clave no existe (NX).
+    Nx,
+    /// Sólo si la clave existe (XX).
+    Xx,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// SetExpiration: expiración con la que el Command::Set guarda el valor.
+pub enum SetExpiration {
+    /// Tiempo de vida a partir de la ejecución del comando (EX y PX).
+    Relative(Duration),
+    /// Instante en el que expira la clave (EXAT y PXAT).
+    Absolute(SystemTime),
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+/// SetParam: opciones del Command::Set.
+pub struct SetParam {
+    /// Condición para guardar el valor (NX o XX).
+    pub condition: Option<SetCondition>,
+    /// Expiración del valor (EX, PX, EXAT o PXAT). Sin expiración ni KEEPTTL, la clave queda
+    /// persistente.
+    pub expiration: Option<SetExpiration>,
+    /// Indica si se mantiene la expiración que tenía la clave (KEEPTTL).
+    pub keep_ttl: bool,
+    /// Indica si se devuelve el valor anterior de la clave en lugar de OK (GET).
+    pub get: bool,
+}
+
+impl SetParam {
+    /// Devuelve el instante en el que expira la clave, si se indicó una expiración. Las
+    /// expiraciones relativas se cuentan desde ahora.
+    pub fn expires_at(&self) -> Option<SystemTime> {
+        match self.expiration? {
+            SetExpiration::Relative(ttl) => Some(SystemTime::now() + ttl),
+            SetExpiration::Absolute(ttl) => Some(ttl),
+        }
+    }
+}
@@ -0,0 +1,481 @@
+use crate::entities::ttl_hash_map::random_u64;
+use std::ops::Bound::{self, Excluded, Included, Unbounded};
+
+/// Cantidad máxima de niveles de la lista, igual que en Redis.
+const MAX_LEVEL: usize = 32;
+/// Posición del nodo cabecera, que no guarda ninguna clave.
+const HEAD: usize = 0;
+
+#[derive(Debug, Clone)]
+/// Enlace de un nodo en uno de los niveles: el próximo nodo del nivel y cuántas posiciones avanza.
+struct Level {
+    forward: Option<usize>,
+    span: usize,
+}
+
+#[derive(Debug, Clone)]
+/// Nodo de la lista. Los nodos libres (y la cabecera) no tienen clave.
+struct Node<K> {
+    key: Option<K>,
+    backward: Option<usize>,
+    levels: Vec<Level>,
+}
+
+#[derive(Debug, Clone)]
+/// SkipList: lista ordenada de claves únicas con enlaces en varios niveles, igual a la que usa Redis
+/// para los sorted sets. Cada enlace guarda cuántas posiciones saltea, por lo que además de buscar,
+/// agregar y borrar en O(log n), permite obtener la posición de una clave y la clave de una
+/// posición en O(log n).
+///
+/// Los nodos se guardan en un vector y se enlazan por su posición en él; los lugares de los nodos
+/// borrados se reutilizan.
+pub struct SkipList<K> {
+    nodes: Vec<Node<K>>,
+    free: Vec<usize>,
+    tail: Option<usize>,
+    level: usize,
+    len: usize,
+}
+
+impl<K: Ord + Clone> Default for SkipList<K> {
+    fn default() -> Self {
+        SkipList::new()
+    }
+}
+
+impl<K: Ord + Clone> SkipList<K> {
+    /// Constructor de una lista vacía.
+    pub fn new() -> Self {
+        let head = Node {
+            key: None,
+            backward: None,
+            levels: vec![
+                Level {
+                    forward: None,
+                    span: 0
+                };
+                MAX_LEVEL
+            ],
+        };
+        SkipList {
+            nodes: vec![head],
+            free: Vec::new(),
+            tail: None,
+            level: 1,
+            len: 0,
+        }
+    }
+
+    /// Devuelve la cantidad de claves.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Devuelve si la lista no tiene claves.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Devuelve la clave de un nodo que forma parte de la lista.
+    fn key(&self, node: usize) -> &K {
+        self.nodes[node]
+            .key
+            .as_ref()
+            .expect("los nodos enlazados tienen clave")
+    }
+
+    /// Devuelve el próximo nodo en el nivel indicado.
+    fn forward(&self, node: usize, level: usize) -> Option<usize> {
+        self.nodes[node].levels[level].forward
+    }
+
+    /// Elige la cantidad de niveles de un nodo nuevo: cada nivel extra tiene probabilidad 1/4,
+    /// como en Redis.
+    fn random_level() -> usize {
+        let level = 1 + random_u64().trailing_zeros() as usize / 2;
+        level.min(MAX_LEVEL)
+    }
+
+    /// Guarda un nodo nuevo, reutilizando el lugar de uno borrado si lo hay.
+    fn allocate(&mut self, key: K, level: usize) -> usize {
+        let node = Node {
+            key: Some(key),
+            backward: None,
+            levels: vec![
+                Level {
+                    forward: None,
+                    span: 0
+                };
+                level
+            ],
+        };
+        match self.free.pop() {
+            Some(position) => {
+                self.nodes[position] = node;
+                position
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    /// Agrega una clave, que no debe estar en la lista.
+    pub fn insert(&mut self, key: K) {
+        // Último nodo anterior a la clave en cada nivel, y su posición.
+        let mut update = [HEAD; MAX_LEVEL];
+        let mut rank = [0; MAX_LEVEL];
+        let mut node = HEAD;
+        for i in (0..self.level).rev() {
+            rank[i] = if i + 1 == self.level { 0 } else { rank[i + 1] };
+            while let Some(next) = self.forward(node, i) {
+                if *self.key(next) >= key {
+                    break;
+                }
+                rank[i] += self.nodes[node].levels[i].span;
+                node = next;
+            }
+            update[i] = node;
+        }
+
+        let level = Self::random_level();
+        if level > self.level {
+            for i in self.level..level {
+                rank[i] = 0;
+                update[i] = HEAD;
+                self.nodes[HEAD].levels[i].span = self.len;
+            }
+            self.level = level;
+        }
+
+        let new = self.allocate(key, level);
+        for i in 0..level {
+            let previous = update[i];
+            let skipped = rank[0] - rank[i];
+            self.nodes[new].levels[i] = Level {
+                forward: self.forward(previous, i),
+                span: self.nodes[previous].levels[i].span - skipped,
+            };
+            self.nodes[previous].levels[i] = Level {
+                forward: Some(new),
+                span: skipped + 1,
+            };
+        }
+        for (i, &previous) in update.iter().enumerate().take(self.level).skip(level) {
+            self.nodes[previous].levels[i].span += 1;
+        }
+
+        self.nodes[new].backward = Some(update[0]).filter(|&previous| previous != HEAD);
+        match self.forward(new, 0) {
+            Some(next) => self.nodes[next].backward = Some(new),
+            None => self.tail = Some(new),
+        }
+        self.len += 1;
+    }
+
+    /// Borra una clave. Devuelve la clave borrada, o None si no estaba.
+    pub fn remove(&mut self, key: &K) -> Option<K> {
+        let mut update = [HEAD; MAX_LEVEL];
+        let mut node = HEAD;
+        for i in (0..self.level).rev() {
+            while let Some(next) = self.forward(node, i) {
+                if self.key(next) >= key {
+                    break;
+                }
+                node = next;
+            }
+            update[i] = node;
+        }
+        let target = self
+            .forward(node, 0)
+            .filter(|&next| self.key(next) == key)?;
+
+        for (i, &previous) in update.iter().enumerate().take(self.level) {
+            if self.forward(previous, i) == Some(target) {
+                let removed = &self.nodes[target].levels[i];
+                let (forward, span) = (removed.forward, removed.span);
+                let level = &mut self.nodes[previous].levels[i];
+                level.span = level.span + span - 1;
+                level.forward = forward;
+            } else {
+                self.nodes[previous].levels[i].span -= 1;
+            }
+        }
+
+        let backward = self.nodes[target].backward;
+        match self.forward(target, 0) {
+            Some(next) => self.nodes[next].backward = backward,
+            None => self.tail = backward,
+        }
+        while self.level > 1 && self.forward(HEAD, self.level - 1).is_none() {
+            self.level -= 1;
+        }
+        self.len -= 1;
+
+        self.free.push(target);
+        self.nodes[target].levels = Vec::new();
+        self.nodes[target].key.take()
+    }
+
+    /// Devuelve la posición de la clave (empezando en 0), o None si no está.
+    pub fn rank(&self, key: &K) -> Option<usize> {
+        let mut rank = 0;
+        let mut node = HEAD;
+        for i in (0..self.level).rev() {
+            while let Some(next) = self.forward(node, i) {
+                if self.key(next) > key {
+                    break;
+                }
+                rank += self.nodes[node].levels[i].span;
+                node = next;
+            }
+            if node != HEAD && self.key(node) == key {
+                return Some(rank - 1);
+            }
+        }
+        None
+    }
+
+    /// Devuelve el nodo en la posición indicada (empezando en 0).
+    fn node_at(&self, index: usize) -> Option<usize> {
+        if index >= self.len {
+            return None;
+        }
+        let mut traversed = 0;
+        let mut node = HEAD;
+        for i in (0..self.level).rev() {
+            while let Some(next) = self.forward(node, i) {
+                let span = self.nodes[node].levels[i].span;
+                if traversed + span > index + 1 {
+                    break;
+                }
+                traversed += span;
+                node = next;
+            }
+            if traversed == index + 1 {
+                return Some(node);
+            }
+        }
+        None
+    }
+
+    /// Devuelve el primer nodo cuya clave cumple el extremo inferior.
+    fn first_from(&self, lower: Bound<&K>) -> Option<usize> {
+        let mut node = HEAD;
+        for i in (0..self.level).rev() {
+            while let Some(next) = self.forward(node, i) {
+                let below = match lower {
+                    Included(bound) => self.key(next) < bound,
+                    Excluded(bound) => self.key(next) <= bound,
+                    Unbounded => false,
+                };
+                if !below {
+                    break;
+                }
+                node = next;
+            }
+        }
+        self.forward(node, 0)
+    }
+
+    /// Devuelve el último nodo cuya clave cumple el extremo superior.
+    fn last_until(&self, upper: Bound<&K>) -> Option<usize> {
+        let mut node = HEAD;
+        for i in (0..self.level).rev() {
+            while let Some(next) = self.forward(node, i) {
+                let within = match upper {
+                    Included(bound) => self.key(next) <= bound,
+                    Excluded(bound) => self.key(next) < bound,
+                    Unbounded => true,
+                };
+                if !within {
+                    break;
+                }
+                node = next;
+            }
+        }
+        Some(node).filter(|&node| node != HEAD)
+    }
+
+    /// Devuelve la primera clave.
+    pub fn first(&self) -> Option<&K> {
+        self.forward(HEAD, 0).map(|node| self.key(node))
+    }
+
+    /// Borra y devuelve la primera clave.
+    pub fn pop_first(&mut self) -> Option<K> {
+        let key = self.first()?.clone();
+        self.remove(&key)
+    }
+
+    /// Borra y devuelve la última clave.
+    pub fn pop_last(&mut self) -> Option<K> {
+        let key = self.key(self.tail?).clone();
+        self.remove(&key)
+    }
+
+    /// Devuelve un iterador ordenado sobre todas las claves.
+    pub fn iter(&self) -> Range<'_, K> {
+        Range::new(self, self.forward(HEAD, 0), self.tail)
+    }
+
+    /// Devuelve un iterador ordenado sobre las claves que están entre los extremos indicados.
+    /// Encontrar los extremos cuesta O(log n).
+    pub fn range(&self, lower: Bound<&K>, upper: Bound<&K>) -> Range<'_, K> {
+        let front = self.first_from(lower);
+        let back = self.last_until(upper);
+        match (front, back) {
+            (Some(first), Some(last)) if self.key(first) <= self.key(last) => {
+                Range::new(self, front, back)
+            }
+            _ => Range::new(self, None, None),
+        }
+    }
+
+    /// Devuelve un iterador ordenado sobre las claves entre las posiciones `start` y `stop`
+    /// (inclusive, empezando en 0). Encontrar los extremos cuesta O(log n).
+    pub fn range_by_rank(&self, start: usize, stop: usize) -> Range<'_, K> {
+        if start > stop || stop >= self.len {
+            return Range::new(self, None, None);
+        }
+        Range::new(self, self.node_at(start), self.node_at(stop))
+    }
+}
+
+/// Iterador sobre las claves de una SkipList entre dos nodos (inclusive).
+pub struct Range<'a, K> {
+    list: &'a SkipList<K>,
+    front: Option<usize>,
+    back: Option<usize>,
+}
+
+impl<'a, K: Ord + Clone> Range<'a, K> {
+    fn new(list: &'a SkipList<K>, front: Option<usize>, back: Option<usize>) -> Self {
+        Range { list, front, back }
+    }
+
+    /// Avanza uno de los extremos, terminando el recorrido si se cruzó con el otro.
+    fn advance(&mut self, node: usize, next: Option<usize>) -> &'a K {
+        if self.front == self.back {
+            self.front = None;
+            self.back = None;
+        } else if self.front == Some(node) {
+            self.front = next;
+        } else {
+            self.back = next;
+        }
+        self.list.key(node)
+    }
+}
+
+impl<'a, K: Ord + Clone> Iterator for Range<'a, K> {
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.front?;
+        Some(self.advance(node, self.list.forward(node, 0)))
+    }
+}
+
+impl<'a, K: Ord + Clone> DoubleEndedIterator for Range<'a, K> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let node = self.back?;
+        Some(self.advance(node, self.list.nodes[node].backward))
+    }
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::entities::skip_list::SkipList;
+    use std::ops::Bound::{Excluded, Included, Unbounded};
+
+    #[allow(dead_code)]
+    fn list_of(keys: &[u32]) -> SkipList<u32> {
+        let mut list = SkipList::new();
+        for key in keys {
+            list.insert(*key);
+        }
+        list
+    }
+
+    #[test]
+    fn test_insert_keeps_keys_ordered() {
+        let list = list_of(&[5, 1, 4, 2, 3]);
+
+        assert_eq!(list.len(), 5);
+        assert_eq!(
+            list.iter().copied().collect::<Vec<_>>(),
+            vec![1, 2, 3, 4, 5]
+        );
+        assert_eq!(
+            list.iter().rev().copied().collect::<Vec<_>>(),
+            vec![5, 4, 3, 2, 1]
+        );
+    }
+
+    #[test]
+    fn test_rank_and_range_by_rank() {
+        let list = list_of(&(0..1000).rev().collect::<Vec<_>>());
+
+        for key in 0..1000 {
+            assert_eq!(list.rank(&key), Some(key as usize));
+        }
+        assert_eq!(list.rank(&1000), None);
+        assert_eq!(
+            list.range_by_rank(10, 12).copied().collect::<Vec<_>>(),
+            vec![10, 11, 12]
+        );
+        assert_eq!(list.range_by_rank(999, 999).next(), Some(&999));
+        assert_eq!(list.range_by_rank(999, 1000).next(), None);
+        assert_eq!(list.range_by_rank(5, 4).next(), None);
+    }
+
+    #[test]
+    fn test_remove_updates_ranks() {
+        let mut list = list_of(&(0..100).collect::<Vec<_>>());
+        for key in (0..100).step_by(2) {
+            assert_eq!(list.remove(&key), Some(key));
+        }
+
+        assert_eq!(list.remove(&0), None);
+        assert_eq!(list.len(), 50);
+        assert_eq!(list.rank(&51), Some(25));
+        assert_eq!(list.range_by_rank(25, 25).next(), Some(&51));
+        assert_eq!(list.pop_first(), Some(1));
+        assert_eq!(list.pop_last(), Some(99));
+        assert_eq!(list.first(), Some(&3));
+
+        list.insert(0);
+        assert_eq!(list.rank(&0), Some(0));
+        assert_eq!(list.rank(&3), Some(1));
+    }
+
+    #[test]
+    fn test_range_between_bounds() {
+        let list = list_of(&[1, 3, 5, 7, 9]);
+
+        let range = |lower, upper| list.range(lower, upper).copied().collect::<Vec<_>>();
+        assert_eq!(range(Included(&3), Excluded(&9)), vec![3, 5, 7]);
+        assert_eq!(range(Excluded(&3), Included(&9)), vec![5, 7, 9]);
+        assert_eq!(range(Included(&4), Included(&4)), Vec::<u32>::new());
+        assert_eq!(range(Included(&8), Included(&2)), Vec::<u32>::new());
+        assert_eq!(range(Unbounded, Included(&5)), vec![1, 3, 5]);
+        assert_eq!(
+            list.range(Excluded(&1), Unbounded)
+                .rev()
+                .copied()
+                .collect::<Vec<_>>(),
+            vec![9, 7, 5, 3]
+        );
+
+        let mut range = list.range(Unbounded, Unbounded);
+        assert_eq!(range.next(), Some(&1));
+        assert_eq!(range.next_back(), Some(&9));
+        assert_eq!(range.next_back(), Some(&7));
+        assert_eq!(range.next(), Some(&3));
+        assert_eq!(range.next(), Some(&5));
+        assert_eq!(range.next(), None);
+        assert_eq!(range.next_back(), None);
+    }
+}
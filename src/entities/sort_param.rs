@@ -0,0 +1,89 @@
+#[derive(Debug, Default)]
+/// SortParam: opciones del Command::Sort.
+pub struct SortParam {
+    /// Patrón de las claves externas cuyo valor se usa para ordenar (BY). Si el patrón no tiene
+    /// `*` (por ejemplo `nosort`), los elementos no se ordenan.
+    pub by: Option<Vec<u8>>,
+    /// Posición del primer elemento devuelto y cantidad de elementos (LIMIT).
+    pub limit: Option<(i64, i64)>,
+    /// Patrones de las claves externas que se devuelven en lugar de cada elemento (GET). El patrón
+    /// `#` devuelve el elemento.
+    pub get: Vec<Vec<u8>>,
+    /// Indica si se ordena de mayor a menor (DESC).
+    pub desc: bool,
+    /// Indica si se ordena lexicográficamente en lugar de numéricamente (ALPHA).
+    pub alpha: bool,
+    /// Clave en la que se guarda el resultado como lista, en lugar de devolverlo (STORE).
+    pub store: Option<Vec<u8>>,
+}
+
+impl SortParam {
+    /// Indica si los elementos se ordenan: no se ordenan si el patrón de BY no tiene `*`.
+    pub fn sorts(&self) -> bool {
+        self.by
+            .as_ref()
+            .is_none_or(|pattern| pattern.contains(&b'*'))
+    }
+
+    /// Devuelve los argumentos con los que se indican las opciones en el comando SORT.
+    pub fn to_args(&self) -> Vec<Vec<u8>> {
+        let mut args = vec![];
+        if let Some(by) = &self.by {
+            args.push(b"by".to_vec());
+            args.push(by.to_vec());
+        }
+        if let Some((offset, count)) = self.limit {
+            args.push(b"limit".to_vec());
+            args.push(offset.to_string().into_bytes());
+            args.push(count.to_string().into_bytes());
+        }
+        for pattern in &self.get {
+            args.push(b"get".to_vec());
+            args.push(pattern.to_vec());
+        }
+        if self.desc {
+            args.push(b"desc".to_vec());
+        }
+        if self.alpha {
+            args.push(b"alpha".to_vec());
+        }
+        if let Some(store) = &self.store {
+            args.push(b"store".to_vec());
+            args.push(store.to_vec());
+        }
+        args
+    }
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::entities::sort_param::SortParam;
+
+    #[test]
+    fn test_sort_param_to_args() {
+        let param = SortParam {
+            by: Some(b"weight_*".to_vec()),
+            limit: Some((0, 10)),
+            get: vec![b"#".to_vec(), b"name_*".to_vec()],
+            desc: true,
+            alpha: false,
+            store: Some(b"dest".to_vec()),
+        };
+        let args: Vec<String> = param
+            .to_args()
+            .into_iter()
+            .map(|arg| String::from_utf8(arg).unwrap())
+            .collect();
+
+        assert!(param.sorts());
+        assert_eq!(
+            args.join(" "),
+            "by weight_* limit 0 10 get # get name_* desc store dest"
+        );
+        assert!(!SortParam {
+            by: Some(b"nosort".to_vec()),
+            ..SortParam::default()
+        }
+        .sorts());
+    }
+}
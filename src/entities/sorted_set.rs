@@ -0,0 +1,576 @@
+use crate::entities::skip_list::SkipList;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::ops::Bound::{self, Excluded, Included, Unbounded};
+
+#[derive(Debug, Clone, Copy)]
+/// Puntaje de un miembro de un SortedSet. Envuelve un f64 para poder ordenarlo totalmente dentro
+/// del índice.
+struct Score(f64);
+
+impl PartialEq for Score {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Score {}
+
+impl PartialOrd for Score {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Score {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+/// Elemento del índice de un SortedSet: el puntaje y el miembro.
+type IndexKey = (Score, Vec<u8>);
+
+#[derive(Debug, Clone, PartialEq)]
+/// ScoreBound: Enum usado para representar los extremos de un rango de puntajes.
+pub enum ScoreBound {
+    /// El extremo forma parte del rango.
+    Inclusive(f64),
+    /// El extremo no forma parte del rango.
+    Exclusive(f64),
+}
+
+impl ScoreBound {
+    /// Indica si el puntaje es mayor (o igual, si es inclusivo) al extremo.
+    fn is_above(&self, score: f64) -> bool {
+        match self {
+            ScoreBound::Inclusive(bound) => score >= *bound,
+            ScoreBound::Exclusive(bound) => score > *bound,
+        }
+    }
+
+    /// Indica si el puntaje es menor (o igual, si es inclusivo) al extremo.
+    fn is_below(&self, score: f64) -> bool {
+        match self {
+            ScoreBound::Inclusive(bound) => score <= *bound,
+            ScoreBound::Exclusive(bound) => score < *bound,
+        }
+    }
+
+    /// Devuelve el puntaje del extremo.
+    fn score(&self) -> f64 {
+        match self {
+            ScoreBound::Inclusive(bound) | ScoreBound::Exclusive(bound) => *bound,
+        }
+    }
+
+    /// Devuelve desde dónde buscar en el índice los miembros con puntaje mayor o igual al extremo.
+    /// El índice ordena -0 antes que 0, por lo que para 0 se busca desde -0.
+    fn lower_key(&self) -> Bound<IndexKey> {
+        let score = match self.score() {
+            0.0 => -0.0,
+            bound => bound,
+        };
+        Included((Score(score), vec![]))
+    }
+
+    /// Devuelve hasta dónde buscar en el índice los miembros con puntaje menor o igual al extremo:
+    /// hasta el primer par con el puntaje siguiente.
+    fn upper_key(&self) -> Bound<IndexKey> {
+        match self.score() {
+            f64::INFINITY => Unbounded,
+            bound => Excluded((Score(bound.next_up()), vec![])),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// LexBound: Enum usado para representar los extremos de un rango lexicográfico.
+pub enum LexBound {
+    /// Representa el string más chico posible (`-`).
+    Min,
+    /// Representa el string más grande posible (`+`).
+    Max,
+    /// El extremo forma parte del rango (`[`).
+    Inclusive(Vec<u8>),
+    /// El extremo no forma parte del rango (`(`).
+    Exclusive(Vec<u8>),
+}
+
+impl LexBound {
+    /// Indica si el miembro es mayor (o igual, si es inclusivo) al extremo.
+    fn is_above(&self, member: &[u8]) -> bool {
+        match self {
+            LexBound::Min => true,
+            LexBound::Max => false,
+            LexBound::Inclusive(bound) => member >= bound.as_slice(),
+            LexBound::Exclusive(bound) => member > bound.as_slice(),
+        }
+    }
+
+    /// Indica si el miembro es menor (o igual, si es inclusivo) al extremo.
+    fn is_below(&self, member: &[u8]) -> bool {
+        match self {
+            LexBound::Min => false,
+            LexBound::Max => true,
+            LexBound::Inclusive(bound) => member <= bound.as_slice(),
+            LexBound::Exclusive(bound) => member < bound.as_slice(),
+        }
+    }
+
+    /// Devuelve el extremo como extremo de un rango del índice, para miembros con el puntaje
+    /// indicado. Los extremos `-` y `+` no limitan el rango.
+    fn key(&self, score: Score) -> Bound<IndexKey> {
+        match self {
+            LexBound::Min | LexBound::Max => Unbounded,
+            LexBound::Inclusive(bound) => Included((score, bound.clone())),
+            LexBound::Exclusive(bound) => Excluded((score, bound.clone())),
+        }
+    }
+}
+
+/// Aplica el desplazamiento y la cantidad de LIMIT a los miembros de un rango, sin recorrer los
+/// que quedan después de la cantidad pedida.
+fn limited<'a>(
+    range: impl Iterator<Item = (&'a Vec<u8>, f64)>,
+    limit: Option<(usize, usize)>,
+) -> Vec<(Vec<u8>, f64)> {
+    let (offset, count) = limit.unwrap_or((0, usize::MAX));
+    range
+        .skip(offset)
+        .take(count)
+        .map(|(member, score)| (member.to_vec(), score))
+        .collect()
+}
+
+#[derive(Debug, Clone, Default)]
+/// Estructura que representa el tipo de dato Sorted Set de Redis: un conjunto de miembros únicos
+/// ordenados por su puntaje, y a igual puntaje, lexicográficamente.
+///
+/// Los puntajes se guardan en un hash para obtenerlos en O(1), y el orden se mantiene en un índice
+/// de pares (puntaje, miembro) guardado en una skiplist que cuenta las posiciones que saltea cada
+/// enlace, como en Redis, por lo que obtener la posición de un miembro o ir a una posición cuesta
+/// O(log n).
+pub struct SortedSet {
+    scores: HashMap<Vec<u8>, f64>,
+    index: SkipList<IndexKey>,
+}
+
+impl PartialEq for SortedSet {
+    fn eq(&self, other: &Self) -> bool {
+        self.scores == other.scores
+    }
+}
+
+impl SortedSet {
+    /// Constructor de un nuevo SortedSet vacío.
+    pub fn new() -> Self {
+        SortedSet {
+            scores: HashMap::new(),
+            index: SkipList::new(),
+        }
+    }
+
+    /// Devuelve la cantidad de miembros.
+    pub fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    /// Devuelve si el SortedSet no tiene miembros.
+    pub fn is_empty(&self) -> bool {
+        self.index.is_empty()
+    }
+
+    /// Devuelve el puntaje del miembro, o None si no existe.
+    pub fn score(&self, member: &[u8]) -> Option<f64> {
+        self.scores.get(member).copied()
+    }
+
+    /// Inserta un miembro con su puntaje, actualizándolo si ya existía.
+    /// Devuelve el puntaje previo, o None si el miembro es nuevo.
+    pub fn insert(&mut self, member: Vec<u8>, score: f64) -> Option<f64> {
+        let previous = self.remove(&member);
+        self.index.insert((Score(score), member.clone()));
+        self.scores.insert(member, score);
+        previous
+    }
+
+    /// Elimina un miembro. Devuelve su puntaje, o None si no existía.
+    pub fn remove(&mut self, member: &[u8]) -> Option<f64> {
+        let score = self.scores.remove(member)?;
+        self.index.remove(&(Score(score), member.to_vec()));
+        Some(score)
+    }
+
+    /// Devuelve la posición del miembro según el orden ascendente, o None si no existe.
+    pub fn rank(&self, member: &[u8]) -> Option<usize> {
+        let key = (Score(self.score(member)?), member.to_vec());
+        self.index.rank(&key)
+    }
+
+    /// Devuelve un iterador ordenado por puntaje sobre los pares (miembro, puntaje).
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (&Vec<u8>, f64)> {
+        self.index.iter().map(|(score, member)| (member, score.0))
+    }
+
+    /// Devuelve un iterador ordenado por puntaje sobre los pares (miembro, puntaje) del índice
+    /// que están entre los extremos indicados.
+    fn iter_range(
+        &self,
+        lower: Bound<IndexKey>,
+        upper: Bound<IndexKey>,
+    ) -> impl DoubleEndedIterator<Item = (&Vec<u8>, f64)> {
+        self.index
+            .range(lower.as_ref(), upper.as_ref())
+            .map(|(score, member)| (member, score.0))
+    }
+
+    /// Elimina y devuelve el miembro con menor puntaje.
+    pub fn pop_min(&mut self) -> Option<(Vec<u8>, f64)> {
+        let (score, member) = self.index.pop_first()?;
+        self.scores.remove(&member);
+        Some((member, score.0))
+    }
+
+    /// Elimina y devuelve el miembro con mayor puntaje.
+    pub fn pop_max(&mut self) -> Option<(Vec<u8>, f64)> {
+        let (score, member) = self.index.pop_last()?;
+        self.scores.remove(&member);
+        Some((member, score.0))
+    }
+
+    /// Devuelve los miembros entre las posiciones `start` y `stop` (inclusive). Los valores
+    /// negativos se cuentan desde el final: -1 es el último miembro.
+    ///
+    /// Si `rev` es verdadero, las posiciones se cuentan desde el mayor puntaje.
+    pub fn range_by_rank(&self, start: i64, stop: i64, rev: bool) -> Vec<(Vec<u8>, f64)> {
+        let len = self.len() as i64;
+        let start = if start < 0 {
+            (len + start).max(0)
+        } else {
+            start
+        };
+        let stop = if stop < 0 {
+            len + stop
+        } else {
+            stop.min(len - 1)
+        };
+
+        if start > stop || start >= len {
+            return vec![];
+        }
+
+        // Se busca cada extremo en el índice y se recorren sólo los miembros del rango.
+        let (start, stop) = (start as usize, stop as usize);
+        let pair = |(score, member): &IndexKey| (member.to_vec(), score.0);
+        if rev {
+            let last = self.len() - 1;
+            self.index
+                .range_by_rank(last - stop, last - start)
+                .rev()
+                .map(pair)
+                .collect()
+        } else {
+            self.index.range_by_rank(start, stop).map(pair).collect()
+        }
+    }
+
+    /// Devuelve los miembros cuyo puntaje está dentro del rango indicado, ordenados de forma
+    /// ascendente, o descendente si `rev` es verdadero. Si se indica `limit`, se saltean los
+    /// primeros `offset` miembros y se devuelven a lo sumo `count`.
+    ///
+    /// Sólo se recorren los miembros del rango, empezando por el extremo correspondiente.
+    pub fn range_by_score(
+        &self,
+        min: &ScoreBound,
+        max: &ScoreBound,
+        rev: bool,
+        limit: Option<(usize, usize)>,
+    ) -> Vec<(Vec<u8>, f64)> {
+        if rev {
+            let range = self
+                .iter_range(Unbounded, max.upper_key())
+                .rev()
+                .skip_while(|(_, score)| !max.is_below(*score))
+                .take_while(|(_, score)| min.is_above(*score));
+            limited(range, limit)
+        } else {
+            limited(self.score_range(min, max), limit)
+        }
+    }
+
+    /// Devuelve la cantidad de miembros cuyo puntaje está dentro del rango indicado.
+    pub fn count_by_score(&self, min: &ScoreBound, max: &ScoreBound) -> usize {
+        self.score_range(min, max).count()
+    }
+
+    /// Devuelve un iterador ascendente sobre los miembros cuyo puntaje está dentro del rango.
+    fn score_range<'a>(
+        &'a self,
+        min: &'a ScoreBound,
+        max: &'a ScoreBound,
+    ) -> impl Iterator<Item = (&'a Vec<u8>, f64)> {
+        self.iter_range(min.lower_key(), Unbounded)
+            .skip_while(move |(_, score)| !min.is_above(*score))
+            .take_while(move |(_, score)| max.is_below(*score))
+    }
+
+    /// Devuelve los miembros que se encuentran dentro del rango lexicográfico indicado, ordenados
+    /// de forma ascendente, o descendente si `rev` es verdadero. Si se indica `limit`, se saltean
+    /// los primeros `offset` miembros y se devuelven a lo sumo `count`.
+    ///
+    /// Al igual que en Redis, se asume que todos los miembros tienen el mismo puntaje, y sólo se
+    /// recorren los miembros del rango.
+    pub fn range_by_lex(
+        &self,
+        min: &LexBound,
+        max: &LexBound,
+        rev: bool,
+        limit: Option<(usize, usize)>,
+    ) -> Vec<(Vec<u8>, f64)> {
+        let score = match self.index.first() {
+            Some((score, _)) if *min != LexBound::Max && *max != LexBound::Min => *score,
+            _ => return vec![],
+        };
+        if rev {
+            let range = self
+                .iter_range(Unbounded, max.key(score))
+                .rev()
+                .take_while(|(member, _)| min.is_above(member));
+            limited(range, limit)
+        } else {
+            let range = self
+                .iter_range(min.key(score), Unbounded)
+                .take_while(|(member, _)| max.is_below(member));
+            limited(range, limit)
+        }
+    }
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::entities::sorted_set::{LexBound, ScoreBound, SortedSet};
+    use crate::entities::ttl_hash_map::random_u64;
+
+    #[allow(dead_code)]
+    fn sorted_set() -> SortedSet {
+        let mut set = SortedSet::new();
+        set.insert(b"c".to_vec(), 3.0);
+        set.insert(b"a".to_vec(), 1.0);
+        set.insert(b"b".to_vec(), 2.0);
+        set
+    }
+
+    #[allow(dead_code)]
+    fn members(range: Vec<(Vec<u8>, f64)>) -> Vec<String> {
+        range
+            .into_iter()
+            .map(|(member, _)| String::from_utf8(member).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_insert_updates_score() {
+        let mut set = sorted_set();
+
+        assert_eq!(set.insert(b"a".to_vec(), 5.0), Some(1.0));
+        assert_eq!(set.score(b"a"), Some(5.0));
+        assert_eq!(set.len(), 3);
+        assert_eq!(set.rank(b"a"), Some(2));
+    }
+
+    #[test]
+    fn test_same_score_ordered_lexicographically() {
+        let mut set = SortedSet::new();
+        set.insert(b"b".to_vec(), 1.0);
+        set.insert(b"a".to_vec(), 1.0);
+
+        assert_eq!(set.rank(b"a"), Some(0));
+        assert_eq!(set.rank(b"b"), Some(1));
+    }
+
+    #[test]
+    fn test_range_by_rank_with_negative_indexes() {
+        let set = sorted_set();
+
+        assert_eq!(
+            members(set.range_by_rank(0, -1, false)),
+            vec!["a", "b", "c"]
+        );
+        assert_eq!(members(set.range_by_rank(-2, -1, false)), vec!["b", "c"]);
+        assert_eq!(members(set.range_by_rank(0, 0, true)), vec!["c"]);
+        assert!(set.range_by_rank(5, 10, false).is_empty());
+    }
+
+    #[test]
+    fn test_range_by_score_exclusive() {
+        let set = sorted_set();
+
+        let range = set.range_by_score(
+            &ScoreBound::Exclusive(1.0),
+            &ScoreBound::Inclusive(f64::INFINITY),
+            true,
+            None,
+        );
+        assert_eq!(members(range), vec!["c", "b"]);
+    }
+
+    #[test]
+    fn test_range_by_lex() {
+        let mut set = SortedSet::new();
+        for member in ["a", "b", "c", "d"].iter() {
+            set.insert(member.as_bytes().to_vec(), 0.0);
+        }
+
+        let range = set.range_by_lex(
+            &LexBound::Inclusive(b"b".to_vec()),
+            &LexBound::Exclusive(b"d".to_vec()),
+            false,
+            None,
+        );
+        assert_eq!(members(range), vec!["b", "c"]);
+
+        let range = set.range_by_lex(&LexBound::Min, &LexBound::Max, true, None);
+        assert_eq!(members(range), vec!["d", "c", "b", "a"]);
+
+        let range = set.range_by_lex(
+            &LexBound::Exclusive(b"a".to_vec()),
+            &LexBound::Max,
+            true,
+            Some((1, 1)),
+        );
+        assert_eq!(members(range), vec!["c"]);
+        assert!(set
+            .range_by_lex(&LexBound::Max, &LexBound::Max, false, None)
+            .is_empty());
+        assert!(set
+            .range_by_lex(&LexBound::Min, &LexBound::Min, true, None)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_range_by_score_bounds_and_limit() {
+        let mut set = sorted_set();
+        set.insert(b"zero".to_vec(), -0.0);
+        set.insert(b"inf".to_vec(), f64::INFINITY);
+
+        let all = set.range_by_score(
+            &ScoreBound::Inclusive(0.0),
+            &ScoreBound::Inclusive(f64::INFINITY),
+            false,
+            None,
+        );
+        assert_eq!(members(all), vec!["zero", "a", "b", "c", "inf"]);
+
+        let range = set.range_by_score(
+            &ScoreBound::Inclusive(1.0),
+            &ScoreBound::Exclusive(3.0),
+            false,
+            Some((1, 5)),
+        );
+        assert_eq!(members(range), vec!["b"]);
+
+        let range = set.range_by_score(
+            &ScoreBound::Exclusive(1.0),
+            &ScoreBound::Inclusive(3.0),
+            true,
+            Some((0, 1)),
+        );
+        assert_eq!(members(range), vec!["c"]);
+
+        assert_eq!(
+            set.count_by_score(&ScoreBound::Exclusive(0.0), &ScoreBound::Inclusive(2.0)),
+            2
+        );
+        assert_eq!(
+            set.count_by_score(&ScoreBound::Inclusive(3.0), &ScoreBound::Inclusive(1.0)),
+            0
+        );
+    }
+
+    #[test]
+    fn test_rank_and_range_by_rank_from_both_ends() {
+        let mut set = SortedSet::new();
+        for score in 0..10 {
+            set.insert(format!("m{}", score).into_bytes(), score as f64);
+        }
+
+        for score in 0..10 {
+            assert_eq!(set.rank(format!("m{}", score).as_bytes()), Some(score));
+        }
+        assert_eq!(set.rank(b"missing"), None);
+        assert_eq!(members(set.range_by_rank(-3, -2, false)), vec!["m7", "m8"]);
+        assert_eq!(members(set.range_by_rank(-3, -2, true)), vec!["m2", "m1"]);
+        assert_eq!(members(set.range_by_rank(1, 2, true)), vec!["m8", "m7"]);
+    }
+
+    #[test]
+    fn test_pop_min_and_max() {
+        let mut set = sorted_set();
+
+        assert_eq!(set.pop_min(), Some((b"a".to_vec(), 1.0)));
+        assert_eq!(set.pop_max(), Some((b"c".to_vec(), 3.0)));
+        assert_eq!(set.len(), 1);
+        assert_eq!(set.score(b"a"), None);
+    }
+
+    #[test]
+    fn test_rank_and_range_by_rank_match_a_linear_scan_on_a_large_set() {
+        let mut set = SortedSet::new();
+        for i in 0..20_000 {
+            // Pocos puntajes distintos para que haya muchos miembros con el mismo puntaje.
+            let score = (random_u64() % 1000) as f64 - 500.0;
+            set.insert(format!("m{}", i).into_bytes(), score);
+        }
+        for _ in 0..5_000 {
+            let member = format!("m{}", random_u64() % 20_000).into_bytes();
+            if random_u64().is_multiple_of(2) {
+                set.remove(&member);
+            } else {
+                set.insert(member, (random_u64() % 1000) as f64);
+            }
+        }
+
+        // Orden de referencia: todos los pares ordenados por puntaje y miembro.
+        let mut expected: Vec<(Vec<u8>, f64)> = set
+            .scores
+            .iter()
+            .map(|(member, score)| (member.clone(), *score))
+            .collect();
+        expected.sort_by(|a, b| a.1.total_cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+
+        assert_eq!(set.len(), expected.len());
+        for (position, (member, _)) in expected.iter().enumerate() {
+            assert_eq!(set.rank(member), Some(position));
+        }
+
+        let len = expected.len() as i64;
+        let mut reversed = expected.clone();
+        reversed.reverse();
+        for _ in 0..200 {
+            let start = (random_u64() % (2 * len as u64)) as i64 - len;
+            let stop = (random_u64() % (2 * len as u64)) as i64 - len;
+            let first = if start < 0 {
+                (len + start).max(0)
+            } else {
+                start
+            };
+            let last = if stop < 0 {
+                len + stop
+            } else {
+                stop.min(len - 1)
+            };
+            let (from, to) = (first as usize, (last + 1).max(first) as usize);
+            assert_eq!(
+                set.range_by_rank(start, stop, false),
+                expected[from..to].to_vec()
+            );
+            assert_eq!(
+                set.range_by_rank(start, stop, true),
+                reversed[from..to].to_vec()
+            );
+        }
+        assert_eq!(set.range_by_rank(0, -1, false), expected);
+        assert_eq!(set.range_by_rank(0, -1, true), reversed);
+    }
+}
@@ -0,0 +1,176 @@
+use crate::entities::command::Command;
+use crate::entities::reply::Reply;
+
+#[derive(Debug)]
+/// TransactionStep: Enum que indica qué debe hacer el cliente con un comando recibido.
+pub enum TransactionStep {
+    /// El comando debe enviarse a la base de datos para ser ejecutado.
+    Execute(Command),
+    /// El comando fue procesado por la transacción, se responde directamente al cliente.
+    Reply(Reply),
+    /// El comando no es válido, se responde con el error al cliente.
+    Error(String),
+}
+
+#[derive(Debug, Default)]
+/// Estado de la transacción (MULTI/EXEC) de una conexión. Mientras la transacción está abierta,
+/// los comandos recibidos se encolan para ser ejecutados atómicamente al recibir EXEC.
+pub struct Transaction {
+    /// Comandos encolados, o None si no hay una transacción abierta.
+    commands: Option<Vec<Command>>,
+    /// Indica si hubo errores al encolar algún comando.
+    aborted: bool,
+}
+
+impl Transaction {
+    /// Constructor de una Transaction sin transacción abierta.
+    pub fn new() -> Self {
+        Transaction {
+            commands: None,
+            aborted: false,
+        }
+    }
+
+    /// Indica si hay una transacción abierta.
+    pub fn is_open(&self) -> bool {
+        self.commands.is_some()
+    }
+
+    /// Procesa el resultado de generar un comando, devolviendo el paso a seguir por el cliente.
+    pub fn handle(&mut self, command: Result<Command, String>) -> TransactionStep {
+        if !self.is_open() {
+            return match command {
+                Ok(Command::Multi) => {
+                    self.commands = Some(vec![]);
+                    self.aborted = false;
+                    TransactionStep::Reply(Reply::SimpleString("OK".to_string()))
+                }
+                Ok(Command::Exec { .. }) => {
+                    TransactionStep::Error("ERR EXEC without MULTI".to_string())
+                }
+                Ok(Command::Discard { .. }) => {
+                    TransactionStep::Error("ERR DISCARD without MULTI".to_string())
+                }
+                Ok(command) => TransactionStep::Execute(command),
+                Err(err) => TransactionStep::Error(err),
+            };
+        }
+
+        match command {
+            Ok(Command::Multi) => {
+                TransactionStep::Error("ERR MULTI calls can not be nested".to_string())
+            }
+            Ok(Command::Watch { .. }) => {
+                TransactionStep::Error("ERR WATCH inside MULTI is not allowed".to_string())
+            }
+            Ok(Command::Exec { client_id, .. }) => {
+                let commands = self.commands.take();
+                let commands = if self.aborted { None } else { commands };
+                TransactionStep::Execute(Command::Exec {
+                    commands,
+                    client_id,
+                })
+            }
+            Ok(Command::Discard { client_id }) => {
+                self.commands = None;
+                TransactionStep::Execute(Command::Discard { client_id })
+            }
+            Ok(Command::Monitor)
+            | Ok(Command::Subscribe { .. })
+            | Ok(Command::Unsubscribe { .. })
+            | Ok(Command::Psubscribe { .. })
+            | Ok(Command::Punsubscribe { .. }) => {
+                self.aborted = true;
+                TransactionStep::Error("ERR Command not allowed inside a transaction".to_string())
+            }
+            Ok(command) => {
+                if let Some(commands) = self.commands.as_mut() {
+                    commands.push(command);
+                }
+                TransactionStep::Reply(Reply::SimpleString("QUEUED".to_string()))
+            }
+            Err(err) => {
+                self.aborted = true;
+                TransactionStep::Error(err)
+            }
+        }
+    }
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::entities::command::Command;
+    use crate::entities::reply::Reply;
+    use crate::entities::transaction::{Transaction, TransactionStep};
+
+    #[allow(dead_code)]
+    fn exec() -> Command {
+        Command::Exec {
+            commands: Some(vec![]),
+            client_id: "client".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_commands_executed_without_multi() {
+        let mut transaction = Transaction::new();
+
+        let step = transaction.handle(Ok(Command::Ping));
+        assert!(matches!(step, TransactionStep::Execute(Command::Ping)));
+        assert!(!transaction.is_open());
+    }
+
+    #[test]
+    fn test_exec_without_multi_err() {
+        let mut transaction = Transaction::new();
+
+        let step = transaction.handle(Ok(exec()));
+        assert!(matches!(step, TransactionStep::Error(_)));
+    }
+
+    #[test]
+    fn test_commands_queued_and_executed() {
+        let mut transaction = Transaction::new();
+
+        let step = transaction.handle(Ok(Command::Multi));
+        assert!(matches!(step, TransactionStep::Reply(Reply::SimpleString(ok)) if ok == "OK"));
+
+        let step = transaction.handle(Ok(Command::Ping));
+        assert!(
+            matches!(step, TransactionStep::Reply(Reply::SimpleString(queued)) if queued == "QUEUED")
+        );
+
+        let step = transaction.handle(Ok(exec()));
+        assert!(matches!(
+            step,
+            TransactionStep::Execute(Command::Exec { commands: Some(commands), .. })
+                if commands.len() == 1
+        ));
+        assert!(!transaction.is_open());
+    }
+
+    #[test]
+    fn test_error_while_queueing_aborts_exec() {
+        let mut transaction = Transaction::new();
+
+        transaction.handle(Ok(Command::Multi));
+        let step = transaction.handle(Err("ERR unknown command".to_string()));
+        assert!(matches!(step, TransactionStep::Error(_)));
+
+        let step = transaction.handle(Ok(exec()));
+        assert!(matches!(
+            step,
+            TransactionStep::Execute(Command::Exec { commands: None, .. })
+        ));
+    }
+
+    #[test]
+    fn test_nested_multi_err() {
+        let mut transaction = Transaction::new();
+
+        transaction.handle(Ok(Command::Multi));
+        let step = transaction.handle(Ok(Command::Multi));
+        assert!(matches!(step, TransactionStep::Error(_)));
+        assert!(transaction.is_open());
+    }
+}
@@ -1,29 +1,188 @@
+use crate::entities::compact_encoding::{intset_entries, listpack_entries, ziplist_entries};
+use crate::entities::crc64::crc64;
+use crate::entities::indexed_keys::IndexedKeys;
+use crate::entities::lzf::{compress, decompress};
 use crate::entities::redis_element::RedisElement;
-use std::collections::hash_map::Keys;
-use std::collections::HashMap;
-use std::hash::Hash;
-use std::str::from_utf8;
+use crate::entities::redis_hash::RedisHash;
+use crate::entities::scan_cursor::{scan_page, scan_position};
+use crate::entities::sorted_set::SortedSet;
+use std::collections::hash_map::RandomState;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::convert::TryFrom;
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::sync::Arc;
 use std::time::{Duration, SystemTime};
 use std::vec::Drain;
 
 #[derive(Debug)]
 /// Estructura para almacenar los pares clave-valor con su expiración, si corresponde. Además maneja los últimos accesos a cada clave.
+///
+/// Cada modificación de una clave le asigna una nueva versión, lo que permite detectar si una clave
+/// cambió entre dos momentos dados (usado por WATCH). La versión de una clave borrada sólo se
+/// conserva mientras algún cliente la observa. Las claves que se borran por haber expirado
+/// se guardan hasta que se piden con `take_expired`, para poder notificarlo.
+///
+/// Para desalojar claves cuando se supera `maxmemory` se lleva, además, un contador logarítmico
+/// de la frecuencia de acceso de cada clave (LFU) y la memoria aproximada que ocupa cada una.
+///
+/// Las claves se indexan también por su posición en el recorrido de SCAN, para poder continuar un
+/// recorrido sin volver a revisar todas las claves.
 pub struct TtlHashMap<K: Eq + Hash, V> {
-    store: HashMap<K, V>,
+    /// Valores guardados. Se comparten con las copias hechas con `snapshot` y sólo se copian si
+    /// se modifican mientras alguna copia los sigue usando.
+    store: HashMap<K, Arc<V>>,
+    /// Claves guardadas, indexadas para elegir candidatas al azar al desalojar.
+    keys: IndexedKeys<K>,
     ttls: HashMap<K, SystemTime>,
     last_access: HashMap<K, SystemTime>,
+    versions: HashMap<K, u64>,
+    version: u64,
+    /// Cantidad de clientes que observan cada clave con WATCH. Las versiones de las claves
+    /// borradas sólo se conservan mientras alguien las observa.
+    watchers: HashMap<K, usize>,
+    expired: Vec<K>,
+    /// Claves con expiración, indexadas para recorrerlas por tramos en la expiración activa.
+    volatile: IndexedKeys<K>,
+    /// Posición de `volatile` desde la que continúa la próxima muestra de `expire_sample`.
+    expire_cursor: usize,
+    frequencies: HashMap<K, u8>,
+    sizes: HashMap<K, usize>,
+    used_memory: usize,
+    /// Claves modificadas cuyo tamaño todavía no se volvió a medir.
+    modified: HashSet<K>,
+    /// Claves agrupadas por su posición en el recorrido de SCAN.
+    scan_positions: BTreeMap<u64, Vec<K>>,
 }
 
-impl<K: Clone + Eq + Hash, V> TtlHashMap<K, V> {
+impl<K: Clone + Eq + Hash, V: Clone> TtlHashMap<K, V> {
     /// Constructor de un nuevo TtlHashMap
     pub fn new() -> Self {
         TtlHashMap {
             store: HashMap::new(),
+            keys: IndexedKeys::new(),
             ttls: HashMap::new(),
             last_access: HashMap::new(),
+            versions: HashMap::new(),
+            version: 0,
+            watchers: HashMap::new(),
+            expired: Vec::new(),
+            volatile: IndexedKeys::new(),
+            expire_cursor: 0,
+            frequencies: HashMap::new(),
+            sizes: HashMap::new(),
+            used_memory: 0,
+            modified: HashSet::new(),
+            scan_positions: BTreeMap::new(),
         }
     }
 
+    /// Asigna una nueva versión a la clave, indicando que fue modificada.
+    fn bump_version(&mut self, key: &K) {
+        self.version += 1;
+        self.versions.insert(key.clone(), self.version);
+        self.modified.insert(key.clone());
+    }
+
+    /// Devuelve la versión actual de la clave. Si la clave nunca fue modificada, devuelve 0.
+    /// Si expiró, la borra antes de obtener la versión.
+    pub fn get_version(&mut self, key: &K) -> u64 {
+        self.contains_key(key);
+        self.versions.get(key).copied().unwrap_or(0)
+    }
+
+    /// Indica que un cliente empezó a observar la clave, por lo que hay que conservar su versión
+    /// aunque se borre.
+    pub fn watch(&mut self, key: &K) {
+        *self.watchers.entry(key.clone()).or_insert(0) += 1;
+    }
+
+    /// Indica que un cliente dejó de observar la clave. Si ya nadie la observa y no existe, se
+    /// olvida su versión.
+    pub fn unwatch(&mut self, key: &K) {
+        if let Some(count) = self.watchers.get_mut(key) {
+            *count -= 1;
+            if *count == 0 {
+                self.watchers.remove(key);
+                if !self.store.contains_key(key) {
+                    self.versions.remove(key);
+                }
+            }
+        }
+    }
+
+    /// Reemplaza la cantidad de clientes que observan cada clave, devolviendo la anterior. Las
+    /// versiones de las claves borradas que ya nadie observa se olvidan.
+    pub fn replace_watchers(&mut self, watchers: HashMap<K, usize>) -> HashMap<K, usize> {
+        let previous = std::mem::replace(&mut self.watchers, watchers);
+        let (store, watchers) = (&self.store, &self.watchers);
+        self.versions
+            .retain(|key, _| store.contains_key(key) || watchers.contains_key(key));
+        previous
+    }
+
+    /// Asigna una nueva versión a la clave borrada si alguien la observa, o sino olvida su
+    /// versión, ya que nadie la va a comparar.
+    fn forget_version(&mut self, key: &K) {
+        if self.watchers.contains_key(key) {
+            self.bump_version(key);
+        } else {
+            self.versions.remove(key);
+        }
+    }
+
+    /// Elimina todas las claves, asignándoles una nueva versión.
+    pub fn clear(&mut self) {
+        let keys: Vec<K> = self.store.keys().cloned().collect();
+        for key in keys.iter() {
+            self.forget_version(key);
+        }
+        self.store.clear();
+        self.keys.clear();
+        self.ttls.clear();
+        self.volatile.clear();
+        self.last_access.clear();
+        self.frequencies.clear();
+        self.sizes.clear();
+        self.modified.clear();
+        self.scan_positions.clear();
+        self.used_memory = 0;
+    }
+
+    /// Borra la clave que expiró, registrándola entre las claves expiradas.
+    fn remove_expired(&mut self, key: &K) {
+        if self.remove(key).is_some() {
+            self.expired.push(key.clone());
+        }
+    }
+
+    /// Devuelve las claves que se borraron por haber expirado desde la última llamada.
+    pub fn take_expired(&mut self) -> Vec<K> {
+        std::mem::take(&mut self.expired)
+    }
+
+    /// Revisa una muestra de hasta `count` claves con expiración, continuando desde donde terminó
+    /// la muestra anterior, y borra las que expiraron. Devuelve la cantidad de claves revisadas y
+    /// la cantidad de claves borradas. Cada muestra cuesta O(count), sin importar cuántas claves
+    /// se revisaron antes.
+    pub fn expire_sample(&mut self, count: usize) -> (usize, usize) {
+        if self.expire_cursor >= self.volatile.len() {
+            self.expire_cursor = 0;
+        }
+        let sample: Vec<K> = self.volatile.range(self.expire_cursor, count).to_vec();
+        let expired: Vec<K> = sample
+            .iter()
+            .filter(|key| self.expired(key))
+            .cloned()
+            .collect();
+
+        // Cada clave borrada deja su lugar a la última, que se revisa en una próxima vuelta.
+        self.expire_cursor += sample.len() - expired.len();
+        for key in expired.iter() {
+            self.remove_expired(key);
+        }
+        (sample.len(), expired.len())
+    }
+
     fn expired(&self, key: &K) -> bool {
         match self.ttls.get(key) {
             Some(ttl) => ttl.elapsed().is_ok(),
@@ -33,16 +192,80 @@ impl<K: Clone + Eq + Hash, V> TtlHashMap<K, V> {
 
     /// Actualiza el último acceso a la clave.
     /// Devuelve el tiempo transcurrido desde el anterior acceso, o None si no existe la clave o expiró.
+    ///
+    /// También actualiza el contador de frecuencia de la clave: primero se decrementa según los
+    /// minutos transcurridos desde el acceso anterior, y luego se incrementa con una probabilidad
+    /// que disminuye a medida que el contador crece, igual que en Redis.
     pub fn update_last_access(&mut self, key: &K) -> Option<Duration> {
         if !self.contains_key(key) {
             return None;
         }
 
+        let counter = lfu_log_incr(self.lfu_counter(key));
+        self.frequencies.insert(key.clone(), counter);
         self.last_access
             .insert(key.clone(), SystemTime::now())
             .map(|value| value.elapsed().unwrap_or_else(|_| Duration::from_secs(0)))
     }
 
+    /// Devuelve el tiempo transcurrido desde el último acceso a la clave.
+    pub fn idle_time(&self, key: &K) -> Duration {
+        self.last_access
+            .get(key)
+            .and_then(|last_access| last_access.elapsed().ok())
+            .unwrap_or_else(|| Duration::from_secs(0))
+    }
+
+    /// Devuelve el contador de frecuencia de acceso a la clave, decrementado en uno por cada
+    /// minuto transcurrido desde su último acceso.
+    pub fn lfu_counter(&self, key: &K) -> u8 {
+        let counter = self.frequencies.get(key).copied().unwrap_or(LFU_INIT_VAL);
+        let idle_minutes = self.idle_time(key).as_secs() / 60;
+        counter.saturating_sub(u8::try_from(idle_minutes).unwrap_or(u8::MAX))
+    }
+
+    /// Devuelve el momento en el que expira la clave, o None si es persistente.
+    pub fn expiration(&self, key: &K) -> Option<SystemTime> {
+        self.ttls.get(key).copied()
+    }
+
+    /// Recorre las claves que no expiraron a partir del cursor, revisando al menos `count` claves.
+    /// Devuelve el cursor con el que se continúa el recorrido (0 si terminó) y las claves.
+    pub fn scan(&self, cursor: u64, count: usize) -> (u64, Vec<K>) {
+        let (next_cursor, keys) = scan_page(&self.scan_positions, cursor, count);
+        let keys = keys
+            .into_iter()
+            .filter(|key| !self.expired(key))
+            .cloned()
+            .collect();
+        (next_cursor, keys)
+    }
+
+    /// Devuelve la cantidad de claves entre las que elige `sample_keys`: todas, o sólo las que
+    /// tienen expiración.
+    pub fn sample_len(&self, volatile: bool) -> usize {
+        match volatile {
+            true => self.volatile.len(),
+            false => self.keys.len(),
+        }
+    }
+
+    /// Devuelve hasta `count` claves distintas, consecutivas en el índice de claves a partir de una
+    /// posición al azar. Cada clave se obtiene en O(1). Si `volatile` es true, sólo se eligen
+    /// claves con expiración.
+    pub fn sample_keys(&self, count: usize, volatile: bool) -> Vec<K> {
+        let keys = match volatile {
+            true => &self.volatile,
+            false => &self.keys,
+        };
+        let len = keys.len();
+        let start = (random_u64() % len.max(1) as u64) as usize;
+        (0..count.min(len))
+            .filter_map(|offset| keys.get_wrapping(start + offset))
+            .cloned()
+            .collect()
+    }
+
     /// Setea una expiración para la clave a partir de una Duration.
     /// Devuelve None si no existe la clave, y SystemTime::UNIX_EPOCH si era persistente. Sino, devuelve el valor previo de ttl.
     pub fn set_ttl_relative(&mut self, key: K, duration: Duration) -> Option<SystemTime> {
@@ -50,6 +273,8 @@ impl<K: Clone + Eq + Hash, V> TtlHashMap<K, V> {
             return None;
         }
         let ttl = SystemTime::now() + duration;
+        self.bump_version(&key);
+        self.volatile.insert(&key);
         Some(self.ttls.insert(key, ttl).unwrap_or(SystemTime::UNIX_EPOCH))
     }
 
@@ -59,6 +284,8 @@ impl<K: Clone + Eq + Hash, V> TtlHashMap<K, V> {
         if !self.contains_key(&key) {
             return None;
         }
+        self.bump_version(&key);
+        self.volatile.insert(&key);
         Some(self.ttls.insert(key, ttl).unwrap_or(SystemTime::UNIX_EPOCH))
     }
 
@@ -66,10 +293,13 @@ impl<K: Clone + Eq + Hash, V> TtlHashMap<K, V> {
     /// Devuelve el ttl que tenía. Si no tenía una expiración, devuelve None.
     pub fn delete_ttl(&mut self, key: &K) -> Option<SystemTime> {
         if self.expired(key) {
-            self.remove(key);
+            self.remove_expired(key);
             return None;
         }
-        self.ttls.remove(key)
+        let ttl = self.ttls.remove(key)?;
+        self.volatile.remove(key);
+        self.bump_version(key);
+        Some(ttl)
     }
 
     /// Obtiene el tiempo restante de expiración de una clave como Duration.
@@ -95,8 +325,29 @@ impl<K: Clone + Eq + Hash, V> TtlHashMap<K, V> {
     /// Guarda un par clave-valor.
     pub fn insert(&mut self, key: K, value: V) {
         self.remove(&key);
+        self.bump_version(&key);
         self.last_access.insert(key.clone(), SystemTime::now());
-        self.store.insert(key, value);
+        self.frequencies.insert(key.clone(), LFU_INIT_VAL);
+        self.scan_positions
+            .entry(scan_position(&key))
+            .or_default()
+            .push(key.clone());
+        self.keys.insert(&key);
+        self.store.insert(key, Arc::new(value));
+    }
+
+    /// Guarda un par clave-valor igual que `insert`, pero manteniendo la expiración que tenía la
+    /// clave, si no expiró todavía.
+    pub fn insert_keeping_ttl(&mut self, key: K, value: V) {
+        let ttl = match self.expired(&key) {
+            true => None,
+            false => self.ttls.get(&key).copied(),
+        };
+        self.insert(key.clone(), value);
+        if let Some(ttl) = ttl {
+            self.volatile.insert(&key);
+            self.ttls.insert(key, ttl);
+        }
     }
 
     /// Devuelve si una clave existe o no, chequeando que no haya expirado. Si expiró, la borra.
@@ -104,7 +355,7 @@ impl<K: Clone + Eq + Hash, V> TtlHashMap<K, V> {
         match self.store.get(key) {
             Some(_value) => {
                 if self.expired(key) {
-                    self.remove(key);
+                    self.remove_expired(key);
                     return false;
                 }
                 true
@@ -116,154 +367,462 @@ impl<K: Clone + Eq + Hash, V> TtlHashMap<K, V> {
     /// Elimina el par clave-valor.
     /// Devuelve el valor, y si no existía la clave, devuelve None.
     pub fn remove(&mut self, key: &K) -> Option<V> {
-        self.ttls.remove(key);
+        if self.ttls.remove(key).is_some() {
+            self.volatile.remove(key);
+        }
         self.last_access.remove(key);
-        self.store.remove(key)
+        self.frequencies.remove(key);
+        let value = self.store.remove(key)?;
+        self.keys.remove(key);
+        let position = scan_position(key);
+        if let Some(keys) = self.scan_positions.get_mut(&position) {
+            keys.retain(|other| other != key);
+            if keys.is_empty() {
+                self.scan_positions.remove(&position);
+            }
+        }
+        self.forget_version(key);
+        self.modified.remove(key);
+        if let Some(size) = self.sizes.remove(key) {
+            self.used_memory -= size;
+        }
+        Some(Arc::try_unwrap(value).unwrap_or_else(|value| (*value).clone()))
     }
 
     /// Devuelve el valor correspondiente a la clave. Si expiró, la borra y devuelve None.
     pub fn get(&mut self, key: &K) -> Option<&V> {
         if self.expired(key) {
-            self.remove(key);
+            self.remove_expired(key);
             return None;
         }
         self.update_last_access(key);
-        self.store.get(key)
+        self.store.get(key).map(Arc::as_ref)
     }
 
     /// Devuelve una referencia mutable del valor correspondiente a la clave. Si expiró, la borra y devuelve None.
+    ///
+    /// Obtener la referencia no cambia la versión de la clave: si se modifica el valor, hay que
+    /// indicarlo con `mark_modified`. Si alguna copia hecha con `snapshot` comparte el valor, antes
+    /// se copia, para que la copia no vea la modificación.
     pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
         if self.expired(key) {
-            self.remove(key);
+            self.remove_expired(key);
             return None;
         }
         self.update_last_access(key);
-        self.store.get_mut(key)
+        self.store.get_mut(key).map(Arc::make_mut)
+    }
+
+    /// Indica que se modificó el valor de la clave a través de `get_mut`, asignándole una nueva
+    /// versión. No hace nada si la clave no existe.
+    pub fn mark_modified(&mut self, key: &K) {
+        if self.store.contains_key(key) {
+            self.bump_version(key);
+        }
     }
 
-    /// Devuelve todas las claves, sin chequear que no hayan expirado.
-    pub fn keys(&self) -> Keys<K, V> {
-        self.store.keys()
+    /// Devuelve un iterador sobre los pares clave-valor que no expiraron, junto a su expiración.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V, Option<SystemTime>)> {
+        self.store
+            .iter()
+            .filter(move |(key, _)| !self.expired(key))
+            .map(move |(key, value)| (key, value.as_ref(), self.ttls.get(key).copied()))
     }
 
     fn set_size(&mut self, store_size: usize, ttl_size: usize) {
         self.store.reserve(store_size);
         self.ttls.reserve(ttl_size);
     }
+
+    /// Devuelve una copia de las claves que no expiraron, con sus valores y expiraciones. No se
+    /// copian los últimos accesos, las versiones ni el índice de SCAN, ya que la copia sólo se usa
+    /// para persistirla.
+    ///
+    /// Los valores no se copian, sino que se comparten con la copia: el costo es proporcional a la
+    /// cantidad de claves y no al tamaño de los valores. Los valores que se modifican mientras la
+    /// copia existe se copian al modificarlos, una única vez cada uno.
+    pub fn snapshot(&self) -> Self {
+        let mut snapshot = TtlHashMap::new();
+        snapshot.set_size(self.store.len(), self.ttls.len());
+        for (key, value) in self.store.iter().filter(|(key, _)| !self.expired(key)) {
+            snapshot.store.insert(key.clone(), Arc::clone(value));
+            if let Some(ttl) = self.ttls.get(key) {
+                snapshot.ttls.insert(key.clone(), *ttl);
+            }
+        }
+        snapshot
+    }
 }
 
-const OP_EOF: u8 = 0xff;
-const OP_EXPIRETIME: u8 = 0xfd;
+/// Valor inicial del contador de frecuencia de una clave nueva, para que no se desaloje antes de
+/// tener la oportunidad de ser accedida.
+const LFU_INIT_VAL: u8 = 5;
+/// Factor que regula qué tan rápido deja de crecer el contador de frecuencia.
+const LFU_LOG_FACTOR: f64 = 10.0;
+/// Memoria aproximada que ocupa cada clave, además de su nombre y su valor.
+const KEY_OVERHEAD: usize = 64;
+
+/// Incrementa el contador de frecuencia con probabilidad 1 / ((contador - inicial) * factor + 1),
+/// de forma que el contador crece en forma logarítmica con la cantidad de accesos.
+fn lfu_log_incr(counter: u8) -> u8 {
+    if counter == u8::MAX {
+        return counter;
+    }
+    let base = counter.saturating_sub(LFU_INIT_VAL) as f64;
+    let probability = 1.0 / (base * LFU_LOG_FACTOR + 1.0);
+    if random_fraction() < probability {
+        counter + 1
+    } else {
+        counter
+    }
+}
+
+/// Devuelve un número pseudoaleatorio entre 0 y 1. Cada `RandomState` se crea con claves
+/// distintas, por lo que alcanza con el hash vacío.
+fn random_fraction() -> f64 {
+    random_u64() as f64 / u64::MAX as f64
+}
+
+/// Devuelve un número pseudoaleatorio.
+pub fn random_u64() -> u64 {
+    RandomState::new().build_hasher().finish()
+}
+
+/// Versión del formato RDB con la que se guardan los archivos.
+const RDB_VERSION: u32 = 9;
+/// Mayor versión del formato RDB que se puede cargar (la de Redis 7.4).
+const RDB_MAX_VERSION: u32 = 12;
+/// Primera versión del formato RDB que incluye un checksum al final del archivo.
+const RDB_CHECKSUM_VERSION: u32 = 5;
+/// Largo a partir del cual los strings se intentan comprimir con LZF.
+const LZF_MIN_LEN: usize = 20;
+
+const OP_FUNCTION2: u8 = 0xf5;
+const OP_MODULE_AUX: u8 = 0xf7;
+const OP_IDLE: u8 = 0xf8;
+const OP_FREQ: u8 = 0xf9;
+const OP_AUX: u8 = 0xfa;
 const OP_RESIZEDB: u8 = 0xfb;
-const WRONG_ELEMENT_TYPE: u8 = 3;
+const OP_EXPIRETIME_MS: u8 = 0xfc;
+const OP_EXPIRETIME: u8 = 0xfd;
+const OP_SELECTDB: u8 = 0xfe;
+const OP_EOF: u8 = 0xff;
 
-impl TtlHashMap<String, RedisElement> {
-    /// Devuelve un vector de bytes con el TtlHashMap serializado según el estandar de REDIS.
+const ENC_INT8: u8 = 0;
+const ENC_INT16: u8 = 1;
+const ENC_INT32: u8 = 2;
+const ENC_LZF: u8 = 3;
+
+const TYPE_STRING: u8 = 0;
+const TYPE_LIST: u8 = 1;
+const TYPE_SET: u8 = 2;
+const TYPE_ZSET: u8 = 3;
+const TYPE_HASH: u8 = 4;
+const TYPE_ZSET_2: u8 = 5;
+const TYPE_LIST_ZIPLIST: u8 = 10;
+const TYPE_SET_INTSET: u8 = 11;
+const TYPE_ZSET_ZIPLIST: u8 = 12;
+const TYPE_HASH_ZIPLIST: u8 = 13;
+const TYPE_LIST_QUICKLIST: u8 = 14;
+const TYPE_HASH_LISTPACK: u8 = 16;
+const TYPE_ZSET_LISTPACK: u8 = 17;
+const TYPE_LIST_QUICKLIST_2: u8 = 18;
+const TYPE_SET_LISTPACK: u8 = 20;
+/// Tipo usado para los elementos que no se guardan en el archivo.
+const WRONG_ELEMENT_TYPE: u8 = u8::MAX;
+
+/// Tipo de nodo de un quicklist que guarda un único elemento, sin empaquetar en un listpack.
+const QUICKLIST_NODE_PLAIN: u32 = 1;
+
+impl TtlHashMap<Vec<u8>, RedisElement> {
+    /// Devuelve la memoria aproximada que ocupan las claves y sus valores. Antes se vuelven a medir
+    /// las claves modificadas desde la llamada anterior.
+    pub fn used_memory(&mut self) -> usize {
+        for key in std::mem::take(&mut self.modified) {
+            if let Some(size) = self.sizes.remove(&key) {
+                self.used_memory -= size;
+            }
+            if let Some(value) = self.store.get(&key) {
+                let size = KEY_OVERHEAD + key.len() + value.memory_usage();
+                self.used_memory += size;
+                self.sizes.insert(key, size);
+            }
+        }
+        self.used_memory
+    }
+
+    /// Devuelve un vector de bytes con el TtlHashMap serializado como un archivo RDB con una única
+    /// base de datos, la 0.
+    #[allow(dead_code)]
     pub fn serialize(&self) -> Vec<u8> {
-        let mut s: Vec<u8> = vec![OP_RESIZEDB];
-        s.append(&mut TtlHashMap::length_encode(self.store.len()));
-        s.append(&mut TtlHashMap::length_encode(self.ttls.len()));
+        TtlHashMap::serialize_databases(&[self])
+    }
 
-        for (key, value) in self.store.iter() {
-            if let Some(ttl) = self.ttls.get(key) {
-                let secs = ttl
-                    .duration_since(SystemTime::UNIX_EPOCH)
-                    .unwrap_or_else(|_| Duration::from_secs(0))
-                    .as_secs();
-                s.push(OP_EXPIRETIME);
-                s.append(&mut (secs as u32).to_be_bytes().to_vec());
+    /// Devuelve un vector de bytes con las bases de datos serializadas como un archivo RDB, igual
+    /// al que genera Redis: encabezado, metadatos (AUX), una sección SELECTDB por cada base con
+    /// las claves que no expiraron y sus expiraciones en milisegundos, y el checksum CRC64 del
+    /// archivo al final. Las bases vacías se omiten, salvo la 0.
+    pub fn serialize_databases(dbs: &[&Self]) -> Vec<u8> {
+        let mut s: Vec<u8> = format!("REDIS{:04}", RDB_VERSION).into_bytes();
+
+        let ctime = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_else(|_| Duration::from_secs(0))
+            .as_secs()
+            .to_string();
+        let aux_fields = [
+            ("redis-ver", env!("CARGO_PKG_VERSION").to_string()),
+            ("redis-bits", "64".to_string()),
+            ("ctime", ctime),
+        ];
+        for (field, value) in aux_fields.iter() {
+            s.push(OP_AUX);
+            s.append(&mut TtlHashMap::string_encode(field.as_bytes().to_vec()));
+            s.append(&mut TtlHashMap::string_encode(value.as_bytes().to_vec()));
+        }
+
+        for (index, db) in dbs.iter().enumerate() {
+            let entries: Vec<(&Vec<u8>, &RedisElement, Option<SystemTime>)> = db
+                .iter()
+                .filter(|(_, value, _)| TtlHashMap::value_type_encode(value) != WRONG_ELEMENT_TYPE)
+                .collect();
+            if index > 0 && entries.is_empty() {
+                continue;
             }
-            let value_type = TtlHashMap::value_type_encode(value);
-            if value_type != WRONG_ELEMENT_TYPE {
-                s.push(value_type);
-                s.append(&mut TtlHashMap::string_encode(key.to_string()));
+            let expires = entries.iter().filter(|(_, _, ttl)| ttl.is_some()).count();
+            s.push(OP_SELECTDB);
+            s.append(&mut TtlHashMap::length_encode(index));
+            s.push(OP_RESIZEDB);
+            s.append(&mut TtlHashMap::length_encode(entries.len()));
+            s.append(&mut TtlHashMap::length_encode(expires));
+
+            for (key, value, ttl) in entries {
+                if let Some(ttl) = ttl {
+                    let millis = ttl
+                        .duration_since(SystemTime::UNIX_EPOCH)
+                        .unwrap_or_else(|_| Duration::from_secs(0))
+                        .as_millis() as u64;
+                    s.push(OP_EXPIRETIME_MS);
+                    s.extend_from_slice(&millis.to_le_bytes());
+                }
+                s.push(TtlHashMap::value_type_encode(value));
+                s.append(&mut TtlHashMap::string_encode(key.to_vec()));
                 s.append(&mut TtlHashMap::value_encode(value.clone()));
             }
         }
         s.push(OP_EOF);
+
+        let checksum = crc64(0, &s);
+        s.extend_from_slice(&checksum.to_le_bytes());
         s
     }
 
-    // Deserializa un vector de bytes para devolver un TtlHashMap cargado con todos los RedisElements.
-    pub fn deserialize(mut s: Vec<u8>) -> Result<Self, Box<dyn std::error::Error>> {
-        let mut map: TtlHashMap<String, RedisElement> = TtlHashMap::new();
-        let mut s = s.drain(..);
+    /// Deserializa un archivo RDB para devolver un TtlHashMap cargado con las claves de la base 0
+    /// que no expiraron.
+    #[allow(dead_code)]
+    pub fn deserialize(s: Vec<u8>) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut dbs = TtlHashMap::deserialize_databases(s, 1)?;
+        Ok(dbs.remove(0))
+    }
+
+    /// Deserializa un archivo RDB (de las versiones 1 a 12, como los generados por Redis) para
+    /// devolver `count` bases de datos cargadas con las claves que no expiraron. Las claves de
+    /// bases con un número mayor se descartan.
+    ///
+    /// Si el archivo tiene checksum, se verifica antes de cargarlo.
+    pub fn deserialize_databases(
+        mut s: Vec<u8>,
+        count: usize,
+    ) -> Result<Vec<Self>, Box<dyn std::error::Error>> {
+        if s.len() < 9 || &s[0..5] != b"REDIS" {
+            return Err("Error: file is not RDB type".into());
+        }
+        let version: u32 = String::from_utf8_lossy(&s[5..9])
+            .parse()
+            .map_err(|_| "Error: corrupt RDB version")?;
+        if version == 0 || version > RDB_MAX_VERSION {
+            return Err(format!("Error: unsupported RDB version {}", version).into());
+        }
 
-        match s.next().unwrap_or(0) {
-            OP_RESIZEDB => {
-                map.set_size(
-                    TtlHashMap::length_decode(&mut s).ok_or("Corrupt store size")? as usize,
-                    TtlHashMap::length_decode(&mut s).ok_or("Corrupt ttl size")? as usize,
-                );
-                map.load(&mut s)?;
-                Ok(map)
+        if version >= RDB_CHECKSUM_VERSION {
+            if s.len() < 18 {
+                return Err("Error: RDB file is truncated".into());
             }
-            OP_EOF => Ok(map),
-            _ => Err("Found unknown OP code.".into()),
+            let mut checksum = [0; 8];
+            checksum.copy_from_slice(&s[s.len() - 8..]);
+            let checksum = u64::from_le_bytes(checksum);
+            s.truncate(s.len() - 8);
+            // Redis guarda un checksum 0 cuando se deshabilita su cálculo.
+            if checksum != 0 && crc64(0, &s) != checksum {
+                return Err("Error: wrong RDB checksum".into());
+            }
+        }
+
+        let mut dbs: Vec<Self> = (0..count).map(|_| TtlHashMap::new()).collect();
+        let mut s = s.drain(9..);
+        TtlHashMap::load(&mut dbs, &mut s)?;
+        Ok(dbs)
+    }
+
+    /// Serializa un valor en el formato de DUMP de Redis: el tipo y el valor codificados como en
+    /// un archivo RDB, seguidos de la versión de RDB y el checksum CRC64.
+    pub fn dump(value: &RedisElement) -> Vec<u8> {
+        let mut s = vec![TtlHashMap::value_type_encode(value)];
+        s.append(&mut TtlHashMap::value_encode(value.clone()));
+        s.extend_from_slice(&(RDB_VERSION as u16).to_le_bytes());
+        let checksum = crc64(0, &s);
+        s.extend_from_slice(&checksum.to_le_bytes());
+        s
+    }
+
+    /// Deserializa un valor generado por `dump`, verificando su versión y checksum.
+    pub fn restore(mut s: Vec<u8>) -> Result<RedisElement, String> {
+        let payload_error = "ERR DUMP payload version or checksum are wrong".to_string();
+        if s.len() < 10 {
+            return Err(payload_error);
+        }
+        let mut checksum = [0; 8];
+        checksum.copy_from_slice(&s[s.len() - 8..]);
+        let version = u16::from_le_bytes([s[s.len() - 10], s[s.len() - 9]]);
+        if version as u32 > RDB_MAX_VERSION
+            || crc64(0, &s[..s.len() - 8]) != u64::from_le_bytes(checksum)
+        {
+            return Err(payload_error);
+        }
+
+        s.truncate(s.len() - 10);
+        let mut s = s.drain(..);
+        let value_type = s.next().ok_or_else(|| payload_error.clone())?;
+        match TtlHashMap::value_decode(&mut s, value_type) {
+            Some(value) if s.next().is_none() => Ok(value),
+            _ => Err("ERR Bad data format".to_string()),
         }
     }
 
-    fn load(&mut self, s: &mut Drain<'_, u8>) -> Result<(), Box<dyn std::error::Error>> {
+    fn load(dbs: &mut [Self], s: &mut Drain<'_, u8>) -> Result<(), Box<dyn std::error::Error>> {
+        let mut db = 0;
+        let mut ttl: Option<SystemTime> = None;
+
         while let Some(op_code) = s.next() {
             match op_code {
-                OP_EXPIRETIME => {
-                    let secs = TtlHashMap::read_int(s).ok_or("Corrupt expiry time")?;
-                    let ttl = SystemTime::UNIX_EPOCH + Duration::from_secs(secs as u64);
-
-                    if SystemTime::now().duration_since(ttl).is_err() {
-                        let value_type = s.next().ok_or("Corrupt value type")?;
-                        let key = TtlHashMap::string_decode(s).ok_or("Corrupt key")?;
-                        let value =
-                            TtlHashMap::value_decode(s, value_type).ok_or("Corrupt value")?;
-                        self.insert(key.clone(), value);
-                        self.set_ttl_absolute(key, ttl);
+                OP_EOF => return Ok(()),
+                OP_SELECTDB => {
+                    db = TtlHashMap::length_decode(s).ok_or("Corrupt database")? as usize
+                }
+                OP_RESIZEDB => {
+                    let store_size = TtlHashMap::length_decode(s).ok_or("Corrupt store size")?;
+                    let ttl_size = TtlHashMap::length_decode(s).ok_or("Corrupt ttl size")?;
+                    if let Some(map) = dbs.get_mut(db) {
+                        map.set_size(store_size as usize, ttl_size as usize);
                     }
                 }
-                OP_EOF => (),
-                _ => {
-                    let value_type = op_code;
+                OP_AUX => {
+                    TtlHashMap::string_decode(s).ok_or("Corrupt aux field")?;
+                    TtlHashMap::string_decode(s).ok_or("Corrupt aux value")?;
+                }
+                OP_FUNCTION2 => {
+                    TtlHashMap::string_decode(s).ok_or("Corrupt function")?;
+                }
+                OP_IDLE => {
+                    TtlHashMap::length_decode(s).ok_or("Corrupt idle time")?;
+                }
+                OP_FREQ => {
+                    s.next().ok_or("Corrupt frequency")?;
+                }
+                OP_EXPIRETIME_MS => {
+                    let millis =
+                        u64::from_le_bytes(TtlHashMap::read_bytes(s).ok_or("Corrupt expiry time")?);
+                    ttl = Some(SystemTime::UNIX_EPOCH + Duration::from_millis(millis));
+                }
+                OP_EXPIRETIME => {
+                    let secs =
+                        u32::from_le_bytes(TtlHashMap::read_bytes(s).ok_or("Corrupt expiry time")?);
+                    ttl = Some(SystemTime::UNIX_EPOCH + Duration::from_secs(secs as u64));
+                }
+                OP_MODULE_AUX => return Err("Found unsupported module data.".into()),
+                value_type => {
                     let key = TtlHashMap::string_decode(s).ok_or("Corrupt key")?;
-                    let value = TtlHashMap::value_decode(s, value_type).ok_or("Corrupt value")?;
-                    self.insert(key, value);
+                    let value = TtlHashMap::value_decode(s, value_type).ok_or_else(|| {
+                        format!("Found unsupported or corrupt value of type {}", value_type)
+                    })?;
+
+                    let ttl = ttl.take();
+                    let expired = matches!(ttl, Some(ttl) if ttl.elapsed().is_ok());
+                    match dbs.get_mut(db) {
+                        Some(map) if !expired => {
+                            map.insert(key.clone(), value);
+                            if let Some(ttl) = ttl {
+                                map.set_ttl_absolute(key, ttl);
+                            }
+                        }
+                        _ => (),
+                    }
                 }
             }
         }
-        Ok(())
+        Err("Corrupt file: EOF not found".into())
     }
 
-    fn bytes_as_u32_be(bytes: &[u8]) -> u32 {
-        ((bytes[0] as u32) << 24)
-            | ((bytes[1] as u32) << 16)
-            | ((bytes[2] as u32) << 8)
-            | (bytes[3] as u32)
+    fn read_bytes<const N: usize>(s: &mut Drain<'_, u8>) -> Option<[u8; N]> {
+        let mut bytes = [0; N];
+        for byte in bytes.iter_mut() {
+            *byte = s.next()?;
+        }
+        Some(bytes)
     }
 
-    fn read_int(s: &mut Drain<'_, u8>) -> Option<u32> {
-        Some(TtlHashMap::bytes_as_u32_be(&[
-            s.next()?,
-            s.next()?,
-            s.next()?,
-            s.next()?,
-        ]))
+    fn read_vec(s: &mut Drain<'_, u8>, len: usize) -> Option<Vec<u8>> {
+        let bytes: Vec<u8> = s.by_ref().take(len).collect();
+        if bytes.len() == len {
+            Some(bytes)
+        } else {
+            None
+        }
     }
 
-    fn string_decode(s: &mut Drain<'_, u8>) -> Option<String> {
-        let mut bytes: Vec<u8> = Vec::new();
-        let len = TtlHashMap::length_decode(s)?;
-        for _ in 0..len {
-            bytes.push(s.next()?);
+    /// Decodifica un string, que puede estar guardado como un entero o comprimido con LZF.
+    fn string_decode(s: &mut Drain<'_, u8>) -> Option<Vec<u8>> {
+        let first_byte = s.next()?;
+        if first_byte >> 6 != 0b11 {
+            let len = TtlHashMap::length_decode_from(first_byte, s)?;
+            return TtlHashMap::read_vec(s, len as usize);
         }
-        Some(from_utf8(&bytes).ok()?.to_string())
+
+        let integer = match first_byte & 0x3f {
+            ENC_INT8 => i8::from_le_bytes(TtlHashMap::read_bytes(s)?) as i32,
+            ENC_INT16 => i16::from_le_bytes(TtlHashMap::read_bytes(s)?) as i32,
+            ENC_INT32 => i32::from_le_bytes(TtlHashMap::read_bytes(s)?),
+            ENC_LZF => {
+                let compressed_len = TtlHashMap::length_decode(s)? as usize;
+                let len = TtlHashMap::length_decode(s)? as usize;
+                let compressed = TtlHashMap::read_vec(s, compressed_len)?;
+                return decompress(&compressed, len);
+            }
+            _ => return None,
+        };
+        Some(integer.to_string().into_bytes())
     }
 
-    pub fn string_encode(string: String) -> Vec<u8> {
+    /// Codifica un string. Los strings largos se comprimen con LZF si así ocupan menos espacio.
+    pub fn string_encode(mut string: Vec<u8>) -> Vec<u8> {
+        if string.len() > LZF_MIN_LEN {
+            let mut compressed = compress(&string);
+            if compressed.len() + 4 <= string.len() {
+                let mut bytes: Vec<u8> = vec![0xc0 | ENC_LZF];
+                bytes.append(&mut TtlHashMap::length_encode(compressed.len()));
+                bytes.append(&mut TtlHashMap::length_encode(string.len()));
+                bytes.append(&mut compressed);
+                return bytes;
+            }
+        }
+
         let mut bytes: Vec<u8> = vec![];
         bytes.append(&mut TtlHashMap::length_encode(string.len()));
-        bytes.append(&mut string.as_bytes().to_vec());
+        bytes.append(&mut string);
         bytes
     }
 
-    pub fn list_encode(list: Vec<String>) -> Vec<u8> {
+    pub fn list_encode(list: Vec<Vec<u8>>) -> Vec<u8> {
         let mut bytes = TtlHashMap::length_encode(list.len());
         for value in list {
             bytes.append(&mut TtlHashMap::string_encode(value));
@@ -271,15 +830,132 @@ impl TtlHashMap<String, RedisElement> {
         bytes
     }
 
-    fn list_decode(s: &mut Drain<'_, u8>) -> Option<Vec<String>> {
+    fn list_decode(s: &mut Drain<'_, u8>) -> Option<Vec<Vec<u8>>> {
         let len = TtlHashMap::length_decode(s)?;
-        let mut vec: Vec<String> = vec![];
+        let mut vec: Vec<Vec<u8>> = vec![];
         for _ in 0..len {
             vec.push(TtlHashMap::string_decode(s)?);
         }
         Some(vec)
     }
 
+    /// Decodifica una lista guardada como quicklist: una secuencia de nodos, cada uno con varios
+    /// elementos empaquetados en un ziplist (`TYPE_LIST_QUICKLIST`) o en un listpack
+    /// (`TYPE_LIST_QUICKLIST_2`).
+    fn quicklist_decode(s: &mut Drain<'_, u8>, value_type: u8) -> Option<Vec<Vec<u8>>> {
+        let nodes = TtlHashMap::length_decode(s)?;
+        let mut list: Vec<Vec<u8>> = vec![];
+        for _ in 0..nodes {
+            if value_type == TYPE_LIST_QUICKLIST {
+                list.append(&mut ziplist_entries(&TtlHashMap::string_decode(s)?)?);
+            } else {
+                let container = TtlHashMap::length_decode(s)?;
+                let node = TtlHashMap::string_decode(s)?;
+                if container == QUICKLIST_NODE_PLAIN {
+                    list.push(node);
+                } else {
+                    list.append(&mut listpack_entries(&node)?);
+                }
+            }
+        }
+        Some(list)
+    }
+
+    pub fn hash_encode(hash: RedisHash) -> Vec<u8> {
+        let mut bytes = TtlHashMap::length_encode(hash.len());
+        for (field, value) in hash {
+            bytes.append(&mut TtlHashMap::string_encode(field));
+            bytes.append(&mut TtlHashMap::string_encode(value));
+        }
+        bytes
+    }
+
+    fn hash_decode(s: &mut Drain<'_, u8>) -> Option<RedisHash> {
+        let len = TtlHashMap::length_decode(s)?;
+        let mut hash = RedisHash::new();
+        for _ in 0..len {
+            let field = TtlHashMap::string_decode(s)?;
+            let value = TtlHashMap::string_decode(s)?;
+            hash.insert(field, value);
+        }
+        Some(hash)
+    }
+
+    /// Arma un hash a partir de los elementos de un ziplist o listpack: campo, valor, campo...
+    fn hash_from_entries(entries: Vec<Vec<u8>>) -> Option<RedisHash> {
+        if !entries.len().is_multiple_of(2) {
+            return None;
+        }
+        let mut entries = entries.into_iter();
+        let mut hash = RedisHash::new();
+        while let (Some(field), Some(value)) = (entries.next(), entries.next()) {
+            hash.insert(field, value);
+        }
+        Some(hash)
+    }
+
+    pub fn sorted_set_encode(sorted_set: SortedSet) -> Vec<u8> {
+        let mut bytes = TtlHashMap::length_encode(sorted_set.len());
+        for (member, score) in sorted_set.iter() {
+            bytes.append(&mut TtlHashMap::string_encode(member.to_vec()));
+            bytes.append(&mut score.to_le_bytes().to_vec());
+        }
+        bytes
+    }
+
+    fn sorted_set_decode(s: &mut Drain<'_, u8>) -> Option<SortedSet> {
+        let len = TtlHashMap::length_decode(s)?;
+        let mut sorted_set = SortedSet::new();
+        for _ in 0..len {
+            let member = TtlHashMap::string_decode(s)?;
+            let score = f64::from_le_bytes(TtlHashMap::read_bytes(s)?);
+            sorted_set.insert(member, score);
+        }
+        Some(sorted_set)
+    }
+
+    /// Decodifica un sorted set con el formato anterior a RDB 8, que guarda los puntajes como
+    /// texto: un byte con el largo (o 254 para +inf, 255 para -inf) seguido del número.
+    fn sorted_set_decode_v1(s: &mut Drain<'_, u8>) -> Option<SortedSet> {
+        let len = TtlHashMap::length_decode(s)?;
+        let mut sorted_set = SortedSet::new();
+        for _ in 0..len {
+            let member = TtlHashMap::string_decode(s)?;
+            let score = match s.next()? {
+                254 => f64::INFINITY,
+                255 => f64::NEG_INFINITY,
+                253 => return None,
+                score_len => {
+                    TtlHashMap::parse_score(&TtlHashMap::read_vec(s, score_len as usize)?)?
+                }
+            };
+            sorted_set.insert(member, score);
+        }
+        Some(sorted_set)
+    }
+
+    /// Arma un sorted set a partir de los elementos de un ziplist o listpack: miembro, puntaje...
+    fn sorted_set_from_entries(entries: Vec<Vec<u8>>) -> Option<SortedSet> {
+        if !entries.len().is_multiple_of(2) {
+            return None;
+        }
+        let mut entries = entries.into_iter();
+        let mut sorted_set = SortedSet::new();
+        while let (Some(member), Some(score)) = (entries.next(), entries.next()) {
+            sorted_set.insert(member, TtlHashMap::parse_score(&score)?);
+        }
+        Some(sorted_set)
+    }
+
+    fn parse_score(score: &[u8]) -> Option<f64> {
+        let score: f64 = String::from_utf8_lossy(score).parse().ok()?;
+        if score.is_nan() {
+            None
+        } else {
+            Some(score)
+        }
+    }
+
     pub fn length_encode(length: usize) -> Vec<u8> {
         if length < 64 {
             // 00 + length in 6 bits
@@ -287,29 +963,31 @@ impl TtlHashMap<String, RedisElement> {
         } else if length < 16384 {
             // 01 + length in 14 bits
             vec![0x40 | (length >> 8) as u8, length as u8]
-        } else {
+        } else if length <= u32::MAX as usize {
             // 1000 0000 + length in 32 bits
-            vec![
-                0x80,
-                (length >> 24) as u8,
-                (length >> 16) as u8,
-                (length >> 8) as u8,
-                length as u8,
-            ]
+            let mut bytes = vec![0x80];
+            bytes.extend_from_slice(&(length as u32).to_be_bytes());
+            bytes
+        } else {
+            // 1000 0001 + length in 64 bits
+            let mut bytes = vec![0x81];
+            bytes.extend_from_slice(&(length as u64).to_be_bytes());
+            bytes
         }
     }
 
     fn length_decode(s: &mut Drain<'_, u8>) -> Option<u32> {
-        let first_byte = s.next().unwrap_or(5);
-        match first_byte >> 6 {
-            0b00 => Some(first_byte as u32),
-            0b01 => Some(TtlHashMap::bytes_as_u32_be(&[
-                0,
-                0,
-                first_byte & 0b00111111,
-                s.next()?,
-            ])),
-            0b10 => Some(TtlHashMap::read_int(s)?),
+        let first_byte = s.next()?;
+        TtlHashMap::length_decode_from(first_byte, s)
+    }
+
+    /// Decodifica un largo a partir de su primer byte, ya leído.
+    fn length_decode_from(first_byte: u8, s: &mut Drain<'_, u8>) -> Option<u32> {
+        match first_byte {
+            0x00..=0x3f => Some(first_byte as u32),
+            0x40..=0x7f => Some((((first_byte & 0x3f) as u32) << 8) | s.next()? as u32),
+            0x80 => Some(u32::from_be_bytes(TtlHashMap::read_bytes(s)?)),
+            0x81 => u32::try_from(u64::from_be_bytes(TtlHashMap::read_bytes(s)?)).ok(),
             _ => None,
         }
     }
@@ -317,28 +995,71 @@ impl TtlHashMap<String, RedisElement> {
     pub fn value_encode(value: RedisElement) -> Vec<u8> {
         match value {
             RedisElement::String(string) => TtlHashMap::string_encode(string),
-            RedisElement::List(list) => TtlHashMap::list_encode(list),
+            RedisElement::List(list) => TtlHashMap::list_encode(list.into()),
             RedisElement::Set(set) => TtlHashMap::list_encode(set.into_iter().collect()),
+            RedisElement::Hash(hash) => TtlHashMap::hash_encode(hash),
+            RedisElement::SortedSet(sorted_set) => TtlHashMap::sorted_set_encode(sorted_set),
             _ => vec![],
         }
     }
 
+    /// Decodifica un valor del tipo indicado. Además de los tipos que se guardan, soporta las
+    /// codificaciones compactas (ziplist, listpack, intset y quicklist) que usa Redis.
     fn value_decode(s: &mut Drain<'_, u8>, value_type: u8) -> Option<RedisElement> {
         match value_type {
-            0 => Some(RedisElement::String(TtlHashMap::string_decode(s)?)),
-            1 => Some(RedisElement::List(TtlHashMap::list_decode(s)?)),
-            2 => Some(RedisElement::Set(
+            TYPE_STRING => Some(RedisElement::String(TtlHashMap::string_decode(s)?)),
+            TYPE_LIST => Some(RedisElement::List(TtlHashMap::list_decode(s)?.into())),
+            TYPE_SET => Some(RedisElement::Set(
                 TtlHashMap::list_decode(s)?.into_iter().collect(),
             )),
+            TYPE_ZSET => Some(RedisElement::SortedSet(TtlHashMap::sorted_set_decode_v1(
+                s,
+            )?)),
+            TYPE_HASH => Some(RedisElement::Hash(TtlHashMap::hash_decode(s)?)),
+            TYPE_ZSET_2 => Some(RedisElement::SortedSet(TtlHashMap::sorted_set_decode(s)?)),
+            TYPE_LIST_ZIPLIST => Some(RedisElement::List(
+                ziplist_entries(&TtlHashMap::string_decode(s)?)?.into(),
+            )),
+            TYPE_LIST_QUICKLIST | TYPE_LIST_QUICKLIST_2 => Some(RedisElement::List(
+                TtlHashMap::quicklist_decode(s, value_type)?.into(),
+            )),
+            TYPE_SET_INTSET => Some(RedisElement::Set(
+                intset_entries(&TtlHashMap::string_decode(s)?)?
+                    .into_iter()
+                    .collect(),
+            )),
+            TYPE_SET_LISTPACK => Some(RedisElement::Set(
+                listpack_entries(&TtlHashMap::string_decode(s)?)?
+                    .into_iter()
+                    .collect(),
+            )),
+            TYPE_HASH_ZIPLIST => Some(RedisElement::Hash(TtlHashMap::hash_from_entries(
+                ziplist_entries(&TtlHashMap::string_decode(s)?)?,
+            )?)),
+            TYPE_HASH_LISTPACK => Some(RedisElement::Hash(TtlHashMap::hash_from_entries(
+                listpack_entries(&TtlHashMap::string_decode(s)?)?,
+            )?)),
+            TYPE_ZSET_ZIPLIST => Some(RedisElement::SortedSet(
+                TtlHashMap::sorted_set_from_entries(ziplist_entries(&TtlHashMap::string_decode(
+                    s,
+                )?)?)?,
+            )),
+            TYPE_ZSET_LISTPACK => Some(RedisElement::SortedSet(
+                TtlHashMap::sorted_set_from_entries(listpack_entries(
+                    &TtlHashMap::string_decode(s)?,
+                )?)?,
+            )),
             _ => None,
         }
     }
 
     pub fn value_type_encode(value: &RedisElement) -> u8 {
         match value {
-            RedisElement::String(_) => 0,
-            RedisElement::List(_) => 1,
-            RedisElement::Set(_) => 2,
+            RedisElement::String(_) => TYPE_STRING,
+            RedisElement::List(_) => TYPE_LIST,
+            RedisElement::Set(_) => TYPE_SET,
+            RedisElement::Hash(_) => TYPE_HASH,
+            RedisElement::SortedSet(_) => TYPE_ZSET_2,
             _ => WRONG_ELEMENT_TYPE,
         }
     }
@@ -346,8 +1067,13 @@ impl TtlHashMap<String, RedisElement> {
 
 #[allow(unused_imports)]
 mod test {
+    use crate::entities::crc64::crc64;
+    use crate::entities::sorted_set::SortedSet;
     use crate::entities::ttl_hash_map::RedisElement;
     use crate::entities::ttl_hash_map::TtlHashMap;
+    use std::collections::HashMap;
+    use std::convert::TryInto;
+    use std::sync::Arc;
     use std::time::{Duration, SystemTime};
 
     #[test]
@@ -420,6 +1146,66 @@ mod test {
         assert!(map.get(&key).is_none());
     }
 
+    #[test]
+    fn test_take_expired_returns_expired_keys_once() {
+        let mut map: TtlHashMap<String, u8> = TtlHashMap::new();
+
+        map.insert("expired".to_string(), 1);
+        map.insert("removed".to_string(), 1);
+        map.set_ttl_absolute("expired".to_string(), SystemTime::now());
+        map.remove(&"removed".to_string());
+
+        assert!(!map.contains_key(&"expired".to_string()));
+        assert!(map.get(&"expired".to_string()).is_none());
+        assert_eq!(map.take_expired(), vec!["expired".to_string()]);
+        assert!(map.take_expired().is_empty());
+    }
+
+    #[test]
+    fn test_expire_sample_removes_expired_keys() {
+        let mut map: TtlHashMap<String, u8> = TtlHashMap::new();
+        for index in 0..10 {
+            let key = index.to_string();
+            map.insert(key.clone(), index);
+            if index % 2 == 0 {
+                map.set_ttl_absolute(key, SystemTime::now());
+            } else {
+                map.set_ttl_relative(key, Duration::from_secs(100));
+            }
+        }
+        map.insert("persistent".to_string(), 0);
+
+        let (sampled, expired) = map.expire_sample(4);
+        let mut total = (sampled, expired);
+        while total.0 < 10 {
+            let (sampled, expired) = map.expire_sample(4);
+            total = (total.0 + sampled, total.1 + expired);
+        }
+
+        assert_eq!(sampled, 4);
+        assert_eq!(total, (10, 5));
+        assert_eq!(map.len(), 6);
+        assert_eq!(map.take_expired().len(), 5);
+        assert_eq!(map.expire_sample(10), (5, 0));
+    }
+
+    #[test]
+    fn test_expire_sample_only_checks_volatile_keys() {
+        let mut map: TtlHashMap<String, u8> = TtlHashMap::new();
+        for index in 0..4 {
+            let key = index.to_string();
+            map.insert(key.clone(), index);
+            map.set_ttl_relative(key, Duration::from_secs(100));
+        }
+        map.delete_ttl(&"0".to_string());
+        map.remove(&"1".to_string());
+        map.insert("2".to_string(), 2);
+
+        assert_eq!(map.expire_sample(10), (1, 0));
+        map.clear();
+        assert_eq!(map.expire_sample(10), (0, 0));
+    }
+
     #[test]
     fn test_delete_ttl_on_presistent_key() {
         let mut map: TtlHashMap<String, u8> = TtlHashMap::new();
@@ -444,6 +1230,83 @@ mod test {
         assert!(map.get(&key).is_some());
     }
 
+    #[test]
+    fn test_insert_keeping_ttl() {
+        let mut map: TtlHashMap<String, u8> = TtlHashMap::new();
+        let ttl = SystemTime::now() + Duration::from_secs(100);
+
+        map.insert("volatile".to_string(), 1);
+        map.set_ttl_absolute("volatile".to_string(), ttl);
+        map.insert_keeping_ttl("volatile".to_string(), 2);
+        map.insert("expired".to_string(), 1);
+        map.set_ttl_absolute("expired".to_string(), SystemTime::now());
+        map.insert_keeping_ttl("expired".to_string(), 2);
+
+        assert_eq!(map.expiration(&"volatile".to_string()), Some(ttl));
+        assert_eq!(map.get(&"volatile".to_string()), Some(&2));
+        assert!(map.expiration(&"expired".to_string()).is_none());
+        assert_eq!(map.get(&"expired".to_string()), Some(&2));
+    }
+
+    #[test]
+    fn test_iter_skips_expired_keys() {
+        let mut map: TtlHashMap<String, u8> = TtlHashMap::new();
+        let ttl = SystemTime::now() + Duration::from_secs(100);
+
+        map.insert("expired".to_string(), 1);
+        map.set_ttl_absolute("expired".to_string(), SystemTime::now());
+        map.insert("volatile".to_string(), 2);
+        map.set_ttl_absolute("volatile".to_string(), ttl);
+        map.insert("persistent".to_string(), 3);
+
+        let mut entries: Vec<(&String, &u8, Option<SystemTime>)> = map.iter().collect();
+        entries.sort();
+        assert_eq!(
+            entries,
+            vec![
+                (&"persistent".to_string(), &3, None),
+                (&"volatile".to_string(), &2, Some(ttl)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_snapshot_is_independent_copy() {
+        let mut map: TtlHashMap<String, u8> = TtlHashMap::new();
+        let ttl = SystemTime::now() + Duration::from_secs(100);
+        map.insert("expired".to_string(), 1);
+        map.set_ttl_absolute("expired".to_string(), SystemTime::now());
+        map.insert("volatile".to_string(), 2);
+        map.set_ttl_absolute("volatile".to_string(), ttl);
+
+        let mut snapshot = map.snapshot();
+        map.insert("volatile".to_string(), 5);
+
+        assert_eq!(snapshot.len(), 1);
+        assert_eq!(snapshot.get(&"volatile".to_string()), Some(&2));
+        assert_eq!(snapshot.iter().next().unwrap().2, Some(ttl));
+    }
+
+    #[test]
+    fn test_snapshot_shares_values_until_modified() {
+        let mut map: TtlHashMap<String, Vec<u8>> = TtlHashMap::new();
+        let key = "key".to_string();
+        map.insert(key.clone(), vec![1, 2]);
+
+        let mut snapshot = map.snapshot();
+        assert!(Arc::ptr_eq(&map.store[&key], &snapshot.store[&key]));
+
+        map.get_mut(&key).unwrap().push(3);
+        assert!(!Arc::ptr_eq(&map.store[&key], &snapshot.store[&key]));
+        assert_eq!(map.get(&key), Some(&vec![1, 2, 3]));
+        assert_eq!(snapshot.get(&key), Some(&vec![1, 2]));
+
+        drop(snapshot);
+        let value = Arc::as_ptr(&map.store[&key]);
+        map.get_mut(&key).unwrap().push(4);
+        assert_eq!(Arc::as_ptr(&map.store[&key]), value);
+    }
+
     #[ignore]
     #[test]
     fn test_new_key_last_access() {
@@ -469,6 +1332,98 @@ mod test {
         assert_eq!(map.update_last_access(&key).unwrap().as_secs(), 0);
     }
 
+    #[test]
+    fn test_versions_change_on_modification() {
+        let mut map: TtlHashMap<String, u8> = TtlHashMap::new();
+        let key = "key".to_string();
+
+        assert_eq!(map.get_version(&key), 0);
+        map.insert(key.clone(), 1);
+        let inserted = map.get_version(&key);
+        assert!(inserted > 0);
+
+        map.get(&key);
+        assert_eq!(map.get_version(&key), inserted);
+
+        map.watch(&key);
+        map.remove(&key);
+        let removed = map.get_version(&key);
+        assert!(removed > inserted);
+
+        map.unwatch(&key);
+        assert_eq!(map.get_version(&key), 0);
+        map.insert(key.clone(), 1);
+        map.remove(&key);
+        assert_eq!(map.get_version(&key), 0);
+        assert!(map.versions.is_empty());
+    }
+
+    #[test]
+    fn test_get_mut_changes_version_only_when_marked() {
+        let mut map: TtlHashMap<String, u8> = TtlHashMap::new();
+        let key = "key".to_string();
+
+        map.insert(key.clone(), 1);
+        let inserted = map.get_version(&key);
+        map.get_mut(&key);
+        assert_eq!(map.get_version(&key), inserted);
+
+        if let Some(value) = map.get_mut(&key) {
+            *value = 2;
+        }
+        map.mark_modified(&key);
+        assert!(map.get_version(&key) > inserted);
+
+        map.mark_modified(&"missing".to_string());
+        assert_eq!(map.get_version(&"missing".to_string()), 0);
+    }
+
+    #[test]
+    fn test_clear_changes_versions() {
+        let mut map: TtlHashMap<String, u8> = TtlHashMap::new();
+        let key = "key".to_string();
+
+        map.insert(key.clone(), 1);
+        let inserted = map.get_version(&key);
+        map.watch(&key);
+        map.clear();
+
+        assert!(map.get_version(&key) > inserted);
+        assert!(map.get(&key).is_none());
+    }
+
+    #[test]
+    fn test_used_memory_follows_modifications() {
+        let mut map: TtlHashMap<Vec<u8>, RedisElement> = TtlHashMap::new();
+        let key = b"key".to_vec();
+
+        assert_eq!(map.used_memory(), 0);
+        map.insert(key.clone(), RedisElement::String(b"value".to_vec()));
+        let inserted = map.used_memory();
+        assert!(inserted > 0);
+
+        if let Some(RedisElement::String(value)) = map.get_mut(&key) {
+            value.extend_from_slice(&[0; 100]);
+        }
+        map.mark_modified(&key);
+        assert!(map.used_memory() >= inserted + 100);
+
+        map.remove(&key);
+        assert_eq!(map.used_memory(), 0);
+    }
+
+    #[test]
+    fn test_sample_keys_only_volatile() {
+        let mut map: TtlHashMap<String, u8> = TtlHashMap::new();
+        for index in 0..10 {
+            map.insert(format!("key{}", index), index);
+        }
+        map.set_ttl_relative("key3".to_string(), Duration::from_secs(100));
+
+        assert_eq!(map.sample_keys(5, false).len(), 5);
+        assert_eq!(map.sample_keys(5, true), vec!["key3".to_string()]);
+    }
+
     #[test]
     fn test_remove_key_and_add_again() {
         let mut map: TtlHashMap<String, u8> = TtlHashMap::new();
@@ -487,7 +1442,7 @@ mod test {
 
     #[test]
     fn test_value_encode_decode() {
-        let value = RedisElement::String("value".to_string());
+        let value = RedisElement::String("value".into());
         let mut encoded = TtlHashMap::value_encode(value.clone());
         let decoded = TtlHashMap::value_decode(
             &mut encoded.drain(..),
@@ -496,73 +1451,230 @@ mod test {
         assert_eq!(value, decoded.unwrap());
     }
 
+    #[allow(dead_code)]
+    fn rdb_file(mut body: Vec<u8>) -> Vec<u8> {
+        let mut bytes = b"REDIS0009".to_vec();
+        bytes.append(&mut body);
+        bytes.push(0xff);
+        let checksum = crc64(0, &bytes);
+        bytes.extend_from_slice(&checksum.to_le_bytes());
+        bytes
+    }
+
     #[test]
     fn test_serialize() {
-        let mut map: TtlHashMap<String, RedisElement> = TtlHashMap::new();
-        let key = "key".to_string();
-        let value = RedisElement::String("value".to_string());
+        let mut map: TtlHashMap<Vec<u8>, RedisElement> = TtlHashMap::new();
+        let key = b"key".to_vec();
+        let value = RedisElement::String("value".into());
         map.insert(key.clone(), value.clone());
-        map.set_ttl_relative(key.clone(), Duration::from_secs(2));
+        let ttl = SystemTime::UNIX_EPOCH + Duration::from_millis(4_000_000_000_123);
+        map.set_ttl_absolute(key.clone(), ttl);
         let bytes = map.serialize();
 
-        let op_resizedb = 0xfb;
-        let mut store_len = TtlHashMap::length_encode(1);
-        let mut ttl_len = TtlHashMap::length_encode(1);
-        let op_expiretime = 0xfd;
-        let secs = (SystemTime::now() + Duration::from_secs(2))
-            .duration_since(SystemTime::UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
-        let mut ttl = (secs as u32).to_be_bytes().to_vec();
-        let byte_value_type = TtlHashMap::value_type_encode(&RedisElement::String("".to_string()));
-        let mut key_encoded = TtlHashMap::string_encode(key);
-        let mut value_encoded = TtlHashMap::string_encode("value".to_string());
-        let op_eof = 0xff;
-
-        let mut vec = vec![op_resizedb];
-        vec.append(&mut store_len);
-        vec.append(&mut ttl_len);
-        vec.push(op_expiretime);
-        vec.append(&mut ttl);
-        vec.push(byte_value_type);
-        vec.append(&mut key_encoded);
-        vec.append(&mut value_encoded);
-        vec.push(op_eof);
-        assert_eq!(bytes, vec);
+        assert_eq!(&bytes[0..9], b"REDIS0009");
+        assert_eq!(bytes[9], 0xfa);
+        let checksum = u64::from_le_bytes(bytes[bytes.len() - 8..].try_into().unwrap());
+        assert_eq!(crc64(0, &bytes[..bytes.len() - 8]), checksum);
+
+        let mut body = vec![0xfe, 0x00, 0xfb, 0x01, 0x01, 0xfc];
+        body.extend_from_slice(&4_000_000_000_123u64.to_le_bytes());
+        body.push(TtlHashMap::value_type_encode(&value));
+        body.append(&mut TtlHashMap::string_encode(key));
+        body.append(&mut TtlHashMap::string_encode("value".into()));
+        body.push(0xff);
+        assert!(bytes[..bytes.len() - 8].ends_with(&body));
     }
 
     #[test]
     fn test_deserialize() {
-        let op_resizedb = 0xfb;
-        let mut store_len = TtlHashMap::length_encode(1);
-        let mut ttl_len = TtlHashMap::length_encode(0);
-        let byte_value_type = TtlHashMap::value_type_encode(&RedisElement::String("".to_string()));
-        let key = "key".to_string();
-        let mut key_encoded = TtlHashMap::string_encode(key.clone());
-        let mut value_encoded = TtlHashMap::string_encode("value".to_string());
-        let op_eof = 0xff;
-
-        let mut bytes = vec![op_resizedb];
-        bytes.append(&mut store_len);
-        bytes.append(&mut ttl_len);
-        bytes.push(byte_value_type);
-        bytes.append(&mut key_encoded);
-        bytes.append(&mut value_encoded);
-        bytes.push(op_eof);
+        let key = b"key".to_vec();
+        let mut body = vec![0xfe, 0x00, 0xfb, 0x01, 0x00];
+        body.push(TtlHashMap::value_type_encode(&RedisElement::String(
+            "".into(),
+        )));
+        body.append(&mut TtlHashMap::string_encode(key.clone()));
+        body.append(&mut TtlHashMap::string_encode("value".into()));
 
-        let mut map = TtlHashMap::deserialize(bytes).unwrap();
+        let mut map = TtlHashMap::deserialize(rdb_file(body)).unwrap();
 
         assert_eq!(
             *map.get(&key).unwrap(),
-            RedisElement::String("value".to_string())
+            RedisElement::String("value".into())
+        );
+    }
+
+    #[test]
+    fn test_deserialize_wrong_checksum() {
+        let mut bytes = rdb_file(vec![0x00, 0x01, b'k', 0x01, b'v']);
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0x01;
+
+        assert!(TtlHashMap::deserialize(bytes).is_err());
+    }
+
+    #[test]
+    fn test_deserialize_zero_checksum_is_not_verified() {
+        let mut bytes = rdb_file(vec![0x00, 0x01, b'k', 0x01, b'v']);
+        let len = bytes.len();
+        bytes[len - 8..].copy_from_slice(&[0; 8]);
+
+        let mut map = TtlHashMap::deserialize(bytes).unwrap();
+
+        assert_eq!(
+            *map.get(&b"k".to_vec()).unwrap(),
+            RedisElement::String("v".into())
+        );
+    }
+
+    #[test]
+    fn test_deserialize_skips_expired_keys_and_other_databases() {
+        let mut body = vec![0xfc];
+        body.extend_from_slice(&1000u64.to_le_bytes());
+        body.extend_from_slice(&[0x00, 0x03, b'o', b'l', b'd', 0x01, b'v']);
+        body.extend_from_slice(&[0x00, 0x03, b'n', b'e', b'w', 0x01, b'v']);
+        body.extend_from_slice(&[
+            0xfe, 0x01, 0x00, 0x05, b'o', b't', b'h', b'e', b'r', 0x01, b'v',
+        ]);
+
+        let mut map = TtlHashMap::deserialize(rdb_file(body)).unwrap();
+
+        assert_eq!(map.len(), 1);
+        assert!(map.contains_key(&b"new".to_vec()));
+    }
+
+    #[test]
+    fn test_scan_skips_removed_and_expired_keys() {
+        let mut map: TtlHashMap<Vec<u8>, RedisElement> = TtlHashMap::new();
+        for key in ["a", "b", "c", "d"] {
+            map.insert(key.into(), RedisElement::String(b"value".to_vec()));
+        }
+        map.remove(&b"b".to_vec());
+        map.set_ttl_absolute(b"c".to_vec(), SystemTime::now());
+
+        let (cursor, mut keys) = map.scan(0, 10);
+        keys.sort();
+
+        assert_eq!(cursor, 0);
+        assert_eq!(keys, vec![b"a".to_vec(), b"d".to_vec()]);
+    }
+
+    #[test]
+    fn test_serialize_and_deserialize_databases() {
+        let mut db0: TtlHashMap<Vec<u8>, RedisElement> = TtlHashMap::new();
+        let empty: TtlHashMap<Vec<u8>, RedisElement> = TtlHashMap::new();
+        let mut db2: TtlHashMap<Vec<u8>, RedisElement> = TtlHashMap::new();
+        db0.insert(b"zero".to_vec(), RedisElement::String("0".into()));
+        db2.insert(b"two".to_vec(), RedisElement::String("2".into()));
+
+        let bytes = TtlHashMap::serialize_databases(&[&db0, &empty, &db2]);
+        let mut dbs = TtlHashMap::deserialize_databases(bytes.clone(), 3).unwrap();
+
+        assert!(!bytes[..bytes.len() - 8]
+            .windows(2)
+            .any(|window| window == [0xfe, 0x01]));
+        assert_eq!(dbs[0].len(), 1);
+        assert_eq!(dbs[1].len(), 0);
+        assert_eq!(
+            *dbs[2].get(&b"two".to_vec()).unwrap(),
+            RedisElement::String("2".into())
+        );
+        assert_eq!(TtlHashMap::deserialize(bytes).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_serialize_and_deserialize_compressed_string() {
+        let mut map: TtlHashMap<Vec<u8>, RedisElement> = TtlHashMap::new();
+        let value = RedisElement::String("abcdefgh".repeat(100).into_bytes());
+        map.insert(b"key".to_vec(), value.clone());
+
+        let bytes = map.serialize();
+        let mut new_map = TtlHashMap::deserialize(bytes.clone()).unwrap();
+
+        assert!(bytes.len() < 200);
+        assert_eq!(*new_map.get(&b"key".to_vec()).unwrap(), value);
+    }
+
+    #[test]
+    fn test_deserialize_redis_dump() {
+        // Archivo armado a mano siguiendo el formato RDB 11 de Redis 7.2, con las codificaciones
+        // compactas que usa Redis: un string entero, un hash (listpack), un set (intset), una
+        // lista (quicklist 2) y un sorted set (listpack). No fue generado por redis-server.
+        let mut bytes = b"REDIS0011".to_vec();
+        bytes.extend_from_slice(&[0xfa, 0x09]);
+        bytes.extend_from_slice(b"redis-ver");
+        bytes.extend_from_slice(&[0x05]);
+        bytes.extend_from_slice(b"7.2.4");
+        bytes.extend_from_slice(&[0xfa, 0x0a]);
+        bytes.extend_from_slice(b"redis-bits");
+        bytes.extend_from_slice(&[0xc0, 0x40]);
+        bytes.extend_from_slice(&[0xfe, 0x00, 0xfb, 0x05, 0x00]);
+        // counter -> 1000
+        bytes.extend_from_slice(&[0x00, 0x07]);
+        bytes.extend_from_slice(b"counter");
+        bytes.extend_from_slice(&[0xc1, 0xe8, 0x03]);
+        // hash -> {f: v}
+        bytes.extend_from_slice(&[0x10, 0x04]);
+        bytes.extend_from_slice(b"hash");
+        bytes.extend_from_slice(&[
+            0x0d, 0x0d, 0x00, 0x00, 0x00, 0x02, 0x00, 0x81, b'f', 0x02, 0x81, b'v', 0x02, 0xff,
+        ]);
+        // set -> {1, 2}
+        bytes.extend_from_slice(&[0x0b, 0x03]);
+        bytes.extend_from_slice(b"set");
+        bytes.extend_from_slice(&[
+            0x0c, 0x02, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x01, 0x00, 0x02, 0x00,
+        ]);
+        // list -> [a, 7]
+        bytes.extend_from_slice(&[0x12, 0x04]);
+        bytes.extend_from_slice(b"list");
+        bytes.extend_from_slice(&[
+            0x01, 0x02, 0x0c, 0x0c, 0x00, 0x00, 0x00, 0x02, 0x00, 0x81, b'a', 0x02, 0x07, 0x01,
+            0xff,
+        ]);
+        // zset -> {m: 2.5}
+        bytes.extend_from_slice(&[0x11, 0x04]);
+        bytes.extend_from_slice(b"zset");
+        bytes.extend_from_slice(&[
+            0x0f, 0x0f, 0x00, 0x00, 0x00, 0x02, 0x00, 0x81, b'm', 0x02, 0x83, b'2', b'.', b'5',
+            0x04, 0xff,
+        ]);
+        bytes.push(0xff);
+        let checksum = crc64(0, &bytes);
+        bytes.extend_from_slice(&checksum.to_le_bytes());
+
+        let mut map = TtlHashMap::deserialize(bytes).unwrap();
+
+        assert_eq!(
+            *map.get(&b"counter".to_vec()).unwrap(),
+            RedisElement::String("1000".into())
+        );
+        let mut hash = HashMap::new();
+        hash.insert(b"f".to_vec(), b"v".to_vec());
+        assert_eq!(
+            *map.get(&b"hash".to_vec()).unwrap(),
+            RedisElement::Hash(hash.into())
+        );
+        assert_eq!(
+            *map.get(&b"set".to_vec()).unwrap(),
+            RedisElement::Set(vec![b"1".to_vec(), b"2".to_vec()].into_iter().collect())
+        );
+        assert_eq!(
+            *map.get(&b"list".to_vec()).unwrap(),
+            RedisElement::List(vec![b"a".to_vec(), b"7".to_vec()].into())
+        );
+        let mut sorted_set = SortedSet::new();
+        sorted_set.insert(b"m".to_vec(), 2.5);
+        assert_eq!(
+            *map.get(&b"zset".to_vec()).unwrap(),
+            RedisElement::SortedSet(sorted_set)
         );
     }
 
     #[test]
     fn test_serialize_and_deserialize_key_value_string() {
-        let mut map: TtlHashMap<String, RedisElement> = TtlHashMap::new();
-        let key = "key".to_string();
-        let value = RedisElement::String("value".to_string());
+        let mut map: TtlHashMap<Vec<u8>, RedisElement> = TtlHashMap::new();
+        let key = b"key".to_vec();
+        let value = RedisElement::String("value".into());
         map.insert(key.clone(), value.clone());
 
         let bytes = map.serialize();
@@ -573,9 +1685,41 @@ mod test {
 
     #[test]
     fn test_serialize_and_deserialize_key_value_list() {
-        let mut map: TtlHashMap<String, RedisElement> = TtlHashMap::new();
-        let key = "key".to_string();
-        let value = RedisElement::List(vec!["1".to_string(), "2".to_string()]);
+        let mut map: TtlHashMap<Vec<u8>, RedisElement> = TtlHashMap::new();
+        let key = b"key".to_vec();
+        let value = RedisElement::List(vec!["1".into(), "2".into()].into());
+        map.insert(key.clone(), value.clone());
+
+        let bytes = map.serialize();
+        let mut new_map = TtlHashMap::deserialize(bytes).unwrap();
+
+        assert_eq!(*new_map.get(&key).unwrap(), value);
+    }
+
+    #[test]
+    fn test_serialize_and_deserialize_key_value_hash() {
+        let mut map: TtlHashMap<Vec<u8>, RedisElement> = TtlHashMap::new();
+        let key = b"key".to_vec();
+        let mut hash = HashMap::new();
+        hash.insert("field1".into(), "1".into());
+        hash.insert("field2".into(), "2".into());
+        let value = RedisElement::Hash(hash.into());
+        map.insert(key.clone(), value.clone());
+
+        let bytes = map.serialize();
+        let mut new_map = TtlHashMap::deserialize(bytes).unwrap();
+
+        assert_eq!(*new_map.get(&key).unwrap(), value);
+    }
+
+    #[test]
+    fn test_serialize_and_deserialize_key_value_sorted_set() {
+        let mut map: TtlHashMap<Vec<u8>, RedisElement> = TtlHashMap::new();
+        let key = b"key".to_vec();
+        let mut sorted_set = SortedSet::new();
+        sorted_set.insert("member1".into(), 1.5);
+        sorted_set.insert("member2".into(), f64::NEG_INFINITY);
+        let value = RedisElement::SortedSet(sorted_set);
         map.insert(key.clone(), value.clone());
 
         let bytes = map.serialize();
@@ -586,11 +1730,11 @@ mod test {
 
     #[test]
     fn test_serialize_and_deserialize_with_ttl() {
-        let mut map: TtlHashMap<String, RedisElement> = TtlHashMap::new();
-        let key = "key".to_string();
+        let mut map: TtlHashMap<Vec<u8>, RedisElement> = TtlHashMap::new();
+        let key = b"key".to_vec();
         let ttl = SystemTime::now() + Duration::from_secs(2);
 
-        map.insert(key.clone(), RedisElement::String("value".to_string()));
+        map.insert(key.clone(), RedisElement::String("value".into()));
         map.set_ttl_absolute(key.clone(), ttl);
 
         let bytes = map.serialize();
@@ -602,4 +1746,48 @@ mod test {
             ttl.duration_since(SystemTime::now()).unwrap().as_secs()
         );
     }
+
+    #[test]
+    fn test_serialize_and_deserialize_invalid_utf8() {
+        let mut map: TtlHashMap<Vec<u8>, RedisElement> = TtlHashMap::new();
+        let key = vec![0xff, 0xfe, b'k'];
+        let string = RedisElement::String(vec![0x00, 0xc3, 0x28, 0xff]);
+        let list = RedisElement::List(vec![vec![0x80], vec![0xe2, 0x82]].into());
+        let set = RedisElement::Set(vec![vec![0xfa], vec![0xfb, 0x00]].into_iter().collect());
+        map.insert(key.clone(), string.clone());
+        map.insert(b"list".to_vec(), list.clone());
+        map.insert(b"set".to_vec(), set.clone());
+
+        let bytes = map.serialize();
+        let mut new_map = TtlHashMap::deserialize(bytes).unwrap();
+
+        assert_eq!(*new_map.get(&key).unwrap(), string);
+        assert_eq!(*new_map.get(&b"list".to_vec()).unwrap(), list);
+        assert_eq!(*new_map.get(&b"set".to_vec()).unwrap(), set);
+    }
+
+    #[test]
+    fn test_dump_and_restore() {
+        let mut hash = HashMap::new();
+        hash.insert(b"field".to_vec(), b"value".to_vec());
+        let values = vec![
+            RedisElement::String(b"value".to_vec()),
+            RedisElement::List(vec![b"a".to_vec(), b"b".to_vec()].into()),
+            RedisElement::Hash(hash.into()),
+        ];
+
+        for value in values {
+            let payload = TtlHashMap::dump(&value);
+            assert_eq!(TtlHashMap::restore(payload).unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn test_restore_wrong_checksum() {
+        let mut payload = TtlHashMap::dump(&RedisElement::String(b"value".to_vec()));
+        payload[1] ^= 0xff;
+
+        assert!(TtlHashMap::restore(payload).is_err());
+        assert!(TtlHashMap::restore(b"short".to_vec()).is_err());
+    }
 }
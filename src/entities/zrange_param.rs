@@ -0,0 +1,24 @@
+use crate::entities::sorted_set::{LexBound, ScoreBound};
+
+#[derive(Debug)]
+/// ZRangeParam: Enum usado para representar el tipo de rango pedido en el Command::Zrange.
+pub enum ZRangeParam {
+    /// Rango por posición, con índices inclusivos que pueden ser negativos.
+    Rank { start: i64, stop: i64 },
+    /// Rango por puntaje.
+    Score { min: ScoreBound, max: ScoreBound },
+    /// Rango lexicográfico.
+    Lex { min: LexBound, max: LexBound },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// AggregateParam: Enum usado para representar cómo se combinan los puntajes en los comandos
+/// ZUNIONSTORE y ZINTERSTORE.
+pub enum AggregateParam {
+    /// Suma los puntajes.
+    Sum,
+    /// Se queda con el menor puntaje.
+    Min,
+    /// Se queda con el mayor puntaje.
+    Max,
+}
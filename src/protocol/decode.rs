@@ -32,29 +32,31 @@ pub fn decode(bytes: &[u8], start: usize) -> std::result::Result<(TypeData, usiz
         }
         ':' => {
             if let Ok((integer, final_index)) = parse(bytes, start + 1) {
-                return Ok((
-                    TypeData::Integer(integer.parse::<i64>().unwrap()),
-                    final_index,
-                ));
+                if let Ok(integer) = integer.parse::<i64>() {
+                    return Ok((TypeData::Integer(integer), final_index));
+                }
             }
             Err("Error parseando el comando enviado".to_string())
         }
 
         '$' => {
             if let Ok((bulk_len, final_index)) = parse(bytes, start + 1) {
-                let length = bulk_len.parse::<usize>().unwrap();
-                if !size_ok(bytes, final_index) {
-                    return Err("Error parseando el comando enviado".to_string());
+                let length = bulk_len
+                    .parse::<usize>()
+                    .map_err(|_| "Error parseando el comando enviado".to_string())?;
+                if !size_ok(bytes, final_index + length + CRLF - 1) {
+                    return Err("Error comando incompleto".to_string());
                 }
-                let bulk =
-                    String::from_utf8(bytes[final_index..length + final_index].to_vec()).unwrap();
+                let bulk = bytes[final_index..length + final_index].to_vec();
                 return Ok((TypeData::BulkString(bulk), length + final_index + CRLF));
             }
             Err("Error parseando el comando enviado".to_string())
         }
         '*' => {
             if let Ok((array_len, mut final_index)) = parse(bytes, start + 1) {
-                let length = array_len.parse::<usize>().unwrap();
+                let length = array_len
+                    .parse::<usize>()
+                    .map_err(|_| "Error parseando el comando enviado".to_string())?;
                 let mut array: Vec<TypeData> = Vec::new();
 
                 for _ in 0..length {
@@ -72,11 +74,9 @@ pub fn decode(bytes: &[u8], start: usize) -> std::result::Result<(TypeData, usiz
         }
 
         _ => {
-            let vector = Vec::from(bytes);
-            if let Ok(string) = String::from_utf8(vector) {
-                let length = string.len();
-                let bulk = String::from_utf8(bytes[0..length - CRLF].to_vec()).unwrap();
-                return Ok((TypeData::BulkString(bulk), length));
+            if let Some(index) = find_crlf(bytes, start) {
+                let bulk = bytes[start..index].to_vec();
+                return Ok((TypeData::BulkString(bulk), index + CRLF));
             }
             Err("Error parseando el comando enviado".to_string())
         }
@@ -93,20 +93,31 @@ pub fn decode(bytes: &[u8], start: usize) -> std::result::Result<(TypeData, usiz
 /// * `bytes` - Comando representado en bytes
 /// * `pos` - Posición desde donde se debe comenzar a decodificar los bytes
 pub fn parse(bytes: &[u8], pos: usize) -> std::result::Result<(String, usize), String> {
-    let vector = Vec::from(&bytes[pos..]);
-    if let Ok(string) = String::from_utf8(vector) {
-        if let Some(index) = string.find("\r\n") {
-            if let Ok(string) = String::from_utf8((bytes[pos..index + pos]).to_vec()) {
-                return Ok((string, index + pos + CRLF));
-            }
+    if let Some(index) = find_crlf(bytes, pos) {
+        if let Ok(string) = String::from_utf8((bytes[pos..index]).to_vec()) {
+            return Ok((string, index + CRLF));
         }
     }
     Err("Error parseando el comando recibido".to_string())
 }
 
+/// Busca la posición del primer `\r\n` a partir de la posición indicada.
+///
+/// # Arguments
+///
+/// * `bytes` - Comando representado en bytes
+/// * `pos` - Posición desde donde se debe comenzar a buscar
+fn find_crlf(bytes: &[u8], pos: usize) -> Option<usize> {
+    bytes
+        .get(pos..)?
+        .windows(CRLF)
+        .position(|window| window == b"\r\n")
+        .map(|index| index + pos)
+}
+
 /// Pregunta si la longitud de los bytes a codificar está bien.
 ///
-/// En caso de que `pos` sea mayor o igual a la longitud de `bytes` (convertidos en `String`), devuelve `false`.
+/// En caso de que `pos` sea mayor o igual a la longitud de `bytes`, devuelve `false`.
 ///
 /// De otro modo, retorna `true`.
 /// # Arguments
@@ -114,13 +125,7 @@ pub fn parse(bytes: &[u8], pos: usize) -> std::result::Result<(String, usize), S
 /// * `bytes` - Comando representado en bytes
 /// * `pos` - Posición desde donde se debe comenzar a decodificar los bytes
 pub fn size_ok(bytes: &[u8], pos: usize) -> bool {
-    let vector = Vec::from(bytes);
-    if let Ok(string) = String::from_utf8(vector) {
-        if string.len() <= pos {
-            return false;
-        }
-    }
-    true
+    pos < bytes.len()
 }
 
 #[cfg(test)]
@@ -158,7 +163,7 @@ mod test {
         let bytes = "$6\r\nfoobar\r\n";
         assert_eq!(
             decode(bytes.as_bytes(), 0).ok().unwrap().0,
-            TypeData::BulkString("foobar".to_string())
+            TypeData::BulkString(b"foobar".to_vec())
         )
     }
 
@@ -167,7 +172,7 @@ mod test {
         let bytes = "$0\r\n\r\n";
         assert_eq!(
             decode(bytes.as_bytes(), 0).ok().unwrap().0,
-            TypeData::BulkString("".to_string())
+            TypeData::BulkString(vec![])
         )
     }
 
@@ -176,8 +181,8 @@ mod test {
         let bytes = "*2\r\n$3\r\nfoo\r\n$3\r\nbar\r\n";
 
         let array = vec![
-            TypeData::BulkString("foo".to_string()),
-            TypeData::BulkString("bar".to_string()),
+            TypeData::BulkString(b"foo".to_vec()),
+            TypeData::BulkString(b"bar".to_vec()),
         ];
         assert_eq!(
             decode(bytes.as_bytes(), 0).ok().unwrap().0,
@@ -189,12 +194,32 @@ mod test {
     fn test_decode_array_bulkstring() {
         let bytes = "*3\r\n$3\r\nset\r\n$5\r\nmykey\r\n$1\r\n1\r\n";
         let mut vector = Vec::new();
-        vector.push(TypeData::BulkString("set".to_string()));
-        vector.push(TypeData::BulkString("mykey".to_string()));
-        vector.push(TypeData::BulkString("1".to_string()));
+        vector.push(TypeData::BulkString(b"set".to_vec()));
+        vector.push(TypeData::BulkString(b"mykey".to_vec()));
+        vector.push(TypeData::BulkString(b"1".to_vec()));
         assert_eq!(
             decode(bytes.as_bytes(), 0).ok().unwrap().0,
             TypeData::Array(vector)
         )
     }
+
+    #[test]
+    fn test_decode_bulk_string_with_invalid_utf8() {
+        let bytes = [
+            b"$4\r\n".to_vec(),
+            vec![0xff, 0xfe, b'\r', b'\n'],
+            b"\r\n".to_vec(),
+        ]
+        .concat();
+        assert_eq!(
+            decode(&bytes, 0).ok().unwrap().0,
+            TypeData::BulkString(vec![0xff, 0xfe, b'\r', b'\n'])
+        )
+    }
+
+    #[test]
+    fn test_decode_incomplete_bulk_string() {
+        let bytes = "$6\r\nfoo";
+        assert!(decode(bytes.as_bytes(), 0).is_err())
+    }
 }
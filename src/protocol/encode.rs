@@ -41,7 +41,7 @@ pub fn encode(data: TypeData) -> Vec<u8> {
                 "$".to_string().as_bytes(),
                 bulk.len().to_string().as_bytes(),
                 "\r\n".as_bytes(),
-                bulk.as_bytes(),
+                &bulk,
                 "\r\n".as_bytes(),
             ]
             .concat();
@@ -55,8 +55,7 @@ pub fn encode(data: TypeData) -> Vec<u8> {
             ]
             .concat();
             for element in array {
-                let result = encode(element.clone());
-                bytes = [bytes, result].concat();
+                bytes.extend(encode(element));
             }
             bytes
         }
@@ -69,6 +68,15 @@ pub fn encode(data: TypeData) -> Vec<u8> {
             .concat();
             bytes
         }
+        TypeData::NullArray => {
+            let bytes = [
+                "*".to_string().as_bytes(),
+                "-1".to_string().as_bytes(),
+                "\r\n".as_bytes(),
+            ]
+            .concat();
+            bytes
+        }
     }
 }
 #[cfg(test)]
@@ -97,16 +105,28 @@ mod test {
     #[test]
     fn test_encode_bulk() {
         let bytes = "$6\r\nfoobar\r\n".as_bytes();
-        assert_eq!(encode(TypeData::BulkString("foobar".to_string())), bytes)
+        assert_eq!(encode(TypeData::BulkString(b"foobar".to_vec())), bytes)
     }
 
     #[test]
     fn test_encode_array() {
         let bytes = "*2\r\n$3\r\nfoo\r\n$3\r\nbar\r\n".as_bytes();
         let array = vec![
-            TypeData::BulkString("foo".to_string()),
-            TypeData::BulkString("bar".to_string()),
+            TypeData::BulkString(b"foo".to_vec()),
+            TypeData::BulkString(b"bar".to_vec()),
         ];
         assert_eq!(encode(TypeData::Array(array)), bytes)
     }
+
+    #[test]
+    fn test_encode_nil() {
+        let bytes = "$-1\r\n".as_bytes();
+        assert_eq!(encode(TypeData::Nil), bytes)
+    }
+
+    #[test]
+    fn test_encode_null_array() {
+        let bytes = "*-1\r\n".as_bytes();
+        assert_eq!(encode(TypeData::NullArray), bytes)
+    }
 }
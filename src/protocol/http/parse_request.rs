@@ -1,5 +1,3 @@
-use std::collections::HashMap;
-
 /// Representa el request que envía el browser, utilizando el protocolo HTTP.
 #[derive(Debug)]
 pub struct Request {
@@ -8,10 +6,6 @@ pub struct Request {
     pub method: String,
     /// Representa la URL desde donde se está enviando el request.
     pub url: String,
-    /// Representa el número de versión HTTP.
-    pub http_version: String,
-    /// Representa la información adicional que se envía con el request HTTP.
-    pub headers: HashMap<String, String>,
     /// Representa los datos del request HTTP enviado, puede estar vacío.
     pub body: String,
 }
@@ -49,7 +43,7 @@ pub fn parse_command_rest(data: &[u8]) -> HttpMethod {
     match request.method.as_str() {
         "POST" => {
             let body = request.body;
-            return if let Some(index_command) = body.find("command") {
+            if let Some(index_command) = body.find("command") {
                 let command_len = 7;
                 let equal = 1;
                 let slice = &body[index_command + command_len + equal..];
@@ -57,7 +51,7 @@ pub fn parse_command_rest(data: &[u8]) -> HttpMethod {
                 HttpMethod::Post(command)
             } else {
                 HttpMethod::Post(vec![])
-            };
+            }
         }
         "GET" => {
             let url = request.url;
@@ -78,11 +72,8 @@ fn parse_request(data: &[u8]) -> Request {
     let mut state = RequestParseState::Method;
     let mut method = 0;
     let mut url = 0;
-    let mut http_version = 0;
     let mut header = 0;
     let mut body = 0;
-    let mut headers_key: Vec<usize> = vec![];
-    let mut headers_value: Vec<usize> = vec![];
     for (i, current) in data.iter().enumerate() {
         match state {
             RequestParseState::Method => {
@@ -102,8 +93,6 @@ fn parse_request(data: &[u8]) -> Request {
             RequestParseState::HttpVersion => {
                 if current == &b'\n' {
                     state = RequestParseState::Headers { is_end: false };
-                } else if current != &b'\r' {
-                    http_version = i;
                 }
             }
             RequestParseState::Headers { is_end } => {
@@ -115,13 +104,11 @@ fn parse_request(data: &[u8]) -> Request {
                     if String::from_utf8(data[header + 3..header + 4].to_vec()).unwrap() == "\r" {
                         state = RequestParseState::Headers { is_end: true };
                     } else {
-                        headers_value.push(header);
                         header = 0;
                     }
                 } else if current == &b':'
                     && String::from_utf8(data[i + 1..i + 2].to_vec()).unwrap() == " "
                 {
-                    headers_key.push(header);
                     header = 0;
                 } else {
                     header = i;
@@ -136,25 +123,11 @@ fn parse_request(data: &[u8]) -> Request {
 
     let method_slice = convert_to_string(&data[..=method]).unwrap();
     let url_slice = convert_to_string(&data[method + 2..=url]).unwrap();
-    let http_version_slice = convert_to_string(&data[url + 2..=http_version]).unwrap();
-
-    let mut headers = HashMap::new();
-    let mut last = http_version + 3;
-
-    for (key, value) in headers_key.iter().zip(headers_value) {
-        let key_slice = convert_to_string(&data[last..*key + 1]).unwrap();
-        let value_slice = convert_to_string(&data[key + 3..value + 1]).unwrap();
-        last = value + 3;
-        headers.insert(key_slice, value_slice);
-    }
-
     let body_slice = convert_to_string(&data[body + 2..]).unwrap();
 
     Request {
         method: method_slice,
         url: url_slice,
-        http_version: http_version_slice,
-        headers,
         body: body_slice.trim_matches(char::from(0)).to_string(),
     }
 }
@@ -15,7 +15,6 @@ impl<'a> LinesIterator<'a> {
     ///
     /// * `input` - `BufReader<TcpStream>`.
     pub fn new(input: &'a mut BufReader<TcpStream>) -> Self {
-        let input = input;
         Self { input }
     }
 }
@@ -29,14 +28,19 @@ impl Iterator for LinesIterator<'_> {
     /// En el caso de que no se pueda, se pide la siguiente línea.
     ///
     /// Si se logró decodificar correctamente, se retorna la línea decodificada como `Some(line)`.
+    /// Si el cliente cerró la conexión, se retorna `None`.
     fn next(&mut self) -> Option<<Self as Iterator>::Item> {
-        let mut buf = String::new();
-        while self.input.read_line(&mut buf).ok()? != 0 {
-            if let Ok(result) = decode(buf.as_bytes(), 0) {
+        let mut buf: Vec<u8> = Vec::new();
+        while self.input.read_until(b'\n', &mut buf).ok()? != 0 {
+            if let Ok(result) = decode(&buf, 0) {
                 let (data, _) = result;
                 return Some(data);
             }
         }
+        // El cliente cerró la conexión. Si quedó un comando incompleto se informa el error.
+        if buf.is_empty() {
+            return None;
+        }
         Some(TypeData::Error("Se ha producido un error".to_string()))
     }
 }
@@ -1,14 +1,14 @@
-use crate::entities::redis_element::RedisElement;
+use crate::entities::reply::Reply;
+use crate::entities::response::Response;
 use crate::protocol::encode::encode;
 use crate::protocol::type_data::TypeData;
-use std::iter::FromIterator;
 
-/// Parsea el comando recibido (`TypeData`) a un `Vec<String>`.
+/// Parsea el comando recibido (`TypeData`) a un `Vec<Vec<u8>>`, con los bytes de cada argumento.
 ///
 /// # Arguments
 ///
 /// * `data` - Comando, representado como `TypeData`.
-pub fn parse_command(data: TypeData) -> Vec<String> {
+pub fn parse_command(data: TypeData) -> Vec<Vec<u8>> {
     let empty_vector = Vec::new();
     if let Ok(vector) = parse_array(data) {
         return vector;
@@ -20,9 +20,9 @@ pub fn parse_command(data: TypeData) -> Vec<String> {
 ///
 /// # Arguments
 ///
-/// * `redis_element` - Respuesta de un comando, representado como `RedisElement`.
-pub fn parse_response_ok(redis_element: RedisElement) -> Vec<u8> {
-    encode(parse_response(redis_element))
+/// * `reply` - Respuesta de un comando, representada como `Reply`.
+pub fn parse_response_ok(reply: Reply) -> Vec<u8> {
+    encode(parse_response(reply))
 }
 
 /// Parsea la respuesta de un comando, en caso de error, a bytes (`Vec<u8>`).
@@ -34,49 +34,61 @@ pub fn parse_response_error(error: String) -> Vec<u8> {
     encode(TypeData::Error(error))
 }
 
-/// Parsea un `RedisElement` a un `TypeData`.
+/// Parsea las respuestas de los comandos ejecutados en una transacción a bytes (`Vec<u8>`).
 ///
 /// # Arguments
 ///
-/// * `redis_element` - Redis element.
-fn parse_response(redis_element: RedisElement) -> TypeData {
-    match redis_element {
-        RedisElement::String(string) => {
-            let number = string.parse::<i64>();
-            match number {
-                Ok(number) => TypeData::Integer(number),
-                Err(_) => TypeData::BulkString(string),
-            }
-        }
-        RedisElement::List(list) => parse_list_and_set(list),
-        RedisElement::Set(set) => parse_list_and_set(Vec::from_iter(set)),
-        RedisElement::Nil => TypeData::Nil,
-        RedisElement::SimpleString(string) => TypeData::String(string),
-    }
+/// * `responses` - Respuestas de cada comando, representadas como `Response`.
+pub fn parse_response_transaction(responses: Vec<Response>) -> Vec<u8> {
+    encode(parse_transaction(responses))
 }
 
-/// Parsea un `Vec<String>` a un `TypeData::Array`.
+/// Parsea las respuestas de una transacción a un `TypeData::Array`.
 ///
 /// # Arguments
 ///
-/// * `vector_re` - Vector a parsear.
-fn parse_list_and_set(vector_re: Vec<String>) -> TypeData {
+/// * `responses` - Respuestas de cada comando.
+fn parse_transaction(responses: Vec<Response>) -> TypeData {
     let mut vector = Vec::new();
-    for element in vector_re {
-        let type_data = parse_response(RedisElement::String(element));
+    for response in responses {
+        let type_data = match response {
+            Response::Normal(reply) => parse_response(reply),
+            Response::Error(error) => TypeData::Error(error),
+            Response::Transaction(responses) => parse_transaction(responses),
+            Response::Stream(_) | Response::Pushed | Response::Blocked(..) => {
+                TypeData::Error("ERR Command not allowed inside a transaction".to_string())
+            }
+        };
         vector.push(type_data);
     }
     TypeData::Array(vector)
 }
 
-/// Intenta parsear un `TypeData` a un `Vec<String>`.
+/// Parsea una `Reply` a un `TypeData`.
+///
+/// # Arguments
+///
+/// * `reply` - Respuesta de un comando.
+fn parse_response(reply: Reply) -> TypeData {
+    match reply {
+        Reply::SimpleString(string) => TypeData::String(string),
+        Reply::BulkString(bytes) => TypeData::BulkString(bytes),
+        Reply::Integer(integer) => TypeData::Integer(integer),
+        Reply::Array(array) => TypeData::Array(array.into_iter().map(parse_response).collect()),
+        Reply::NullBulk => TypeData::Nil,
+        Reply::NullArray => TypeData::NullArray,
+        Reply::Error(error) => TypeData::Error(error),
+    }
+}
+
+/// Intenta parsear un `TypeData` a un `Vec<Vec<u8>>`.
 ///
-/// En caso de que falle, devuelve un error de tipo `String`. Sino, devuelve un `Vec<String>`.
+/// En caso de que falle, devuelve un error de tipo `String`. Sino, devuelve un `Vec<Vec<u8>>`.
 ///
 /// # Arguments
 ///
 /// * `type_data` - Type data.
-fn parse_array(type_data: TypeData) -> Result<Vec<String>, String> {
+fn parse_array(type_data: TypeData) -> Result<Vec<Vec<u8>>, String> {
     match type_data {
         TypeData::Array(vec) => {
             let mut vector = Vec::new();
@@ -91,17 +103,17 @@ fn parse_array(type_data: TypeData) -> Result<Vec<String>, String> {
     }
 }
 
-/// Intenta parsear un `TypeData` a un `String`.
+/// Intenta parsear un `TypeData` a los bytes que representa.
 ///
 /// En caso de el `TypeData` no exista, devuelve un error de tipo `String`.
 ///
 /// # Arguments
 ///
 /// * `type_data` - Type data.
-fn parse_type_data(type_data: TypeData) -> Result<String, String> {
+fn parse_type_data(type_data: TypeData) -> Result<Vec<u8>, String> {
     match type_data {
-        TypeData::String(string) => Ok(string),
-        TypeData::Integer(integer) => Ok(integer.to_string()),
+        TypeData::String(string) => Ok(string.into_bytes()),
+        TypeData::Integer(integer) => Ok(integer.to_string().into_bytes()),
         TypeData::BulkString(bulkstring) => Ok(bulkstring),
         _ => Err("Error tipo de dato".to_string()),
     }
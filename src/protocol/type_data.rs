@@ -8,9 +8,11 @@ pub enum TypeData {
     /// Representa los enteros (aquellos que tiene como primer byte `:`)
     Integer(i64),
     /// Representa Bulk Strings (aquellos que tiene como primer byte `$`)
-    BulkString(String),
+    BulkString(Vec<u8>),
     /// Representa las matrices (aquellas que tiene como primer byte `*`)
     Array(Vec<TypeData>),
-    /// Representa el nulo (`*-1\r\n`)
+    /// Representa el bulk string nulo (`$-1\r\n`)
     Nil,
+    /// Representa el array nulo (`*-1\r\n`)
+    NullArray,
 }
@@ -0,0 +1,472 @@
+use crate::entities::append_fsync::AppendFsync;
+use crate::entities::command::unix_millis;
+use crate::entities::redis_element::RedisElement;
+use crate::entities::ttl_hash_map::TtlHashMap;
+use crate::protocol::decode::decode;
+use crate::protocol::encode::encode;
+use crate::protocol::parse_data::parse_command;
+use crate::protocol::type_data::TypeData;
+use crate::service::float::format_score;
+use std::fs;
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::io::{Error, Write};
+use std::sync::{Arc, Mutex, Weak};
+use std::thread;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// Cantidad máxima de elementos por comando al reescribir una colección.
+const REWRITE_ITEMS_PER_COMMAND: usize = 64;
+/// Tiempo entre una sincronización y la siguiente con la política everysec.
+const FSYNC_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug)]
+/// Archivo AOF abierto junto a su política de sincronización. Se comparte con el hilo que lo
+/// sincroniza cada segundo.
+struct AofFile {
+    file: File,
+    fsync: AppendFsync,
+    /// Comandos agregados mientras se reescribe el archivo en segundo plano, que se copian al
+    /// final del archivo nuevo al terminar. Es None si no hay una reescritura en curso.
+    rewrite_buffer: Option<Vec<u8>>,
+}
+
+#[derive(Debug)]
+/// Entidad que representa el archivo de sólo agregado (AOF): un registro, en formato RESP, de los
+/// comandos de escritura ejecutados en la base de datos. Reproduciéndolo se reconstruye la base.
+pub struct Aof {
+    /// Ruta del archivo AOF.
+    path: String,
+    /// Archivo abierto, compartido con el hilo de sincronización.
+    inner: Arc<Mutex<AofFile>>,
+    /// Base de datos seleccionada con el último SELECT registrado, si lo hay.
+    selected_db: Option<usize>,
+}
+
+impl Aof {
+    /// Abre (o crea) el archivo AOF para agregar comandos al final, y lanza el hilo que lo
+    /// sincroniza con el disco cada segundo mientras la política sea everysec. El hilo termina
+    /// cuando se cierra el AOF.
+    pub fn open(path: String, fsync: AppendFsync) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let inner = Arc::new(Mutex::new(AofFile {
+            file,
+            fsync,
+            rewrite_buffer: None,
+        }));
+        Aof::fsync_thread(Arc::downgrade(&inner));
+
+        Ok(Self {
+            path,
+            inner,
+            selected_db: None,
+        })
+    }
+
+    fn fsync_thread(inner: Weak<Mutex<AofFile>>) {
+        let _: JoinHandle<()> = thread::spawn(move || loop {
+            thread::sleep(FSYNC_INTERVAL);
+            let inner = match inner.upgrade() {
+                Some(inner) => inner,
+                None => break,
+            };
+            if let Ok(aof_file) = inner.lock() {
+                if aof_file.fsync == AppendFsync::Everysec {
+                    let _ = aof_file.file.sync_data();
+                }
+            };
+        });
+    }
+
+    fn lock(&self) -> io::Result<std::sync::MutexGuard<'_, AofFile>> {
+        self.inner
+            .lock()
+            .map_err(|_| Error::other("AOF lock error"))
+    }
+
+    /// Cambia la política de sincronización del archivo.
+    pub fn set_fsync(&mut self, fsync: AppendFsync) -> io::Result<()> {
+        self.lock()?.fsync = fsync;
+        Ok(())
+    }
+
+    /// Agrega un comando de la base de datos indicada al final del archivo, codificado como un
+    /// array RESP de bulk strings. Si la base es distinta a la del comando anterior, se agrega
+    /// antes un SELECT.
+    pub fn append(&mut self, db: usize, args: Vec<Vec<u8>>) -> io::Result<()> {
+        let mut bytes = vec![];
+        if self.selected_db != Some(db) {
+            bytes = encode_command(select_command(db));
+        }
+        bytes.append(&mut encode_command(args));
+
+        self.write(&bytes)?;
+        self.selected_db = Some(db);
+        Ok(())
+    }
+
+    /// Escribe los bytes al final del archivo. Con la política always, sincroniza el archivo antes
+    /// de retornar.
+    fn write(&self, bytes: &[u8]) -> io::Result<()> {
+        let mut aof_file = self.lock()?;
+        aof_file.file.write_all(bytes)?;
+        if aof_file.fsync == AppendFsync::Always {
+            aof_file.file.sync_data()?;
+        }
+        if let Some(buffer) = aof_file.rewrite_buffer.as_mut() {
+            buffer.extend_from_slice(bytes);
+        }
+        Ok(())
+    }
+
+    /// Compacta el archivo AOF, reemplazándolo por los comandos mínimos que reconstruyen el
+    /// contenido actual de las bases de datos. Los próximos comandos se agregan al archivo nuevo.
+    pub fn rewrite(&mut self, dbs: &[&TtlHashMap<Vec<u8>, RedisElement>]) -> io::Result<()> {
+        self.selected_db = None;
+        let mut aof_file = self.lock()?;
+        Aof::write_snapshot(&self.path, dbs)?;
+        aof_file.file = OpenOptions::new().append(true).open(&self.path)?;
+        Ok(())
+    }
+
+    /// Compacta el archivo AOF en un hilo aparte, a partir de la copia de las bases de datos
+    /// recibida. Mientras tanto los comandos se siguen agregando al archivo actual y además se
+    /// guardan, para agregarlos al final del archivo nuevo antes de reemplazar al actual. Si el
+    /// AOF se cierra antes de terminar, se descarta la reescritura.
+    pub fn bgrewrite(
+        &mut self,
+        snapshot: Vec<TtlHashMap<Vec<u8>, RedisElement>>,
+    ) -> io::Result<JoinHandle<()>> {
+        self.start_rewrite()?;
+        let path = self.path.clone();
+        let inner = Arc::downgrade(&self.inner);
+        Ok(thread::spawn(move || {
+            let dbs: Vec<&TtlHashMap<Vec<u8>, RedisElement>> = snapshot.iter().collect();
+            let written = Aof::write_temp_snapshot(&path, &dbs);
+            if let Some(inner) = inner.upgrade() {
+                Aof::finish_rewrite(&inner, &path, written);
+            }
+            let _ = fs::remove_file(temp_snapshot_path(&path));
+        }))
+    }
+
+    /// Empieza a guardar los comandos que se agregan, para copiarlos al archivo reescrito. Como
+    /// el archivo nuevo no termina necesariamente en la base de datos del último comando, el
+    /// próximo comando se agrega con un SELECT.
+    fn start_rewrite(&mut self) -> io::Result<()> {
+        self.lock()?.rewrite_buffer = Some(vec![]);
+        self.selected_db = None;
+        Ok(())
+    }
+
+    /// Termina una reescritura en segundo plano: si el archivo temporal se escribió bien, le
+    /// agrega los comandos guardados durante la reescritura, reemplaza con él al archivo actual y
+    /// sigue agregando los comandos en el nuevo. Si algo falla, se sigue usando el archivo actual,
+    /// que ya tiene todos los comandos.
+    fn finish_rewrite(inner: &Mutex<AofFile>, path: &str, written: io::Result<()>) {
+        let mut aof_file = match inner.lock() {
+            Ok(aof_file) => aof_file,
+            Err(_) => return,
+        };
+        let buffer = aof_file.rewrite_buffer.take().unwrap_or_default();
+        let replaced = written.and_then(|_| {
+            let temp_path = temp_snapshot_path(path);
+            let mut file = OpenOptions::new().append(true).open(&temp_path)?;
+            file.write_all(&buffer)?;
+            file.sync_all()?;
+            fs::rename(temp_path, path)?;
+            OpenOptions::new().append(true).open(path)
+        });
+        if let Ok(file) = replaced {
+            aof_file.file = file;
+        }
+    }
+
+    /// Escribe en un hilo aparte, en la ruta indicada, un AOF con la copia de las bases de datos
+    /// recibida. Se usa para reescribir el AOF cuando no está habilitado.
+    pub fn bgwrite_snapshot(
+        path: String,
+        snapshot: Vec<TtlHashMap<Vec<u8>, RedisElement>>,
+    ) -> JoinHandle<()> {
+        thread::spawn(move || {
+            let dbs: Vec<&TtlHashMap<Vec<u8>, RedisElement>> = snapshot.iter().collect();
+            let _ = Aof::write_snapshot(&path, &dbs);
+        })
+    }
+
+    /// Escribe en la ruta indicada un AOF con el contenido actual de las bases de datos. Se
+    /// escribe primero en un archivo temporal y luego se renombra, para no dejar un archivo
+    /// incompleto.
+    pub fn write_snapshot(
+        path: &str,
+        dbs: &[&TtlHashMap<Vec<u8>, RedisElement>],
+    ) -> io::Result<()> {
+        Aof::write_temp_snapshot(path, dbs)?;
+        fs::rename(temp_snapshot_path(path), path)
+    }
+
+    /// Escribe en el archivo temporal de la ruta indicada un AOF con el contenido actual de las
+    /// bases de datos, con un SELECT antes de los comandos de cada base que no esté vacía (salvo
+    /// la 0, con la que empieza la lectura del archivo).
+    fn write_temp_snapshot(
+        path: &str,
+        dbs: &[&TtlHashMap<Vec<u8>, RedisElement>],
+    ) -> io::Result<()> {
+        let mut file = File::create(temp_snapshot_path(path))?;
+        for (index, db) in dbs.iter().enumerate() {
+            let commands = rewrite_commands(db);
+            if commands.is_empty() {
+                continue;
+            }
+            if index != 0 {
+                file.write_all(&encode_command(select_command(index)))?;
+            }
+            for args in commands {
+                file.write_all(&encode_command(args))?;
+            }
+        }
+        file.sync_all()
+    }
+
+    /// Lee los comandos guardados en el archivo AOF, en el orden en que fueron ejecutados.
+    ///
+    /// Si el archivo termina con un comando incompleto (por ejemplo, por una caída del servidor
+    /// mientras se escribía), se descarta y se trunca el archivo en el último comando completo.
+    pub fn read(path: &str) -> io::Result<Vec<Vec<Vec<u8>>>> {
+        let bytes = fs::read(path)?;
+        let mut commands = Vec::new();
+        let mut position = 0;
+
+        while position < bytes.len() {
+            match decode(&bytes, position) {
+                Ok((data, next)) => {
+                    commands.push(parse_command(data));
+                    position = next;
+                }
+                Err(_) => {
+                    OpenOptions::new()
+                        .write(true)
+                        .open(path)?
+                        .set_len(position as u64)?;
+                    break;
+                }
+            }
+        }
+        Ok(commands)
+    }
+}
+
+/// Devuelve la ruta del archivo temporal en el que se reescribe el AOF de la ruta indicada.
+fn temp_snapshot_path(path: &str) -> String {
+    format!("{}.rewrite.tmp", path)
+}
+
+/// Codifica los argumentos de un comando como un array RESP de bulk strings.
+fn encode_command(args: Vec<Vec<u8>>) -> Vec<u8> {
+    encode(TypeData::Array(
+        args.into_iter().map(TypeData::BulkString).collect(),
+    ))
+}
+
+/// Arma el comando SELECT de la base de datos indicada.
+fn select_command(db: usize) -> Vec<Vec<u8>> {
+    vec![b"select".to_vec(), db.to_string().into_bytes()]
+}
+
+/// Arma los comandos que reconstruyen el contenido de la base de datos: uno por cada clave (o
+/// varios, si la colección es grande) más un PEXPIREAT por cada clave con expiración.
+fn rewrite_commands(db: &TtlHashMap<Vec<u8>, RedisElement>) -> Vec<Vec<Vec<u8>>> {
+    let mut commands = Vec::new();
+
+    for (key, value, ttl) in db.iter() {
+        let (name, items): (&str, Vec<Vec<Vec<u8>>>) = match value {
+            RedisElement::String(string) => ("set", vec![vec![string.to_vec()]]),
+            RedisElement::List(list) => ("rpush", list.iter().map(|e| vec![e.to_vec()]).collect()),
+            RedisElement::Set(set) => ("sadd", set.iter().map(|e| vec![e.to_vec()]).collect()),
+            RedisElement::Hash(hash) => (
+                "hset",
+                hash.iter()
+                    .map(|(field, value)| vec![field.to_vec(), value.to_vec()])
+                    .collect(),
+            ),
+            RedisElement::SortedSet(sorted_set) => (
+                "zadd",
+                sorted_set
+                    .iter()
+                    .map(|(member, score)| vec![format_score(score).into_bytes(), member.to_vec()])
+                    .collect(),
+            ),
+            _ => continue,
+        };
+
+        for chunk in items.chunks(REWRITE_ITEMS_PER_COMMAND) {
+            let mut args = vec![name.as_bytes().to_vec(), key.to_vec()];
+            args.extend(chunk.iter().flatten().cloned());
+            commands.push(args);
+        }
+        if let Some(ttl) = ttl {
+            commands.push(vec![b"pexpireat".to_vec(), key.to_vec(), unix_millis(ttl)]);
+        }
+    }
+    commands
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::entities::append_fsync::AppendFsync;
+    use crate::entities::redis_element::RedisElement;
+    use crate::entities::sorted_set::SortedSet;
+    use crate::entities::ttl_hash_map::TtlHashMap;
+    use crate::service::aof::{rewrite_commands, Aof};
+    use std::collections::HashSet;
+    use std::fs;
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn test_append_and_read_commands() {
+        let path = "test_append_and_read_commands.aof".to_string();
+        let _ = fs::remove_file(&path);
+
+        let mut aof = Aof::open(path.clone(), AppendFsync::Always).unwrap();
+        aof.append(
+            0,
+            vec!["set".into(), "key".into(), vec![0xff, b'\r', b'\n']],
+        )
+        .unwrap();
+        aof.append(0, vec!["del".into(), "key".into()]).unwrap();
+        aof.append(2, vec!["del".into(), "key".into()]).unwrap();
+
+        let commands = Aof::read(&path).unwrap();
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(
+            commands,
+            vec![
+                vec![b"select".to_vec(), b"0".to_vec()],
+                vec![b"set".to_vec(), b"key".to_vec(), vec![0xff, b'\r', b'\n']],
+                vec![b"del".to_vec(), b"key".to_vec()],
+                vec![b"select".to_vec(), b"2".to_vec()],
+                vec![b"del".to_vec(), b"key".to_vec()],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_bgrewrite_keeps_commands_appended_during_rewrite() {
+        let path = "test_bgrewrite_keeps_commands_appended_during_rewrite.aof".to_string();
+        let _ = fs::remove_file(&path);
+        let mut db: TtlHashMap<Vec<u8>, RedisElement> = TtlHashMap::new();
+        db.insert("key".into(), RedisElement::String("3".into()));
+
+        let mut aof = Aof::open(path.clone(), AppendFsync::No).unwrap();
+        aof.append(0, vec!["incr".into(), "key".into()]).unwrap();
+        aof.start_rewrite().unwrap();
+        aof.append(0, vec!["del".into(), "other".into()]).unwrap();
+        let written = Aof::write_temp_snapshot(&path, &[&db]);
+        Aof::finish_rewrite(&aof.inner, &path, written);
+        aof.append(0, vec!["del".into(), "key".into()]).unwrap();
+
+        let commands = Aof::read(&path).unwrap();
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(
+            commands,
+            vec![
+                vec![b"set".to_vec(), b"key".to_vec(), b"3".to_vec()],
+                vec![b"select".to_vec(), b"0".to_vec()],
+                vec![b"del".to_vec(), b"other".to_vec()],
+                vec![b"del".to_vec(), b"key".to_vec()],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_bgrewrite_replaces_file() {
+        let path = "test_bgrewrite_replaces_file.aof".to_string();
+        let _ = fs::remove_file(&path);
+        let mut db: TtlHashMap<Vec<u8>, RedisElement> = TtlHashMap::new();
+        db.insert("key".into(), RedisElement::String("value".into()));
+
+        let mut aof = Aof::open(path.clone(), AppendFsync::No).unwrap();
+        aof.append(0, vec!["del".into(), "key".into()]).unwrap();
+        aof.bgrewrite(vec![db]).unwrap().join().unwrap();
+        let commands = Aof::read(&path).unwrap();
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(
+            commands,
+            vec![vec![b"set".to_vec(), b"key".to_vec(), b"value".to_vec()]]
+        );
+        assert!(aof.lock().unwrap().rewrite_buffer.is_none());
+    }
+
+    #[test]
+    fn test_read_truncates_incomplete_command() {
+        let path = "test_read_truncates_incomplete_command.aof";
+        let complete = "*2\r\n$3\r\ndel\r\n$3\r\nkey\r\n";
+        fs::write(path, format!("{}*3\r\n$3\r\nset\r\n$3\r\nke", complete)).unwrap();
+
+        let commands = Aof::read(path).unwrap();
+        let content = fs::read(path).unwrap();
+        let _ = fs::remove_file(path);
+
+        assert_eq!(commands, vec![vec![b"del".to_vec(), b"key".to_vec()]]);
+        assert_eq!(content, complete.as_bytes());
+    }
+
+    #[test]
+    fn test_rewrite_commands_with_ttl() {
+        let mut db: TtlHashMap<Vec<u8>, RedisElement> = TtlHashMap::new();
+        let ttl = SystemTime::UNIX_EPOCH + Duration::from_secs(4_000_000_000);
+        db.insert("key".into(), RedisElement::String("value".into()));
+        db.set_ttl_absolute("key".into(), ttl);
+
+        assert_eq!(
+            rewrite_commands(&db),
+            vec![
+                vec![b"set".to_vec(), b"key".to_vec(), b"value".to_vec()],
+                vec![
+                    b"pexpireat".to_vec(),
+                    b"key".to_vec(),
+                    b"4000000000000".to_vec()
+                ],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rewrite_commands_splits_big_collections() {
+        let mut db: TtlHashMap<Vec<u8>, RedisElement> = TtlHashMap::new();
+        let list = (0..100).map(|i| i.to_string().into_bytes()).collect();
+        db.insert("list".into(), RedisElement::List(list));
+
+        let commands = rewrite_commands(&db);
+
+        assert_eq!(commands.len(), 2);
+        assert_eq!(commands[0].len(), 66);
+        assert_eq!(commands[1].len(), 38);
+        assert_eq!(commands[1][2], b"64".to_vec());
+    }
+
+    #[test]
+    fn test_rewrite_commands_sorted_set() {
+        let mut db: TtlHashMap<Vec<u8>, RedisElement> = TtlHashMap::new();
+        let mut sorted_set = SortedSet::new();
+        sorted_set.insert("a".into(), 1.5);
+        sorted_set.insert("b".into(), f64::INFINITY);
+        db.insert("zset".into(), RedisElement::SortedSet(sorted_set));
+
+        assert_eq!(
+            rewrite_commands(&db),
+            vec![vec![
+                b"zadd".to_vec(),
+                b"zset".to_vec(),
+                b"1.5".to_vec(),
+                b"a".to_vec(),
+                b"inf".to_vec(),
+                b"b".to_vec(),
+            ]]
+        );
+    }
+}
@@ -0,0 +1,810 @@
+use crate::entities::cluster_param::{Gossip, SlotState};
+use crate::entities::crc16::crc16;
+use crate::entities::log::Log;
+use crate::entities::log_level::LogLevel;
+use crate::entities::reply::Reply;
+use crate::protocol::parse_data::parse_response_ok;
+use crate::service::rdb::Rdb;
+use crate::service::replication::new_replid;
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::mpsc::Sender;
+use std::thread;
+use std::time::Duration;
+
+/// Cantidad de hash slots en los que se reparten las claves del cluster.
+pub const CLUSTER_SLOTS: usize = 16384;
+/// Diferencia entre el puerto de los clientes y el del bus del cluster, como en Redis. Sólo se usa
+/// para informarlo en CLUSTER NODES.
+const CLUSTER_BUS_PORT_OFFSET: u32 = 10000;
+/// Tiempo máximo de espera al enviar el estado a otro nodo.
+const GOSSIP_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Devuelve el hash slot de la clave: el CRC16 de la clave módulo 16384. Si la clave tiene un hash
+/// tag (un texto no vacío entre la primera `{` y la siguiente `}`), sólo se usa el hash tag, para
+/// que claves relacionadas queden en el mismo slot.
+pub fn key_hash_slot(key: &[u8]) -> u16 {
+    let tag = key.iter().position(|byte| *byte == b'{').and_then(|start| {
+        key[start + 1..]
+            .iter()
+            .position(|byte| *byte == b'}')
+            .filter(|len| *len > 0)
+            .map(|len| &key[start + 1..start + 1 + len])
+    });
+    crc16(tag.unwrap_or(key)) % CLUSTER_SLOTS as u16
+}
+
+#[derive(Debug)]
+/// Otro nodo del cluster.
+struct ClusterNode {
+    host: String,
+    port: u16,
+    epoch: u64,
+}
+
+#[derive(Debug)]
+/// Estado del cluster visto por este nodo: qué nodo atiende cada slot y qué slots se están
+/// migrando. Los nodos se informan su estado entre sí enviándose `CLUSTER GOSSIP` periódicamente.
+pub struct Cluster {
+    /// Indica si el servidor corre en modo cluster.
+    enabled: bool,
+    /// Identificador de este nodo.
+    myself: String,
+    /// Dirección que este nodo informa a los demás.
+    host: String,
+    /// Puerto en el que este nodo atiende a los clientes.
+    port: u16,
+    /// Época de configuración de este nodo.
+    epoch: u64,
+    /// Mayor época vista en el cluster.
+    current_epoch: u64,
+    /// Mapa en donde se guarda {Id_nodo, nodo} de los demás nodos conocidos.
+    nodes: HashMap<String, ClusterNode>,
+    /// Identificador del nodo que atiende cada slot.
+    slots: Vec<Option<String>>,
+    /// Slots de este nodo que se están enviando a otro nodo: {slot, Id_nodo destino}.
+    migrating: HashMap<u16, String>,
+    /// Slots que este nodo está recibiendo de otro nodo: {slot, Id_nodo origen}.
+    importing: HashMap<u16, String>,
+    /// Direcciones indicadas con CLUSTER MEET de las que todavía no se recibió el estado.
+    meet: Vec<(String, u16)>,
+    /// Archivo en el que se guarda la configuración del nodo (`cluster-config-file`), si se indicó.
+    config_file: Option<String>,
+    /// Último contenido escrito en el archivo de configuración.
+    saved_config: String,
+}
+
+impl Cluster {
+    /// Constructor del Cluster. Los identificadores de nodo tienen el mismo formato que los de
+    /// replicación.
+    pub fn new(enabled: bool, host: String, port: u16) -> Self {
+        Self {
+            enabled,
+            myself: new_replid(),
+            host,
+            port,
+            epoch: 0,
+            current_epoch: 0,
+            nodes: HashMap::new(),
+            slots: vec![None; CLUSTER_SLOTS],
+            migrating: HashMap::new(),
+            importing: HashMap::new(),
+            meet: vec![],
+            config_file: None,
+            saved_config: String::new(),
+        }
+    }
+
+    /// Carga la configuración del nodo desde el archivo indicado, si existe, y lo usa para guardar
+    /// los próximos cambios. Así el nodo conserva su identificador, su época, los slots que atiende
+    /// y los nodos que conoce al reiniciarse. Si el archivo no existe, se crea con la
+    /// configuración actual.
+    ///
+    /// El archivo tiene el formato de `nodes.conf` de Redis: las líneas de CLUSTER NODES y una
+    /// última línea `vars` con la época actual del cluster.
+    pub fn load_config(&mut self, path: String) -> io::Result<()> {
+        match fs::read_to_string(&path) {
+            Ok(content) => {
+                self.parse_config(&content).ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!(
+                            "Unrecoverable error: corrupted cluster config file {}",
+                            path
+                        ),
+                    )
+                })?;
+                self.saved_config = content;
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+        self.config_file = Some(path);
+        self.save_config()
+    }
+
+    /// Guarda la configuración del nodo en su archivo, si se indicó uno y la configuración cambió
+    /// desde el último guardado.
+    pub fn save_config(&mut self) -> io::Result<()> {
+        let path = match &self.config_file {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+        let content = format!(
+            "{}vars currentEpoch {} lastVoteEpoch 0\n",
+            self.nodes(),
+            self.current_epoch
+        );
+        if content != self.saved_config {
+            Rdb::write_file(path, content.as_bytes())?;
+            self.saved_config = content;
+        }
+        Ok(())
+    }
+
+    /// Reemplaza la configuración del nodo por la del contenido de un archivo de configuración.
+    /// Devuelve None si el contenido es inválido.
+    fn parse_config(&mut self, content: &str) -> Option<()> {
+        let mut slots = vec![None; CLUSTER_SLOTS];
+        let mut nodes = HashMap::new();
+        let (mut migrating, mut importing) = (HashMap::new(), HashMap::new());
+        let mut myself = None;
+        let mut current_epoch = 0;
+
+        for line in content.lines().filter(|line| !line.trim().is_empty()) {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields[0] == "vars" {
+                let epoch = fields.iter().position(|field| *field == "currentEpoch")?;
+                current_epoch = fields.get(epoch + 1)?.parse().ok()?;
+                continue;
+            }
+            let id = fields[0].to_string();
+            let (host, port) = fields.get(1)?.split('@').next()?.rsplit_once(':')?;
+            let port = port.parse().ok()?;
+            let epoch = fields.get(6)?.parse().ok()?;
+            if fields.get(2)?.split(',').any(|flag| flag == "myself") {
+                myself = Some((id.clone(), epoch));
+            } else {
+                let host = host.to_string();
+                nodes.insert(id.clone(), ClusterNode { host, port, epoch });
+            }
+
+            for range in fields.get(8..).unwrap_or(&[]) {
+                if let Some(migration) = range.strip_prefix('[').and_then(|r| r.strip_suffix(']')) {
+                    if let Some((slot, target)) = migration.split_once("->-") {
+                        migrating.insert(slot.parse().ok()?, target.to_string());
+                    } else {
+                        let (slot, source) = migration.split_once("-<-")?;
+                        importing.insert(slot.parse().ok()?, source.to_string());
+                    }
+                    continue;
+                }
+                let (start, end) = range.split_once('-').unwrap_or((range, range));
+                let (start, end): (usize, usize) = (start.parse().ok()?, end.parse().ok()?);
+                if start > end || end >= CLUSTER_SLOTS {
+                    return None;
+                }
+                for slot in &mut slots[start..=end] {
+                    *slot = Some(id.clone());
+                }
+            }
+        }
+
+        let (id, epoch) = myself?;
+        self.myself = id;
+        self.epoch = epoch;
+        self.current_epoch = current_epoch.max(epoch);
+        self.nodes = nodes;
+        self.slots = slots;
+        self.migrating = migrating;
+        self.importing = importing;
+        Some(())
+    }
+
+    /// Indica si el servidor corre en modo cluster.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Devuelve el identificador de este nodo.
+    pub fn myid(&self) -> &str {
+        &self.myself
+    }
+
+    /// Indica si el slot es atendido por este nodo.
+    pub fn is_mine(&self, slot: u16) -> bool {
+        self.slots[slot as usize].as_deref() == Some(self.myself.as_str())
+    }
+
+    /// Devuelve la dirección `host:port` del nodo indicado.
+    fn address(&self, id: &str) -> String {
+        match self.nodes.get(id) {
+            Some(node) => format!("{}:{}", node.host, node.port),
+            None => format!("{}:{}", self.host, self.port),
+        }
+    }
+
+    /// Verifica que las claves de un comando puedan atenderse en este nodo. Devuelve el error a
+    /// informar al cliente en caso contrario:
+    ///
+    /// * `CROSSSLOT` si las claves no están en un mismo slot.
+    /// * `MOVED` si el slot lo atiende otro nodo.
+    /// * `ASK` si el slot se está migrando y las claves ya no están en este nodo.
+    ///
+    /// Con `asking` (el cliente envió ASKING) se aceptan las claves de los slots que se están
+    /// recibiendo de otro nodo.
+    pub fn redirect<F>(&self, keys: &[&[u8]], asking: bool, mut exists: F) -> Option<String>
+    where
+        F: FnMut(&[u8]) -> bool,
+    {
+        let slot = key_hash_slot(keys.first()?);
+        if keys.iter().any(|key| key_hash_slot(key) != slot) {
+            return Some("CROSSSLOT Keys in request don't hash to the same slot".to_string());
+        }
+
+        match &self.slots[slot as usize] {
+            None if asking && self.importing.contains_key(&slot) => None,
+            None => Some("CLUSTERDOWN Hash slot not served".to_string()),
+            Some(owner) if *owner == self.myself => match self.migrating.get(&slot) {
+                Some(target) => {
+                    let existing = keys.iter().filter(|key| exists(key)).count();
+                    if existing == keys.len() {
+                        None
+                    } else if existing == 0 {
+                        Some(format!("ASK {} {}", slot, self.address(target)))
+                    } else {
+                        Some("TRYAGAIN Multiple keys request during rehashing of slot".to_string())
+                    }
+                }
+                None => None,
+            },
+            Some(_) if asking && self.importing.contains_key(&slot) => None,
+            Some(owner) => Some(format!("MOVED {} {}", slot, self.address(owner))),
+        }
+    }
+
+    /// Asigna los slots a este nodo. Falla si alguno ya está asignado.
+    pub fn addslots(&mut self, slots: &[u16]) -> Result<(), String> {
+        if let Some(slot) = slots
+            .iter()
+            .find(|slot| self.slots[**slot as usize].is_some())
+        {
+            return Err(format!("ERR Slot {} is already busy", slot));
+        }
+        for slot in slots {
+            self.slots[*slot as usize] = Some(self.myself.clone());
+        }
+        Ok(())
+    }
+
+    /// Agrega la dirección a las que se les envía el estado de este nodo, para que el nodo que
+    /// atiende en ella lo agregue al cluster y responda con el suyo.
+    pub fn meet(&mut self, host: String, port: u16) {
+        if !self.meet.contains(&(host.clone(), port)) {
+            self.meet.push((host, port));
+        }
+    }
+
+    /// Cambia el estado de un slot. Al migrar un slot, se lo marca como MIGRATING en el nodo de
+    /// origen e IMPORTING en el de destino, se mueven las claves con MIGRATE y por último se
+    /// indica en ambos nodos el nuevo dueño con NODE.
+    pub fn setslot(&mut self, slot: u16, state: SlotState) -> Result<(), String> {
+        match state {
+            SlotState::Importing(id) => {
+                if self.is_mine(slot) {
+                    return Err(format!("ERR I'm already the owner of hash slot {}", slot));
+                }
+                self.known_node(&id)?;
+                self.importing.insert(slot, id);
+            }
+            SlotState::Migrating(id) => {
+                if !self.is_mine(slot) {
+                    return Err(format!("ERR I'm not the owner of hash slot {}", slot));
+                }
+                self.known_node(&id)?;
+                self.migrating.insert(slot, id);
+            }
+            SlotState::Node(id) => {
+                if id != self.myself {
+                    self.known_node(&id)?;
+                }
+                self.migrating.remove(&slot);
+                // Al recibir un slot, el nodo toma una época nueva para que su configuración
+                // prevalezca sobre la del nodo de origen.
+                if self.importing.remove(&slot).is_some() && id == self.myself {
+                    self.current_epoch += 1;
+                    self.epoch = self.current_epoch;
+                }
+                self.slots[slot as usize] = Some(id);
+            }
+            SlotState::Stable => {
+                self.migrating.remove(&slot);
+                self.importing.remove(&slot);
+            }
+        }
+        Ok(())
+    }
+
+    fn known_node(&self, id: &str) -> Result<(), String> {
+        if self.nodes.contains_key(id) {
+            Ok(())
+        } else {
+            Err(format!("ERR I don't know about node {}", id))
+        }
+    }
+
+    /// Actualiza el estado del cluster con el informado por otro nodo. Los slots que el nodo dice
+    /// atender se le asignan salvo que los atienda otro nodo con mayor época.
+    pub fn gossip(&mut self, gossip: Gossip) {
+        if gossip.id == self.myself {
+            return;
+        }
+        self.current_epoch = self.current_epoch.max(gossip.epoch);
+        self.meet
+            .retain(|(host, port)| *port != gossip.port || !same_host(host, &gossip.host));
+
+        for (id, host, port) in gossip.nodes {
+            if id != self.myself && !self.nodes.contains_key(&id) {
+                self.nodes.insert(
+                    id,
+                    ClusterNode {
+                        host,
+                        port,
+                        epoch: 0,
+                    },
+                );
+            }
+        }
+        self.nodes.insert(
+            gossip.id.clone(),
+            ClusterNode {
+                host: gossip.host,
+                port: gossip.port,
+                epoch: gossip.epoch,
+            },
+        );
+
+        let gossip_epoch = gossip.epoch;
+        let mut claimed = vec![false; CLUSTER_SLOTS];
+        for (start, end) in gossip.slots.iter().copied() {
+            for slot in start..=end.min(CLUSTER_SLOTS as u16 - 1) {
+                claimed[slot as usize] = true;
+            }
+        }
+        for (slot, claimed) in claimed.into_iter().enumerate() {
+            let owner_epoch = match &self.slots[slot] {
+                Some(owner) if *owner == gossip.id => None,
+                Some(owner) if *owner == self.myself => Some(self.epoch),
+                Some(owner) => Some(self.nodes.get(owner).map_or(0, |node| node.epoch)),
+                None => None,
+            };
+            if claimed && owner_epoch.is_none_or(|epoch| epoch < gossip_epoch) {
+                self.slots[slot] = Some(gossip.id.clone());
+            } else if !claimed && self.slots[slot].as_deref() == Some(gossip.id.as_str()) {
+                self.slots[slot] = None;
+            }
+        }
+    }
+
+    /// Devuelve el estado de este nodo, para enviarlo a los demás.
+    pub fn my_gossip(&self) -> Gossip {
+        Gossip {
+            id: self.myself.clone(),
+            host: self.host.clone(),
+            port: self.port,
+            epoch: self.epoch,
+            slots: self.slot_ranges(&self.myself),
+            nodes: self
+                .nodes
+                .iter()
+                .map(|(id, node)| (id.clone(), node.host.clone(), node.port))
+                .collect(),
+        }
+    }
+
+    /// Envía, desde un hilo aparte, el estado de este nodo a los demás nodos conocidos y a las
+    /// direcciones indicadas con CLUSTER MEET.
+    pub fn announce(&self, log_sender: Sender<Log>) {
+        let mut addresses: Vec<(String, u16)> = self
+            .nodes
+            .values()
+            .map(|node| (node.host.clone(), node.port))
+            .collect();
+        addresses.extend(self.meet.iter().cloned());
+        if addresses.is_empty() {
+            return;
+        }
+
+        let message = parse_response_ok(Reply::bulk_strings(self.my_gossip().to_args()));
+        thread::spawn(move || {
+            for (host, port) in addresses {
+                if let Err(e) = send_gossip(&host, port, &message) {
+                    let _ = log_sender.send(Log::new(
+                        LogLevel::Debug,
+                        line!(),
+                        column!(),
+                        file!().to_string(),
+                        format!("Cluster node {}:{} unreachable: {}", host, port, e),
+                    ));
+                }
+            }
+        });
+    }
+
+    /// Devuelve los rangos (inclusivos) de slots que atiende el nodo indicado.
+    fn slot_ranges(&self, id: &str) -> Vec<(u16, u16)> {
+        let mut ranges: Vec<(u16, u16)> = vec![];
+        for (slot, owner) in self.slots.iter().enumerate() {
+            if owner.as_deref() != Some(id) {
+                continue;
+            }
+            let slot = slot as u16;
+            match ranges.last_mut() {
+                Some((_, end)) if *end + 1 == slot => *end = slot,
+                _ => ranges.push((slot, slot)),
+            }
+        }
+        ranges
+    }
+
+    /// Devuelve la respuesta de CLUSTER INFO.
+    pub fn info(&self) -> String {
+        let assigned = self.slots.iter().filter(|owner| owner.is_some()).count();
+        let state = if assigned == CLUSTER_SLOTS {
+            "ok"
+        } else {
+            "fail"
+        };
+        format!(
+            "cluster_state:{}\r\ncluster_slots_assigned:{}\r\ncluster_known_nodes:{}\r\n\
+             cluster_size:{}\r\ncluster_current_epoch:{}\r\ncluster_my_epoch:{}\r\n",
+            state,
+            assigned,
+            self.nodes.len() + 1,
+            self.masters_with_slots(),
+            self.current_epoch,
+            self.epoch
+        )
+    }
+
+    fn masters_with_slots(&self) -> usize {
+        let mut owners: Vec<&String> = self.slots.iter().flatten().collect();
+        owners.sort();
+        owners.dedup();
+        owners.len()
+    }
+
+    /// Devuelve la respuesta de CLUSTER NODES: una línea por nodo con su identificador, dirección,
+    /// flags, época, estado de la conexión y los slots que atiende. Para este nodo se agregan
+    /// además los slots en migración.
+    pub fn nodes(&self) -> String {
+        let mut lines = vec![self.node_line(
+            &self.myself,
+            &self.host,
+            self.port,
+            "myself,master",
+            self.epoch,
+        )];
+        let mut ids: Vec<&String> = self.nodes.keys().collect();
+        ids.sort();
+        for id in ids {
+            let node = &self.nodes[id];
+            lines.push(self.node_line(id, &node.host, node.port, "master", node.epoch));
+        }
+        lines.join("\n") + "\n"
+    }
+
+    fn node_line(&self, id: &str, host: &str, port: u16, flags: &str, epoch: u64) -> String {
+        let mut line = format!(
+            "{} {}:{}@{} {} - 0 0 {} connected",
+            id,
+            host,
+            port,
+            port as u32 + CLUSTER_BUS_PORT_OFFSET,
+            flags,
+            epoch
+        );
+        for (start, end) in self.slot_ranges(id) {
+            if start == end {
+                line += &format!(" {}", start);
+            } else {
+                line += &format!(" {}-{}", start, end);
+            }
+        }
+        if id == self.myself {
+            let mut migrating: Vec<(&u16, &String)> = self.migrating.iter().collect();
+            migrating.sort();
+            for (slot, target) in migrating {
+                line += &format!(" [{}->-{}]", slot, target);
+            }
+            let mut importing: Vec<(&u16, &String)> = self.importing.iter().collect();
+            importing.sort();
+            for (slot, source) in importing {
+                line += &format!(" [{}-<-{}]", slot, source);
+            }
+        }
+        line
+    }
+
+    /// Devuelve la respuesta de CLUSTER SLOTS: por cada rango de slots, su inicio, su fin y la
+    /// dirección e identificador del nodo que lo atiende.
+    pub fn slots(&self) -> Reply {
+        let mut ids: Vec<&String> = self.nodes.keys().collect();
+        ids.push(&self.myself);
+
+        let mut ranges: Vec<(u16, u16, &String)> = ids
+            .into_iter()
+            .flat_map(|id| {
+                self.slot_ranges(id)
+                    .into_iter()
+                    .map(move |(start, end)| (start, end, id))
+            })
+            .collect();
+        ranges.sort();
+
+        Reply::Array(
+            ranges
+                .into_iter()
+                .map(|(start, end, id)| {
+                    let (host, port) = match self.nodes.get(id) {
+                        Some(node) => (node.host.clone(), node.port),
+                        None => (self.host.clone(), self.port),
+                    };
+                    Reply::Array(vec![
+                        Reply::Integer(start as i64),
+                        Reply::Integer(end as i64),
+                        Reply::Array(vec![
+                            Reply::BulkString(host.into_bytes()),
+                            Reply::Integer(port as i64),
+                            Reply::BulkString(id.clone().into_bytes()),
+                        ]),
+                    ])
+                })
+                .collect(),
+        )
+    }
+}
+
+/// Indica si dos direcciones corresponden al mismo equipo, considerando `localhost` igual a
+/// `127.0.0.1`.
+fn same_host(a: &str, b: &str) -> bool {
+    let normalize = |host: &str| {
+        if host == "localhost" {
+            "127.0.0.1".to_string()
+        } else {
+            host.to_string()
+        }
+    };
+    normalize(a) == normalize(b)
+}
+
+/// Envía el mensaje con el estado de este nodo a la dirección indicada y espera la respuesta.
+fn send_gossip(host: &str, port: u16, message: &[u8]) -> std::io::Result<()> {
+    let address = (host, port)
+        .to_socket_addrs()?
+        .next()
+        .ok_or_else(|| std::io::Error::other("Invalid address"))?;
+    let mut stream = TcpStream::connect_timeout(&address, GOSSIP_TIMEOUT)?;
+    stream.set_read_timeout(Some(GOSSIP_TIMEOUT))?;
+    stream.write_all(message)?;
+    let mut reply = String::new();
+    BufReader::new(stream).read_line(&mut reply)?;
+    Ok(())
+}
+
+/// Envía los comandos al nodo indicado, esperando la respuesta de cada uno. Lo usa MIGRATE para
+/// enviar las claves a otro nodo con RESTORE-ASKING.
+pub fn send_commands(
+    host: &str,
+    port: u16,
+    timeout: Duration,
+    commands: Vec<Vec<Vec<u8>>>,
+) -> Result<(), String> {
+    let io_error =
+        |e: std::io::Error| format!("IOERR error or timeout writing to target instance: {}", e);
+    let address = (host, port)
+        .to_socket_addrs()
+        .map_err(io_error)?
+        .next()
+        .ok_or_else(|| "IOERR invalid target instance address".to_string())?;
+    let mut stream = TcpStream::connect_timeout(&address, timeout).map_err(io_error)?;
+    stream.set_read_timeout(Some(timeout)).map_err(io_error)?;
+    stream.set_write_timeout(Some(timeout)).map_err(io_error)?;
+    let mut input = BufReader::new(stream.try_clone().map_err(io_error)?);
+
+    for command in commands {
+        stream
+            .write_all(&parse_response_ok(Reply::bulk_strings(command)))
+            .map_err(io_error)?;
+        let mut reply = String::new();
+        input.read_line(&mut reply).map_err(io_error)?;
+        if let Some(error) = reply.strip_prefix('-') {
+            return Err(format!(
+                "ERR Target instance replied with error: {}",
+                error.trim_end()
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::entities::cluster_param::{Gossip, SlotState};
+    use crate::entities::reply::Reply;
+    use crate::service::cluster::{key_hash_slot, Cluster};
+    use std::fs;
+
+    #[allow(dead_code)]
+    fn gossip(id: &str, port: u16, epoch: u64, slots: Vec<(u16, u16)>) -> Gossip {
+        Gossip {
+            id: id.to_string(),
+            host: "127.0.0.1".to_string(),
+            port,
+            epoch,
+            slots,
+            nodes: vec![],
+        }
+    }
+
+    #[test]
+    fn test_key_hash_slot() {
+        assert_eq!(key_hash_slot(b"foo"), 12182);
+        assert_eq!(key_hash_slot(b"123456789"), 12739);
+        assert_eq!(
+            key_hash_slot(b"{user1000}.following"),
+            key_hash_slot(b"user1000")
+        );
+        assert_eq!(key_hash_slot(b"foo{}{bar}"), key_hash_slot(b"foo{}{bar}"));
+        assert_ne!(key_hash_slot(b"foo{}{bar}"), key_hash_slot(b"bar"));
+        assert_eq!(key_hash_slot(b"foo{{bar}}zap"), key_hash_slot(b"{bar"));
+    }
+
+    #[test]
+    fn test_redirect_moved_and_crossslot() {
+        let mut cluster = Cluster::new(true, "127.0.0.1".to_string(), 7000);
+        cluster.addslots(&[key_hash_slot(b"foo")]).unwrap();
+        cluster.gossip(gossip("other", 7001, 0, vec![(0, 100)]));
+        let key_in_other = (0..)
+            .map(|i: u32| format!("key{}", i))
+            .find(|key| key_hash_slot(key.as_bytes()) <= 100)
+            .unwrap();
+
+        assert_eq!(cluster.redirect(&[b"foo"], false, |_| true), None);
+        assert_eq!(
+            cluster.redirect(&[key_in_other.as_bytes()], false, |_| true),
+            Some(format!(
+                "MOVED {} 127.0.0.1:7001",
+                key_hash_slot(key_in_other.as_bytes())
+            ))
+        );
+        assert!(cluster
+            .redirect(&[b"foo", key_in_other.as_bytes()], false, |_| true)
+            .unwrap()
+            .starts_with("CROSSSLOT"));
+        assert!(cluster
+            .redirect(&[b"bar"], false, |_| true)
+            .unwrap()
+            .starts_with("CLUSTERDOWN"));
+    }
+
+    #[test]
+    fn test_redirect_during_migration() {
+        let slot = key_hash_slot(b"foo");
+        let mut source = Cluster::new(true, "127.0.0.1".to_string(), 7000);
+        source.addslots(&[slot]).unwrap();
+        source.gossip(gossip("target", 7001, 0, vec![]));
+        source
+            .setslot(slot, SlotState::Migrating("target".to_string()))
+            .unwrap();
+
+        let mut target = Cluster::new(true, "127.0.0.1".to_string(), 7001);
+        target.gossip(source.my_gossip());
+        target
+            .setslot(slot, SlotState::Importing(source.myid().to_string()))
+            .unwrap();
+
+        assert_eq!(source.redirect(&[b"foo"], false, |_| true), None);
+        assert_eq!(
+            source.redirect(&[b"foo"], false, |_| false),
+            Some(format!("ASK {} 127.0.0.1:7001", slot))
+        );
+        assert!(target.redirect(&[b"foo"], false, |_| true).is_some());
+        assert_eq!(target.redirect(&[b"foo"], true, |_| true), None);
+    }
+
+    #[test]
+    fn test_setslot_node_wins_with_new_epoch() {
+        let slot = key_hash_slot(b"foo");
+        let mut source = Cluster::new(true, "127.0.0.1".to_string(), 7000);
+        let mut target = Cluster::new(true, "127.0.0.1".to_string(), 7001);
+        source.addslots(&[slot]).unwrap();
+        source.gossip(target.my_gossip());
+        target.gossip(source.my_gossip());
+        target
+            .setslot(slot, SlotState::Importing(source.myid().to_string()))
+            .unwrap();
+
+        let target_id = target.myid().to_string();
+        target
+            .setslot(slot, SlotState::Node(target_id.clone()))
+            .unwrap();
+        source.gossip(target.my_gossip());
+        target.gossip(source.my_gossip());
+
+        assert!(target.is_mine(slot));
+        assert!(!source.is_mine(slot));
+        assert_eq!(
+            source.redirect(&[b"foo"], false, |_| true),
+            Some(format!("MOVED {} 127.0.0.1:7001", slot))
+        );
+    }
+
+    #[test]
+    fn test_addslots_busy_and_slots_reply() {
+        let mut cluster = Cluster::new(true, "127.0.0.1".to_string(), 7000);
+        cluster.addslots(&[0, 1, 2, 5]).unwrap();
+
+        assert!(cluster.addslots(&[2]).is_err());
+        assert_eq!(
+            cluster.slots(),
+            Reply::Array(vec![
+                Reply::Array(vec![
+                    Reply::Integer(0),
+                    Reply::Integer(2),
+                    Reply::Array(vec![
+                        Reply::BulkString(b"127.0.0.1".to_vec()),
+                        Reply::Integer(7000),
+                        Reply::BulkString(cluster.myid().as_bytes().to_vec()),
+                    ]),
+                ]),
+                Reply::Array(vec![
+                    Reply::Integer(5),
+                    Reply::Integer(5),
+                    Reply::Array(vec![
+                        Reply::BulkString(b"127.0.0.1".to_vec()),
+                        Reply::Integer(7000),
+                        Reply::BulkString(cluster.myid().as_bytes().to_vec()),
+                    ]),
+                ]),
+            ])
+        );
+        assert!(cluster
+            .nodes()
+            .contains("myself,master - 0 0 0 connected 0-2 5"));
+    }
+
+    #[test]
+    fn test_config_file_keeps_node_id_slots_and_epoch() {
+        let path = "test_config_file_keeps_node_id_slots_and_epoch.conf".to_string();
+        let _ = fs::remove_file(&path);
+        let slot = key_hash_slot(b"foo");
+
+        let mut cluster = Cluster::new(true, "127.0.0.1".to_string(), 7000);
+        cluster.load_config(path.clone()).unwrap();
+        cluster.addslots(&[0, 1, 2, slot]).unwrap();
+        cluster.gossip(gossip("other", 7001, 3, vec![(100, 200)]));
+        cluster
+            .setslot(slot, SlotState::Migrating("other".to_string()))
+            .unwrap();
+        cluster.save_config().unwrap();
+
+        let mut restarted = Cluster::new(true, "127.0.0.1".to_string(), 7000);
+        restarted.load_config(path.clone()).unwrap();
+        let content = fs::read_to_string(&path).unwrap();
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(restarted.myid(), cluster.myid());
+        assert!(restarted.is_mine(slot));
+        assert!(restarted.is_mine(2));
+        assert!(!restarted.is_mine(150));
+        assert_eq!(restarted.nodes(), cluster.nodes());
+        assert_eq!(restarted.info(), cluster.info());
+        assert!(content.ends_with("vars currentEpoch 3 lastVoteEpoch 0\n"));
+        assert!(Cluster::new(true, "127.0.0.1".to_string(), 7000)
+            .parse_config("not a cluster config")
+            .is_none());
+    }
+}
@@ -1,24 +1,63 @@
+use crate::entities::cluster_param::{ClusterParam, Gossip, SlotState};
 use crate::entities::command::Command;
 use crate::entities::info_param::InfoParam;
 use crate::entities::pubsub_param::PubSubParam;
+use crate::entities::set_operation::SetOperation;
+use crate::entities::set_param::{SetCondition, SetExpiration, SetParam};
+use crate::entities::sort_param::SortParam;
+use crate::entities::sorted_set::{LexBound, ScoreBound};
+use crate::entities::zrange_param::{AggregateParam, ZRangeParam};
+use crate::service::cluster::CLUSTER_SLOTS;
+use crate::service::float::parse_float;
 use core::time::Duration;
 use std::collections::HashSet;
 use std::iter::FromIterator;
 use std::time::SystemTime;
 
+/// Mensaje de error usado cuando el valor no es entero o está fuera de rango permitido.
+const OUT_OF_RANGE_MSG: &str = "ERR value is not an integer or out of range";
+
+/// Mensaje de error usado cuando el incremento de INCRBYFLOAT o HINCRBYFLOAT no es un número.
+const NOT_FLOAT_MSG: &str = "ERR value is not a valid float";
+
+/// Máximo valor absoluto del count negativo de SRANDMEMBER. Con count negativo los miembros se
+/// repiten, así que la respuesta (que se arma completa antes de enviarla) tiene exactamente ese
+/// largo: un count mayor se rechaza en lugar de agotar la memoria del servidor.
+const SRANDMEMBER_MAX_REPEATED: i64 = 1 << 24;
+
+/// Pares campo-valor de los comandos HSET y HMSET.
+type FieldValues = Vec<(Vec<u8>, Vec<u8>)>;
+/// Parametros de ZUNIONSTORE y ZINTERSTORE: clave destino, claves de origen, pesos y agregación.
+type ZStoreParams = (Vec<u8>, Vec<Vec<u8>>, Vec<f64>, AggregateParam);
+
+/// Convierte un parámetro a texto, para los parámetros que no son claves ni valores (nombres de
+/// comandos, opciones, números, canales y rutas). Los bytes que no son UTF-8 válido se reemplazan.
+fn to_text(param: &[u8]) -> String {
+    String::from_utf8_lossy(param).to_string()
+}
+
+/// Convierte varios parámetros a texto.
+fn to_texts(params: &[Vec<u8>]) -> Vec<String> {
+    params.iter().map(|param| to_text(param)).collect()
+}
+
 #[allow(dead_code)]
 /// Generador de comandos validos a partir de listado de strings provenientes del Cliente.
-pub fn generate(params: Vec<String>, client_id: String) -> Result<Command, String> {
+pub fn generate(params: Vec<Vec<u8>>, client_id: String) -> Result<Command, String> {
     if params.is_empty() {
         return Err("Params can't be empty".to_string());
     }
 
-    let command = params.first().unwrap();
+    let command = to_text(params.first().unwrap());
     let params = Vec::from(params.get(1..).unwrap());
     match command.to_lowercase().as_str() {
         // Server
         "ping" => generate_ping(params),
+        "quit" => generate_quit(params),
+        "select" => generate_select(params),
+        "swapdb" => generate_swapdb(params),
         "flushdb" => generate_flushdb(params),
+        "flushall" => generate_flushall(params),
         "dbsize" => generate_dbsize(params),
         "monitor" => generate_monitor(params),
         "info" => generate_info(params),
@@ -26,17 +65,34 @@ pub fn generate(params: Vec<String>, client_id: String) -> Result<Command, Strin
         "store" => generate_store(params),
         "load" => generate_load(params),
         "config" => generate_config(params),
+        "bgrewriteaof" => generate_bgrewriteaof(params),
+        "save" => generate_save(params),
+        "bgsave" => generate_bgsave(params),
+        "lastsave" => generate_lastsave(params),
+        "replicaof" | "slaveof" => generate_replicaof(params),
+        "role" => generate_role(params),
+        "replconf" => generate_replconf(params, client_id),
+        "psync" => generate_psync(params, client_id),
+        "cluster" => generate_cluster(params),
+        "asking" => generate_asking(params),
 
         // Strings
         "get" => generate_get(params),
         "getset" => generate_getset(params),
         "set" => generate_set(params),
+        "setnx" => generate_setnx(params),
+        "setex" => generate_setex(params, false),
+        "psetex" => generate_setex(params, true),
+        "incr" => generate_incr(params, false),
+        "decr" => generate_incr(params, true),
         "incrby" => generate_incrby(params),
         "decrby" => generate_decrby(params),
+        "incrbyfloat" => generate_incrbyfloat(params),
         "getdel" => generate_getdel(params),
         "append" => generate_append(params),
         "mget" => generate_mget(params),
         "mset" => generate_mset(params),
+        "msetnx" => generate_msetnx(params),
         "strlen" => generate_strlen(params),
 
         // Keys
@@ -45,47 +101,122 @@ pub fn generate(params: Vec<String>, client_id: String) -> Result<Command, Strin
         "exists" => generate_exists(params),
         "expire" => generate_expire(params),
         "expireat" => generate_expireat(params),
+        "move" => generate_move(params),
+        "pexpireat" => generate_pexpireat(params),
         "persist" => generate_persist(params),
         "rename" => generate_rename(params),
-        "sort" => generate_sort(params),
+        "sort" => generate_sort(params, false),
+        "sort_ro" => generate_sort(params, true),
         "touch" => generate_touch(params),
         "ttl" => generate_ttl(params),
         "type" => generate_type(params),
+        "dump" => generate_dump(params),
+        "restore" => generate_restore(params, false),
+        "restore-asking" => generate_restore(params, true),
+        "migrate" => generate_migrate(params),
 
         // Lists
+        "blmove" => generate_blmove(params, client_id),
+        "blpop" => generate_bpop(params, client_id, true),
+        "brpop" => generate_bpop(params, client_id, false),
         "lindex" => generate_lindex(params),
+        "linsert" => generate_linsert(params),
         "llen" => generate_llen(params),
+        "lmove" => generate_lmove(params),
+        "lmpop" => generate_lmpop(params),
         "lpop" => generate_lpop(params),
+        "lpos" => generate_lpos(params),
         "lpush" => generate_lpush(params),
         "lpushx" => generate_lpushx(params),
         "lrange" => generate_lrange(params),
         "lrem" => generate_lrem(params),
         "lset" => generate_lset(params),
+        "ltrim" => generate_ltrim(params),
         "rpop" => generate_rpop(params),
+        "rpoplpush" => generate_rpoplpush(params),
         "rpush" => generate_rpush(params),
         "rpushx" => generate_rpushx(params),
 
         //Sets
         "sadd" => generate_sadd(params),
         "scard" => generate_scard(params),
+        "sdiff" => generate_set_operation(params, SetOperation::Diff),
+        "sdiffstore" => generate_set_operation_store(params, SetOperation::Diff),
+        "sinter" => generate_set_operation(params, SetOperation::Inter),
+        "sintercard" => generate_sintercard(params),
+        "sinterstore" => generate_set_operation_store(params, SetOperation::Inter),
         "sismember" => generate_sismember(params),
         "smembers" => generate_smembers(params),
+        "smismember" => generate_smismember(params),
+        "smove" => generate_smove(params),
+        "spop" => generate_spop(params),
+        "srandmember" => generate_srandmember(params),
+        "sscan" => generate_sscan(params),
         "srem" => generate_srem(params),
+        "sunion" => generate_set_operation(params, SetOperation::Union),
+        "sunionstore" => generate_set_operation_store(params, SetOperation::Union),
         "keys" => generate_keys(params),
+        "scan" => generate_scan(params),
+
+        // Hashes
+        "hdel" => generate_hdel(params),
+        "hexists" => generate_hexists(params),
+        "hget" => generate_hget(params),
+        "hgetall" => generate_hgetall(params),
+        "hincrby" => generate_hincrby(params),
+        "hincrbyfloat" => generate_hincrbyfloat(params),
+        "hkeys" => generate_hkeys(params),
+        "hlen" => generate_hlen(params),
+        "hmget" => generate_hmget(params),
+        "hmset" => generate_hmset(params),
+        "hscan" => generate_hscan(params),
+        "hset" => generate_hset(params),
+        "hsetnx" => generate_hsetnx(params),
+        "hstrlen" => generate_hstrlen(params),
+        "hvals" => generate_hvals(params),
+
+        // Sorted Sets
+        "zadd" => generate_zadd(params),
+        "zcard" => generate_zcard(params),
+        "zcount" => generate_zcount(params),
+        "zincrby" => generate_zincrby(params),
+        "zinterstore" => generate_zinterstore(params),
+        "zpopmax" => generate_zpopmax(params),
+        "zpopmin" => generate_zpopmin(params),
+        "zrange" => generate_zrange(params, "zrange", "rank", false, true),
+        "zrangebylex" => generate_zrange(params, "zrangebylex", "lex", false, false),
+        "zrangebyscore" => generate_zrange(params, "zrangebyscore", "score", false, false),
+        "zrank" => generate_zrank(params),
+        "zrem" => generate_zrem(params),
+        "zrevrange" => generate_zrange(params, "zrevrange", "rank", true, false),
+        "zrevrangebylex" => generate_zrange(params, "zrevrangebylex", "lex", true, false),
+        "zrevrangebyscore" => generate_zrange(params, "zrevrangebyscore", "score", true, false),
+        "zrevrank" => generate_zrevrank(params),
+        "zscore" => generate_zscore(params),
+        "zunionstore" => generate_zunionstore(params),
 
         //PubSub
         "pubsub" => generate_pubsub(params),
         "subscribe" => generate_subscribe(params, client_id),
         "publish" => generate_publish(params),
         "unsubscribe" => Ok(generate_unsubscribe(params, client_id)),
+        "psubscribe" => generate_psubscribe(params, client_id),
+        "punsubscribe" => Ok(generate_punsubscribe(params, client_id)),
         "command" => Ok(Command::Command),
 
+        // Transactions
+        "multi" => generate_multi(params),
+        "exec" => generate_exec(params, client_id),
+        "discard" => generate_discard(params, client_id),
+        "watch" => generate_watch(params, client_id),
+        "unwatch" => generate_unwatch(params, client_id),
+
         _ => Err("Command not valid".to_string()),
     }
 }
 
 /// Generador de comando Command::Ping.
-fn generate_ping(params: Vec<String>) -> Result<Command, String> {
+fn generate_ping(params: Vec<Vec<u8>>) -> Result<Command, String> {
     if params.len() > 1 {
         return Err("ERR wrong number of arguments for 'ping' command".to_string());
     }
@@ -93,8 +224,17 @@ fn generate_ping(params: Vec<String>) -> Result<Command, String> {
     Ok(Command::Ping)
 }
 
+/// Generador de comando Command::Quit.
+fn generate_quit(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    if !params.is_empty() {
+        return Err("ERR wrong number of arguments for 'quit' command".to_string());
+    }
+
+    Ok(Command::Quit)
+}
+
 /// Generador de comando Command::Monitor.
-fn generate_monitor(params: Vec<String>) -> Result<Command, String> {
+fn generate_monitor(params: Vec<Vec<u8>>) -> Result<Command, String> {
     if params.len() > 1 {
         return Err("ERR wrong number of arguments for 'monitor' command".to_string());
     }
@@ -103,12 +243,12 @@ fn generate_monitor(params: Vec<String>) -> Result<Command, String> {
 }
 
 /// Generador de comando Command::Info
-fn generate_info(params: Vec<String>) -> Result<Command, String> {
+fn generate_info(params: Vec<Vec<u8>>) -> Result<Command, String> {
     if params.len() != 1 {
         return Err("ERR wrong number of arguments for 'info' command".to_string());
     }
 
-    match params[0].to_lowercase().as_str() {
+    match to_text(&params[0]).to_lowercase().as_str() {
         "processid" => Ok(Command::Info {
             param: InfoParam::ProcessId,
         }),
@@ -127,23 +267,35 @@ fn generate_info(params: Vec<String>) -> Result<Command, String> {
         "connectedclients" => Ok(Command::Info {
             param: InfoParam::ConnectedClients,
         }),
+        "persistence" => Ok(Command::Info {
+            param: InfoParam::Persistence,
+        }),
+        "stats" => Ok(Command::Info {
+            param: InfoParam::Stats,
+        }),
+        "memory" => Ok(Command::Info {
+            param: InfoParam::Memory,
+        }),
+        "keyspace" => Ok(Command::Info {
+            param: InfoParam::Keyspace,
+        }),
         _ => Err("ERR wrong command param".to_string()),
     }
 }
 
 /// Generador de comando Command::Config
-fn generate_config(params: Vec<String>) -> Result<Command, String> {
+fn generate_config(params: Vec<Vec<u8>>) -> Result<Command, String> {
     if params.is_empty() {
         return Err("ERR wrong number of arguments for 'config' command".to_string());
     }
 
-    match params[0].as_str() {
+    match to_text(&params[0]).as_str() {
         "set" => {
             if params.len() != 3 {
                 return Err("ERR wrong number of arguments for 'config set' command".to_string());
             }
-            let parameter = params[1].clone();
-            let value = params[2].clone();
+            let parameter = to_text(&params[1]);
+            let value = to_text(&params[2]);
             Ok(Command::ConfigSet { parameter, value })
         }
         "get" => Ok(Command::ConfigGet),
@@ -151,16 +303,270 @@ fn generate_config(params: Vec<String>) -> Result<Command, String> {
     }
 }
 
+/// Generador de comando Command::Bgrewriteaof
+fn generate_bgrewriteaof(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    if !params.is_empty() {
+        return Err("ERR wrong number of arguments for 'bgrewriteaof' command".to_string());
+    }
+
+    Ok(Command::Bgrewriteaof)
+}
+
+/// Generador de comando Command::Save
+fn generate_save(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    if !params.is_empty() {
+        return Err("ERR wrong number of arguments for 'save' command".to_string());
+    }
+
+    Ok(Command::Save)
+}
+
+/// Generador de comando Command::Bgsave
+fn generate_bgsave(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    if !params.is_empty() {
+        return Err("ERR wrong number of arguments for 'bgsave' command".to_string());
+    }
+
+    Ok(Command::Bgsave)
+}
+
+/// Generador de comando Command::Lastsave
+fn generate_lastsave(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    if !params.is_empty() {
+        return Err("ERR wrong number of arguments for 'lastsave' command".to_string());
+    }
+
+    Ok(Command::Lastsave)
+}
+
+/// Generador de comando Command::Replicaof. Con `NO ONE` el servidor deja de ser réplica.
+fn generate_replicaof(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    if params.len() != 2 {
+        return Err("ERR wrong number of arguments for 'replicaof' command".to_string());
+    }
+
+    let host = to_text(&params[0]);
+    let port = to_text(&params[1]);
+    if host.to_lowercase() == "no" && port.to_lowercase() == "one" {
+        return Ok(Command::Replicaof { master: None });
+    }
+    let port: u16 = port
+        .parse()
+        .map_err(|_| "ERR Invalid master port".to_string())?;
+
+    Ok(Command::Replicaof {
+        master: Some((host, port)),
+    })
+}
+
+/// Generador de comando Command::Role
+fn generate_role(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    if !params.is_empty() {
+        return Err("ERR wrong number of arguments for 'role' command".to_string());
+    }
+
+    Ok(Command::Role)
+}
+
+/// Generador de comando Command::Replconf. Sólo se tiene en cuenta la opción `listening-port`,
+/// el resto de las opciones se aceptan y se ignoran.
+fn generate_replconf(params: Vec<Vec<u8>>, client_id: String) -> Result<Command, String> {
+    if !params.len().is_multiple_of(2) {
+        return Err("ERR syntax error".to_string());
+    }
+
+    let mut listening_port = None;
+    for option in params.chunks(2) {
+        if to_text(&option[0]).to_lowercase() == "listening-port" {
+            let port = to_text(&option[1])
+                .parse::<u16>()
+                .map_err(|_| OUT_OF_RANGE_MSG.to_string())?;
+            listening_port = Some(port);
+        }
+    }
+
+    Ok(Command::Replconf {
+        client_id,
+        listening_port,
+    })
+}
+
+/// Generador de comando Command::Psync
+fn generate_psync(params: Vec<Vec<u8>>, client_id: String) -> Result<Command, String> {
+    if params.len() != 2 {
+        return Err("ERR wrong number of arguments for 'psync' command".to_string());
+    }
+
+    let replid = to_text(&params[0]);
+    let offset: i64 = to_text(&params[1])
+        .parse()
+        .map_err(|_| OUT_OF_RANGE_MSG.to_string())?;
+
+    Ok(Command::Psync {
+        replid,
+        offset,
+        client_id,
+    })
+}
+
+/// Generador de comando Command::Cluster
+fn generate_cluster(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    if params.is_empty() {
+        return Err("ERR wrong number of arguments for 'cluster' command".to_string());
+    }
+
+    let subcommand = to_text(&params[0]).to_lowercase();
+    let args = &params[1..];
+    let wrong_args = || {
+        Err(format!(
+            "ERR Unknown subcommand or wrong number of arguments for '{}'",
+            subcommand
+        ))
+    };
+    let param = match (subcommand.as_str(), args.len()) {
+        ("info", 0) => ClusterParam::Info,
+        ("myid", 0) => ClusterParam::Myid,
+        ("nodes", 0) => ClusterParam::Nodes,
+        ("slots", 0) => ClusterParam::Slots,
+        ("keyslot", 1) => ClusterParam::Keyslot(args[0].clone()),
+        ("addslots", len) if len > 0 => ClusterParam::Addslots(
+            args.iter()
+                .map(|slot| parse_slot(slot))
+                .collect::<Result<Vec<u16>, String>>()?,
+        ),
+        ("addslotsrange", len) if len > 0 && len.is_multiple_of(2) => {
+            let mut slots = vec![];
+            for range in args.chunks(2) {
+                let (start, end) = (parse_slot(&range[0])?, parse_slot(&range[1])?);
+                if start > end {
+                    return Err(format!(
+                        "ERR start slot number {} is greater than end slot number {}",
+                        start, end
+                    ));
+                }
+                slots.extend(start..=end);
+            }
+            ClusterParam::Addslots(slots)
+        }
+        ("meet", 2) => ClusterParam::Meet(
+            to_text(&args[0]),
+            to_text(&args[1])
+                .parse()
+                .map_err(|_| "ERR Invalid node address specified".to_string())?,
+        ),
+        ("setslot", 2) | ("setslot", 3) => {
+            let slot = parse_slot(&args[0])?;
+            let node = args.get(2).map(|node| to_text(node));
+            let state = match (to_text(&args[1]).to_lowercase().as_str(), node) {
+                ("importing", Some(node)) => SlotState::Importing(node),
+                ("migrating", Some(node)) => SlotState::Migrating(node),
+                ("node", Some(node)) => SlotState::Node(node),
+                ("stable", None) => SlotState::Stable,
+                _ => {
+                    return Err(
+                        "ERR Invalid CLUSTER SETSLOT action or number of arguments".to_string()
+                    )
+                }
+            };
+            ClusterParam::Setslot(slot, state)
+        }
+        ("countkeysinslot", 1) => ClusterParam::Countkeysinslot(parse_slot(&args[0])?),
+        ("getkeysinslot", 2) => ClusterParam::Getkeysinslot(
+            parse_slot(&args[0])?,
+            to_text(&args[1])
+                .parse()
+                .map_err(|_| "ERR Invalid number of keys".to_string())?,
+        ),
+        ("gossip", _) => ClusterParam::Gossip(
+            Gossip::from_args(&to_texts(args))
+                .ok_or_else(|| "ERR Invalid gossip message".to_string())?,
+        ),
+        _ => return wrong_args(),
+    };
+
+    Ok(Command::Cluster { param })
+}
+
+/// Parsea el número de un hash slot.
+fn parse_slot(param: &[u8]) -> Result<u16, String> {
+    match to_text(param).parse::<u16>() {
+        Ok(slot) if (slot as usize) < CLUSTER_SLOTS => Ok(slot),
+        _ => Err("ERR Invalid or out of range slot".to_string()),
+    }
+}
+
+/// Generador de comando Command::Asking
+fn generate_asking(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    if !params.is_empty() {
+        return Err("ERR wrong number of arguments for 'asking' command".to_string());
+    }
+
+    Ok(Command::Asking)
+}
+
+/// Parsea el número de una base de datos. El límite superior depende de la configuración, por lo
+/// que se verifica al ejecutar el comando.
+fn parse_db_index(param: &[u8], error: &str) -> Result<usize, String> {
+    let index: i64 = to_text(param)
+        .parse()
+        .map_err(|_| OUT_OF_RANGE_MSG.to_string())?;
+    if index < 0 {
+        return Err(error.to_string());
+    }
+    Ok(index as usize)
+}
+
+/// Generador de comando Command::Select
+fn generate_select(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    if params.len() != 1 {
+        return Err("ERR wrong number of arguments for 'select' command".to_string());
+    }
+
+    let index = parse_db_index(&params[0], "ERR DB index is out of range")?;
+    Ok(Command::Select { index })
+}
+
+/// Generador de comando Command::Swapdb
+fn generate_swapdb(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    if params.len() != 2 {
+        return Err("ERR wrong number of arguments for 'swapdb' command".to_string());
+    }
+
+    let index1 = parse_db_index(&params[0], "ERR invalid first DB index")?;
+    let index2 = parse_db_index(&params[1], "ERR invalid second DB index")?;
+    Ok(Command::Swapdb { index1, index2 })
+}
+
 /// Generador de comando Command::Flushdb
-fn generate_flushdb(params: Vec<String>) -> Result<Command, String> {
+fn generate_flushdb(params: Vec<Vec<u8>>) -> Result<Command, String> {
     if params.len() > 1 {
         return Err("ERR wrong number of arguments for 'flushdb' command".to_string());
     }
 
     Ok(Command::Flushdb)
 }
+
+/// Generador de comando Command::Flushall
+fn generate_flushall(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    if params.len() > 1 {
+        return Err("ERR wrong number of arguments for 'flushall' command".to_string());
+    }
+
+    Ok(Command::Flushall)
+}
+
+/// Generador de comando Command::Move
+fn generate_move(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    if params.len() != 2 {
+        return Err("ERR wrong number of arguments for 'move' command".to_string());
+    }
+
+    let key = params[0].clone();
+    let db = parse_db_index(&params[1], "ERR DB index is out of range")?;
+    Ok(Command::Move { key, db })
+}
 /// Generador de comando Command::Copy
-fn generate_copy(params: Vec<String>) -> Result<Command, String> {
+fn generate_copy(params: Vec<Vec<u8>>) -> Result<Command, String> {
     if params.len() != 2 {
         return Err("ERR wrong number of arguments for 'copy' command".to_string());
     }
@@ -174,7 +580,7 @@ fn generate_copy(params: Vec<String>) -> Result<Command, String> {
 }
 
 /// Generador de comando Command::Get
-fn generate_get(params: Vec<String>) -> Result<Command, String> {
+fn generate_get(params: Vec<Vec<u8>>) -> Result<Command, String> {
     if params.len() != 1 {
         return Err("ERR wrong number of arguments for 'get' command".to_string());
     }
@@ -184,7 +590,7 @@ fn generate_get(params: Vec<String>) -> Result<Command, String> {
 }
 
 /// Generador de comando Command::GetSet
-fn generate_getset(params: Vec<String>) -> Result<Command, String> {
+fn generate_getset(params: Vec<Vec<u8>>) -> Result<Command, String> {
     if params.len() != 2 {
         return Err("ERR wrong number of arguments for 'getset' command".to_string());
     }
@@ -195,24 +601,125 @@ fn generate_getset(params: Vec<String>) -> Result<Command, String> {
 }
 
 /// Generador de comando Command::Set
-fn generate_set(params: Vec<String>) -> Result<Command, String> {
-    if params.len() != 2 {
-        return Err("ERR syntax error".to_string());
+fn generate_set(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    if params.len() < 2 {
+        return Err("ERR wrong number of arguments for 'set' command".to_string());
+    }
+
+    let mut param = SetParam::default();
+    let mut options = params[2..].iter();
+    while let Some(option) = options.next() {
+        match to_text(option).to_lowercase().as_str() {
+            "nx" if param.condition.is_none() => param.condition = Some(SetCondition::Nx),
+            "xx" if param.condition.is_none() => param.condition = Some(SetCondition::Xx),
+            "get" => param.get = true,
+            "keepttl" if param.expiration.is_none() => param.keep_ttl = true,
+            unit @ ("ex" | "px" | "exat" | "pxat")
+                if param.expiration.is_none() && !param.keep_ttl =>
+            {
+                let value = options.next().ok_or("ERR syntax error")?;
+                param.expiration = Some(parse_set_expiration(unit, value, "set")?);
+            }
+            _ => return Err("ERR syntax error".to_string()),
+        }
     }
 
     let key = params[0].clone();
     let value = params[1].clone();
-    Ok(Command::Set { key, value })
+    Ok(Command::Set { key, value, param })
+}
+
+/// Parsea la expiración de SET, SETEX o PSETEX según su unidad: `ex` y `px` son segundos y
+/// milisegundos desde ahora, y `exat` y `pxat` son timestamps Unix en segundos y milisegundos.
+fn parse_set_expiration(unit: &str, param: &[u8], command: &str) -> Result<SetExpiration, String> {
+    let value = match to_text(param).parse::<i64>() {
+        Ok(value) => value,
+        Err(_) => return Err(OUT_OF_RANGE_MSG.to_string()),
+    };
+    let invalid = format!("ERR invalid expire time in '{}' command", command);
+    if value <= 0 {
+        return Err(invalid);
+    }
+
+    let duration = match unit {
+        "ex" | "exat" => Duration::from_secs(value as u64),
+        _ => Duration::from_millis(value as u64),
+    };
+    match unit {
+        "ex" | "px" => SystemTime::now()
+            .checked_add(duration)
+            .map(|_| SetExpiration::Relative(duration)),
+        _ => SystemTime::UNIX_EPOCH
+            .checked_add(duration)
+            .map(SetExpiration::Absolute),
+    }
+    .ok_or(invalid)
+}
+
+/// Generador de comando Command::Setnx
+fn generate_setnx(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    if params.len() != 2 {
+        return Err("ERR wrong number of arguments for 'setnx' command".to_string());
+    }
+
+    Ok(Command::Setnx {
+        key: params[0].clone(),
+        value: params[1].clone(),
+    })
+}
+
+/// Generador de comando Command::Set para SETEX, o para PSETEX si la expiración se indica en
+/// milisegundos.
+fn generate_setex(params: Vec<Vec<u8>>, millis: bool) -> Result<Command, String> {
+    let (name, unit) = if millis {
+        ("psetex", "px")
+    } else {
+        ("setex", "ex")
+    };
+    if params.len() != 3 {
+        return Err(format!(
+            "ERR wrong number of arguments for '{}' command",
+            name
+        ));
+    }
+
+    let expiration = parse_set_expiration(unit, &params[1], name)?;
+    Ok(Command::Set {
+        key: params[0].clone(),
+        value: params[2].clone(),
+        param: SetParam {
+            expiration: Some(expiration),
+            ..SetParam::default()
+        },
+    })
+}
+
+/// Generador de comando Command::Incrby para INCR, o Command::Decrby para DECR: incrementan o
+/// decrementan en 1.
+fn generate_incr(params: Vec<Vec<u8>>, decrement: bool) -> Result<Command, String> {
+    if params.len() != 1 {
+        let name = if decrement { "decr" } else { "incr" };
+        return Err(format!(
+            "ERR wrong number of arguments for '{}' command",
+            name
+        ));
+    }
+
+    let key = params[0].clone();
+    match decrement {
+        true => Ok(Command::Decrby { key, decrement: 1 }),
+        false => Ok(Command::Incrby { key, increment: 1 }),
+    }
 }
 
 /// Generador de comando Command::Incrby
-fn generate_incrby(params: Vec<String>) -> Result<Command, String> {
+fn generate_incrby(params: Vec<Vec<u8>>) -> Result<Command, String> {
     if params.len() != 2 {
         return Err("ERR syntax error".to_string());
     }
 
     let key = params[0].clone();
-    let increment: Result<u32, _> = params[1].to_string().parse();
+    let increment: Result<i64, _> = to_text(&params[1]).parse();
 
     if increment.is_err() {
         return Err("ERR value is not an integer or out of range".to_string());
@@ -223,13 +730,13 @@ fn generate_incrby(params: Vec<String>) -> Result<Command, String> {
 }
 
 /// Generador de comando Command::Decrby
-fn generate_decrby(params: Vec<String>) -> Result<Command, String> {
+fn generate_decrby(params: Vec<Vec<u8>>) -> Result<Command, String> {
     if params.len() != 2 {
         return Err("ERR syntax error".to_string());
     }
 
     let key = params[0].clone();
-    let decrement: Result<u32, _> = params[1].to_string().parse();
+    let decrement: Result<i64, _> = to_text(&params[1]).parse();
 
     if decrement.is_err() {
         return Err("ERR value is not an integer or out of range".to_string());
@@ -239,8 +746,19 @@ fn generate_decrby(params: Vec<String>) -> Result<Command, String> {
     Ok(Command::Decrby { key, decrement })
 }
 
+/// Generador de comando Command::Incrbyfloat
+fn generate_incrbyfloat(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    if params.len() != 2 {
+        return Err("ERR wrong number of arguments for 'incrbyfloat' command".to_string());
+    }
+
+    let key = params[0].clone();
+    let increment = parse_float(&params[1]).ok_or(NOT_FLOAT_MSG)?;
+    Ok(Command::Incrbyfloat { key, increment })
+}
+
 /// Generador de comando Command::GetDel
-fn generate_getdel(params: Vec<String>) -> Result<Command, String> {
+fn generate_getdel(params: Vec<Vec<u8>>) -> Result<Command, String> {
     if params.len() != 1 {
         return Err("ERR wrong number of arguments for 'getdel' command".to_string());
     }
@@ -250,7 +768,7 @@ fn generate_getdel(params: Vec<String>) -> Result<Command, String> {
 }
 
 /// Generador de comando Command::Del
-fn generate_del(params: Vec<String>) -> Result<Command, String> {
+fn generate_del(params: Vec<Vec<u8>>) -> Result<Command, String> {
     if params.is_empty() {
         return Err("ERR wrong number of arguments for 'del' command".to_string());
     }
@@ -259,7 +777,7 @@ fn generate_del(params: Vec<String>) -> Result<Command, String> {
 }
 
 /// Generador de comando Command::Append
-fn generate_append(params: Vec<String>) -> Result<Command, String> {
+fn generate_append(params: Vec<Vec<u8>>) -> Result<Command, String> {
     if params.len() != 2 {
         return Err("ERR wrong number of arguments for 'append' command".to_string());
     }
@@ -270,7 +788,7 @@ fn generate_append(params: Vec<String>) -> Result<Command, String> {
 }
 
 /// Generador de comando Command::Exists
-fn generate_exists(params: Vec<String>) -> Result<Command, String> {
+fn generate_exists(params: Vec<Vec<u8>>) -> Result<Command, String> {
     if params.is_empty() {
         return Err("ERR wrong number of arguments for 'exists' command".to_string());
     }
@@ -279,13 +797,13 @@ fn generate_exists(params: Vec<String>) -> Result<Command, String> {
 }
 
 /// Generador de comando Command::Expire
-fn generate_expire(params: Vec<String>) -> Result<Command, String> {
+fn generate_expire(params: Vec<Vec<u8>>) -> Result<Command, String> {
     if params.len() != 2 {
         return Err("ERR wrong number of arguments for 'expire' command".to_string());
     }
 
     let key = params[0].clone();
-    let seconds: Result<u32, _> = params[1].to_string().parse();
+    let seconds: Result<u32, _> = to_text(&params[1]).parse();
 
     if seconds.is_err() {
         return Err("ERR value is not an integer or out of range".to_string());
@@ -297,13 +815,13 @@ fn generate_expire(params: Vec<String>) -> Result<Command, String> {
 }
 
 /// Generador de comando Command::ExpireAt
-fn generate_expireat(params: Vec<String>) -> Result<Command, String> {
+fn generate_expireat(params: Vec<Vec<u8>>) -> Result<Command, String> {
     if params.len() != 2 {
         return Err("ERR wrong number of arguments for 'expireat' command".to_string());
     }
 
     let key = params[0].clone();
-    let seconds: Result<u32, _> = params[1].to_string().parse();
+    let seconds: Result<u32, _> = to_text(&params[1]).parse();
 
     if seconds.is_err() {
         return Err("ERR value is not an integer or out of range".to_string());
@@ -314,8 +832,24 @@ fn generate_expireat(params: Vec<String>) -> Result<Command, String> {
     Ok(Command::Expireat { key, ttl })
 }
 
+/// Generador de comando Command::Expireat a partir de un instante en milisegundos (PEXPIREAT).
+fn generate_pexpireat(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    if params.len() != 2 {
+        return Err("ERR wrong number of arguments for 'pexpireat' command".to_string());
+    }
+
+    let key = params[0].clone();
+    let millis: u64 = to_text(&params[1])
+        .parse()
+        .map_err(|_| OUT_OF_RANGE_MSG.to_string())?;
+
+    let ttl = SystemTime::UNIX_EPOCH + Duration::from_millis(millis);
+
+    Ok(Command::Expireat { key, ttl })
+}
+
 /// Generador de comando Command::Persist
-fn generate_persist(params: Vec<String>) -> Result<Command, String> {
+fn generate_persist(params: Vec<Vec<u8>>) -> Result<Command, String> {
     if params.len() != 1 {
         return Err("ERR wrong number of arguments for 'persist' command".to_string());
     }
@@ -325,7 +859,7 @@ fn generate_persist(params: Vec<String>) -> Result<Command, String> {
 }
 
 /// Generador de comando Command::Rename
-fn generate_rename(params: Vec<String>) -> Result<Command, String> {
+fn generate_rename(params: Vec<Vec<u8>>) -> Result<Command, String> {
     if params.len() != 2 {
         return Err("ERR wrong number of arguments for 'rename' command".to_string());
     }
@@ -338,18 +872,55 @@ fn generate_rename(params: Vec<String>) -> Result<Command, String> {
     })
 }
 
-/// Generador de comando Command::Sort
-fn generate_sort(params: Vec<String>) -> Result<Command, String> {
-    if params.len() != 1 {
-        return Err("ERR wrong number of arguments for 'sort' command".to_string());
+/// Generador de comando Command::Sort, para SORT, y Command::SortRo, para SORT_RO. Las opciones
+/// BY, LIMIT, GET, ASC, DESC, ALPHA y STORE se aceptan en cualquier orden; SORT_RO no acepta STORE.
+fn generate_sort(params: Vec<Vec<u8>>, read_only: bool) -> Result<Command, String> {
+    if params.is_empty() {
+        let name = if read_only { "sort_ro" } else { "sort" };
+        return Err(format!(
+            "ERR wrong number of arguments for '{}' command",
+            name
+        ));
+    }
+
+    let mut param = SortParam::default();
+    let mut options = params[1..].iter();
+    while let Some(option) = options.next() {
+        let mut next = || {
+            options
+                .next()
+                .cloned()
+                .ok_or_else(|| "ERR syntax error".to_string())
+        };
+        match to_text(option).to_lowercase().as_str() {
+            "by" => param.by = Some(next()?),
+            "get" => param.get.push(next()?),
+            "store" if !read_only => param.store = Some(next()?),
+            "limit" => {
+                let offset = to_text(&next()?).parse::<i64>();
+                let count = to_text(&next()?).parse::<i64>();
+                match (offset, count) {
+                    (Ok(offset), Ok(count)) => param.limit = Some((offset, count)),
+                    _ => return Err(OUT_OF_RANGE_MSG.to_string()),
+                }
+            }
+            "asc" => param.desc = false,
+            "desc" => param.desc = true,
+            "alpha" => param.alpha = true,
+            _ => return Err("ERR syntax error".to_string()),
+        }
     }
 
     let key = params[0].clone();
-    Ok(Command::Sort { key })
+    if read_only {
+        Ok(Command::SortRo { key, param })
+    } else {
+        Ok(Command::Sort { key, param })
+    }
 }
 
 /// Generador de comando Command::Touch
-fn generate_touch(params: Vec<String>) -> Result<Command, String> {
+fn generate_touch(params: Vec<Vec<u8>>) -> Result<Command, String> {
     if params.is_empty() {
         return Err("ERR wrong number of arguments for 'touch' command".to_string());
     }
@@ -358,7 +929,7 @@ fn generate_touch(params: Vec<String>) -> Result<Command, String> {
 }
 
 /// Generador de comando Command::Ttl
-fn generate_ttl(params: Vec<String>) -> Result<Command, String> {
+fn generate_ttl(params: Vec<Vec<u8>>) -> Result<Command, String> {
     if params.len() != 1 {
         return Err("ERR wrong number of arguments for 'ttl' command".to_string());
     }
@@ -368,7 +939,7 @@ fn generate_ttl(params: Vec<String>) -> Result<Command, String> {
 }
 
 /// Generador de comando Command::Type
-fn generate_type(params: Vec<String>) -> Result<Command, String> {
+fn generate_type(params: Vec<Vec<u8>>) -> Result<Command, String> {
     if params.len() != 1 {
         return Err("ERR wrong number of arguments for 'type' command".to_string());
     }
@@ -377,8 +948,102 @@ fn generate_type(params: Vec<String>) -> Result<Command, String> {
     Ok(Command::Type { key })
 }
 
+/// Generador de comando Command::Dump
+fn generate_dump(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    if params.len() != 1 {
+        return Err("ERR wrong number of arguments for 'dump' command".to_string());
+    }
+
+    let key = params[0].clone();
+    Ok(Command::Dump { key })
+}
+
+/// Generador de comando Command::Restore, para RESTORE y RESTORE-ASKING. Se aceptan las opciones
+/// REPLACE y ABSTTL; IDLETIME y FREQ se ignoran.
+fn generate_restore(params: Vec<Vec<u8>>, asking: bool) -> Result<Command, String> {
+    if params.len() < 3 {
+        return Err("ERR wrong number of arguments for 'restore' command".to_string());
+    }
+
+    let ttl: u64 = to_text(&params[1])
+        .parse()
+        .map_err(|_| "ERR Invalid TTL value, must be >= 0".to_string())?;
+    let mut replace = false;
+    let mut absttl = false;
+    let mut options = params[3..].iter();
+    while let Some(option) = options.next() {
+        match to_text(option).to_lowercase().as_str() {
+            "replace" => replace = true,
+            "absttl" => absttl = true,
+            "idletime" | "freq" => {
+                options
+                    .next()
+                    .ok_or_else(|| "ERR syntax error".to_string())?;
+            }
+            _ => return Err("ERR syntax error".to_string()),
+        }
+    }
+
+    Ok(Command::Restore {
+        key: params[0].clone(),
+        ttl,
+        payload: params[2].clone(),
+        replace,
+        absttl,
+        asking,
+    })
+}
+
+/// Generador de comando Command::Migrate. Un timeout de 0 se reemplaza por uno de un segundo.
+fn generate_migrate(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    if params.len() < 5 {
+        return Err("ERR wrong number of arguments for 'migrate' command".to_string());
+    }
+
+    let host = to_text(&params[0]);
+    let port: u16 = to_text(&params[1])
+        .parse()
+        .map_err(|_| OUT_OF_RANGE_MSG.to_string())?;
+    let db = parse_db_index(&params[3], "ERR DB index is out of range")?;
+    let timeout: u64 = to_text(&params[4])
+        .parse()
+        .map_err(|_| OUT_OF_RANGE_MSG.to_string())?;
+    let timeout = Duration::from_millis(if timeout == 0 { 1000 } else { timeout });
+
+    let mut keys = vec![];
+    let mut copy = false;
+    let mut replace = false;
+    let mut options = params[5..].iter();
+    while let Some(option) = options.next() {
+        match to_text(option).to_lowercase().as_str() {
+            "copy" => copy = true,
+            "replace" => replace = true,
+            "keys" => {
+                if !params[2].is_empty() {
+                    return Err("ERR When using MIGRATE KEYS option, the key argument must be set to the empty string".to_string());
+                }
+                keys.extend(options.by_ref().cloned());
+            }
+            _ => return Err("ERR syntax error".to_string()),
+        }
+    }
+    if !params[2].is_empty() {
+        keys.push(params[2].clone());
+    }
+
+    Ok(Command::Migrate {
+        host,
+        port,
+        keys,
+        db,
+        timeout,
+        copy,
+        replace,
+    })
+}
+
 /// Generador de comando Command::Mget
-fn generate_mget(params: Vec<String>) -> Result<Command, String> {
+fn generate_mget(params: Vec<Vec<u8>>) -> Result<Command, String> {
     if params.is_empty() {
         return Err("ERR wrong number of arguments for 'mget' command".to_string());
     }
@@ -387,21 +1052,34 @@ fn generate_mget(params: Vec<String>) -> Result<Command, String> {
 }
 
 /// Generador de comando Command::Mset
-fn generate_mset(params: Vec<String>) -> Result<Command, String> {
-    if params.is_empty() || params.len() % 2 != 0 {
+fn generate_mset(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    if params.is_empty() || !params.len().is_multiple_of(2) {
         return Err("ERR wrong number of arguments for 'mset' command".to_string());
     }
 
-    let mut key_values: Vec<(String, String)> = Vec::new();
+    let mut key_values: Vec<(Vec<u8>, Vec<u8>)> = Vec::new();
     for pair in params.chunks(2) {
-        let tuple = (pair[0].to_string(), pair[1].to_string());
+        let tuple = (pair[0].clone(), pair[1].clone());
         key_values.push(tuple);
     }
     Ok(Command::Mset { key_values })
 }
 
+/// Generador de comando Command::Msetnx
+fn generate_msetnx(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    if params.is_empty() || !params.len().is_multiple_of(2) {
+        return Err("ERR wrong number of arguments for 'msetnx' command".to_string());
+    }
+
+    let key_values = params
+        .chunks(2)
+        .map(|pair| (pair[0].clone(), pair[1].clone()))
+        .collect();
+    Ok(Command::Msetnx { key_values })
+}
+
 /// Generador de comando Command::Strlen
-fn generate_strlen(params: Vec<String>) -> Result<Command, String> {
+fn generate_strlen(params: Vec<Vec<u8>>) -> Result<Command, String> {
     if params.len() != 1 {
         return Err("ERR wrong number of arguments for 'strlen' command".to_string());
     }
@@ -411,7 +1089,7 @@ fn generate_strlen(params: Vec<String>) -> Result<Command, String> {
 }
 
 /// Generador de comando Command::Dbsize
-fn generate_dbsize(params: Vec<String>) -> Result<Command, String> {
+fn generate_dbsize(params: Vec<Vec<u8>>) -> Result<Command, String> {
     if !params.is_empty() {
         return Err("ERR wrong number of arguments for 'dbsize' command".to_string());
     }
@@ -419,14 +1097,141 @@ fn generate_dbsize(params: Vec<String>) -> Result<Command, String> {
     Ok(Command::Dbsize)
 }
 
+/// Generador de comando Command::Blpop, para BLPOP, y Command::Brpop, para BRPOP. El último
+/// parámetro es el timeout.
+fn generate_bpop(params: Vec<Vec<u8>>, client_id: String, left: bool) -> Result<Command, String> {
+    if params.len() < 2 {
+        let name = if left { "blpop" } else { "brpop" };
+        return Err(format!(
+            "ERR wrong number of arguments for '{}' command",
+            name
+        ));
+    }
+
+    let timeout = parse_timeout(&params[params.len() - 1])?;
+    let keys = params[..params.len() - 1].to_vec();
+    if left {
+        Ok(Command::Blpop {
+            keys,
+            timeout,
+            client_id,
+        })
+    } else {
+        Ok(Command::Brpop {
+            keys,
+            timeout,
+            client_id,
+        })
+    }
+}
+
+/// Generador de comando Command::Blmove
+fn generate_blmove(params: Vec<Vec<u8>>, client_id: String) -> Result<Command, String> {
+    if params.len() != 5 {
+        return Err("ERR wrong number of arguments for 'blmove' command".to_string());
+    }
+
+    Ok(Command::Blmove {
+        source: params[0].clone(),
+        destination: params[1].clone(),
+        from_left: parse_list_side(&params[2])?,
+        to_left: parse_list_side(&params[3])?,
+        timeout: parse_timeout(&params[4])?,
+        client_id,
+    })
+}
+
+/// Generador de comando Command::Lmove
+fn generate_lmove(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    if params.len() != 4 {
+        return Err("ERR wrong number of arguments for 'lmove' command".to_string());
+    }
+
+    Ok(Command::Lmove {
+        source: params[0].clone(),
+        destination: params[1].clone(),
+        from_left: parse_list_side(&params[2])?,
+        to_left: parse_list_side(&params[3])?,
+    })
+}
+
+/// Generador de comando Command::Lmove para RPOPLPUSH, que equivale a LMOVE con RIGHT LEFT.
+fn generate_rpoplpush(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    if params.len() != 2 {
+        return Err("ERR wrong number of arguments for 'rpoplpush' command".to_string());
+    }
+
+    Ok(Command::Lmove {
+        source: params[0].clone(),
+        destination: params[1].clone(),
+        from_left: false,
+        to_left: true,
+    })
+}
+
+/// Generador de comando Command::Lmpop
+fn generate_lmpop(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    if params.len() < 3 {
+        return Err("ERR wrong number of arguments for 'lmpop' command".to_string());
+    }
+
+    let numkeys = match to_text(&params[0]).parse::<usize>() {
+        Ok(numkeys) if numkeys > 0 => numkeys,
+        _ => return Err("ERR numkeys should be greater than 0".to_string()),
+    };
+    if params.len() < numkeys + 2 {
+        return Err("ERR syntax error".to_string());
+    }
+
+    let keys = params[1..=numkeys].to_vec();
+    let left = parse_list_side(&params[numkeys + 1])?;
+    let count = match &params[numkeys + 2..] {
+        [] => 1,
+        [option, count] if to_text(option).to_lowercase() == "count" => {
+            match to_text(count).parse::<usize>() {
+                Ok(count) if count > 0 => count,
+                _ => return Err("ERR count should be greater than 0".to_string()),
+            }
+        }
+        _ => return Err("ERR syntax error".to_string()),
+    };
+
+    Ok(Command::Lmpop { keys, left, count })
+}
+
+/// Parsea el extremo de una lista: devuelve true para LEFT y false para RIGHT.
+fn parse_list_side(param: &[u8]) -> Result<bool, String> {
+    match to_text(param).to_lowercase().as_str() {
+        "left" => Ok(true),
+        "right" => Ok(false),
+        _ => Err("ERR syntax error".to_string()),
+    }
+}
+
+/// Parsea el timeout de un comando bloqueante, en segundos. Un timeout de 0 espera
+/// indefinidamente, por lo que se devuelve None.
+fn parse_timeout(param: &[u8]) -> Result<Option<Duration>, String> {
+    let timeout = match to_text(param).parse::<f64>() {
+        Ok(timeout) if timeout.is_finite() => timeout,
+        _ => return Err("ERR timeout is not a float or out of range".to_string()),
+    };
+    if timeout < 0.0 {
+        return Err("ERR timeout is negative".to_string());
+    }
+    if timeout == 0.0 {
+        return Ok(None);
+    }
+    Ok(Some(Duration::from_secs_f64(timeout)))
+}
+
 /// Generador de comando Command::Lindex
-fn generate_lindex(params: Vec<String>) -> Result<Command, String> {
+fn generate_lindex(params: Vec<Vec<u8>>) -> Result<Command, String> {
     if params.len() != 2 {
         return Err("ERR wrong number of arguments for 'lindex' command".to_string());
     }
 
     let key = params[0].clone();
-    let index: Result<i32, _> = params[1].to_string().parse();
+    let index: Result<i32, _> = to_text(&params[1]).parse();
 
     if index.is_err() {
         return Err("ERR value is not an integer or out of range".to_string());
@@ -436,25 +1241,45 @@ fn generate_lindex(params: Vec<String>) -> Result<Command, String> {
     Ok(Command::Lindex { key, index })
 }
 
+/// Generador de comando Command::Linsert
+fn generate_linsert(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    if params.len() != 4 {
+        return Err("ERR wrong number of arguments for 'linsert' command".to_string());
+    }
+
+    let before = match to_text(&params[1]).to_lowercase().as_str() {
+        "before" => true,
+        "after" => false,
+        _ => return Err("ERR syntax error".to_string()),
+    };
+
+    Ok(Command::Linsert {
+        key: params[0].clone(),
+        before,
+        pivot: params[2].clone(),
+        element: params[3].clone(),
+    })
+}
+
 /// Generador de comando Command::Llen
-fn generate_llen(params: Vec<String>) -> Result<Command, String> {
+fn generate_llen(params: Vec<Vec<u8>>) -> Result<Command, String> {
     if params.is_empty() {
         return Err("ERR wrong number of arguments for 'llen' command".to_string());
     }
 
-    let key = params[0].to_string();
+    let key = params[0].clone();
     Ok(Command::Llen { key })
 }
 
 /// Generador de comando Command::Lpop
-fn generate_lpop(params: Vec<String>) -> Result<Command, String> {
+fn generate_lpop(params: Vec<Vec<u8>>) -> Result<Command, String> {
     if params.is_empty() || params.len() > 2 {
         return Err("ERR wrong number of arguments for 'lpop' command".to_string());
     }
 
     let mut count: usize = 0;
     if params.len() == 2 {
-        let parse_count: Result<usize, _> = params[1].to_string().parse();
+        let parse_count: Result<usize, _> = to_text(&params[1]).parse();
 
         if parse_count.is_err() {
             return Err("ERR value is not an integer or out of range".to_string());
@@ -463,42 +1288,78 @@ fn generate_lpop(params: Vec<String>) -> Result<Command, String> {
         count = parse_count.unwrap();
     }
 
-    let key = params[0].to_string();
+    let key = params[0].clone();
     Ok(Command::Lpop { key, count })
 }
 
+/// Generador de comando Command::Lpos
+fn generate_lpos(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    if params.len() < 2 || !params.len().is_multiple_of(2) {
+        return Err("ERR wrong number of arguments for 'lpos' command".to_string());
+    }
+
+    let mut rank = 1;
+    let mut count = None;
+    let mut maxlen = 0;
+    for option in params[2..].chunks(2) {
+        let value = match to_text(&option[1]).parse::<i64>() {
+            Ok(value) => value,
+            Err(_) => return Err("ERR value is not an integer or out of range".to_string()),
+        };
+        match to_text(&option[0]).to_lowercase().as_str() {
+            "rank" if value == 0 => {
+                return Err("ERR RANK can't be zero: use 1 to start from the first match, 2 from the second ... or use negative to start from the end of the list".to_string());
+            }
+            "rank" => rank = value,
+            "count" if value < 0 => return Err("ERR COUNT can't be negative".to_string()),
+            "count" => count = Some(value as usize),
+            "maxlen" if value < 0 => return Err("ERR MAXLEN can't be negative".to_string()),
+            "maxlen" => maxlen = value as usize,
+            _ => return Err("ERR syntax error".to_string()),
+        }
+    }
+
+    Ok(Command::Lpos {
+        key: params[0].clone(),
+        element: params[1].clone(),
+        rank,
+        count,
+        maxlen,
+    })
+}
+
 /// Generador de comando Command::Lrange
-fn generate_lrange(params: Vec<String>) -> Result<Command, String> {
+fn generate_lrange(params: Vec<Vec<u8>>) -> Result<Command, String> {
     if params.len() != 3 {
         return Err("ERR wrong number of arguments for 'lrange' command".to_string());
     }
 
-    let parse_begin: Result<i32, _> = params[1].to_string().parse();
+    let parse_begin: Result<i32, _> = to_text(&params[1]).parse();
     if parse_begin.is_err() {
         return Err("ERR value is not an integer or out of range".to_string());
     }
 
     let begin = parse_begin.unwrap();
 
-    let parse_end: Result<i32, _> = params[2].to_string().parse();
+    let parse_end: Result<i32, _> = to_text(&params[2]).parse();
     if parse_end.is_err() {
         return Err("ERR value is not an integer or out of range".to_string());
     }
 
     let end = parse_end.unwrap();
-    let key = params[0].to_string();
+    let key = params[0].clone();
 
     Ok(Command::Lrange { key, begin, end })
 }
 
 /// Generador de comando Command::Lrem
-fn generate_lrem(params: Vec<String>) -> Result<Command, String> {
+fn generate_lrem(params: Vec<Vec<u8>>) -> Result<Command, String> {
     if params.len() != 3 {
         return Err("ERR wrong number of arguments for 'lrem' command".to_string());
     }
 
     let key = params[0].clone();
-    let count: Result<i32, _> = params[1].clone().parse();
+    let count: Result<i32, _> = to_text(&params[1]).parse();
 
     if count.is_err() {
         return Err("ERR value is not an integer or out of range".to_string());
@@ -514,14 +1375,32 @@ fn generate_lrem(params: Vec<String>) -> Result<Command, String> {
     })
 }
 
+/// Generador de comando Command::Ltrim
+fn generate_ltrim(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    if params.len() != 3 {
+        return Err("ERR wrong number of arguments for 'ltrim' command".to_string());
+    }
+
+    let begin = to_text(&params[1]).parse::<i32>();
+    let end = to_text(&params[2]).parse::<i32>();
+    match (begin, end) {
+        (Ok(begin), Ok(end)) => Ok(Command::Ltrim {
+            key: params[0].clone(),
+            begin,
+            end,
+        }),
+        _ => Err("ERR value is not an integer or out of range".to_string()),
+    }
+}
+
 /// Generador de comando Command::Lset
-fn generate_lset(params: Vec<String>) -> Result<Command, String> {
+fn generate_lset(params: Vec<Vec<u8>>) -> Result<Command, String> {
     if params.len() != 3 {
         return Err("ERR wrong number of arguments for 'lset' command".to_string());
     }
 
     let key = params[0].clone();
-    let index: Result<i32, _> = params[1].clone().parse();
+    let index: Result<i32, _> = to_text(&params[1]).parse();
 
     if index.is_err() {
         return Err("ERR value is not an integer or out of range".to_string());
@@ -538,14 +1417,14 @@ fn generate_lset(params: Vec<String>) -> Result<Command, String> {
 }
 
 /// Generador de comando Command::Rpop
-fn generate_rpop(params: Vec<String>) -> Result<Command, String> {
+fn generate_rpop(params: Vec<Vec<u8>>) -> Result<Command, String> {
     if params.is_empty() || params.len() > 2 {
         return Err("ERR wrong number of arguments for 'rpop' command".to_string());
     }
 
     let mut count: usize = 0;
     if params.len() == 2 {
-        let parse_count: Result<usize, _> = params[1].to_string().parse();
+        let parse_count: Result<usize, _> = to_text(&params[1]).parse();
 
         if parse_count.is_err() {
             return Err("ERR value is not an integer or out of range".to_string());
@@ -554,12 +1433,12 @@ fn generate_rpop(params: Vec<String>) -> Result<Command, String> {
         count = parse_count.unwrap();
     }
 
-    let key = params[0].to_string();
+    let key = params[0].clone();
     Ok(Command::Rpop { key, count })
 }
 
 /// Generador de comando Command::Lpush
-fn generate_lpush(params: Vec<String>) -> Result<Command, String> {
+fn generate_lpush(params: Vec<Vec<u8>>) -> Result<Command, String> {
     if params.len() <= 1 {
         return Err("ERR wrong number of arguments for 'lpush' command".to_string());
     }
@@ -571,7 +1450,7 @@ fn generate_lpush(params: Vec<String>) -> Result<Command, String> {
 }
 
 /// Generador de comando Command::Lpushx
-fn generate_lpushx(params: Vec<String>) -> Result<Command, String> {
+fn generate_lpushx(params: Vec<Vec<u8>>) -> Result<Command, String> {
     if params.len() <= 1 {
         return Err("ERR wrong number of arguments for 'lpushx' command".to_string());
     }
@@ -583,7 +1462,7 @@ fn generate_lpushx(params: Vec<String>) -> Result<Command, String> {
 }
 
 /// Generador de comando Command::Rpush
-fn generate_rpush(params: Vec<String>) -> Result<Command, String> {
+fn generate_rpush(params: Vec<Vec<u8>>) -> Result<Command, String> {
     if params.len() <= 1 {
         return Err("ERR wrong number of arguments for 'rpush' command".to_string());
     }
@@ -595,7 +1474,7 @@ fn generate_rpush(params: Vec<String>) -> Result<Command, String> {
 }
 
 /// Generador de comando Command::Rpushx
-fn generate_rpushx(params: Vec<String>) -> Result<Command, String> {
+fn generate_rpushx(params: Vec<Vec<u8>>) -> Result<Command, String> {
     if params.len() <= 1 {
         return Err("ERR wrong number of arguments for 'rpushx' command".to_string());
     }
@@ -607,7 +1486,7 @@ fn generate_rpushx(params: Vec<String>) -> Result<Command, String> {
 }
 
 /// Generador de comando Command::Sadd
-fn generate_sadd(params: Vec<String>) -> Result<Command, String> {
+fn generate_sadd(params: Vec<Vec<u8>>) -> Result<Command, String> {
     if params.len() <= 1 {
         return Err("ERR wrong number of arguments for 'sadd' command".to_string());
     }
@@ -620,7 +1499,7 @@ fn generate_sadd(params: Vec<String>) -> Result<Command, String> {
 }
 
 /// Generador de comando Command::Scard
-fn generate_scard(params: Vec<String>) -> Result<Command, String> {
+fn generate_scard(params: Vec<Vec<u8>>) -> Result<Command, String> {
     if params.is_empty() {
         return Err("ERR wrong number of arguments for 'scard' command".to_string());
     }
@@ -629,7 +1508,7 @@ fn generate_scard(params: Vec<String>) -> Result<Command, String> {
 }
 
 /// Generador de comando Command::Sismember
-fn generate_sismember(params: Vec<String>) -> Result<Command, String> {
+fn generate_sismember(params: Vec<Vec<u8>>) -> Result<Command, String> {
     if params.len() != 2 {
         return Err("ERR wrong number of arguments for 'sismember' command".to_string());
     }
@@ -640,7 +1519,7 @@ fn generate_sismember(params: Vec<String>) -> Result<Command, String> {
 }
 
 /// Generador de comando Command::Srem
-fn generate_srem(params: Vec<String>) -> Result<Command, String> {
+fn generate_srem(params: Vec<Vec<u8>>) -> Result<Command, String> {
     if params.len() <= 1 {
         return Err("ERR wrong number of arguments for 'srem' command".to_string());
     }
@@ -653,7 +1532,7 @@ fn generate_srem(params: Vec<String>) -> Result<Command, String> {
 }
 
 /// Generador de comando Command::Smembers
-fn generate_smembers(params: Vec<String>) -> Result<Command, String> {
+fn generate_smembers(params: Vec<Vec<u8>>) -> Result<Command, String> {
     if params.is_empty() {
         return Err("ERR wrong number of arguments for 'smembers' command".to_string());
     }
@@ -662,52 +1541,837 @@ fn generate_smembers(params: Vec<String>) -> Result<Command, String> {
     Ok(Command::Smembers { key })
 }
 
-/// Generador de comando Command::Keys
-fn generate_keys(params: Vec<String>) -> Result<Command, String> {
-    if params.is_empty() {
-        return Err("ERR wrong number of arguments for 'keys' command".to_string());
+/// Generador de comando Command::Smismember
+fn generate_smismember(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    if params.len() < 2 {
+        return Err("ERR wrong number of arguments for 'smismember' command".to_string());
     }
-    let pattern = params[0].clone();
-    Ok(Command::Keys { pattern })
+
+    Ok(Command::Smismember {
+        key: params[0].clone(),
+        members: params[1..].to_vec(),
+    })
 }
 
-/// Generador de comando Command::Store
-fn generate_store(params: Vec<String>) -> Result<Command, String> {
-    if params.is_empty() {
-        return Err("ERR wrong number of arguments for 'store' command".to_string());
+/// Generador de comando Command::Smove
+fn generate_smove(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    if params.len() != 3 {
+        return Err("ERR wrong number of arguments for 'smove' command".to_string());
     }
 
-    let path = params[0].clone();
-    Ok(Command::Store { path })
+    Ok(Command::Smove {
+        source: params[0].clone(),
+        destination: params[1].clone(),
+        member: params[2].clone(),
+    })
+}
+
+/// Generador de comando Command::Spop
+fn generate_spop(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    if params.is_empty() || params.len() > 2 {
+        return Err("ERR wrong number of arguments for 'spop' command".to_string());
+    }
+
+    let count = match params.get(1) {
+        Some(count) => match to_text(count).parse::<i64>() {
+            Ok(count) if count >= 0 => Some(count as usize),
+            Ok(_) => return Err("ERR value is out of range, must be positive".to_string()),
+            Err(_) => return Err(OUT_OF_RANGE_MSG.to_string()),
+        },
+        None => None,
+    };
+    Ok(Command::Spop {
+        key: params[0].clone(),
+        count,
+    })
+}
+
+/// Generador de comando Command::Srandmember
+fn generate_srandmember(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    if params.is_empty() || params.len() > 2 {
+        return Err("ERR wrong number of arguments for 'srandmember' command".to_string());
+    }
+
+    let count = match params.get(1) {
+        Some(count) => match to_text(count).parse::<i64>() {
+            Ok(count) if count < -SRANDMEMBER_MAX_REPEATED => {
+                return Err("ERR value is out of range".to_string())
+            }
+            Ok(count) => Some(count),
+            Err(_) => return Err(OUT_OF_RANGE_MSG.to_string()),
+        },
+        None => None,
+    };
+    Ok(Command::Srandmember {
+        key: params[0].clone(),
+        count,
+    })
+}
+
+/// Generador de comando Command::Sinter, Command::Sunion o Command::Sdiff, según la operación.
+fn generate_set_operation(
+    params: Vec<Vec<u8>>,
+    operation: SetOperation,
+) -> Result<Command, String> {
+    if params.is_empty() {
+        return Err(format!(
+            "ERR wrong number of arguments for '{}' command",
+            operation.command_name()
+        ));
+    }
+
+    let keys = params;
+    match operation {
+        SetOperation::Inter => Ok(Command::Sinter { keys }),
+        SetOperation::Union => Ok(Command::Sunion { keys }),
+        SetOperation::Diff => Ok(Command::Sdiff { keys }),
+    }
+}
+
+/// Generador de comando Command::Sinterstore, Command::Sunionstore o Command::Sdiffstore, según
+/// la operación. El primer parámetro es la clave destino.
+fn generate_set_operation_store(
+    params: Vec<Vec<u8>>,
+    operation: SetOperation,
+) -> Result<Command, String> {
+    if params.len() < 2 {
+        return Err(format!(
+            "ERR wrong number of arguments for '{}store' command",
+            operation.command_name()
+        ));
+    }
+
+    let destination = params[0].clone();
+    let keys = params[1..].to_vec();
+    match operation {
+        SetOperation::Inter => Ok(Command::Sinterstore { destination, keys }),
+        SetOperation::Union => Ok(Command::Sunionstore { destination, keys }),
+        SetOperation::Diff => Ok(Command::Sdiffstore { destination, keys }),
+    }
+}
+
+/// Generador de comando Command::Sintercard
+fn generate_sintercard(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    if params.len() < 2 {
+        return Err("ERR wrong number of arguments for 'sintercard' command".to_string());
+    }
+
+    let numkeys = match to_text(&params[0]).parse::<usize>() {
+        Ok(numkeys) if numkeys > 0 => numkeys,
+        _ => return Err("ERR numkeys should be greater than 0".to_string()),
+    };
+    if params.len() < numkeys + 1 {
+        return Err("ERR Number of keys can't be greater than number of args".to_string());
+    }
+
+    let keys = params[1..=numkeys].to_vec();
+    let limit = match &params[numkeys + 1..] {
+        [] => 0,
+        [option, limit] if to_text(option).to_lowercase() == "limit" => {
+            match to_text(limit).parse::<i64>() {
+                Ok(limit) if limit >= 0 => limit as usize,
+                Ok(_) => return Err("ERR LIMIT can't be negative".to_string()),
+                Err(_) => return Err(OUT_OF_RANGE_MSG.to_string()),
+            }
+        }
+        _ => return Err("ERR syntax error".to_string()),
+    };
+
+    Ok(Command::Sintercard { keys, limit })
+}
+
+/// Generador de comando Command::Hdel
+fn generate_hdel(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    if params.len() <= 1 {
+        return Err("ERR wrong number of arguments for 'hdel' command".to_string());
+    }
+
+    let key = params[0].clone();
+    let fields = Vec::from(params.get(1..).unwrap());
+    Ok(Command::Hdel { key, fields })
+}
+
+/// Generador de comando Command::Hexists
+fn generate_hexists(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    if params.len() != 2 {
+        return Err("ERR wrong number of arguments for 'hexists' command".to_string());
+    }
+
+    let key = params[0].clone();
+    let field = params[1].clone();
+    Ok(Command::Hexists { key, field })
+}
+
+/// Generador de comando Command::Hget
+fn generate_hget(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    if params.len() != 2 {
+        return Err("ERR wrong number of arguments for 'hget' command".to_string());
+    }
+
+    let key = params[0].clone();
+    let field = params[1].clone();
+    Ok(Command::Hget { key, field })
+}
+
+/// Generador de comando Command::Hgetall
+fn generate_hgetall(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    if params.len() != 1 {
+        return Err("ERR wrong number of arguments for 'hgetall' command".to_string());
+    }
+
+    let key = params[0].clone();
+    Ok(Command::Hgetall { key })
+}
+
+/// Generador de comando Command::Hincrby
+fn generate_hincrby(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    if params.len() != 3 {
+        return Err("ERR wrong number of arguments for 'hincrby' command".to_string());
+    }
+
+    let key = params[0].clone();
+    let field = params[1].clone();
+    let increment: Result<i64, _> = to_text(&params[2]).parse();
+
+    if increment.is_err() {
+        return Err("ERR value is not an integer or out of range".to_string());
+    }
+
+    let increment = increment.unwrap();
+    Ok(Command::Hincrby {
+        key,
+        field,
+        increment,
+    })
+}
+
+/// Generador de comando Command::Hincrbyfloat
+fn generate_hincrbyfloat(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    if params.len() != 3 {
+        return Err("ERR wrong number of arguments for 'hincrbyfloat' command".to_string());
+    }
+
+    let key = params[0].clone();
+    let field = params[1].clone();
+    let increment = parse_float(&params[2]).ok_or(NOT_FLOAT_MSG)?;
+    Ok(Command::Hincrbyfloat {
+        key,
+        field,
+        increment,
+    })
+}
+
+/// Generador de comando Command::Hkeys
+fn generate_hkeys(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    if params.len() != 1 {
+        return Err("ERR wrong number of arguments for 'hkeys' command".to_string());
+    }
+
+    let key = params[0].clone();
+    Ok(Command::Hkeys { key })
+}
+
+/// Generador de comando Command::Hlen
+fn generate_hlen(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    if params.len() != 1 {
+        return Err("ERR wrong number of arguments for 'hlen' command".to_string());
+    }
+
+    let key = params[0].clone();
+    Ok(Command::Hlen { key })
+}
+
+/// Generador de comando Command::Hmget
+fn generate_hmget(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    if params.len() <= 1 {
+        return Err("ERR wrong number of arguments for 'hmget' command".to_string());
+    }
+
+    let key = params[0].clone();
+    let fields = Vec::from(params.get(1..).unwrap());
+    Ok(Command::Hmget { key, fields })
+}
+
+/// Genera los pares campo-valor de los comandos HSET y HMSET.
+fn generate_field_values(params: &[Vec<u8>], command: &str) -> Result<FieldValues, String> {
+    if params.len() < 3 || params.len().is_multiple_of(2) {
+        return Err(format!(
+            "ERR wrong number of arguments for '{}' command",
+            command
+        ));
+    }
+
+    let mut field_values: Vec<(Vec<u8>, Vec<u8>)> = Vec::new();
+    for pair in params[1..].chunks(2) {
+        field_values.push((pair[0].clone(), pair[1].clone()));
+    }
+    Ok(field_values)
+}
+
+/// Generador de comando Command::Hmset
+fn generate_hmset(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    let field_values = generate_field_values(&params, "hmset")?;
+
+    let key = params[0].clone();
+    Ok(Command::Hmset { key, field_values })
+}
+
+/// Opciones de los comandos de iteración: cursor, patrón (MATCH), cantidad (COUNT) y tipo (TYPE).
+type ScanOptions = (u64, Option<Vec<u8>>, usize, Option<String>);
+
+/// Parsea el cursor y las opciones de SCAN, SSCAN y HSCAN. La opción TYPE sólo la acepta SCAN.
+fn parse_scan_options(params: &[Vec<u8>], with_type: bool) -> Result<ScanOptions, String> {
+    let cursor: u64 = to_text(&params[0])
+        .parse()
+        .map_err(|_| "ERR invalid cursor".to_string())?;
+
+    let mut pattern = None;
+    let mut count = 10;
+    let mut value_type = None;
+    for option in params[1..].chunks(2) {
+        match to_text(&option[0]).to_lowercase().as_str() {
+            "match" => pattern = Some(option[1].clone()),
+            "count" => {
+                count = match to_text(&option[1]).parse::<usize>() {
+                    Ok(value) if value > 0 => value,
+                    _ => return Err("ERR value is not an integer or out of range".to_string()),
+                }
+            }
+            "type" if with_type => value_type = Some(to_text(&option[1]).to_lowercase()),
+            _ => return Err("ERR syntax error".to_string()),
+        }
+    }
+    Ok((cursor, pattern, count, value_type))
+}
+
+/// Generador de comando Command::Scan
+fn generate_scan(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    if params.is_empty() || params.len().is_multiple_of(2) {
+        return Err("ERR wrong number of arguments for 'scan' command".to_string());
+    }
+
+    let (cursor, pattern, count, value_type) = parse_scan_options(&params, true)?;
+    Ok(Command::Scan {
+        cursor,
+        pattern,
+        count,
+        value_type,
+    })
+}
+
+/// Generador de comando Command::Sscan
+fn generate_sscan(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    if params.len() < 2 || !params.len().is_multiple_of(2) {
+        return Err("ERR wrong number of arguments for 'sscan' command".to_string());
+    }
+
+    let key = params[0].clone();
+    let (cursor, pattern, count, _) = parse_scan_options(&params[1..], false)?;
+    Ok(Command::Sscan {
+        key,
+        cursor,
+        pattern,
+        count,
+    })
+}
+
+/// Generador de comando Command::Hscan
+fn generate_hscan(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    if params.len() < 2 || !params.len().is_multiple_of(2) {
+        return Err("ERR wrong number of arguments for 'hscan' command".to_string());
+    }
+
+    let key = params[0].clone();
+    let (cursor, pattern, count, _) = parse_scan_options(&params[1..], false)?;
+    Ok(Command::Hscan {
+        key,
+        cursor,
+        pattern,
+        count,
+    })
+}
+
+/// Generador de comando Command::Hset
+fn generate_hset(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    let field_values = generate_field_values(&params, "hset")?;
+
+    let key = params[0].clone();
+    Ok(Command::Hset { key, field_values })
+}
+
+/// Generador de comando Command::Hsetnx
+fn generate_hsetnx(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    if params.len() != 3 {
+        return Err("ERR wrong number of arguments for 'hsetnx' command".to_string());
+    }
+
+    let key = params[0].clone();
+    let field = params[1].clone();
+    let value = params[2].clone();
+    Ok(Command::Hsetnx { key, field, value })
+}
+
+/// Generador de comando Command::Hstrlen
+fn generate_hstrlen(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    if params.len() != 2 {
+        return Err("ERR wrong number of arguments for 'hstrlen' command".to_string());
+    }
+
+    let key = params[0].clone();
+    let field = params[1].clone();
+    Ok(Command::Hstrlen { key, field })
+}
+
+/// Generador de comando Command::Hvals
+fn generate_hvals(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    if params.len() != 1 {
+        return Err("ERR wrong number of arguments for 'hvals' command".to_string());
+    }
+
+    let key = params[0].clone();
+    Ok(Command::Hvals { key })
+}
+
+/// Parsea el puntaje de un miembro de un sorted set. Acepta los valores `inf`, `+inf` y `-inf`.
+fn parse_score(param: &[u8]) -> Result<f64, String> {
+    match to_text(param).parse::<f64>() {
+        Ok(score) if !score.is_nan() => Ok(score),
+        _ => Err("ERR value is not a valid float".to_string()),
+    }
+}
+
+/// Parsea un extremo de un rango de puntajes. Si comienza con `(` el extremo es exclusivo.
+fn parse_score_bound(param: &[u8]) -> Result<ScoreBound, String> {
+    let error = "ERR min or max is not a float".to_string();
+    match param.strip_prefix(b"(") {
+        Some(score) => Ok(ScoreBound::Exclusive(
+            parse_score(score).map_err(|_| error)?,
+        )),
+        None => Ok(ScoreBound::Inclusive(
+            parse_score(param).map_err(|_| error)?,
+        )),
+    }
+}
+
+/// Parsea un extremo de un rango lexicográfico: `-`, `+`, `[miembro` o `(miembro`.
+fn parse_lex_bound(param: &[u8]) -> Result<LexBound, String> {
+    if param == b"-" {
+        return Ok(LexBound::Min);
+    }
+    if param == b"+" {
+        return Ok(LexBound::Max);
+    }
+    if let Some(member) = param.strip_prefix(b"[") {
+        return Ok(LexBound::Inclusive(member.to_vec()));
+    }
+    if let Some(member) = param.strip_prefix(b"(") {
+        return Ok(LexBound::Exclusive(member.to_vec()));
+    }
+    Err("ERR min or max not valid string range item".to_string())
+}
+
+/// Generador de comando Command::Zadd
+fn generate_zadd(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    if params.len() < 3 {
+        return Err("ERR wrong number of arguments for 'zadd' command".to_string());
+    }
+
+    let key = params[0].clone();
+    let (mut nx, mut xx, mut gt, mut lt, mut ch, mut incr) =
+        (false, false, false, false, false, false);
+
+    let mut index = 1;
+    while index < params.len() {
+        match to_text(&params[index]).to_lowercase().as_str() {
+            "nx" => nx = true,
+            "xx" => xx = true,
+            "gt" => gt = true,
+            "lt" => lt = true,
+            "ch" => ch = true,
+            "incr" => incr = true,
+            _ => break,
+        }
+        index += 1;
+    }
+
+    let pairs = &params[index..];
+    if pairs.is_empty() || !pairs.len().is_multiple_of(2) {
+        return Err("ERR syntax error".to_string());
+    }
+    if nx && xx {
+        return Err("ERR XX and NX options at the same time are not compatible".to_string());
+    }
+    if (gt && lt) || (nx && (gt || lt)) {
+        return Err(
+            "ERR GT, LT, and/or NX options at the same time are not compatible".to_string(),
+        );
+    }
+    if incr && pairs.len() > 2 {
+        return Err("ERR INCR option supports a single increment-element pair".to_string());
+    }
+
+    let mut members: Vec<(f64, Vec<u8>)> = Vec::new();
+    for pair in pairs.chunks(2) {
+        members.push((parse_score(&pair[0])?, pair[1].clone()));
+    }
+
+    Ok(Command::Zadd {
+        key,
+        members,
+        nx,
+        xx,
+        gt,
+        lt,
+        ch,
+        incr,
+    })
+}
+
+/// Generador de comando Command::Zcard
+fn generate_zcard(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    if params.len() != 1 {
+        return Err("ERR wrong number of arguments for 'zcard' command".to_string());
+    }
+
+    let key = params[0].clone();
+    Ok(Command::Zcard { key })
+}
+
+/// Generador de comando Command::Zcount
+fn generate_zcount(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    if params.len() != 3 {
+        return Err("ERR wrong number of arguments for 'zcount' command".to_string());
+    }
+
+    let key = params[0].clone();
+    let min = parse_score_bound(&params[1])?;
+    let max = parse_score_bound(&params[2])?;
+    Ok(Command::Zcount { key, min, max })
+}
+
+/// Generador de comando Command::Zincrby
+fn generate_zincrby(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    if params.len() != 3 {
+        return Err("ERR wrong number of arguments for 'zincrby' command".to_string());
+    }
+
+    let key = params[0].clone();
+    let increment = parse_score(&params[1])?;
+    let member = params[2].clone();
+    Ok(Command::Zincrby {
+        key,
+        increment,
+        member,
+    })
+}
+
+/// Genera los parametros de los comandos ZPOPMIN y ZPOPMAX: la clave y la cantidad de miembros.
+fn generate_zpop(params: Vec<Vec<u8>>, command: &str) -> Result<(Vec<u8>, usize), String> {
+    if params.is_empty() || params.len() > 2 {
+        return Err(format!(
+            "ERR wrong number of arguments for '{}' command",
+            command
+        ));
+    }
+
+    let mut count: usize = 1;
+    if params.len() == 2 {
+        count = to_text(&params[1])
+            .parse()
+            .map_err(|_| "ERR value is out of range, must be positive".to_string())?;
+    }
+
+    Ok((params[0].clone(), count))
+}
+
+/// Generador de comando Command::Zpopmax
+fn generate_zpopmax(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    let (key, count) = generate_zpop(params, "zpopmax")?;
+    Ok(Command::Zpopmax { key, count })
+}
+
+/// Generador de comando Command::Zpopmin
+fn generate_zpopmin(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    let (key, count) = generate_zpop(params, "zpopmin")?;
+    Ok(Command::Zpopmin { key, count })
+}
+
+/// Generador de comando Command::Zrange, utilizado por ZRANGE y todas sus variantes
+/// (ZREVRANGE, ZRANGEBYSCORE, ZREVRANGEBYSCORE, ZRANGEBYLEX y ZREVRANGEBYLEX).
+///
+/// # Arguments
+///
+/// * `command` - Nombre del comando, usado en los mensajes de error.
+/// * `by` - Tipo de rango por defecto del comando: `rank`, `score` o `lex`.
+/// * `rev` - Indica si el comando devuelve los miembros en orden descendente.
+/// * `allow_by` - Indica si el comando acepta las opciones BYSCORE, BYLEX y REV.
+fn generate_zrange(
+    params: Vec<Vec<u8>>,
+    command: &str,
+    by: &str,
+    rev: bool,
+    allow_by: bool,
+) -> Result<Command, String> {
+    if params.len() < 3 {
+        return Err(format!(
+            "ERR wrong number of arguments for '{}' command",
+            command
+        ));
+    }
+
+    let key = params[0].clone();
+    let (mut by, mut rev) = (by, rev);
+    let mut limit = None;
+    let mut with_scores = false;
+
+    let mut index = 3;
+    while index < params.len() {
+        match to_text(&params[index]).to_lowercase().as_str() {
+            "withscores" => with_scores = true,
+            "limit" if index + 2 < params.len() => {
+                let offset: i64 = to_text(&params[index + 1])
+                    .parse()
+                    .map_err(|_| "ERR value is not an integer or out of range".to_string())?;
+                let count: i64 = to_text(&params[index + 2])
+                    .parse()
+                    .map_err(|_| "ERR value is not an integer or out of range".to_string())?;
+                limit = Some((offset, count));
+                index += 2;
+            }
+            "byscore" if allow_by => by = "score",
+            "bylex" if allow_by => by = "lex",
+            "rev" if allow_by => rev = true,
+            _ => return Err("ERR syntax error".to_string()),
+        }
+        index += 1;
+    }
+
+    if by == "rank" && limit.is_some() {
+        return Err(
+            "ERR syntax error, LIMIT is only supported in combination with either BYSCORE or BYLEX"
+                .to_string(),
+        );
+    }
+    if by == "lex" && with_scores {
+        return Err(
+            "ERR syntax error, WITHSCORES not supported in combination with BYLEX".to_string(),
+        );
+    }
+
+    // En los rangos por puntaje y lexicográficos en orden inverso, primero se indica el máximo.
+    let (first, second) = if rev && by != "rank" {
+        (&params[2], &params[1])
+    } else {
+        (&params[1], &params[2])
+    };
+
+    let param = match by {
+        "score" => ZRangeParam::Score {
+            min: parse_score_bound(first)?,
+            max: parse_score_bound(second)?,
+        },
+        "lex" => ZRangeParam::Lex {
+            min: parse_lex_bound(first)?,
+            max: parse_lex_bound(second)?,
+        },
+        _ => ZRangeParam::Rank {
+            start: to_text(first)
+                .parse()
+                .map_err(|_| OUT_OF_RANGE_MSG.to_string())?,
+            stop: to_text(second)
+                .parse()
+                .map_err(|_| OUT_OF_RANGE_MSG.to_string())?,
+        },
+    };
+
+    Ok(Command::Zrange {
+        key,
+        param,
+        rev,
+        limit,
+        with_scores,
+    })
+}
+
+/// Generador de comando Command::Zrank
+fn generate_zrank(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    if params.len() != 2 {
+        return Err("ERR wrong number of arguments for 'zrank' command".to_string());
+    }
+
+    let key = params[0].clone();
+    let member = params[1].clone();
+    Ok(Command::Zrank { key, member })
+}
+
+/// Generador de comando Command::Zrem
+fn generate_zrem(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    if params.len() <= 1 {
+        return Err("ERR wrong number of arguments for 'zrem' command".to_string());
+    }
+
+    let key = params[0].clone();
+    let members = Vec::from(params.get(1..).unwrap());
+    Ok(Command::Zrem { key, members })
+}
+
+/// Generador de comando Command::Zrevrank
+fn generate_zrevrank(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    if params.len() != 2 {
+        return Err("ERR wrong number of arguments for 'zrevrank' command".to_string());
+    }
+
+    let key = params[0].clone();
+    let member = params[1].clone();
+    Ok(Command::Zrevrank { key, member })
+}
+
+/// Generador de comando Command::Zscore
+fn generate_zscore(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    if params.len() != 2 {
+        return Err("ERR wrong number of arguments for 'zscore' command".to_string());
+    }
+
+    let key = params[0].clone();
+    let member = params[1].clone();
+    Ok(Command::Zscore { key, member })
+}
+
+/// Genera los parametros de los comandos ZUNIONSTORE y ZINTERSTORE: la clave destino, las
+/// claves de origen, sus pesos y la forma de combinar los puntajes.
+fn generate_zstore(params: Vec<Vec<u8>>, command: &str) -> Result<ZStoreParams, String> {
+    if params.len() < 3 {
+        return Err(format!(
+            "ERR wrong number of arguments for '{}' command",
+            command
+        ));
+    }
+
+    let destination = params[0].clone();
+    let numkeys: usize = to_text(&params[1])
+        .parse()
+        .map_err(|_| OUT_OF_RANGE_MSG.to_string())?;
+    if numkeys == 0 {
+        return Err(format!(
+            "ERR at least 1 input key is needed for '{}' command",
+            command
+        ));
+    }
+    if params.len() < numkeys + 2 {
+        return Err("ERR syntax error".to_string());
+    }
+
+    let keys = Vec::from(&params[2..numkeys + 2]);
+    let mut weights = vec![1.0; numkeys];
+    let mut aggregate = AggregateParam::Sum;
+
+    let mut index = numkeys + 2;
+    while index < params.len() {
+        match to_text(&params[index]).to_lowercase().as_str() {
+            "weights" if index + numkeys < params.len() => {
+                for (position, weight) in weights.iter_mut().enumerate() {
+                    *weight = parse_score(&params[index + 1 + position])
+                        .map_err(|_| "ERR weight value is not a float".to_string())?;
+                }
+                index += numkeys;
+            }
+            "aggregate" if index + 1 < params.len() => {
+                aggregate = match to_text(&params[index + 1]).to_lowercase().as_str() {
+                    "sum" => AggregateParam::Sum,
+                    "min" => AggregateParam::Min,
+                    "max" => AggregateParam::Max,
+                    _ => return Err("ERR syntax error".to_string()),
+                };
+                index += 1;
+            }
+            _ => return Err("ERR syntax error".to_string()),
+        }
+        index += 1;
+    }
+
+    Ok((destination, keys, weights, aggregate))
+}
+
+/// Generador de comando Command::Zinterstore
+fn generate_zinterstore(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    let (destination, keys, weights, aggregate) = generate_zstore(params, "zinterstore")?;
+    Ok(Command::Zinterstore {
+        destination,
+        keys,
+        weights,
+        aggregate,
+    })
+}
+
+/// Generador de comando Command::Zunionstore
+fn generate_zunionstore(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    let (destination, keys, weights, aggregate) = generate_zstore(params, "zunionstore")?;
+    Ok(Command::Zunionstore {
+        destination,
+        keys,
+        weights,
+        aggregate,
+    })
+}
+
+/// Generador de comando Command::Keys
+fn generate_keys(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    if params.is_empty() {
+        return Err("ERR wrong number of arguments for 'keys' command".to_string());
+    }
+    if params.len() != 1 {
+        return Err("ERR wrong number of arguments for 'keys' command".to_string());
+    }
+    let pattern = params[0].clone();
+    Ok(Command::Keys { pattern })
+}
+
+/// Generador de comando Command::Store
+fn generate_store(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    if params.is_empty() {
+        return Err("ERR wrong number of arguments for 'store' command".to_string());
+    }
+
+    let path = to_text(&params[0]);
+    Ok(Command::Store { path })
 }
 
 /// Generador de comando Command::Load
-fn generate_load(params: Vec<String>) -> Result<Command, String> {
+fn generate_load(params: Vec<Vec<u8>>) -> Result<Command, String> {
     if params.is_empty() {
         return Err("ERR wrong number of arguments for 'load' command".to_string());
     }
 
-    let path = params[0].clone();
+    let path = to_text(&params[0]);
     Ok(Command::Load { path })
 }
 
 /// Generador de comando Command::Pubsub
-fn generate_pubsub(params: Vec<String>) -> Result<Command, String> {
+fn generate_pubsub(params: Vec<Vec<u8>>) -> Result<Command, String> {
     if params.is_empty() {
         return Err("ERR wrong number of arguments for 'pubsub' command".to_string());
     }
 
-    match params[0].clone().to_lowercase().as_str() {
+    match to_text(&params[0]).to_lowercase().as_str() {
         "channels" => match params.len() {
             1 => Ok(Command::Pubsub {
                 param: PubSubParam::Channels,
             }),
             2 => Ok(Command::Pubsub {
-                param: PubSubParam::ChannelsWithChannel(params[1].clone()),
+                param: PubSubParam::ChannelsWithPattern(to_text(&params[1])),
+            }),
+            _ => Err(
+                "ERR Unknown subcommand or wrong number of arguments for ".to_string()
+                    + &to_text(&params[0]),
+            ),
+        },
+        "numpat" => match params.len() {
+            1 => Ok(Command::Pubsub {
+                param: PubSubParam::Numpat,
             }),
             _ => Err(
                 "ERR Unknown subcommand or wrong number of arguments for ".to_string()
-                    + params[0].as_str(),
+                    + &to_text(&params[0]),
             ),
         },
         "numsub" => match params.len() {
@@ -715,49 +2379,125 @@ fn generate_pubsub(params: Vec<String>) -> Result<Command, String> {
                 param: PubSubParam::Numsub,
             }),
             _ => Ok(Command::Pubsub {
-                param: PubSubParam::NumsubWithChannels(Vec::from(params.get(1..).unwrap())),
+                param: PubSubParam::NumsubWithChannels(to_texts(&params[1..])),
             }),
         },
         _ => Err(
             "ERR Unknown subcommand or wrong number of arguments for ".to_string()
-                + params[0].as_str(),
+                + &to_text(&params[0]),
         ),
     }
 }
 
 /// Generador de comando Command::Subscribe
-fn generate_subscribe(params: Vec<String>, client_id: String) -> Result<Command, String> {
+fn generate_subscribe(params: Vec<Vec<u8>>, client_id: String) -> Result<Command, String> {
     if params.is_empty() {
         return Err("ERR wrong number of arguments for 'subscribe' command".to_string());
     }
 
     Ok(Command::Subscribe {
-        channels: params,
+        channels: to_texts(&params),
         client_id,
     })
 }
 
 /// Generador de comando Command::Publish
-fn generate_publish(params: Vec<String>) -> Result<Command, String> {
+fn generate_publish(params: Vec<Vec<u8>>) -> Result<Command, String> {
     if params.len() != 2 {
         return Err("ERR wrong number of arguments for 'publish' command".to_string());
     }
-    let channel = params[0].clone();
+    let channel = to_text(&params[0]);
     let message = params[1].clone();
     Ok(Command::Publish { channel, message })
 }
 
 /// Generador de comando Command::Unsubscribe
-fn generate_unsubscribe(params: Vec<String>, client_id: String) -> Command {
+fn generate_unsubscribe(params: Vec<Vec<u8>>, client_id: String) -> Command {
     Command::Unsubscribe {
-        channels: params,
+        channels: to_texts(&params),
+        client_id,
+    }
+}
+
+/// Generador de comando Command::Psubscribe
+fn generate_psubscribe(params: Vec<Vec<u8>>, client_id: String) -> Result<Command, String> {
+    if params.is_empty() {
+        return Err("ERR wrong number of arguments for 'psubscribe' command".to_string());
+    }
+
+    Ok(Command::Psubscribe {
+        patterns: to_texts(&params),
+        client_id,
+    })
+}
+
+/// Generador de comando Command::Punsubscribe
+fn generate_punsubscribe(params: Vec<Vec<u8>>, client_id: String) -> Command {
+    Command::Punsubscribe {
+        patterns: to_texts(&params),
+        client_id,
+    }
+}
+
+/// Generador de comando Command::Multi
+fn generate_multi(params: Vec<Vec<u8>>) -> Result<Command, String> {
+    if !params.is_empty() {
+        return Err("ERR wrong number of arguments for 'multi' command".to_string());
+    }
+
+    Ok(Command::Multi)
+}
+
+/// Generador de comando Command::Exec. Los comandos encolados los agrega el cliente.
+fn generate_exec(params: Vec<Vec<u8>>, client_id: String) -> Result<Command, String> {
+    if !params.is_empty() {
+        return Err("ERR wrong number of arguments for 'exec' command".to_string());
+    }
+
+    Ok(Command::Exec {
+        commands: Some(vec![]),
+        client_id,
+    })
+}
+
+/// Generador de comando Command::Discard
+fn generate_discard(params: Vec<Vec<u8>>, client_id: String) -> Result<Command, String> {
+    if !params.is_empty() {
+        return Err("ERR wrong number of arguments for 'discard' command".to_string());
+    }
+
+    Ok(Command::Discard { client_id })
+}
+
+/// Generador de comando Command::Watch
+fn generate_watch(params: Vec<Vec<u8>>, client_id: String) -> Result<Command, String> {
+    if params.is_empty() {
+        return Err("ERR wrong number of arguments for 'watch' command".to_string());
+    }
+
+    Ok(Command::Watch {
+        keys: params,
         client_id,
+    })
+}
+
+/// Generador de comando Command::Unwatch
+fn generate_unwatch(params: Vec<Vec<u8>>, client_id: String) -> Result<Command, String> {
+    if !params.is_empty() {
+        return Err("ERR wrong number of arguments for 'unwatch' command".to_string());
     }
+
+    Ok(Command::Unwatch { client_id })
 }
 
-#[allow(unused_imports)]
+#[allow(unused_imports, clippy::match_like_matches_macro, clippy::useless_vec)]
 mod test {
+    use crate::entities::cluster_param::{ClusterParam, SlotState};
     use crate::entities::command::Command;
+    use crate::entities::pubsub_param::PubSubParam;
+    use crate::entities::set_param::{SetCondition, SetExpiration, SetParam};
+    use crate::entities::sorted_set::{LexBound, ScoreBound};
+    use crate::entities::zrange_param::{AggregateParam, ZRangeParam};
     use crate::service::command_generator::generate;
     use core::time::Duration;
     use std::collections::HashSet;
@@ -773,7 +2513,7 @@ mod test {
 
     #[test]
     fn generate_command_with_command_invalid_err() {
-        let params = vec!["metodo".to_string()];
+        let params = vec!["metodo".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err())
@@ -781,7 +2521,7 @@ mod test {
 
     #[test]
     fn generate_command_with_command_ping() {
-        let params = vec!["ping".to_string()];
+        let params = vec!["ping".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_ok());
@@ -793,7 +2533,7 @@ mod test {
 
     #[test]
     fn generate_command_with_command_monitor() {
-        let params = vec!["monitor".to_string()];
+        let params = vec!["monitor".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_ok());
@@ -805,7 +2545,7 @@ mod test {
 
     #[test]
     fn generate_command_with_command_flushdb() {
-        let params = vec!["flushdb".to_string()];
+        let params = vec!["flushdb".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_ok());
@@ -817,7 +2557,7 @@ mod test {
 
     #[test]
     fn generate_command_copy_without_params_err() {
-        let params = vec!["copy".to_string()];
+        let params = vec!["copy".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err())
@@ -825,7 +2565,7 @@ mod test {
 
     #[test]
     fn generate_command_copy_with_one_param_err() {
-        let params = vec!["copy".to_string(), "key".to_string()];
+        let params = vec!["copy".into(), "key".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err())
@@ -833,7 +2573,7 @@ mod test {
 
     #[test]
     fn generate_command_copy_ok() {
-        let params = vec!["copy".to_string(), "key".to_string(), "key1".to_string()];
+        let params = vec!["copy".into(), "key".into(), "key1".into()];
         let result = generate(params, "client-test".to_string());
 
         let _key = "key".to_string();
@@ -850,7 +2590,7 @@ mod test {
 
     #[test]
     fn generate_command_get_without_param_err() {
-        let params = vec!["get".to_string()];
+        let params = vec!["get".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err())
@@ -858,7 +2598,7 @@ mod test {
 
     #[test]
     fn generate_command_get_ok() {
-        let params = vec!["get".to_string(), "key".to_string()];
+        let params = vec!["get".into(), "key".into()];
         let result = generate(params, "client-test".to_string());
 
         let _key = "key".to_string();
@@ -871,7 +2611,7 @@ mod test {
 
     #[test]
     fn generate_command_getset_without_param_err() {
-        let params = vec!["getset".to_string()];
+        let params = vec!["getset".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err())
@@ -879,7 +2619,7 @@ mod test {
 
     #[test]
     fn generate_command_getset_with_one_param_err() {
-        let params = vec!["getset".to_string(), "key".to_string()];
+        let params = vec!["getset".into(), "key".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err())
@@ -887,7 +2627,7 @@ mod test {
 
     #[test]
     fn generate_command_getset_ok() {
-        let params = vec!["getset".to_string(), "key".to_string(), "value".to_string()];
+        let params = vec!["getset".into(), "key".into(), "value".into()];
         let result = generate(params, "client-test".to_string());
 
         let _key = "key".to_string();
@@ -904,7 +2644,7 @@ mod test {
 
     #[test]
     fn generate_command_set_without_param_err() {
-        let params = vec!["set".to_string()];
+        let params = vec!["set".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err())
@@ -912,7 +2652,7 @@ mod test {
 
     #[test]
     fn generate_command_set_with_one_param_err() {
-        let params = vec!["set".to_string(), "key".to_string()];
+        let params = vec!["set".into(), "key".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err())
@@ -920,7 +2660,7 @@ mod test {
 
     #[test]
     fn generate_command_set_ok() {
-        let params = vec!["set".to_string(), "key".to_string(), "value".to_string()];
+        let params = vec!["set".into(), "key".into(), "value".into()];
         let result = generate(params, "client-test".to_string());
 
         let _key = "key".to_string();
@@ -930,14 +2670,91 @@ mod test {
             Command::Set {
                 key: _key,
                 value: _value,
+                ..
             } => true,
             _ => false,
         });
     }
 
+    #[test]
+    fn generate_command_set_with_options_ok() {
+        let set = generate_from(&["set", "key", "value", "NX", "px", "1500", "get"]);
+        let pxat = generate_from(&["set", "key", "value", "xx", "pxat", "1000"]);
+        let keepttl = generate_from(&["set", "key", "value", "keepttl"]);
+
+        assert!(matches!(
+            set.unwrap(),
+            Command::Set { key, value, param } if key == b"key" && value == b"value"
+                && param == SetParam {
+                    condition: Some(SetCondition::Nx),
+                    expiration: Some(SetExpiration::Relative(Duration::from_millis(1500))),
+                    keep_ttl: false,
+                    get: true,
+                }
+        ));
+        assert!(matches!(
+            pxat.unwrap(),
+            Command::Set { param, .. } if param.condition == Some(SetCondition::Xx)
+                && param.expires_at() == Some(SystemTime::UNIX_EPOCH + Duration::from_secs(1))
+        ));
+        assert!(matches!(keepttl.unwrap(), Command::Set { param, .. } if param.keep_ttl));
+    }
+
+    #[test]
+    fn generate_command_set_with_invalid_options_err() {
+        let conditions = generate_from(&["set", "key", "value", "nx", "xx"]);
+        let expirations = generate_from(&["set", "key", "value", "ex", "1", "px", "1"]);
+        let keepttl = generate_from(&["set", "key", "value", "ex", "1", "keepttl"]);
+        let missing_ttl = generate_from(&["set", "key", "value", "ex"]);
+        let not_integer = generate_from(&["set", "key", "value", "ex", "a"]);
+        let negative = generate_from(&["set", "key", "value", "ex", "-1"]);
+        let unknown = generate_from(&["set", "key", "value", "foo"]);
+
+        assert_eq!(conditions.unwrap_err(), "ERR syntax error");
+        assert_eq!(expirations.unwrap_err(), "ERR syntax error");
+        assert_eq!(keepttl.unwrap_err(), "ERR syntax error");
+        assert_eq!(missing_ttl.unwrap_err(), "ERR syntax error");
+        assert!(not_integer.is_err());
+        assert_eq!(
+            negative.unwrap_err(),
+            "ERR invalid expire time in 'set' command"
+        );
+        assert_eq!(unknown.unwrap_err(), "ERR syntax error");
+    }
+
+    #[test]
+    fn generate_command_legacy_set_variants_ok() {
+        let setnx = generate_from(&["setnx", "key", "value"]);
+        let setex = generate_from(&["setex", "key", "10", "value"]);
+        let psetex = generate_from(&["psetex", "key", "100", "value"]);
+        let msetnx = generate_from(&["msetnx", "a", "1", "b", "2"]);
+
+        assert!(
+            matches!(setnx.unwrap(), Command::Setnx { key, value } if key == b"key" && value == b"value")
+        );
+        assert!(matches!(
+            setex.unwrap(),
+            Command::Set { value, param, .. } if value == b"value"
+                && param.expiration == Some(SetExpiration::Relative(Duration::from_secs(10)))
+        ));
+        assert!(matches!(
+            psetex.unwrap(),
+            Command::Set { param, .. }
+                if param.expiration == Some(SetExpiration::Relative(Duration::from_millis(100)))
+        ));
+        assert!(matches!(msetnx.unwrap(), Command::Msetnx { key_values } if key_values.len() == 2));
+        assert_eq!(
+            generate_from(&["setex", "key", "0", "value"]).unwrap_err(),
+            "ERR invalid expire time in 'setex' command"
+        );
+        assert!(generate_from(&["psetex", "key", "100"]).is_err());
+        assert!(generate_from(&["setnx", "key"]).is_err());
+        assert!(generate_from(&["msetnx", "a", "1", "b"]).is_err());
+    }
+
     #[test]
     fn generate_command_del_without_param_err() {
-        let params = vec!["del".to_string()];
+        let params = vec!["del".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err())
@@ -945,7 +2762,7 @@ mod test {
 
     #[test]
     fn generate_command_del_ok() {
-        let params = vec!["del".to_string(), "key".to_string()];
+        let params = vec!["del".into(), "key".into()];
         let result = generate(params, "client-test".to_string());
 
         let _keys = vec!["key".to_string()];
@@ -958,7 +2775,7 @@ mod test {
 
     #[test]
     fn generate_command_mget_without_param_err() {
-        let params = vec!["mget".to_string()];
+        let params = vec!["mget".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err())
@@ -966,7 +2783,7 @@ mod test {
 
     #[test]
     fn generate_command_mget_ok() {
-        let params = vec!["mget".to_string(), "key1".to_string(), "key2".to_string()];
+        let params = vec!["mget".into(), "key1".into(), "key2".into()];
         let result = generate(params, "client-test".to_string());
 
         let _keys = vec!["key1".to_string(), "key2".to_string()];
@@ -979,7 +2796,7 @@ mod test {
 
     #[test]
     fn generate_command_mset_without_param_err() {
-        let params = vec!["mset".to_string()];
+        let params = vec!["mset".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err())
@@ -987,12 +2804,7 @@ mod test {
 
     #[test]
     fn generate_command_mset_with_missing_value_err() {
-        let params = vec![
-            "mset".to_string(),
-            "key1".to_string(),
-            "value1".to_string(),
-            "key2".to_string(),
-        ];
+        let params = vec!["mset".into(), "key1".into(), "value1".into(), "key2".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err())
@@ -1001,11 +2813,11 @@ mod test {
     #[test]
     fn generate_command_mset_ok() {
         let params = vec![
-            "mset".to_string(),
-            "key1".to_string(),
-            "value1".to_string(),
-            "key2".to_string(),
-            "value2".to_string(),
+            "mset".into(),
+            "key1".into(),
+            "value1".into(),
+            "key2".into(),
+            "value2".into(),
         ];
         let result = generate(params, "client-test".to_string());
 
@@ -1022,7 +2834,7 @@ mod test {
 
     #[test]
     fn generate_command_strlen_without_param_err() {
-        let params = vec!["strlen".to_string()];
+        let params = vec!["strlen".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err())
@@ -1030,7 +2842,7 @@ mod test {
 
     #[test]
     fn generate_command_strlen_ok() {
-        let params = vec!["strlen".to_string(), "key".to_string()];
+        let params = vec!["strlen".into(), "key".into()];
         let result = generate(params, "client-test".to_string());
 
         let _key = "key".to_string();
@@ -1043,7 +2855,7 @@ mod test {
 
     #[test]
     fn generate_command_exists_without_param_err() {
-        let params = vec!["exists".to_string()];
+        let params = vec!["exists".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err())
@@ -1051,7 +2863,7 @@ mod test {
 
     #[test]
     fn generate_command_exists_ok() {
-        let params = vec!["exists".to_string(), "key".to_string()];
+        let params = vec!["exists".into(), "key".into()];
         let result = generate(params, "client-test".to_string());
 
         let _keys = vec!["key".to_string()];
@@ -1062,7 +2874,7 @@ mod test {
             _ => false,
         });
 
-        let params = vec!["exists".to_string(), "key".to_string()];
+        let params = vec!["exists".into(), "key".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(match result.unwrap() {
@@ -1073,7 +2885,7 @@ mod test {
 
     #[test]
     fn generate_command_rename_without_param_err() {
-        let params = vec!["rename".to_string()];
+        let params = vec!["rename".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err())
@@ -1081,7 +2893,7 @@ mod test {
 
     #[test]
     fn generate_command_rename_ok() {
-        let params = vec!["rename".to_string(), "key1".to_string(), "key2".to_string()];
+        let params = vec!["rename".into(), "key1".into(), "key2".into()];
         let result = generate(params, "client-test".to_string());
 
         let _key_origin = "key1".to_string();
@@ -1100,7 +2912,7 @@ mod test {
 
     #[test]
     fn generate_command_expire_without_param_err() {
-        let params = vec!["expire".to_string()];
+        let params = vec!["expire".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err());
@@ -1108,7 +2920,7 @@ mod test {
 
     #[test]
     fn generate_command_expire_with_fractional_time_err() {
-        let params = vec!["expire".to_string(), "key".to_string(), "10.5".to_string()];
+        let params = vec!["expire".into(), "key".into(), "10.5".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err());
@@ -1116,7 +2928,7 @@ mod test {
 
     #[test]
     fn generate_command_expire_ok() {
-        let params = vec!["expire".to_string(), "key".to_string(), "1".to_string()];
+        let params = vec!["expire".into(), "key".into(), "1".into()];
         let result = generate(params, "client-test".to_string());
 
         let _key = "key".to_string();
@@ -1135,7 +2947,7 @@ mod test {
 
     #[test]
     fn generate_command_expireat_without_param_err() {
-        let params = vec!["expireat".to_string()];
+        let params = vec!["expireat".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err());
@@ -1143,11 +2955,7 @@ mod test {
 
     #[test]
     fn generate_command_expireat_with_fractional_time_err() {
-        let params = vec![
-            "expireat".to_string(),
-            "key".to_string(),
-            "10.5".to_string(),
-        ];
+        let params = vec!["expireat".into(), "key".into(), "10.5".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err());
@@ -1155,26 +2963,117 @@ mod test {
 
     #[test]
     fn generate_command_expireat_ok() {
-        let params = vec!["expireat".to_string(), "key".to_string(), "1".to_string()];
+        let params = vec!["expireat".into(), "key".into(), "1".into()];
         let result = generate(params, "client-test".to_string());
 
         let _key = "key".to_string();
         let _ttl = SystemTime::UNIX_EPOCH + Duration::from_secs(1);
 
-        assert!(result.is_ok());
+        assert!(result.is_ok());
+
+        assert!(match result.unwrap() {
+            Command::Expireat {
+                key: _key,
+                ttl: _ttl,
+            } => true,
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn generate_command_pexpireat_ok() {
+        let params = vec!["pexpireat".into(), "key".into(), "1500".into()];
+        let result = generate(params, "client-test".to_string());
+
+        let ttl = SystemTime::UNIX_EPOCH + Duration::from_millis(1500);
+        assert!(matches!(
+            result,
+            Ok(Command::Expireat { key, ttl: command_ttl }) if key == b"key" && command_ttl == ttl
+        ));
+    }
+
+    #[test]
+    fn generate_command_bgrewriteaof_ok() {
+        let params = vec!["bgrewriteaof".into()];
+        let result = generate(params, "client-test".to_string());
+
+        assert!(matches!(result, Ok(Command::Bgrewriteaof)));
+    }
+
+    #[test]
+    fn generate_command_replicaof_ok() {
+        let replicaof = generate(
+            vec!["replicaof".into(), "localhost".into(), "6380".into()],
+            "client-test".to_string(),
+        );
+        let no_one = generate(
+            vec!["REPLICAOF".into(), "NO".into(), "ONE".into()],
+            "client-test".to_string(),
+        );
+        let wrong_port = generate(
+            vec!["replicaof".into(), "localhost".into(), "port".into()],
+            "client-test".to_string(),
+        );
+
+        assert!(matches!(
+            replicaof,
+            Ok(Command::Replicaof { master: Some((host, 6380)) }) if host == "localhost"
+        ));
+        assert!(matches!(no_one, Ok(Command::Replicaof { master: None })));
+        assert!(wrong_port.is_err());
+    }
+
+    #[test]
+    fn generate_command_replconf_and_psync_ok() {
+        let replconf = generate(
+            vec!["replconf".into(), "listening-port".into(), "6380".into()],
+            "client-test".to_string(),
+        );
+        let capa = generate(
+            vec!["replconf".into(), "capa".into(), "psync2".into()],
+            "client-test".to_string(),
+        );
+        let psync = generate(
+            vec!["psync".into(), "?".into(), "-1".into()],
+            "client-test".to_string(),
+        );
+
+        assert!(matches!(
+            replconf,
+            Ok(Command::Replconf {
+                listening_port: Some(6380),
+                ..
+            })
+        ));
+        assert!(matches!(
+            capa,
+            Ok(Command::Replconf {
+                listening_port: None,
+                ..
+            })
+        ));
+        assert!(matches!(
+            psync,
+            Ok(Command::Psync { replid, offset: -1, .. }) if replid == "?"
+        ));
+    }
+
+    #[test]
+    fn generate_command_save_bgsave_lastsave_ok() {
+        let save = generate(vec!["save".into()], "client-test".to_string());
+        let bgsave = generate(vec!["BGSAVE".into()], "client-test".to_string());
+        let lastsave = generate(vec!["lastsave".into()], "client-test".to_string());
+        let wrong = generate(vec!["bgsave".into(), "x".into()], "client-test".to_string());
 
-        assert!(match result.unwrap() {
-            Command::Expireat {
-                key: _key,
-                ttl: _ttl,
-            } => true,
-            _ => false,
-        });
+        assert!(matches!(save, Ok(Command::Save)));
+        assert!(matches!(bgsave, Ok(Command::Bgsave)));
+        assert!(matches!(lastsave, Ok(Command::Lastsave)));
+        assert!(wrong.is_err());
     }
 
     #[test]
     fn generate_command_persist_without_param_err() {
-        let params = vec!["persist".to_string()];
+        let params = vec!["persist".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err())
@@ -1182,7 +3081,7 @@ mod test {
 
     #[test]
     fn generate_command_persist_ok() {
-        let params = vec!["persist".to_string(), "key".to_string()];
+        let params = vec!["persist".into(), "key".into()];
         let result = generate(params, "client-test".to_string());
 
         let _key = "key".to_string();
@@ -1194,9 +3093,54 @@ mod test {
         });
     }
 
+    #[test]
+    fn generate_command_select_and_swapdb() {
+        let select = generate(vec!["select".into(), "3".into()], "client-test".to_string());
+        let negative = generate(
+            vec!["select".into(), "-1".into()],
+            "client-test".to_string(),
+        );
+        let not_integer = generate(vec!["select".into(), "a".into()], "client-test".to_string());
+        let swapdb = generate(
+            vec!["swapdb".into(), "0".into(), "1".into()],
+            "client-test".to_string(),
+        );
+        let invalid_second = generate(
+            vec!["swapdb".into(), "0".into(), "-1".into()],
+            "client-test".to_string(),
+        );
+
+        assert!(matches!(select, Ok(Command::Select { index: 3 })));
+        assert_eq!(negative.unwrap_err(), "ERR DB index is out of range");
+        assert!(not_integer.is_err());
+        assert!(matches!(
+            swapdb,
+            Ok(Command::Swapdb {
+                index1: 0,
+                index2: 1
+            })
+        ));
+        assert_eq!(invalid_second.unwrap_err(), "ERR invalid second DB index");
+    }
+
+    #[test]
+    fn generate_command_move_ok() {
+        let result = generate(
+            vec!["move".into(), "key".into(), "2".into()],
+            "client-test".to_string(),
+        );
+        let missing_db = generate(vec!["move".into(), "key".into()], "client-test".to_string());
+
+        assert!(matches!(
+            result,
+            Ok(Command::Move { key, db: 2 }) if key == b"key".to_vec()
+        ));
+        assert!(missing_db.is_err());
+    }
+
     #[test]
     fn generate_command_sort_without_param_err() {
-        let params = vec!["sort".to_string()];
+        let params = vec!["sort".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err())
@@ -1204,21 +3148,98 @@ mod test {
 
     #[test]
     fn generate_command_sort_ok() {
-        let params = vec!["sort".to_string(), "key".to_string()];
+        let params = vec!["sort".into(), "key".into()];
         let result = generate(params, "client-test".to_string());
 
         let _key = "key".to_string();
         assert!(result.is_ok());
 
         assert!(match result.unwrap() {
-            Command::Sort { key: _key } => true,
+            Command::Sort { key: _key, .. } => true,
             _ => false,
         });
     }
 
+    #[test]
+    fn generate_command_sort_with_options_ok() {
+        let params = vec![
+            "sort".into(),
+            "key".into(),
+            "BY".into(),
+            "weight_*".into(),
+            "limit".into(),
+            "1".into(),
+            "2".into(),
+            "GET".into(),
+            "#".into(),
+            "GET".into(),
+            "name_*".into(),
+            "DESC".into(),
+            "ALPHA".into(),
+            "STORE".into(),
+            "dest".into(),
+        ];
+        let result = generate(params, "client-test".to_string());
+
+        match result.unwrap() {
+            Command::Sort { key, param } => {
+                assert_eq!(key, b"key".to_vec());
+                assert_eq!(param.by, Some(b"weight_*".to_vec()));
+                assert_eq!(param.limit, Some((1, 2)));
+                assert_eq!(param.get, vec![b"#".to_vec(), b"name_*".to_vec()]);
+                assert!(param.desc);
+                assert!(param.alpha);
+                assert_eq!(param.store, Some(b"dest".to_vec()));
+            }
+            _ => panic!("expected sort command"),
+        }
+    }
+
+    #[test]
+    fn generate_command_sort_with_invalid_options_err() {
+        let missing_pattern = generate(
+            vec!["sort".into(), "key".into(), "by".into()],
+            "client-test".to_string(),
+        );
+        let invalid_limit = generate(
+            vec![
+                "sort".into(),
+                "key".into(),
+                "limit".into(),
+                "a".into(),
+                "1".into(),
+            ],
+            "client-test".to_string(),
+        );
+        let read_only_store = generate(
+            vec![
+                "sort_ro".into(),
+                "key".into(),
+                "store".into(),
+                "dest".into(),
+            ],
+            "client-test".to_string(),
+        );
+
+        assert_eq!(missing_pattern.unwrap_err(), "ERR syntax error");
+        assert_eq!(
+            invalid_limit.unwrap_err(),
+            "ERR value is not an integer or out of range"
+        );
+        assert_eq!(read_only_store.unwrap_err(), "ERR syntax error");
+        assert!(matches!(
+            generate(
+                vec!["sort_ro".into(), "key".into(), "desc".into()],
+                "client-test".to_string()
+            )
+            .unwrap(),
+            Command::SortRo { .. }
+        ));
+    }
+
     #[test]
     fn generate_command_touch_without_param_err() {
-        let params = vec!["touch".to_string()];
+        let params = vec!["touch".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err())
@@ -1226,7 +3247,7 @@ mod test {
 
     #[test]
     fn generate_command_touch_ok() {
-        let params = vec!["touch".to_string(), "key1".to_string(), "key2".to_string()];
+        let params = vec!["touch".into(), "key1".into(), "key2".into()];
         let result = generate(params, "client-test".to_string());
 
         let _keys = vec!["key1".to_string(), "key2".to_string()];
@@ -1240,7 +3261,7 @@ mod test {
 
     #[test]
     fn generate_command_ttl_without_param_err() {
-        let params = vec!["ttl".to_string()];
+        let params = vec!["ttl".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err())
@@ -1248,7 +3269,7 @@ mod test {
 
     #[test]
     fn generate_command_ttl_ok() {
-        let params = vec!["ttl".to_string(), "key".to_string()];
+        let params = vec!["ttl".into(), "key".into()];
         let result = generate(params, "client-test".to_string());
 
         let _key = "key".to_string();
@@ -1262,7 +3283,7 @@ mod test {
 
     #[test]
     fn generate_command_type_without_param_err() {
-        let params = vec!["type".to_string()];
+        let params = vec!["type".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err())
@@ -1270,7 +3291,7 @@ mod test {
 
     #[test]
     fn generate_command_type_ok() {
-        let params = vec!["type".to_string(), "key".to_string()];
+        let params = vec!["type".into(), "key".into()];
         let result = generate(params, "client-test".to_string());
 
         let _key = "key".to_string();
@@ -1285,12 +3306,12 @@ mod test {
 
     #[test]
     fn generate_command_incrby_without_param_err() {
-        let params = vec!["incrby".to_string()];
+        let params = vec!["incrby".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err());
 
-        let params = vec!["incrby".to_string(), "key".to_string(), "hola".to_string()];
+        let params = vec!["incrby".into(), "key".into(), "hola".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err())
@@ -1298,7 +3319,7 @@ mod test {
 
     #[test]
     fn generate_command_incrby_ok() {
-        let params = vec!["incrby".to_string(), "key1".to_string(), "1".to_string()];
+        let params = vec!["incrby".into(), "key1".into(), "1".into()];
         let result = generate(params, "client-test".to_string());
 
         let _key = "key1".to_string();
@@ -1314,14 +3335,63 @@ mod test {
         });
     }
 
+    #[test]
+    fn generate_command_incr_and_decr_ok() {
+        let incr = generate_from(&["incr", "key"]);
+        let decr = generate_from(&["DECR", "key"]);
+        let negative = generate_from(&["incrby", "key", "-5"]);
+        let big = generate_from(&["decrby", "key", "9223372036854775807"]);
+
+        assert!(matches!(incr.unwrap(), Command::Incrby { key, increment: 1 } if key == b"key"));
+        assert!(matches!(
+            decr.unwrap(),
+            Command::Decrby { decrement: 1, .. }
+        ));
+        assert!(matches!(
+            negative.unwrap(),
+            Command::Incrby { increment: -5, .. }
+        ));
+        assert!(matches!(
+            big.unwrap(),
+            Command::Decrby {
+                decrement: i64::MAX,
+                ..
+            }
+        ));
+        assert!(generate_from(&["incr"]).is_err());
+        assert!(generate_from(&["decr", "key", "1"]).is_err());
+        assert!(generate_from(&["incrby", "key", "9223372036854775808"]).is_err());
+    }
+
+    #[test]
+    fn generate_command_incrbyfloat_ok() {
+        let incrbyfloat = generate_from(&["incrbyfloat", "key", "5.0e3"]);
+        let hincrbyfloat = generate_from(&["hincrbyfloat", "key", "field", "-0.5"]);
+
+        assert!(matches!(
+            incrbyfloat.unwrap(),
+            Command::Incrbyfloat { increment, .. } if increment == 5000.0
+        ));
+        assert!(matches!(
+            hincrbyfloat.unwrap(),
+            Command::Hincrbyfloat { field, increment, .. } if field == b"field" && increment == -0.5
+        ));
+        assert_eq!(
+            generate_from(&["incrbyfloat", "key", "one"]).unwrap_err(),
+            "ERR value is not a valid float"
+        );
+        assert!(generate_from(&["incrbyfloat", "key", "nan"]).is_err());
+        assert!(generate_from(&["hincrbyfloat", "key", "field"]).is_err());
+    }
+
     #[test]
     fn generate_command_decrby_without_param_err() {
-        let params = vec!["decrby".to_string()];
+        let params = vec!["decrby".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err());
 
-        let params = vec!["decrby".to_string(), "key".to_string(), "hola".to_string()];
+        let params = vec!["decrby".into(), "key".into(), "hola".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err())
@@ -1329,7 +3399,7 @@ mod test {
 
     #[test]
     fn generate_command_decrby_ok() {
-        let params = vec!["decrby".to_string(), "key1".to_string(), "1".to_string()];
+        let params = vec!["decrby".into(), "key1".into(), "1".into()];
         let result = generate(params, "client-test".to_string());
 
         let _key = "key1".to_string();
@@ -1347,7 +3417,7 @@ mod test {
 
     #[test]
     fn generate_command_getdel_without_param_err() {
-        let params = vec!["getdel".to_string()];
+        let params = vec!["getdel".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err())
@@ -1355,7 +3425,7 @@ mod test {
 
     #[test]
     fn generate_command_getdel_ok() {
-        let params = vec!["getdel".to_string(), "key".to_string()];
+        let params = vec!["getdel".into(), "key".into()];
         let result = generate(params, "client-test".to_string());
 
         let _key = "key".to_string();
@@ -1365,7 +3435,7 @@ mod test {
             _ => false,
         });
 
-        let params = vec!["getdel".to_string(), "key".to_string()];
+        let params = vec!["getdel".into(), "key".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(match result.unwrap() {
@@ -1376,7 +3446,7 @@ mod test {
 
     #[test]
     fn generate_command_append_without_param_err() {
-        let params = vec!["append".to_string()];
+        let params = vec!["append".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err())
@@ -1384,7 +3454,7 @@ mod test {
 
     #[test]
     fn generate_command_append_ok() {
-        let params = vec!["append".to_string(), "key".to_string(), "Value".to_string()];
+        let params = vec!["append".into(), "key".into(), "Value".into()];
         let result = generate(params, "client-test".to_string());
 
         let _key = "key".to_string();
@@ -1399,7 +3469,7 @@ mod test {
             _ => false,
         });
 
-        let params = vec!["append".to_string(), "key".to_string(), "Value".to_string()];
+        let params = vec!["append".into(), "key".into(), "Value".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(match result.unwrap() {
@@ -1410,7 +3480,7 @@ mod test {
 
     #[test]
     fn generate_command_with_command_dbsize() {
-        let params = vec!["dbsize".to_string()];
+        let params = vec!["dbsize".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_ok());
@@ -1420,29 +3490,128 @@ mod test {
         });
     }
 
+    #[test]
+    fn generate_command_blpop_ok() {
+        let params = vec!["blpop".into(), "a".into(), "b".into(), "0.5".into()];
+        let result = generate(params, "client-test".to_string());
+
+        match result.unwrap() {
+            Command::Blpop {
+                keys,
+                timeout,
+                client_id,
+            } => {
+                assert_eq!(keys, vec![b"a".to_vec(), b"b".to_vec()]);
+                assert_eq!(timeout, Some(Duration::from_millis(500)));
+                assert_eq!(client_id, "client-test");
+            }
+            _ => panic!("expected blpop command"),
+        }
+        assert!(matches!(
+            generate(
+                vec!["brpop".into(), "a".into(), "0".into()],
+                "client-test".to_string()
+            )
+            .unwrap(),
+            Command::Brpop { timeout: None, .. }
+        ));
+    }
+
+    #[test]
+    fn generate_command_blpop_invalid_timeout_err() {
+        let negative = generate(
+            vec!["blpop".into(), "a".into(), "-1".into()],
+            "client-test".to_string(),
+        );
+        let not_float = generate(
+            vec!["brpop".into(), "a".into(), "soon".into()],
+            "client-test".to_string(),
+        );
+        let without_keys = generate(vec!["blpop".into(), "1".into()], "client-test".to_string());
+
+        assert_eq!(negative.unwrap_err(), "ERR timeout is negative");
+        assert_eq!(
+            not_float.unwrap_err(),
+            "ERR timeout is not a float or out of range"
+        );
+        assert_eq!(
+            without_keys.unwrap_err(),
+            "ERR wrong number of arguments for 'blpop' command"
+        );
+    }
+
+    #[test]
+    fn generate_command_lmove_and_blmove_ok() {
+        let lmove = generate(
+            vec![
+                "lmove".into(),
+                "src".into(),
+                "dst".into(),
+                "LEFT".into(),
+                "right".into(),
+            ],
+            "client-test".to_string(),
+        );
+        let blmove = generate(
+            vec![
+                "blmove".into(),
+                "src".into(),
+                "dst".into(),
+                "right".into(),
+                "left".into(),
+                "2".into(),
+            ],
+            "client-test".to_string(),
+        );
+        let invalid_side = generate(
+            vec![
+                "lmove".into(),
+                "src".into(),
+                "dst".into(),
+                "up".into(),
+                "left".into(),
+            ],
+            "client-test".to_string(),
+        );
+
+        assert!(matches!(
+            lmove.unwrap(),
+            Command::Lmove {
+                from_left: true,
+                to_left: false,
+                ..
+            }
+        ));
+        assert!(matches!(
+            blmove.unwrap(),
+            Command::Blmove {
+                from_left: false,
+                to_left: true,
+                timeout: Some(_),
+                ..
+            }
+        ));
+        assert_eq!(invalid_side.unwrap_err(), "ERR syntax error");
+    }
+
     #[test]
     fn generate_command_lindex_incorrect_params_err() {
-        let params = vec!["lindex".to_string()];
+        let params = vec!["lindex".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err());
 
-        let params = vec!["lindex".to_string(), "key".to_string()];
+        let params = vec!["lindex".into(), "key".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err());
 
-        let params = vec!["lindex".to_string(), "key".to_string(), "key".to_string()];
+        let params = vec!["lindex".into(), "key".into(), "key".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err());
 
-        let params = vec![
-            "lindex".to_string(),
-            "key".to_string(),
-            "1".to_string(),
-            "value".to_string(),
-        ];
+        let params = vec!["lindex".into(), "key".into(), "1".into(), "value".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err());
@@ -1450,7 +3619,7 @@ mod test {
 
     #[test]
     fn generate_command_lindex_ok() {
-        let params = vec!["lindex".to_string(), "key".to_string(), "1".to_string()];
+        let params = vec!["lindex".into(), "key".into(), "1".into()];
         let result = generate(params, "client-test".to_string());
 
         let _key = "key".to_string();
@@ -1464,7 +3633,7 @@ mod test {
             _ => false,
         });
 
-        let params = vec!["lindex".to_string(), "key".to_string(), "-1".to_string()];
+        let params = vec!["lindex".into(), "key".into(), "-1".into()];
         let result = generate(params, "client-test".to_string());
 
         let _key = "key".to_string();
@@ -1481,7 +3650,7 @@ mod test {
 
     #[test]
     fn generate_command_llen_without_param_err() {
-        let params = vec!["llen".to_string()];
+        let params = vec!["llen".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err())
@@ -1489,7 +3658,7 @@ mod test {
 
     #[test]
     fn generate_command_llen_ok() {
-        let params = vec!["llen".to_string(), "key".to_string()];
+        let params = vec!["llen".into(), "key".into()];
         let result = generate(params, "client-test".to_string());
 
         let _key = "key".to_string();
@@ -1502,7 +3671,7 @@ mod test {
 
     #[test]
     fn generate_command_lpop_without_param_err() {
-        let params = vec!["lpop".to_string()];
+        let params = vec!["lpop".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err())
@@ -1510,7 +3679,7 @@ mod test {
 
     #[test]
     fn generate_command_lpop_without_param_count_not_u32_err() {
-        let params = vec!["lpop".to_string(), "key".to_string(), "value".to_string()];
+        let params = vec!["lpop".into(), "key".into(), "value".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err())
@@ -1518,7 +3687,7 @@ mod test {
 
     #[test]
     fn generate_command_lpop_ok() {
-        let params = vec!["lpop".to_string(), "key".to_string()];
+        let params = vec!["lpop".into(), "key".into()];
         let result = generate(params, "client-test".to_string());
 
         let _key = "key".to_string();
@@ -1531,7 +3700,7 @@ mod test {
             _ => false,
         });
 
-        let params = vec!["lpop".to_string(), "key".to_string(), "3".to_string()];
+        let params = vec!["lpop".into(), "key".into(), "3".into()];
         let result = generate(params, "client-test".to_string());
 
         let _key = "key".to_string();
@@ -1547,37 +3716,27 @@ mod test {
 
     #[test]
     fn generate_command_lrange_bad_params_err() {
-        let params = vec!["lrange".to_string()];
+        let params = vec!["lrange".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err());
 
-        let params = vec![
-            "lrange".to_string(),
-            "key".to_string(),
-            "a".to_string(),
-            "1".to_string(),
-        ];
+        let params = vec!["lrange".into(), "key".into(), "a".into(), "1".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err());
 
-        let params = vec![
-            "lrange".to_string(),
-            "key".to_string(),
-            "1".to_string(),
-            "a".to_string(),
-        ];
+        let params = vec!["lrange".into(), "key".into(), "1".into(), "a".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err());
 
         let params = vec![
-            "lrange".to_string(),
-            "key".to_string(),
-            "1".to_string(),
-            "2".to_string(),
-            "3".to_string(),
+            "lrange".into(),
+            "key".into(),
+            "1".into(),
+            "2".into(),
+            "3".into(),
         ];
         let result = generate(params, "client-test".to_string());
         assert!(result.is_err())
@@ -1585,12 +3744,7 @@ mod test {
 
     #[test]
     fn generate_command_lrange_ok() {
-        let params = vec![
-            "lrange".to_string(),
-            "key".to_string(),
-            "0".to_string(),
-            "-1".to_string(),
-        ];
+        let params = vec!["lrange".into(), "key".into(), "0".into(), "-1".into()];
         let result = generate(params, "client-test".to_string());
 
         let _key = "key".to_string();
@@ -1608,27 +3762,22 @@ mod test {
     #[test]
     fn generate_command_lrem_err() {
         let params = vec![
-            "lrem".to_string(),
-            "key".to_string(),
-            "-1".to_string(),
-            "element".to_string(),
-            "element".to_string(),
+            "lrem".into(),
+            "key".into(),
+            "-1".into(),
+            "element".into(),
+            "element".into(),
         ];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err());
 
-        let params = vec!["lrem".to_string(), "key".to_string()];
+        let params = vec!["lrem".into(), "key".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err());
 
-        let params = vec![
-            "lrem".to_string(),
-            "key".to_string(),
-            "a".to_string(),
-            "element".to_string(),
-        ];
+        let params = vec!["lrem".into(), "key".into(), "a".into(), "element".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err());
@@ -1636,12 +3785,7 @@ mod test {
 
     #[test]
     fn generate_command_lrem_ok() {
-        let params = vec![
-            "lrem".to_string(),
-            "key".to_string(),
-            "0".to_string(),
-            "element".to_string(),
-        ];
+        let params = vec!["lrem".into(), "key".into(), "0".into(), "element".into()];
         let result = generate(params, "client-test".to_string());
 
         let _key = "key".to_string();
@@ -1658,30 +3802,115 @@ mod test {
         });
     }
 
+    #[allow(dead_code)]
+    fn generate_from(params: &[&str]) -> Result<Command, String> {
+        generate(
+            params
+                .iter()
+                .map(|param| param.as_bytes().to_vec())
+                .collect(),
+            "client-test".to_string(),
+        )
+    }
+
+    #[test]
+    fn generate_command_ltrim_and_linsert_ok() {
+        let ltrim = generate_from(&["ltrim", "key", "1", "-1"]);
+        let linsert = generate_from(&["linsert", "key", "AFTER", "pivot", "element"]);
+        let invalid_position = generate_from(&["linsert", "key", "middle", "pivot", "element"]);
+        let invalid_range = generate_from(&["ltrim", "key", "a", "-1"]);
+
+        assert!(matches!(
+            ltrim.unwrap(),
+            Command::Ltrim {
+                begin: 1,
+                end: -1,
+                ..
+            }
+        ));
+        assert!(matches!(
+            linsert.unwrap(),
+            Command::Linsert { before: false, .. }
+        ));
+        assert_eq!(invalid_position.unwrap_err(), "ERR syntax error");
+        assert_eq!(
+            invalid_range.unwrap_err(),
+            "ERR value is not an integer or out of range"
+        );
+    }
+
+    #[test]
+    fn generate_command_lpos_ok() {
+        let lpos = generate_from(&[
+            "lpos", "key", "a", "RANK", "-2", "COUNT", "0", "MAXLEN", "10",
+        ]);
+        let zero_rank = generate_from(&["lpos", "key", "a", "rank", "0"]);
+        let negative_count = generate_from(&["lpos", "key", "a", "count", "-1"]);
+        let missing_value = generate_from(&["lpos", "key", "a", "count"]);
+
+        assert!(matches!(
+            lpos.unwrap(),
+            Command::Lpos {
+                rank: -2,
+                count: Some(0),
+                maxlen: 10,
+                ..
+            }
+        ));
+        assert!(zero_rank.unwrap_err().starts_with("ERR RANK can't be zero"));
+        assert_eq!(negative_count.unwrap_err(), "ERR COUNT can't be negative");
+        assert!(missing_value.is_err());
+    }
+
+    #[test]
+    fn generate_command_lmpop_and_rpoplpush_ok() {
+        let lmpop = generate_from(&["lmpop", "2", "a", "b", "right", "count", "3"]);
+        let rpoplpush = generate_from(&["rpoplpush", "src", "dst"]);
+        let zero_keys = generate_from(&["lmpop", "0", "a", "left"]);
+        let missing_side = generate_from(&["lmpop", "2", "a", "b"]);
+
+        match lmpop.unwrap() {
+            Command::Lmpop { keys, left, count } => {
+                assert_eq!(keys, vec![b"a".to_vec(), b"b".to_vec()]);
+                assert!(!left);
+                assert_eq!(count, 3);
+            }
+            _ => panic!("lmpop should generate Command::Lmpop"),
+        }
+        assert!(matches!(
+            rpoplpush.unwrap(),
+            Command::Lmove {
+                from_left: false,
+                to_left: true,
+                ..
+            }
+        ));
+        assert_eq!(
+            zero_keys.unwrap_err(),
+            "ERR numkeys should be greater than 0"
+        );
+        assert_eq!(missing_side.unwrap_err(), "ERR syntax error");
+    }
+
     #[test]
     fn generate_command_lset_err() {
         let params = vec![
-            "lset".to_string(),
-            "key".to_string(),
-            "-1".to_string(),
-            "element".to_string(),
-            "element".to_string(),
+            "lset".into(),
+            "key".into(),
+            "-1".into(),
+            "element".into(),
+            "element".into(),
         ];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err());
 
-        let params = vec!["lset".to_string(), "key".to_string()];
+        let params = vec!["lset".into(), "key".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err());
 
-        let params = vec![
-            "lset".to_string(),
-            "key".to_string(),
-            "a".to_string(),
-            "element".to_string(),
-        ];
+        let params = vec!["lset".into(), "key".into(), "a".into(), "element".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err());
@@ -1689,12 +3918,7 @@ mod test {
 
     #[test]
     fn generate_command_lset_ok() {
-        let params = vec![
-            "lset".to_string(),
-            "key".to_string(),
-            "1".to_string(),
-            "Hola".to_string(),
-        ];
+        let params = vec!["lset".into(), "key".into(), "1".into(), "Hola".into()];
         let result = generate(params, "client-test".to_string());
 
         let _key = "key".to_string();
@@ -1713,7 +3937,7 @@ mod test {
 
     #[test]
     fn generate_command_rpop_without_param_err() {
-        let params = vec!["rpop".to_string()];
+        let params = vec!["rpop".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err())
@@ -1721,7 +3945,7 @@ mod test {
 
     #[test]
     fn generate_command_rpop_without_param_count_not_u32_err() {
-        let params = vec!["rpop".to_string(), "key".to_string(), "value".to_string()];
+        let params = vec!["rpop".into(), "key".into(), "value".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err())
@@ -1729,7 +3953,7 @@ mod test {
 
     #[test]
     fn generate_command_rpop_ok() {
-        let params = vec!["rpop".to_string(), "key".to_string()];
+        let params = vec!["rpop".into(), "key".into()];
         let result = generate(params, "client-test".to_string());
 
         let _key = "key".to_string();
@@ -1742,7 +3966,7 @@ mod test {
             _ => false,
         });
 
-        let params = vec!["rpop".to_string(), "key".to_string(), "3".to_string()];
+        let params = vec!["rpop".into(), "key".into(), "3".into()];
         let result = generate(params, "client-test".to_string());
 
         let _key = "key".to_string();
@@ -1758,12 +3982,12 @@ mod test {
 
     #[test]
     fn generate_command_lpush_incorrect_params_err() {
-        let params = vec!["lpush".to_string()];
+        let params = vec!["lpush".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err());
 
-        let params = vec!["lpush".to_string(), "key".to_string()];
+        let params = vec!["lpush".into(), "key".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err())
@@ -1771,7 +3995,7 @@ mod test {
 
     #[test]
     fn generate_command_lpush_ok() {
-        let params = vec!["lpush".to_string(), "key".to_string(), "value".to_string()];
+        let params = vec!["lpush".into(), "key".into(), "value".into()];
         let result = generate(params, "client-test".to_string());
 
         let _key = "key".to_string();
@@ -1788,12 +4012,12 @@ mod test {
 
     #[test]
     fn generate_command_lpushx_incorrect_params_err() {
-        let params = vec!["lpushx".to_string()];
+        let params = vec!["lpushx".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err());
 
-        let params = vec!["lpushx".to_string(), "key".to_string()];
+        let params = vec!["lpushx".into(), "key".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err())
@@ -1801,12 +4025,12 @@ mod test {
 
     #[test]
     fn generate_command_rpush_incorrect_params_err() {
-        let params = vec!["rpush".to_string()];
+        let params = vec!["rpush".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err());
 
-        let params = vec!["rpush".to_string(), "key".to_string()];
+        let params = vec!["rpush".into(), "key".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err())
@@ -1814,7 +4038,7 @@ mod test {
 
     #[test]
     fn generate_command_rpush_ok() {
-        let params = vec!["rpush".to_string(), "key".to_string(), "value".to_string()];
+        let params = vec!["rpush".into(), "key".into(), "value".into()];
         let result = generate(params, "client-test".to_string());
 
         let _key = "key".to_string();
@@ -1831,12 +4055,12 @@ mod test {
 
     #[test]
     fn generate_command_rpushx_incorrect_params_err() {
-        let params = vec!["rpushx".to_string()];
+        let params = vec!["rpushx".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err());
 
-        let params = vec!["rpushx".to_string(), "key".to_string()];
+        let params = vec!["rpushx".into(), "key".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err())
@@ -1844,12 +4068,12 @@ mod test {
 
     #[test]
     fn generate_command_sadd_incorrect_params_err() {
-        let params = vec!["sadd".to_string()];
+        let params = vec!["sadd".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err());
 
-        let params = vec!["sadd".to_string(), "key".to_string()];
+        let params = vec!["sadd".into(), "key".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err())
@@ -1857,7 +4081,7 @@ mod test {
 
     #[test]
     fn generate_command_lpushx_ok() {
-        let params = vec!["lpushx".to_string(), "key".to_string(), "value".to_string()];
+        let params = vec!["lpushx".into(), "key".into(), "value".into()];
         let result = generate(params, "client-test".to_string());
 
         let _key = "key".to_string();
@@ -1874,13 +4098,13 @@ mod test {
 
     #[test]
     fn generate_command_sadd_ok() {
-        let params = vec!["sadd".to_string(), "key".to_string(), "value".to_string()];
+        let params = vec!["sadd".into(), "key".into(), "value".into()];
         let result = generate(params, "client-test".to_string());
 
         let _key = "key".to_string();
-        let mut _values = HashSet::new();
-        _values.insert("value1".to_string());
-        _values.insert("value2".to_string());
+        let mut _values: HashSet<Vec<u8>> = HashSet::new();
+        _values.insert("value1".into());
+        _values.insert("value2".into());
         assert!(result.is_ok());
         assert!(match result.unwrap() {
             Command::Sadd {
@@ -1893,7 +4117,7 @@ mod test {
 
     #[test]
     fn generate_command_scard_without_param_err() {
-        let params = vec!["scard".to_string()];
+        let params = vec!["scard".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err())
@@ -1901,7 +4125,7 @@ mod test {
 
     #[test]
     fn generate_command_scard_ok() {
-        let params = vec!["scard".to_string(), "key".to_string()];
+        let params = vec!["scard".into(), "key".into()];
         let result = generate(params, "client-test".to_string());
 
         let _key = "key".to_string();
@@ -1914,7 +4138,7 @@ mod test {
 
     #[test]
     fn generate_command_sismember_without_param_err() {
-        let params = vec!["sismember".to_string()];
+        let params = vec!["sismember".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err())
@@ -1922,11 +4146,7 @@ mod test {
 
     #[test]
     fn generate_command_sismember_ok() {
-        let params = vec![
-            "sismember".to_string(),
-            "key".to_string(),
-            "value".to_string(),
-        ];
+        let params = vec!["sismember".into(), "key".into(), "value".into()];
         let result = generate(params, "client-test".to_string());
 
         let _key = "key".to_string();
@@ -1944,12 +4164,12 @@ mod test {
 
     #[test]
     fn generate_command_srem_incorrect_params_err() {
-        let params = vec!["srem".to_string()];
+        let params = vec!["srem".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err());
 
-        let params = vec!["srem".to_string(), "key".to_string()];
+        let params = vec!["srem".into(), "key".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err())
@@ -1957,13 +4177,13 @@ mod test {
 
     #[test]
     fn generate_command_srem_ok() {
-        let params = vec!["srem".to_string(), "key".to_string(), "value".to_string()];
+        let params = vec!["srem".into(), "key".into(), "value".into()];
         let result = generate(params, "client-test".to_string());
 
         let _key = "key".to_string();
-        let mut _values = HashSet::new();
-        _values.insert("value1".to_string());
-        _values.insert("value2".to_string());
+        let mut _values: HashSet<Vec<u8>> = HashSet::new();
+        _values.insert("value1".into());
+        _values.insert("value2".into());
         assert!(result.is_ok());
         assert!(match result.unwrap() {
             Command::Srem {
@@ -1976,7 +4196,7 @@ mod test {
 
     #[test]
     fn generate_command_smembers_without_param_err() {
-        let params = vec!["smembers".to_string()];
+        let params = vec!["smembers".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err())
@@ -1984,7 +4204,7 @@ mod test {
 
     #[test]
     fn generate_command_smembers_ok() {
-        let params = vec!["smembers".to_string(), "key".to_string()];
+        let params = vec!["smembers".into(), "key".into()];
         let result = generate(params, "client-test".to_string());
 
         let _key = "key".to_string();
@@ -1995,9 +4215,91 @@ mod test {
         });
     }
 
+    #[test]
+    fn generate_command_set_operations_ok() {
+        let sinter = generate_from(&["sinter", "a", "b"]);
+        let sdiffstore = generate_from(&["sdiffstore", "dest", "a", "b"]);
+        let sunion_without_keys = generate_from(&["sunion"]);
+        let sunionstore_without_keys = generate_from(&["sunionstore", "dest"]);
+
+        assert!(matches!(sinter.unwrap(), Command::Sinter { keys } if keys.len() == 2));
+        assert!(matches!(
+            sdiffstore.unwrap(),
+            Command::Sdiffstore { destination, keys } if destination == b"dest" && keys.len() == 2
+        ));
+        assert_eq!(
+            sunion_without_keys.unwrap_err(),
+            "ERR wrong number of arguments for 'sunion' command"
+        );
+        assert_eq!(
+            sunionstore_without_keys.unwrap_err(),
+            "ERR wrong number of arguments for 'sunionstore' command"
+        );
+    }
+
+    #[test]
+    fn generate_command_sintercard_ok() {
+        let sintercard = generate_from(&["sintercard", "2", "a", "b", "LIMIT", "5"]);
+        let too_many_keys = generate_from(&["sintercard", "3", "a", "b"]);
+        let negative_limit = generate_from(&["sintercard", "1", "a", "limit", "-1"]);
+
+        assert!(matches!(
+            sintercard.unwrap(),
+            Command::Sintercard { keys, limit: 5 } if keys.len() == 2
+        ));
+        assert_eq!(
+            too_many_keys.unwrap_err(),
+            "ERR Number of keys can't be greater than number of args"
+        );
+        assert_eq!(negative_limit.unwrap_err(), "ERR LIMIT can't be negative");
+    }
+
+    #[test]
+    fn generate_command_spop_and_srandmember_ok() {
+        let spop = generate_from(&["spop", "key"]);
+        let spop_count = generate_from(&["spop", "key", "3"]);
+        let spop_negative = generate_from(&["spop", "key", "-3"]);
+        let srandmember = generate_from(&["srandmember", "key", "-3"]);
+
+        assert!(matches!(spop.unwrap(), Command::Spop { count: None, .. }));
+        assert!(matches!(
+            spop_count.unwrap(),
+            Command::Spop { count: Some(3), .. }
+        ));
+        assert_eq!(
+            spop_negative.unwrap_err(),
+            "ERR value is out of range, must be positive"
+        );
+        assert!(matches!(
+            srandmember.unwrap(),
+            Command::Srandmember {
+                count: Some(-3),
+                ..
+            }
+        ));
+        assert_eq!(
+            generate_from(&["srandmember", "key", "-10000000000"]).unwrap_err(),
+            "ERR value is out of range"
+        );
+    }
+
+    #[test]
+    fn generate_command_smove_and_smismember_ok() {
+        let smove = generate_from(&["smove", "src", "dst", "member"]);
+        let smismember = generate_from(&["smismember", "key", "a", "b"]);
+        let smismember_without_members = generate_from(&["smismember", "key"]);
+
+        assert!(matches!(smove.unwrap(), Command::Smove { .. }));
+        assert!(matches!(
+            smismember.unwrap(),
+            Command::Smismember { members, .. } if members.len() == 2
+        ));
+        assert!(smismember_without_members.is_err());
+    }
+
     #[test]
     fn generate_command_keys_ok() {
-        let params = vec!["keys".to_string(), "/*".to_string()];
+        let params = vec!["keys".into(), "/*".into()];
         let result = generate(params, "client-test".to_string());
 
         let _pattern = "/*".to_string();
@@ -2010,7 +4312,7 @@ mod test {
 
     #[test]
     fn generate_command_store_without_param_err() {
-        let params = vec!["store".to_string()];
+        let params = vec!["store".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err())
@@ -2018,7 +4320,7 @@ mod test {
 
     #[test]
     fn generate_command_store_ok() {
-        let params = vec!["store".to_string(), "/store.file".to_string()];
+        let params = vec!["store".into(), "/store.file".into()];
         let result = generate(params, "client-test".to_string());
 
         let _path = "/store.file".to_string();
@@ -2031,7 +4333,7 @@ mod test {
 
     #[test]
     fn generate_command_config_set_without_param_err() {
-        let params = vec!["config".to_string(), "set".to_string()];
+        let params = vec!["config".into(), "set".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err())
@@ -2039,12 +4341,7 @@ mod test {
 
     #[test]
     fn generate_command_config_set_ok() {
-        let params = vec![
-            "config".to_string(),
-            "set".to_string(),
-            "verbose".to_string(),
-            "1".to_string(),
-        ];
+        let params = vec!["config".into(), "set".into(), "verbose".into(), "1".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_ok());
@@ -2061,7 +4358,7 @@ mod test {
 
     #[test]
     fn generate_command_config_get_ok() {
-        let params = vec!["config".to_string(), "get".to_string()];
+        let params = vec!["config".into(), "get".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_ok());
@@ -2073,7 +4370,7 @@ mod test {
 
     #[test]
     fn generate_command_load_without_param_err() {
-        let params = vec!["load".to_string()];
+        let params = vec!["load".into()];
         let result = generate(params, "client-test".to_string());
 
         assert!(result.is_err())
@@ -2081,7 +4378,7 @@ mod test {
 
     #[test]
     fn generate_command_load_ok() {
-        let params = vec!["load".to_string(), "/store.file".to_string()];
+        let params = vec!["load".into(), "/store.file".into()];
         let result = generate(params, "client-test".to_string());
 
         let _path = "/store.file".to_string();
@@ -2091,4 +4388,479 @@ mod test {
             _ => false,
         });
     }
+
+    #[test]
+    fn generate_command_hset_ok() {
+        let params = vec![
+            "hset".into(),
+            "key".into(),
+            "field1".into(),
+            "value1".into(),
+            "field2".into(),
+            "value2".into(),
+        ];
+        let result = generate(params, "client-test".to_string());
+
+        assert!(result.is_ok());
+        assert!(matches!(
+            result.unwrap(),
+            Command::Hset { key, field_values } if key == b"key" && field_values.len() == 2
+        ));
+    }
+
+    #[test]
+    fn generate_command_hset_incorrect_params_err() {
+        let params = vec!["hset".into(), "key".into()];
+        let result = generate(params, "client-test".to_string());
+
+        assert!(result.is_err());
+
+        let params = vec![
+            "hset".into(),
+            "key".into(),
+            "field1".into(),
+            "value1".into(),
+            "field2".into(),
+        ];
+        let result = generate(params, "client-test".to_string());
+
+        assert!(result.is_err())
+    }
+
+    #[test]
+    fn generate_command_hget_ok() {
+        let params = vec!["hget".into(), "key".into(), "field".into()];
+        let result = generate(params, "client-test".to_string());
+
+        assert!(result.is_ok());
+        assert!(matches!(
+            result.unwrap(),
+            Command::Hget { key, field } if key == b"key" && field == b"field"
+        ));
+    }
+
+    #[test]
+    fn generate_command_hdel_without_fields_err() {
+        let params = vec!["hdel".into(), "key".into()];
+        let result = generate(params, "client-test".to_string());
+
+        assert!(result.is_err())
+    }
+
+    #[test]
+    fn generate_command_hincrby_ok() {
+        let params = vec!["hincrby".into(), "key".into(), "field".into(), "-5".into()];
+        let result = generate(params, "client-test".to_string());
+
+        assert!(result.is_ok());
+        assert!(matches!(
+            result.unwrap(),
+            Command::Hincrby { increment: -5, .. }
+        ));
+    }
+
+    #[test]
+    fn generate_command_hincrby_not_integer_err() {
+        let params = vec!["hincrby".into(), "key".into(), "field".into(), "one".into()];
+        let result = generate(params, "client-test".to_string());
+
+        assert!(result.is_err())
+    }
+
+    #[test]
+    fn generate_command_hscan_with_options_ok() {
+        let params = vec![
+            "hscan".into(),
+            "key".into(),
+            "0".into(),
+            "MATCH".into(),
+            "f*".into(),
+            "COUNT".into(),
+            "5".into(),
+        ];
+        let result = generate(params, "client-test".to_string());
+
+        assert!(result.is_ok());
+        assert!(matches!(
+            result.unwrap(),
+            Command::Hscan { cursor: 0, pattern: Some(pattern), count: 5, .. } if pattern == b"f*"
+        ));
+    }
+
+    #[test]
+    fn generate_command_scan_with_options_ok() {
+        let scan = generate(
+            vec![
+                "scan".into(),
+                "42".into(),
+                "TYPE".into(),
+                "Hash".into(),
+                "COUNT".into(),
+                "100".into(),
+            ],
+            "client-test".to_string(),
+        );
+        let sscan = generate(
+            vec![
+                "sscan".into(),
+                "set".into(),
+                "0".into(),
+                "MATCH".into(),
+                "a*".into(),
+            ],
+            "client-test".to_string(),
+        );
+        let sscan_with_type = generate(
+            vec![
+                "sscan".into(),
+                "set".into(),
+                "0".into(),
+                "TYPE".into(),
+                "set".into(),
+            ],
+            "client-test".to_string(),
+        );
+        let missing_value = generate(
+            vec!["scan".into(), "0".into(), "COUNT".into()],
+            "client-test".to_string(),
+        );
+
+        assert!(matches!(
+            scan,
+            Ok(Command::Scan { cursor: 42, pattern: None, count: 100, value_type: Some(value_type) })
+                if value_type == "hash"
+        ));
+        assert!(matches!(
+            sscan,
+            Ok(Command::Sscan { cursor: 0, pattern: Some(pattern), count: 10, .. })
+                if pattern == b"a*"
+        ));
+        assert_eq!(sscan_with_type.unwrap_err(), "ERR syntax error");
+        assert!(missing_value.is_err());
+    }
+
+    #[test]
+    fn generate_command_hscan_invalid_cursor_err() {
+        let params = vec!["hscan".into(), "key".into(), "a".into()];
+        let result = generate(params, "client-test".to_string());
+
+        assert!(result.is_err())
+    }
+
+    #[test]
+    fn generate_command_zadd_with_options_ok() {
+        let params = vec![
+            "zadd".into(),
+            "key".into(),
+            "XX".into(),
+            "CH".into(),
+            "1.5".into(),
+            "a".into(),
+            "-inf".into(),
+            "b".into(),
+        ];
+        let result = generate(params, "client-test".to_string());
+
+        assert!(result.is_ok());
+        assert!(matches!(
+            result.unwrap(),
+            Command::Zadd { members, xx: true, ch: true, nx: false, .. } if members.len() == 2
+        ));
+    }
+
+    #[test]
+    fn generate_command_zadd_incompatible_options_err() {
+        let params = vec![
+            "zadd".into(),
+            "key".into(),
+            "NX".into(),
+            "GT".into(),
+            "1".into(),
+            "a".into(),
+        ];
+        let result = generate(params, "client-test".to_string());
+
+        assert!(result.is_err())
+    }
+
+    #[test]
+    fn generate_command_zadd_not_float_err() {
+        let params = vec!["zadd".into(), "key".into(), "one".into(), "a".into()];
+        let result = generate(params, "client-test".to_string());
+
+        assert!(result.is_err())
+    }
+
+    #[test]
+    fn generate_command_zrange_byscore_rev_ok() {
+        let params = vec![
+            "zrange".into(),
+            "key".into(),
+            "(5".into(),
+            "1".into(),
+            "BYSCORE".into(),
+            "REV".into(),
+            "LIMIT".into(),
+            "0".into(),
+            "2".into(),
+        ];
+        let result = generate(params, "client-test".to_string());
+
+        assert!(result.is_ok());
+        assert!(matches!(
+            result.unwrap(),
+            Command::Zrange {
+                param: ZRangeParam::Score {
+                    min: ScoreBound::Inclusive(min),
+                    max: ScoreBound::Exclusive(max),
+                },
+                rev: true,
+                limit: Some((0, 2)),
+                ..
+            } if min == 1.0 && max == 5.0
+        ));
+    }
+
+    #[test]
+    fn generate_command_zrevrangebylex_ok() {
+        let params = vec![
+            "zrevrangebylex".into(),
+            "key".into(),
+            "+".into(),
+            "[b".into(),
+        ];
+        let result = generate(params, "client-test".to_string());
+
+        assert!(result.is_ok());
+        assert!(matches!(
+            result.unwrap(),
+            Command::Zrange {
+                param: ZRangeParam::Lex {
+                    min: LexBound::Inclusive(_),
+                    max: LexBound::Max,
+                },
+                rev: true,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn generate_command_zrange_limit_by_rank_err() {
+        let params = vec![
+            "zrange".into(),
+            "key".into(),
+            "0".into(),
+            "-1".into(),
+            "LIMIT".into(),
+            "0".into(),
+            "2".into(),
+        ];
+        let result = generate(params, "client-test".to_string());
+
+        assert!(result.is_err())
+    }
+
+    #[test]
+    fn generate_command_zunionstore_ok() {
+        let params = vec![
+            "zunionstore".into(),
+            "out".into(),
+            "2".into(),
+            "zset1".into(),
+            "zset2".into(),
+            "WEIGHTS".into(),
+            "2".into(),
+            "3".into(),
+            "AGGREGATE".into(),
+            "MIN".into(),
+        ];
+        let result = generate(params, "client-test".to_string());
+
+        assert!(result.is_ok());
+        assert!(matches!(
+            result.unwrap(),
+            Command::Zunionstore { keys, weights, aggregate: AggregateParam::Min, .. }
+                if keys.len() == 2 && weights == vec![2.0, 3.0]
+        ));
+    }
+
+    #[test]
+    fn generate_command_zinterstore_missing_keys_err() {
+        let params = vec![
+            "zinterstore".into(),
+            "out".into(),
+            "3".into(),
+            "zset1".into(),
+        ];
+        let result = generate(params, "client-test".to_string());
+
+        assert!(result.is_err())
+    }
+
+    #[test]
+    fn generate_command_watch_ok() {
+        let params = vec!["watch".into(), "key1".into(), "key2".into()];
+        let result = generate(params, "client-test".to_string());
+
+        assert!(result.is_ok());
+        assert!(matches!(
+            result.unwrap(),
+            Command::Watch { keys, client_id } if keys.len() == 2 && client_id == "client-test"
+        ));
+    }
+
+    #[test]
+    fn generate_command_watch_without_keys_err() {
+        let params = vec!["watch".into()];
+        let result = generate(params, "client-test".to_string());
+
+        assert!(result.is_err())
+    }
+
+    #[test]
+    fn generate_command_multi_with_params_err() {
+        let params = vec!["multi".into(), "extra".into()];
+        let result = generate(params, "client-test".to_string());
+
+        assert!(result.is_err())
+    }
+
+    #[test]
+    fn generate_command_cluster_ok() {
+        let keyslot = generate(
+            vec!["cluster".into(), "keyslot".into(), "foo".into()],
+            "client-test".to_string(),
+        );
+        let addslots = generate(
+            vec!["cluster".into(), "addslots".into(), "1".into(), "2".into()],
+            "client-test".to_string(),
+        );
+        let setslot = generate(
+            vec![
+                "cluster".into(),
+                "setslot".into(),
+                "1".into(),
+                "migrating".into(),
+                "id".into(),
+            ],
+            "client-test".to_string(),
+        );
+        let out_of_range = generate(
+            vec!["cluster".into(), "addslots".into(), "16384".into()],
+            "client-test".to_string(),
+        );
+
+        assert!(matches!(
+            keyslot,
+            Ok(Command::Cluster { param: ClusterParam::Keyslot(key) }) if key == b"foo"
+        ));
+        assert!(matches!(
+            addslots,
+            Ok(Command::Cluster { param: ClusterParam::Addslots(slots) }) if slots == vec![1, 2]
+        ));
+        assert!(matches!(
+            setslot,
+            Ok(Command::Cluster {
+                param: ClusterParam::Setslot(1, SlotState::Migrating(id))
+            }) if id == "id"
+        ));
+        assert!(out_of_range.is_err());
+    }
+
+    #[test]
+    fn generate_command_migrate_ok() {
+        let single = generate(
+            vec![
+                "migrate".into(),
+                "127.0.0.1".into(),
+                "7001".into(),
+                "key".into(),
+                "0".into(),
+                "5000".into(),
+                "copy".into(),
+            ],
+            "client-test".to_string(),
+        );
+        let multiple = generate(
+            vec![
+                "migrate".into(),
+                "127.0.0.1".into(),
+                "7001".into(),
+                "".into(),
+                "0".into(),
+                "5000".into(),
+                "replace".into(),
+                "keys".into(),
+                "a".into(),
+                "b".into(),
+            ],
+            "client-test".to_string(),
+        );
+
+        assert!(matches!(
+            single,
+            Ok(Command::Migrate { keys, copy: true, replace: false, port: 7001, .. })
+                if keys == vec![b"key".to_vec()]
+        ));
+        assert!(matches!(
+            multiple,
+            Ok(Command::Migrate { keys, copy: false, replace: true, .. })
+                if keys == vec![b"a".to_vec(), b"b".to_vec()]
+        ));
+    }
+
+    #[test]
+    fn generate_command_restore_ok() {
+        let restore = generate(
+            vec![
+                "restore-asking".into(),
+                "key".into(),
+                "100".into(),
+                "payload".into(),
+                "replace".into(),
+            ],
+            "client-test".to_string(),
+        );
+
+        assert!(matches!(
+            restore,
+            Ok(Command::Restore {
+                ttl: 100,
+                replace: true,
+                absttl: false,
+                asking: true,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn generate_command_psubscribe_ok() {
+        let psubscribe = generate(
+            vec!["psubscribe".into(), "news.*".into(), "h?llo".into()],
+            "client-test".to_string(),
+        );
+        let punsubscribe = generate(vec!["punsubscribe".into()], "client-test".to_string());
+        let numpat = generate(
+            vec!["pubsub".into(), "numpat".into()],
+            "client-test".to_string(),
+        );
+
+        assert!(matches!(
+            psubscribe,
+            Ok(Command::Psubscribe { patterns, .. }) if patterns == vec!["news.*", "h?llo"]
+        ));
+        assert!(matches!(
+            punsubscribe,
+            Ok(Command::Punsubscribe { patterns, .. }) if patterns.is_empty()
+        ));
+        assert!(matches!(
+            numpat,
+            Ok(Command::Pubsub {
+                param: PubSubParam::Numpat
+            })
+        ));
+        assert!(generate(vec!["psubscribe".into()], "client-test".to_string()).is_err());
+    }
 }
@@ -0,0 +1,137 @@
+/// Dígitos significativos con los que se muestran los resultados de INCRBYFLOAT y HINCRBYFLOAT.
+/// Redis suma en `long double` y muestra 17 dígitos, menos que su precisión, para que el error de
+/// redondeo de la suma no aparezca en el resultado (`0.1 + 0.2` se muestra como `0.3`). Con `f64`
+/// el equivalente son 15 dígitos, los que un `f64` representa siempre sin error.
+const SIGNIFICANT_DIGITS: i32 = 15;
+
+/// Máximo de decimales con los que se muestra un resultado, igual que el `%.17Lf` de Redis: los
+/// números menores se muestran como 0.
+const MAX_DECIMALS: i32 = 17;
+
+/// Mayor valor absoluto de un puntaje entero que se muestra sin exponente, igual que Redis, que
+/// solamente muestra como entero lo que entra holgadamente en un `long long`.
+const MAX_INTEGER_SCORE: f64 = (i64::MAX / 2) as f64;
+
+/// Interpreta un valor como número de punto flotante, aceptando notación exponencial e `inf`.
+/// Devuelve None si no es un número o es NaN.
+pub fn parse_float(value: &[u8]) -> Option<f64> {
+    String::from_utf8_lossy(value)
+        .parse::<f64>()
+        .ok()
+        .filter(|number| !number.is_nan())
+}
+
+/// Formatea el resultado de INCRBYFLOAT y HINCRBYFLOAT igual que Redis: sin exponente, con a lo
+/// sumo `SIGNIFICANT_DIGITS` dígitos significativos, sin ceros finales y sin punto decimal si el
+/// número es entero (por ejemplo `5200` para `5.2e3`). Devuelve error si el resultado es infinito
+/// o NaN.
+pub fn format_float(value: f64) -> Result<String, String> {
+    if !value.is_finite() {
+        return Err("ERR increment would produce NaN or Infinity".to_string());
+    }
+
+    let exponent = match value {
+        0.0 => 0,
+        _ => value.abs().log10().floor() as i32,
+    };
+    let decimals = (SIGNIFICANT_DIGITS - 1 - exponent).clamp(0, MAX_DECIMALS) as usize;
+    let text = format!("{:.*}", decimals, value);
+    match text.contains('.') {
+        true => Ok(text.trim_end_matches('0').trim_end_matches('.').to_string()),
+        false => Ok(text),
+    }
+}
+
+/// Formatea un puntaje de un sorted set igual que Redis (`d2string`): los enteros sin decimales, y
+/// el resto con la menor cantidad de dígitos que identifica al número, usando exponente cuando es
+/// muy grande o muy chico (por ejemplo `1.5e+21` o `1e-7`).
+pub fn format_score(value: f64) -> String {
+    if value.is_infinite() {
+        return if value > 0.0 { "inf" } else { "-inf" }.to_string();
+    }
+    if value == 0.0 {
+        return if value.is_sign_negative() { "-0" } else { "0" }.to_string();
+    }
+    if value.fract() == 0.0 && value.abs() <= MAX_INTEGER_SCORE {
+        return (value as i64).to_string();
+    }
+
+    // `{:e}` da los dígitos mínimos que identifican al número: value = digits * 10^shift.
+    let scientific = format!("{:e}", value.abs());
+    let (mantissa, exponent) = scientific.split_once('e').unwrap_or((&scientific, "0"));
+    let digits = mantissa.replace('.', "");
+    let exponent: i32 = exponent.parse().unwrap_or(0);
+    let shift = exponent - (digits.len() as i32 - 1);
+    let sign = if value < 0.0 { "-" } else { "" };
+
+    let text = if shift >= 0 && exponent.abs() < digits.len() as i32 + 7 {
+        [digits, "0".repeat(shift as usize)].concat()
+    } else if shift < 0 && (shift > -7 || exponent.abs() < 4) {
+        let integer_digits = digits.len() as i32 + shift;
+        if integer_digits <= 0 {
+            ["0.", &"0".repeat(-integer_digits as usize), &digits].concat()
+        } else {
+            let (integer, decimals) = digits.split_at(integer_digits as usize);
+            [integer, ".", decimals].concat()
+        }
+    } else {
+        let (first, rest) = digits.split_at(1);
+        let rest = if rest.is_empty() {
+            String::new()
+        } else {
+            [".", rest].concat()
+        };
+        let exponent_sign = if exponent < 0 { "-" } else { "+" };
+        format!("{}{}e{}{}", first, rest, exponent_sign, exponent.abs())
+    };
+    [sign, &text].concat()
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::service::float::{format_float, format_score, parse_float};
+
+    #[test]
+    fn test_parse_float() {
+        assert_eq!(parse_float(b"5.0e3"), Some(5000.0));
+        assert_eq!(parse_float(b"-0.5"), Some(-0.5));
+        assert_eq!(parse_float(b"inf"), Some(f64::INFINITY));
+        assert_eq!(parse_float(b"nan"), None);
+        assert_eq!(parse_float(b"one"), None);
+        assert_eq!(parse_float(b" 1"), None);
+    }
+
+    #[test]
+    fn test_format_float_like_redis() {
+        assert_eq!(format_float(0.1 + 0.2).unwrap(), "0.3");
+        assert_eq!(format_float(10.5 + 0.1).unwrap(), "10.6");
+        assert_eq!(format_float(5.0e3 + 2.0e2).unwrap(), "5200");
+        assert_eq!(format_float(-1.25).unwrap(), "-1.25");
+        assert_eq!(format_float(0.0).unwrap(), "0");
+        assert_eq!(format_float(1e20).unwrap(), "100000000000000000000");
+        assert_eq!(format_float(1.5e-10).unwrap(), "0.00000000015");
+        assert_eq!(format_float(1e-20).unwrap(), "0");
+        assert!(format_float(f64::INFINITY).is_err());
+        assert!(format_float(f64::NAN).is_err());
+    }
+
+    #[test]
+    fn test_format_score_like_redis() {
+        assert_eq!(format_score(1.5e21), "1.5e+21");
+        assert_eq!(format_score(-1.5e21), "-1.5e+21");
+        assert_eq!(format_score(4.7e18), "4.7e+18");
+        assert_eq!(format_score(1e15), "1000000000000000");
+        assert_eq!(format_score(3.0), "3");
+        assert_eq!(format_score(-2.5), "-2.5");
+        assert_eq!(format_score(123.456), "123.456");
+        assert_eq!(format_score(0.1 + 0.2), "0.30000000000000004");
+        assert_eq!(format_score(0.001), "0.001");
+        assert_eq!(format_score(1e-7), "1e-7");
+        assert_eq!(format_score(1.5e-10), "1.5e-10");
+        assert_eq!(format_score(1e300), "1e+300");
+        assert_eq!(format_score(0.0), "0");
+        assert_eq!(format_score(-0.0), "-0");
+        assert_eq!(format_score(f64::INFINITY), "inf");
+        assert_eq!(format_score(f64::NEG_INFINITY), "-inf");
+    }
+}
@@ -0,0 +1,122 @@
+/// Indica si el texto coincide con el patrón, usando la sintaxis glob de Redis:
+///
+/// * `*` coincide con cualquier secuencia de caracteres, incluso vacía.
+/// * `?` coincide con un caracter cualquiera.
+/// * `[abc]` coincide con uno de los caracteres indicados, `[^a]` con cualquiera excepto los
+///   indicados y `[a-z]` con uno del rango.
+/// * `\` escapa el caracter siguiente, para buscarlo literalmente.
+///
+/// Ante un `*` se recuerda la última posición desde la cual reintentar, por lo que el tiempo de
+/// ejecución es a lo sumo proporcional al largo del patrón por el largo del texto.
+pub fn glob_match(pattern: &[u8], text: &[u8]) -> bool {
+    let mut p = 0;
+    let mut t = 0;
+    // Posición del patrón después del último `*` y posición del texto desde la que se reintenta.
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        let matched = match pattern.get(p) {
+            Some(b'*') => {
+                backtrack = Some((p + 1, t));
+                p += 1;
+                continue;
+            }
+            Some(b'?') => Some(p + 1),
+            Some(b'[') => match_class(pattern, p + 1, text[t]),
+            Some(b'\\') if p + 1 < pattern.len() => {
+                Some(p + 2).filter(|_| pattern[p + 1] == text[t])
+            }
+            Some(byte) => Some(p + 1).filter(|_| *byte == text[t]),
+            None => None,
+        };
+
+        match (matched, backtrack) {
+            (Some(next), _) => {
+                p = next;
+                t += 1;
+            }
+            (None, Some((star_p, star_t))) => {
+                p = star_p;
+                t = star_t + 1;
+                backtrack = Some((star_p, star_t + 1));
+            }
+            (None, None) => return false,
+        }
+    }
+
+    pattern[p.min(pattern.len())..]
+        .iter()
+        .all(|byte| *byte == b'*')
+}
+
+/// Compara el caracter con la clase que empieza en la posición `start` del patrón (después del
+/// `[`). Devuelve la posición siguiente al `]` si coincide. Una clase sin `]` termina con el
+/// patrón.
+fn match_class(pattern: &[u8], start: usize, byte: u8) -> Option<usize> {
+    let mut p = start;
+    let negate = pattern.get(p) == Some(&b'^');
+    if negate {
+        p += 1;
+    }
+
+    let mut matched = false;
+    while p < pattern.len() && pattern[p] != b']' {
+        if pattern[p] == b'\\' && p + 1 < pattern.len() {
+            matched |= pattern[p + 1] == byte;
+            p += 2;
+        } else if p + 2 < pattern.len() && pattern[p + 1] == b'-' && pattern[p + 2] != b']' {
+            let (low, high) = if pattern[p] <= pattern[p + 2] {
+                (pattern[p], pattern[p + 2])
+            } else {
+                (pattern[p + 2], pattern[p])
+            };
+            matched |= low <= byte && byte <= high;
+            p += 3;
+        } else {
+            matched |= pattern[p] == byte;
+            p += 1;
+        }
+    }
+
+    if matched != negate {
+        Some((p + 1).min(pattern.len()))
+    } else {
+        None
+    }
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::service::glob::glob_match;
+
+    #[test]
+    fn test_glob_wildcards() {
+        assert!(glob_match(b"*", b""));
+        assert!(glob_match(b"*", b"anything"));
+        assert!(glob_match(b"orders.*", b"orders.created"));
+        assert!(!glob_match(b"orders.*", b"users.created"));
+        assert!(glob_match(b"h?llo", b"hello"));
+        assert!(!glob_match(b"h?llo", b"hllo"));
+        assert!(glob_match(b"*a*b*", b"xxaxxbxx"));
+        assert!(!glob_match(b"*a*b", b"xxaxxbxx"));
+    }
+
+    #[test]
+    fn test_glob_classes() {
+        assert!(glob_match(b"h[ae]llo", b"hallo"));
+        assert!(!glob_match(b"h[ae]llo", b"hillo"));
+        assert!(glob_match(b"h[^e]llo", b"hallo"));
+        assert!(!glob_match(b"h[^e]llo", b"hello"));
+        assert!(glob_match(b"h[a-b]llo", b"hbllo"));
+        assert!(glob_match(b"h[b-a]llo", b"hallo"));
+        assert!(!glob_match(b"h[a-b]llo", b"hcllo"));
+    }
+
+    #[test]
+    fn test_glob_escapes() {
+        assert!(glob_match(b"a\\*b", b"a*b"));
+        assert!(!glob_match(b"a\\*b", b"axb"));
+        assert!(glob_match(b"a[\\]]b", b"a]b"));
+        assert!(glob_match(b"a\\?", b"a?"));
+    }
+}
@@ -41,7 +41,6 @@ impl Logger {
     pub fn log(mut self) {
         let _: JoinHandle<Result<(), Error>> = thread::spawn(move || {
             let mut file = OpenOptions::new()
-                .write(true)
                 .create(true)
                 .append(true)
                 .open(self.config.lock().unwrap().get_logfile())?;
@@ -49,7 +48,6 @@ impl Logger {
             while let Ok(log) = self.receiver.recv() {
                 if self.file != self.config.lock().unwrap().get_logfile() {
                     file = OpenOptions::new()
-                        .write(true)
                         .create(true)
                         .append(true)
                         .open(self.config.lock().unwrap().get_logfile())?;
@@ -62,7 +60,7 @@ impl Logger {
 
                 let level = log.clone().get_level();
                 if level <= self.loglevel {
-                    file.write(log.to_string().as_bytes());
+                    file.write_all(log.to_string().as_bytes());
                 }
             }
             Ok(())
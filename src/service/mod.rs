@@ -1,6 +1,12 @@
+pub mod aof;
+pub mod cluster;
 pub mod command_generator;
 pub mod constants;
+pub mod float;
+pub mod glob;
 pub mod logger;
+pub mod rdb;
 pub mod redis;
+pub mod replication;
 pub mod server;
 pub mod timestamp_to_string;
@@ -0,0 +1,184 @@
+use crate::entities::redis_element::RedisElement;
+use crate::entities::ttl_hash_map::TtlHashMap;
+use std::fs;
+use std::fs::File;
+use std::io;
+use std::io::{Error, Write};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use std::thread::JoinHandle;
+use std::time::SystemTime;
+
+/// Mensaje de error usado cuando ya hay un guardado en segundo plano en curso.
+const BGSAVE_IN_PROGRESS_MSG: &str = "ERR Background save already in progress";
+
+#[derive(Debug)]
+/// Estado de los guardados del archivo RDB. Se comparte con el hilo que guarda en segundo plano.
+struct RdbStatus {
+    /// Indica si hay un guardado en segundo plano en curso.
+    bgsave_in_progress: bool,
+    /// Momento del último guardado exitoso.
+    last_save_time: SystemTime,
+    /// Indica si el último guardado en segundo plano terminó sin errores.
+    last_bgsave_ok: bool,
+    /// Momento en el que se lanzó el último guardado en segundo plano.
+    last_bgsave_try: SystemTime,
+}
+
+#[derive(Debug)]
+/// Entidad encargada de persistir la base de datos en archivos RDB, ya sea en el hilo que la
+/// llama (SAVE) o en un hilo aparte a partir de una copia de los datos (BGSAVE).
+pub struct Rdb {
+    status: Arc<Mutex<RdbStatus>>,
+}
+
+impl Rdb {
+    /// Constructor del Rdb. Se toma el momento de creación como el del último guardado, ya que
+    /// coincide con la carga de la base de datos.
+    pub fn new() -> Self {
+        Self {
+            status: Arc::new(Mutex::new(RdbStatus {
+                bgsave_in_progress: false,
+                last_save_time: SystemTime::now(),
+                last_bgsave_ok: true,
+                last_bgsave_try: SystemTime::UNIX_EPOCH,
+            })),
+        }
+    }
+
+    fn lock(&self) -> io::Result<MutexGuard<'_, RdbStatus>> {
+        self.status
+            .lock()
+            .map_err(|_| Error::other("RDB lock error"))
+    }
+
+    /// Guarda las bases de datos en la ruta indicada, bloqueando hasta terminar. Falla si hay un
+    /// guardado en segundo plano en curso.
+    pub fn save(&self, path: &str, dbs: &[&TtlHashMap<Vec<u8>, RedisElement>]) -> io::Result<()> {
+        if self.lock()?.bgsave_in_progress {
+            return Err(Error::other(BGSAVE_IN_PROGRESS_MSG));
+        }
+        Rdb::write_file(path, &TtlHashMap::serialize_databases(dbs))?;
+        self.lock()?.last_save_time = SystemTime::now();
+        Ok(())
+    }
+
+    /// Guarda en un hilo aparte la copia de las bases de datos recibida. Falla si ya hay un
+    /// guardado en segundo plano en curso.
+    pub fn bgsave(
+        &self,
+        path: String,
+        snapshot: Vec<TtlHashMap<Vec<u8>, RedisElement>>,
+    ) -> io::Result<JoinHandle<()>> {
+        let mut status = self.lock()?;
+        if status.bgsave_in_progress {
+            return Err(Error::other(BGSAVE_IN_PROGRESS_MSG));
+        }
+        status.bgsave_in_progress = true;
+        status.last_bgsave_try = SystemTime::now();
+
+        let shared_status = Arc::clone(&self.status);
+        Ok(thread::spawn(move || {
+            let dbs: Vec<&TtlHashMap<Vec<u8>, RedisElement>> = snapshot.iter().collect();
+            let result = Rdb::write_file(&path, &TtlHashMap::serialize_databases(&dbs));
+            if let Ok(mut status) = shared_status.lock() {
+                status.bgsave_in_progress = false;
+                status.last_bgsave_ok = result.is_ok();
+                if result.is_ok() {
+                    status.last_save_time = SystemTime::now();
+                }
+            }
+        }))
+    }
+
+    /// Indica si hay un guardado en segundo plano en curso.
+    pub fn bgsave_in_progress(&self) -> bool {
+        self.lock().is_ok_and(|status| status.bgsave_in_progress)
+    }
+
+    /// Devuelve el momento del último guardado exitoso.
+    pub fn last_save_time(&self) -> SystemTime {
+        self.lock()
+            .map_or(SystemTime::UNIX_EPOCH, |status| status.last_save_time)
+    }
+
+    /// Indica si el último guardado en segundo plano terminó sin errores.
+    pub fn last_bgsave_ok(&self) -> bool {
+        self.lock().is_ok_and(|status| status.last_bgsave_ok)
+    }
+
+    /// Devuelve el momento en el que se lanzó el último guardado en segundo plano.
+    pub fn last_bgsave_try(&self) -> SystemTime {
+        self.lock()
+            .map_or(SystemTime::UNIX_EPOCH, |status| status.last_bgsave_try)
+    }
+
+    /// Escribe los bytes en la ruta indicada. Se escribe primero en un archivo temporal y luego se
+    /// renombra, para no dejar nunca un archivo incompleto.
+    pub fn write_file(path: &str, bytes: &[u8]) -> io::Result<()> {
+        let temp_path = format!("{}.tmp", path);
+        let mut file = File::create(&temp_path)?;
+        file.write_all(bytes)?;
+        file.sync_all()?;
+        fs::rename(temp_path, path)
+    }
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::entities::redis_element::RedisElement;
+    use crate::entities::ttl_hash_map::TtlHashMap;
+    use crate::service::rdb::Rdb;
+    use std::fs;
+    use std::path::Path;
+
+    #[test]
+    fn test_bgsave_writes_snapshot() {
+        let path = "test_bgsave_writes_snapshot.rdb".to_string();
+        let rdb = Rdb::new();
+        let mut db: TtlHashMap<Vec<u8>, RedisElement> = TtlHashMap::new();
+        db.insert("key".into(), RedisElement::String("value".into()));
+        let before = rdb.last_save_time();
+
+        let handle = rdb.bgsave(path.clone(), vec![db.snapshot()]).unwrap();
+        db.insert("other".into(), RedisElement::String("value".into()));
+        handle.join().unwrap();
+
+        let mut loaded = TtlHashMap::deserialize(fs::read(&path).unwrap()).unwrap();
+        let _ = fs::remove_file(&path);
+
+        assert!(!rdb.bgsave_in_progress());
+        assert!(rdb.last_bgsave_ok());
+        assert!(rdb.last_save_time() >= before);
+        assert_eq!(loaded.len(), 1);
+        assert!(loaded.contains_key(&b"key".to_vec()));
+        assert!(!Path::new(&format!("{}.tmp", path)).exists());
+    }
+
+    #[test]
+    fn test_bgsave_failure_is_reported() {
+        let rdb = Rdb::new();
+        let db: TtlHashMap<Vec<u8>, RedisElement> = TtlHashMap::new();
+
+        let handle = rdb
+            .bgsave("missing_dir/test.rdb".to_string(), vec![db.snapshot()])
+            .unwrap();
+        handle.join().unwrap();
+
+        assert!(!rdb.bgsave_in_progress());
+        assert!(!rdb.last_bgsave_ok());
+    }
+
+    #[test]
+    fn test_save_fails_while_bgsave_in_progress() {
+        let rdb = Rdb::new();
+        let db: TtlHashMap<Vec<u8>, RedisElement> = TtlHashMap::new();
+        rdb.status.lock().unwrap().bgsave_in_progress = true;
+
+        assert!(rdb.save("test_save_fails.rdb", &[&db]).is_err());
+        assert!(rdb
+            .bgsave("test_save_fails.rdb".to_string(), vec![db.snapshot()])
+            .is_err());
+        assert!(!Path::new("test_save_fails.rdb").exists());
+    }
+}
@@ -1,21 +1,42 @@
 use crate::config::server_config::Config;
-use crate::entities::command::Command;
+use crate::entities::append_fsync::AppendFsync;
+use crate::entities::blocked_client::BlockedClient;
+use crate::entities::cluster_param::ClusterParam;
+use crate::entities::command::{restore_expiration, Command};
 use crate::entities::info_param::InfoParam;
+use crate::entities::keyspace_events::KeyspaceEvents;
 use crate::entities::log::Log;
 use crate::entities::log_level::LogLevel;
+use crate::entities::maxmemory_policy::MaxmemoryPolicy;
 use crate::entities::pubsub_param::PubSubParam;
 use crate::entities::redis_element::{RedisElement as Re, RedisElement};
+use crate::entities::redis_hash::RedisHash;
+use crate::entities::redis_set::RedisSet;
+use crate::entities::reply::Reply;
 use crate::entities::response::Response;
-use crate::entities::ttl_hash_map::TtlHashMap;
+use crate::entities::save_point::SavePoint;
+use crate::entities::set_operation::SetOperation;
+use crate::entities::set_param::{SetCondition, SetExpiration, SetParam};
+use crate::entities::sort_param::SortParam;
+use crate::entities::sorted_set::{ScoreBound, SortedSet};
+use crate::entities::ttl_hash_map::{random_u64, TtlHashMap};
+use crate::entities::zrange_param::{AggregateParam, ZRangeParam};
+use crate::service::aof::Aof;
+use crate::service::cluster::{key_hash_slot, send_commands, Cluster};
+use crate::service::command_generator::generate;
+use crate::service::float::{format_float, format_score, parse_float};
+use crate::service::glob::glob_match;
+use crate::service::rdb::Rdb;
+use crate::service::replication::Replication;
 use crate::service::timestamp_to_string::timestamp_to_string;
-use regex::Regex;
 use std::cmp::Ordering;
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::fmt::Debug;
-use std::io::Write;
+use std::path::Path;
 use std::sync::mpsc;
 use std::sync::mpsc::{Receiver, Sender};
 use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
 use std::time::{Duration, SystemTime};
 use std::{fs, process};
 
@@ -23,27 +44,173 @@ use std::{fs, process};
 const WRONGTYPE_MSG: &str = "WRONGTYPE Operation against a key holding the wrong kind of value";
 /// Mensaje de error usado cuando el valor no es entero o está fuera de rango permitido.
 const OUT_OF_RANGE_MSG: &str = "ERR value is not an integer or out of range";
-const VERSION_NUMBER: &str = "0001";
+
+/// Mensaje de error usado cuando se intenta escribir en una réplica de sólo lectura.
+const READONLY_MSG: &str = "READONLY You can't write against a read only replica.";
+/// Mensaje de error usado cuando se supera `maxmemory` y no se pueden desalojar claves.
+const OOM_MSG: &str = "OOM command not allowed when used memory > 'maxmemory'.";
+/// Mensaje de error usado cuando ya hay una reescritura del AOF en segundo plano en curso.
+const AOF_REWRITE_IN_PROGRESS_MSG: &str =
+    "ERR Background append only file rewriting already in progress";
+/// Tiempo de espera antes de reintentar un guardado automático que falló.
+const BGSAVE_RETRY_DELAY: Duration = Duration::from_secs(5);
+/// Cantidad de claves con expiración que se revisan en cada iteración de la expiración activa.
+const ACTIVE_EXPIRE_KEYS_PER_LOOP: usize = 20;
+/// Porcentaje de claves expiradas en una muestra por encima del cual se revisa otra muestra.
+const ACTIVE_EXPIRE_ACCEPTABLE_STALE: usize = 10;
+/// Tiempo máximo de cada ciclo de expiración activa.
+const ACTIVE_EXPIRE_TIME_LIMIT: Duration = Duration::from_millis(25);
+/// Cantidad de claves entre las que se elige la que se desaloja.
+const MAXMEMORY_SAMPLES: usize = 5;
+
+/// Contenido de un valor de tipo hash: {campo, valor}.
+type Hash = RedisHash;
+/// Base de datos lógica: {clave, valor}, con sus expiraciones.
+type Database = TtlHashMap<Vec<u8>, RedisElement>;
+
+/// Versión con la que se marcan las claves observadas con WATCH que se consideran modificadas
+/// aunque su versión no haya cambiado.
+const TOUCHED_VERSION: u64 = u64::MAX;
+
+/// Devuelve todas las bases de datos, en orden, a partir de la seleccionada (`db`, con número
+/// `selected`) y del resto (`dbs`).
+fn all_databases<'a>(db: &'a Database, dbs: &'a [Database], selected: usize) -> Vec<&'a Database> {
+    dbs.iter()
+        .enumerate()
+        .map(|(index, other)| if index == selected { db } else { other })
+        .collect()
+}
+
+/// Devuelve el nombre del tipo del valor, como lo muestran TYPE y la opción TYPE de SCAN.
+fn type_name(value: &Re) -> &'static str {
+    match value {
+        Re::String(_) | Re::SimpleString(_) => "string",
+        Re::List(_) => "list",
+        Re::Set(_) => "set",
+        Re::Hash(_) => "hash",
+        Re::SortedSet(_) => "zset",
+        Re::Nil => "none",
+    }
+}
+
+/// Convierte bytes a texto, reemplazando los bytes que no son UTF-8 válido. Se usa para loggear
+/// claves y para interpretar valores numéricos.
+fn to_text(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes).to_string()
+}
+
+/// Devuelve hasta `count` miembros distintos del set, elegidos al azar.
+fn random_members(set: &HashSet<Vec<u8>>, count: usize) -> Vec<Vec<u8>> {
+    let mut members: Vec<&Vec<u8>> = set.iter().collect();
+    let count = count.min(members.len());
+    for i in 0..count {
+        let j = i + (random_u64() % (members.len() - i) as u64) as usize;
+        members.swap(i, j);
+    }
+    members.into_iter().take(count).cloned().collect()
+}
+
+/// Elige al azar una clave a desalojar, y su base de datos, entre todas las claves de las bases (o
+/// sólo las que tienen expiración), de forma que todas tengan la misma probabilidad.
+fn random_eviction_candidate(dbs: &[&Database], volatile: bool) -> Option<(usize, Vec<u8>)> {
+    let total: usize = dbs.iter().map(|db| db.sample_len(volatile)).sum();
+    if total == 0 {
+        return None;
+    }
+    let mut position = (random_u64() % total as u64) as usize;
+    for (index, db) in dbs.iter().enumerate() {
+        let len = db.sample_len(volatile);
+        if position < len {
+            return db.sample_keys(1, volatile).pop().map(|key| (index, key));
+        }
+        position -= len;
+    }
+    None
+}
+
+/// Convierte una lista de claves a texto, separándolas con ` - `.
+fn join_text(keys: &[Vec<u8>]) -> String {
+    keys.iter()
+        .map(|key| to_text(key))
+        .collect::<Vec<String>>()
+        .join(" - ")
+}
+
+/// Indica si los argumentos son el comando indicado, sin parámetros. Se usa para reconocer los
+/// MULTI y EXEC que delimitan las transacciones en el AOF y en los comandos del master.
+fn is_command(args: &[Vec<u8>], name: &str) -> bool {
+    args.len() == 1 && args[0].eq_ignore_ascii_case(name.as_bytes())
+}
 
 #[derive(Debug)]
 /// Entidad que representa la Base de Datos Redis dentro de nuestro modelado.
 pub struct Redis {
-    /// Atributo en el cual se guardarán los datos de la DB
-    db: TtlHashMap<String, RedisElement>,
+    /// Base de datos seleccionada, sobre la que operan los comandos.
+    db: Database,
+    /// Bases de datos lógicas del servidor. Mientras una está seleccionada se guarda en `db`, y su
+    /// posición queda vacía.
+    dbs: Vec<Database>,
+    /// Número de la base de datos seleccionada.
+    selected_db: usize,
+    /// Base de datos seleccionada por los comandos recibidos del master, si el servidor es réplica.
+    master_db: usize,
     /// Canal para enviar eventos de loggeo al Logger
     log_sender: Sender<Log>,
     /// Mapa en donde se guardan los Senders de los clientes subscriptos al Command::Monitor
-    monitor_subs_vec: Vec<Sender<Re>>,
+    monitor_subs_vec: Vec<Sender<Reply>>,
     /// Mapa en donde se guarda {id_canal, Vec<Senders de los Usuarios subscriptos a esos canales>}.
-    subscribers: HashMap<String, Vec<(String, Sender<Re>)>>,
+    subscribers: HashMap<String, Vec<(String, Sender<Reply>)>>,
     /// Mapa en donde se guarda {Id_cliente, Vec<Canales a los que esta subscripto>}.
     client_channel: HashMap<String, Vec<String>>,
+    /// Mapa en donde se guarda {patrón, Vec<Senders de los Usuarios subscriptos a ese patrón>}.
+    psubscribers: HashMap<String, Vec<(String, Sender<Reply>)>>,
+    /// Mapa en donde se guarda {Id_cliente, Vec<Patrones a los que esta subscripto>}.
+    client_patterns: HashMap<String, Vec<String>>,
+    /// Mapa en donde se guarda {Id_cliente, Sender} del canal por el que se le envían al cliente
+    /// las confirmaciones y los mensajes de PubSub, mientras tenga alguna suscripción.
+    push_senders: HashMap<String, Sender<Reply>>,
     /// Cantidad de usuarios conectados
     users_connected: u64,
     /// Hora en cuando comenzó el servicio.
     server_time: SystemTime,
     /// Configuración del servidor compartida.
     config: Arc<Mutex<Config>>,
+    /// Mapa en donde se guarda {Id_cliente, Vec<(Base de datos, Clave observada, versión al
+    /// observarla)>}.
+    watched_keys: HashMap<String, Vec<(usize, Vec<u8>, u64)>>,
+    /// Archivo AOF en el que se registran los comandos de escritura, si está habilitado.
+    aof: Option<Aof>,
+    /// Hilo de la última reescritura del AOF en segundo plano, si se lanzó alguna.
+    aof_rewrite: Option<JoinHandle<()>>,
+    /// Encargado de los guardados de la base de datos en archivos RDB.
+    rdb: Rdb,
+    /// Cantidad de cambios realizados desde el último guardado.
+    dirty: u64,
+    /// Cantidad de cambios que había al lanzar el guardado en segundo plano en curso, si lo hay.
+    dirty_before_bgsave: Option<u64>,
+    /// Cantidad de claves borradas por haber expirado.
+    expired_keys: u64,
+    /// Estimación del porcentaje de claves con expiración que ya expiraron, según las muestras
+    /// de la expiración activa.
+    expired_stale_perc: f64,
+    /// Cantidad de claves desalojadas por superar `maxmemory`.
+    evicted_keys: u64,
+    /// Estado de la replicación: réplicas conectadas o conexión con el master.
+    replication: Replication,
+    /// Canal para enviar comandos al hilo de la base de datos, usado por la conexión con el master.
+    db_sender: Option<Sender<(Command, Sender<Response>)>>,
+    /// Estado del cluster, si el servidor corre en modo cluster.
+    cluster: Cluster,
+    /// Clientes bloqueados por BLPOP, BRPOP o BLMOVE, en el orden en que se bloquearon.
+    blocked_clients: VecDeque<BlockedClient>,
+    /// Argumentos con los que se registra el comando en ejecución, si no son los suyos. Por
+    /// ejemplo, SPOP elige los miembros al azar, así que se registra como SREM de los elegidos.
+    rewritten_args: Option<Vec<Vec<u8>>>,
+    /// Escrituras de la transacción en ejecución, si hay alguna. Se propagan todas juntas, entre
+    /// MULTI y EXEC, al terminar la transacción.
+    transaction_writes: Option<Vec<(usize, Vec<Vec<u8>>)>>,
+    /// Comandos recibidos del master desde su último MULTI, que se aplican al recibir el EXEC.
+    master_transaction: Option<Vec<Vec<Vec<u8>>>>,
 }
 
 impl Redis {
@@ -54,17 +221,65 @@ impl Redis {
     /// * Una configuracion compartida
     pub fn new(log_sender: Sender<Log>, config: Arc<Mutex<Config>>) -> Self {
         let db = TtlHashMap::new();
-        let vec_senders: Vec<Sender<Re>> = Vec::new();
+        let vec_senders: Vec<Sender<Reply>> = Vec::new();
+        let (mut cluster, cluster_config_file, databases) = {
+            let config = config.lock().unwrap();
+            let cluster = Cluster::new(
+                config.get_cluster_enabled(),
+                config.get_cluster_announce_ip(),
+                config.get_port().parse().unwrap_or(0),
+            );
+            (
+                cluster,
+                config.get_cluster_config_file(),
+                config.get_databases(),
+            )
+        };
+        // La configuración del cluster se carga antes que los datos, para que el nodo conserve
+        // su identificador y sus slots al reiniciarse.
+        if cluster.is_enabled() {
+            if let Err(e) = cluster.load_config(cluster_config_file) {
+                let _ = log_sender.send(Log::new(
+                    LogLevel::Error,
+                    line!(),
+                    column!(),
+                    file!().to_string(),
+                    format!("Error loading cluster config: {}", e),
+                ));
+            }
+        }
 
         Self {
             db,
+            dbs: (0..databases).map(|_| TtlHashMap::new()).collect(),
+            selected_db: 0,
+            master_db: 0,
             log_sender,
             monitor_subs_vec: vec_senders,
             users_connected: 0,
             subscribers: HashMap::new(),
             client_channel: HashMap::new(),
+            psubscribers: HashMap::new(),
+            client_patterns: HashMap::new(),
+            push_senders: HashMap::new(),
             server_time: SystemTime::now(),
             config,
+            watched_keys: HashMap::new(),
+            aof: None,
+            aof_rewrite: None,
+            rdb: Rdb::new(),
+            dirty: 0,
+            dirty_before_bgsave: None,
+            expired_keys: 0,
+            expired_stale_perc: 0.0,
+            evicted_keys: 0,
+            replication: Replication::new(),
+            db_sender: None,
+            cluster,
+            blocked_clients: VecDeque::new(),
+            rewritten_args: None,
+            transaction_writes: None,
+            master_transaction: None,
         }
     }
 
@@ -73,21 +288,50 @@ impl Redis {
     fn new_for_test() -> Self {
         let db = TtlHashMap::new();
         let (log_sender, _): (Sender<Log>, _) = mpsc::channel();
-        let vec_senders: Vec<Sender<Re>> = Vec::new();
+        let vec_senders: Vec<Sender<Reply>> = Vec::new();
         let config = Arc::new(Mutex::new(Config::new()));
+        let databases = config.lock().unwrap().get_databases();
 
         Self {
             db,
+            dbs: (0..databases).map(|_| TtlHashMap::new()).collect(),
+            selected_db: 0,
+            master_db: 0,
             log_sender,
             monitor_subs_vec: vec_senders,
             users_connected: 0,
             subscribers: HashMap::new(),
             client_channel: HashMap::new(),
+            psubscribers: HashMap::new(),
+            client_patterns: HashMap::new(),
+            push_senders: HashMap::new(),
             server_time: SystemTime::now(),
             config,
+            watched_keys: HashMap::new(),
+            aof: None,
+            aof_rewrite: None,
+            rdb: Rdb::new(),
+            dirty: 0,
+            dirty_before_bgsave: None,
+            expired_keys: 0,
+            expired_stale_perc: 0.0,
+            evicted_keys: 0,
+            replication: Replication::new(),
+            db_sender: None,
+            cluster: Cluster::new(false, "127.0.0.1".to_string(), 8080),
+            blocked_clients: VecDeque::new(),
+            rewritten_args: None,
+            transaction_writes: None,
+            master_transaction: None,
         }
     }
 
+    /// Guarda el canal por el que se envían comandos al hilo de la base de datos. Es necesario para
+    /// que el servidor pueda ser réplica de otro.
+    pub fn set_db_sender(&mut self, db_sender: Sender<(Command, Sender<Response>)>) {
+        self.db_sender = Some(db_sender);
+    }
+
     #[allow(dead_code)]
     /// Metodo utilizado para ejecutar un comando dentro de la Base de datos Redis.
     ///
@@ -95,13 +339,284 @@ impl Redis {
     ///
     /// En caso de error en la ejecución se retornará Err(msg) con el mensaje de error.
     /// En caso de ejecución efectiva del comando se retornará un Response.
+    ///
+    /// Los comandos de escritura ejecutados sin error se cuentan como cambios pendientes de guardar,
+    /// se registran en el AOF si está habilitado y se envían a las réplicas. Si el servidor es una
+    /// réplica de sólo lectura, los comandos de escritura se rechazan.
+    ///
+    /// En modo cluster, los comandos cuyas claves no atiende este nodo se rechazan con una
+    /// redirección (MOVED o ASK) al nodo que corresponde.
+    ///
+    /// Los comandos de los clientes llegan junto a la base de datos que tienen seleccionada, que se
+    /// selecciona antes de ejecutarlos.
+    ///
+    /// Después de cada comando se atiende a los clientes bloqueados cuyas listas tengan elementos.
     pub fn execute(&mut self, command: Command) -> Result<Response, String> {
+        let response = self.execute_single(command);
+        self.serve_blocked_clients();
+        response
+    }
+
+    /// Ejecuta un comando como `execute`, pero sin atender a los clientes bloqueados. Se usa para
+    /// los comandos que se ejecutan como parte de otro, como los de una transacción.
+    fn execute_single(&mut self, command: Command) -> Result<Response, String> {
+        let command = match command {
+            Command::SelectedCommand { db, command } => {
+                self.select_method(db)?;
+                *command
+            }
+            command => command,
+        };
+        let (command, asking) = match command {
+            Command::AskingCommand { command } => (*command, true),
+            command => (command, false),
+        };
         self.notify_monitor(&command);
+        let write_args = command.write_args();
+        if write_args.is_some()
+            && self.replication.is_replica()
+            && self.config.lock().unwrap().get_replica_read_only()
+        {
+            return Err(READONLY_MSG.to_string());
+        }
+        if let Some(redirection) = self.cluster_redirect(&command, asking) {
+            return Err(redirection);
+        }
+        if !self.free_memory_if_needed() && command.denies_oom() {
+            return Err(OOM_MSG.to_string());
+        }
+
+        let response = self.execute_notifying(command);
+        let write_args = self.rewritten_args.take().or(write_args);
+        if let (Ok(_), Some(args)) = (&response, write_args) {
+            self.dirty += 1;
+            self.propagate(self.selected_db, args);
+        }
+        response
+    }
+
+    /// Registra el comando de escritura de la base de datos indicada en el AOF y lo envía a las
+    /// réplicas. Durante una transacción, el comando se guarda para propagarlo al terminarla.
+    fn propagate(&mut self, db: usize, args: Vec<Vec<u8>>) {
+        if let Some(writes) = self.transaction_writes.as_mut() {
+            writes.push((db, args));
+            return;
+        }
+        self.append_aof(db, args.clone());
+        self.replication.feed_db(db, args);
+    }
+
+    /// Propaga las escrituras de una transacción entre MULTI y EXEC, para que al reproducirlas se
+    /// apliquen todas juntas o ninguna. Si la transacción no escribió nada, no se propaga.
+    fn propagate_transaction(&mut self, writes: Vec<(usize, Vec<Vec<u8>>)>) {
+        let (first_db, last_db) = match (writes.first(), writes.last()) {
+            (Some((first_db, _)), Some((last_db, _))) => (*first_db, *last_db),
+            _ => return,
+        };
+        self.propagate(first_db, vec![b"multi".to_vec()]);
+        for (db, args) in writes {
+            self.propagate(db, args);
+        }
+        self.propagate(last_db, vec![b"exec".to_vec()]);
+    }
+
+    /// Selecciona la base de datos indicada, que tiene que existir.
+    fn select_db(&mut self, index: usize) {
+        if index != self.selected_db {
+            std::mem::swap(&mut self.db, &mut self.dbs[self.selected_db]);
+            std::mem::swap(&mut self.db, &mut self.dbs[index]);
+            self.selected_db = index;
+        }
+    }
+
+    /// Devuelve la base de datos indicada, esté seleccionada o no.
+    fn database(&mut self, index: usize) -> &mut Database {
+        if index == self.selected_db {
+            &mut self.db
+        } else {
+            &mut self.dbs[index]
+        }
+    }
+
+    /// Devuelve todas las bases de datos, en orden.
+    fn databases(&self) -> Vec<&Database> {
+        all_databases(&self.db, &self.dbs, self.selected_db)
+    }
+
+    /// Reemplaza todas las bases de datos, seleccionando la 0.
+    fn set_databases(&mut self, dbs: Vec<Database>) {
+        self.dbs = dbs;
+        self.db = std::mem::replace(&mut self.dbs[0], TtlHashMap::new());
+        self.selected_db = 0;
+    }
+
+    /// Verifica que exista la base de datos indicada.
+    fn check_db_index(&self, index: usize) -> Result<(), String> {
+        if index >= self.dbs.len() {
+            return Err("ERR DB index is out of range".to_string());
+        }
+        Ok(())
+    }
+
+    /// Devuelve la memoria aproximada que ocupan los datos de todas las bases.
+    fn used_memory(&mut self) -> usize {
+        (0..self.dbs.len())
+            .map(|index| self.database(index).used_memory())
+            .sum()
+    }
+
+    /// Si la memoria usada supera `maxmemory`, desaloja claves según la política configurada hasta
+    /// volver a estar por debajo del límite. Los desalojos se registran en el AOF y se envían a
+    /// las réplicas como DEL. Las réplicas no desalojan claves, sino que esperan los DEL del
+    /// master. Devuelve false si no se pudo liberar la memoria necesaria.
+    fn free_memory_if_needed(&mut self) -> bool {
+        let (maxmemory, policy, events) = {
+            let config = self.config.lock().unwrap();
+            (
+                config.get_maxmemory(),
+                config.get_maxmemory_policy(),
+                config.get_notify_keyspace_events(),
+            )
+        };
+        if maxmemory == 0 || self.replication.is_replica() {
+            return true;
+        }
+
+        while self.used_memory() as u64 > maxmemory {
+            let (index, key) = match self.eviction_candidate(policy) {
+                Some(candidate) => candidate,
+                None => return false,
+            };
+            self.database(index).remove(&key);
+            self.evicted_keys += 1;
+            self.propagate(index, vec![b"del".to_vec(), key.clone()]);
+            if events.notifies('e') {
+                self.notify_keyspace_event(events, index, "evicted", &key);
+            }
+        }
+        true
+    }
+
+    /// Elige la clave a desalojar, y su base de datos, entre una muestra de claves de cada base,
+    /// según la política. Con las políticas aleatorias, en cambio, se elige primero la base, con
+    /// probabilidad proporcional a su cantidad de candidatas, y luego una clave de ella.
+    fn eviction_candidate(&self, policy: MaxmemoryPolicy) -> Option<(usize, Vec<u8>)> {
+        let dbs = self.databases();
+        let sample = dbs
+            .iter()
+            .enumerate()
+            .flat_map(|(index, db)| {
+                db.sample_keys(MAXMEMORY_SAMPLES, policy.is_volatile())
+                    .into_iter()
+                    .map(move |key| (index, key))
+            })
+            .collect::<Vec<(usize, Vec<u8>)>>()
+            .into_iter();
+        match policy {
+            MaxmemoryPolicy::NoEviction => None,
+            MaxmemoryPolicy::AllkeysLru | MaxmemoryPolicy::VolatileLru => {
+                sample.max_by_key(|(index, key)| dbs[*index].idle_time(key))
+            }
+            MaxmemoryPolicy::AllkeysLfu | MaxmemoryPolicy::VolatileLfu => {
+                sample.min_by_key(|(index, key)| dbs[*index].lfu_counter(key))
+            }
+            MaxmemoryPolicy::AllkeysRandom | MaxmemoryPolicy::VolatileRandom => {
+                random_eviction_candidate(&dbs, policy.is_volatile())
+            }
+            MaxmemoryPolicy::VolatileTtl => {
+                sample.min_by_key(|(index, key)| dbs[*index].expiration(key))
+            }
+        }
+    }
+
+    /// Devuelve la redirección con la que se rechaza el comando si el servidor corre en modo cluster
+    /// y sus claves no pertenecen a un slot que atienda este nodo.
+    fn cluster_redirect(&mut self, command: &Command, asking: bool) -> Option<String> {
+        if !self.cluster.is_enabled() {
+            return None;
+        }
+        let asking = asking || matches!(command, Command::Restore { asking: true, .. });
+        let db = &mut self.db;
+        self.cluster.redirect(&command.keys(), asking, |key| {
+            db.contains_key(&key.to_vec())
+        })
+    }
+
+    /// Ejecuta el comando y publica los eventos de keyspace que haya generado, de las clases
+    /// habilitadas con `notify-keyspace-events`: primero las claves que expiraron y luego las
+    /// modificadas por el comando. Si el comando vació una colección, también se notifica su
+    /// borrado.
+    fn execute_notifying(&mut self, command: Command) -> Result<Response, String> {
+        let events = self.config.lock().unwrap().get_notify_keyspace_events();
+        let keyspace_events: Vec<(char, &str, Vec<u8>)> = command
+            .keyspace_events()
+            .into_iter()
+            .filter(|(class, _, _)| events.notifies(*class))
+            .collect();
+        let before: Vec<(u64, bool)> = keyspace_events
+            .iter()
+            .map(|(_, _, key)| (self.db.get_version(key), self.db.contains_key(key)))
+            .collect();
+
+        let response = self.execute_command(command);
+
+        self.notify_expired(events);
+        if response.is_ok() {
+            let db = self.selected_db;
+            for ((class, event, key), (version, existed)) in keyspace_events.into_iter().zip(before)
+            {
+                if self.db.get_version(&key) == version {
+                    continue;
+                }
+                self.notify_keyspace_event(events, db, event, &key);
+                if existed && class != 'g' && events.notifies('g') && !self.db.contains_key(&key) {
+                    self.notify_keyspace_event(events, db, "del", &key);
+                }
+            }
+        }
+        response
+    }
+
+    /// Cuenta las claves de la base de datos seleccionada que se borraron por haber expirado, y
+    /// publica sus eventos si están habilitados.
+    fn notify_expired(&mut self, events: KeyspaceEvents) {
+        let expired = self.db.take_expired();
+        self.expired_keys += expired.len() as u64;
+        if events.notifies('x') {
+            for key in expired {
+                self.notify_keyspace_event(events, self.selected_db, "expired", &key);
+            }
+        }
+    }
+
+    /// Publica el evento de la clave de la base de datos `db` en los canales
+    /// `__keyspace@<db>__:<clave>` y/o `__keyevent@<db>__:<evento>`, según estén habilitados.
+    fn notify_keyspace_event(
+        &mut self,
+        events: KeyspaceEvents,
+        db: usize,
+        event: &str,
+        key: &[u8],
+    ) {
+        if events.keyspace() {
+            let channel = format!("__keyspace@{}__:{}", db, String::from_utf8_lossy(key));
+            self.publish_method(channel, event.as_bytes().to_vec());
+        }
+        if events.keyevent() {
+            self.publish_method(format!("__keyevent@{}__:{}", db, event), key.to_vec());
+        }
+    }
 
+    /// Ejecuta el comando, sin registrarlo en el AOF ni enviarlo a las réplicas.
+    fn execute_command(&mut self, command: Command) -> Result<Response, String> {
         match command {
             // Server
             Command::Ping => Ok(self.ping_method()),
+            Command::Quit => Ok(Response::Normal(Reply::SimpleString("OK".to_string()))),
+            Command::Select { index } => self.select_method(index),
+            Command::Swapdb { index1, index2 } => self.swapdb_method(index1, index2),
             Command::Flushdb => Ok(self.flushdb_method()),
+            Command::Flushall => Ok(self.flushall_method()),
             Command::Dbsize => Ok(self.dbsize_method()),
             Command::Monitor => self.monitor_method(),
             Command::Info { param } => self.info_method(param),
@@ -109,14 +624,55 @@ impl Redis {
             // System
             Command::Store { path } => self.store_method(path),
             Command::Load { path } => self.load_method(path),
-            Command::ConfigGet => Ok(Response::Normal(Re::List(self.config_get_method()))),
+            Command::LoadAof { path } => self.load_aof_method(path),
+            Command::CheckSavePoints => Ok(self.check_save_points_method()),
+            Command::ActiveExpireCycle => Ok(self.active_expire_cycle_method()),
+            Command::ReplicaFullSync {
+                rdb,
+                replid,
+                offset,
+                link_id,
+            } => self.replica_full_sync_method(rdb, replid, offset, link_id),
+            Command::MasterCommand { args, link_id } => {
+                Ok(self.master_command_method(args, link_id))
+            }
+            Command::ConfigGet => Ok(Response::Normal(Reply::bulk_strings(
+                self.config_get_method().into_iter().map(String::into_bytes),
+            ))),
             Command::ConfigSet { parameter, value } => self.config_set_method(parameter, value),
+            Command::Bgrewriteaof => self.bgrewriteaof_method(),
+            Command::Save => self.save_method(),
+            Command::Bgsave => self.bgsave_method(),
+            Command::Lastsave => Ok(self.lastsave_method()),
+            Command::Replicaof { master } => Ok(self.replicaof_method(master)),
+            Command::Role => Ok(Response::Normal(self.replication.role())),
+            Command::Replconf {
+                client_id,
+                listening_port,
+            } => Ok(self.replconf_method(client_id, listening_port)),
+            Command::Psync {
+                replid,
+                offset,
+                client_id,
+            } => Ok(self.psync_method(replid, offset, client_id)),
+            Command::Cluster { param } => self.cluster_method(param),
+            Command::Asking => Ok(Response::Normal(Reply::SimpleString("OK".to_string()))),
+            Command::ClusterCron => Ok(self.cluster_cron_method()),
+            Command::AskingCommand { command } => self.execute_command(*command),
+            Command::SelectedCommand { db, command } => {
+                self.select_method(db)?;
+                self.execute_command(*command)
+            }
             Command::AddClient => Ok(self.addclient_method()),
             Command::RemoveClient => Ok(self.removeclient_method()),
+            Command::UnblockClient { client_id } => Ok(self.unblock_client_method(client_id)),
 
             // Strings
             Command::Append { key, value } => self.append_method(key, value),
-            Command::Decrby { key, decrement } => self.incrby_method(key, -(decrement as i32)),
+            Command::Decrby { key, decrement } => match decrement.checked_neg() {
+                Some(increment) => self.incrby_method(key, increment),
+                None => Err("ERR decrement would overflow".to_string()),
+            },
             Command::Get { key } => match self.get_method(key) {
                 Ok(re) => Ok(Response::Normal(re)),
                 Err(e) => Err(e),
@@ -126,12 +682,13 @@ impl Redis {
                 Err(e) => Err(e),
             },
             Command::Getset { key, value } => self.getset_method(key, value),
-            Command::Incrby { key, increment } => self.incrby_method(key, increment as i32),
+            Command::Incrby { key, increment } => self.incrby_method(key, increment),
+            Command::Incrbyfloat { key, increment } => self.incrbyfloat_method(key, increment),
             Command::Mget { keys } => Ok(self.mget_method(keys)),
             Command::Mset { key_values } => Ok(self.mset_method(key_values)),
-            Command::Set { key, value } => Ok(Response::Normal(Re::SimpleString(
-                self.set_method(key, value),
-            ))),
+            Command::Msetnx { key_values } => Ok(self.msetnx_method(key_values)),
+            Command::Set { key, value, param } => self.set_with_param_method(key, value, param),
+            Command::Setnx { key, value } => Ok(self.setnx_method(key, value)),
             Command::Strlen { key } => self.strlen_method(key),
 
             // Keys
@@ -139,29 +696,87 @@ impl Redis {
                 key_origin,
                 key_destination,
             } => Ok(self.copy_method(key_origin, key_destination)),
-            Command::Del { keys } => Ok(Response::Normal(Re::String(self.del_method(keys)))),
+            Command::Del { keys } => Ok(Response::Normal(Reply::Integer(self.del_method(keys)))),
             Command::Exists { keys } => Ok(self.exists_method(keys)),
-            Command::Expire { key, ttl } => {
-                Ok(Response::Normal(Re::String(self.expire_method(key, ttl))))
-            }
-            Command::Expireat { key, ttl } => {
-                Ok(Response::Normal(Re::String(self.expireat_method(key, ttl))))
+            Command::Expire { key, ttl } => Ok(Response::Normal(Reply::Integer(
+                self.expire_method(key, ttl),
+            ))),
+            Command::Expireat { key, ttl } => Ok(Response::Normal(Reply::Integer(
+                self.expireat_method(key, ttl),
+            ))),
+            Command::Persist { key } => {
+                Ok(Response::Normal(Reply::Integer(self.persist_method(key))))
             }
-            Command::Persist { key } => Ok(Response::Normal(Re::String(self.persist_method(key)))),
+            Command::Move { key, db } => self.move_method(key, db),
             Command::Rename {
                 key_origin,
                 key_destination,
             } => self.rename_method(key_origin, key_destination),
-            Command::Keys { pattern } => Ok(Response::Normal(Re::List(self.keys_method(pattern)))),
-            Command::Touch { keys } => Ok(Response::Normal(Re::String(self.touch_method(keys)))),
-            Command::Ttl { key } => Ok(Response::Normal(Re::String(self.ttl_method(key)))),
-            Command::Type { key } => Ok(Response::Normal(Re::String(self.type_method(key)))),
-            Command::Sort { key } => self.sort_method(key),
+            Command::Keys { pattern } => Ok(Response::Normal(Reply::bulk_strings(
+                self.keys_method(pattern),
+            ))),
+            Command::Scan {
+                cursor,
+                pattern,
+                count,
+                value_type,
+            } => Ok(self.scan_method(cursor, pattern, count, value_type)),
+            Command::Touch { keys } => {
+                Ok(Response::Normal(Reply::Integer(self.touch_method(keys))))
+            }
+            Command::Ttl { key } => Ok(Response::Normal(Reply::Integer(self.ttl_method(key)))),
+            Command::Type { key } => {
+                Ok(Response::Normal(Reply::SimpleString(self.type_method(key))))
+            }
+            Command::Sort { key, param } | Command::SortRo { key, param } => {
+                self.sort_method(key, param)
+            }
+            Command::Dump { key } => Ok(Response::Normal(self.dump_method(key))),
+            Command::Restore {
+                key,
+                ttl,
+                payload,
+                replace,
+                absttl,
+                ..
+            } => self.restore_method(key, restore_expiration(ttl, absttl), payload, replace),
+            Command::Migrate {
+                host,
+                port,
+                keys,
+                db,
+                timeout,
+                copy,
+                replace,
+            } => self.migrate_method(host, port, keys, db, timeout, copy, replace),
 
             // Lists
+            command @ (Command::Blpop { .. } | Command::Brpop { .. } | Command::Blmove { .. }) => {
+                self.blocking_method(command)
+            }
             Command::Lindex { key, index } => self.lindex_method(key, index),
+            Command::Linsert {
+                key,
+                before,
+                pivot,
+                element,
+            } => self.linsert_method(key, before, pivot, element),
             Command::Llen { key } => self.llen_method(key),
+            Command::Lmove {
+                source,
+                destination,
+                from_left,
+                to_left,
+            } => self.lmove_method(source, destination, from_left, to_left),
+            Command::Lmpop { keys, left, count } => self.lmpop_method(keys, left, count),
             Command::Lpop { key, count } => self.lpop_method(key, count),
+            Command::Lpos {
+                key,
+                element,
+                rank,
+                count,
+                maxlen,
+            } => self.lpos_method(key, element, rank, count, maxlen),
             Command::Lpush { key, value } => self.lpush_method(key, value),
             Command::Lpushx { key, value } => self.lpushx_method(key, value),
             Command::Lrange { key, begin, end } => self.lrange_method(key, begin, end),
@@ -175,6 +790,7 @@ impl Redis {
                 index,
                 element,
             } => self.lset_method(key, index, element),
+            Command::Ltrim { key, begin, end } => self.ltrim_method(key, begin, end),
             Command::Rpop { key, count } => self.rpop_method(key, count),
             Command::Rpush { key, value } => self.rpush_method(key, value),
             Command::Rpushx { key, value } => self.rpushx_method(key, value),
@@ -183,9 +799,110 @@ impl Redis {
             Command::Sadd { key, values } => self.sadd_method(key, values),
             Command::Scard { key } => self.scard_method(key),
             Command::Sismember { key, value } => self.sismember_method(key, value),
+            Command::Sdiff { keys } => self.set_operation_method(keys, SetOperation::Diff),
+            Command::Sinter { keys } => self.set_operation_method(keys, SetOperation::Inter),
+            Command::Sunion { keys } => self.set_operation_method(keys, SetOperation::Union),
+            Command::Sdiffstore { destination, keys } => {
+                self.set_operation_store_method(destination, keys, SetOperation::Diff)
+            }
+            Command::Sinterstore { destination, keys } => {
+                self.set_operation_store_method(destination, keys, SetOperation::Inter)
+            }
+            Command::Sunionstore { destination, keys } => {
+                self.set_operation_store_method(destination, keys, SetOperation::Union)
+            }
+            Command::Sintercard { keys, limit } => self.sintercard_method(keys, limit),
+            Command::Smismember { key, members } => self.smismember_method(key, members),
+            Command::Smove {
+                source,
+                destination,
+                member,
+            } => self.smove_method(source, destination, member),
+            Command::Spop { key, count } => self.spop_method(key, count),
+            Command::Srandmember { key, count } => self.srandmember_method(key, count),
             Command::Smembers { key } => self.smembers_method(key),
+            Command::Sscan {
+                key,
+                cursor,
+                pattern,
+                count,
+            } => self.sscan_method(key, cursor, pattern, count),
             Command::Srem { key, values } => self.srem_method(key, values),
 
+            // Hashes
+            Command::Hdel { key, fields } => self.hdel_method(key, fields),
+            Command::Hexists { key, field } => self.hexists_method(key, field),
+            Command::Hget { key, field } => self.hget_method(key, field),
+            Command::Hgetall { key } => self.hgetall_method(key),
+            Command::Hincrby {
+                key,
+                field,
+                increment,
+            } => self.hincrby_method(key, field, increment),
+            Command::Hincrbyfloat {
+                key,
+                field,
+                increment,
+            } => self.hincrbyfloat_method(key, field, increment),
+            Command::Hkeys { key } => self.hkeys_method(key),
+            Command::Hlen { key } => self.hlen_method(key),
+            Command::Hmget { key, fields } => self.hmget_method(key, fields),
+            Command::Hmset { key, field_values } => self.hmset_method(key, field_values),
+            Command::Hscan {
+                key,
+                cursor,
+                pattern,
+                count,
+            } => self.hscan_method(key, cursor, pattern, count),
+            Command::Hset { key, field_values } => self.hset_method(key, field_values),
+            Command::Hsetnx { key, field, value } => self.hsetnx_method(key, field, value),
+            Command::Hstrlen { key, field } => self.hstrlen_method(key, field),
+            Command::Hvals { key } => self.hvals_method(key),
+
+            // Sorted Sets
+            Command::Zadd {
+                key,
+                members,
+                nx,
+                xx,
+                gt,
+                lt,
+                ch,
+                incr,
+            } => self.zadd_method(key, members, nx, xx, gt, lt, ch, incr),
+            Command::Zcard { key } => self.zcard_method(key),
+            Command::Zcount { key, min, max } => self.zcount_method(key, min, max),
+            Command::Zincrby {
+                key,
+                increment,
+                member,
+            } => self.zincrby_method(key, increment, member),
+            Command::Zinterstore {
+                destination,
+                keys,
+                weights,
+                aggregate,
+            } => self.zstore_method(destination, keys, weights, aggregate, true),
+            Command::Zpopmax { key, count } => self.zpop_method(key, count, true),
+            Command::Zpopmin { key, count } => self.zpop_method(key, count, false),
+            Command::Zrange {
+                key,
+                param,
+                rev,
+                limit,
+                with_scores,
+            } => self.zrange_method(key, param, rev, limit, with_scores),
+            Command::Zrank { key, member } => self.zrank_method(key, member, false),
+            Command::Zrem { key, members } => self.zrem_method(key, members),
+            Command::Zrevrank { key, member } => self.zrank_method(key, member, true),
+            Command::Zscore { key, member } => self.zscore_method(key, member),
+            Command::Zunionstore {
+                destination,
+                keys,
+                weights,
+                aggregate,
+            } => self.zstore_method(destination, keys, weights, aggregate, false),
+
             // Pubsub
             Command::Pubsub { param } => Ok(self.pubsub_method(param)),
             Command::Subscribe {
@@ -197,7 +914,136 @@ impl Redis {
                 channels,
                 client_id,
             } => Ok(self.unsubscribe_method(channels, client_id)),
-            Command::Command => (Ok(Response::Normal(Re::SimpleString("OK".to_string())))),
+            Command::Psubscribe {
+                patterns,
+                client_id,
+            } => Ok(self.psubscribe_method(patterns, client_id)),
+            Command::Punsubscribe {
+                patterns,
+                client_id,
+            } => Ok(self.punsubscribe_method(patterns, client_id)),
+            Command::Command => Ok(Response::Normal(Reply::SimpleString("OK".to_string()))),
+
+            // Transactions
+            Command::Multi => Ok(Response::Normal(Reply::SimpleString("OK".to_string()))),
+            Command::Exec {
+                commands,
+                client_id,
+            } => self.exec_method(commands, client_id),
+            Command::Discard { client_id } => Ok(self.unwatch_method(client_id)),
+            Command::Watch { keys, client_id } => Ok(self.watch_method(keys, client_id)),
+            Command::Unwatch { client_id } => Ok(self.unwatch_method(client_id)),
+        }
+    }
+
+    /// Registra un comando de escritura de la base de datos indicada en el AOF. Si falla la
+    /// escritura, se loggea el error.
+    fn append_aof(&mut self, db: usize, args: Vec<Vec<u8>>) {
+        if let Some(aof) = self.aof.as_mut() {
+            if let Err(e) = aof.append(db, args) {
+                let _ = self.log_sender.send(Log::new(
+                    LogLevel::Error,
+                    line!(),
+                    column!(),
+                    file!().to_string(),
+                    "Error writing AOF: ".to_string() + &e.to_string(),
+                ));
+            }
+        }
+    }
+
+    /// Ejecuta atómicamente los comandos encolados en una transacción. Sus escrituras se registran
+    /// en el AOF y se envían a las réplicas entre MULTI y EXEC.
+    ///
+    /// Si alguna de las claves observadas por el cliente con WATCH fue modificada, la transacción
+    /// no se ejecuta y se devuelve el array nulo. En cualquier caso, se dejan de observar las claves.
+    ///
+    /// # Return value
+    /// Response::Transaction con la respuesta de cada comando, en el orden en que fueron encolados.
+    fn exec_method(
+        &mut self,
+        commands: Option<Vec<Command>>,
+        client_id: String,
+    ) -> Result<Response, String> {
+        let watched = self.watched_keys.remove(&client_id).unwrap_or_default();
+        let touched = watched
+            .iter()
+            .any(|(db, key, version)| self.database(*db).get_version(key) != *version);
+        self.release_watched(watched);
+
+        let commands = match commands {
+            Some(commands) => commands,
+            None => {
+                return Err(
+                    "EXECABORT Transaction discarded because of previous errors.".to_string(),
+                )
+            }
+        };
+        if touched {
+            return Ok(Response::Normal(Reply::NullArray));
+        }
+
+        let mut responses = Vec::new();
+        self.transaction_writes = Some(Vec::new());
+        for command in commands {
+            let response = match self.execute_single(command) {
+                // Dentro de una transacción los comandos bloqueantes no bloquean: responden como
+                // si hubiera vencido el timeout.
+                Ok(Response::Blocked(receiver, _)) => {
+                    self.unblock_client_method(client_id.clone());
+                    receiver
+                        .try_recv()
+                        .unwrap_or(Response::Normal(Reply::NullBulk))
+                }
+                Ok(response) => response,
+                Err(msg) => Response::Error(msg),
+            };
+            responses.push(response);
+        }
+        let writes = self.transaction_writes.take().unwrap_or_default();
+        self.propagate_transaction(writes);
+        Ok(Response::Transaction(responses))
+    }
+
+    /// Observa las claves indicadas, para que la próxima transacción del cliente sólo se ejecute
+    /// si ninguna fue modificada.
+    ///
+    /// # Return value
+    /// Re::SimpleString OK
+    fn watch_method(&mut self, keys: Vec<Vec<u8>>, client_id: String) -> Response {
+        let mut watched = self.watched_keys.remove(&client_id).unwrap_or_default();
+        let db = self.selected_db;
+        for key in keys {
+            if !watched
+                .iter()
+                .any(|(watched_db, watched_key, _)| *watched_db == db && *watched_key == key)
+            {
+                let version = self.db.get_version(&key);
+                self.db.watch(&key);
+                watched.push((db, key, version));
+            }
+        }
+        self.watched_keys.insert(client_id, watched);
+
+        Response::Normal(Reply::SimpleString("OK".to_string()))
+    }
+
+    /// Deja de observar todas las claves observadas por el cliente.
+    ///
+    /// # Return value
+    /// Re::SimpleString OK
+    fn unwatch_method(&mut self, client_id: String) -> Response {
+        let watched = self.watched_keys.remove(&client_id).unwrap_or_default();
+        self.release_watched(watched);
+
+        Response::Normal(Reply::SimpleString("OK".to_string()))
+    }
+
+    /// Deja de observar las claves indicadas, para que se olviden las versiones de las que ya no
+    /// existen y nadie más observa.
+    fn release_watched(&mut self, watched: Vec<(usize, Vec<u8>, u64)>) {
+        for (db, key, _) in watched {
+            self.database(db).unwatch(&key);
         }
     }
 
@@ -211,20 +1057,22 @@ impl Redis {
     /// pueden ser:
     ///
     /// * `Channels` - Indica los canales existentes en PubSub.
-    /// * `ChannelsWithChannel` - Indica si ese canal existe o no.
+    /// * `ChannelsWithPattern` - Indica los canales existentes que coinciden con el patrón.
     /// * `Numsub` - retorna una lista vacía
     /// * `NumsubWithChannel` - Indica la cantidad de usuarios subscriptos a ese canal.
+    /// * `Numpat` - Indica la cantidad de patrones a los que hay usuarios subscriptos.
     fn pubsub_method(&mut self, param: PubSubParam) -> Response {
         Response::Normal(match param {
             PubSubParam::Channels => self.channels_method(),
-            PubSubParam::ChannelsWithChannel(channel) => self.channels_with_channel_method(channel),
+            PubSubParam::ChannelsWithPattern(pattern) => self.channels_with_pattern_method(pattern),
             PubSubParam::Numsub => self.numsub_method(),
             PubSubParam::NumsubWithChannels(channels) => self.numsub_with_channels_method(channels),
+            PubSubParam::Numpat => self.numpat_method(),
         })
     }
 
-    /// Indica los canales existentes en PubSub.
-    fn channels_method(&mut self) -> Re {
+    /// Indica los canales existentes en PubSub: los que tienen al menos un cliente suscripto.
+    fn channels_method(&mut self) -> Reply {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
@@ -234,35 +1082,38 @@ impl Redis {
         ));
 
         let mut vec_response = vec![];
-        for (key, _) in self.subscribers.iter() {
-            vec_response.push(key.to_string());
+        for (key, senders) in self.subscribers.iter() {
+            if !senders.is_empty() {
+                vec_response.push(key.as_bytes().to_vec());
+            }
         }
 
-        Re::List(vec_response)
+        Reply::bulk_strings(vec_response)
     }
 
-    /// Indica si ese canal existe o no en PubSub
-    fn channels_with_channel_method(&mut self, channel: String) -> Re {
+    /// Indica los canales existentes en PubSub que coinciden con el patrón, con la misma sintaxis
+    /// glob que PSUBSCRIBE.
+    fn channels_with_pattern_method(&mut self, pattern: String) -> Reply {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
             column!(),
             file!().to_string(),
-            "Command Pubsub Channels Received".to_string(),
+            "Command Pubsub Channels Received - pattern: ".to_string() + &pattern,
         ));
 
         let mut vec_response = vec![];
-        for (key, _) in self.subscribers.iter() {
-            if channel == *key {
-                vec_response.push(key.to_string());
+        for (key, senders) in self.subscribers.iter() {
+            if !senders.is_empty() && glob_match(pattern.as_bytes(), key.as_bytes()) {
+                vec_response.push(key.as_bytes().to_vec());
             }
         }
 
-        Re::List(vec_response)
+        Reply::bulk_strings(vec_response)
     }
 
     /// Retorna una lista vacía
-    fn numsub_method(&mut self) -> Re {
+    fn numsub_method(&mut self) -> Reply {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
@@ -271,11 +1122,11 @@ impl Redis {
             "Command Pubsub Numsub Received".to_string(),
         ));
 
-        Re::List(vec![])
+        Reply::Array(vec![])
     }
 
     /// Indica la cantidad de usuarios subscriptos a ese canal.
-    fn numsub_with_channels_method(&mut self, channels: Vec<String>) -> Re {
+    fn numsub_with_channels_method(&mut self, channels: Vec<String>) -> Reply {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
@@ -287,16 +1138,40 @@ impl Redis {
         let mut vec_response = vec![];
 
         for channel in channels {
-            if let Some(value) = self.subscribers.get(&channel) {
-                vec_response.push(channel.to_string());
-                vec_response.push(value.len().to_string());
-            } else {
-                vec_response.push(channel.to_string());
-                vec_response.push("0".to_string());
-            }
+            let subscribers = self
+                .subscribers
+                .get(&channel)
+                .map_or(0, |value| value.len());
+            vec_response.push(Reply::BulkString(channel.into_bytes()));
+            vec_response.push(Reply::Integer(subscribers as i64));
         }
 
-        Re::List(vec_response)
+        Reply::Array(vec_response)
+    }
+
+    /// Indica la cantidad de patrones a los que hay al menos un usuario subscripto.
+    fn numpat_method(&mut self) -> Reply {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command Pubsub Numpat Received".to_string(),
+        ));
+
+        let patterns = self
+            .psubscribers
+            .values()
+            .filter(|senders| !senders.is_empty())
+            .count();
+
+        Reply::Integer(patterns as i64)
+    }
+
+    /// Cantidad total de suscripciones del cliente, tanto a canales como a patrones.
+    fn client_subscriptions(&self, client_id: &str) -> usize {
+        self.client_channel.get(client_id).map_or(0, Vec::len)
+            + self.client_patterns.get(client_id).map_or(0, Vec::len)
     }
 
     /// Permite suscribirse a uno o mas canales
@@ -309,26 +1184,22 @@ impl Redis {
             "Command Subscribe Received".to_string(),
         ));
 
-        let (sen, rec): (Sender<Re>, Receiver<Re>) = mpsc::channel();
+        let (sen, response) = self.push_channel(&client_id);
         for channel in channels {
-            let mut vector_sender;
-
-            if let Some(vector) = self.subscribers.get_mut(&channel) {
-                vector_sender = vector.clone();
-                vector_sender.push((client_id.clone(), sen.clone()));
-            } else {
-                vector_sender = vec![(client_id.clone(), sen.clone())];
+            let client_channels = self.client_channel.entry(client_id.clone()).or_default();
+            if !client_channels.contains(&channel) {
+                client_channels.push(channel.clone());
             }
+            let senders = self.subscribers.entry(channel.clone()).or_default();
+            senders.retain(|(client, _)| *client != client_id);
+            senders.push((client_id.clone(), sen.clone()));
 
-            self.subscribers
-                .insert(channel.clone(), vector_sender.to_vec());
-
+            let subscriptions = self.client_subscriptions(&client_id);
             if sen
-                .clone()
-                .send(Re::List(vec![
-                    "subscribe".to_string(),
-                    channel.clone(),
-                    "1".to_string(),
+                .send(Reply::Array(vec![
+                    Reply::BulkString(b"subscribe".to_vec()),
+                    Reply::BulkString(channel.into_bytes()),
+                    Reply::Integer(subscriptions as i64),
                 ]))
                 .is_err()
             {
@@ -340,28 +1211,13 @@ impl Redis {
                     "Error Subscribing".to_string(),
                 ));
             }
-
-            self.set_client_channels(client_id.clone(), channel);
         }
 
-        Response::Stream(rec)
-    }
-
-    fn set_client_channels(&mut self, client_id: String, channel: String) {
-        let mut vector_channels;
-        if let Some(vector) = self.client_channel.get_mut(&client_id) {
-            vector_channels = vector.clone();
-            vector_channels.push(channel);
-        } else {
-            vector_channels = vec![channel];
-        }
-
-        self.client_channel
-            .insert(client_id, vector_channels.to_vec());
+        response
     }
 
     /// Permite Publicar un mensaje en un canal específico.
-    fn publish_method(&mut self, channel: String, msg: String) -> Response {
+    fn publish_method(&mut self, channel: String, msg: Vec<u8>) -> Response {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
@@ -370,23 +1226,22 @@ impl Redis {
             "Command Publish Received".to_string(),
         ));
 
-        if !self.subscribers.contains_key(&channel) {
-            return Response::Normal(Re::String("0".to_string()));
-        }
-
+        let mut receivers = 0;
         if let Some(vector) = self.subscribers.get_mut(&channel) {
-            let mut empty_vec: Vec<(String, Sender<RedisElement>)> = Vec::new();
+            let mut empty_vec: Vec<(String, Sender<Reply>)> = Vec::new();
             for (client, sender) in vector {
                 if sender
-                    .send(Re::List(vec![
-                        "message".to_string(),
-                        channel.clone(),
-                        msg.to_string(),
+                    .send(Reply::Array(vec![
+                        Reply::BulkString(b"message".to_vec()),
+                        Reply::BulkString(channel.as_bytes().to_vec()),
+                        Reply::BulkString(msg.clone()),
                     ]))
                     .is_ok()
                 {
+                    receivers += 1;
                     empty_vec.push((client.to_string(), sender.clone()));
                 } else if let Some(vector) = self.client_channel.get_mut(client) {
+                    self.push_senders.remove(client);
                     let mut vector_update: Vec<String> = Vec::new();
                     for element in vector {
                         if *element != channel {
@@ -398,77 +1253,223 @@ impl Redis {
                 }
             }
 
-            self.subscribers.insert(channel, empty_vec);
+            self.subscribers.insert(channel.clone(), empty_vec);
+        }
+
+        receivers += self.publish_to_patterns(&channel, &msg);
+
+        Response::Normal(Reply::Integer(receivers))
+    }
+
+    /// Envía el mensaje a los clientes subscriptos a los patrones que coinciden con el canal, y
+    /// descarta a los que ya no están conectados. Devuelve la cantidad de mensajes enviados.
+    fn publish_to_patterns(&mut self, channel: &str, msg: &[u8]) -> i64 {
+        let mut receivers = 0;
+        let mut disconnected: Vec<(String, String)> = Vec::new();
+        for (pattern, senders) in self.psubscribers.iter_mut() {
+            if !glob_match(pattern.as_bytes(), channel.as_bytes()) {
+                continue;
+            }
+            senders.retain(|(client, sender)| {
+                let sent = sender
+                    .send(Reply::Array(vec![
+                        Reply::BulkString(b"pmessage".to_vec()),
+                        Reply::BulkString(pattern.as_bytes().to_vec()),
+                        Reply::BulkString(channel.as_bytes().to_vec()),
+                        Reply::BulkString(msg.to_vec()),
+                    ]))
+                    .is_ok();
+                if sent {
+                    receivers += 1;
+                } else {
+                    disconnected.push((client.to_string(), pattern.to_string()));
+                }
+                sent
+            });
+        }
+
+        for (client, pattern) in disconnected {
+            self.push_senders.remove(&client);
+            if let Some(patterns) = self.client_patterns.get_mut(&client) {
+                patterns.retain(|element| *element != pattern);
+            }
         }
+        self.psubscribers.retain(|_, senders| !senders.is_empty());
 
-        Response::Normal(Re::SimpleString("OK".to_string()))
+        receivers
     }
 
-    /// Permite desuscribirse a uno o mas canales
-    fn unsubscribe_method(&mut self, channels: Vec<String>, client_id: String) -> Response {
+    /// Permite suscribirse a uno o mas patrones. Los mensajes publicados en los canales que
+    /// coinciden con el patrón se reciben como `pmessage`.
+    fn psubscribe_method(&mut self, patterns: Vec<String>, client_id: String) -> Response {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
             column!(),
             file!().to_string(),
-            "Command Unsubscribe Received".to_string(),
+            "Command Psubscribe Received".to_string(),
         ));
 
-        let mut return_vec = Vec::new();
-        if let Some(subscribed_channels) = self.client_channel.get_mut(&client_id) {
-            let mut channels_to_keep = Vec::new();
-            let mut channels_to_delete = Vec::new();
-            for sub_channel in subscribed_channels {
-                if channels.iter().any(|i| *i == *sub_channel) {
-                    channels_to_delete.push(sub_channel.to_string());
-                    return_vec.push("unsubscribe".to_string());
-                    return_vec.push(sub_channel.to_string());
-                    return_vec.push("0".to_string());
-                } else {
-                    channels_to_keep.push(sub_channel.to_string());
-                }
+        let (sen, response) = self.push_channel(&client_id);
+        for pattern in patterns {
+            let client_patterns = self.client_patterns.entry(client_id.clone()).or_default();
+            if !client_patterns.contains(&pattern) {
+                client_patterns.push(pattern.clone());
             }
+            let senders = self.psubscribers.entry(pattern.clone()).or_default();
+            senders.retain(|(client, _)| *client != client_id);
+            senders.push((client_id.clone(), sen.clone()));
 
-            self.client_channel
-                .insert(client_id.clone(), channels_to_keep);
-
-            for channel in channels_to_delete {
-                if let Some(senders) = self.subscribers.get(&channel) {
-                    let mut vec_senders: Vec<(String, Sender<Re>)> = Vec::new();
-                    for (client, sender) in senders {
-                        if client_id != *client {
-                            vec_senders.push((client.to_string(), sender.clone()));
-                        }
-                    }
-                    self.subscribers.insert(channel, vec_senders);
-                }
+            let subscriptions = self.client_subscriptions(&client_id);
+            if sen
+                .send(Reply::Array(vec![
+                    Reply::BulkString(b"psubscribe".to_vec()),
+                    Reply::BulkString(pattern.into_bytes()),
+                    Reply::Integer(subscriptions as i64),
+                ]))
+                .is_err()
+            {
+                let _ = self.log_sender.send(Log::new(
+                    LogLevel::Error,
+                    line!(),
+                    column!(),
+                    file!().to_string(),
+                    "Error Subscribing".to_string(),
+                ));
             }
-
-            return Response::Normal(Re::List(return_vec));
         }
 
-        Response::Normal(Re::List(vec![
-            "unsubscribe".to_string(),
-            "nil".to_string(),
-            "0".to_string(),
-        ]))
+        response
     }
 
-    /// Comando interno que es ejecutado cuando un cliente nuevo se conecta.
-    fn addclient_method(&mut self) -> Response {
-        self.users_connected += 1;
-        Response::Normal(RedisElement::String("OK".to_string()))
-    }
+    /// Permite desuscribirse a uno o mas patrones. Sin patrones, se desuscribe de todos.
+    fn punsubscribe_method(&mut self, patterns: Vec<String>, client_id: String) -> Response {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command Punsubscribe Received".to_string(),
+        ));
 
-    /// Comando interno que es ejecutado cuando un cliente nuevo se desconecta.
-    fn removeclient_method(&mut self) -> Response {
-        self.users_connected -= 1;
-        Response::Normal(RedisElement::String("OK".to_string()))
+        let removed = Redis::remove_subscriptions(
+            &mut self.psubscribers,
+            &mut self.client_patterns,
+            &patterns,
+            &client_id,
+        );
+        self.unsubscribe_frames(b"punsubscribe", removed, &client_id)
     }
 
-    /// El comando INFO retorna información y estadísticas sobre el servidor en un formato facil de
-    /// parsear por computadores y facil de leer por humanos.
-    ///
+    /// Permite desuscribirse a uno o mas canales. Sin canales, se desuscribe de todos.
+    fn unsubscribe_method(&mut self, channels: Vec<String>, client_id: String) -> Response {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command Unsubscribe Received".to_string(),
+        ));
+
+        let removed = Redis::remove_subscriptions(
+            &mut self.subscribers,
+            &mut self.client_channel,
+            &channels,
+            &client_id,
+        );
+        self.unsubscribe_frames(b"unsubscribe", removed, &client_id)
+    }
+
+    /// Quita las suscripciones del cliente a los canales (o patrones) indicados, o a todos si no
+    /// se indica ninguno. Devuelve los canales de los que se desuscribió.
+    fn remove_subscriptions(
+        subscribers: &mut HashMap<String, Vec<(String, Sender<Reply>)>>,
+        client_subscriptions: &mut HashMap<String, Vec<String>>,
+        requested: &[String],
+        client_id: &str,
+    ) -> Vec<String> {
+        let subscribed = client_subscriptions.remove(client_id).unwrap_or_default();
+        let (to_delete, to_keep): (Vec<String>, Vec<String>) = subscribed
+            .into_iter()
+            .partition(|channel| requested.is_empty() || requested.contains(channel));
+        if !to_keep.is_empty() {
+            client_subscriptions.insert(client_id.to_string(), to_keep);
+        }
+
+        for channel in &to_delete {
+            if let Some(senders) = subscribers.get_mut(channel) {
+                senders.retain(|(client, _)| client != client_id);
+                if senders.is_empty() {
+                    subscribers.remove(channel);
+                }
+            }
+        }
+
+        to_delete
+    }
+
+    /// Devuelve el canal por el que se envían al cliente sus mensajes de PubSub, creándolo si
+    /// todavía no tiene uno, junto a la respuesta del comando: `Stream` con el canal creado, o
+    /// `Pushed` si el cliente ya lo recibió. Como todos los mensajes del cliente pasan por un mismo
+    /// canal, le llegan en el orden en que se enviaron.
+    fn push_channel(&mut self, client_id: &str) -> (Sender<Reply>, Response) {
+        if let Some(sender) = self.push_senders.get(client_id) {
+            return (sender.clone(), Response::Pushed);
+        }
+        let (sender, receiver): (Sender<Reply>, Receiver<Reply>) = mpsc::channel();
+        self.push_senders
+            .insert(client_id.to_string(), sender.clone());
+        (sender, Response::Stream(receiver))
+    }
+
+    /// Envía las respuestas de UNSUBSCRIBE y PUNSUBSCRIBE: una por cada canal quitado, con la
+    /// cantidad de suscripciones que le quedan al cliente. Si no se quitó ninguno, se responde
+    /// una sola vez sin canal. Si el cliente se quedó sin suscripciones, se cierra su canal.
+    fn unsubscribe_frames(
+        &mut self,
+        kind: &[u8],
+        removed: Vec<String>,
+        client_id: &str,
+    ) -> Response {
+        let (sen, response) = self.push_channel(client_id);
+        let mut remaining = self.client_subscriptions(client_id) + removed.len();
+        if removed.is_empty() {
+            let _ = sen.send(Reply::Array(vec![
+                Reply::BulkString(kind.to_vec()),
+                Reply::NullBulk,
+                Reply::Integer(remaining as i64),
+            ]));
+        }
+        for channel in removed {
+            remaining -= 1;
+            let _ = sen.send(Reply::Array(vec![
+                Reply::BulkString(kind.to_vec()),
+                Reply::BulkString(channel.into_bytes()),
+                Reply::Integer(remaining as i64),
+            ]));
+        }
+        if remaining == 0 {
+            self.push_senders.remove(client_id);
+        }
+
+        response
+    }
+
+    /// Comando interno que es ejecutado cuando un cliente nuevo se conecta.
+    fn addclient_method(&mut self) -> Response {
+        self.users_connected += 1;
+        Response::Normal(Reply::SimpleString("OK".to_string()))
+    }
+
+    /// Comando interno que es ejecutado cuando un cliente nuevo se desconecta.
+    fn removeclient_method(&mut self) -> Response {
+        self.users_connected -= 1;
+        Response::Normal(Reply::SimpleString("OK".to_string()))
+    }
+
+    /// El comando INFO retorna información y estadísticas sobre el servidor en un formato facil de
+    /// parsear por computadores y facil de leer por humanos.
+    ///
     /// # Arguments
     ///
     /// Los parametros que este puede recibir están definidos en el enum `InfoParam`, los cuales
@@ -480,6 +1481,10 @@ impl Redis {
     /// * `Uptime` - Indica el tiempo en el que el servidor está en funcionamiento.
     /// * `ServerTime` - Indica la hora del servidor. (UTC-0).
     /// * `ProcessID` - Indica el processID del proceso en el SO.
+    /// * `Persistence` - Indica el estado de los guardados en archivos RDB.
+    /// * `Stats` - Indica las estadísticas de claves expiradas y desalojadas.
+    /// * `Memory` - Indica la memoria usada y el límite configurado.
+    /// * `Keyspace` - Indica la cantidad de claves, y de claves con expiración, de cada base.
     fn info_method(&mut self, param: InfoParam) -> Result<Response, String> {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
@@ -490,29 +1495,115 @@ impl Redis {
         ));
 
         match param {
-            InfoParam::ConnectedClients => Ok(Response::Normal(RedisElement::String(
-                self.users_connected.to_string(),
+            InfoParam::ConnectedClients => Ok(Response::Normal(Reply::BulkString(
+                self.users_connected.to_string().into_bytes(),
             ))),
-            InfoParam::Port => Ok(Response::Normal(RedisElement::String(
-                self.config.lock().unwrap().get_port(),
+            InfoParam::Port => Ok(Response::Normal(Reply::BulkString(
+                self.config.lock().unwrap().get_port().into_bytes(),
             ))),
-            InfoParam::ConfigFile => Ok(Response::Normal(RedisElement::String(
-                self.config.lock().unwrap().get_configfile(),
+            InfoParam::ConfigFile => Ok(Response::Normal(Reply::BulkString(
+                self.config.lock().unwrap().get_configfile().into_bytes(),
             ))),
             InfoParam::Uptime => self.get_server_uptime(),
-            InfoParam::ServerTime => Ok(Response::Normal(Re::String(timestamp_to_string(
-                SystemTime::now(),
-            )))),
-            InfoParam::ProcessId => Ok(Response::Normal(Re::String(process::id().to_string()))),
+            InfoParam::ServerTime => Ok(Response::Normal(Reply::BulkString(
+                timestamp_to_string(SystemTime::now()).into_bytes(),
+            ))),
+            InfoParam::ProcessId => Ok(Response::Normal(Reply::BulkString(
+                process::id().to_string().into_bytes(),
+            ))),
+            InfoParam::Persistence => Ok(Response::Normal(Reply::BulkString(
+                self.persistence_info().into_bytes(),
+            ))),
+            InfoParam::Stats => Ok(Response::Normal(Reply::BulkString(
+                format!(
+                    "# Stats\r\nexpired_keys:{}\r\nexpired_stale_perc:{:.2}\r\nevicted_keys:{}\r\n",
+                    self.expired_keys, self.expired_stale_perc, self.evicted_keys
+                )
+                .into_bytes(),
+            ))),
+            InfoParam::Memory => Ok(Response::Normal(Reply::BulkString(
+                self.memory_info().into_bytes(),
+            ))),
+            InfoParam::Keyspace => Ok(Response::Normal(Reply::BulkString(
+                self.keyspace_info().into_bytes(),
+            ))),
+        }
+    }
+
+    /// Arma la sección de claves del comando INFO, con una línea por cada base de datos no vacía.
+    fn keyspace_info(&mut self) -> String {
+        let now = SystemTime::now();
+        let mut info = "# Keyspace\r\n".to_string();
+        for (index, db) in self.databases().into_iter().enumerate() {
+            let ttls: Vec<Duration> = db
+                .iter()
+                .filter_map(|(_, _, ttl)| ttl)
+                .map(|ttl| ttl.duration_since(now).unwrap_or_default())
+                .collect();
+            let keys = db.iter().count();
+            if keys == 0 {
+                continue;
+            }
+            let avg_ttl = match ttls.len() {
+                0 => 0,
+                expires => ttls.iter().sum::<Duration>().as_millis() / expires as u128,
+            };
+            info.push_str(&format!(
+                "db{}:keys={},expires={},avg_ttl={}\r\n",
+                index,
+                keys,
+                ttls.len(),
+                avg_ttl
+            ));
         }
+        info
+    }
+
+    /// Arma la sección de memoria del comando INFO.
+    fn memory_info(&mut self) -> String {
+        let (maxmemory, policy) = {
+            let config = self.config.lock().unwrap();
+            (config.get_maxmemory(), config.get_maxmemory_policy())
+        };
+        format!(
+            "# Memory\r\nused_memory:{}\r\nmaxmemory:{}\r\nmaxmemory_policy:{}\r\n",
+            self.used_memory(),
+            maxmemory,
+            policy.as_str()
+        )
+    }
+
+    /// Arma la sección de persistencia del comando INFO.
+    fn persistence_info(&mut self) -> String {
+        self.update_dirty();
+        let last_save_time = self
+            .rdb
+            .last_save_time()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_else(|_| Duration::from_secs(0))
+            .as_secs();
+        let last_bgsave_status = if self.rdb.last_bgsave_ok() {
+            "ok"
+        } else {
+            "err"
+        };
+
+        format!(
+            "# Persistence\r\nrdb_changes_since_last_save:{}\r\nrdb_bgsave_in_progress:{}\r\nrdb_last_save_time:{}\r\nrdb_last_bgsave_status:{}\r\naof_rewrite_in_progress:{}\r\n",
+            self.dirty,
+            self.rdb.bgsave_in_progress() as u8,
+            last_save_time,
+            last_bgsave_status,
+            self.aof_rewrite_in_progress() as u8
+        )
     }
 
     /// Indica el tiempo en el que el servidor está en funcionamiento.
     fn get_server_uptime(&mut self) -> Result<Response, String> {
         let result_time = SystemTime::now().duration_since(self.server_time);
         match result_time {
-            Ok(duration) => Ok(Response::Normal(RedisElement::String(
-                duration.as_secs().to_string(),
+            Ok(duration) => Ok(Response::Normal(Reply::BulkString(
+                duration.as_secs().to_string().into_bytes(),
             ))),
             Err(e) => {
                 let _ = self.log_sender.send(Log::new(
@@ -529,7 +1620,7 @@ impl Redis {
 
     /// Indica cuantos datos están guardados en la DB.
     fn dbsize_method(&mut self) -> Response {
-        Response::Normal(Re::String(self.db.len().to_string()))
+        Response::Normal(Reply::Integer(self.db.len() as i64))
     }
 
     /// Methodo para chequear si la DB responde. En caso que responda se retorna PONG.
@@ -542,7 +1633,7 @@ impl Redis {
             "Command PING Received".to_string(),
         ));
 
-        Response::Normal(Re::SimpleString("PONG".to_string()))
+        Response::Normal(Reply::SimpleString("PONG".to_string()))
     }
 
     /// Metodo utilizado para notificar a los subscriptores de Monitor los nuevos comandos que van a
@@ -550,9 +1641,12 @@ impl Redis {
     fn notify_monitor(&mut self, command: &Command) {
         let command_str = command.as_str().to_string();
         if !command_str.is_empty() {
-            let mut empty_vec: Vec<Sender<Re>> = Vec::new();
+            let mut empty_vec: Vec<Sender<Reply>> = Vec::new();
             for sender in &self.monitor_subs_vec {
-                if sender.send(Re::String(command_str.to_string())).is_ok() {
+                if sender
+                    .send(Reply::SimpleString(command_str.to_string()))
+                    .is_ok()
+                {
                     empty_vec.push(sender.clone());
                 }
             }
@@ -572,11 +1666,11 @@ impl Redis {
             "Command MONITOR Received".to_string(),
         ));
 
-        let (sen, rec): (Sender<Re>, Receiver<Re>) = mpsc::channel();
+        let (sen, rec): (Sender<Reply>, Receiver<Reply>) = mpsc::channel();
 
         let sen_clone = sen.clone();
 
-        let result = sen_clone.send(Re::SimpleString("OK".to_string()));
+        let result = sen_clone.send(Reply::SimpleString("OK".to_string()));
         match result {
             Ok(_) => {
                 self.monitor_subs_vec.push(sen);
@@ -605,35 +1699,102 @@ impl Redis {
             "Command FLUSHDB Received".to_string(),
         ));
 
-        self.db = TtlHashMap::new();
-        Response::Normal(Re::SimpleString("OK".to_string()))
+        self.db.clear();
+        Response::Normal(Reply::SimpleString("OK".to_string()))
+    }
+
+    /// Borra todas las claves de todas las bases de datos.
+    fn flushall_method(&mut self) -> Response {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command FLUSHALL Received".to_string(),
+        ));
+
+        for index in 0..self.dbs.len() {
+            self.database(index).clear();
+        }
+        Response::Normal(Reply::SimpleString("OK".to_string()))
+    }
+
+    /// Selecciona la base de datos sobre la que operan los comandos siguientes de la conexión. En
+    /// modo cluster sólo existe la base de datos 0.
+    fn select_method(&mut self, index: usize) -> Result<Response, String> {
+        self.check_db_index(index)?;
+        if index != 0 && self.cluster.is_enabled() {
+            return Err("ERR SELECT is not allowed in cluster mode".to_string());
+        }
+
+        self.select_db(index);
+        Ok(Response::Normal(Reply::SimpleString("OK".to_string())))
+    }
+
+    /// Intercambia el contenido de dos bases de datos, por lo que los clientes conectados a una
+    /// pasan a ver los datos de la otra. Las claves observadas con WATCH en ambas bases se
+    /// consideran modificadas.
+    fn swapdb_method(&mut self, index1: usize, index2: usize) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            format!("Command SWAPDB Received - dbs: {} {}", index1, index2),
+        ));
+
+        if index1 >= self.dbs.len() {
+            return Err("ERR invalid first DB index".to_string());
+        }
+        if index2 >= self.dbs.len() {
+            return Err("ERR invalid second DB index".to_string());
+        }
+        if self.cluster.is_enabled() {
+            return Err("ERR SWAPDB is not allowed in cluster mode".to_string());
+        }
+
+        std::mem::swap(&mut self.db, &mut self.dbs[self.selected_db]);
+        self.dbs.swap(index1, index2);
+        std::mem::swap(&mut self.db, &mut self.dbs[self.selected_db]);
+        for (db, _, version) in self.watched_keys.values_mut().flatten() {
+            if *db == index1 || *db == index2 {
+                *version = TOUCHED_VERSION;
+            }
+        }
+        // Los clientes observan las bases por su número, así que la cantidad de clientes que
+        // observa cada clave no se intercambia con los datos.
+        if index1 != index2 {
+            let watchers = self.database(index1).replace_watchers(HashMap::new());
+            let watchers = self.database(index2).replace_watchers(watchers);
+            self.database(index1).replace_watchers(watchers);
+        }
+        Ok(Response::Normal(Reply::SimpleString("OK".to_string())))
     }
 
     #[allow(dead_code)]
     /// Copia el valor almacenado en una clave origen a una clave destino.
-    fn copy_method(&mut self, key_origin: String, key_destination: String) -> Response {
+    fn copy_method(&mut self, key_origin: Vec<u8>, key_destination: Vec<u8>) -> Response {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
             column!(),
             file!().to_string(),
             "Command COPY Received - key origin:".to_string()
-                + &*key_origin
+                + &to_text(&key_origin)
                 + " - key destination: "
-                + &*key_destination,
+                + &to_text(&key_destination),
         ));
 
-        let value_origin;
-        match self.db.get(&key_origin) {
-            Some(value) => value_origin = value.clone(),
-            None => return Response::Normal(Re::String("0".to_string())),
+        let value_origin = match self.db.get(&key_origin) {
+            Some(value) => value.clone(),
+            None => return Response::Normal(Reply::Integer(0)),
         };
 
         match self.db.get(&key_destination) {
-            Some(_) => Response::Normal(Re::String("0".to_string())),
+            Some(_) => Response::Normal(Reply::Integer(0)),
             None => {
                 self.db.insert(key_destination, value_origin);
-                Response::Normal(Re::String("1".to_string()))
+                Response::Normal(Reply::Integer(1))
             }
         }
     }
@@ -643,18 +1804,18 @@ impl Redis {
     ///
     /// Se retorna un error si el valor almacenado en esa clave no es un string, porque GET maneja
     /// solamente strings.
-    fn get_method(&mut self, key: String) -> Result<Re, String> {
+    fn get_method(&mut self, key: Vec<u8>) -> Result<Reply, String> {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
             column!(),
             file!().to_string(),
-            "Command GET Received - key: ".to_string() + &*key,
+            "Command GET Received - key: ".to_string() + &to_text(&key),
         ));
 
         match self.db.get(&key) {
             Some(return_value) => match return_value {
-                Re::String(s) => Ok(Re::String(s.to_string())),
+                Re::String(s) => Ok(Reply::BulkString(s.to_vec())),
                 _ => {
                     let _ = self.log_sender.send(Log::new(
                         LogLevel::Error,
@@ -666,24 +1827,24 @@ impl Redis {
                     Err(WRONGTYPE_MSG.to_string())
                 }
             },
-            None => Ok(Re::Nil),
+            None => Ok(Reply::NullBulk),
         }
     }
 
     /// Retorna el largo del valor de tipo string almacenado en una clave. Retorna error si la clave
     /// no almacena un string.
-    fn strlen_method(&mut self, key: String) -> Result<Response, String> {
+    fn strlen_method(&mut self, key: Vec<u8>) -> Result<Response, String> {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
             column!(),
             file!().to_string(),
-            "Command STRLEN Received - key: ".to_string() + &*key,
+            "Command STRLEN Received - key: ".to_string() + &to_text(&key),
         ));
 
         match self.db.get(&key) {
             Some(return_value) => match return_value {
-                Re::String(s) => Ok(Response::Normal(Re::String(s.len().to_string()))),
+                Re::String(s) => Ok(Response::Normal(Reply::Integer(s.len() as i64))),
                 _ => {
                     let _ = self.log_sender.send(Log::new(
                         LogLevel::Error,
@@ -695,20 +1856,20 @@ impl Redis {
                     Err(WRONGTYPE_MSG.to_string())
                 }
             },
-            None => Ok(Response::Normal(Re::String("0".to_string()))),
+            None => Ok(Response::Normal(Reply::Integer(0))),
         }
     }
 
     #[allow(dead_code)]
     /// Atómicamente setea el valor a la clave deseada, y retorna el valor anterior almacenado en la
     /// clave.
-    fn getset_method(&mut self, key: String, value: String) -> Result<Response, String> {
+    fn getset_method(&mut self, key: Vec<u8>, value: Vec<u8>) -> Result<Response, String> {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
             column!(),
             file!().to_string(),
-            "Command GETSET Received - key: ".to_string() + &*key,
+            "Command GETSET Received - key: ".to_string() + &to_text(&key),
         ));
 
         match self.get_method(key.clone()) {
@@ -734,13 +1895,13 @@ impl Redis {
     ///
     /// Si la clave contiene un valor previo, la clave es sobreescrita, independientemente del tipo de dato
     /// contenido (descartando también el valor previo de TTL).
-    fn set_method(&mut self, key: String, value: String) -> String {
+    fn set_method(&mut self, key: Vec<u8>, value: Vec<u8>) -> String {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
             column!(),
             file!().to_string(),
-            "Command SET Received - key: ".to_string() + &*key,
+            "Command SET Received - key: ".to_string() + &to_text(&key),
         ));
 
         self.db.insert(key, Re::String(value));
@@ -748,59 +1909,158 @@ impl Redis {
         "OK".to_string()
     }
 
+    /// Setea el valor de la clave con las opciones de SET: sólo si la clave no existe (NX) o si
+    /// existe (XX), con una expiración (EX, PX, EXAT o PXAT) o manteniendo la que tenía (KEEPTTL).
+    ///
+    /// Devuelve OK, o nil si no se cumplió la condición. Con GET, devuelve el valor anterior de la
+    /// clave (o nil si no existía), y error si el valor anterior no es un string.
+    fn set_with_param_method(
+        &mut self,
+        key: Vec<u8>,
+        value: Vec<u8>,
+        param: SetParam,
+    ) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command SET Received - key: ".to_string() + &to_text(&key),
+        ));
+
+        let old_value = match param.get {
+            true => self.get_method(key.clone())?,
+            false => Reply::NullBulk,
+        };
+        let exists = self.db.contains_key(&key);
+        let skipped = match param.condition {
+            Some(SetCondition::Nx) => exists,
+            Some(SetCondition::Xx) => !exists,
+            None => false,
+        };
+        if skipped {
+            return Ok(Response::Normal(old_value));
+        }
+
+        match param.keep_ttl {
+            true => self.db.insert_keeping_ttl(key.clone(), Re::String(value)),
+            false => self.db.insert(key.clone(), Re::String(value)),
+        }
+        match param.expiration {
+            Some(SetExpiration::Relative(ttl)) => self.db.set_ttl_relative(key, ttl),
+            Some(SetExpiration::Absolute(ttl)) => self.db.set_ttl_absolute(key, ttl),
+            None => None,
+        };
+
+        match param.get {
+            true => Ok(Response::Normal(old_value)),
+            false => Ok(Response::Normal(Reply::SimpleString("OK".to_string()))),
+        }
+    }
+
+    /// Setea el valor de la clave sólo si la clave no existe. Devuelve 1 si se seteó el valor, o 0
+    /// si la clave ya existía.
+    fn setnx_method(&mut self, key: Vec<u8>, value: Vec<u8>) -> Response {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command SETNX Received - key: ".to_string() + &to_text(&key),
+        ));
+
+        if self.db.contains_key(&key) {
+            return Response::Normal(Reply::Integer(0));
+        }
+        self.db.insert(key, Re::String(value));
+        Response::Normal(Reply::Integer(1))
+    }
+
     #[allow(dead_code)]
     /// Incrementa el número almacenado en la clave en un incremento.
     ///
     /// Si la clave no existe, es seteado a 0 antes de realizar la operación. Devuelve error si la clave contiene un valor de
-    /// tipo erróneo o un string que no puede ser representado como entero.
-    fn incrby_method(&mut self, key: String, increment: i32) -> Result<Response, String> {
+    /// tipo erróneo o un string que no puede ser representado como entero de 64 bits, o si la operación produce un
+    /// overflow. La clave mantiene su TTL.
+    fn incrby_method(&mut self, key: Vec<u8>, increment: i64) -> Result<Response, String> {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
             column!(),
             file!().to_string(),
-            "Command INCRBY Received - key: ".to_string() + &*key,
+            "Command INCRBY Received - key: ".to_string() + &to_text(&key),
         ));
 
-        match self.get_method(key.clone()) {
-            Ok(return_value) => match return_value {
-                Re::String(value) => {
-                    let my_int: Result<i32, _> = value.parse();
-                    if my_int.is_err() {
-                        let _ = self.log_sender.send(Log::new(
-                            LogLevel::Error,
-                            line!(),
-                            column!(),
-                            file!().to_string(),
-                            OUT_OF_RANGE_MSG.to_string(),
-                        ));
-
-                        return Err(OUT_OF_RANGE_MSG.to_string());
-                    }
-
-                    let my_int = my_int.unwrap() + increment;
-                    Ok(Response::Normal(Re::String(
-                        self.set_method(key, my_int.to_string()),
-                    )))
-                }
-                Re::Nil => Ok(Response::Normal(Re::String(
-                    self.set_method(key, increment.to_string()),
-                ))),
-                _ => {
+        let current = match self.get_method(key.clone())? {
+            Reply::BulkString(value) => match to_text(&value).parse::<i64>() {
+                Ok(number) => number,
+                Err(_) => {
                     let _ = self.log_sender.send(Log::new(
                         LogLevel::Error,
                         line!(),
                         column!(),
                         file!().to_string(),
-                        WRONGTYPE_MSG.to_string(),
+                        OUT_OF_RANGE_MSG.to_string(),
                     ));
-                    Err(WRONGTYPE_MSG.to_string())
+                    return Err(OUT_OF_RANGE_MSG.to_string());
                 }
             },
-            Err(_) => Ok(Response::Normal(Re::String(
-                self.set_method(key, increment.to_string()),
-            ))),
-        }
+            _ => 0,
+        };
+
+        let value = match current.checked_add(increment) {
+            Some(value) => value,
+            None => {
+                let _ = self.log_sender.send(Log::new(
+                    LogLevel::Error,
+                    line!(),
+                    column!(),
+                    file!().to_string(),
+                    "ERR increment or decrement would overflow".to_string(),
+                ));
+                return Err("ERR increment or decrement would overflow".to_string());
+            }
+        };
+
+        self.db
+            .insert_keeping_ttl(key, Re::String(value.to_string().into_bytes()));
+        Ok(Response::Normal(Reply::Integer(value)))
+    }
+
+    /// Incrementa el número de punto flotante almacenado en la clave en un incremento, y devuelve
+    /// el resultado con el formato de `format_float`.
+    ///
+    /// Si la clave no existe, es seteado a 0 antes de realizar la operación. Devuelve error si la
+    /// clave contiene un valor de tipo erróneo o un string que no es un número, o si el resultado
+    /// no es finito. La clave mantiene su TTL. Como el resultado depende de la precisión de la
+    /// suma, se registra como SET del resultado con KEEPTTL.
+    fn incrbyfloat_method(&mut self, key: Vec<u8>, increment: f64) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command INCRBYFLOAT Received - key: ".to_string() + &to_text(&key),
+        ));
+
+        let current = match self.get_method(key.clone())? {
+            Reply::BulkString(value) => match parse_float(&value) {
+                Some(number) => number,
+                None => return Err("ERR value is not a valid float".to_string()),
+            },
+            _ => 0.0,
+        };
+
+        let value = format_float(current + increment)?.into_bytes();
+        self.db
+            .insert_keeping_ttl(key.clone(), Re::String(value.clone()));
+        self.rewritten_args = Some(vec![
+            b"set".to_vec(),
+            key,
+            value.clone(),
+            b"keepttl".to_vec(),
+        ]);
+        Ok(Response::Normal(Reply::BulkString(value)))
     }
 
     #[allow(dead_code)]
@@ -808,24 +2068,23 @@ impl Redis {
     ///
     /// Para las claves que no contienen valor o
     /// el valor no es un string, se retorna el tipo especial nil.
-    fn mget_method(&mut self, keys: Vec<String>) -> Response {
+    fn mget_method(&mut self, keys: Vec<Vec<u8>>) -> Response {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
             column!(),
             file!().to_string(),
-            "Command MGET Received - keys: ".to_string() + &keys.join(" - "),
+            "Command MGET Received - keys: ".to_string() + &join_text(&keys),
         ));
 
-        let mut elements: Vec<String> = Vec::new();
+        let mut elements = Vec::new();
         for key in keys.iter() {
-            elements.push(
-                self.get_method(key.to_string())
-                    .unwrap_or(Re::Nil)
-                    .to_string(),
-            );
+            elements.push(match self.get_method(key.to_vec()) {
+                Ok(Reply::BulkString(value)) => Reply::BulkString(value),
+                _ => Reply::NullBulk,
+            });
         }
-        Response::Normal(Re::List(elements))
+        Response::Normal(Reply::Array(elements))
     }
 
     #[allow(dead_code)]
@@ -834,7 +2093,7 @@ impl Redis {
     ///
     /// `MSET` es atómica, de modo que todas las claves son actualizadas a la vez. No es posible para
     /// los clientes ver que algunas claves del conjunto fueron modificadas, mientras otras no.
-    fn mset_method(&mut self, key_values: Vec<(String, String)>) -> Response {
+    fn mset_method(&mut self, key_values: Vec<(Vec<u8>, Vec<u8>)>) -> Response {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
@@ -844,30 +2103,51 @@ impl Redis {
         ));
 
         for (key, value) in key_values.iter() {
-            self.set_method(key.to_string(), value.to_string());
+            self.set_method(key.to_vec(), value.to_vec());
         }
 
-        Response::Normal(Re::SimpleString("OK".to_string()))
+        Response::Normal(Reply::SimpleString("OK".to_string()))
+    }
+
+    /// Setea las claves a sus respectivos valores como MSET, pero sólo si ninguna de las claves
+    /// existe: si alguna existe, no se setea ninguna. Devuelve 1 si se setearon las claves, o 0 si
+    /// no.
+    fn msetnx_method(&mut self, key_values: Vec<(Vec<u8>, Vec<u8>)>) -> Response {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command MSETNX Received".to_string(),
+        ));
+
+        if key_values.iter().any(|(key, _)| self.db.contains_key(key)) {
+            return Response::Normal(Reply::Integer(0));
+        }
+        for (key, value) in key_values {
+            self.db.insert(key, Re::String(value));
+        }
+        Response::Normal(Reply::Integer(1))
     }
 
     #[allow(dead_code)]
     /// obtiene el valor y elimina la clave. Es similar a GET, pero adicionalmente elimina la clave.
-    fn getdel_method(&mut self, key: String) -> Result<Re, String> {
+    fn getdel_method(&mut self, key: Vec<u8>) -> Result<Reply, String> {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
             column!(),
             file!().to_string(),
-            "Command GETDEL Received - keys: ".to_string() + &key,
+            "Command GETDEL Received - keys: ".to_string() + &to_text(&key),
         ));
 
         match self.get_method(key.clone()) {
             Ok(return_value) => match return_value {
-                Re::String(_) => {
+                Reply::BulkString(_) => {
                     self.db.remove(&key);
                     Ok(return_value)
                 }
-                Re::Nil => Err("ERR no such key".to_string()),
+                Reply::NullBulk => Err("ERR no such key".to_string()),
                 _ => {
                     let _ = self.log_sender.send(Log::new(
                         LogLevel::Error,
@@ -885,13 +2165,13 @@ impl Redis {
 
     #[allow(dead_code)]
     /// Elimina una clave específica. La clave es ignorada si no existe.
-    fn del_method(&mut self, keys: Vec<String>) -> String {
+    fn del_method(&mut self, keys: Vec<Vec<u8>>) -> i64 {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
             column!(),
             file!().to_string(),
-            "Command DEL Received - keys: ".to_string() + &keys.join(" - "),
+            "Command DEL Received - keys: ".to_string() + &join_text(&keys),
         ));
 
         let mut count = 0;
@@ -901,29 +2181,35 @@ impl Redis {
             }
         }
 
-        count.to_string()
+        count
     }
 
     #[allow(dead_code)]
     /// Si la clave ya existe y es un string, este comando agrega el valor al final del string. Si
     /// no existe, es creada con el string vacío y luego le agrega el valor deseado. En este caso es
     /// similar al comando SET.
-    fn append_method(&mut self, key: String, value: String) -> Result<Response, String> {
+    fn append_method(&mut self, key: Vec<u8>, value: Vec<u8>) -> Result<Response, String> {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
             column!(),
             file!().to_string(),
-            "Command APPEND Received - key: ".to_string() + &*key,
+            "Command APPEND Received - key: ".to_string() + &to_text(&key),
         ));
 
         match self.get_method(key.clone()) {
             Ok(redis_element) => match redis_element {
-                Re::String(s) => {
-                    let value = s + &value;
-                    Ok(Response::Normal(Re::String(self.set_method(key, value))))
+                Reply::BulkString(mut s) => {
+                    s.extend(value);
+                    let len = s.len() as i64;
+                    self.set_method(key, s);
+                    Ok(Response::Normal(Reply::Integer(len)))
+                }
+                Reply::NullBulk => {
+                    let len = value.len() as i64;
+                    self.set_method(key, value);
+                    Ok(Response::Normal(Reply::Integer(len)))
                 }
-                Re::Nil => Ok(Response::Normal(Re::String(self.set_method(key, value)))),
                 _ => {
                     let _ = self.log_sender.send(Log::new(
                         LogLevel::Error,
@@ -949,13 +2235,13 @@ impl Redis {
     }
 
     /// Retorna si la/s clave/s existe/n.
-    fn exists_method(&mut self, keys: Vec<String>) -> Response {
+    fn exists_method(&mut self, keys: Vec<Vec<u8>>) -> Response {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
             column!(),
             file!().to_string(),
-            "Command EXISTS Received - key: ".to_string() + &keys.join(" - "),
+            "Command EXISTS Received - key: ".to_string() + &join_text(&keys),
         ));
 
         let mut count = 0;
@@ -965,66 +2251,103 @@ impl Redis {
             }
         }
 
-        Response::Normal(Re::String(count.to_string()))
+        Response::Normal(Reply::Integer(count))
     }
 
     /// Configura un tiempo de expiración sobre una clave (la clave se dice que es volátil). Luego
     /// de ese tiempo de expiración, la clave es automáticamente eliminada.
-    fn expire_method(&mut self, key: String, ttl: Duration) -> String {
+    fn expire_method(&mut self, key: Vec<u8>, ttl: Duration) -> i64 {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
             column!(),
             file!().to_string(),
-            "Command EXPIRE Received - key: ".to_string() + &*key,
+            "Command EXPIRE Received - key: ".to_string() + &to_text(&key),
         ));
 
         match self.db.set_ttl_relative(key, ttl) {
-            Some(_) => "1".to_string(),
-            None => "0".to_string(),
+            Some(_) => 1,
+            None => 0,
         }
     }
 
     /// Tiene el mismo efecto que EXPIRE, pero en lugar de indicar el número de segundos que
     /// representa el TTL (time to live), toma el tiempo absoluto en el timestamp de Unix (segundos
     /// desde el 1ro de enero de 1970).
-    fn expireat_method(&mut self, key: String, ttl: SystemTime) -> String {
+    fn expireat_method(&mut self, key: Vec<u8>, ttl: SystemTime) -> i64 {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
             column!(),
             file!().to_string(),
-            "Command EXPIREAT Received - key: ".to_string() + &*key,
+            "Command EXPIREAT Received - key: ".to_string() + &to_text(&key),
         ));
 
         match self.db.set_ttl_absolute(key, ttl) {
-            Some(_) => "1".to_string(),
-            None => "0".to_string(),
+            Some(_) => 1,
+            None => 0,
         }
     }
 
     /// Elimina el tiempo de expiración existente en una clave, tornando una clave volátil en
     /// persistente (una clave que no expira, dado que no tiene timeout asociado)
-    fn persist_method(&mut self, key: String) -> String {
+    fn persist_method(&mut self, key: Vec<u8>) -> i64 {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
             column!(),
             file!().to_string(),
-            "Command PERSIST Received - key: ".to_string() + &*key,
+            "Command PERSIST Received - key: ".to_string() + &to_text(&key),
         ));
 
         match self.db.delete_ttl(&key) {
-            Some(_) => "1".to_string(),
-            None => "0".to_string(),
+            Some(_) => 1,
+            None => 0,
+        }
+    }
+
+    /// Mueve la clave de la base de datos seleccionada a la indicada, junto a su expiración.
+    /// Devuelve 1 si se movió, o 0 si no existe la clave o ya existe en la base de destino.
+    fn move_method(&mut self, key: Vec<u8>, db: usize) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command MOVE Received - key: ".to_string() + &to_text(&key),
+        ));
+
+        self.check_db_index(db)?;
+        if self.cluster.is_enabled() {
+            return Err("ERR MOVE is not allowed in cluster mode".to_string());
+        }
+        if db == self.selected_db {
+            return Err("ERR source and destination objects are the same".to_string());
+        }
+        if !self.db.contains_key(&key) || self.database(db).contains_key(&key) {
+            return Ok(Response::Normal(Reply::Integer(0)));
+        }
+
+        let ttl = self.db.expiration(&key);
+        if let Some(value) = self.db.remove(&key) {
+            let destination = self.database(db);
+            destination.insert(key.clone(), value);
+            if let Some(ttl) = ttl {
+                destination.set_ttl_absolute(key.clone(), ttl);
+            }
+        }
+        let events = self.config.lock().unwrap().get_notify_keyspace_events();
+        if events.notifies('g') {
+            self.notify_keyspace_event(events, db, "move_to", &key);
         }
+        Ok(Response::Normal(Reply::Integer(1)))
     }
 
     /// Renombra una clave a un nuevo nombre de clave.
     fn rename_method(
         &mut self,
-        key_origin: String,
-        key_destination: String,
+        key_origin: Vec<u8>,
+        key_destination: Vec<u8>,
     ) -> Result<Response, String> {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
@@ -1032,15 +2355,16 @@ impl Redis {
             column!(),
             file!().to_string(),
             "Command RENAME Received - key origin: ".to_string()
-                + &*key_origin
+                + &to_text(&key_origin)
                 + " - key destination: "
-                + &*key_destination,
+                + &to_text(&key_destination),
         ));
 
         match self.getdel_method(key_origin) {
-            Ok(value) => Ok(Response::Normal(Re::String(
-                self.set_method(key_destination, value.to_string()),
+            Ok(Reply::BulkString(value)) => Ok(Response::Normal(Reply::SimpleString(
+                self.set_method(key_destination, value),
             ))),
+            Ok(_) => Err(WRONGTYPE_MSG.to_string()),
             Err(msg) => {
                 let _ = self.log_sender.send(Log::new(
                     LogLevel::Error,
@@ -1054,75 +2378,182 @@ impl Redis {
         }
     }
 
-    /// Retorna ordenados los elementos de una clave
-    fn sort_method(&mut self, key: String) -> Result<Response, String> {
+    /// Ordena los elementos de la lista, set o sorted set de la clave. Por defecto se ordenan
+    /// numéricamente; con ALPHA, lexicográficamente. Con BY se ordenan según el valor de las claves
+    /// externas que resultan de reemplazar el `*` del patrón por cada elemento (`clave->campo`
+    /// indica un campo de un hash), y con un patrón sin `*` no se ordenan. Con GET se devuelve, por
+    /// cada elemento, el valor de las claves externas de cada patrón (`#` es el elemento).
+    ///
+    /// Con STORE el resultado se guarda como lista en la clave destino y se retorna su largo.
+    fn sort_method(&mut self, key: Vec<u8>, param: SortParam) -> Result<Response, String> {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
             column!(),
             file!().to_string(),
-            "Command SORT Received - key: ".to_string() + &key,
+            "Command SORT Received - key: ".to_string() + &to_text(&key),
         ));
 
-        let collection = match self.db.get(&key) {
-            Some(element) => match element {
-                Re::List(list) => list.clone(),
-                Re::Set(set) => set.clone().into_iter().collect::<Vec<String>>(),
-                _ => {
-                    let _ = self.log_sender.send(Log::new(
-                        LogLevel::Error,
-                        line!(),
-                        column!(),
-                        file!().to_string(),
-                        WRONGTYPE_MSG.to_string(),
-                    ));
-                    return Err(WRONGTYPE_MSG.to_string());
-                }
-            },
-            None => {
-                return Ok(Response::Normal(Re::List(vec![])));
+        if self.cluster.is_enabled() {
+            if param.by.as_ref().is_some_and(|_| param.sorts()) {
+                return Err("ERR BY option of SORT denied in Cluster mode.".to_string());
             }
-        };
-        let transformed_collection: Result<Vec<f64>, String> = collection
-            .iter()
-            .map(|a| a.parse::<f64>())
-            .collect::<Result<Vec<_>, _>>()
-            .map_err(|_| "ERR One or more scores can't be converted into double".to_string());
-        let mut transformed_collection = match transformed_collection {
-            Ok(vec) => vec,
-            Err(msg) => {
+            if param.get.iter().any(|pattern| pattern != b"#") {
+                return Err("ERR GET option of SORT denied in Cluster mode.".to_string());
+            }
+        }
+
+        let (mut elements, from_set, from_sorted_set) = match self.db.get(&key) {
+            Some(Re::List(list)) => (list.iter().cloned().collect(), false, false),
+            Some(Re::Set(set)) => (set.iter().cloned().collect(), true, false),
+            Some(Re::SortedSet(sorted_set)) => (
+                sorted_set
+                    .iter()
+                    .map(|(member, _)| member.to_vec())
+                    .collect(),
+                false,
+                true,
+            ),
+            Some(_) => {
                 let _ = self.log_sender.send(Log::new(
                     LogLevel::Error,
                     line!(),
                     column!(),
                     file!().to_string(),
-                    msg.to_string(),
+                    WRONGTYPE_MSG.to_string(),
                 ));
-                return Err(msg);
+                return Err(WRONGTYPE_MSG.to_string());
             }
+            None => (vec![], false, false),
         };
 
-        transformed_collection.sort_by(|a, b| a.partial_cmp(b).unwrap());
-        let sorted = transformed_collection
-            .iter()
-            .map(|a| a.to_string())
-            .collect();
-        Ok(Response::Normal(Re::List(sorted)))
+        // Los sets no tienen orden, así que para guardarlos se ordenan igual.
+        let sorts = param.sorts() || (from_set && param.store.is_some());
+        if sorts {
+            let by = param.by.as_ref().filter(|_| param.sorts());
+            let mut weighted = vec![];
+            for element in elements {
+                let weight = match by {
+                    Some(pattern) => self.sort_lookup(pattern, &element),
+                    None => Some(element.to_vec()),
+                };
+                weighted.push((weight, element));
+            }
+            let mut sorted = if param.alpha || (by.is_none() && !param.sorts()) {
+                weighted.sort_by(|(a, a_element), (b, b_element)| {
+                    a.cmp(b).then_with(|| a_element.cmp(b_element))
+                });
+                weighted
+                    .into_iter()
+                    .map(|(_, element)| element)
+                    .collect::<Vec<Vec<u8>>>()
+            } else {
+                let mut scored = vec![];
+                for (weight, element) in weighted {
+                    let score = match weight {
+                        Some(weight) => to_text(&weight).parse::<f64>().map_err(|_| {
+                            "ERR One or more scores can't be converted into double".to_string()
+                        })?,
+                        None => 0.0,
+                    };
+                    scored.push((score, element));
+                }
+                scored.sort_by(|(a, a_element), (b, b_element)| {
+                    a.total_cmp(b).then_with(|| a_element.cmp(b_element))
+                });
+                scored.into_iter().map(|(_, element)| element).collect()
+            };
+            if param.desc {
+                sorted.reverse();
+            }
+            elements = sorted;
+        } else if from_sorted_set && param.desc {
+            elements.reverse();
+        }
+
+        if let Some((offset, count)) = param.limit {
+            let offset = offset.max(0) as usize;
+            let count = if count < 0 {
+                elements.len()
+            } else {
+                count as usize
+            };
+            elements = elements.into_iter().skip(offset).take(count).collect();
+        }
+
+        let mut values = vec![];
+        for element in elements.iter() {
+            for pattern in param.get.iter() {
+                values.push(match pattern.as_slice() {
+                    b"#" => Some(element.to_vec()),
+                    _ => self.sort_lookup(pattern, element),
+                });
+            }
+        }
+
+        match param.store {
+            Some(destination) => {
+                let list: Vec<Vec<u8>> = if param.get.is_empty() {
+                    elements
+                } else {
+                    values
+                        .into_iter()
+                        .map(|value| value.unwrap_or_default())
+                        .collect()
+                };
+                let len = list.len();
+                self.db.remove(&destination);
+                if len > 0 {
+                    self.db.insert(destination, Re::List(list.into()));
+                }
+                Ok(Response::Normal(Reply::Integer(len as i64)))
+            }
+            None if param.get.is_empty() => Ok(Response::Normal(Reply::bulk_strings(elements))),
+            None => Ok(Response::Normal(Reply::Array(
+                values
+                    .into_iter()
+                    .map(|value| value.map_or(Reply::NullBulk, Reply::BulkString))
+                    .collect(),
+            ))),
+        }
+    }
+
+    /// Devuelve el valor de la clave externa que resulta de reemplazar el primer `*` del patrón
+    /// por el elemento, usado por las opciones BY y GET de SORT. Si después del `*` el patrón
+    /// tiene `->`, se devuelve el campo indicado del hash. Si el patrón no tiene `*` o la clave no
+    /// existe o no es del tipo esperado, no se devuelve nada.
+    fn sort_lookup(&mut self, pattern: &[u8], element: &[u8]) -> Option<Vec<u8>> {
+        let star = pattern.iter().position(|byte| *byte == b'*')?;
+        let field_start = pattern[star..]
+            .windows(2)
+            .position(|window| window == b"->")
+            .map(|position| star + position)
+            .filter(|position| position + 2 < pattern.len());
+        let key_pattern = &pattern[..field_start.unwrap_or(pattern.len())];
+
+        let mut key = key_pattern[..star].to_vec();
+        key.extend_from_slice(element);
+        key.extend_from_slice(&key_pattern[star + 1..]);
+        match (self.db.get(&key), field_start) {
+            (Some(Re::String(value)), None) => Some(value.to_vec()),
+            (Some(Re::Hash(hash)), Some(position)) => hash.get(&pattern[position + 2..]).cloned(),
+            _ => None,
+        }
     }
 
     /// Actualiza el valor de último acceso a la clave.
-    fn touch_method(&mut self, keys: Vec<String>) -> String {
+    fn touch_method(&mut self, keys: Vec<Vec<u8>>) -> i64 {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
             column!(),
             file!().to_string(),
-            "Command TOUCH Received - keys: ".to_string() + &keys.join(" - "),
+            "Command TOUCH Received - keys: ".to_string() + &join_text(&keys),
         ));
 
         let mut count = 0;
         for key in keys.iter() {
-            match self.db.update_last_access(&key.to_string()) {
+            match self.db.update_last_access(key) {
                 None => (),
                 Some(time) => {
                     count += 1;
@@ -1132,80 +2563,188 @@ impl Redis {
                         line!(),
                         column!(),
                         file!().to_string(),
-                        format!("Key {} previous access: {} secs ago.", &key, &time),
+                        format!("Key {} previous access: {} secs ago.", to_text(key), &time),
                     ));
                 }
             }
         }
 
-        count.to_string()
+        count
     }
 
     /// Retorna el tiempo que le queda a una clave para que se cumpla su timeout. Permite a un
     /// cliente Redis conocer cuántos segundos le quedan a una clave como parte del dataset.
-    fn ttl_method(&mut self, key: String) -> String {
+    fn ttl_method(&mut self, key: Vec<u8>) -> i64 {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
             column!(),
             file!().to_string(),
-            "Command TTL Received - key: ".to_string() + &*key,
+            "Command TTL Received - key: ".to_string() + &to_text(&key),
         ));
 
         match self.db.get_ttl(&key) {
             Some(value) => {
                 if value == Duration::from_secs(0) {
-                    return "-1".to_string();
+                    return -1;
                 }
-                value.as_secs().to_string()
+                value.as_secs() as i64
             }
-            None => "-2".to_string(),
+            None => -2,
         }
     }
 
     /// Retorna un string que representa el tipo de valor almacenado en una clave. Los tipos que
-    /// puede retornar son: `string`, `list`, `set` (no consideramos los tipos de datos que no se
-    /// implementan en el proyecto).
-    fn type_method(&mut self, key: String) -> String {
+    /// puede retornar son: `string`, `list`, `set`, `hash`, `zset` (no consideramos los tipos de
+    /// datos que no se implementan en el proyecto).
+    fn type_method(&mut self, key: Vec<u8>) -> String {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command TYPE Received - key: ".to_string() + &to_text(&key),
+        ));
+
+        self.db.get(&key).map_or("none", type_name).to_string()
+    }
+
+    /// Devuelve el valor de la clave serializado en el formato de Redis, para recrearlo con
+    /// RESTORE. Si la clave no existe devuelve Nil.
+    fn dump_method(&mut self, key: Vec<u8>) -> Reply {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
             column!(),
             file!().to_string(),
-            "Command TYPE Received - key: ".to_string() + &*key,
+            "Command DUMP Received - key: ".to_string() + &to_text(&key),
         ));
 
         match self.db.get(&key) {
-            Some(return_value) => match return_value {
-                Re::String(_) => "string".to_string(),
-                Re::List(_) => "list".to_string(),
-                Re::Set(_) => "set".to_string(),
-                Re::Nil => "none".to_string(),
-                Re::SimpleString(_) => "string".to_string(),
-            },
-            None => "none".to_string(),
+            Some(value) => Reply::BulkString(TtlHashMap::dump(value)),
+            None => Reply::NullBulk,
         }
     }
 
-    /// Retorna el elemento de la posición index en la lista almacenada en la clave indicada.
-    ///
-    /// El índice comienza en 0. Los valores negativos se pueden usar para determinar elementos desde
-    /// el final de la lista: -1 es el último elemento, -2 es el anteúlitmo, y así.
-    ///
-    /// Retorna error si el valor de esa clave no es una lista.
-    fn lindex_method(&mut self, key: String, index: i32) -> Result<Response, String> {
+    /// Crea la clave con el valor serializado por DUMP y la expiración indicada. Si la clave ya
+    /// existe, falla salvo que se indique REPLACE. Si la expiración ya pasó, la clave no se crea.
+    fn restore_method(
+        &mut self,
+        key: Vec<u8>,
+        expiration: Option<SystemTime>,
+        payload: Vec<u8>,
+        replace: bool,
+    ) -> Result<Response, String> {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
             column!(),
             file!().to_string(),
-            "Command LINDEX Received - key: ".to_string() + &*key,
+            "Command RESTORE Received - key: ".to_string() + &to_text(&key),
         ));
 
-        match self.db.get_mut(&key) {
-            Some(value) => match value {
-                Re::List(value) => {
-                    let len_value = value.len() as i32;
+        if !replace && self.db.contains_key(&key) {
+            return Err("BUSYKEY Target key name already exists.".to_string());
+        }
+        let value = TtlHashMap::restore(payload)?;
+        self.db.remove(&key);
+        match expiration {
+            Some(expiration) if expiration.elapsed().is_ok() => (),
+            Some(expiration) => {
+                self.db.insert(key.clone(), value);
+                self.db.set_ttl_absolute(key, expiration);
+            }
+            None => self.db.insert(key, value),
+        }
+        Ok(Response::Normal(Reply::SimpleString("OK".to_string())))
+    }
+
+    /// Mueve las claves a otro nodo: las envía con RESTORE-ASKING a la base de datos `db` y, si el
+    /// nodo las recibió, las borra de la base de datos (salvo que se indique COPY). Si ninguna
+    /// clave existe devuelve NOKEY.
+    #[allow(clippy::too_many_arguments)]
+    fn migrate_method(
+        &mut self,
+        host: String,
+        port: u16,
+        keys: Vec<Vec<u8>>,
+        db: usize,
+        timeout: Duration,
+        copy: bool,
+        replace: bool,
+    ) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            format!(
+                "Command MIGRATE Received - {}:{} keys: {}",
+                host,
+                port,
+                join_text(&keys)
+            ),
+        ));
+
+        let mut migrated = vec![];
+        let mut commands = vec![];
+        for key in keys {
+            // get_ttl devuelve una duración 0 para las claves persistentes, que RESTORE también
+            // interpreta como una clave sin expiración.
+            let ttl = self
+                .db
+                .get_ttl(&key)
+                .map_or(0, |ttl| ttl.as_millis())
+                .to_string();
+            if let Some(value) = self.db.get(&key) {
+                let mut command = vec![
+                    b"restore-asking".to_vec(),
+                    key.clone(),
+                    ttl.into_bytes(),
+                    TtlHashMap::dump(value),
+                ];
+                if replace {
+                    command.push(b"replace".to_vec());
+                }
+                commands.push(command);
+                migrated.push(key);
+            }
+        }
+        if migrated.is_empty() {
+            return Ok(Response::Normal(Reply::SimpleString("NOKEY".to_string())));
+        }
+        if db != 0 {
+            commands.insert(0, vec![b"select".to_vec(), db.to_string().into_bytes()]);
+        }
+
+        send_commands(&host, port, timeout, commands)?;
+        if !copy {
+            for key in migrated {
+                self.db.remove(&key);
+            }
+        }
+        Ok(Response::Normal(Reply::SimpleString("OK".to_string())))
+    }
+
+    /// Retorna el elemento de la posición index en la lista almacenada en la clave indicada.
+    ///
+    /// El índice comienza en 0. Los valores negativos se pueden usar para determinar elementos desde
+    /// el final de la lista: -1 es el último elemento, -2 es el anteúlitmo, y así.
+    ///
+    /// Retorna error si el valor de esa clave no es una lista.
+    fn lindex_method(&mut self, key: Vec<u8>, index: i32) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command LINDEX Received - key: ".to_string() + &to_text(&key),
+        ));
+
+        match self.db.get(&key) {
+            Some(value) => match value {
+                Re::List(value) => {
+                    let len_value = value.len() as i32;
                     let mut position: i32 = index;
 
                     if index < 0 {
@@ -1214,9 +2753,9 @@ impl Redis {
 
                     match value.get(position as usize) {
                         Some(saved_value) => {
-                            Ok(Response::Normal(Re::String(saved_value.to_string())))
+                            Ok(Response::Normal(Reply::BulkString(saved_value.to_vec())))
                         }
-                        None => Ok(Response::Normal(Re::Nil)),
+                        None => Ok(Response::Normal(Reply::NullBulk)),
                     }
                 }
                 _ => {
@@ -1230,7 +2769,7 @@ impl Redis {
                     Err(WRONGTYPE_MSG.to_string())
                 }
             },
-            None => Ok(Response::Normal(Re::Nil)),
+            None => Ok(Response::Normal(Reply::NullBulk)),
         }
     }
 
@@ -1239,18 +2778,18 @@ impl Redis {
     /// Si la clave no existe, se interpreta
     /// como lista vacía, retornando 0. Se retorna error si el valor almacenado en la clave no es
     /// una lista.
-    fn llen_method(&mut self, key: String) -> Result<Response, String> {
+    fn llen_method(&mut self, key: Vec<u8>) -> Result<Response, String> {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
             column!(),
             file!().to_string(),
-            "Command LLEN Received - key: ".to_string() + &*key,
+            "Command LLEN Received - key: ".to_string() + &to_text(&key),
         ));
 
-        match self.db.get_mut(&key) {
+        match self.db.get(&key) {
             Some(value) => match value {
-                Re::List(value) => Ok(Response::Normal(Re::String(value.len().to_string()))),
+                Re::List(value) => Ok(Response::Normal(Reply::Integer(value.len() as i64))),
                 _ => {
                     let _ = self.log_sender.send(Log::new(
                         LogLevel::Error,
@@ -1262,157 +2801,380 @@ impl Redis {
                     Err(WRONGTYPE_MSG.to_string())
                 }
             },
-            None => Ok(Response::Normal(Re::String("0".to_string()))),
+            None => Ok(Response::Normal(Reply::Integer(0))),
         }
     }
 
-    /// Elimina y retorna el primer elemento de la lista almacenada en la clave. Se puede indicar un
-    /// parámetro adicional `count` para indicar obtener esa cantidad de elementos.
-    fn lpop_method(&mut self, key: String, count: usize) -> Result<Response, String> {
+    /// Saca un elemento de un extremo de la lista `source` y lo agrega en un extremo de la lista
+    /// `destination`, que puede ser la misma. Retorna el elemento, o Nil si `source` no existe.
+    ///
+    /// Se retorna error si alguna de las claves almacena un elemento que no es una lista.
+    fn lmove_method(
+        &mut self,
+        source: Vec<u8>,
+        destination: Vec<u8>,
+        from_left: bool,
+        to_left: bool,
+    ) -> Result<Response, String> {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
             column!(),
             file!().to_string(),
-            "Command LPOP Received - key: ".to_string() + &*key,
+            "Command LMOVE Received - source: ".to_string()
+                + &to_text(&source)
+                + " - destination: "
+                + &to_text(&destination),
         ));
 
-        match self.db.get_mut(&key) {
-            Some(value) => match value {
-                Re::List(value) => {
-                    let return_value: Vec<String>;
-                    let vector_to_save: Vec<String>;
-                    if count == 0 && !value.clone().is_empty() {
-                        return_value = Vec::from(value.get(..=count).unwrap());
-                        vector_to_save = Vec::from(value.get(count + 1..).unwrap());
-                    } else {
-                        let qty = match count {
-                            x if x >= value.len() => value.len(),
-                            _ => count,
-                        };
-                        return_value = Vec::from(value.get(..qty).unwrap());
-                        vector_to_save = Vec::from(value.get(qty..).unwrap());
-                    }
+        if !self.list_ready(&source)? {
+            return Ok(Response::Normal(Reply::NullBulk));
+        }
+        self.list_ready(&destination)?;
+
+        let element = match self.pop_elements(&source, 1, from_left)? {
+            Some(mut popped) if !popped.is_empty() => popped.remove(0),
+            _ => return Ok(Response::Normal(Reply::NullBulk)),
+        };
+        if self.list_mut(&destination)?.is_none() {
+            self.db
+                .insert(destination.to_vec(), Re::List(VecDeque::new()));
+        }
+        if let Some(list) = self.list_mut(&destination)? {
+            if to_left {
+                list.push_front(element.to_vec());
+            } else {
+                list.push_back(element.to_vec());
+            }
+        }
+        self.db.mark_modified(&destination);
+        Ok(Response::Normal(Reply::BulkString(element)))
+    }
 
-                    self.db.insert(key, Re::List(vector_to_save));
+    /// Indica si la clave almacena una lista con elementos. Se retorna error si la clave almacena
+    /// un elemento que no es una lista.
+    fn list_ready(&mut self, key: &[u8]) -> Result<bool, String> {
+        match self.db.get(&key.to_vec()) {
+            Some(Re::List(list)) => Ok(!list.is_empty()),
+            Some(_) => Err(WRONGTYPE_MSG.to_string()),
+            None => Ok(false),
+        }
+    }
 
-                    if return_value.len() == 1 && return_value.first().is_some() {
-                        let value = return_value.first();
-                        return Ok(Response::Normal(Re::String(value.unwrap().to_string())));
-                    }
+    /// Ejecuta BLPOP, BRPOP o BLMOVE. Si alguna de las listas tiene elementos, se comporta como
+    /// LPOP, RPOP o LMOVE, y así se propaga. Si no, el cliente queda bloqueado hasta que otro
+    /// comando agregue elementos a alguna de ellas o hasta que venza el timeout: la respuesta se
+    /// envía luego por el canal de `Response::Blocked`, sin detener a la base de datos.
+    ///
+    /// BLPOP y BRPOP retornan la clave y el elemento, y NullArray si vence el timeout. BLMOVE
+    /// retorna el elemento, y Nil si vence el timeout.
+    fn blocking_method(&mut self, command: Command) -> Result<Response, String> {
+        let (timeout, client_id) = match &command {
+            Command::Blpop {
+                timeout, client_id, ..
+            }
+            | Command::Brpop {
+                timeout, client_id, ..
+            }
+            | Command::Blmove {
+                timeout, client_id, ..
+            } => (*timeout, client_id.to_string()),
+            _ => (None, String::new()),
+        };
+        let keys: Vec<Vec<u8>> = command.keys().into_iter().map(<[u8]>::to_vec).collect();
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            format!(
+                "Command {} Received - keys: {}",
+                command.as_str().to_uppercase(),
+                join_text(&keys)
+            ),
+        ));
+
+        if self.replication.is_replica() && self.config.lock().unwrap().get_replica_read_only() {
+            return Err(READONLY_MSG.to_string());
+        }
+        if let Some(response) = self.try_blocking_pop(&command) {
+            return response;
+        }
+
+        let (sender, receiver) = mpsc::channel();
+        self.blocked_clients.push_back(BlockedClient {
+            client_id,
+            db: self.selected_db,
+            command,
+            sender,
+        });
+        Ok(Response::Blocked(receiver, timeout))
+    }
+
+    /// Atiende el comando bloqueante con la primera de sus listas que tenga elementos, ejecutando
+    /// LPOP, RPOP o LMOVE. Retorna None si todas las listas están vacías.
+    fn try_blocking_pop(&mut self, command: &Command) -> Option<Result<Response, String>> {
+        let (keys, left) = match command {
+            Command::Blpop { keys, .. } => (keys, true),
+            Command::Brpop { keys, .. } => (keys, false),
+            Command::Blmove {
+                source,
+                destination,
+                from_left,
+                to_left,
+                ..
+            } => {
+                return match self.list_ready(source) {
+                    Ok(true) => Some(self.execute_single(Command::Lmove {
+                        source: source.to_vec(),
+                        destination: destination.to_vec(),
+                        from_left: *from_left,
+                        to_left: *to_left,
+                    })),
+                    Ok(false) => None,
+                    Err(msg) => Some(Err(msg)),
+                }
+            }
+            _ => return None,
+        };
+
+        for key in keys {
+            match self.list_ready(key) {
+                Ok(true) => {}
+                Ok(false) => continue,
+                Err(msg) => return Some(Err(msg)),
+            }
+            let pop = if left {
+                Command::Lpop {
+                    key: key.to_vec(),
+                    count: 0,
+                }
+            } else {
+                Command::Rpop {
+                    key: key.to_vec(),
+                    count: 0,
+                }
+            };
+            let response = self.execute_single(pop).map(|response| match response {
+                Response::Normal(Reply::BulkString(element)) => {
+                    Response::Normal(Reply::Array(vec![
+                        Reply::BulkString(key.to_vec()),
+                        Reply::BulkString(element),
+                    ]))
+                }
+                response => response,
+            });
+            return Some(response);
+        }
+        None
+    }
+
+    /// Atiende, en el orden en que se bloquearon, a los clientes bloqueados cuyas listas tienen
+    /// elementos. Como atender a un cliente con BLMOVE agrega elementos a otra lista, se vuelve a
+    /// empezar hasta que no quede ningún cliente para atender.
+    fn serve_blocked_clients(&mut self) {
+        if self.blocked_clients.is_empty() {
+            return;
+        }
 
-                    match return_value.len() {
-                        x if x > 0 => Ok(Response::Normal(Re::List(return_value.to_vec()))),
-                        _ => Ok(Response::Normal(Re::Nil)),
+        let selected_db = self.selected_db;
+        let mut position = 0;
+        while position < self.blocked_clients.len() {
+            self.select_db(self.blocked_clients[position].db);
+            let keys: Vec<Vec<u8>> = self.blocked_clients[position]
+                .keys()
+                .into_iter()
+                .cloned()
+                .collect();
+            if keys.iter().any(|key| self.list_ready(key) == Ok(true)) {
+                if let Some(client) = self.blocked_clients.remove(position) {
+                    if let Some(response) = self.try_blocking_pop(&client.command) {
+                        let _ = client.sender.send(response.unwrap_or_else(Response::Error));
+                        position = 0;
+                        continue;
                     }
+                    self.blocked_clients.insert(position, client);
                 }
-                _ => {
-                    let _ = self.log_sender.send(Log::new(
-                        LogLevel::Error,
-                        line!(),
-                        column!(),
-                        file!().to_string(),
-                        WRONGTYPE_MSG.to_string(),
-                    ));
-                    Err(WRONGTYPE_MSG.to_string())
+            }
+            position += 1;
+        }
+        self.select_db(selected_db);
+    }
+
+    /// Desbloquea al cliente, si está bloqueado, respondiéndole como si hubiera vencido el
+    /// timeout. Se usa al vencer el timeout y al desconectarse el cliente.
+    ///
+    /// # Return value
+    /// Re::SimpleString OK
+    fn unblock_client_method(&mut self, client_id: String) -> Response {
+        let position = self
+            .blocked_clients
+            .iter()
+            .position(|client| client.client_id == client_id);
+        if let Some(client) = position.and_then(|position| self.blocked_clients.remove(position)) {
+            let _ = client.sender.send(Response::Normal(client.timeout_reply()));
+        }
+
+        Response::Normal(Reply::SimpleString("OK".to_string()))
+    }
+
+    /// Devuelve la lista almacenada en la clave, sin modificarla, o None si la clave no existe. Se
+    /// retorna error si la clave almacena un elemento que no es una lista.
+    fn get_list(&mut self, key: &[u8]) -> Result<Option<&VecDeque<Vec<u8>>>, String> {
+        match self.db.get(&key.to_vec()) {
+            Some(Re::List(list)) => Ok(Some(list)),
+            Some(_) => {
+                let _ = self.log_sender.send(Log::new(
+                    LogLevel::Error,
+                    line!(),
+                    column!(),
+                    file!().to_string(),
+                    WRONGTYPE_MSG.to_string(),
+                ));
+                Err(WRONGTYPE_MSG.to_string())
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Devuelve la lista almacenada en la clave, o None si la clave no existe. Se retorna error si
+    /// la clave almacena un elemento que no es una lista. Si se modifica la lista, hay que
+    /// indicarlo con `mark_modified`.
+    fn list_mut(&mut self, key: &[u8]) -> Result<Option<&mut VecDeque<Vec<u8>>>, String> {
+        match self.db.get_mut(&key.to_vec()) {
+            Some(Re::List(list)) => Ok(Some(list)),
+            Some(_) => {
+                let _ = self.log_sender.send(Log::new(
+                    LogLevel::Error,
+                    line!(),
+                    column!(),
+                    file!().to_string(),
+                    WRONGTYPE_MSG.to_string(),
+                ));
+                Err(WRONGTYPE_MSG.to_string())
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Borra la clave si almacena una lista vacía: en Redis no existen las listas vacías.
+    fn remove_if_empty(&mut self, key: &[u8]) {
+        let key = key.to_vec();
+        if let Some(Re::List(list)) = self.db.get(&key) {
+            if list.is_empty() {
+                self.db.remove(&key);
+            }
+        }
+    }
+
+    /// Saca hasta `count` elementos del inicio (o del final) de la lista de la clave. Retorna None
+    /// si la clave no existe.
+    fn pop_elements(
+        &mut self,
+        key: &[u8],
+        count: usize,
+        left: bool,
+    ) -> Result<Option<Vec<Vec<u8>>>, String> {
+        let popped: Vec<Vec<u8>> = match self.list_mut(key)? {
+            Some(list) => {
+                let count = count.min(list.len());
+                if left {
+                    list.drain(..count).collect()
+                } else {
+                    list.drain(list.len() - count..).rev().collect()
                 }
-            },
-            None => Ok(Response::Normal(Re::Nil)),
+            }
+            None => return Ok(None),
+        };
+        if !popped.is_empty() {
+            self.db.mark_modified(&key.to_vec());
+        }
+        self.remove_if_empty(key);
+        Ok(Some(popped))
+    }
+
+    /// Respuesta de LPOP y RPOP: el elemento si se sacó uno solo, o la lista de elementos.
+    fn popped_response(popped: Option<Vec<Vec<u8>>>) -> Response {
+        match popped {
+            Some(mut popped) if popped.len() == 1 => {
+                Response::Normal(Reply::BulkString(popped.remove(0)))
+            }
+            Some(popped) if !popped.is_empty() => Response::Normal(Reply::bulk_strings(popped)),
+            _ => Response::Normal(Reply::NullBulk),
         }
     }
 
+    /// Elimina y retorna el primer elemento de la lista almacenada en la clave. Se puede indicar un
+    /// parámetro adicional `count` para indicar obtener esa cantidad de elementos.
+    fn lpop_method(&mut self, key: Vec<u8>, count: usize) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command LPOP Received - key: ".to_string() + &to_text(&key),
+        ));
+
+        let popped = self.pop_elements(&key, count.max(1), true)?;
+        Ok(Redis::popped_response(popped))
+    }
+
     /// Inserta todos los valores especificados en el inicio de la lista de la clave especificada.
     ///
     /// Si no existe la clave, se crea inicialmente como una lista vacía para luego aplicar las
     /// operaciones.
     ///
     /// Se retorna error si la clave almacena un elemento que no es una lista.
-    fn lpush_method(&mut self, key: String, values: Vec<String>) -> Result<Response, String> {
+    fn lpush_method(&mut self, key: Vec<u8>, values: Vec<Vec<u8>>) -> Result<Response, String> {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
             column!(),
             file!().to_string(),
-            "Command LPUSH Received - key: ".to_string() + &*key,
+            "Command LPUSH Received - key: ".to_string() + &to_text(&key),
         ));
 
-        let mut redis_element: Vec<String> = values;
-        redis_element.reverse();
-
-        match self.db.get_mut(&key) {
-            Some(value) => match value {
-                Re::List(value) => {
-                    let saved_vector = value.clone();
-                    redis_element.extend(saved_vector);
-                    self.db.insert(key, Re::List(redis_element.clone()));
-
-                    Ok(Response::Normal(Re::String(
-                        redis_element.len().to_string(),
-                    )))
-                }
-                _ => {
-                    let _ = self.log_sender.send(Log::new(
-                        LogLevel::Debug,
-                        line!(),
-                        column!(),
-                        file!().to_string(),
-                        WRONGTYPE_MSG.to_string(),
-                    ));
-                    Err(WRONGTYPE_MSG.to_string())
-                }
-            },
-            None => {
-                self.db.insert(key, Re::List(redis_element.clone()));
-
-                Ok(Response::Normal(Re::String(
-                    redis_element.len().to_string(),
-                )))
-            }
+        if self.list_mut(&key)?.is_none() {
+            self.db.insert(key.to_vec(), Re::List(VecDeque::new()));
         }
+        self.lpushx_method(key, values)
     }
 
     /// Inserta los valores especificados al inicio de lalista, solamente si la clave existe y
     /// almacena una lista. A diferencia de LPUSH, no se realiza operación si la clave no existe.
-    fn lpushx_method(&mut self, key: String, values: Vec<String>) -> Result<Response, String> {
+    fn lpushx_method(&mut self, key: Vec<u8>, values: Vec<Vec<u8>>) -> Result<Response, String> {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
             column!(),
             file!().to_string(),
-            "Command LPUSHX Received - key: ".to_string() + &*key,
+            "Command LPUSHX Received - key: ".to_string() + &to_text(&key),
         ));
 
-        let mut redis_element: Vec<String> = values;
-        redis_element.reverse();
-
-        match self.db.get_mut(&key) {
-            Some(value) => match value {
-                RedisElement::List(value) => {
-                    let saved_vector = value.clone();
-                    redis_element.extend(saved_vector);
-                    self.db
-                        .insert(key, RedisElement::List(redis_element.clone()));
-
-                    Ok(Response::Normal(Re::String(
-                        redis_element.len().to_string(),
-                    )))
-                }
-                _ => {
-                    let _ = self.log_sender.send(Log::new(
-                        LogLevel::Error,
-                        line!(),
-                        column!(),
-                        file!().to_string(),
-                        WRONGTYPE_MSG.to_string(),
-                    ));
-                    Err(WRONGTYPE_MSG.to_string())
+        let len = match self.list_mut(&key)? {
+            Some(list) => {
+                for value in values {
+                    list.push_front(value);
                 }
-            },
-            None => {
-                self.db.insert(key, Re::List(vec![]));
-                Ok(Response::Normal(Re::String("0".to_string())))
+                list.len()
             }
+            None => return Ok(Response::Normal(Reply::Integer(0))),
+        };
+        self.db.mark_modified(&key);
+        Ok(Response::Normal(Reply::Integer(len as i64)))
+    }
+
+    /// Convierte un rango de LRANGE o LTRIM, cuyos extremos pueden ser negativos (-1 es el último
+    /// elemento), en las posiciones de inicio y fin, inclusive, dentro de una lista del largo
+    /// indicado. Retorna None si el rango queda vacío.
+    fn list_range(len: usize, begin: i64, end: i64) -> Option<(usize, usize)> {
+        let len = len as i64;
+        let begin = if begin < 0 { begin + len } else { begin }.max(0);
+        let end = if end < 0 { end + len } else { end }.min(len - 1);
+        if begin > end {
+            return None;
         }
+        Some((begin as usize, end as usize))
     }
 
     /// Retorna los elementos especificados de la lista almacenada en la clave indicada.
@@ -1420,121 +3182,226 @@ impl Redis {
     /// Los inicios
     /// y fin de rango se consideran con el 0 como primer elemento de la lista. Estos valores pueden
     /// ser negativos, indicando que corresponde al final de la lista: -1 es el último elemento.
-    fn lrange_method(&mut self, key: String, begin: i32, end: i32) -> Result<Response, String> {
+    fn lrange_method(&mut self, key: Vec<u8>, begin: i32, end: i32) -> Result<Response, String> {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
             column!(),
             file!().to_string(),
-            "Command LRANGE Received - key: ".to_string() + &*key,
+            "Command LRANGE Received - key: ".to_string() + &to_text(&key),
         ));
 
-        match self.db.get_mut(&key) {
-            Some(value) => match value {
-                Re::List(value) => {
-                    let len_value = value.len() as i32;
-                    let mut begin_position: i32 = begin;
-
-                    if begin < 0 {
-                        begin_position = begin + len_value + 1;
-                    };
-
-                    let mut end_position: i32 = end;
-
-                    if end < 0 {
-                        end_position = end + len_value + 1;
-                    }
+        let list = match self.get_list(&key)? {
+            Some(list) => list,
+            None => return Ok(Response::Normal(Reply::Array(vec![]))),
+        };
+        let range = match Redis::list_range(list.len(), begin as i64, end as i64) {
+            Some((begin, end)) => list.range(begin..=end).cloned().collect(),
+            None => VecDeque::new(),
+        };
+        Ok(Response::Normal(Reply::bulk_strings(range)))
+    }
 
-                    let begin_position: usize = begin_position as usize;
-                    let end_position: usize = end_position as usize;
-                    let return_value = value.get(begin_position..end_position);
-                    if return_value.is_none() {
-                        return Ok(Response::Normal(Re::List(vec![])));
-                    }
+    /// Recorta la lista almacenada en la clave para que sólo contenga los elementos del rango
+    /// indicado, con los mismos extremos que LRANGE. Si el rango queda vacío, se borra la clave.
+    fn ltrim_method(&mut self, key: Vec<u8>, begin: i32, end: i32) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command LTRIM Received - key: ".to_string() + &to_text(&key),
+        ));
 
-                    Ok(Response::Normal(Re::List(return_value.unwrap().to_vec())))
-                }
-                _ => {
-                    let _ = self.log_sender.send(Log::new(
-                        LogLevel::Error,
-                        line!(),
-                        column!(),
-                        file!().to_string(),
-                        WRONGTYPE_MSG.to_string(),
-                    ));
-                    Err(WRONGTYPE_MSG.to_string())
+        if let Some(list) = self.list_mut(&key)? {
+            let len = list.len();
+            match Redis::list_range(len, begin as i64, end as i64) {
+                Some((begin, end)) => {
+                    list.truncate(end + 1);
+                    list.drain(..begin);
                 }
-            },
-            None => Ok(Response::Normal(Re::List(vec![]))),
+                None => list.clear(),
+            }
+            if list.len() != len {
+                self.db.mark_modified(&key);
+            }
         }
+        self.remove_if_empty(&key);
+        Ok(Response::Normal(Reply::SimpleString("OK".to_string())))
     }
 
-    /// Elimina la primer cantidad count de ocurrencias de elementos de la lista almacenada en la
-    /// clave, igual al elemento indicado por parámetro.
-    ///
-    /// El parámetro cantidad influye de esta manera:
+    /// Inserta el elemento antes o después de la primera aparición de `pivot` en la lista
+    /// almacenada en la clave. Retorna el largo de la lista, -1 si no se encontró `pivot`, o 0 si
+    /// la clave no existe.
+    fn linsert_method(
+        &mut self,
+        key: Vec<u8>,
+        before: bool,
+        pivot: Vec<u8>,
+        element: Vec<u8>,
+    ) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command LINSERT Received - key: ".to_string() + &to_text(&key),
+        ));
+
+        let list = match self.list_mut(&key)? {
+            Some(list) => list,
+            None => return Ok(Response::Normal(Reply::Integer(0))),
+        };
+        let len = match list.iter().position(|value| *value == pivot) {
+            Some(position) => {
+                list.insert(if before { position } else { position + 1 }, element);
+                list.len()
+            }
+            None => return Ok(Response::Normal(Reply::Integer(-1))),
+        };
+        self.db.mark_modified(&key);
+        Ok(Response::Normal(Reply::Integer(len as i64)))
+    }
+
+    /// Retorna la posición del elemento en la lista almacenada en la clave, o Nil si no está.
     ///
-    /// * `count > 0` - Elimina elementos iguales al indicado comenzando desde el inicio de la lista.
-    /// * `count < 0` - Elimina elementos iguales al indicado comenzando desde el final de la lista.
-    /// * `count = 0` - Elimina todos los elementos iguales al indicado.
-    fn lrem_method(
+    /// * `rank` - Indica cuál de las apariciones se busca: 1 es la primera, 2 la segunda, y los
+    ///   valores negativos buscan desde el final de la lista.
+    /// * `count` - Si se indica, se retornan las posiciones de hasta `count` apariciones (todas si
+    ///   es 0), a partir de la indicada por `rank`.
+    /// * `maxlen` - Cantidad máxima de elementos a comparar (todos si es 0).
+    fn lpos_method(
         &mut self,
-        key: String,
-        count: i32,
-        element: String,
+        key: Vec<u8>,
+        element: Vec<u8>,
+        rank: i64,
+        count: Option<usize>,
+        maxlen: usize,
     ) -> Result<Response, String> {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
             column!(),
             file!().to_string(),
-            "Command LREM Received - key: ".to_string() + &*key,
+            "Command LPOS Received - key: ".to_string() + &to_text(&key),
         ));
 
-        match self.db.get_mut(&key) {
-            Some(value) => match value {
-                Re::List(value) => match count.cmp(&0) {
-                    Ordering::Greater => {
-                        let (final_vector, deleted) =
-                            Self::remove_repeats(count as usize, element, value.clone());
-                        self.db.insert(key.clone(), Re::List(final_vector));
-                        Ok(Response::Normal(Re::String(deleted.to_string())))
-                    }
-                    Ordering::Less => {
-                        value.reverse();
-                        let (mut final_vector, deleted) =
-                            Self::remove_repeats(count as usize, element, value.clone());
-                        final_vector.reverse();
-                        self.db.insert(key.clone(), Re::List(final_vector));
-                        Ok(Response::Normal(Re::String(deleted.to_string())))
-                    }
-                    Ordering::Equal => {
-                        let (final_vector, deleted) =
-                            Self::remove_all_repeats(element, value.clone());
-                        self.db.insert(key.clone(), Re::List(final_vector));
-                        Ok(Response::Normal(Re::String(deleted.to_string())))
-                    }
-                },
-                _ => {
-                    let _ = self.log_sender.send(Log::new(
-                        LogLevel::Error,
-                        line!(),
-                        column!(),
-                        file!().to_string(),
-                        WRONGTYPE_MSG.to_string(),
-                    ));
-                    Err(WRONGTYPE_MSG.to_string())
-                }
-            },
-            None => Ok(Response::Normal(Re::String("0".to_string()))),
+        let mut positions: Vec<usize> = vec![];
+        if let Some(list) = self.get_list(&key)? {
+            let len = list.len();
+            let maxlen = if maxlen == 0 { len } else { maxlen };
+            let wanted = match count {
+                Some(0) => usize::MAX,
+                Some(count) => count,
+                None => 1,
+            };
+            let indexes: Box<dyn Iterator<Item = usize>> = if rank > 0 {
+                Box::new(0..len)
+            } else {
+                Box::new((0..len).rev())
+            };
+            positions = indexes
+                .take(maxlen)
+                .filter(|index| list[*index] == element)
+                .skip(rank.unsigned_abs() as usize - 1)
+                .take(wanted)
+                .collect();
+        }
+
+        match count {
+            Some(_) => Ok(Response::Normal(Reply::Array(
+                positions
+                    .into_iter()
+                    .map(|position| Reply::Integer(position as i64))
+                    .collect(),
+            ))),
+            None => Ok(Response::Normal(
+                positions
+                    .first()
+                    .map_or(Reply::NullBulk, |position| Reply::Integer(*position as i64)),
+            )),
+        }
+    }
+
+    /// Saca hasta `count` elementos del inicio (o del final) de la primera lista con elementos
+    /// entre las claves indicadas. Retorna la clave y los elementos, o NullArray si todas las
+    /// listas están vacías.
+    fn lmpop_method(
+        &mut self,
+        keys: Vec<Vec<u8>>,
+        left: bool,
+        count: usize,
+    ) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command LMPOP Received - keys: ".to_string() + &join_text(&keys),
+        ));
+
+        for key in keys {
+            if let Some(popped) = self.pop_elements(&key, count, left)? {
+                return Ok(Response::Normal(Reply::Array(vec![
+                    Reply::BulkString(key),
+                    Reply::bulk_strings(popped),
+                ])));
+            }
+        }
+        Ok(Response::Normal(Reply::NullArray))
+    }
+
+    /// Elimina la primer cantidad count de ocurrencias de elementos de la lista almacenada en la
+    /// clave, igual al elemento indicado por parámetro.
+    ///
+    /// El parámetro cantidad influye de esta manera:
+    ///
+    /// * `count > 0` - Elimina elementos iguales al indicado comenzando desde el inicio de la lista.
+    /// * `count < 0` - Elimina elementos iguales al indicado comenzando desde el final de la lista.
+    /// * `count = 0` - Elimina todos los elementos iguales al indicado.
+    fn lrem_method(
+        &mut self,
+        key: Vec<u8>,
+        count: i32,
+        element: Vec<u8>,
+    ) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command LREM Received - key: ".to_string() + &to_text(&key),
+        ));
+
+        let list = match self.list_mut(&key)? {
+            Some(list) => list,
+            None => return Ok(Response::Normal(Reply::Integer(0))),
+        };
+        let vector: Vec<Vec<u8>> = list.drain(..).collect();
+        let (final_vector, deleted) = match count.cmp(&0) {
+            Ordering::Greater => Self::remove_repeats(count as usize, element, vector),
+            Ordering::Less => {
+                let reversed = vector.into_iter().rev().collect();
+                let (mut final_vector, deleted) =
+                    Self::remove_repeats(count as usize, element, reversed);
+                final_vector.reverse();
+                (final_vector, deleted)
+            }
+            Ordering::Equal => Self::remove_all_repeats(element, vector),
+        };
+        list.extend(final_vector);
+        if deleted > 0 {
+            self.db.mark_modified(&key);
         }
+        self.remove_if_empty(&key);
+        Ok(Response::Normal(Reply::Integer(deleted as i64)))
     }
 
     fn remove_repeats(
         count: usize,
-        element: String,
-        mut vector: Vec<String>,
-    ) -> (Vec<String>, usize) {
+        element: Vec<u8>,
+        mut vector: Vec<Vec<u8>>,
+    ) -> (Vec<Vec<u8>>, usize) {
         let mut n = 0;
         for i in 0..vector.len() {
             if n <= count && vector.get(i).is_some() && *vector.get(i).unwrap() == element {
@@ -1545,7 +3412,7 @@ impl Redis {
         (vector, n)
     }
 
-    fn remove_all_repeats(element: String, mut vector: Vec<String>) -> (Vec<String>, usize) {
+    fn remove_all_repeats(element: Vec<u8>, mut vector: Vec<Vec<u8>>) -> (Vec<Vec<u8>>, usize) {
         let mut n = 0;
         for i in 0..vector.len() {
             if vector.get(i).is_some() && *vector.get(i).unwrap() == element {
@@ -1562,56 +3429,20 @@ impl Redis {
     /// error si se indica un rango inválido.
     fn lset_method(
         &mut self,
-        key: String,
+        key: Vec<u8>,
         index: i32,
-        element: String,
+        element: Vec<u8>,
     ) -> Result<Response, String> {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
             column!(),
             file!().to_string(),
-            "Command LSET Received - key: ".to_string() + &*key,
+            "Command LSET Received - key: ".to_string() + &to_text(&key),
         ));
 
-        match self.db.get_mut(&key) {
-            Some(value) => match value {
-                Re::List(value) => {
-                    let len_value = value.len() as i32;
-                    let mut position: i32 = index;
-
-                    if index < 0 {
-                        position = index + len_value;
-                    };
-
-                    if position < 0 || position > len_value {
-                        let _ = self.log_sender.send(Log::new(
-                            LogLevel::Error,
-                            line!(),
-                            column!(),
-                            file!().to_string(),
-                            "ERR index out of range".to_string(),
-                        ));
-                        return Err("ERR index out of range".to_string());
-                    }
-
-                    let saved_value = value;
-
-                    saved_value[position as usize] = element;
-
-                    Ok(Response::Normal(Re::SimpleString("OK".to_string())))
-                }
-                _ => {
-                    let _ = self.log_sender.send(Log::new(
-                        LogLevel::Error,
-                        line!(),
-                        column!(),
-                        file!().to_string(),
-                        WRONGTYPE_MSG.to_string(),
-                    ));
-                    Err(WRONGTYPE_MSG.to_string())
-                }
-            },
+        let list = match self.list_mut(&key)? {
+            Some(list) => list,
             None => {
                 let _ = self.log_sender.send(Log::new(
                     LogLevel::Error,
@@ -1620,67 +3451,44 @@ impl Redis {
                     file!().to_string(),
                     "ERR no such key".to_string(),
                 ));
-                Err("ERR no such key".to_string())
+                return Err("ERR no such key".to_string());
             }
+        };
+        let len = list.len() as i64;
+        let position = if index < 0 {
+            index as i64 + len
+        } else {
+            index as i64
+        };
+        if position < 0 || position >= len {
+            let _ = self.log_sender.send(Log::new(
+                LogLevel::Error,
+                line!(),
+                column!(),
+                file!().to_string(),
+                "ERR index out of range".to_string(),
+            ));
+            return Err("ERR index out of range".to_string());
         }
+
+        list[position as usize] = element;
+        self.db.mark_modified(&key);
+        Ok(Response::Normal(Reply::SimpleString("OK".to_string())))
     }
 
     /// Elimina y obtiene el/los último/s elemento/s de la lista almacenada en la clave indicada.
     /// Por defecto, es un solo elemento, se puede indicar una cantidad.
-    fn rpop_method(&mut self, key: String, count: usize) -> Result<Response, String> {
+    fn rpop_method(&mut self, key: Vec<u8>, count: usize) -> Result<Response, String> {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
             column!(),
             file!().to_string(),
-            "Command RPOP Received - key: ".to_string() + &*key,
+            "Command RPOP Received - key: ".to_string() + &to_text(&key),
         ));
 
-        match self.db.get_mut(&key) {
-            Some(value) => match value {
-                Re::List(value) => {
-                    let return_value: Vec<String>;
-                    let mut vector_to_save: Vec<String>;
-                    value.reverse();
-
-                    if count == 0 {
-                        return_value = Vec::from(value.get(..=count).unwrap());
-                        vector_to_save = Vec::from(value.get(count + 1..).unwrap());
-                    } else {
-                        let qty = match count {
-                            x if x >= value.len() => value.len(),
-                            _ => count,
-                        };
-                        return_value = Vec::from(value.get(..qty).unwrap());
-                        vector_to_save = Vec::from(value.get(qty..).unwrap());
-                    }
-
-                    vector_to_save.reverse();
-                    self.db.insert(key, Re::List(vector_to_save));
-
-                    if return_value.len() == 1 && return_value.first().is_some() {
-                        let value = return_value.first();
-                        return Ok(Response::Normal(Re::String(value.unwrap().to_string())));
-                    }
-
-                    match return_value.len() {
-                        x if x > 0 => Ok(Response::Normal(Re::List(return_value.to_vec()))),
-                        _ => Ok(Response::Normal(Re::Nil)),
-                    }
-                }
-                _ => {
-                    let _ = self.log_sender.send(Log::new(
-                        LogLevel::Error,
-                        line!(),
-                        column!(),
-                        file!().to_string(),
-                        WRONGTYPE_MSG.to_string(),
-                    ));
-                    Err(WRONGTYPE_MSG.to_string())
-                }
-            },
-            None => Ok(Response::Normal(Re::Nil)),
-        }
+        let popped = self.pop_elements(&key, count.max(1), false)?;
+        Ok(Redis::popped_response(popped))
     }
 
     /// Inserta todos los valores especificados al final de la lista indicada en la clave.
@@ -1690,77 +3498,41 @@ impl Redis {
     ///
     /// Se retorna
     /// error si el elemento contenido no es una lista.
-    fn rpush_method(&mut self, key: String, values: Vec<String>) -> Result<Response, String> {
+    fn rpush_method(&mut self, key: Vec<u8>, values: Vec<Vec<u8>>) -> Result<Response, String> {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
             column!(),
             file!().to_string(),
-            "Command RPUSH Received - key: ".to_string() + &*key,
+            "Command RPUSH Received - key: ".to_string() + &to_text(&key),
         ));
 
-        match self.db.get_mut(&key) {
-            Some(value) => match value {
-                Re::List(value) => {
-                    let mut saved_vector = value.clone();
-                    saved_vector.extend(values);
-                    self.db.insert(key, Re::List(saved_vector.clone()));
-
-                    Ok(Response::Normal(Re::String(saved_vector.len().to_string())))
-                }
-                _ => {
-                    let _ = self.log_sender.send(Log::new(
-                        LogLevel::Error,
-                        line!(),
-                        column!(),
-                        file!().to_string(),
-                        WRONGTYPE_MSG.to_string(),
-                    ));
-                    Err(WRONGTYPE_MSG.to_string())
-                }
-            },
-            None => {
-                self.db.insert(key, Re::List(values.clone()));
-
-                Ok(Response::Normal(Re::String(values.len().to_string())))
-            }
+        if self.list_mut(&key)?.is_none() {
+            self.db.insert(key.to_vec(), Re::List(VecDeque::new()));
         }
+        self.rpushx_method(key, values)
     }
 
     /// Inserta los valores especificados al final de la lista almacenada en la clave indicada,
     /// solamente si la clave contiene una lista. En caso contrario, no se realiza ninguna operación.
-    fn rpushx_method(&mut self, key: String, values: Vec<String>) -> Result<Response, String> {
+    fn rpushx_method(&mut self, key: Vec<u8>, values: Vec<Vec<u8>>) -> Result<Response, String> {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
             column!(),
             file!().to_string(),
-            "Command RPUSHX Received - key: ".to_string() + &*key,
+            "Command RPUSHX Received - key: ".to_string() + &to_text(&key),
         ));
 
-        match self.db.get_mut(&key) {
-            Some(value) => match value {
-                RedisElement::List(value) => {
-                    let mut saved_vector = value.clone();
-                    saved_vector.extend(values);
-                    self.db
-                        .insert(key, RedisElement::List(saved_vector.clone()));
-
-                    Ok(Response::Normal(Re::String(saved_vector.len().to_string())))
-                }
-                _ => {
-                    let _ = self.log_sender.send(Log::new(
-                        LogLevel::Error,
-                        line!(),
-                        column!(),
-                        file!().to_string(),
-                        WRONGTYPE_MSG.to_string(),
-                    ));
-                    Err(WRONGTYPE_MSG.to_string())
-                }
-            },
-            None => Ok(Response::Normal(Re::String("0".to_string()))),
-        }
+        let len = match self.list_mut(&key)? {
+            Some(list) => {
+                list.extend(values);
+                list.len()
+            }
+            None => return Ok(Response::Normal(Reply::Integer(0))),
+        };
+        self.db.mark_modified(&key);
+        Ok(Response::Normal(Reply::Integer(len as i64)))
     }
 
     /// Agrega el elemento indicado al set de la clave especificada.
@@ -1769,27 +3541,26 @@ impl Redis {
     /// set vacío para agregar el valor. Si el valor ya existía en el set, no se realiza agregado.
     ///
     /// Retorna error si el valor almacenado en la clave no es un set.
-    fn sadd_method(&mut self, key: String, values: HashSet<String>) -> Result<Response, String> {
+    fn sadd_method(&mut self, key: Vec<u8>, values: HashSet<Vec<u8>>) -> Result<Response, String> {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
             column!(),
             file!().to_string(),
-            "Command SADD Received - key: ".to_string() + &*key,
+            "Command SADD Received - key: ".to_string() + &to_text(&key),
         ));
 
         match self.db.get_mut(&key) {
             Some(value) => match value {
-                RedisElement::Set(value) => {
-                    let mut set = value.clone();
+                RedisElement::Set(set) => {
                     let start_set_len = set.len();
                     set.extend(values);
-                    let final_set_len = set.len();
-                    self.db.insert(key, RedisElement::Set(set));
+                    let added = set.len() - start_set_len;
+                    if added > 0 {
+                        self.db.mark_modified(&key);
+                    }
 
-                    Ok(Response::Normal(Re::String(
-                        (final_set_len - start_set_len).to_string(),
-                    )))
+                    Ok(Response::Normal(Reply::Integer(added as i64)))
                 }
                 _ => {
                     let _ = self.log_sender.send(Log::new(
@@ -1803,27 +3574,28 @@ impl Redis {
                 }
             },
             None => {
-                self.db.insert(key, RedisElement::Set(values.clone()));
-                Ok(Response::Normal(Re::String(values.len().to_string())))
+                self.db
+                    .insert(key, RedisElement::Set(values.clone().into()));
+                Ok(Response::Normal(Reply::Integer(values.len() as i64)))
             }
         }
     }
 
     /// Retorna la cantidad de elementos del set almacenado en la clave indicada.
-    fn scard_method(&mut self, key: String) -> Result<Response, String> {
+    fn scard_method(&mut self, key: Vec<u8>) -> Result<Response, String> {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
             column!(),
             file!().to_string(),
-            "Command SCARD Received - key: ".to_string() + &*key,
+            "Command SCARD Received - key: ".to_string() + &to_text(&key),
         ));
 
-        match self.db.get_mut(&key) {
+        match self.db.get(&key) {
             Some(value) => match value {
                 RedisElement::Set(value) => {
                     let set = value.clone();
-                    Ok(Response::Normal(Re::String(set.len().to_string())))
+                    Ok(Response::Normal(Reply::Integer(set.len() as i64)))
                 }
                 _ => {
                     let _ = self.log_sender.send(Log::new(
@@ -1837,28 +3609,28 @@ impl Redis {
                     Err("WRONGTYPE A hashset data type expected".to_string())
                 }
             },
-            None => Ok(Response::Normal(Re::String("0".to_string()))),
+            None => Ok(Response::Normal(Reply::Integer(0))),
         }
     }
 
     /// Retorna si el elemento indicado es miembro del set indicado en la clave.
-    fn sismember_method(&mut self, key: String, value: String) -> Result<Response, String> {
+    fn sismember_method(&mut self, key: Vec<u8>, value: Vec<u8>) -> Result<Response, String> {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
             column!(),
             file!().to_string(),
-            "Command SISMEMBER Received - key: ".to_string() + &*key,
+            "Command SISMEMBER Received - key: ".to_string() + &to_text(&key),
         ));
 
-        match self.db.get_mut(&key) {
+        match self.db.get(&key) {
             Some(redis_element) => match redis_element {
                 RedisElement::Set(redis_element) => {
                     let set = redis_element.clone();
                     if set.contains(&value) {
-                        Ok(Response::Normal(Re::String("1".to_string())))
+                        Ok(Response::Normal(Reply::Integer(1)))
                     } else {
-                        Ok(Response::Normal(Re::String("0".to_string())))
+                        Ok(Response::Normal(Reply::Integer(0)))
                     }
                 }
                 _ => {
@@ -1867,7 +3639,7 @@ impl Redis {
                         line!(),
                         column!(),
                         file!().to_string(),
-                        "WRONGTYPE A hashset data type expected".to_string() + &*key,
+                        "WRONGTYPE A hashset data type expected".to_string() + &to_text(&key),
                     ));
 
                     Err("WRONGTYPE A hashset data type expected".to_string())
@@ -1887,20 +3659,20 @@ impl Redis {
     }
 
     /// Retorna todos los miembros del set almacenado en la clave indicada.
-    fn smembers_method(&mut self, key: String) -> Result<Response, String> {
+    fn smembers_method(&mut self, key: Vec<u8>) -> Result<Response, String> {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
             column!(),
             file!().to_string(),
-            "Command SMEMBERS Received - key: ".to_string() + &*key,
+            "Command SMEMBERS Received - key: ".to_string() + &to_text(&key),
         ));
 
-        match self.db.get_mut(&key) {
+        match self.db.get(&key) {
             Some(redis_element) => match redis_element {
-                RedisElement::Set(redis_element) => {
-                    Ok(Response::Normal(Re::Set(redis_element.clone())))
-                }
+                RedisElement::Set(redis_element) => Ok(Response::Normal(Reply::bulk_strings(
+                    redis_element.iter().cloned(),
+                ))),
 
                 _ => {
                     let _ = self.log_sender.send(Log::new(
@@ -1921,11 +3693,47 @@ impl Redis {
                     file!().to_string(),
                     "The key doesn't exist".to_string(),
                 ));
-                Ok(Response::Normal(Re::Set(HashSet::new())))
+                Ok(Response::Normal(Reply::Array(vec![])))
             }
         }
     }
 
+    /// Itera los miembros del set almacenado en la clave indicada a partir del cursor, de la misma
+    /// forma que SCAN itera las claves. Si se indica un patrón, solamente se retornan los miembros
+    /// que hacen match con él.
+    fn sscan_method(
+        &mut self,
+        key: Vec<u8>,
+        cursor: u64,
+        pattern: Option<Vec<u8>>,
+        count: usize,
+    ) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command SSCAN Received - key: ".to_string() + &to_text(&key),
+        ));
+
+        let set = match self.db.get(&key) {
+            Some(Re::Set(set)) => set,
+            Some(_) => return Err(WRONGTYPE_MSG.to_string()),
+            None => return Ok(Response::Normal(Reply::scan("0".to_string(), vec![]))),
+        };
+
+        let (next_cursor, members) = set.scan(cursor, count);
+        let members = members
+            .into_iter()
+            .filter(|member| pattern.as_ref().is_none_or(|p| glob_match(p, member)))
+            .cloned()
+            .collect();
+        Ok(Response::Normal(Reply::scan(
+            next_cursor.to_string(),
+            members,
+        )))
+    }
+
     /// Elimina los miembros especificados del set almacenado en la clave indicada.
     ///
     /// Si la clave no
@@ -1933,27 +3741,28 @@ impl Redis {
     ///
     /// Retorna error si el valor almacenado
     /// en esa clave no es un set.
-    fn srem_method(&mut self, key: String, values: HashSet<String>) -> Result<Response, String> {
+    fn srem_method(&mut self, key: Vec<u8>, values: HashSet<Vec<u8>>) -> Result<Response, String> {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
             column!(),
             file!().to_string(),
-            "Command SREM Received - key: ".to_string() + &*key,
+            "Command SREM Received - key: ".to_string() + &to_text(&key),
         ));
 
         match self.db.get_mut(&key) {
             Some(redis_element) => match redis_element {
-                RedisElement::Set(redis_element) => {
-                    let mut set = redis_element.clone();
+                RedisElement::Set(set) => {
                     let mut count = 0;
                     for value in values {
                         if set.remove(&value) {
                             count += 1;
                         }
                     }
-                    self.db.insert(key.clone(), RedisElement::Set(set));
-                    Ok(Response::Normal(Re::String(count.to_string())))
+                    if count > 0 {
+                        self.db.mark_modified(&key);
+                    }
+                    Ok(Response::Normal(Reply::Integer(count as i64)))
                 }
                 _ => {
                     let _ = self.log_sender.send(Log::new(
@@ -1961,144 +3770,2031 @@ impl Redis {
                         line!(),
                         column!(),
                         file!().to_string(),
-                        "WRONGTYPE A hashset data type expected".to_string() + &*key,
+                        "WRONGTYPE A hashset data type expected".to_string() + &to_text(&key),
                     ));
                     Err("WRONGTYPE A hashset data type expected".to_string())
                 }
             },
-            None => Ok(Response::Normal(Re::String("0".to_string()))),
-        }
-    }
-
-    /// Retorna todas las claves que hacen match con un patrón.
-    fn keys_method(&mut self, pattern: String) -> Vec<String> {
-        let _ = self.log_sender.send(Log::new(
-            LogLevel::Debug,
-            line!(),
-            column!(),
-            file!().to_string(),
-            "Command KEYS Received".to_string(),
-        ));
-
-        let mut vector = vec![];
-        for key in self.db.keys() {
-            if let Ok(re) = Regex::new(&*pattern) {
-                if re.is_match(key) {
-                    vector.push(key.to_string());
-                }
-            }
+            None => Ok(Response::Normal(Reply::Integer(0))),
         }
-        vector
     }
 
-    /// Comando interno para Persistir los elementos de la Base de datos en un archivo
-    fn store_method(&self, path: String) -> Result<Response, String> {
-        let _ = self.log_sender.send(Log::new(
-            LogLevel::Debug,
-            line!(),
-            column!(),
-            file!().to_string(),
-            "Command STORE Received - path: ".to_string() + &*path,
-        ));
-
-        let mut file = match fs::File::create(path) {
-            Ok(file) => file,
-            Err(e) => {
-                let _ = self.log_sender.send(Log::new(
+    /// Obtiene una referencia al set almacenado en la clave indicada, sin modificarlo.
+    ///
+    /// Retorna None si la clave no existe, y error si el valor almacenado en la clave no es un
+    /// set.
+    fn get_set(&mut self, key: &[u8]) -> Result<Option<&HashSet<Vec<u8>>>, String> {
+        let log_sender = &self.log_sender;
+        match self.db.get(&key.to_vec()) {
+            Some(Re::Set(set)) => Ok(Some(set)),
+            Some(_) => {
+                let _ = log_sender.send(Log::new(
                     LogLevel::Error,
                     line!(),
                     column!(),
                     file!().to_string(),
-                    e.to_string(),
+                    WRONGTYPE_MSG.to_string(),
                 ));
-                return Err(e.to_string());
+                Err(WRONGTYPE_MSG.to_string())
             }
-        };
-
-        let rdb_file = [
-            "REDIS".as_bytes(),
-            VERSION_NUMBER.as_bytes(),
-            &self.db.serialize(),
-        ]
-        .concat();
+            None => Ok(None),
+        }
+    }
 
-        match file.write_all(&rdb_file) {
-            Ok(_) => Ok(Response::Normal(RedisElement::String("Ok".to_string()))),
-            Err(e) => {
-                let _ = self.log_sender.send(Log::new(
+    /// Obtiene una referencia mutable al set almacenado en la clave indicada.
+    ///
+    /// Retorna None si la clave no existe, y error si el valor almacenado en la clave no es un
+    /// set.
+    fn get_set_mut(&mut self, key: &[u8]) -> Result<Option<&mut RedisSet>, String> {
+        let log_sender = &self.log_sender;
+        match self.db.get_mut(&key.to_vec()) {
+            Some(Re::Set(set)) => Ok(Some(set)),
+            Some(_) => {
+                let _ = log_sender.send(Log::new(
                     LogLevel::Error,
                     line!(),
                     column!(),
                     file!().to_string(),
-                    e.to_string(),
+                    WRONGTYPE_MSG.to_string(),
                 ));
-                Err(e.to_string())
+                Err(WRONGTYPE_MSG.to_string())
             }
+            None => Ok(None),
         }
     }
 
-    /// Comando interno para Cargar los elementos de la Base de datos desde un archivo
-    fn load_method(&mut self, path: String) -> Result<Response, String> {
+    /// Aplica la operación a los sets almacenados en las claves indicadas. Las claves que no
+    /// existen se consideran sets vacíos. Se retorna error si alguna clave no almacena un set.
+    fn apply_set_operation(
+        &mut self,
+        keys: &[Vec<u8>],
+        operation: SetOperation,
+    ) -> Result<HashSet<Vec<u8>>, String> {
+        let mut sets = vec![];
+        for key in keys {
+            sets.push(self.get_set(key)?.cloned().unwrap_or_default());
+        }
+        Ok(operation.apply(&sets))
+    }
+
+    /// Retorna la intersección, la unión o la diferencia de los sets almacenados en las claves
+    /// indicadas, según la operación.
+    fn set_operation_method(
+        &mut self,
+        keys: Vec<Vec<u8>>,
+        operation: SetOperation,
+    ) -> Result<Response, String> {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
             column!(),
             file!().to_string(),
-            "Command LOAD Received - path: ".to_string() + &*path,
+            format!(
+                "Command {} Received - keys: {}",
+                operation.command_name().to_uppercase(),
+                join_text(&keys)
+            ),
         ));
 
-        let stream = match fs::read(path) {
-            Ok(stream) => stream,
-            Err(e) => {
-                let _ = self.log_sender.send(Log::new(
-                    LogLevel::Error,
-                    line!(),
-                    column!(),
-                    file!().to_string(),
-                    format!("{:?}", e),
-                ));
-                return Err(format!("{:?}", e));
-            }
-        };
-
-        let mut stream = stream.to_vec();
-
-        if stream.len() < 5 || stream.drain(0..5).collect::<Vec<u8>>() != "REDIS".as_bytes() {
-            return Err("Error: file is not RDB type".to_string());
-        }
-
-        if stream.len() < 4
-            || String::from_utf8_lossy(&stream.drain(0..4).collect::<Vec<u8>>()) != VERSION_NUMBER
-        {
-            return Err("Error: file is not same redis version.".to_string());
-        }
-        match TtlHashMap::deserialize(stream) {
-            Ok(map) => {
-                self.db = map;
-                Ok(Response::Normal(RedisElement::String("OK".to_string())))
-            }
-            Err(e) => {
-                let _ = self.log_sender.send(Log::new(
-                    LogLevel::Error,
-                    line!(),
-                    column!(),
-                    file!().to_string(),
-                    format!("{:?}", e),
-                ));
-                Err(format!("{:?}", e))
-            }
-        }
+        let result = self.apply_set_operation(&keys, operation)?;
+        Ok(Response::Normal(Reply::bulk_strings(result)))
     }
 
-    /// El comando CONFIG GET se utiliza para leer los parámetros de configuración de un servidor en
-    /// ejecución.
-    fn config_get_method(&mut self) -> Vec<String> {
+    /// Calcula la intersección, la unión o la diferencia de los sets almacenados en las claves
+    /// indicadas y guarda el resultado en la clave destino.
+    ///
+    /// Si la clave destino existía, es sobreescrita, y pierde su expiración. Si el resultado es
+    /// vacío, la clave destino se borra.
+    ///
+    /// Retorna la cantidad de miembros del set resultante.
+    fn set_operation_store_method(
+        &mut self,
+        destination: Vec<u8>,
+        keys: Vec<Vec<u8>>,
+        operation: SetOperation,
+    ) -> Result<Response, String> {
         let _ = self.log_sender.send(Log::new(
             LogLevel::Debug,
             line!(),
             column!(),
             file!().to_string(),
-            "Command CONFIG GET Received".to_string(),
+            format!(
+                "Command {}STORE Received - destination: {}",
+                operation.command_name().to_uppercase(),
+                to_text(&destination)
+            ),
+        ));
+
+        let result = self.apply_set_operation(&keys, operation)?;
+        self.db.remove(&destination);
+        let len = result.len();
+        if len > 0 {
+            self.db.insert(destination, Re::Set(result.into()));
+        }
+
+        Ok(Response::Normal(Reply::Integer(len as i64)))
+    }
+
+    /// Retorna la cantidad de miembros de la intersección de los sets almacenados en las claves
+    /// indicadas. Si `limit` no es 0, se deja de contar al llegar a esa cantidad.
+    fn sintercard_method(&mut self, keys: Vec<Vec<u8>>, limit: usize) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command SINTERCARD Received - keys: ".to_string() + &join_text(&keys),
+        ));
+
+        let mut sets = vec![];
+        for key in keys.iter() {
+            sets.push(self.get_set(key)?.cloned().unwrap_or_default());
+        }
+        let limit = if limit == 0 { usize::MAX } else { limit };
+        let count = match sets.split_first() {
+            Some((first, others)) => first
+                .iter()
+                .filter(|member| others.iter().all(|set| set.contains(*member)))
+                .take(limit)
+                .count(),
+            None => 0,
+        };
+
+        Ok(Response::Normal(Reply::Integer(count as i64)))
+    }
+
+    /// Retorna, para cada uno de los miembros indicados, si pertenece (1) o no (0) al set
+    /// almacenado en la clave.
+    fn smismember_method(
+        &mut self,
+        key: Vec<u8>,
+        members: Vec<Vec<u8>>,
+    ) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command SMISMEMBER Received - key: ".to_string() + &to_text(&key),
+        ));
+
+        let set = self.get_set(&key)?;
+        let flags = members
+            .iter()
+            .map(|member| Reply::Integer(set.is_some_and(|set| set.contains(member)) as i64))
+            .collect();
+        Ok(Response::Normal(Reply::Array(flags)))
+    }
+
+    /// Mueve el miembro del set de la clave origen al set de la clave destino. Retorna 1 si el
+    /// miembro se movió, o 0 si no pertenecía al set de origen.
+    ///
+    /// Retorna error si alguna de las claves almacena un elemento que no es un set.
+    fn smove_method(
+        &mut self,
+        source: Vec<u8>,
+        destination: Vec<u8>,
+        member: Vec<u8>,
+    ) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command SMOVE Received - source: ".to_string()
+                + &to_text(&source)
+                + " - destination: "
+                + &to_text(&destination),
+        ));
+
+        let found = self
+            .get_set(&source)?
+            .is_some_and(|set| set.contains(&member));
+        self.get_set(&destination)?;
+        if !found {
+            return Ok(Response::Normal(Reply::Integer(0)));
+        }
+        if source == destination {
+            return Ok(Response::Normal(Reply::Integer(1)));
+        }
+
+        if let Some(set) = self.get_set_mut(&source)? {
+            set.remove(&member);
+            if set.is_empty() {
+                self.db.remove(&source);
+            }
+        }
+        self.db.mark_modified(&source);
+        match self.get_set_mut(&destination)? {
+            Some(set) => {
+                set.insert(member);
+                self.db.mark_modified(&destination);
+            }
+            None => self
+                .db
+                .insert(destination, Re::Set(HashSet::from([member]).into())),
+        }
+        Ok(Response::Normal(Reply::Integer(1)))
+    }
+
+    /// Elimina y retorna miembros al azar del set almacenado en la clave. Sin `count`, se retorna
+    /// un único miembro (o Nil si la clave no existe); con `count`, hasta esa cantidad de miembros.
+    ///
+    /// Como los miembros se eligen al azar, el comando se registra como SREM de los elegidos.
+    fn spop_method(&mut self, key: Vec<u8>, count: Option<usize>) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command SPOP Received - key: ".to_string() + &to_text(&key),
+        ));
+
+        let popped = match self.get_set_mut(&key)? {
+            Some(set) => {
+                let popped = random_members(set, count.unwrap_or(1));
+                for member in popped.iter() {
+                    set.remove(member);
+                }
+                if set.is_empty() {
+                    self.db.remove(&key);
+                }
+                popped
+            }
+            None => vec![],
+        };
+        if !popped.is_empty() {
+            self.db.mark_modified(&key);
+            let mut args = vec![b"srem".to_vec(), key];
+            args.extend(popped.iter().cloned());
+            self.rewritten_args = Some(args);
+        }
+
+        match count {
+            Some(_) => Ok(Response::Normal(Reply::bulk_strings(popped))),
+            None => Ok(Response::Normal(
+                popped
+                    .into_iter()
+                    .next()
+                    .map_or(Reply::NullBulk, Reply::BulkString),
+            )),
+        }
+    }
+
+    /// Retorna miembros al azar del set almacenado en la clave, sin modificarlo. Sin `count`, se
+    /// retorna un único miembro (o Nil si la clave no existe). Con un `count` positivo, se
+    /// retornan hasta esa cantidad de miembros distintos; con uno negativo, se retornan
+    /// exactamente `-count` miembros, que pueden repetirse.
+    fn srandmember_method(&mut self, key: Vec<u8>, count: Option<i64>) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command SRANDMEMBER Received - key: ".to_string() + &to_text(&key),
+        ));
+
+        let set = match self.get_set(&key)? {
+            Some(set) => set,
+            None if count.is_none() => return Ok(Response::Normal(Reply::NullBulk)),
+            None => return Ok(Response::Normal(Reply::Array(vec![]))),
+        };
+        let members: Vec<Vec<u8>> = match count {
+            Some(count) if count < 0 => {
+                let members: Vec<&Vec<u8>> = set.iter().collect();
+                (0..count.unsigned_abs())
+                    .map(|_| members[(random_u64() % members.len() as u64) as usize].to_vec())
+                    .collect()
+            }
+            Some(count) => random_members(set, count as usize),
+            None => {
+                return Ok(Response::Normal(
+                    random_members(set, 1)
+                        .into_iter()
+                        .next()
+                        .map_or(Reply::NullBulk, Reply::BulkString),
+                ))
+            }
+        };
+        Ok(Response::Normal(Reply::bulk_strings(members)))
+    }
+
+    /// Obtiene una referencia al hash almacenado en la clave indicada, sin modificarlo.
+    ///
+    /// Retorna None si la clave no existe, y error si el valor almacenado en la clave no es un
+    /// hash.
+    fn get_hash(&mut self, key: &[u8]) -> Result<Option<&Hash>, String> {
+        let log_sender = &self.log_sender;
+        match self.db.get(&key.to_vec()) {
+            Some(Re::Hash(hash)) => Ok(Some(hash)),
+            Some(_) => {
+                let _ = log_sender.send(Log::new(
+                    LogLevel::Error,
+                    line!(),
+                    column!(),
+                    file!().to_string(),
+                    WRONGTYPE_MSG.to_string(),
+                ));
+                Err(WRONGTYPE_MSG.to_string())
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Obtiene una referencia mutable al hash almacenado en la clave indicada.
+    ///
+    /// Retorna None si la clave no existe, y error si el valor almacenado en la clave no es un
+    /// hash.
+    fn get_hash_mut(&mut self, key: &[u8]) -> Result<Option<&mut Hash>, String> {
+        let log_sender = &self.log_sender;
+        match self.db.get_mut(&key.to_vec()) {
+            Some(Re::Hash(hash)) => Ok(Some(hash)),
+            Some(_) => {
+                let _ = log_sender.send(Log::new(
+                    LogLevel::Error,
+                    line!(),
+                    column!(),
+                    file!().to_string(),
+                    WRONGTYPE_MSG.to_string(),
+                ));
+                Err(WRONGTYPE_MSG.to_string())
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Setea los campos indicados con sus respectivos valores en el hash almacenado en la clave.
+    ///
+    /// Si la clave no existe, se crea un hash vacío antes de realizar la operación. Si el campo ya
+    /// existía en el hash, se sobreescribe su valor. Retorna la cantidad de campos agregados.
+    ///
+    /// Retorna error si el valor almacenado en la clave no es un hash.
+    fn hset_method(
+        &mut self,
+        key: Vec<u8>,
+        field_values: Vec<(Vec<u8>, Vec<u8>)>,
+    ) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command HSET Received - key: ".to_string() + &to_text(&key),
+        ));
+
+        let mut count = 0;
+        match self.get_hash_mut(&key)? {
+            Some(hash) => {
+                for (field, value) in field_values {
+                    if hash.insert(field, value).is_none() {
+                        count += 1;
+                    }
+                }
+                self.db.mark_modified(&key);
+            }
+            None => {
+                let hash: Hash = field_values.into_iter().collect();
+                count = hash.len();
+                self.db.insert(key, Re::Hash(hash));
+            }
+        }
+
+        Ok(Response::Normal(Reply::Integer(count as i64)))
+    }
+
+    /// Setea los campos indicados con sus respectivos valores en el hash almacenado en la clave.
+    ///
+    /// Se comporta igual que HSET, pero retorna OK en lugar de la cantidad de campos agregados.
+    fn hmset_method(
+        &mut self,
+        key: Vec<u8>,
+        field_values: Vec<(Vec<u8>, Vec<u8>)>,
+    ) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command HMSET Received - key: ".to_string() + &to_text(&key),
+        ));
+
+        self.hset_method(key, field_values)?;
+        Ok(Response::Normal(Reply::SimpleString("OK".to_string())))
+    }
+
+    /// Setea el campo del hash almacenado en la clave con el valor indicado, solamente si el campo
+    /// no existe. Si la clave no existe, se crea un hash nuevo.
+    ///
+    /// Retorna 1 si el campo fue seteado, y 0 si el campo ya existía.
+    fn hsetnx_method(
+        &mut self,
+        key: Vec<u8>,
+        field: Vec<u8>,
+        value: Vec<u8>,
+    ) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command HSETNX Received - key: ".to_string() + &to_text(&key),
+        ));
+
+        if let Some(hash) = self.get_hash(&key)? {
+            if hash.contains_key(&field) {
+                return Ok(Response::Normal(Reply::Integer(0)));
+            }
+        }
+
+        self.hset_method(key, vec![(field, value)])
+    }
+
+    /// Retorna el valor asociado al campo del hash almacenado en la clave indicada. Si el campo o
+    /// la clave no existen, se retorna el valor especial nil.
+    fn hget_method(&mut self, key: Vec<u8>, field: Vec<u8>) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command HGET Received - key: ".to_string() + &to_text(&key),
+        ));
+
+        match self.get_hash(&key)?.and_then(|hash| hash.get(&field)) {
+            Some(value) => Ok(Response::Normal(Reply::BulkString(value.to_vec()))),
+            None => Ok(Response::Normal(Reply::NullBulk)),
+        }
+    }
+
+    /// Retorna los valores asociados a los campos indicados del hash almacenado en la clave. Para
+    /// los campos que no existen se retorna el valor especial nil.
+    fn hmget_method(&mut self, key: Vec<u8>, fields: Vec<Vec<u8>>) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command HMGET Received - key: ".to_string() + &to_text(&key),
+        ));
+
+        let hash = self.get_hash(&key)?;
+        let mut values = Vec::new();
+        for field in fields.iter() {
+            let value = match hash.as_ref().and_then(|hash| hash.get(field)) {
+                Some(value) => Reply::BulkString(value.to_vec()),
+                None => Reply::NullBulk,
+            };
+            values.push(value);
+        }
+
+        Ok(Response::Normal(Reply::Array(values)))
+    }
+
+    /// Retorna todos los campos con sus valores del hash almacenado en la clave indicada. Si la
+    /// clave no existe, se retorna un hash vacío.
+    fn hgetall_method(&mut self, key: Vec<u8>) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command HGETALL Received - key: ".to_string() + &to_text(&key),
+        ));
+
+        match self.get_hash(&key)? {
+            Some(hash) => Ok(Response::Normal(Re::Hash(hash.clone()).into())),
+            None => Ok(Response::Normal(Reply::Array(vec![]))),
+        }
+    }
+
+    /// Elimina los campos especificados del hash almacenado en la clave indicada. Los campos que
+    /// no existen son ignorados. Si el hash queda vacío, se elimina la clave.
+    ///
+    /// Retorna la cantidad de campos eliminados.
+    fn hdel_method(&mut self, key: Vec<u8>, fields: Vec<Vec<u8>>) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command HDEL Received - key: ".to_string() + &to_text(&key),
+        ));
+
+        let mut count = 0;
+        if let Some(hash) = self.get_hash_mut(&key)? {
+            for field in fields.iter() {
+                if hash.remove(field).is_some() {
+                    count += 1;
+                }
+            }
+
+            if hash.is_empty() {
+                self.db.remove(&key);
+            }
+        }
+        if count > 0 {
+            self.db.mark_modified(&key);
+        }
+
+        Ok(Response::Normal(Reply::Integer(count as i64)))
+    }
+
+    /// Retorna si el campo indicado existe en el hash almacenado en la clave.
+    fn hexists_method(&mut self, key: Vec<u8>, field: Vec<u8>) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command HEXISTS Received - key: ".to_string() + &to_text(&key),
+        ));
+
+        match self.get_hash(&key)? {
+            Some(hash) if hash.contains_key(&field) => Ok(Response::Normal(Reply::Integer(1))),
+            _ => Ok(Response::Normal(Reply::Integer(0))),
+        }
+    }
+
+    /// Retorna la cantidad de campos del hash almacenado en la clave indicada. Si la clave no
+    /// existe, se retorna 0.
+    fn hlen_method(&mut self, key: Vec<u8>) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command HLEN Received - key: ".to_string() + &to_text(&key),
+        ));
+
+        let len = self.get_hash(&key)?.map_or(0, |hash| hash.len());
+        Ok(Response::Normal(Reply::Integer(len as i64)))
+    }
+
+    /// Retorna el largo del valor asociado al campo del hash almacenado en la clave indicada. Si
+    /// el campo o la clave no existen, se retorna 0.
+    fn hstrlen_method(&mut self, key: Vec<u8>, field: Vec<u8>) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command HSTRLEN Received - key: ".to_string() + &to_text(&key),
+        ));
+
+        let len = self
+            .get_hash(&key)?
+            .and_then(|hash| hash.get(&field))
+            .map_or(0, |value| value.len());
+        Ok(Response::Normal(Reply::Integer(len as i64)))
+    }
+
+    /// Retorna todos los campos del hash almacenado en la clave indicada.
+    fn hkeys_method(&mut self, key: Vec<u8>) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command HKEYS Received - key: ".to_string() + &to_text(&key),
+        ));
+
+        let fields = match self.get_hash(&key)? {
+            Some(hash) => hash.keys().cloned().collect(),
+            None => vec![],
+        };
+        Ok(Response::Normal(Reply::bulk_strings(fields)))
+    }
+
+    /// Retorna todos los valores del hash almacenado en la clave indicada.
+    fn hvals_method(&mut self, key: Vec<u8>) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command HVALS Received - key: ".to_string() + &to_text(&key),
+        ));
+
+        let values = match self.get_hash(&key)? {
+            Some(hash) => hash.values().cloned().collect(),
+            None => vec![],
+        };
+        Ok(Response::Normal(Reply::bulk_strings(values)))
+    }
+
+    /// Incrementa el número almacenado en el campo del hash de la clave indicada en un incremento.
+    ///
+    /// Si la clave o el campo no existen, el valor es seteado a 0 antes de realizar la operación.
+    /// Devuelve error si el campo contiene un valor que no puede ser representado como entero, o
+    /// si la operación produce un overflow.
+    fn hincrby_method(
+        &mut self,
+        key: Vec<u8>,
+        field: Vec<u8>,
+        increment: i64,
+    ) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command HINCRBY Received - key: ".to_string() + &to_text(&key),
+        ));
+
+        let current = match self.get_hash(&key)?.and_then(|hash| hash.get(&field)) {
+            Some(value) => match to_text(value).parse::<i64>() {
+                Ok(number) => number,
+                Err(_) => {
+                    let _ = self.log_sender.send(Log::new(
+                        LogLevel::Error,
+                        line!(),
+                        column!(),
+                        file!().to_string(),
+                        "ERR hash value is not an integer".to_string(),
+                    ));
+                    return Err("ERR hash value is not an integer".to_string());
+                }
+            },
+            None => 0,
+        };
+
+        let value = match current.checked_add(increment) {
+            Some(value) => value,
+            None => {
+                let _ = self.log_sender.send(Log::new(
+                    LogLevel::Error,
+                    line!(),
+                    column!(),
+                    file!().to_string(),
+                    "ERR increment or decrement would overflow".to_string(),
+                ));
+                return Err("ERR increment or decrement would overflow".to_string());
+            }
+        };
+
+        self.hset_method(key, vec![(field, value.to_string().into_bytes())])?;
+        Ok(Response::Normal(Reply::Integer(value)))
+    }
+
+    /// Incrementa el número de punto flotante almacenado en el campo del hash de la clave indicada
+    /// en un incremento, y devuelve el resultado con el formato de `format_float`.
+    ///
+    /// Si la clave o el campo no existen, el valor es seteado a 0 antes de realizar la operación.
+    /// Devuelve error si el campo contiene un valor que no es un número, o si el resultado no es
+    /// finito. Se registra como HSET del resultado, igual que INCRBYFLOAT.
+    fn hincrbyfloat_method(
+        &mut self,
+        key: Vec<u8>,
+        field: Vec<u8>,
+        increment: f64,
+    ) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command HINCRBYFLOAT Received - key: ".to_string() + &to_text(&key),
+        ));
+
+        let current = match self.get_hash(&key)?.and_then(|hash| hash.get(&field)) {
+            Some(value) => match parse_float(value) {
+                Some(number) => number,
+                None => return Err("ERR hash value is not a float".to_string()),
+            },
+            None => 0.0,
+        };
+
+        let value = format_float(current + increment)?.into_bytes();
+        self.hset_method(key.clone(), vec![(field.clone(), value.clone())])?;
+        self.rewritten_args = Some(vec![b"hset".to_vec(), key, field, value.clone()]);
+        Ok(Response::Normal(Reply::BulkString(value)))
+    }
+
+    /// Itera incrementalmente los campos del hash almacenado en la clave indicada.
+    ///
+    /// Cada llamada revisa aproximadamente `count` campos a partir del cursor indicado, y retorna
+    /// los campos con sus valores junto con el cursor a utilizar en la próxima llamada, igual que
+    /// SCAN. Cuando el cursor retornado es 0 la iteración finalizó. Si se indica un patrón,
+    /// solamente se retornan los campos que hacen match con él.
+    fn hscan_method(
+        &mut self,
+        key: Vec<u8>,
+        cursor: u64,
+        pattern: Option<Vec<u8>>,
+        count: usize,
+    ) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command HSCAN Received - key: ".to_string() + &to_text(&key),
+        ));
+
+        let hash = match self.get_hash(&key)? {
+            Some(hash) => hash,
+            None => return Ok(Response::Normal(Reply::scan("0".to_string(), vec![]))),
+        };
+
+        let (next_cursor, fields) = hash.scan(cursor, count);
+        let mut elements = vec![];
+        for field in fields {
+            if pattern.as_ref().is_none_or(|p| glob_match(p, field)) {
+                elements.push(field.to_vec());
+                elements.push(hash[field].clone());
+            }
+        }
+
+        Ok(Response::Normal(Reply::scan(
+            next_cursor.to_string(),
+            elements,
+        )))
+    }
+
+    /// Obtiene una referencia al sorted set almacenado en la clave indicada, sin modificarlo.
+    ///
+    /// Retorna None si la clave no existe, y error si el valor almacenado en la clave no es un
+    /// sorted set.
+    fn get_sorted_set(&mut self, key: &[u8]) -> Result<Option<&SortedSet>, String> {
+        let log_sender = &self.log_sender;
+        match self.db.get(&key.to_vec()) {
+            Some(Re::SortedSet(sorted_set)) => Ok(Some(sorted_set)),
+            Some(_) => {
+                let _ = log_sender.send(Log::new(
+                    LogLevel::Error,
+                    line!(),
+                    column!(),
+                    file!().to_string(),
+                    WRONGTYPE_MSG.to_string(),
+                ));
+                Err(WRONGTYPE_MSG.to_string())
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Obtiene una referencia mutable al sorted set almacenado en la clave indicada.
+    ///
+    /// Retorna None si la clave no existe, y error si el valor almacenado en la clave no es un
+    /// sorted set.
+    fn get_sorted_set_mut(&mut self, key: &[u8]) -> Result<Option<&mut SortedSet>, String> {
+        let log_sender = &self.log_sender;
+        match self.db.get_mut(&key.to_vec()) {
+            Some(Re::SortedSet(sorted_set)) => Ok(Some(sorted_set)),
+            Some(_) => {
+                let _ = log_sender.send(Log::new(
+                    LogLevel::Error,
+                    line!(),
+                    column!(),
+                    file!().to_string(),
+                    WRONGTYPE_MSG.to_string(),
+                ));
+                Err(WRONGTYPE_MSG.to_string())
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Agrega los miembros indicados con sus puntajes al sorted set almacenado en la clave. Si un
+    /// miembro ya existía, se actualiza su puntaje. Si la clave no existe, se crea un sorted set
+    /// nuevo.
+    ///
+    /// Las opciones modifican el comportamiento del comando:
+    ///
+    /// * `nx` - Solamente agrega miembros nuevos, nunca actualiza los existentes.
+    /// * `xx` - Solamente actualiza miembros existentes, nunca agrega nuevos.
+    /// * `gt` - Solamente actualiza si el nuevo puntaje es mayor al actual.
+    /// * `lt` - Solamente actualiza si el nuevo puntaje es menor al actual.
+    /// * `ch` - Retorna la cantidad de miembros agregados y actualizados, en lugar de solamente
+    ///   los agregados.
+    /// * `incr` - Se comporta como ZINCRBY, retornando el nuevo puntaje del miembro.
+    #[allow(clippy::too_many_arguments)]
+    fn zadd_method(
+        &mut self,
+        key: Vec<u8>,
+        members: Vec<(f64, Vec<u8>)>,
+        nx: bool,
+        xx: bool,
+        gt: bool,
+        lt: bool,
+        ch: bool,
+        incr: bool,
+    ) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command ZADD Received - key: ".to_string() + &to_text(&key),
+        ));
+
+        let mut created = SortedSet::new();
+        let exists = self.get_sorted_set(&key)?.is_some();
+        let sorted_set = match self.get_sorted_set_mut(&key)? {
+            Some(sorted_set) => sorted_set,
+            None => &mut created,
+        };
+
+        let (mut added, mut changed) = (0, 0);
+        let mut incr_result = Reply::NullBulk;
+        let mut nan_score = false;
+        for (score, member) in members {
+            let current = sorted_set.score(&member);
+            let score = if incr {
+                current.unwrap_or(0.0) + score
+            } else {
+                score
+            };
+            if score.is_nan() {
+                nan_score = true;
+                break;
+            }
+
+            match current {
+                Some(current) => {
+                    if nx || (gt && score <= current) || (lt && score >= current) {
+                        continue;
+                    }
+                    if score != current {
+                        sorted_set.insert(member, score);
+                        changed += 1;
+                    }
+                }
+                None => {
+                    if xx {
+                        continue;
+                    }
+                    sorted_set.insert(member, score);
+                    added += 1;
+                }
+            }
+            incr_result = Reply::BulkString(format_score(score).into_bytes());
+        }
+
+        if exists && added + changed > 0 {
+            self.db.mark_modified(&key);
+        }
+        if nan_score {
+            return Err("ERR resulting score is not a number (NaN)".to_string());
+        }
+        if !exists && !created.is_empty() {
+            self.db.insert(key, Re::SortedSet(created));
+        }
+
+        if incr {
+            return Ok(Response::Normal(incr_result));
+        }
+        let count = if ch { added + changed } else { added };
+        Ok(Response::Normal(Reply::Integer(count as i64)))
+    }
+
+    /// Incrementa el puntaje del miembro del sorted set almacenado en la clave indicada. Si el
+    /// miembro o la clave no existen, se agrega el miembro con el incremento como puntaje.
+    ///
+    /// Retorna el nuevo puntaje del miembro.
+    fn zincrby_method(
+        &mut self,
+        key: Vec<u8>,
+        increment: f64,
+        member: Vec<u8>,
+    ) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command ZINCRBY Received - key: ".to_string() + &to_text(&key),
+        ));
+
+        self.zadd_method(
+            key,
+            vec![(increment, member)],
+            false,
+            false,
+            false,
+            false,
+            false,
+            true,
+        )
+    }
+
+    /// Retorna la cantidad de miembros del sorted set almacenado en la clave indicada. Si la
+    /// clave no existe, se retorna 0.
+    fn zcard_method(&mut self, key: Vec<u8>) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command ZCARD Received - key: ".to_string() + &to_text(&key),
+        ));
+
+        let len = self.get_sorted_set(&key)?.map_or(0, |set| set.len());
+        Ok(Response::Normal(Reply::Integer(len as i64)))
+    }
+
+    /// Retorna la cantidad de miembros del sorted set almacenado en la clave indicada cuyo
+    /// puntaje se encuentra dentro del rango indicado.
+    fn zcount_method(
+        &mut self,
+        key: Vec<u8>,
+        min: ScoreBound,
+        max: ScoreBound,
+    ) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command ZCOUNT Received - key: ".to_string() + &to_text(&key),
+        ));
+
+        let count = self
+            .get_sorted_set(&key)?
+            .map_or(0, |set| set.count_by_score(&min, &max));
+        Ok(Response::Normal(Reply::Integer(count as i64)))
+    }
+
+    /// Retorna el puntaje del miembro del sorted set almacenado en la clave indicada. Si el
+    /// miembro o la clave no existen, se retorna el valor especial nil.
+    fn zscore_method(&mut self, key: Vec<u8>, member: Vec<u8>) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command ZSCORE Received - key: ".to_string() + &to_text(&key),
+        ));
+
+        match self
+            .get_sorted_set(&key)?
+            .and_then(|set| set.score(&member))
+        {
+            Some(score) => Ok(Response::Normal(Reply::BulkString(
+                format_score(score).into_bytes(),
+            ))),
+            None => Ok(Response::Normal(Reply::NullBulk)),
+        }
+    }
+
+    /// Retorna la posición del miembro en el sorted set almacenado en la clave indicada, ordenado
+    /// de menor a mayor puntaje, o de mayor a menor si `rev` es verdadero. La posición comienza en
+    /// 0. Si el miembro o la clave no existen, se retorna el valor especial nil.
+    fn zrank_method(
+        &mut self,
+        key: Vec<u8>,
+        member: Vec<u8>,
+        rev: bool,
+    ) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command ZRANK Received - key: ".to_string() + &to_text(&key),
+        ));
+
+        let rank = self.get_sorted_set(&key)?.and_then(|set| {
+            let rank = set.rank(&member)?;
+            if rev {
+                Some(set.len() - 1 - rank)
+            } else {
+                Some(rank)
+            }
+        });
+
+        match rank {
+            Some(rank) => Ok(Response::Normal(Reply::Integer(rank as i64))),
+            None => Ok(Response::Normal(Reply::NullBulk)),
+        }
+    }
+
+    /// Elimina los miembros indicados del sorted set almacenado en la clave. Los miembros que no
+    /// existen son ignorados. Si el sorted set queda vacío, se elimina la clave.
+    ///
+    /// Retorna la cantidad de miembros eliminados.
+    fn zrem_method(&mut self, key: Vec<u8>, members: Vec<Vec<u8>>) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command ZREM Received - key: ".to_string() + &to_text(&key),
+        ));
+
+        let mut count = 0;
+        if let Some(sorted_set) = self.get_sorted_set_mut(&key)? {
+            for member in members.iter() {
+                if sorted_set.remove(member).is_some() {
+                    count += 1;
+                }
+            }
+
+            if sorted_set.is_empty() {
+                self.db.remove(&key);
+            }
+        }
+        if count > 0 {
+            self.db.mark_modified(&key);
+        }
+
+        Ok(Response::Normal(Reply::Integer(count as i64)))
+    }
+
+    /// Elimina y retorna los `count` miembros con menor puntaje del sorted set almacenado en la
+    /// clave indicada, o los de mayor puntaje si `max` es verdadero. Si el sorted set queda vacío,
+    /// se elimina la clave.
+    ///
+    /// Retorna una lista de la forma miembro, puntaje, miembro, puntaje...
+    fn zpop_method(&mut self, key: Vec<u8>, count: usize, max: bool) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            if max {
+                "Command ZPOPMAX Received - key: ".to_string() + &to_text(&key)
+            } else {
+                "Command ZPOPMIN Received - key: ".to_string() + &to_text(&key)
+            },
+        ));
+
+        let mut popped = vec![];
+        if let Some(sorted_set) = self.get_sorted_set_mut(&key)? {
+            for _ in 0..count {
+                let element = if max {
+                    sorted_set.pop_max()
+                } else {
+                    sorted_set.pop_min()
+                };
+                match element {
+                    Some((member, score)) => {
+                        popped.push(member);
+                        popped.push(format_score(score).into_bytes());
+                    }
+                    None => break,
+                }
+            }
+
+            if sorted_set.is_empty() {
+                self.db.remove(&key);
+            }
+        }
+        if !popped.is_empty() {
+            self.db.mark_modified(&key);
+        }
+
+        Ok(Response::Normal(Reply::bulk_strings(popped)))
+    }
+
+    /// Retorna los miembros del sorted set almacenado en la clave indicada que se encuentran
+    /// dentro del rango pedido. El rango puede ser por posición, por puntaje o lexicográfico,
+    /// según lo indicado en `ZRangeParam`.
+    ///
+    /// # Arguments
+    ///
+    /// * `rev` - Indica si los miembros se devuelven de mayor a menor.
+    /// * `limit` - Desplazamiento y cantidad de miembros a devolver. Una cantidad negativa
+    ///   devuelve todos los miembros a partir del desplazamiento.
+    /// * `with_scores` - Indica si se devuelven los puntajes junto a los miembros.
+    fn zrange_method(
+        &mut self,
+        key: Vec<u8>,
+        param: ZRangeParam,
+        rev: bool,
+        limit: Option<(i64, i64)>,
+        with_scores: bool,
+    ) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command ZRANGE Received - key: ".to_string() + &to_text(&key),
+        ));
+
+        let sorted_set = match self.get_sorted_set(&key)? {
+            Some(sorted_set) => sorted_set,
+            None => return Ok(Response::Normal(Reply::Array(vec![]))),
+        };
+
+        let limit = match limit {
+            Some((offset, _)) if offset < 0 => return Ok(Response::Normal(Reply::Array(vec![]))),
+            Some((offset, count)) if count < 0 => Some((offset as usize, usize::MAX)),
+            Some((offset, count)) => Some((offset as usize, count as usize)),
+            None => None,
+        };
+
+        let range = match param {
+            ZRangeParam::Rank { start, stop } => sorted_set.range_by_rank(start, stop, rev),
+            ZRangeParam::Score { min, max } => sorted_set.range_by_score(&min, &max, rev, limit),
+            ZRangeParam::Lex { min, max } => sorted_set.range_by_lex(&min, &max, rev, limit),
+        };
+
+        let mut elements = vec![];
+        for (member, score) in range {
+            elements.push(member);
+            if with_scores {
+                elements.push(format_score(score).into_bytes());
+            }
+        }
+        Ok(Response::Normal(Reply::bulk_strings(elements)))
+    }
+
+    /// Obtiene los miembros con sus puntajes de la clave indicada para ser usados como origen de
+    /// ZUNIONSTORE y ZINTERSTORE. Los sets son considerados como sorted sets con puntaje 1.
+    ///
+    /// Retorna None si la clave no existe, y error si el valor no es un set o un sorted set.
+    fn zstore_source(&mut self, key: &[u8]) -> Result<Option<HashMap<Vec<u8>, f64>>, String> {
+        match self.db.get(&key.to_vec()) {
+            Some(Re::SortedSet(sorted_set)) => Ok(Some(
+                sorted_set
+                    .iter()
+                    .map(|(member, score)| (member.to_vec(), score))
+                    .collect(),
+            )),
+            Some(Re::Set(set)) => Ok(Some(
+                set.iter().map(|member| (member.to_vec(), 1.0)).collect(),
+            )),
+            Some(_) => {
+                let _ = self.log_sender.send(Log::new(
+                    LogLevel::Error,
+                    line!(),
+                    column!(),
+                    file!().to_string(),
+                    WRONGTYPE_MSG.to_string(),
+                ));
+                Err(WRONGTYPE_MSG.to_string())
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Calcula la unión, o la intersección si `inter` es verdadero, de los sorted sets
+    /// almacenados en las claves indicadas y guarda el resultado en la clave destino.
+    ///
+    /// El puntaje de cada miembro se multiplica por el peso de su clave de origen, y los puntajes
+    /// de un mismo miembro se combinan según lo indicado en `AggregateParam`. Si la clave destino
+    /// existía, es sobreescrita.
+    ///
+    /// Retorna la cantidad de miembros del sorted set resultante.
+    fn zstore_method(
+        &mut self,
+        destination: Vec<u8>,
+        keys: Vec<Vec<u8>>,
+        weights: Vec<f64>,
+        aggregate: AggregateParam,
+        inter: bool,
+    ) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            if inter {
+                "Command ZINTERSTORE Received - destination: ".to_string() + &to_text(&destination)
+            } else {
+                "Command ZUNIONSTORE Received - destination: ".to_string() + &to_text(&destination)
+            },
+        ));
+
+        let mut sources = vec![];
+        for key in keys.iter() {
+            sources.push(self.zstore_source(key)?.unwrap_or_default());
+        }
+
+        let mut result: HashMap<Vec<u8>, f64> = HashMap::new();
+        for (source, weight) in sources.iter().zip(weights) {
+            for (member, score) in source.iter() {
+                if inter && sources.iter().any(|other| !other.contains_key(member)) {
+                    continue;
+                }
+
+                let mut score = score * weight;
+                if score.is_nan() {
+                    score = 0.0;
+                }
+                let combined = match result.get(member) {
+                    Some(current) => match aggregate {
+                        AggregateParam::Sum => current + score,
+                        AggregateParam::Min => current.min(score),
+                        AggregateParam::Max => current.max(score),
+                    },
+                    None => score,
+                };
+                result.insert(
+                    member.to_vec(),
+                    if combined.is_nan() { 0.0 } else { combined },
+                );
+            }
+        }
+
+        self.db.remove(&destination);
+        let len = result.len();
+        if len > 0 {
+            let mut sorted_set = SortedSet::new();
+            for (member, score) in result {
+                sorted_set.insert(member, score);
+            }
+            self.db.insert(destination, Re::SortedSet(sorted_set));
+        }
+
+        Ok(Response::Normal(Reply::Integer(len as i64)))
+    }
+
+    /// Itera las claves de la base de datos a partir del cursor, revisando aproximadamente `count`
+    /// claves por llamada. Retorna el cursor con el que se continúa la iteración (0 si terminó) y
+    /// las claves obtenidas, que pueden ser menos que `count` si se indica un patrón o un tipo.
+    ///
+    /// Toda clave que exista durante toda la iteración se devuelve al menos una vez, aunque se
+    /// agreguen o borren otras claves entre llamadas.
+    fn scan_method(
+        &mut self,
+        cursor: u64,
+        pattern: Option<Vec<u8>>,
+        count: usize,
+        value_type: Option<String>,
+    ) -> Response {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            format!("Command SCAN Received - cursor: {}", cursor),
+        ));
+
+        let (next_cursor, keys) = self.db.scan(cursor, count);
+        let keys = keys
+            .into_iter()
+            .filter(|key| pattern.as_ref().is_none_or(|p| glob_match(p, key)))
+            .filter(|key| {
+                value_type.as_ref().is_none_or(|value_type| {
+                    self.db.get(key).map(type_name) == Some(value_type.as_str())
+                })
+            })
+            .collect();
+        Response::Normal(Reply::scan(next_cursor.to_string(), keys))
+    }
+
+    /// Retorna todas las claves que no expiraron y hacen match con un patrón glob (ver
+    /// `glob_match`).
+    fn keys_method(&mut self, pattern: Vec<u8>) -> Vec<Vec<u8>> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command KEYS Received - pattern: ".to_string() + &to_text(&pattern),
+        ));
+
+        self.db
+            .iter()
+            .filter(|(key, _, _)| glob_match(&pattern, key))
+            .map(|(key, _, _)| key.to_vec())
+            .collect()
+    }
+
+    /// Comando interno para Persistir los elementos de la Base de datos en un archivo
+    fn store_method(&self, path: String) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command STORE Received - path: ".to_string() + &*path,
+        ));
+
+        match Rdb::write_file(&path, &TtlHashMap::serialize_databases(&self.databases())) {
+            Ok(_) => Ok(Response::Normal(Reply::SimpleString("OK".to_string()))),
+            Err(e) => {
+                let _ = self.log_sender.send(Log::new(
+                    LogLevel::Error,
+                    line!(),
+                    column!(),
+                    file!().to_string(),
+                    e.to_string(),
+                ));
+                Err(e.to_string())
+            }
+        }
+    }
+
+    /// Comando interno para Cargar los elementos de la Base de datos desde un archivo
+    fn load_method(&mut self, path: String) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command LOAD Received - path: ".to_string() + &*path,
+        ));
+
+        let stream = match fs::read(path) {
+            Ok(stream) => stream,
+            Err(e) => {
+                let _ = self.log_sender.send(Log::new(
+                    LogLevel::Error,
+                    line!(),
+                    column!(),
+                    file!().to_string(),
+                    format!("{:?}", e),
+                ));
+                return Err(format!("{:?}", e));
+            }
+        };
+
+        match TtlHashMap::deserialize_databases(stream, self.dbs.len()) {
+            Ok(dbs) => {
+                self.set_databases(dbs);
+                self.dirty = 0;
+                Ok(Response::Normal(Reply::SimpleString("OK".to_string())))
+            }
+            Err(e) => {
+                let _ = self.log_sender.send(Log::new(
+                    LogLevel::Error,
+                    line!(),
+                    column!(),
+                    file!().to_string(),
+                    format!("{:?}", e),
+                ));
+                Err(format!("{:?}", e))
+            }
+        }
+    }
+
+    /// Comando interno para cargar la Base de datos reproduciendo los comandos del archivo AOF, y
+    /// habilitar el registro de los próximos comandos de escritura en él.
+    ///
+    /// Si el archivo AOF no existe, se cargan los datos desde el archivo RDB configurado y se crea
+    /// el AOF a partir de ellos.
+    ///
+    /// Igual que los comandos del master, los comandos del AOF se aplican directamente: no se
+    /// rechazan por ser réplica de sólo lectura ni se redirigen en modo cluster. Los comandos de
+    /// una transacción se aplican al llegar a su EXEC; si el archivo termina antes, se descartan.
+    fn load_aof_method(&mut self, path: String) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command LOAD AOF Received - path: ".to_string() + &*path,
+        ));
+
+        self.aof = None;
+        let exists = Path::new(&path).exists();
+        if exists {
+            let commands = Aof::read(&path).map_err(|e| e.to_string())?;
+            self.select_db(0);
+            let mut transaction: Option<Vec<Vec<Vec<u8>>>> = None;
+            for args in commands {
+                if is_command(&args, "multi") {
+                    transaction = Some(Vec::new());
+                } else if is_command(&args, "exec") {
+                    for args in transaction.take().unwrap_or_default() {
+                        self.replay_aof_command(args);
+                    }
+                } else if let Some(queued) = transaction.as_mut() {
+                    queued.push(args);
+                } else {
+                    self.replay_aof_command(args);
+                }
+            }
+            if transaction.is_some() {
+                let _ = self.log_sender.send(Log::new(
+                    LogLevel::Error,
+                    line!(),
+                    column!(),
+                    file!().to_string(),
+                    "AOF ends inside a transaction, discarding it".to_string(),
+                ));
+            }
+        } else {
+            let dbfilename = self.config.lock().unwrap().get_dbfilename();
+            let _ = self.load_method(dbfilename);
+        }
+
+        let fsync = self.config.lock().unwrap().get_appendfsync();
+        let mut aof = Aof::open(path, fsync).map_err(|e| e.to_string())?;
+        if !exists {
+            aof.rewrite(&all_databases(&self.db, &self.dbs, self.selected_db))
+                .map_err(|e| e.to_string())?;
+        }
+        self.aof = Some(aof);
+        self.dirty = 0;
+
+        Ok(Response::Normal(Reply::SimpleString("OK".to_string())))
+    }
+
+    /// Aplica un comando leído del AOF, loggeando el error si falla.
+    fn replay_aof_command(&mut self, args: Vec<Vec<u8>>) {
+        let result =
+            generate(args, "AOF".to_string()).and_then(|command| self.execute_notifying(command));
+        if let Err(e) = result {
+            let _ = self.log_sender.send(Log::new(
+                LogLevel::Error,
+                line!(),
+                column!(),
+                file!().to_string(),
+                "Error replaying AOF: ".to_string() + &e,
+            ));
+        }
+    }
+
+    /// El comando SAVE guarda la base de datos en el archivo RDB configurado, bloqueando al resto
+    /// de los clientes hasta terminar.
+    fn save_method(&mut self) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command SAVE Received".to_string(),
+        ));
+
+        let path = self.config.lock().unwrap().get_dbfilename();
+        match self.rdb.save(&path, &self.databases()) {
+            Ok(_) => {
+                self.dirty = 0;
+                Ok(Response::Normal(Reply::SimpleString("OK".to_string())))
+            }
+            Err(e) => {
+                let _ = self.log_sender.send(Log::new(
+                    LogLevel::Error,
+                    line!(),
+                    column!(),
+                    file!().to_string(),
+                    e.to_string(),
+                ));
+                Err(e.to_string())
+            }
+        }
+    }
+
+    /// El comando BGSAVE guarda la base de datos en el archivo RDB configurado desde un hilo
+    /// aparte, por lo que el resto de los clientes no queda bloqueado mientras se escribe el
+    /// archivo.
+    ///
+    /// El hilo escribe una copia de las bases de datos que comparte los valores con ellas (ver
+    /// `TtlHashMap::snapshot`). En el hilo de la base de datos sólo se copian las claves, y cada
+    /// valor que se modifica mientras se escribe el archivo se copia al modificarlo.
+    fn bgsave_method(&mut self) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command BGSAVE Received".to_string(),
+        ));
+
+        let path = self.config.lock().unwrap().get_dbfilename();
+        let snapshot = self.databases().iter().map(|db| db.snapshot()).collect();
+        match self.rdb.bgsave(path, snapshot) {
+            Ok(_) => {
+                self.dirty_before_bgsave = Some(self.dirty);
+                Ok(Response::Normal(Reply::SimpleString(
+                    "Background saving started".to_string(),
+                )))
+            }
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    /// Comando interno, ejecutado periódicamente, que lanza un guardado en segundo plano si se
+    /// cumple alguna de las reglas `save` configuradas. Si el último guardado falló, espera unos
+    /// segundos antes de reintentarlo.
+    fn check_save_points_method(&mut self) -> Response {
+        let ok = Response::Normal(Reply::SimpleString("OK".to_string()));
+        self.update_dirty();
+        if self.rdb.bgsave_in_progress() {
+            return ok;
+        }
+        let retry_allowed = self.rdb.last_bgsave_ok()
+            || self
+                .rdb
+                .last_bgsave_try()
+                .elapsed()
+                .is_ok_and(|elapsed| elapsed >= BGSAVE_RETRY_DELAY);
+        if !retry_allowed {
+            return ok;
+        }
+
+        let elapsed = self
+            .rdb
+            .last_save_time()
+            .elapsed()
+            .unwrap_or_else(|_| Duration::from_secs(0))
+            .as_secs();
+        let save_points = self.config.lock().unwrap().get_save();
+        let save_point = save_points
+            .into_iter()
+            .find(|save_point| save_point.is_satisfied(elapsed, self.dirty));
+        match save_point {
+            Some(save_point) => {
+                let _ = self.log_sender.send(Log::new(
+                    LogLevel::Info,
+                    line!(),
+                    column!(),
+                    file!().to_string(),
+                    format!(
+                        "{} changes in {} seconds. Saving...",
+                        save_point.changes, save_point.seconds
+                    ),
+                ));
+                self.bgsave_method().unwrap_or(ok)
+            }
+            None => ok,
+        }
+    }
+
+    /// Comando interno, ejecutado periódicamente, que borra las claves expiradas que nadie volvió
+    /// a leer. Igual que en Redis, se revisan muestras de claves con expiración mientras la
+    /// proporción de claves expiradas en la muestra supere el porcentaje aceptable, sin exceder el
+    /// tiempo máximo del ciclo. El tiempo máximo se comparte entre todas las bases de datos.
+    fn active_expire_cycle_method(&mut self) -> Response {
+        let events = self.config.lock().unwrap().get_notify_keyspace_events();
+        let selected_db = self.selected_db;
+        let start = SystemTime::now();
+        let (mut sampled, mut expired) = (0, 0);
+        for index in 0..self.dbs.len() {
+            self.select_db(index);
+            loop {
+                let (loop_sampled, loop_expired) =
+                    self.db.expire_sample(ACTIVE_EXPIRE_KEYS_PER_LOOP);
+                sampled += loop_sampled;
+                expired += loop_expired;
+
+                let stale = loop_expired * 100 > loop_sampled * ACTIVE_EXPIRE_ACCEPTABLE_STALE;
+                if loop_sampled == 0 || !stale || self.active_expire_timeout(start) {
+                    break;
+                }
+            }
+            self.notify_expired(events);
+            if self.active_expire_timeout(start) {
+                break;
+            }
+        }
+        self.select_db(selected_db);
+
+        if sampled > 0 {
+            let current_perc = expired as f64 * 100.0 / sampled as f64;
+            self.expired_stale_perc = current_perc * 0.05 + self.expired_stale_perc * 0.95;
+        }
+        Response::Normal(Reply::SimpleString("OK".to_string()))
+    }
+
+    /// Indica si el ciclo de expiración que empezó en `start` excedió su tiempo máximo.
+    fn active_expire_timeout(&self, start: SystemTime) -> bool {
+        start
+            .elapsed()
+            .is_ok_and(|elapsed| elapsed >= ACTIVE_EXPIRE_TIME_LIMIT)
+    }
+
+    /// Descuenta de los cambios pendientes los que persistió el último guardado en segundo plano,
+    /// una vez que terminó exitosamente.
+    fn update_dirty(&mut self) {
+        if self.rdb.bgsave_in_progress() {
+            return;
+        }
+        if let Some(saved) = self.dirty_before_bgsave.take() {
+            if self.rdb.last_bgsave_ok() {
+                self.dirty = self.dirty.saturating_sub(saved);
+            }
+        }
+    }
+
+    /// El comando LASTSAVE devuelve el momento, en segundos desde el epoch, del último guardado
+    /// exitoso de la base de datos.
+    fn lastsave_method(&mut self) -> Response {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command LASTSAVE Received".to_string(),
+        ));
+
+        let last_save_time = self
+            .rdb
+            .last_save_time()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_else(|_| Duration::from_secs(0));
+        Response::Normal(Reply::Integer(last_save_time.as_secs() as i64))
+    }
+
+    /// El comando CLUSTER permite consultar y modificar el estado del cluster. Los cambios en los
+    /// slots asignados a este nodo se informan enseguida a los demás nodos.
+    fn cluster_method(&mut self, param: ClusterParam) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            format!("Command CLUSTER Received - {:?}", param),
+        ));
+
+        if !self.cluster.is_enabled() {
+            return Err("ERR This instance has cluster support disabled".to_string());
+        }
+
+        let ok = Reply::SimpleString("OK".to_string());
+        let response = match param {
+            ClusterParam::Info => Reply::BulkString(self.cluster.info().into_bytes()),
+            ClusterParam::Myid => Reply::BulkString(self.cluster.myid().as_bytes().to_vec()),
+            ClusterParam::Nodes => Reply::BulkString(self.cluster.nodes().into_bytes()),
+            ClusterParam::Slots => self.cluster.slots(),
+            ClusterParam::Keyslot(key) => Reply::Integer(key_hash_slot(&key) as i64),
+            ClusterParam::Countkeysinslot(slot) => Reply::Integer(
+                self.db
+                    .iter()
+                    .filter(|(key, _, _)| key_hash_slot(key) == slot)
+                    .count() as i64,
+            ),
+            ClusterParam::Getkeysinslot(slot, count) => Reply::bulk_strings(
+                self.db
+                    .iter()
+                    .filter(|(key, _, _)| key_hash_slot(key) == slot)
+                    .take(count)
+                    .map(|(key, _, _)| key.to_vec()),
+            ),
+            ClusterParam::Addslots(slots) => {
+                self.cluster.addslots(&slots)?;
+                self.cluster.announce(self.log_sender.clone());
+                ok
+            }
+            ClusterParam::Meet(host, port) => {
+                self.cluster.meet(host, port);
+                self.cluster.announce(self.log_sender.clone());
+                ok
+            }
+            ClusterParam::Setslot(slot, state) => {
+                self.cluster.setslot(slot, state)?;
+                self.cluster.announce(self.log_sender.clone());
+                ok
+            }
+            ClusterParam::Gossip(gossip) => {
+                self.cluster.gossip(gossip);
+                ok
+            }
+        };
+        self.save_cluster_config();
+        Ok(Response::Normal(response))
+    }
+
+    /// Guarda la configuración del cluster en su archivo si cambió.
+    fn save_cluster_config(&mut self) {
+        if let Err(e) = self.cluster.save_config() {
+            let _ = self.log_sender.send(Log::new(
+                LogLevel::Error,
+                line!(),
+                column!(),
+                file!().to_string(),
+                format!("Error saving cluster config: {}", e),
+            ));
+        }
+    }
+
+    /// Comando interno, ejecutado periódicamente, que envía el estado de este nodo a los demás
+    /// nodos del cluster.
+    fn cluster_cron_method(&mut self) -> Response {
+        if self.cluster.is_enabled() {
+            self.cluster.announce(self.log_sender.clone());
+        }
+        Response::Normal(Reply::SimpleString("OK".to_string()))
+    }
+
+    /// El comando REPLICAOF convierte al servidor en réplica del master indicado: descarta sus
+    /// datos, recibe una copia de los del master y luego aplica sus comandos de escritura. Con
+    /// `REPLICAOF NO ONE` el servidor deja de ser réplica y conserva los datos.
+    fn replicaof_method(&mut self, master: Option<(String, u16)>) -> Response {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command REPLICAOF Received".to_string(),
+        ));
+
+        match (master, self.db_sender.clone()) {
+            (Some((host, port)), Some(db_sender)) => {
+                let listening_port = self.config.lock().unwrap().get_port().parse().unwrap_or(0);
+                self.replication.start_link(
+                    host,
+                    port,
+                    listening_port,
+                    db_sender,
+                    self.log_sender.clone(),
+                );
+            }
+            (Some(_), None) => {
+                return Response::Error("ERR Replication is not available".to_string());
+            }
+            (None, _) => self.replication.stop_link(),
+        }
+        Response::Normal(Reply::SimpleString("OK".to_string()))
+    }
+
+    /// El comando REPLCONF lo envía una réplica para configurar su conexión. Se guarda el puerto en
+    /// el que atiende a sus clientes, para informarlo en ROLE.
+    fn replconf_method(&mut self, client_id: String, listening_port: Option<u16>) -> Response {
+        if let Some(port) = listening_port {
+            self.replication.set_listening_port(client_id, port);
+        }
+        Response::Normal(Reply::SimpleString("OK".to_string()))
+    }
+
+    /// El comando PSYNC lo envía una réplica para sincronizarse. Se responde con un flujo de
+    /// datos: la copia completa de la base de datos o los comandos que le faltan, seguidos de
+    /// los comandos de escritura que se ejecuten.
+    fn psync_method(&mut self, replid: String, offset: i64, client_id: String) -> Response {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Info,
+            line!(),
+            column!(),
+            file!().to_string(),
+            format!("Replica {} asks for synchronization", client_id),
+        ));
+
+        let dbs = all_databases(&self.db, &self.dbs, self.selected_db);
+        Response::Stream(self.replication.psync(replid, offset, client_id, &dbs))
+    }
+
+    /// Comando interno que reemplaza la base de datos por la copia recibida del master.
+    fn replica_full_sync_method(
+        &mut self,
+        rdb: Vec<u8>,
+        replid: String,
+        offset: u64,
+        link_id: u64,
+    ) -> Result<Response, String> {
+        if !self.replication.is_current_link(link_id) {
+            return Err("ERR Replication link is closed".to_string());
+        }
+        let dbs =
+            TtlHashMap::deserialize_databases(rdb, self.dbs.len()).map_err(|e| e.to_string())?;
+        self.set_databases(dbs);
+        self.master_db = 0;
+        self.master_transaction = None;
+        self.watched_keys.clear();
+        self.replication.full_sync(replid, offset);
+        if let Some(aof) = self.aof.as_mut() {
+            aof.rewrite(&all_databases(&self.db, &self.dbs, self.selected_db))
+                .map_err(|e| e.to_string())?;
+        }
+
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Info,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "MASTER <-> REPLICA sync: Finished with success".to_string(),
+        ));
+        Ok(Response::Normal(Reply::SimpleString("OK".to_string())))
+    }
+
+    /// Comando interno que aplica un comando recibido del master. Se ejecuta aunque la réplica sea
+    /// de sólo lectura, y se reenvía tal cual a las réplicas de este servidor.
+    ///
+    /// Los comandos se aplican sobre la última base de datos seleccionada por el master. Los de una
+    /// transacción se encolan hasta recibir su EXEC, y entonces se aplican y se registran en el AOF
+    /// todos juntos.
+    fn master_command_method(&mut self, args: Vec<Vec<u8>>, link_id: u64) -> Response {
+        if !self.replication.is_current_link(link_id) {
+            return Response::Error("ERR Replication link is closed".to_string());
+        }
+        self.replication.feed(args.clone());
+        if is_command(&args, "multi") {
+            self.master_transaction = Some(Vec::new());
+            return Response::Normal(Reply::SimpleString("OK".to_string()));
+        }
+        if let (Some(queued), false) = (self.master_transaction.as_mut(), is_command(&args, "exec"))
+        {
+            queued.push(args);
+            return Response::Normal(Reply::SimpleString("QUEUED".to_string()));
+        }
+
+        let mut writes = Vec::new();
+        let (response, transaction) = match self.master_transaction.take() {
+            Some(queued) => {
+                let responses = queued
+                    .into_iter()
+                    .map(|args| self.apply_master_command(args, &mut writes))
+                    .collect();
+                (Response::Transaction(responses), !writes.is_empty())
+            }
+            None => (self.apply_master_command(args, &mut writes), false),
+        };
+        if transaction {
+            self.append_aof(writes[0].0, vec![b"multi".to_vec()]);
+        }
+        for (db, write_args) in writes {
+            self.append_aof(db, write_args);
+        }
+        if transaction {
+            self.append_aof(self.master_db, vec![b"exec".to_vec()]);
+        }
+        response
+    }
+
+    /// Aplica un comando del master sobre la base de datos que tiene seleccionada, agregando a
+    /// `writes` el comando de escritura a registrar en el AOF, si lo es.
+    fn apply_master_command(
+        &mut self,
+        args: Vec<Vec<u8>>,
+        writes: &mut Vec<(usize, Vec<Vec<u8>>)>,
+    ) -> Response {
+        self.select_db(self.master_db);
+        let result = generate(args, "MASTER".to_string()).and_then(|command| {
+            let write_args = command.write_args();
+            let response = self.execute_notifying(command);
+            if let (Ok(_), Some(write_args)) = (&response, write_args) {
+                self.dirty += 1;
+                writes.push((self.selected_db, write_args));
+            }
+            response
+        });
+        self.master_db = self.selected_db;
+
+        match result {
+            Ok(response) => response,
+            Err(e) => {
+                let _ = self.log_sender.send(Log::new(
+                    LogLevel::Error,
+                    line!(),
+                    column!(),
+                    file!().to_string(),
+                    "Error applying command from master: ".to_string() + &e,
+                ));
+                Response::Error(e)
+            }
+        }
+    }
+
+    /// El comando BGREWRITEAOF compacta el archivo AOF, reemplazándolo por los comandos mínimos
+    /// que reconstruyen el contenido actual de la base de datos. Si el AOF no está habilitado, sólo
+    /// crea el archivo.
+    ///
+    /// Igual que BGSAVE, la reescritura se hace en un hilo aparte a partir de una copia de las
+    /// bases de datos que comparte los valores con ellas. Los comandos de escritura ejecutados mientras tanto se agregan también al
+    /// archivo nuevo, por lo que ninguno se pierde al reemplazarlo.
+    fn bgrewriteaof_method(&mut self) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command BGREWRITEAOF Received".to_string(),
+        ));
+
+        if self.aof_rewrite_in_progress() {
+            return Err(AOF_REWRITE_IN_PROGRESS_MSG.to_string());
+        }
+
+        let snapshot = self.databases().iter().map(|db| db.snapshot()).collect();
+        let result = match self.aof.as_mut() {
+            Some(aof) => aof.bgrewrite(snapshot),
+            None => {
+                let path = self.config.lock().unwrap().get_appendfilename();
+                Ok(Aof::bgwrite_snapshot(path, snapshot))
+            }
+        };
+
+        match result {
+            Ok(rewrite) => {
+                self.aof_rewrite = Some(rewrite);
+                Ok(Response::Normal(Reply::SimpleString(
+                    "Background append only file rewriting started".to_string(),
+                )))
+            }
+            Err(e) => {
+                let _ = self.log_sender.send(Log::new(
+                    LogLevel::Error,
+                    line!(),
+                    column!(),
+                    file!().to_string(),
+                    e.to_string(),
+                ));
+                Err("ERR ".to_string() + &e.to_string())
+            }
+        }
+    }
+
+    /// Indica si hay una reescritura del AOF en segundo plano en curso.
+    fn aof_rewrite_in_progress(&self) -> bool {
+        self.aof_rewrite
+            .as_ref()
+            .is_some_and(|rewrite| !rewrite.is_finished())
+    }
+
+    /// Habilita o deshabilita el AOF en tiempo de ejecución. Al habilitarlo, se crea el archivo a
+    /// partir del contenido actual de la base de datos.
+    fn config_set_appendonly(&mut self, value: String) -> Result<Response, String> {
+        match value.to_lowercase().as_str() {
+            "yes" if self.aof.is_none() => {
+                let (path, fsync) = {
+                    let config = self.config.lock().unwrap();
+                    (config.get_appendfilename(), config.get_appendfsync())
+                };
+                let mut aof = Aof::open(path, fsync).map_err(|e| e.to_string())?;
+                aof.rewrite(&all_databases(&self.db, &self.dbs, self.selected_db))
+                    .map_err(|e| e.to_string())?;
+                self.aof = Some(aof);
+            }
+            "yes" => (),
+            "no" => self.aof = None,
+            _ => {
+                return Err(format!(
+                    "ERR Invalid argument '{}' for CONFIG SET 'appendonly'",
+                    value
+                ))
+            }
+        }
+        self.config.lock().unwrap().set_appendonly(value);
+        Ok(Response::Normal(Reply::SimpleString("OK".to_string())))
+    }
+
+    /// Cambia la política de sincronización del AOF en tiempo de ejecución.
+    fn config_set_appendfsync(&mut self, value: String) -> Result<Response, String> {
+        let fsync = match AppendFsync::parse(&value) {
+            Some(fsync) => fsync,
+            None => {
+                return Err(format!(
+                    "ERR Invalid argument '{}' for CONFIG SET 'appendfsync'",
+                    value
+                ))
+            }
+        };
+        if let Some(aof) = self.aof.as_mut() {
+            aof.set_fsync(fsync).map_err(|e| e.to_string())?;
+        }
+        self.config.lock().unwrap().set_appendfsync(value);
+        Ok(Response::Normal(Reply::SimpleString("OK".to_string())))
+    }
+
+    /// Reemplaza las reglas de guardado automático, por ejemplo `900 1 300 10`. Con un valor vacío
+    /// se deshabilitan los guardados automáticos.
+    fn config_set_save(&mut self, value: String) -> Result<Response, String> {
+        if !self.config.lock().unwrap().set_save(value.clone()) {
+            return Err(format!(
+                "ERR Invalid argument '{}' for CONFIG SET 'save'",
+                value
+            ));
+        }
+        Ok(Response::Normal(Reply::SimpleString("OK".to_string())))
+    }
+
+    /// Reemplaza las clases de eventos de keyspace que se publican, por ejemplo `KEA`. Con un valor
+    /// vacío se deshabilitan las notificaciones.
+    fn config_set_notify_keyspace_events(&mut self, value: String) -> Result<Response, String> {
+        if !self
+            .config
+            .lock()
+            .unwrap()
+            .set_notify_keyspace_events(value.clone())
+        {
+            return Err(format!(
+                "ERR Invalid argument '{}' for CONFIG SET 'notify-keyspace-events'",
+                value
+            ));
+        }
+        Ok(Response::Normal(Reply::SimpleString("OK".to_string())))
+    }
+
+    /// Reemplaza la memoria máxima, por ejemplo `100mb`. Con 0 se deshabilita el límite.
+    fn config_set_maxmemory(&mut self, value: String) -> Result<Response, String> {
+        if !self.config.lock().unwrap().set_maxmemory(value.clone()) {
+            return Err(format!(
+                "ERR Invalid argument '{}' for CONFIG SET 'maxmemory'",
+                value
+            ));
+        }
+        Ok(Response::Normal(Reply::SimpleString("OK".to_string())))
+    }
+
+    /// Reemplaza la política de desalojo, por ejemplo `allkeys-lru`.
+    fn config_set_maxmemory_policy(&mut self, value: String) -> Result<Response, String> {
+        if !self
+            .config
+            .lock()
+            .unwrap()
+            .set_maxmemory_policy(value.clone())
+        {
+            return Err(format!(
+                "ERR Invalid argument '{}' for CONFIG SET 'maxmemory-policy'",
+                value
+            ));
+        }
+        Ok(Response::Normal(Reply::SimpleString("OK".to_string())))
+    }
+
+    /// El comando CONFIG GET se utiliza para leer los parámetros de configuración de un servidor en
+    /// ejecución.
+    fn config_get_method(&mut self) -> Vec<String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command CONFIG GET Received".to_string(),
         ));
 
         let config = self.config.lock().unwrap();
@@ -2108,2433 +5804,6183 @@ impl Redis {
             config.get_port(),
             config.get_verbose(),
             config.get_timeout().to_string(),
+            SavePoint::list_to_string(&config.get_save()),
         ]
     }
 
-    /// El comando CONFIG SET se utiliza para reconfigurar un servidor en tiempo de ejecución sin
-    /// necesidad de reiniciarlo.
-    fn config_set_method(&mut self, parameter: String, value: String) -> Result<Response, String> {
-        let _ = self.log_sender.send(Log::new(
-            LogLevel::Debug,
-            line!(),
-            column!(),
-            file!().to_string(),
-            "Command CONFIG SET Received - parameter: ".to_string() + &parameter,
-        ));
-        let mut config = self.config.lock().unwrap();
+    /// El comando CONFIG SET se utiliza para reconfigurar un servidor en tiempo de ejecución sin
+    /// necesidad de reiniciarlo.
+    fn config_set_method(&mut self, parameter: String, value: String) -> Result<Response, String> {
+        let _ = self.log_sender.send(Log::new(
+            LogLevel::Debug,
+            line!(),
+            column!(),
+            file!().to_string(),
+            "Command CONFIG SET Received - parameter: ".to_string() + &parameter,
+        ));
+        match parameter.as_str() {
+            "appendonly" => return self.config_set_appendonly(value),
+            "appendfsync" => return self.config_set_appendfsync(value),
+            "save" => return self.config_set_save(value),
+            "notify-keyspace-events" => return self.config_set_notify_keyspace_events(value),
+            "maxmemory" => return self.config_set_maxmemory(value),
+            "maxmemory-policy" => return self.config_set_maxmemory_policy(value),
+            _ => (),
+        }
+        let mut config = self.config.lock().unwrap();
+
+        match parameter.as_str() {
+            "verbose" => config.set_verbose(value),
+            "dbfilename" => config.set_dbfilename(value),
+            "logfile" => config.set_logfile(value),
+            _ => {
+                let _ = self.log_sender.send(Log::new(
+                    LogLevel::Error,
+                    line!(),
+                    column!(),
+                    file!().to_string(),
+                    "Parameter does not exist".to_string(),
+                ));
+                return Err("Parameter does not exist".to_string());
+            }
+        }
+        Ok(Response::Normal(Reply::SimpleString("OK".to_string())))
+    }
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::entities::append_fsync::AppendFsync;
+    use crate::entities::cluster_param::ClusterParam;
+    use crate::entities::command::Command;
+    use crate::entities::info_param::InfoParam;
+    use crate::entities::maxmemory_policy::MaxmemoryPolicy;
+    use crate::entities::pubsub_param::PubSubParam;
+    use crate::entities::set_param::{SetCondition, SetExpiration, SetParam};
+    use crate::entities::sort_param::SortParam;
+    use crate::entities::sorted_set::{LexBound, ScoreBound};
+    use crate::entities::zrange_param::{AggregateParam, ZRangeParam};
+    use crate::service::aof::Aof;
+    use crate::service::cluster::{key_hash_slot, Cluster};
+    use crate::service::redis::TtlHashMap;
+    use crate::service::redis::{to_text, Re, Redis, Reply, Response, WRONGTYPE_MSG};
+    use std::collections::{HashMap, HashSet};
+    use std::fs;
+    use std::io::Write;
+    use std::process;
+    use std::sync::mpsc::Receiver;
+    use std::thread::{self, sleep};
+    use std::time::{Duration, SystemTime};
+
+    #[allow(dead_code)]
+    fn eq_response(content: Reply, response: Response) -> bool {
+        if let Response::Normal(reply) = response {
+            return content == reply;
+        };
+        false
+    }
+
+    #[allow(dead_code)]
+    fn bulk_strings(response: Result<Response, String>) -> Vec<Vec<u8>> {
+        if let Ok(Response::Normal(Reply::Array(elements))) = response {
+            let elements = elements.into_iter().map(|element| match element {
+                Reply::BulkString(bytes) => bytes,
+                _ => panic!("array should contain only bulk strings"),
+            });
+            return elements.collect();
+        }
+        panic!("response should be an array")
+    }
+
+    #[allow(dead_code)]
+    fn eq_members(members: HashSet<Vec<u8>>, response: Response) -> bool {
+        let elements = bulk_strings(Ok(response));
+        elements.len() == members.len() && elements.into_iter().all(|e| members.contains(&e))
+    }
+
+    #[allow(dead_code)]
+    fn eq_fields(fields: HashMap<Vec<u8>, Vec<u8>>, response: Response) -> bool {
+        let elements = bulk_strings(Ok(response));
+        let pairs = elements
+            .chunks(2)
+            .map(|pair| (pair[0].clone(), pair[1].clone()));
+        elements.len() == 2 * fields.len() && pairs.collect::<HashMap<_, _>>() == fields
+    }
+
+    #[test]
+    fn test_strlen_element_fail_if_is_not_string() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".into();
+        let value = vec!["value".into(), "value2".into()];
+        let _lpush = redis.execute(Command::Lpush { key, value });
+
+        let key = "key".into();
+        let strlen = redis.execute(Command::Strlen { key });
+
+        assert!(strlen.is_err());
+    }
+
+    #[ignore]
+    #[test]
+    fn test_strlen_element_not_found() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".into();
+        let strlen = redis.execute(Command::Strlen { key });
+
+        assert!(strlen.is_ok());
+        assert!(eq_response(Reply::Integer(0), strlen.unwrap()));
+    }
+
+    #[test]
+    fn test_strlen_element_saved_before() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let value = "value".into();
+        let key = "hola".into();
+
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            param: SetParam::default(),
+        });
+
+        let key = "hola".into();
+        let strlen = redis.execute(Command::Strlen { key });
+
+        assert!(strlen.is_ok());
+        assert!(eq_response(Reply::Integer(5), strlen.unwrap()));
+    }
+
+    #[allow(unused_imports)]
+    #[test]
+    fn test_set_element_and_get_the_same() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let value = "value".into();
+        let key = "hola".into();
+
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            param: SetParam::default(),
+        });
+
+        let key = "hola".into();
+        let get = redis.execute(Command::Get { key });
+
+        assert!(get.is_ok());
+        assert!(eq_response(Reply::BulkString("value".into()), get.unwrap()));
+    }
+
+    #[test]
+    fn test_set_element_twice_and_get_the_last_set() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "hola".into();
+        let value = "chau".into();
+
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            param: SetParam::default(),
+        });
+
+        let key = "hola".into();
+        let value = "test".into();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            param: SetParam::default(),
+        });
+
+        let key = "hola".into();
+        let get = redis.execute(Command::Get { key });
+
+        assert!(get.is_ok());
+        assert!(eq_response(Reply::BulkString("test".into()), get.unwrap()));
+    }
+
+    #[test]
+    fn test_get_on_empty_key_returns_nil() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "hola".into();
+        let get = redis.execute(Command::Get { key });
+
+        assert!(get.is_ok());
+        assert!(eq_response(Reply::NullBulk, get.unwrap()));
+    }
+
+    #[test]
+    fn test_get_element_fail_if_is_not_string() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".into();
+        let value = vec!["value".into(), "value2".into()];
+        let _lpush = redis.execute(Command::Lpush { key, value });
+
+        let key = "key".into();
+        let get = redis.execute(Command::Get { key });
+
+        assert!(get.is_err());
+    }
+
+    #[test]
+    fn test_getset_fails_if_is_not_string() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".into();
+        let value = vec!["value".into(), "value2".into()];
+        let _lpush = redis.execute(Command::Lpush { key, value });
+
+        let key = "key".into();
+        let value = "value".into();
+        let getset = redis.execute(Command::Getset { key, value });
+
+        assert!(getset.is_err());
+    }
+
+    #[test]
+    fn test_getset_on_empty_key_returns_nil() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".into();
+        let value = "value".into();
+        let getset = redis.execute(Command::Getset { key, value });
+
+        assert!(getset.is_ok());
+        assert!(eq_response(Reply::NullBulk, getset.unwrap()));
+    }
+
+    #[test]
+    fn test_getset_ok() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".into();
+        let value = "1".into();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            param: SetParam::default(),
+        });
+
+        let key = "key".into();
+        let value = "value".into();
+        let getset = redis.execute(Command::Getset { key, value });
+        assert!(getset.is_ok());
+        assert!(eq_response(Reply::BulkString("1".into()), getset.unwrap()));
+
+        let key = "key".into();
+        let get = redis.execute(Command::Get { key });
+        assert!(get.is_ok());
+        assert!(eq_response(Reply::BulkString("value".into()), get.unwrap()));
+    }
+
+    #[test]
+    fn test_ping_returns_pong() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let ping = redis.execute(Command::Ping);
+
+        assert!(ping.is_ok());
+        assert!(eq_response(
+            Reply::SimpleString("PONG".into()),
+            ping.unwrap(),
+        ));
+    }
+
+    #[test]
+    fn test_incrby_with_2_as_value() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".into();
+        let value = "1".into();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            param: SetParam::default(),
+        });
+
+        let key = "key".into();
+        let increment: i64 = 1;
+        let _incrby = redis.execute(Command::Incrby { key, increment });
+
+        let key = "key".into();
+        let get = redis.execute(Command::Get { key });
+
+        let key = "key".into();
+        let increment: i64 = 2;
+        let _incrby = redis.execute(Command::Incrby { key, increment });
+
+        let key = "key".into();
+        let second_get = redis.execute(Command::Get { key });
+
+        assert!(get.is_ok());
+        assert!(eq_response(Reply::BulkString("2".into()), get.unwrap()));
+
+        assert!(second_get.is_ok());
+        assert!(eq_response(
+            Reply::BulkString("4".into()),
+            second_get.unwrap(),
+        ));
+    }
+
+    #[test]
+    fn test_incrby_value_err_initial_value_string() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".into();
+        let value = "hola".into();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            param: SetParam::default(),
+        });
+
+        let key = "key".into();
+        let increment: i64 = 1;
+        let incrby = redis.execute(Command::Incrby { key, increment });
+
+        assert!(incrby.is_err());
+    }
+
+    #[test]
+    fn test_incrby_not_saved_value() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".into();
+        let increment: i64 = 1;
+        let _incrby = redis.execute(Command::Incrby { key, increment });
+
+        let key = "key".into();
+        let get = redis.execute(Command::Get { key });
+
+        assert!(get.is_ok());
+        assert!(eq_response(Reply::BulkString("1".into()), get.unwrap()));
+    }
+
+    #[test]
+    fn test_incrby_uses_64_bits_and_detects_overflow() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let incrby = redis.execute(Command::Incrby {
+            key: "key".into(),
+            increment: 1 << 40,
+        });
+        let decrby = redis.execute(Command::Decrby {
+            key: "key".into(),
+            decrement: -5,
+        });
+        let _set = redis.execute(Command::Set {
+            key: "max".into(),
+            value: i64::MAX.to_string().into_bytes(),
+            param: SetParam::default(),
+        });
+        let overflow = redis.execute(Command::Incrby {
+            key: "max".into(),
+            increment: 1,
+        });
+        let min_decrement = redis.execute(Command::Decrby {
+            key: "key".into(),
+            decrement: i64::MIN,
+        });
+
+        assert!(eq_response(Reply::Integer(1 << 40), incrby.unwrap()));
+        assert!(eq_response(Reply::Integer((1 << 40) + 5), decrby.unwrap()));
+        assert_eq!(
+            overflow.unwrap_err(),
+            "ERR increment or decrement would overflow"
+        );
+        assert_eq!(min_decrement.unwrap_err(), "ERR decrement would overflow");
+        assert!(eq_response(
+            Reply::BulkString(i64::MAX.to_string().into_bytes()),
+            redis.execute(Command::Get { key: "max".into() }).unwrap()
+        ));
+    }
+
+    #[test]
+    fn test_incrby_wrong_type_and_ttl() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let _lpush = redis.execute(Command::Lpush {
+            key: "list".into(),
+            value: vec!["element".into()],
+        });
+        let wrong_type = redis.execute(Command::Incrby {
+            key: "list".into(),
+            increment: 1,
+        });
+        let _set = redis.execute(Command::Set {
+            key: "key".into(),
+            value: "1".into(),
+            param: SetParam {
+                expiration: Some(SetExpiration::Relative(Duration::from_secs(100))),
+                ..SetParam::default()
+            },
+        });
+        let _incrby = redis.execute(Command::Incrby {
+            key: "key".into(),
+            increment: 1,
+        });
+
+        assert_eq!(wrong_type.unwrap_err(), WRONGTYPE_MSG);
+        assert!(eq_response(
+            Reply::bulk_strings(vec!["element".into()]),
+            redis
+                .execute(Command::Lrange {
+                    key: "list".into(),
+                    begin: 0,
+                    end: -1,
+                })
+                .unwrap()
+        ));
+        assert!(eq_response(
+            Reply::Integer(99),
+            redis.execute(Command::Ttl { key: "key".into() }).unwrap()
+        ));
+    }
+
+    #[test]
+    fn test_incrbyfloat() {
+        let mut redis: Redis = Redis::new_for_test();
+        let mut incrbyfloat = |key: &str, increment| {
+            redis.execute(Command::Incrbyfloat {
+                key: key.into(),
+                increment,
+            })
+        };
+
+        let new_key = incrbyfloat("key", 10.5);
+        let decimal = incrbyfloat("key", 0.1);
+        let integer = incrbyfloat("key", -0.6);
+        let exponent = incrbyfloat("exp", 5.0e3);
+        let exponent = exponent.and(incrbyfloat("exp", 2.0e2));
+        let infinite = incrbyfloat("key", f64::INFINITY);
+        let _first = incrbyfloat("sum", 0.1);
+        let rounded = incrbyfloat("sum", 0.2);
+
+        assert!(eq_response(
+            Reply::BulkString("10.5".into()),
+            new_key.unwrap()
+        ));
+        assert!(eq_response(
+            Reply::BulkString("10.6".into()),
+            decimal.unwrap()
+        ));
+        assert!(eq_response(
+            Reply::BulkString("10".into()),
+            integer.unwrap()
+        ));
+        assert!(eq_response(
+            Reply::BulkString("5200".into()),
+            exponent.unwrap()
+        ));
+        assert!(eq_response(
+            Reply::BulkString("0.3".into()),
+            rounded.unwrap()
+        ));
+        assert_eq!(
+            infinite.unwrap_err(),
+            "ERR increment would produce NaN or Infinity"
+        );
+
+        let _set = redis.execute(Command::Set {
+            key: "text".into(),
+            value: "value".into(),
+            param: SetParam::default(),
+        });
+        let not_float = redis.execute(Command::Incrbyfloat {
+            key: "text".into(),
+            increment: 1.0,
+        });
+        assert_eq!(not_float.unwrap_err(), "ERR value is not a valid float");
+        assert!(eq_response(
+            Reply::BulkString("10".into()),
+            redis.execute(Command::Get { key: "key".into() }).unwrap()
+        ));
+    }
+
+    #[test]
+    fn test_incrbyfloat_is_logged_as_set() {
+        let path = "test_incrbyfloat_is_logged_as_set.aof".to_string();
+        let _ = fs::remove_file(&path);
+
+        let mut redis: Redis = Redis::new_for_test();
+        let _load = redis.execute(Command::LoadAof { path: path.clone() });
+        let _incrbyfloat = redis.execute(Command::Incrbyfloat {
+            key: "key".into(),
+            increment: 1.5,
+        });
+        let _hincrbyfloat = redis.execute(Command::Hincrbyfloat {
+            key: "hash".into(),
+            field: "field".into(),
+            increment: 2.5,
+        });
+
+        let mut redis_new: Redis = Redis::new_for_test();
+        let _load = redis_new.execute(Command::LoadAof { path: path.clone() });
+        let content = fs::read(&path).unwrap();
+        let _ = fs::remove_file(&path);
+        let get = redis_new.execute(Command::Get { key: "key".into() });
+        let hget = redis_new.execute(Command::Hget {
+            key: "hash".into(),
+            field: "field".into(),
+        });
+
+        assert!(!String::from_utf8_lossy(&content).contains("incrbyfloat"));
+        assert!(eq_response(Reply::BulkString("1.5".into()), get.unwrap()));
+        assert!(eq_response(Reply::BulkString("2.5".into()), hget.unwrap()));
+    }
+
+    #[test]
+    fn test_decrby_on_new_key() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".into();
+        let decrement: i64 = 3;
+        let _decrby = redis.execute(Command::Decrby { key, decrement });
+
+        let key = "key".into();
+        let get = redis.execute(Command::Get { key });
+
+        assert!(get.is_ok());
+        assert!(eq_response(Reply::BulkString("-3".into()), get.unwrap()));
+    }
+
+    #[test]
+    fn test_decrby_on_existing_key() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".into();
+        let value = "5".into();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            param: SetParam::default(),
+        });
+
+        let key = "key".into();
+        let decrement: i64 = 3;
+        let _decrby = redis.execute(Command::Decrby { key, decrement });
+
+        let key = "key".into();
+        let get = redis.execute(Command::Get { key });
+
+        assert!(get.is_ok());
+        assert!(eq_response(Reply::BulkString("2".into()), get.unwrap()));
+    }
+
+    #[test]
+    fn test_mset_sets_2_values() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key_values = vec![
+            ("key1".into(), "value1".into()),
+            ("key2".into(), "value2".into()),
+        ];
+        let _mset = redis.execute(Command::Mset { key_values });
+
+        let key = "key1".into();
+        let get = redis.execute(Command::Get { key });
+        assert!(get.is_ok());
+        assert!(eq_response(
+            Reply::BulkString("value1".into()),
+            get.unwrap()
+        ));
+
+        let key = "key2".into();
+        let get = redis.execute(Command::Get { key });
+        assert!(get.is_ok());
+        assert!(eq_response(
+            Reply::BulkString("value2".into()),
+            get.unwrap()
+        ));
+    }
+
+    #[test]
+    fn test_set_nx_and_xx() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let set_xx = redis.execute(Command::Set {
+            key: "key".into(),
+            value: "value".into(),
+            param: SetParam {
+                condition: Some(SetCondition::Xx),
+                ..SetParam::default()
+            },
+        });
+        assert!(eq_response(Reply::NullBulk, set_xx.unwrap()));
+        assert!(eq_response(
+            Reply::NullBulk,
+            redis.execute(Command::Get { key: "key".into() }).unwrap()
+        ));
+
+        let set_nx = redis.execute(Command::Set {
+            key: "key".into(),
+            value: "value".into(),
+            param: SetParam {
+                condition: Some(SetCondition::Nx),
+                ..SetParam::default()
+            },
+        });
+        assert!(eq_response(
+            Reply::SimpleString("OK".into()),
+            set_nx.unwrap()
+        ));
+
+        let set_nx = redis.execute(Command::Set {
+            key: "key".into(),
+            value: "other".into(),
+            param: SetParam {
+                condition: Some(SetCondition::Nx),
+                ..SetParam::default()
+            },
+        });
+        assert!(eq_response(Reply::NullBulk, set_nx.unwrap()));
+        assert!(eq_response(
+            Reply::BulkString("value".into()),
+            redis.execute(Command::Get { key: "key".into() }).unwrap()
+        ));
+    }
+
+    #[test]
+    fn test_set_get_returns_old_value() {
+        let mut redis: Redis = Redis::new_for_test();
+        let get_param = SetParam {
+            get: true,
+            ..SetParam::default()
+        };
+
+        let first = redis.execute(Command::Set {
+            key: "key".into(),
+            value: "value".into(),
+            param: get_param.clone(),
+        });
+        let second = redis.execute(Command::Set {
+            key: "key".into(),
+            value: "other".into(),
+            param: get_param.clone(),
+        });
+        let _lpush = redis.execute(Command::Lpush {
+            key: "list".into(),
+            value: vec!["element".into()],
+        });
+        let wrong_type = redis.execute(Command::Set {
+            key: "list".into(),
+            value: "value".into(),
+            param: get_param,
+        });
+
+        assert!(eq_response(Reply::NullBulk, first.unwrap()));
+        assert!(eq_response(
+            Reply::BulkString("value".into()),
+            second.unwrap()
+        ));
+        assert_eq!(wrong_type.unwrap_err(), WRONGTYPE_MSG);
+        assert!(eq_response(
+            Reply::BulkString("other".into()),
+            redis.execute(Command::Get { key: "key".into() }).unwrap()
+        ));
+    }
+
+    #[test]
+    fn test_set_with_expiration_and_keepttl() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let _set = redis.execute(Command::Set {
+            key: "key".into(),
+            value: "value".into(),
+            param: SetParam {
+                expiration: Some(SetExpiration::Relative(Duration::from_secs(100))),
+                ..SetParam::default()
+            },
+        });
+        assert!(eq_response(
+            Reply::Integer(99),
+            redis.execute(Command::Ttl { key: "key".into() }).unwrap()
+        ));
+
+        let _set = redis.execute(Command::Set {
+            key: "key".into(),
+            value: "other".into(),
+            param: SetParam {
+                keep_ttl: true,
+                ..SetParam::default()
+            },
+        });
+        assert!(eq_response(
+            Reply::Integer(99),
+            redis.execute(Command::Ttl { key: "key".into() }).unwrap()
+        ));
+
+        let _set = redis.execute(Command::Set {
+            key: "key".into(),
+            value: "value".into(),
+            param: SetParam::default(),
+        });
+        assert!(eq_response(
+            Reply::Integer(-1),
+            redis.execute(Command::Ttl { key: "key".into() }).unwrap()
+        ));
+    }
+
+    #[test]
+    fn test_setnx_and_msetnx() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let setnx = redis.execute(Command::Setnx {
+            key: "key1".into(),
+            value: "value1".into(),
+        });
+        let setnx_again = redis.execute(Command::Setnx {
+            key: "key1".into(),
+            value: "other".into(),
+        });
+        let msetnx_existing = redis.execute(Command::Msetnx {
+            key_values: vec![
+                ("key1".into(), "other".into()),
+                ("key2".into(), "value2".into()),
+            ],
+        });
+        let exists = redis.execute(Command::Exists {
+            keys: vec!["key2".into()],
+        });
+        let msetnx = redis.execute(Command::Msetnx {
+            key_values: vec![
+                ("key2".into(), "value2".into()),
+                ("key3".into(), "value3".into()),
+            ],
+        });
+
+        assert!(eq_response(Reply::Integer(1), setnx.unwrap()));
+        assert!(eq_response(Reply::Integer(0), setnx_again.unwrap()));
+        assert!(eq_response(Reply::Integer(0), msetnx_existing.unwrap()));
+        assert!(eq_response(Reply::Integer(0), exists.unwrap()));
+        assert!(eq_response(Reply::Integer(1), msetnx.unwrap()));
+        assert!(eq_response(
+            Reply::Array(vec![
+                Reply::BulkString("value1".into()),
+                Reply::BulkString("value2".into()),
+                Reply::BulkString("value3".into())
+            ]),
+            redis
+                .execute(Command::Mget {
+                    keys: vec!["key1".into(), "key2".into(), "key3".into()]
+                })
+                .unwrap()
+        ));
+    }
+
+    #[test]
+    fn test_mget_gets_2_values() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key_values = vec![
+            ("key1".into(), "value1".into()),
+            ("key2".into(), "value2".into()),
+        ];
+        let _mset = redis.execute(Command::Mset { key_values });
+
+        let keys = vec!["key1".into(), "key2".into()];
+        let mget = redis.execute(Command::Mget { keys });
+
+        assert!(mget.is_ok());
+        assert!(eq_response(
+            Reply::Array(vec![
+                Reply::BulkString("value1".into()),
+                Reply::BulkString("value2".into())
+            ]),
+            mget.unwrap(),
+        ));
+    }
+
+    #[test]
+    fn test_mget_nil_for_missing_value() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".into();
+        let value = "value".into();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            param: SetParam::default(),
+        });
+
+        let keys = vec!["key".into(), "key_empty".into()];
+        let mget = redis.execute(Command::Mget { keys });
+
+        assert!(mget.is_ok());
+        assert!(eq_response(
+            Reply::Array(vec![Reply::BulkString("value".into()), Reply::NullBulk]),
+            mget.unwrap(),
+        ));
+    }
+
+    #[test]
+    fn test_mget_nil_for_non_string_value() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".into();
+        let value = "value".into();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            param: SetParam::default(),
+        });
+
+        let key = "key_list".into();
+        let value = vec!["value1".into(), "value2".into()];
+        let _lpush = redis.execute(Command::Lpush { key, value });
+
+        let keys = vec!["key".into(), "key_list".into()];
+        let mget = redis.execute(Command::Mget { keys });
+
+        assert!(mget.is_ok());
+        assert!(eq_response(
+            Reply::Array(vec![Reply::BulkString("value".into()), Reply::NullBulk]),
+            mget.unwrap(),
+        ));
+    }
+
+    #[test]
+    fn test_set_element_and_getdel() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let value = "value".into();
+        let key = "key".into();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            param: SetParam::default(),
+        });
+
+        let key = "key".into();
+        let get = redis.execute(Command::Get { key });
+
+        let key = "key".into();
+        let getdel = redis.execute(Command::Getdel { key });
+
+        assert!(get.is_ok());
+        assert!(eq_response(Reply::BulkString("value".into()), get.unwrap()));
+
+        assert!(getdel.is_ok());
+        assert!(eq_response(
+            Reply::BulkString("value".into()),
+            getdel.unwrap(),
+        ));
+
+        let key = "key".into();
+        let get = redis.execute(Command::Get { key });
+        assert!(eq_response(Reply::NullBulk, get.unwrap()));
+    }
+
+    #[test]
+    fn test_getdel_without_previews_saving_err() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".into();
+        let getdel = redis.execute(Command::Getdel { key });
+
+        assert!(getdel.is_err());
+    }
+
+    #[test]
+    fn test_dbsize() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let dbsize = redis.execute(Command::Dbsize);
+        assert!(eq_response(Reply::Integer(0), dbsize.unwrap()));
+
+        let value = "value".into();
+        let key = "key".into();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            param: SetParam::default(),
+        });
+
+        let dbsize = redis.execute(Command::Dbsize);
+        assert!(eq_response(Reply::Integer(1), dbsize.unwrap()));
+
+        let key = "key".into();
+        let _getdel = redis.execute(Command::Getdel { key });
+
+        let dbsize = redis.execute(Command::Dbsize);
+        assert!(eq_response(Reply::Integer(0), dbsize.unwrap()));
+    }
+
+    #[test]
+    fn test_set_element_and_del() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let value = "value".into();
+        let key = "key".into();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            param: SetParam::default(),
+        });
+
+        let keys = vec!["key".into()];
+        let del = redis.execute(Command::Del { keys });
+        assert!(eq_response(Reply::Integer(1), del.unwrap()));
+
+        let key = "key".into();
+        let get = redis.execute(Command::Get { key });
+        assert!(eq_response(Reply::NullBulk, get.unwrap()));
+    }
+
+    #[test]
+    fn test_set_two_elements_and_del_both() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let value = "value".into();
+        let key = "key1".into();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            param: SetParam::default(),
+        });
+
+        let value = "value".into();
+        let key = "key2".into();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            param: SetParam::default(),
+        });
+
+        let keys = vec!["key1".into(), "key2".into()];
+        let del = redis.execute(Command::Del { keys });
+
+        assert!(eq_response(Reply::Integer(2), del.unwrap()));
+    }
+
+    #[test]
+    fn test_append_adds_word() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".into();
+        let value = "value".into();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            param: SetParam::default(),
+        });
+
+        let key = "key".into();
+        let value = " appended".into();
+        let _append = redis.execute(Command::Append { key, value });
+
+        let key = "key".into();
+        let get = redis.execute(Command::Get { key });
+        assert!(eq_response(
+            Reply::BulkString("value appended".into()),
+            get.unwrap(),
+        ));
+    }
+
+    #[test]
+    fn test_append_on_non_existent_key() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".into();
+        let value = " appended".into();
+        let _append = redis.execute(Command::Append { key, value });
+
+        let key = "key".into();
+        let get = redis.execute(Command::Get { key });
+
+        assert!(eq_response(
+            Reply::BulkString(" appended".into()),
+            get.unwrap(),
+        ));
+    }
+
+    #[test]
+    fn test_set_two_elements_and_check_exists_equal_2() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key1".into();
+        let value = "value".into();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            param: SetParam::default(),
+        });
+
+        let key = "key2".into();
+        let value = "value".into();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            param: SetParam::default(),
+        });
+
+        let keys = vec!["key1".into(), "key2".into()];
+        let exists = redis.execute(Command::Exists { keys });
+        assert!(eq_response(Reply::Integer(2), exists.unwrap()));
+
+        let keys = vec!["key1".into(), "key2".into(), "key3".into()];
+        let exists = redis.execute(Command::Exists { keys });
+        assert!(eq_response(Reply::Integer(2), exists.unwrap()));
+    }
+
+    #[test]
+    fn test_copy_on_existing_key_returns_0() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key1".into();
+        let value = "value1".into();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            param: SetParam::default(),
+        });
+
+        let key = "key2".into();
+        let value = "value2".into();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            param: SetParam::default(),
+        });
+
+        let key_origin: Vec<u8> = "key1".into();
+        let key_destination: Vec<u8> = "key2".into();
+        let copy = redis.execute(Command::Copy {
+            key_destination,
+            key_origin,
+        });
+
+        assert!(eq_response(Reply::Integer(0), copy.unwrap()));
+    }
+
+    #[test]
+    fn test_copy_ok() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key1".into();
+        let value = "value1".into();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            param: SetParam::default(),
+        });
+
+        let key_origin: Vec<u8> = "key1".into();
+        let key_destination: Vec<u8> = "key2".into();
+        let _copy = redis.execute(Command::Copy {
+            key_destination,
+            key_origin,
+        });
+
+        let key = "key2".into();
+        let get = redis.execute(Command::Get { key });
+        assert!(eq_response(
+            Reply::BulkString("value1".into()),
+            get.unwrap()
+        ));
+    }
+
+    #[ignore]
+    #[test]
+    fn test_expire_deletes_key() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".into();
+        let value = "value".into();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            param: SetParam::default(),
+        });
+
+        let key = "key".into();
+        let ttl = Duration::from_secs(1);
+        let expire = redis.execute(Command::Expire { key, ttl });
+
+        thread::sleep(Duration::from_secs(1));
+
+        let key = "key".into();
+        let get = redis.execute(Command::Get { key });
+        assert!(eq_response(Reply::NullBulk, get.unwrap()));
+        assert!(eq_response(Reply::Integer(1), expire.unwrap()));
+    }
+
+    #[test]
+    fn test_expire_returns_0_on_unexisting_key() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".into();
+        let ttl = Duration::from_secs(1);
+        let expire = redis.execute(Command::Expire { key, ttl });
+
+        assert!(eq_response(Reply::Integer(0), expire.unwrap()));
+    }
+
+    #[test]
+    fn test_expireat_with_past_time_deletes_key() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".into();
+        let value = "value".into();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            param: SetParam::default(),
+        });
+
+        let key = "key".into();
+        let ttl = SystemTime::UNIX_EPOCH + Duration::from_secs(1623793215);
+        let expire = redis.execute(Command::Expireat { key, ttl });
+
+        let key = "key".into();
+        let get = redis.execute(Command::Get { key });
+        assert!(eq_response(Reply::NullBulk, get.unwrap()));
+        assert!(eq_response(Reply::Integer(1), expire.unwrap()));
+    }
+
+    #[test]
+    fn test_expireat_returns_0_on_unexisting_key() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".into();
+        let ttl = SystemTime::UNIX_EPOCH + Duration::from_secs(1623793215);
+        let expire = redis.execute(Command::Expireat { key, ttl });
+
+        assert!(eq_response(Reply::Integer(0), expire.unwrap()));
+    }
+
+    #[ignore]
+    #[test]
+    fn test_persist_deletes_expire_time() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".into();
+        let value = "value".into();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            param: SetParam::default(),
+        });
+
+        let key = "key".into();
+        let ttl = Duration::from_secs(1);
+        let _expire = redis.execute(Command::Expire { key, ttl });
+
+        let key = "key".into();
+        let persist = redis.execute(Command::Persist { key });
+
+        thread::sleep(Duration::from_secs(1));
+
+        let key = "key".into();
+        let get = redis.execute(Command::Get { key });
+
+        assert!(eq_response(Reply::Integer(1), persist.unwrap()));
+        assert!(eq_response(Reply::BulkString("value".into()), get.unwrap()));
+    }
+
+    #[test]
+    fn test_persist_returns_0_on_persistent_value() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".into();
+        let value = "value".into();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            param: SetParam::default(),
+        });
+
+        let key = "key".into();
+        let persist = redis.execute(Command::Persist { key });
+
+        let key: Vec<u8> = "key".into();
+        let get = redis.execute(Command::Get { key });
+
+        assert!(eq_response(Reply::Integer(0), persist.unwrap()));
+        assert!(eq_response(Reply::BulkString("value".into()), get.unwrap()));
+    }
+
+    #[test]
+    fn test_persist_returns_0_on_unexisting_key() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".into();
+        let persist = redis.execute(Command::Persist { key });
+        assert!(eq_response(Reply::Integer(0), persist.unwrap()));
+    }
+
+    #[test]
+    fn test_set_and_rename() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key1".into();
+        let value = "value1".into();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            param: SetParam::default(),
+        });
+
+        let key_origin: Vec<u8> = "key1".into();
+        let key_destination: Vec<u8> = "key2".into();
+        let rename = redis.execute(Command::Rename {
+            key_origin,
+            key_destination,
+        });
+        assert!(rename.is_ok());
+
+        let key = "key1".into();
+        let get = redis.execute(Command::Get { key });
+        assert!(eq_response(Reply::NullBulk, get.unwrap()));
+
+        let key = "key2".into();
+        let get = redis.execute(Command::Get { key });
+        assert!(get.is_ok());
+        assert!(eq_response(
+            Reply::BulkString("value1".into()),
+            get.unwrap()
+        ));
+    }
+
+    #[test]
+    fn test_sort_set() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".into();
+        let mut values = HashSet::new();
+        values.insert("2".into());
+        values.insert("1".into());
+        let _sadd = redis.execute(Command::Sadd { key, values });
+
+        let key = "key".into();
+        let sort = redis.execute(Command::Sort {
+            key,
+            param: SortParam::default(),
+        });
+        assert!(eq_response(
+            Reply::bulk_strings(vec!["1".into(), "2".into()]),
+            sort.unwrap(),
+        ));
+    }
+
+    #[test]
+    fn test_sort_list() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".into();
+        let value = vec!["3".into(), "2".into()];
+        let _lpush = redis.execute(Command::Lpush { key, value });
+
+        let key = "key".into();
+        let sort = redis.execute(Command::Sort {
+            key,
+            param: SortParam::default(),
+        });
+
+        assert!(eq_response(
+            Reply::bulk_strings(vec!["2".into(), "3".into()]),
+            sort.unwrap(),
+        ));
+    }
+
+    #[test]
+    fn test_sort_string_returns_err() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".into();
+        let value = "value".into();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            param: SetParam::default(),
+        });
+
+        let key = "key".into();
+        let sort = redis.execute(Command::Sort {
+            key,
+            param: SortParam::default(),
+        });
+        assert_eq!(
+            sort.err(),
+            Some("WRONGTYPE Operation against a key holding the wrong kind of value".into())
+        );
+    }
+
+    #[test]
+    fn test_sort_empty_key() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".into();
+        let sort = redis.execute(Command::Sort {
+            key,
+            param: SortParam::default(),
+        });
+        assert!(eq_response(Reply::Array(vec![]), sort.unwrap()));
+    }
+
+    #[test]
+    fn test_sort_non_numeric_value_returns_err() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".into();
+        let value = vec!["value1".into(), "value2".into()];
+        let _lpush = redis.execute(Command::Lpush { key, value });
+
+        let key = "key".into();
+        let sort = redis.execute(Command::Sort {
+            key,
+            param: SortParam::default(),
+        });
+        assert_eq!(
+            sort.err(),
+            Some("ERR One or more scores can't be converted into double".into())
+        );
+    }
+
+    #[allow(dead_code)]
+    fn set_sort_fixture(redis: &mut Redis) {
+        let _rpush = redis.execute(Command::Rpush {
+            key: "ids".into(),
+            value: vec!["1".into(), "2".into(), "3".into()],
+        });
+        for (id, weight, name) in [("1", "30", "c"), ("2", "10", "a"), ("3", "20", "b")] {
+            let _set = redis.execute(Command::Set {
+                key: format!("weight_{}", id).into_bytes(),
+                value: weight.into(),
+                param: SetParam::default(),
+            });
+            let _hset = redis.execute(Command::Hset {
+                key: format!("user_{}", id).into_bytes(),
+                field_values: vec![("name".into(), name.into())],
+            });
+        }
+    }
+
+    #[test]
+    fn test_sort_by_and_get_external_keys() {
+        let mut redis: Redis = Redis::new_for_test();
+        set_sort_fixture(&mut redis);
+
+        let sort = redis.execute(Command::Sort {
+            key: "ids".into(),
+            param: SortParam {
+                by: Some("weight_*".into()),
+                get: vec!["#".into(), "user_*->name".into(), "missing_*".into()],
+                ..SortParam::default()
+            },
+        });
+
+        assert!(eq_response(
+            Reply::Array(vec![
+                Reply::BulkString("2".into()),
+                Reply::BulkString("a".into()),
+                Reply::NullBulk,
+                Reply::BulkString("3".into()),
+                Reply::BulkString("b".into()),
+                Reply::NullBulk,
+                Reply::BulkString("1".into()),
+                Reply::BulkString("c".into()),
+                Reply::NullBulk,
+            ]),
+            sort.unwrap(),
+        ));
+    }
+
+    #[test]
+    fn test_sort_alpha_desc_with_limit() {
+        let mut redis: Redis = Redis::new_for_test();
+        let _rpush = redis.execute(Command::Rpush {
+            key: "key".into(),
+            value: vec!["b".into(), "d".into(), "a".into(), "c".into()],
+        });
+
+        let sort = redis.execute(Command::SortRo {
+            key: "key".into(),
+            param: SortParam {
+                limit: Some((1, 2)),
+                desc: true,
+                alpha: true,
+                ..SortParam::default()
+            },
+        });
+        let by_hash = redis.execute(Command::Sort {
+            key: "key".into(),
+            param: SortParam {
+                by: Some("user_*->name".into()),
+                limit: Some((-1, -1)),
+                alpha: true,
+                ..SortParam::default()
+            },
+        });
+
+        assert!(eq_response(
+            Reply::bulk_strings(vec!["c".into(), "b".into()]),
+            sort.unwrap(),
+        ));
+        assert!(eq_response(
+            Reply::bulk_strings(vec!["a".into(), "b".into(), "c".into(), "d".into()]),
+            by_hash.unwrap(),
+        ));
+    }
+
+    #[test]
+    fn test_sort_store_saves_list() {
+        let mut redis: Redis = Redis::new_for_test();
+        set_sort_fixture(&mut redis);
+
+        let sort = redis.execute(Command::Sort {
+            key: "ids".into(),
+            param: SortParam {
+                by: Some("weight_*".into()),
+                get: vec!["user_*->name".into(), "missing_*".into()],
+                desc: true,
+                store: Some("dest".into()),
+                ..SortParam::default()
+            },
+        });
+        let stored = redis.execute(Command::Lrange {
+            key: "dest".into(),
+            begin: 0,
+            end: -1,
+        });
+        let empty = redis.execute(Command::Sort {
+            key: "missing".into(),
+            param: SortParam {
+                store: Some("dest".into()),
+                ..SortParam::default()
+            },
+        });
+
+        assert!(eq_response(Reply::Integer(6), sort.unwrap()));
+        assert!(eq_response(
+            Reply::bulk_strings(vec![
+                "c".into(),
+                vec![],
+                "b".into(),
+                vec![],
+                "a".into(),
+                vec![],
+            ]),
+            stored.unwrap(),
+        ));
+        assert!(eq_response(Reply::Integer(0), empty.unwrap()));
+        assert!(!redis.db.contains_key(&"dest".into()));
+    }
+
+    #[test]
+    fn test_sort_by_pattern_without_star_does_not_sort() {
+        let mut redis: Redis = Redis::new_for_test();
+        let _rpush = redis.execute(Command::Rpush {
+            key: "key".into(),
+            value: vec!["3".into(), "x".into(), "1".into()],
+        });
+
+        let sort = redis.execute(Command::Sort {
+            key: "key".into(),
+            param: SortParam {
+                by: Some("nosort".into()),
+                ..SortParam::default()
+            },
+        });
+
+        assert!(eq_response(
+            Reply::bulk_strings(vec!["3".into(), "x".into(), "1".into()]),
+            sort.unwrap(),
+        ));
+    }
+
+    #[test]
+    fn test_ttl_returns_neg2_on_unexisting_key() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".into();
+        let ttl = redis.execute(Command::Ttl { key });
+
+        assert!(eq_response(Reply::Integer(-2), ttl.unwrap()));
+    }
+
+    #[test]
+    fn test_ttl_returns_neg1_on_persistent_value() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".into();
+        let value = "value".into();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            param: SetParam::default(),
+        });
+
+        let key = "key".into();
+        let ttl = redis.execute(Command::Ttl { key });
+
+        assert!(eq_response(Reply::Integer(-1), ttl.unwrap()));
+    }
+
+    #[test]
+    fn test_ttl_returns_secs_remaining() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".into();
+        let value = "value".into();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            param: SetParam::default(),
+        });
+
+        let key = "key".into();
+        let ttl = Duration::from_secs(5);
+        let _expire = redis.execute(Command::Expire { key, ttl });
+
+        let key = "key".into();
+        let ttl = redis.execute(Command::Ttl { key });
+
+        let _key: String = "key".into();
+
+        assert!(eq_response(Reply::Integer(4), ttl.unwrap()));
+    }
+
+    #[test]
+    fn test_type_on_string() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".into();
+        let value = "value".into();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            param: SetParam::default(),
+        });
+
+        let key = "key".into();
+        let type_method = redis.execute(Command::Type { key });
+        assert!(eq_response(
+            Reply::SimpleString("string".into()),
+            type_method.unwrap(),
+        ));
+    }
+
+    #[test]
+    fn test_type_on_empty_key() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".into();
+        let type_method = redis.execute(Command::Type { key });
+
+        assert!(eq_response(
+            Reply::SimpleString("none".into()),
+            type_method.unwrap(),
+        ));
+    }
+
+    #[test]
+    fn test_type_on_list() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".into();
+        let value = vec!["value".into()];
+        let _lpush = redis.execute(Command::Lpush { key, value });
+
+        let key = "key".into();
+        let type_method = redis.execute(Command::Type { key });
+        assert!(eq_response(
+            Reply::SimpleString("list".into()),
+            type_method.unwrap(),
+        ));
+    }
+
+    #[test]
+    fn test_type_on_set() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".into();
+        let mut values = HashSet::new();
+        values.insert("value".into());
+        let _sadd = redis.execute(Command::Sadd { key, values });
+
+        let key = "key".into();
+        let type_method = redis.execute(Command::Type { key });
+        assert!(eq_response(
+            Reply::SimpleString("set".into()),
+            type_method.unwrap(),
+        ));
+    }
+
+    #[test]
+    fn test_lindex_with_key_used_err() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".into();
+        let value = "value".into();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            param: SetParam::default(),
+        });
+
+        let key = "key".into();
+        let index = 1;
+        let lindex = redis.execute(Command::Lindex { key, index });
+
+        assert!(lindex.is_err());
+    }
+
+    #[test]
+    fn test_lindex_ok() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".into();
+        let value = vec!["value".into(), "value2".into()];
+        let _lpush = redis.execute(Command::Lpush { key, value });
+
+        let key = "key".into();
+        let index = 0;
+        let lindex = redis.execute(Command::Lindex { key, index });
+
+        assert!(lindex.is_ok());
+        assert!(eq_response(
+            Reply::BulkString("value2".into()),
+            lindex.unwrap(),
+        ));
+    }
+
+    #[test]
+    fn test_lindex_negative_index_ok() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".into();
+        let value = vec!["value".into(), "value2".into()];
+        let _lpush = redis.execute(Command::Lpush { key, value });
+
+        let key = "key".into();
+        let index = -1;
+        let lindex = redis.execute(Command::Lindex { key, index });
+
+        assert!(lindex.is_ok());
+        assert!(eq_response(
+            Reply::BulkString("value".into()),
+            lindex.unwrap(),
+        ));
+    }
+
+    #[test]
+    fn test_lindex_negative_index_result_nil_ok() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".into();
+        let value = vec!["value".into(), "value2".into()];
+        let _lpush = redis.execute(Command::Lpush { key, value });
+
+        let key = "key".into();
+        let index = -3;
+        let lindex = redis.execute(Command::Lindex { key, index });
+
+        assert!(lindex.is_ok());
+        assert!(eq_response(Reply::NullBulk, lindex.unwrap()));
+    }
+
+    #[test]
+    fn test_llen_key_saved_as_string_ok() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".into();
+        let value = "value".into();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            param: SetParam::default(),
+        });
+
+        let key = "key".into();
+        let llen = redis.execute(Command::Llen { key });
+
+        assert!(llen.is_err());
+    }
+
+    #[test]
+    fn test_llen_key_not_found_ok() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".into();
+        let llen = redis.execute(Command::Llen { key });
+
+        assert!(llen.is_ok());
+        assert!(eq_response(Reply::Integer(0), llen.unwrap()));
+    }
+
+    #[test]
+    fn test_llen_key_used_twice_ok() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".into();
+        let value = vec!["value".into(), "value2".into()];
+        let _lpush = redis.execute(Command::Lpush { key, value });
+
+        let key = "key".into();
+        let value = vec!["value".into(), "value2".into()];
+        let _lpush = redis.execute(Command::Lpush { key, value });
+
+        let key = "key".into();
+        let llen = redis.execute(Command::Llen { key });
+
+        assert!(eq_response(Reply::Integer(4), llen.unwrap()));
+    }
+
+    #[allow(dead_code)]
+    fn blpop(keys: &[&str], client_id: &str) -> Command {
+        Command::Blpop {
+            keys: keys.iter().map(|key| key.as_bytes().to_vec()).collect(),
+            timeout: None,
+            client_id: client_id.to_string(),
+        }
+    }
+
+    #[allow(dead_code)]
+    fn blocked_receiver(response: Result<Response, String>) -> Receiver<Response> {
+        match response {
+            Ok(Response::Blocked(receiver, _)) => receiver,
+            _ => panic!("the client must be blocked"),
+        }
+    }
+
+    #[test]
+    fn test_blpop_pops_without_blocking_when_list_has_elements() {
+        let mut redis: Redis = Redis::new_for_test();
+        let _rpush = redis.execute(Command::Rpush {
+            key: "second".into(),
+            value: vec!["a".into(), "b".into()],
+        });
+
+        let blpop = redis.execute(blpop(&["first", "second"], "client"));
+        let brpop = redis.execute(Command::Brpop {
+            keys: vec!["second".into()],
+            timeout: None,
+            client_id: "client".into(),
+        });
+
+        assert!(eq_response(
+            Reply::Array(vec![
+                Reply::BulkString("second".into()),
+                Reply::BulkString("a".into())
+            ]),
+            blpop.unwrap(),
+        ));
+        assert!(eq_response(
+            Reply::Array(vec![
+                Reply::BulkString("second".into()),
+                Reply::BulkString("b".into())
+            ]),
+            brpop.unwrap(),
+        ));
+        assert!(redis.blocked_clients.is_empty());
+    }
+
+    #[test]
+    fn test_blpop_serves_blocked_clients_in_order() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let first = blocked_receiver(redis.execute(blpop(&["jobs"], "first")));
+        let second = blocked_receiver(redis.execute(blpop(&["other", "jobs"], "second")));
+        let _rpush = redis.execute(Command::Rpush {
+            key: "jobs".into(),
+            value: vec!["job1".into()],
+        });
+
+        assert!(second.try_recv().is_err());
+        assert!(matches!(
+            first.try_recv(),
+            Ok(Response::Normal(Reply::Array(reply))) if reply[1] == Reply::BulkString("job1".into())
+        ));
+
+        let _lpush = redis.execute(Command::Lpush {
+            key: "jobs".into(),
+            value: vec!["job2".into()],
+        });
+
+        assert!(matches!(
+            second.try_recv(),
+            Ok(Response::Normal(Reply::Array(reply))) if reply[1] == Reply::BulkString("job2".into())
+        ));
+        assert!(redis.blocked_clients.is_empty());
+    }
+
+    #[test]
+    fn test_blpop_only_wakes_on_its_database() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let blocked = blocked_receiver(redis.execute(Command::SelectedCommand {
+            db: 1,
+            command: Box::new(blpop(&["jobs"], "client")),
+        }));
+        let _rpush = redis.execute(Command::SelectedCommand {
+            db: 0,
+            command: Box::new(Command::Rpush {
+                key: "jobs".into(),
+                value: vec!["job".into()],
+            }),
+        });
+
+        assert!(blocked.try_recv().is_err());
+
+        let _rpush = redis.execute(Command::SelectedCommand {
+            db: 1,
+            command: Box::new(Command::Rpush {
+                key: "jobs".into(),
+                value: vec!["job".into()],
+            }),
+        });
+
+        assert!(blocked.try_recv().is_ok());
+        assert_eq!(redis.selected_db, 1);
+    }
+
+    #[test]
+    fn test_unblock_client_replies_timeout() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let blpop = blocked_receiver(redis.execute(blpop(&["jobs"], "client")));
+        let blmove = blocked_receiver(redis.execute(Command::Blmove {
+            source: "jobs".into(),
+            destination: "done".into(),
+            from_left: true,
+            to_left: false,
+            timeout: Some(Duration::from_secs(1)),
+            client_id: "other".into(),
+        }));
+        let _unblock = redis.execute(Command::UnblockClient {
+            client_id: "client".into(),
+        });
+        let _unblock = redis.execute(Command::UnblockClient {
+            client_id: "other".into(),
+        });
+
+        assert!(matches!(
+            blpop.try_recv(),
+            Ok(Response::Normal(Reply::NullArray))
+        ));
+        assert!(matches!(
+            blmove.try_recv(),
+            Ok(Response::Normal(Reply::NullBulk))
+        ));
+        assert!(redis.blocked_clients.is_empty());
+    }
+
+    #[test]
+    fn test_blmove_wakes_clients_blocked_on_destination() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let mover = blocked_receiver(redis.execute(Command::Blmove {
+            source: "jobs".into(),
+            destination: "done".into(),
+            from_left: true,
+            to_left: false,
+            timeout: None,
+            client_id: "mover".into(),
+        }));
+        let waiter = blocked_receiver(redis.execute(blpop(&["done"], "waiter")));
+        let _rpush = redis.execute(Command::Rpush {
+            key: "jobs".into(),
+            value: vec!["job".into()],
+        });
+
+        assert!(matches!(
+            mover.try_recv(),
+            Ok(Response::Normal(Reply::BulkString(element))) if element == b"job"
+        ));
+        assert!(matches!(
+            waiter.try_recv(),
+            Ok(Response::Normal(Reply::Array(reply))) if reply[0] == Reply::BulkString("done".into())
+        ));
+    }
+
+    #[test]
+    fn test_blpop_inside_transaction_does_not_block() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let exec = redis.execute(Command::Exec {
+            commands: Some(vec![blpop(&["jobs"], "client")]),
+            client_id: "client".into(),
+        });
+
+        match exec.unwrap() {
+            Response::Transaction(responses) => {
+                assert!(matches!(&responses[0], Response::Normal(Reply::NullArray)));
+            }
+            _ => panic!("EXEC must reply with a transaction response"),
+        }
+        assert!(redis.blocked_clients.is_empty());
+    }
+
+    #[test]
+    fn test_blpop_wrong_type_returns_err() {
+        let mut redis: Redis = Redis::new_for_test();
+        let _set = redis.execute(Command::Set {
+            key: "key".into(),
+            value: "value".into(),
+            param: SetParam::default(),
+        });
+
+        let blpop = redis.execute(blpop(&["key"], "client"));
+
+        assert_eq!(
+            blpop.err(),
+            Some("WRONGTYPE Operation against a key holding the wrong kind of value".into())
+        );
+    }
+
+    #[test]
+    fn test_lmove_moves_between_ends() {
+        let mut redis: Redis = Redis::new_for_test();
+        let _rpush = redis.execute(Command::Rpush {
+            key: "source".into(),
+            value: vec!["a".into(), "b".into()],
+        });
+
+        let first = redis.execute(Command::Lmove {
+            source: "source".into(),
+            destination: "destination".into(),
+            from_left: false,
+            to_left: true,
+        });
+        let second = redis.execute(Command::Lmove {
+            source: "source".into(),
+            destination: "destination".into(),
+            from_left: true,
+            to_left: true,
+        });
+        let missing = redis.execute(Command::Lmove {
+            source: "source".into(),
+            destination: "destination".into(),
+            from_left: true,
+            to_left: true,
+        });
+        let destination = redis.execute(Command::Lrange {
+            key: "destination".into(),
+            begin: 0,
+            end: -1,
+        });
+
+        assert!(eq_response(Reply::BulkString("b".into()), first.unwrap()));
+        assert!(eq_response(Reply::BulkString("a".into()), second.unwrap()));
+        assert!(eq_response(Reply::NullBulk, missing.unwrap()));
+        assert!(eq_response(
+            Reply::bulk_strings(vec!["a".into(), "b".into()]),
+            destination.unwrap(),
+        ));
+        assert!(!redis.db.contains_key(&"source".into()));
+    }
+
+    #[test]
+    fn test_lmove_to_wrong_type_returns_err() {
+        let mut redis: Redis = Redis::new_for_test();
+        let _rpush = redis.execute(Command::Rpush {
+            key: "source".into(),
+            value: vec!["a".into()],
+        });
+        let _set = redis.execute(Command::Set {
+            key: "destination".into(),
+            value: "value".into(),
+            param: SetParam::default(),
+        });
+
+        let lmove = redis.execute(Command::Lmove {
+            source: "source".into(),
+            destination: "destination".into(),
+            from_left: true,
+            to_left: true,
+        });
+
+        assert_eq!(
+            lmove.err(),
+            Some("WRONGTYPE Operation against a key holding the wrong kind of value".into())
+        );
+        assert!(redis.db.contains_key(&"source".into()));
+    }
+
+    #[test]
+    fn test_lpop_without_count_ok() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".into();
+        let value = vec!["value".into(), "value2".into()];
+        let _lpush = redis.execute(Command::Lpush { key, value });
+
+        let key = "key".into();
+        let lpop = redis.execute(Command::Lpop { key, count: 0 });
+        assert!(lpop.is_ok());
+        assert!(eq_response(
+            Reply::BulkString("value2".into()),
+            lpop.unwrap()
+        ));
+
+        let key = "key".into();
+        let llen = redis.execute(Command::Llen { key });
+        assert!(llen.is_ok());
+        assert!(eq_response(Reply::Integer(1), llen.unwrap()));
+    }
+
+    #[test]
+    fn test_lpop_with_count_ok() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".into();
+        let value = vec![
+            "value".into(),
+            "value2".into(),
+            "value3".into(),
+            "value4".into(),
+        ];
+        let _lpush = redis.execute(Command::Lpush { key, value });
+
+        let key = "key".into();
+        let lpop = redis.execute(Command::Lpop { key, count: 2 });
+        assert!(lpop.is_ok());
+        assert!(eq_response(
+            Reply::bulk_strings(vec!["value4".into(), "value3".into()]),
+            lpop.unwrap(),
+        ));
+
+        let key = "key".into();
+        let llen = redis.execute(Command::Llen { key });
+        assert!(llen.is_ok());
+        assert!(eq_response(Reply::Integer(2), llen.unwrap()));
+    }
+
+    #[test]
+    fn test_lpop_with_count_major_than_len_ok() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".into();
+        let value = vec![
+            "value".into(),
+            "value2".into(),
+            "value3".into(),
+            "value4".into(),
+        ];
+        let _lpush = redis.execute(Command::Lpush { key, value });
+
+        let key = "key".into();
+        let lpop = redis.execute(Command::Lpop { key, count: 5 });
+        assert!(lpop.is_ok());
+        assert!(eq_response(
+            Reply::bulk_strings(vec![
+                "value4".into(),
+                "value3".into(),
+                "value2".into(),
+                "value".into()
+            ]),
+            lpop.unwrap(),
+        ));
+
+        let key = "key".into();
+        let llen = redis.execute(Command::Llen { key });
+        assert!(llen.is_ok());
+        assert!(eq_response(Reply::Integer(0), llen.unwrap()));
+
+        let key = "key".into();
+        let lpop = redis.execute(Command::Lpop { key, count: 5 });
+        assert!(lpop.is_ok());
+        assert!(eq_response(Reply::NullBulk, lpop.unwrap()));
+    }
+
+    #[test]
+    fn test_lpop_with_saved_string_err() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".into();
+        let value = "value".into();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            param: SetParam::default(),
+        });
+
+        let key = "key".into();
+        let lpop = redis.execute(Command::Lpop { key, count: 5 });
+        assert!(lpop.is_err());
+    }
+
+    #[test]
+    fn test_lrange_ok() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".into();
+        let value = vec![
+            "value1".into(),
+            "value2".into(),
+            "value3".into(),
+            "value4".into(),
+        ];
+
+        let _lpush = redis.execute(Command::Lpush { key, value });
+        let key = "key".into();
+        let lrange = redis.execute(Command::Lrange {
+            key,
+            begin: 0,
+            end: -1,
+        });
+
+        assert!(lrange.is_ok());
+        assert!(eq_response(
+            Reply::bulk_strings(vec![
+                "value4".into(),
+                "value3".into(),
+                "value2".into(),
+                "value1".into()
+            ]),
+            lrange.unwrap(),
+        ));
+    }
+
+    #[test]
+    fn test_lrange_ranges_incorrect_return_empty_vec_ok() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".into();
+        let value = vec![
+            "value1".into(),
+            "value2".into(),
+            "value3".into(),
+            "value4".into(),
+        ];
+
+        let _lpush = redis.execute(Command::Lpush { key, value });
+
+        let key = "key".into();
+        let lrange = redis.execute(Command::Lrange {
+            key,
+            begin: -1,
+            end: 0,
+        });
+
+        assert!(lrange.is_ok());
+        assert!(eq_response(Reply::Array(vec![]), lrange.unwrap()));
+    }
+
+    #[test]
+    fn test_lrange_using_ranges_ok() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".into();
+        let value = vec![
+            "value1".into(),
+            "value2".into(),
+            "value3".into(),
+            "value4".into(),
+        ];
+
+        let _lpush = redis.execute(Command::Lpush { key, value });
+
+        let key = "key".into();
+        let lrange = redis.execute(Command::Lrange {
+            key,
+            begin: 2,
+            end: 4,
+        });
+
+        assert!(lrange.is_ok());
+        assert!(eq_response(
+            Reply::bulk_strings(vec!["value2".into(), "value1".into(),]),
+            lrange.unwrap(),
+        ));
+    }
+
+    #[test]
+    fn test_lrange_for_string_value_err() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".into();
+        let value = "value1".into();
+
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            param: SetParam::default(),
+        });
+
+        let key = "key".into();
+        let lrange = redis.execute(Command::Lrange {
+            key,
+            begin: 2,
+            end: 4,
+        });
+
+        assert!(lrange.is_err());
+    }
+
+    #[test]
+    fn test_lset_ok() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".into();
+        let value = vec!["value".into(), "value2".into()];
+        let _lpush = redis.execute(Command::Lpush { key, value });
+
+        let key = "key".into();
+        let index = -1;
+        let element = "Nuevos".into();
+        let lset = redis.execute(Command::Lset {
+            key,
+            index,
+            element,
+        });
+
+        assert!(lset.is_ok());
+        assert!(eq_response(Reply::SimpleString("OK".into()), lset.unwrap(),));
+
+        let key = "key".into();
+        let lrange = redis.execute(Command::Lrange {
+            key,
+            begin: 0,
+            end: -1,
+        });
+
+        assert!(lrange.is_ok());
+        assert!(eq_response(
+            Reply::bulk_strings(vec!["value2".into(), "Nuevos".into(),]),
+            lrange.unwrap(),
+        ));
+    }
+
+    #[test]
+    fn test_lset_out_of_range_err() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".into();
+        let value = vec!["value".into(), "value2".into()];
+        let _lpush = redis.execute(Command::Lpush { key, value });
+
+        let key = "key".into();
+        let index = -50;
+        let element = "Nuevos".into();
+        let lset = redis.execute(Command::Lset {
+            key,
+            index,
+            element,
+        });
+
+        assert!(lset.is_err());
+    }
+
+    #[test]
+    fn test_lset_out_of_range_upper_err() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".into();
+        let value = vec!["value".into(), "value2".into()];
+        let _lpush = redis.execute(Command::Lpush { key, value });
+
+        let key = "key".into();
+        let index = 70;
+        let element = "Nuevos".into();
+        let lset = redis.execute(Command::Lset {
+            key,
+            index,
+            element,
+        });
+
+        assert!(lset.is_err());
+    }
+
+    #[test]
+    fn test_lset_key_not_found_err() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".into();
+        let index = 70;
+        let element = "Nuevos".into();
+        let lset = redis.execute(Command::Lset {
+            key,
+            index,
+            element,
+        });
+
+        assert!(lset.is_err());
+    }
+
+    #[test]
+    fn test_lset_value_saved_was_string_err() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let value = "value".into();
+        let key = "key".into();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            param: SetParam::default(),
+        });
+
+        let key = "key".into();
+        let index = 70;
+        let element = "Nuevos".into();
+        let lset = redis.execute(Command::Lset {
+            key,
+            index,
+            element,
+        });
+
+        assert!(lset.is_err());
+    }
+
+    #[test]
+    fn test_rpop_without_count_ok() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".into();
+        let value = vec!["value".into(), "value2".into()];
+        let _lpush = redis.execute(Command::Lpush { key, value });
+
+        let key = "key".into();
+        let rpop = redis.execute(Command::Rpop { key, count: 0 });
+        assert!(rpop.is_ok());
+        assert!(eq_response(
+            Reply::BulkString("value".into()),
+            rpop.unwrap()
+        ));
+
+        let key = "key".into();
+        let llen = redis.execute(Command::Llen { key });
+        assert!(llen.is_ok());
+        assert!(eq_response(Reply::Integer(1), llen.unwrap()));
+    }
+
+    #[test]
+    fn test_rpop_with_count_ok() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".into();
+        let value = vec![
+            "value".into(),
+            "value2".into(),
+            "value3".into(),
+            "value4".into(),
+        ];
+        let _lpush = redis.execute(Command::Lpush { key, value });
+
+        let key = "key".into();
+        let rpop = redis.execute(Command::Rpop { key, count: 2 });
+        assert!(rpop.is_ok());
+        assert!(eq_response(
+            Reply::bulk_strings(vec!["value".into(), "value2".into(),]),
+            rpop.unwrap(),
+        ));
+
+        let key = "key".into();
+        let llen = redis.execute(Command::Llen { key });
+        assert!(llen.is_ok());
+        assert!(eq_response(Reply::Integer(2), llen.unwrap()));
+    }
+
+    #[test]
+    fn test_rpop_with_count_major_than_len_ok() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".into();
+        let value = vec![
+            "value".into(),
+            "value2".into(),
+            "value3".into(),
+            "value4".into(),
+        ];
+        let _lpush = redis.execute(Command::Lpush { key, value });
+
+        let key = "key".into();
+        let rpop = redis.execute(Command::Rpop { key, count: 5 });
+        assert!(rpop.is_ok());
+        assert!(eq_response(
+            Reply::bulk_strings(vec![
+                "value".into(),
+                "value2".into(),
+                "value3".into(),
+                "value4".into()
+            ]),
+            rpop.unwrap(),
+        ));
+
+        let key = "key".into();
+        let llen = redis.execute(Command::Llen { key });
+        assert!(llen.is_ok());
+        assert!(eq_response(Reply::Integer(0), llen.unwrap()));
+
+        let key = "key".into();
+        let rpop = redis.execute(Command::Rpop { key, count: 5 });
+        assert!(rpop.is_ok());
+        assert!(eq_response(Reply::NullBulk, rpop.unwrap()));
+    }
+
+    #[test]
+    fn test_rpop_with_saved_string_err() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".into();
+        let value = "value".into();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            param: SetParam::default(),
+        });
+
+        let key = "key".into();
+        let rpop = redis.execute(Command::Rpop { key, count: 5 });
+        assert!(rpop.is_err());
+    }
+
+    #[test]
+    fn test_lpush_ok() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".into();
+        let value = vec!["value".into(), "value2".into()];
+        let lpush = redis.execute(Command::Lpush { key, value });
+
+        assert!(lpush.is_ok());
+        assert!(eq_response(Reply::Integer(2), lpush.unwrap()));
+    }
+
+    #[test]
+    fn test_lpush_with_key_used_err() {
+        let mut redis: Redis = Redis::new_for_test();
 
-        match parameter.as_str() {
-            "verbose" => config.set_verbose(value),
-            "dbfilename" => config.set_dbfilename(value),
-            "logfile" => config.set_logfile(value),
-            _ => {
-                let _ = self.log_sender.send(Log::new(
-                    LogLevel::Error,
-                    line!(),
-                    column!(),
-                    file!().to_string(),
-                    "Parameter does not exist".to_string(),
-                ));
-                return Err("Parameter does not exist".to_string());
-            }
-        }
-        Ok(Response::Normal(Re::SimpleString("OK".to_string())))
+        let key = "key".into();
+        let value = "value".into();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            param: SetParam::default(),
+        });
+
+        let key = "key".into();
+        let value = vec!["value".into(), "value2".into()];
+        let lpush = redis.execute(Command::Lpush { key, value });
+
+        assert!(lpush.is_err());
     }
-}
 
-#[allow(unused_imports)]
-mod test {
-    use crate::entities::command::Command;
-    use crate::entities::info_param::InfoParam;
-    use crate::service::redis::TtlHashMap;
-    use crate::service::redis::{Re, Redis, Response};
-    use std::collections::HashSet;
-    use std::fs;
-    use std::io::Write;
-    use std::process;
-    use std::thread::{self, sleep};
-    use std::time::{Duration, SystemTime};
+    #[test]
+    fn test_lpush_key_used_ok() {
+        let mut redis: Redis = Redis::new_for_test();
 
-    #[allow(dead_code)]
-    fn eq_response(content: Re, response: Response) -> bool {
-        if let Response::Normal(redis_element) = response {
-            return content == redis_element;
-        };
-        false
+        let key = "key".into();
+        let value = vec!["value".into(), "value2".into()];
+        let lpush = redis.execute(Command::Lpush { key, value });
+
+        assert!(lpush.is_ok());
+        assert!(eq_response(Reply::Integer(2), lpush.unwrap()));
+
+        let key = "key".into();
+        let value = vec!["value".into(), "value2".into()];
+        let lpush = redis.execute(Command::Lpush { key, value });
+
+        assert!(lpush.is_ok());
+        assert!(eq_response(Reply::Integer(4), lpush.unwrap()));
     }
 
     #[test]
-    fn test_strlen_element_fail_if_is_not_string() {
+    fn test_lpush_key_used_check_ok() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "key".to_string();
-        let value = vec!["value".to_string(), "value2".to_string()];
+        let key = "key".into();
+        let value = vec!["1".into(), "2".into()];
         let _lpush = redis.execute(Command::Lpush { key, value });
 
-        let key = "key".to_string();
-        let strlen = redis.execute(Command::Strlen { key });
+        let key = "key".into();
+        let value = vec!["3".into(), "4".into()];
+        let _lpush = redis.execute(Command::Lpush { key, value });
 
-        assert!(strlen.is_err());
+        let key = "key".into();
+        let index = -1;
+        let lindex = redis.execute(Command::Lindex { key, index });
+        assert!(lindex.is_ok());
+        assert!(eq_response(Reply::BulkString("1".into()), lindex.unwrap()));
+        let key = "key".into();
+        let index = -2;
+        let lindex = redis.execute(Command::Lindex { key, index });
+        assert!(lindex.is_ok());
+        assert!(eq_response(Reply::BulkString("2".into()), lindex.unwrap()));
+        let key = "key".into();
+        let index = -3;
+        let lindex = redis.execute(Command::Lindex { key, index });
+        assert!(lindex.is_ok());
+        assert!(eq_response(Reply::BulkString("3".into()), lindex.unwrap()));
+        let key = "key".into();
+        let index = -4;
+        let lindex = redis.execute(Command::Lindex { key, index });
+        assert!(lindex.is_ok());
+        assert!(eq_response(Reply::BulkString("4".into()), lindex.unwrap()));
     }
 
-    #[ignore]
     #[test]
-    fn test_strlen_element_not_found() {
+    fn test_rpush_ok() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "key".to_string();
-        let strlen = redis.execute(Command::Strlen { key });
+        let key = "key".into();
+        let value = vec!["value".into(), "value2".into()];
+        let rpush = redis.execute(Command::Rpush { key, value });
 
-        assert!(strlen.is_ok());
-        assert!(eq_response(Re::String("0".to_string()), strlen.unwrap()));
+        assert!(rpush.is_ok());
+        assert!(eq_response(Reply::Integer(2), rpush.unwrap()));
     }
 
     #[test]
-    fn test_strlen_element_saved_before() {
+    fn test_rpush_with_key_used_err() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let value = "value".to_string();
-        let key = "hola".to_string();
+        let key = "key".into();
+        let value = "value".into();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            param: SetParam::default(),
+        });
 
-        let _set = redis.execute(Command::Set { key, value });
+        let key = "key".into();
+        let value = vec!["value".into(), "value2".into()];
+        let rpush = redis.execute(Command::Rpush { key, value });
 
-        let key = "hola".to_string();
-        let strlen = redis.execute(Command::Strlen { key });
+        assert!(rpush.is_err());
+    }
 
-        assert!(strlen.is_ok());
-        assert!(eq_response(Re::String("5".to_string()), strlen.unwrap()));
+    #[test]
+    fn test_rpush_key_used_ok() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".into();
+        let value = vec!["value".into(), "value2".into()];
+        let rpush = redis.execute(Command::Rpush { key, value });
+
+        assert!(rpush.is_ok());
+        assert!(eq_response(Reply::Integer(2), rpush.unwrap()));
+
+        let key = "key".into();
+        let value = vec!["value".into(), "value2".into()];
+        let rpush = redis.execute(Command::Rpush { key, value });
+
+        assert!(rpush.is_ok());
+        assert!(eq_response(Reply::Integer(4), rpush.unwrap()));
     }
 
-    #[allow(unused_imports)]
     #[test]
-    fn test_set_element_and_get_the_same() {
+    fn test_rpush_key_used_check_ok() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let value = "value".to_string();
-        let key = "hola".to_string();
+        let key = "key".into();
+        let value = vec!["1".into(), "2".into()];
+        let _rpush = redis.execute(Command::Rpush { key, value });
 
-        let _set = redis.execute(Command::Set { key, value });
+        let key = "key".into();
+        let value = vec!["3".into(), "4".into()];
+        let _rpush = redis.execute(Command::Rpush { key, value });
 
-        let key = "hola".to_string();
-        let get = redis.execute(Command::Get { key });
+        let key = "key".into();
+        let index = -1;
+        let lindex = redis.execute(Command::Lindex { key, index });
+        assert!(lindex.is_ok());
+        assert!(eq_response(Reply::BulkString("4".into()), lindex.unwrap()));
+        let key = "key".into();
+        let index = -2;
+        let lindex = redis.execute(Command::Lindex { key, index });
+        assert!(lindex.is_ok());
+        assert!(eq_response(Reply::BulkString("3".into()), lindex.unwrap()));
+        let key = "key".into();
+        let index = -3;
+        let lindex = redis.execute(Command::Lindex { key, index });
+        assert!(lindex.is_ok());
+        assert!(eq_response(Reply::BulkString("2".into()), lindex.unwrap()));
+        let key = "key".into();
+        let index = -4;
+        let lindex = redis.execute(Command::Lindex { key, index });
+        assert!(lindex.is_ok());
+        assert!(eq_response(Reply::BulkString("1".into()), lindex.unwrap()));
+    }
 
-        assert!(get.is_ok());
-        assert!(eq_response(Re::String("value".to_string()), get.unwrap()));
+    #[test]
+    fn test_sadd() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "key".into();
+        let mut values = HashSet::new();
+        values.insert("value1".into());
+        values.insert("value2".into());
+        values.insert("value3".into());
+        let sadd = redis.execute(Command::Sadd { key, values });
+
+        assert!(eq_response(Reply::Integer(3), sadd.unwrap()));
     }
 
     #[test]
-    fn test_set_element_twice_and_get_the_last_set() {
+    fn test_sadd_with_existing_key() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "hola".to_string();
-        let value = "chau".to_string();
+        let key = "set".into();
+        let mut values = HashSet::new();
+        values.insert("value1".into());
+        values.insert("value2".into());
+        values.insert("value3".into());
+        let sadd = redis.execute(Command::Sadd { key, values });
 
-        let _set = redis.execute(Command::Set { key, value });
+        assert!(eq_response(Reply::Integer(3), sadd.unwrap()));
 
-        let key = "hola".to_string();
-        let value = "test".to_string();
-        let _set = redis.execute(Command::Set { key, value });
+        let key = "set".into();
+        let mut values = HashSet::new();
+        values.insert("value3".into());
+        values.insert("value4".into());
 
-        let key = "hola".to_string();
-        let get = redis.execute(Command::Get { key });
+        let sadd2 = redis.execute(Command::Sadd { key, values });
+        assert!(eq_response(Reply::Integer(1), sadd2.unwrap()));
+    }
 
-        assert!(get.is_ok());
-        assert!(eq_response(Re::String("test".to_string()), get.unwrap()));
+    #[test]
+    fn test_sadd_error() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "set".into();
+        let value = "value".into();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            param: SetParam::default(),
+        });
+
+        let key = "set".into();
+        let mut values = HashSet::new();
+        values.insert("value1".into());
+        values.insert("value2".into());
+        values.insert("value3".into());
+        let sadd = redis.execute(Command::Sadd { key, values });
+
+        assert_eq!(
+            "WRONGTYPE A hashset data type expected".to_string(),
+            sadd.err().unwrap()
+        )
     }
 
     #[test]
-    fn test_get_on_empty_key_returns_nil() {
+    fn test_scard() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "hola".to_string();
-        let get = redis.execute(Command::Get { key });
+        let key = "key".into();
+        let mut values = HashSet::new();
+        values.insert("value1".into());
+        values.insert("value2".into());
+        values.insert("value3".into());
+        let _sadd = redis.execute(Command::Sadd { key, values });
 
-        assert!(get.is_ok());
-        assert!(eq_response(Re::Nil, get.unwrap()));
+        let key = "key".into();
+        let scard = redis.execute(Command::Scard { key });
+
+        assert!(eq_response(Reply::Integer(3), scard.unwrap()));
     }
 
     #[test]
-    fn test_get_element_fail_if_is_not_string() {
+    fn test_scard_error() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "key".to_string();
-        let value = vec!["value".to_string(), "value2".to_string()];
-        let _lpush = redis.execute(Command::Lpush { key, value });
+        let key = "set".into();
+        let value = "value".into();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            param: SetParam::default(),
+        });
 
-        let key = "key".to_string();
-        let get = redis.execute(Command::Get { key });
+        let key = "set".into();
+        let scard = redis.execute(Command::Scard { key });
 
-        assert!(get.is_err());
+        assert_eq!(
+            "WRONGTYPE A hashset data type expected".to_string(),
+            scard.err().unwrap()
+        )
     }
 
     #[test]
-    fn test_getset_fails_if_is_not_string() {
+    fn test_sismember() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "key".to_string();
-        let value = vec!["value".to_string(), "value2".to_string()];
-        let _lpush = redis.execute(Command::Lpush { key, value });
+        let key = "key".into();
+        let mut values = HashSet::new();
+        values.insert("value1".into());
+        values.insert("value2".into());
+        values.insert("value3".into());
+        let _sadd = redis.execute(Command::Sadd { key, values });
 
-        let key = "key".to_string();
-        let value = "value".to_string();
-        let getset = redis.execute(Command::Getset { key, value });
+        let key = "key".into();
+        let value = "value1".into();
+        let sismember = redis.execute(Command::Sismember { key, value });
 
-        assert!(getset.is_err());
+        assert!(eq_response(Reply::Integer(1), sismember.unwrap()));
+
+        let key = "key".into();
+        let value = "value".into();
+        let sismember = redis.execute(Command::Sismember { key, value });
+
+        assert!(eq_response(Reply::Integer(0), sismember.unwrap()));
     }
 
     #[test]
-    fn test_getset_on_empty_key_returns_nil() {
+    fn test_sismember_error() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "key".to_string();
-        let value = "value".to_string();
-        let getset = redis.execute(Command::Getset { key, value });
+        let key = "key".into();
+        let mut values = HashSet::new();
+        values.insert("value1".into());
+        values.insert("value2".into());
+        values.insert("value3".into());
+        let _sadd = redis.execute(Command::Sadd { key, values });
 
-        assert!(getset.is_ok());
-        assert!(eq_response(Re::Nil, getset.unwrap()));
+        let key = "key1".into();
+        let value = "value1".into();
+        let sismember = redis.execute(Command::Sismember { key, value });
+
+        assert_eq!(
+            "The key doesn't exist".to_string(),
+            sismember.err().unwrap()
+        );
+
+        let key = "set".into();
+        let value = "value".into();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            param: SetParam::default(),
+        });
+
+        let key = "set".into();
+        let value = "value".into();
+        let sismember = redis.execute(Command::Sismember { key, value });
+
+        assert_eq!(
+            "WRONGTYPE A hashset data type expected".to_string(),
+            sismember.err().unwrap()
+        );
     }
 
     #[test]
-    fn test_getset_ok() {
+    fn test_srem() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "key".to_string();
-        let value = "1".to_string();
-        let _set = redis.execute(Command::Set { key, value });
+        let key = "key".into();
+        let mut values = HashSet::new();
+        values.insert("value1".into());
+        values.insert("value2".into());
+        values.insert("value3".into());
+        let _sadd = redis.execute(Command::Sadd { key, values });
 
-        let key = "key".to_string();
-        let value = "value".to_string();
-        let getset = redis.execute(Command::Getset { key, value });
-        assert!(getset.is_ok());
-        assert!(eq_response(Re::String("1".to_string()), getset.unwrap()));
+        let key = "key".into();
+        let mut values = HashSet::new();
+        values.insert("value1".into());
+        let srem = redis.execute(Command::Srem { key, values });
 
-        let key = "key".to_string();
-        let get = redis.execute(Command::Get { key });
-        assert!(get.is_ok());
-        assert!(eq_response(Re::String("value".to_string()), get.unwrap()));
+        assert!(eq_response(Reply::Integer(1), srem.unwrap()));
+
+        let key = "key_inexistente".into();
+        let mut values = HashSet::new();
+        values.insert("value2".into());
+        let srem = redis.execute(Command::Srem { key, values });
+
+        assert!(eq_response(Reply::Integer(0), srem.unwrap()));
     }
 
     #[test]
-    fn test_ping_returns_pong() {
+    fn test_srem_value_two_times() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let ping = redis.execute(Command::Ping);
+        let key = "key".into();
+        let mut values = HashSet::new();
+        values.insert("value1".into());
+        values.insert("value2".into());
+        values.insert("value3".into());
+        let _sadd = redis.execute(Command::Sadd { key, values });
 
-        assert!(ping.is_ok());
-        assert!(eq_response(
-            Re::SimpleString("PONG".to_string()),
-            ping.unwrap(),
-        ));
+        let key = "key".into();
+        let mut values = HashSet::new();
+        values.insert("value1".into());
+        let srem = redis.execute(Command::Srem { key, values });
+
+        assert!(eq_response(Reply::Integer(1), srem.unwrap()));
+
+        let key = "key".into();
+        let mut values = HashSet::new();
+        values.insert("value1".into());
+        let srem = redis.execute(Command::Srem { key, values });
+
+        assert!(eq_response(Reply::Integer(0), srem.unwrap()));
+    }
+
+    #[test]
+    fn test_srem_error() {
+        let mut redis: Redis = Redis::new_for_test();
+
+        let key = "set".into();
+        let value = "value".into();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            param: SetParam::default(),
+        });
+
+        let key = "set".into();
+        let mut values = HashSet::new();
+        values.insert("value1".into());
+        let srem = redis.execute(Command::Srem { key, values });
+
+        assert_eq!(
+            "WRONGTYPE A hashset data type expected".to_string(),
+            srem.err().unwrap()
+        );
     }
 
     #[test]
-    fn test_incrby_with_2_as_value() {
+    fn test_smembers() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "key".to_string();
-        let value = "1".to_string();
-        let _set = redis.execute(Command::Set { key, value });
-
-        let key = "key".to_string();
-        let increment: u32 = 1;
-        let _incrby = redis.execute(Command::Incrby { key, increment });
-
-        let key = "key".to_string();
-        let get = redis.execute(Command::Get { key });
+        let key = "key".into();
+        let mut values = HashSet::new();
+        values.insert("value1".into());
+        values.insert("value2".into());
+        values.insert("value3".into());
+        let _sadd = redis.execute(Command::Sadd { key, values });
 
-        let key = "key".to_string();
-        let increment: u32 = 2;
-        let _incrby = redis.execute(Command::Incrby { key, increment });
+        let key = "key".into();
+        let mut values = HashSet::new();
+        values.insert("value1".into());
+        values.insert("value2".into());
+        values.insert("value3".into());
+        let smembers = redis.execute(Command::Smembers { key });
 
-        let key = "key".to_string();
-        let second_get = redis.execute(Command::Get { key });
+        assert!(eq_members(values, smembers.unwrap()));
+    }
 
-        assert!(get.is_ok());
-        assert!(eq_response(Re::String("2".to_string()), get.unwrap()));
+    #[allow(dead_code)]
+    fn sadd_members(redis: &mut Redis, key: &str, members: &[&str]) {
+        let _sadd = redis.execute(Command::Sadd {
+            key: key.into(),
+            values: members
+                .iter()
+                .map(|member| member.as_bytes().to_vec())
+                .collect(),
+        });
+    }
 
-        assert!(second_get.is_ok());
-        assert!(eq_response(
-            Re::String("4".to_string()),
-            second_get.unwrap(),
-        ));
+    #[allow(dead_code)]
+    fn members_of(members: &[&str]) -> HashSet<Vec<u8>> {
+        members
+            .iter()
+            .map(|member| member.as_bytes().to_vec())
+            .collect()
     }
 
     #[test]
-    fn test_incrby_value_err_initial_value_string() {
+    fn test_sinter_sunion_and_sdiff() {
         let mut redis: Redis = Redis::new_for_test();
+        sadd_members(&mut redis, "a", &["1", "2", "3"]);
+        sadd_members(&mut redis, "b", &["2", "3", "4"]);
+        let keys = || vec![b"a".to_vec(), b"b".to_vec(), b"missing".to_vec()];
+
+        let sinter = redis.execute(Command::Sinter { keys: keys() });
+        let sunion = redis.execute(Command::Sunion { keys: keys() });
+        let sdiff = redis.execute(Command::Sdiff { keys: keys() });
+        let sinter_existing = redis.execute(Command::Sinter {
+            keys: vec!["a".into(), "b".into()],
+        });
 
-        let key = "key".to_string();
-        let value = "hola".to_string();
-        let _set = redis.execute(Command::Set { key, value });
-
-        let key = "key".to_string();
-        let increment: u32 = 1;
-        let incrby = redis.execute(Command::Incrby { key, increment });
-
-        assert!(incrby.is_err());
+        assert!(eq_members(HashSet::new(), sinter.unwrap()));
+        assert!(eq_members(
+            members_of(&["1", "2", "3", "4"]),
+            sunion.unwrap()
+        ));
+        assert!(eq_members(members_of(&["1"]), sdiff.unwrap()));
+        assert!(eq_members(
+            members_of(&["2", "3"]),
+            sinter_existing.unwrap()
+        ));
     }
 
     #[test]
-    fn test_incrby_not_saved_value() {
+    fn test_set_operation_wrong_type_returns_err() {
         let mut redis: Redis = Redis::new_for_test();
+        sadd_members(&mut redis, "a", &["1"]);
+        let _set = redis.execute(Command::Set {
+            key: "string".into(),
+            value: "value".into(),
+            param: SetParam::default(),
+        });
 
-        let key = "key".to_string();
-        let increment: u32 = 1;
-        let _incrby = redis.execute(Command::Incrby { key, increment });
-
-        let key = "key".to_string();
-        let get = redis.execute(Command::Get { key });
+        let sunion = redis.execute(Command::Sunion {
+            keys: vec!["a".into(), "string".into()],
+        });
 
-        assert!(get.is_ok());
-        assert!(eq_response(Re::String("1".to_string()), get.unwrap()));
+        assert!(sunion.unwrap_err().starts_with("WRONGTYPE"));
     }
 
     #[test]
-    fn test_decrby_on_new_key() {
+    fn test_sinterstore_overwrites_destination_and_its_ttl() {
         let mut redis: Redis = Redis::new_for_test();
+        sadd_members(&mut redis, "a", &["1", "2"]);
+        sadd_members(&mut redis, "b", &["2", "3"]);
+        sadd_members(&mut redis, "dest", &["old"]);
+        let _expire = redis.execute(Command::Expire {
+            key: "dest".into(),
+            ttl: Duration::from_secs(100),
+        });
 
-        let key = "key".to_string();
-        let decrement: u32 = 3;
-        let _decrby = redis.execute(Command::Decrby { key, decrement });
-
-        let key = "key".to_string();
-        let get = redis.execute(Command::Get { key });
+        let sinterstore = redis.execute(Command::Sinterstore {
+            destination: "dest".into(),
+            keys: vec!["a".into(), "b".into()],
+        });
+        let ttl = redis.execute(Command::Ttl { key: "dest".into() });
+        let smembers = redis.execute(Command::Smembers { key: "dest".into() });
+        let empty = redis.execute(Command::Sdiffstore {
+            destination: "dest".into(),
+            keys: vec!["a".into(), "a".into()],
+        });
 
-        assert!(get.is_ok());
-        assert!(eq_response(Re::String("-3".to_string()), get.unwrap()));
+        assert!(eq_response(Reply::Integer(1), sinterstore.unwrap()));
+        assert!(eq_response(Reply::Integer(-1), ttl.unwrap()));
+        assert!(eq_members(members_of(&["2"]), smembers.unwrap()));
+        assert!(eq_response(Reply::Integer(0), empty.unwrap()));
+        assert!(!redis.db.contains_key(&"dest".into()));
     }
 
     #[test]
-    fn test_decrby_on_existing_key() {
+    fn test_sunionstore_can_use_destination_as_source() {
         let mut redis: Redis = Redis::new_for_test();
+        sadd_members(&mut redis, "a", &["1", "2"]);
+        sadd_members(&mut redis, "dest", &["3"]);
 
-        let key = "key".to_string();
-        let value = "5".to_string();
-        let _set = redis.execute(Command::Set { key, value });
-
-        let key = "key".to_string();
-        let decrement: u32 = 3;
-        let _decrby = redis.execute(Command::Decrby { key, decrement });
-
-        let key = "key".to_string();
-        let get = redis.execute(Command::Get { key });
+        let sunionstore = redis.execute(Command::Sunionstore {
+            destination: "dest".into(),
+            keys: vec!["a".into(), "dest".into()],
+        });
+        let smembers = redis.execute(Command::Smembers { key: "dest".into() });
 
-        assert!(get.is_ok());
-        assert!(eq_response(Re::String("2".to_string()), get.unwrap()));
+        assert!(eq_response(Reply::Integer(3), sunionstore.unwrap()));
+        assert!(eq_members(members_of(&["1", "2", "3"]), smembers.unwrap()));
     }
 
     #[test]
-    fn test_mset_sets_2_values() {
+    fn test_sintercard_with_limit() {
         let mut redis: Redis = Redis::new_for_test();
+        sadd_members(&mut redis, "a", &["1", "2", "3", "4"]);
+        sadd_members(&mut redis, "b", &["2", "3", "4", "5"]);
 
-        let key_values = vec![
-            ("key1".to_string(), "value1".to_string()),
-            ("key2".to_string(), "value2".to_string()),
-        ];
-        let _mset = redis.execute(Command::Mset { key_values });
-
-        let key = "key1".to_string();
-        let get = redis.execute(Command::Get { key });
-        assert!(get.is_ok());
-        assert!(eq_response(Re::String("value1".to_string()), get.unwrap()));
+        let sintercard = redis.execute(Command::Sintercard {
+            keys: vec!["a".into(), "b".into()],
+            limit: 0,
+        });
+        let limited = redis.execute(Command::Sintercard {
+            keys: vec!["a".into(), "b".into()],
+            limit: 2,
+        });
 
-        let key = "key2".to_string();
-        let get = redis.execute(Command::Get { key });
-        assert!(get.is_ok());
-        assert!(eq_response(Re::String("value2".to_string()), get.unwrap()));
+        assert!(eq_response(Reply::Integer(3), sintercard.unwrap()));
+        assert!(eq_response(Reply::Integer(2), limited.unwrap()));
     }
 
     #[test]
-    fn test_mget_gets_2_values() {
+    fn test_smismember() {
         let mut redis: Redis = Redis::new_for_test();
+        sadd_members(&mut redis, "key", &["a", "b"]);
 
-        let key_values = vec![
-            ("key1".to_string(), "value1".to_string()),
-            ("key2".to_string(), "value2".to_string()),
-        ];
-        let _mset = redis.execute(Command::Mset { key_values });
-
-        let keys = vec!["key1".to_string(), "key2".to_string()];
-        let mget = redis.execute(Command::Mget { keys });
+        let smismember = redis.execute(Command::Smismember {
+            key: "key".into(),
+            members: vec!["a".into(), "x".into(), "b".into()],
+        });
+        let missing = redis.execute(Command::Smismember {
+            key: "missing".into(),
+            members: vec!["a".into()],
+        });
 
-        assert!(mget.is_ok());
         assert!(eq_response(
-            Re::List(vec!["value1".to_string(), "value2".to_string()]),
-            mget.unwrap(),
+            Reply::Array(vec![
+                Reply::Integer(1),
+                Reply::Integer(0),
+                Reply::Integer(1)
+            ]),
+            smismember.unwrap()
+        ));
+        assert!(eq_response(
+            Reply::Array(vec![Reply::Integer(0)]),
+            missing.unwrap()
         ));
     }
 
     #[test]
-    fn test_mget_nil_for_missing_value() {
+    fn test_smove_moves_member() {
         let mut redis: Redis = Redis::new_for_test();
+        sadd_members(&mut redis, "source", &["a"]);
+        sadd_members(&mut redis, "destination", &["b"]);
 
-        let key = "key".to_string();
-        let value = "value".to_string();
-        let _set = redis.execute(Command::Set { key, value });
-
-        let keys = vec!["key".to_string(), "key_empty".to_string()];
-        let mget = redis.execute(Command::Mget { keys });
+        let smove = redis.execute(Command::Smove {
+            source: "source".into(),
+            destination: "destination".into(),
+            member: "a".into(),
+        });
+        let not_found = redis.execute(Command::Smove {
+            source: "source".into(),
+            destination: "destination".into(),
+            member: "a".into(),
+        });
+        let smembers = redis.execute(Command::Smembers {
+            key: "destination".into(),
+        });
 
-        assert!(mget.is_ok());
-        assert!(eq_response(
-            Re::List(vec!["value".to_string(), "(nil)".to_string()]),
-            mget.unwrap(),
-        ));
+        assert!(eq_response(Reply::Integer(1), smove.unwrap()));
+        assert!(eq_response(Reply::Integer(0), not_found.unwrap()));
+        assert!(eq_members(members_of(&["a", "b"]), smembers.unwrap()));
+        assert!(!redis.db.contains_key(&"source".into()));
     }
 
     #[test]
-    fn test_mget_nil_for_non_string_value() {
+    fn test_smove_to_wrong_type_returns_err() {
         let mut redis: Redis = Redis::new_for_test();
+        sadd_members(&mut redis, "source", &["a"]);
+        let _set = redis.execute(Command::Set {
+            key: "destination".into(),
+            value: "value".into(),
+            param: SetParam::default(),
+        });
 
-        let key = "key".to_string();
-        let value = "value".to_string();
-        let _set = redis.execute(Command::Set { key, value });
-
-        let key = "key_list".to_string();
-        let value = vec!["value1".to_string(), "value2".to_string()];
-        let _lpush = redis.execute(Command::Lpush { key, value });
-
-        let keys = vec!["key".to_string(), "key_list".to_string()];
-        let mget = redis.execute(Command::Mget { keys });
+        let smove = redis.execute(Command::Smove {
+            source: "source".into(),
+            destination: "destination".into(),
+            member: "a".into(),
+        });
 
-        assert!(mget.is_ok());
-        assert!(eq_response(
-            Re::List(vec!["value".to_string(), "(nil)".to_string()]),
-            mget.unwrap(),
-        ));
+        assert!(smove.unwrap_err().starts_with("WRONGTYPE"));
+        assert!(redis.db.contains_key(&"source".into()));
     }
 
     #[test]
-    fn test_set_element_and_getdel() {
+    fn test_spop_with_and_without_count() {
         let mut redis: Redis = Redis::new_for_test();
+        sadd_members(&mut redis, "key", &["a", "b", "c"]);
 
-        let value = "value".to_string();
-        let key = "key".to_string();
-        let _set = redis.execute(Command::Set { key, value });
+        let single = redis.execute(Command::Spop {
+            key: "key".into(),
+            count: None,
+        });
+        let rest = redis.execute(Command::Spop {
+            key: "key".into(),
+            count: Some(5),
+        });
+        let missing = redis.execute(Command::Spop {
+            key: "key".into(),
+            count: None,
+        });
 
-        let key = "key".to_string();
-        let get = redis.execute(Command::Get { key });
+        let mut popped = HashSet::new();
+        match single {
+            Ok(Response::Normal(Reply::BulkString(member))) => popped.insert(member),
+            _ => panic!("spop should return a member"),
+        };
+        let members = bulk_strings(rest);
+        assert_eq!(members.len(), 2);
+        popped.extend(members);
+        assert_eq!(popped, members_of(&["a", "b", "c"]));
+        assert!(eq_response(Reply::NullBulk, missing.unwrap()));
+        assert!(!redis.db.contains_key(&"key".into()));
+    }
 
-        let key = "key".to_string();
-        let getdel = redis.execute(Command::Getdel { key });
+    #[test]
+    fn test_spop_is_logged_as_srem() {
+        let path = "test_spop_is_logged_as_srem.aof".to_string();
+        let _ = fs::remove_file(&path);
 
-        assert!(get.is_ok());
-        assert!(eq_response(Re::String("value".to_string()), get.unwrap()));
+        let mut redis: Redis = Redis::new_for_test();
+        let _load = redis.execute(Command::LoadAof { path: path.clone() });
+        sadd_members(&mut redis, "key", &["a", "b", "c", "d"]);
+        let _spop = redis.execute(Command::Spop {
+            key: "key".into(),
+            count: Some(2),
+        });
+        let smembers = redis.execute(Command::Smembers { key: "key".into() });
 
-        assert!(getdel.is_ok());
-        assert!(eq_response(
-            Re::String("value".to_string()),
-            getdel.unwrap(),
-        ));
+        let mut redis_new: Redis = Redis::new_for_test();
+        let _load = redis_new.execute(Command::LoadAof { path: path.clone() });
+        let content = fs::read(&path).unwrap();
+        let _ = fs::remove_file(&path);
+        let smembers_new = redis_new.execute(Command::Smembers { key: "key".into() });
 
-        let key = "key".to_string();
-        let get = redis.execute(Command::Get { key });
-        assert!(eq_response(Re::Nil, get.unwrap()));
+        assert!(String::from_utf8_lossy(&content).contains("srem"));
+        assert!(!String::from_utf8_lossy(&content).contains("spop"));
+        let members: HashSet<Vec<u8>> = bulk_strings(smembers).into_iter().collect();
+        let loaded: HashSet<Vec<u8>> = bulk_strings(smembers_new).into_iter().collect();
+        assert_eq!(members.len(), 2);
+        assert_eq!(members, loaded);
     }
 
     #[test]
-    fn test_getdel_without_previews_saving_err() {
+    fn test_srandmember_counts() {
         let mut redis: Redis = Redis::new_for_test();
+        sadd_members(&mut redis, "key", &["a", "b", "c"]);
+        let mut srandmember = |count| {
+            redis.execute(Command::Srandmember {
+                key: "key".into(),
+                count,
+            })
+        };
 
-        let key = "key".to_string();
-        let getdel = redis.execute(Command::Getdel { key });
+        let single = srandmember(None);
+        let distinct = srandmember(Some(10));
+        let repeated = srandmember(Some(-10));
+        let none = srandmember(Some(0));
 
-        assert_eq!(true, getdel.is_err());
+        assert!(matches!(single, Ok(Response::Normal(Reply::BulkString(_)))));
+        assert_eq!(
+            bulk_strings(distinct)
+                .into_iter()
+                .collect::<HashSet<Vec<u8>>>(),
+            members_of(&["a", "b", "c"])
+        );
+        let members = bulk_strings(repeated);
+        assert_eq!(members.len(), 10);
+        assert!(members
+            .iter()
+            .all(|member| members_of(&["a", "b", "c"]).contains(member)));
+        assert!(eq_response(Reply::Array(vec![]), none.unwrap()));
+        assert_eq!(redis.db.len(), 1);
     }
 
     #[test]
-    fn test_dbsize() {
+    fn test_lpushx_not_pre_save_return_0() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let dbsize = redis.execute(Command::Dbsize);
-        assert!(eq_response(Re::String("0".to_string()), dbsize.unwrap()));
-
-        let value = "value".to_string();
-        let key = "key".to_string();
-        let _set = redis.execute(Command::Set { key, value });
-
-        let dbsize = redis.execute(Command::Dbsize);
-        assert!(eq_response(Re::String("1".to_string()), dbsize.unwrap()));
-
-        let key = "key".to_string();
-        let _getdel = redis.execute(Command::Getdel { key });
+        let key = "key".into();
+        let value = vec!["value".into(), "value2".into()];
+        let lpushx = redis.execute(Command::Lpushx { key, value });
 
-        let dbsize = redis.execute(Command::Dbsize);
-        assert!(eq_response(Re::String("0".to_string()), dbsize.unwrap()));
+        assert!(lpushx.is_ok());
+        assert!(eq_response(Reply::Integer(0), lpushx.unwrap()));
     }
 
     #[test]
-    fn test_set_element_and_del() {
+    fn test_lpushx_with_key_used_with_string_err() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let value = "value".to_string();
-        let key = "key".to_string();
-        let _set = redis.execute(Command::Set { key, value });
+        let key = "key".into();
+        let value = "value".into();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            param: SetParam::default(),
+        });
 
-        let keys = vec!["key".to_string()];
-        let del = redis.execute(Command::Del { keys });
-        assert!(eq_response(Re::String("1".to_string()), del.unwrap()));
+        let key = "key".into();
+        let value = vec!["value".into(), "value2".into()];
+        let lpushx = redis.execute(Command::Lpushx { key, value });
 
-        let key = "key".to_string();
-        let get = redis.execute(Command::Get { key });
-        assert!(eq_response(Re::Nil, get.unwrap()));
+        assert!(lpushx.is_err());
     }
 
     #[test]
-    fn test_set_two_elements_and_del_both() {
+    fn test_lpushx_after_lpush_ok() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let value = "value".to_string();
-        let key = "key1".to_string();
-        let _set = redis.execute(Command::Set { key, value });
+        let key = "key".into();
+        let value = vec!["value".into(), "value2".into()];
+        let lpush = redis.execute(Command::Lpush { key, value });
 
-        let value = "value".to_string();
-        let key = "key2".to_string();
-        let _set = redis.execute(Command::Set { key, value });
+        assert!(lpush.is_ok());
+        assert!(eq_response(Reply::Integer(2), lpush.unwrap()));
 
-        let keys = vec!["key1".to_string(), "key2".to_string()];
-        let del = redis.execute(Command::Del { keys });
+        let key = "key".into();
+        let value = vec!["value".into(), "value2".into()];
+        let lpush = redis.execute(Command::Lpushx { key, value });
 
-        assert!(eq_response(Re::String("2".to_string()), del.unwrap()));
+        assert!(lpush.is_ok());
+        assert!(eq_response(Reply::Integer(4), lpush.unwrap()));
     }
 
     #[test]
-    fn test_append_adds_word() {
+    fn test_rpushx_not_pre_save_return_0() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "key".to_string();
-        let value = "value".to_string();
-        let _set = redis.execute(Command::Set { key, value });
-
-        let key = "key".to_string();
-        let value = " appended".to_string();
-        let _append = redis.execute(Command::Append { key, value });
+        let key = "key".into();
+        let value = vec!["value".into(), "value2".into()];
+        let rpushx = redis.execute(Command::Rpushx { key, value });
 
-        let key = "key".to_string();
-        let get = redis.execute(Command::Get { key });
-        assert!(eq_response(
-            Re::String("value appended".to_string()),
-            get.unwrap(),
-        ));
+        assert!(rpushx.is_ok());
+        assert!(eq_response(Reply::Integer(0), rpushx.unwrap()));
     }
 
     #[test]
-    fn test_append_on_non_existent_key() {
+    fn test_rpushx_with_key_used_with_string_err() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "key".to_string();
-        let value = " appended".to_string();
-        let _append = redis.execute(Command::Append { key, value });
+        let key = "key".into();
+        let value = "value".into();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            param: SetParam::default(),
+        });
 
-        let key = "key".to_string();
-        let get = redis.execute(Command::Get { key });
+        let key = "key".into();
+        let value = vec!["value".into(), "value2".into()];
+        let rpushx = redis.execute(Command::Rpushx { key, value });
 
-        assert!(eq_response(
-            Re::String(" appended".to_string()),
-            get.unwrap(),
-        ));
+        assert!(rpushx.is_err());
     }
 
     #[test]
-    fn test_set_two_elements_and_check_exists_equal_2() {
+    fn test_rpushx_after_rpush_ok() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "key1".to_string();
-        let value = "value".to_string();
-        let _set = redis.execute(Command::Set { key, value });
+        let key = "key".into();
+        let value = vec!["value".into(), "value2".into()];
+        let rpushx = redis.execute(Command::Rpush { key, value });
 
-        let key = "key2".to_string();
-        let value = "value".to_string();
-        let _set = redis.execute(Command::Set { key, value });
+        assert!(rpushx.is_ok());
+        assert!(eq_response(Reply::Integer(2), rpushx.unwrap()));
 
-        let keys = vec!["key1".to_string(), "key2".to_string()];
-        let exists = redis.execute(Command::Exists { keys });
-        assert!(eq_response(Re::String("2".to_string()), exists.unwrap()));
+        let key = "key".into();
+        let value = vec!["value".into(), "value2".into()];
+        let rpushx = redis.execute(Command::Rpushx { key, value });
 
-        let keys = vec!["key1".to_string(), "key2".to_string(), "key3".to_string()];
-        let exists = redis.execute(Command::Exists { keys });
-        assert!(eq_response(Re::String("2".to_string()), exists.unwrap()));
+        assert!(rpushx.is_ok());
+        assert!(eq_response(Reply::Integer(4), rpushx.unwrap()));
     }
 
     #[test]
-    fn test_copy_on_existing_key_returns_0() {
+    fn test_rpush_and_check_elements_ok() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "key1".to_string();
-        let value = "value1".to_string();
-        let _set = redis.execute(Command::Set { key, value });
+        let key = "key".into();
+        let value = vec!["1".into(), "2".into(), "3".into(), "4".into()];
+        let rpushx = redis.execute(Command::Rpush { key, value });
 
-        let key = "key2".to_string();
-        let value = "value2".to_string();
-        let _set = redis.execute(Command::Set { key, value });
+        assert!(rpushx.is_ok());
+        assert!(eq_response(Reply::Integer(4), rpushx.unwrap()));
 
-        let key_origin: String = "key1".to_string();
-        let key_destination: String = "key2".to_string();
-        let copy = redis.execute(Command::Copy {
-            key_destination,
-            key_origin,
+        let key = "key".into();
+        let value = vec!["1".into(), "2".into(), "3".into(), "4".into()];
+        let rpushx = redis.execute(Command::Lrange {
+            key,
+            begin: 0,
+            end: -1,
         });
 
-        assert!(eq_response(Re::String("0".to_string()), copy.unwrap()));
+        assert!(rpushx.is_ok());
+        assert!(eq_response(Reply::bulk_strings(value), rpushx.unwrap()));
     }
 
     #[test]
-    fn test_copy_ok() {
+    fn test_rpush_rpushx_and_check_elements_ok() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "key1".to_string();
-        let value = "value1".to_string();
-        let _set = redis.execute(Command::Set { key, value });
+        let key = "key".into();
+        let value = vec!["1".into(), "2".into()];
+        let rpush = redis.execute(Command::Rpush { key, value });
 
-        let key_origin: String = "key1".to_string();
-        let key_destination: String = "key2".to_string();
-        let _copy = redis.execute(Command::Copy {
-            key_destination,
-            key_origin,
+        assert!(rpush.is_ok());
+        assert!(eq_response(Reply::Integer(2), rpush.unwrap()));
+
+        let key = "key".into();
+        let value = vec!["3".into(), "4".into()];
+        let rpushx = redis.execute(Command::Rpushx { key, value });
+
+        assert!(rpushx.is_ok());
+        assert!(eq_response(Reply::Integer(4), rpushx.unwrap()));
+
+        let key = "key".into();
+        let rpushx = redis.execute(Command::Lrange {
+            key,
+            begin: 0,
+            end: -1,
         });
 
-        let key = "key2".to_string();
-        let get = redis.execute(Command::Get { key });
-        assert!(eq_response(Re::String("value1".to_string()), get.unwrap()));
+        assert!(rpushx.is_ok());
+        assert!(eq_response(
+            Reply::bulk_strings(vec!["1".into(), "2".into(), "3".into(), "4".into()]),
+            rpushx.unwrap(),
+        ));
     }
 
-    #[ignore]
     #[test]
-    fn test_expire_deletes_key() {
+    fn test_lrem_ok() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "key".to_string();
-        let value = "value".to_string();
-        let _set = redis.execute(Command::Set { key, value });
+        let key = "key".into();
+        let value = vec![
+            "value".into(),
+            "value1".into(),
+            "value2".into(),
+            "value".into(),
+            "value".into(),
+        ];
+        let _lpush = redis.execute(Command::Lpush { key, value });
+
+        let key = "key".into();
 
-        let key = "key".to_string();
-        let ttl = Duration::from_secs(1);
-        let expire = redis.execute(Command::Expire { key, ttl });
+        let lrem = redis.execute(Command::Lrem {
+            key,
+            count: 2,
+            element: "value".into(),
+        });
+        assert!(lrem.is_ok());
+        assert!(eq_response(Reply::Integer(2), lrem.unwrap()));
 
-        thread::sleep(Duration::from_secs(1));
+        let key = "key".into();
 
-        let key = "key".to_string();
-        let get = redis.execute(Command::Get { key });
-        assert!(eq_response(Re::Nil, get.unwrap()));
-        assert!(eq_response(Re::String("1".to_string()), expire.unwrap()));
+        let lrange = redis.execute(Command::Lrange {
+            key,
+            begin: 0,
+            end: -1,
+        });
+
+        let mut vector = vec!["value1".into(), "value2".into(), "value".into()];
+        vector.reverse();
+        assert!(eq_response(Reply::bulk_strings(vector), lrange.unwrap()));
     }
 
     #[test]
-    fn test_expire_returns_0_on_unexisting_key() {
+    fn test_lrem_reverse_ok() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "key".to_string();
-        let ttl = Duration::from_secs(1);
-        let expire = redis.execute(Command::Expire { key, ttl });
+        let key = "key".into();
+        let value = vec![
+            "value".into(),
+            "value".into(),
+            "value2".into(),
+            "value3".into(),
+            "value1".into(),
+            "value".into(),
+        ];
+        let _lpush = redis.execute(Command::Lpush { key, value });
+
+        let key = "key".into();
+
+        let lrem = redis.execute(Command::Lrem {
+            key,
+            count: -2,
+            element: "value".into(),
+        });
+        assert!(lrem.is_ok());
+        assert!(eq_response(Reply::Integer(2), lrem.unwrap()));
 
-        assert!(eq_response(Re::String("0".to_string()), expire.unwrap()));
-    }
+        let key = "key".into();
 
-    #[test]
-    fn test_expireat_with_past_time_deletes_key() {
-        let mut redis: Redis = Redis::new_for_test();
+        let lrange = redis.execute(Command::Lrange {
+            key,
+            begin: 0,
+            end: -1,
+        });
 
-        let key = "key".to_string();
-        let value = "value".to_string();
-        let _set = redis.execute(Command::Set { key, value });
+        let mut vector = vec![
+            "value".into(),
+            "value2".into(),
+            "value3".into(),
+            "value1".into(),
+        ];
 
-        let key = "key".to_string();
-        let ttl = SystemTime::UNIX_EPOCH + Duration::from_secs(1623793215);
-        let expire = redis.execute(Command::Expireat { key, ttl });
+        vector.reverse();
 
-        let key = "key".to_string();
-        let get = redis.execute(Command::Get { key });
-        assert!(eq_response(Re::Nil, get.unwrap()));
-        assert!(eq_response(Re::String("1".to_string()), expire.unwrap()));
+        assert!(eq_response(Reply::bulk_strings(vector), lrange.unwrap()));
     }
 
     #[test]
-    fn test_expireat_returns_0_on_unexisting_key() {
+    fn test_lrem_all_ok() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "key".to_string();
-        let ttl = SystemTime::UNIX_EPOCH + Duration::from_secs(1623793215);
-        let expire = redis.execute(Command::Expireat { key, ttl });
-
-        assert!(eq_response(Re::String("0".to_string()), expire.unwrap()));
-    }
+        let key = "key".into();
+        let value = vec![
+            "value".into(),
+            "value2".into(),
+            "value3".into(),
+            "value1".into(),
+            "value".into(),
+        ];
+        let _lpush = redis.execute(Command::Lpush { key, value });
 
-    #[ignore]
-    #[test]
-    fn test_persist_deletes_expire_time() {
-        let mut redis: Redis = Redis::new_for_test();
+        let key = "key".into();
 
-        let key = "key".to_string();
-        let value = "value".to_string();
-        let _set = redis.execute(Command::Set { key, value });
+        let lrem = redis.execute(Command::Lrem {
+            key,
+            count: 0,
+            element: "value".into(),
+        });
+        assert!(lrem.is_ok());
+        assert!(eq_response(Reply::Integer(2), lrem.unwrap()));
 
-        let key = "key".to_string();
-        let ttl = Duration::from_secs(1);
-        let _expire = redis.execute(Command::Expire { key, ttl });
+        let key = "key".into();
 
-        let key = "key".to_string();
-        let persist = redis.execute(Command::Persist { key });
+        let lrange = redis.execute(Command::Lrange {
+            key,
+            begin: 0,
+            end: -1,
+        });
 
-        thread::sleep(Duration::from_secs(1));
+        let mut vector = vec!["value2".into(), "value3".into(), "value1".into()];
 
-        let key = "key".to_string();
-        let get = redis.execute(Command::Get { key });
+        vector.reverse();
 
-        assert!(eq_response(Re::String("1".to_string()), persist.unwrap()));
-        assert!(eq_response(Re::String("value".to_string()), get.unwrap()));
+        assert!(eq_response(Reply::bulk_strings(vector), lrange.unwrap()));
     }
 
     #[test]
-    fn test_persist_returns_0_on_persistent_value() {
+    fn test_lrem_invalid_key_ok() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "key".to_string();
-        let value = "value".to_string();
-        let _set = redis.execute(Command::Set { key, value });
-
-        let key = "key".to_string();
-        let persist = redis.execute(Command::Persist { key });
+        let key = "key".into();
 
-        let key: String = "key".to_string();
-        let get = redis.execute(Command::Get { key });
+        let lrem = redis.execute(Command::Lrem {
+            key,
+            count: 0,
+            element: "value".into(),
+        });
+        assert!(lrem.is_ok());
+        assert!(eq_response(Reply::Integer(0), lrem.unwrap()));
+    }
 
-        assert!(eq_response(Re::String("0".to_string()), persist.unwrap()));
-        assert!(eq_response(Re::String("value".to_string()), get.unwrap()));
+    #[allow(dead_code)]
+    fn list_of(redis: &mut Redis, key: &str) -> Response {
+        redis
+            .execute(Command::Lrange {
+                key: key.into(),
+                begin: 0,
+                end: -1,
+            })
+            .unwrap()
     }
 
     #[test]
-    fn test_persist_returns_0_on_unexisting_key() {
+    fn test_lpop_deletes_empty_list() {
         let mut redis: Redis = Redis::new_for_test();
+        let _rpush = redis.execute(Command::Rpush {
+            key: "key".into(),
+            value: vec!["a".into(), "b".into()],
+        });
 
-        let key = "key".to_string();
-        let persist = redis.execute(Command::Persist { key });
-        assert!(eq_response(Re::String("0".to_string()), persist.unwrap()));
+        let lpop = redis.execute(Command::Lpop {
+            key: "key".into(),
+            count: 5,
+        });
+
+        assert!(eq_response(
+            Reply::bulk_strings(vec!["a".into(), "b".into()]),
+            lpop.unwrap()
+        ));
+        assert!(!redis.db.contains_key(&"key".into()));
     }
 
     #[test]
-    fn test_set_and_rename() {
+    fn test_lpushx_missing_key_does_not_create_list() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "key1".to_string();
-        let value = "value1".to_string();
-        let _set = redis.execute(Command::Set { key, value });
-
-        let key_origin: String = "key1".to_string();
-        let key_destination: String = "key2".to_string();
-        let rename = redis.execute(Command::Rename {
-            key_origin,
-            key_destination,
+        let lpushx = redis.execute(Command::Lpushx {
+            key: "key".into(),
+            value: vec!["a".into()],
         });
-        assert!(rename.is_ok());
-
-        let key = "key1".to_string();
-        let get = redis.execute(Command::Get { key });
-        assert!(eq_response(Re::Nil, get.unwrap()));
 
-        let key = "key2".to_string();
-        let get = redis.execute(Command::Get { key });
-        assert!(get.is_ok());
-        assert!(eq_response(Re::String("value1".to_string()), get.unwrap()));
+        assert!(eq_response(Reply::Integer(0), lpushx.unwrap()));
+        assert!(!redis.db.contains_key(&"key".into()));
     }
 
     #[test]
-    fn test_sort_set() {
+    fn test_lrange_clips_end_to_list() {
         let mut redis: Redis = Redis::new_for_test();
+        let _rpush = redis.execute(Command::Rpush {
+            key: "key".into(),
+            value: vec!["a".into(), "b".into(), "c".into()],
+        });
 
-        let key = "key".to_string();
-        let mut values = HashSet::new();
-        values.insert("2".to_string());
-        values.insert("1".to_string());
-        let _sadd = redis.execute(Command::Sadd { key, values });
+        let lrange = redis.execute(Command::Lrange {
+            key: "key".into(),
+            begin: -2,
+            end: 10,
+        });
 
-        let key = "key".to_string();
-        let sort = redis.execute(Command::Sort { key });
         assert!(eq_response(
-            Re::List(vec!["1".to_string(), "2".to_string()]),
-            sort.unwrap(),
+            Reply::bulk_strings(vec!["b".into(), "c".into()]),
+            lrange.unwrap()
         ));
     }
 
     #[test]
-    fn test_sort_list() {
+    fn test_ltrim_keeps_range_and_deletes_empty_list() {
         let mut redis: Redis = Redis::new_for_test();
+        let _rpush = redis.execute(Command::Rpush {
+            key: "key".into(),
+            value: vec!["a".into(), "b".into(), "c".into(), "d".into()],
+        });
 
-        let key = "key".to_string();
-        let value = vec!["3".to_string(), "2".to_string()];
-        let _lpush = redis.execute(Command::Lpush { key, value });
-
-        let key = "key".to_string();
-        let sort = redis.execute(Command::Sort { key });
+        let ltrim = redis.execute(Command::Ltrim {
+            key: "key".into(),
+            begin: 1,
+            end: -2,
+        });
+        let trimmed = list_of(&mut redis, "key");
+        let empty = redis.execute(Command::Ltrim {
+            key: "key".into(),
+            begin: 5,
+            end: 10,
+        });
 
         assert!(eq_response(
-            Re::List(vec!["2".to_string(), "3".to_string()]),
-            sort.unwrap(),
+            Reply::SimpleString("OK".into()),
+            ltrim.unwrap()
         ));
+        assert!(eq_response(
+            Reply::bulk_strings(vec!["b".into(), "c".into()]),
+            trimmed
+        ));
+        assert!(eq_response(
+            Reply::SimpleString("OK".into()),
+            empty.unwrap()
+        ));
+        assert!(!redis.db.contains_key(&"key".into()));
     }
 
     #[test]
-    fn test_sort_string_returns_err() {
+    fn test_linsert_before_and_after_pivot() {
         let mut redis: Redis = Redis::new_for_test();
+        let _rpush = redis.execute(Command::Rpush {
+            key: "key".into(),
+            value: vec!["a".into(), "c".into()],
+        });
 
-        let key = "key".to_string();
-        let value = "value".to_string();
-        let _set = redis.execute(Command::Set { key, value });
+        let before = redis.execute(Command::Linsert {
+            key: "key".into(),
+            before: true,
+            pivot: "c".into(),
+            element: "b".into(),
+        });
+        let after = redis.execute(Command::Linsert {
+            key: "key".into(),
+            before: false,
+            pivot: "c".into(),
+            element: "d".into(),
+        });
+        let no_pivot = redis.execute(Command::Linsert {
+            key: "key".into(),
+            before: true,
+            pivot: "x".into(),
+            element: "y".into(),
+        });
+        let missing = redis.execute(Command::Linsert {
+            key: "missing".into(),
+            before: true,
+            pivot: "a".into(),
+            element: "b".into(),
+        });
 
-        let key = "key".to_string();
-        let sort = redis.execute(Command::Sort { key });
-        assert_eq!(
-            sort.err(),
-            Some("WRONGTYPE Operation against a key holding the wrong kind of value".to_string())
-        );
+        assert!(eq_response(Reply::Integer(3), before.unwrap()));
+        assert!(eq_response(Reply::Integer(4), after.unwrap()));
+        assert!(eq_response(Reply::Integer(-1), no_pivot.unwrap()));
+        assert!(eq_response(Reply::Integer(0), missing.unwrap()));
+        assert!(eq_response(
+            Reply::bulk_strings(vec!["a".into(), "b".into(), "c".into(), "d".into()]),
+            list_of(&mut redis, "key")
+        ));
     }
 
     #[test]
-    fn test_sort_empty_key() {
+    fn test_lpos_with_rank_count_and_maxlen() {
         let mut redis: Redis = Redis::new_for_test();
+        let _rpush = redis.execute(Command::Rpush {
+            key: "key".into(),
+            value: vec![
+                "a".into(),
+                "b".into(),
+                "c".into(),
+                "1".into(),
+                "2".into(),
+                "3".into(),
+                "c".into(),
+                "c".into(),
+            ],
+        });
+        let mut lpos = |rank, count, maxlen| {
+            redis
+                .execute(Command::Lpos {
+                    key: "key".into(),
+                    element: "c".into(),
+                    rank,
+                    count,
+                    maxlen,
+                })
+                .unwrap()
+        };
 
-        let key = "key".to_string();
-        let sort = redis.execute(Command::Sort { key });
-        assert!(eq_response(Re::List(vec![]), sort.unwrap()));
+        assert!(eq_response(Reply::Integer(2), lpos(1, None, 0)));
+        assert!(eq_response(Reply::Integer(6), lpos(2, None, 0)));
+        assert!(eq_response(Reply::Integer(7), lpos(-1, None, 0)));
+        assert!(eq_response(Reply::NullBulk, lpos(4, None, 0)));
+        assert!(eq_response(
+            Reply::Array(vec![
+                Reply::Integer(2),
+                Reply::Integer(6),
+                Reply::Integer(7)
+            ]),
+            lpos(1, Some(0), 0)
+        ));
+        assert!(eq_response(
+            Reply::Array(vec![Reply::Integer(7), Reply::Integer(6)]),
+            lpos(-1, Some(2), 0)
+        ));
+        assert!(eq_response(
+            Reply::Array(vec![Reply::Integer(2)]),
+            lpos(1, Some(0), 6)
+        ));
+        assert!(eq_response(Reply::Array(vec![]), lpos(1, Some(1), 2)));
     }
 
     #[test]
-    fn test_sort_non_numeric_value_returns_err() {
+    fn test_lmpop_pops_from_first_non_empty_list() {
         let mut redis: Redis = Redis::new_for_test();
+        let _rpush = redis.execute(Command::Rpush {
+            key: "second".into(),
+            value: vec!["a".into(), "b".into(), "c".into()],
+        });
 
-        let key = "key".to_string();
-        let value = vec!["value1".to_string(), "value2".to_string()];
-        let _lpush = redis.execute(Command::Lpush { key, value });
+        let lmpop = redis.execute(Command::Lmpop {
+            keys: vec!["first".into(), "second".into()],
+            left: false,
+            count: 2,
+        });
+        let rest = redis.execute(Command::Lmpop {
+            keys: vec!["first".into(), "second".into()],
+            left: true,
+            count: 10,
+        });
+        let empty = redis.execute(Command::Lmpop {
+            keys: vec!["first".into(), "second".into()],
+            left: true,
+            count: 1,
+        });
 
-        let key = "key".to_string();
-        let sort = redis.execute(Command::Sort { key });
-        assert_eq!(
-            sort.err(),
-            Some("ERR One or more scores can't be converted into double".to_string())
-        );
+        assert!(eq_response(
+            Reply::Array(vec![
+                Reply::BulkString("second".into()),
+                Reply::bulk_strings(vec!["c".into(), "b".into()]),
+            ]),
+            lmpop.unwrap()
+        ));
+        assert!(eq_response(
+            Reply::Array(vec![
+                Reply::BulkString("second".into()),
+                Reply::bulk_strings(vec!["a".into()]),
+            ]),
+            rest.unwrap()
+        ));
+        assert!(eq_response(Reply::NullArray, empty.unwrap()));
+        assert!(!redis.db.contains_key(&"second".into()));
     }
 
     #[test]
-    fn test_ttl_returns_neg2_on_unexisting_key() {
+    fn test_lmpop_wrong_type_returns_err() {
         let mut redis: Redis = Redis::new_for_test();
+        let _set = redis.execute(Command::Set {
+            key: "key".into(),
+            value: "value".into(),
+            param: SetParam::default(),
+        });
 
-        let key = "key".to_string();
-        let ttl = redis.execute(Command::Ttl { key });
+        let lmpop = redis.execute(Command::Lmpop {
+            keys: vec!["key".into()],
+            left: true,
+            count: 1,
+        });
 
-        assert!(eq_response(Re::String("-2".to_string()), ttl.unwrap()));
+        assert!(lmpop.is_err());
     }
 
     #[test]
-    fn test_ttl_returns_neg1_on_persistent_value() {
+    fn test_keys_ok() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "key".to_string();
-        let value = "value".to_string();
-        let _set = redis.execute(Command::Set { key, value });
-
-        let key = "key".to_string();
-        let ttl = redis.execute(Command::Ttl { key });
+        let key = "key".into();
+        let value = "value".into();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            param: SetParam::default(),
+        });
 
-        assert!(eq_response(Re::String("-1".to_string()), ttl.unwrap()));
-    }
+        let key = "key1".into();
+        let value = "value".into();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            param: SetParam::default(),
+        });
 
-    #[test]
-    fn test_ttl_returns_secs_remaining() {
-        let mut redis: Redis = Redis::new_for_test();
+        let pattern: Vec<u8> = "/*".into();
 
-        let key = "key".to_string();
-        let value = "value".to_string();
-        let _set = redis.execute(Command::Set { key, value });
+        let keys = redis.execute(Command::Keys { pattern });
 
-        let key = "key".to_string();
-        let ttl = Duration::from_secs(5);
-        let _expire = redis.execute(Command::Expire { key, ttl });
+        assert!(keys.is_ok());
 
-        let key = "key".to_string();
-        let ttl = redis.execute(Command::Ttl { key });
+        let pattern: Vec<u8> = "k**".into();
 
-        let _key: String = "key".to_string();
+        let keys = redis.execute(Command::Keys { pattern });
 
-        assert!(eq_response(Re::String("4".to_string()), ttl.unwrap()));
+        assert!(keys.is_ok());
     }
 
     #[test]
-    fn test_type_on_string() {
+    fn test_keys_uses_glob_patterns() {
         let mut redis: Redis = Redis::new_for_test();
+        for key in ["user:1", "user:22", "users", "order:1"] {
+            let _set = redis.execute(Command::Set {
+                key: key.into(),
+                value: "value".into(),
+                param: SetParam::default(),
+            });
+        }
 
-        let key = "key".to_string();
-        let value = "value".to_string();
-        let _set = redis.execute(Command::Set { key, value });
+        let mut keys_of = |pattern: &str| {
+            let mut keys = bulk_strings(redis.execute(Command::Keys {
+                pattern: pattern.into(),
+            }));
+            keys.sort();
+            keys
+        };
 
-        let key = "key".to_string();
-        let type_method = redis.execute(Command::Type { key });
-        assert!(eq_response(
-            Re::String("string".to_string()),
-            type_method.unwrap(),
-        ));
+        assert_eq!(keys_of("*").len(), 4);
+        assert_eq!(
+            keys_of("user:*"),
+            vec![b"user:1".to_vec(), b"user:22".to_vec()]
+        );
+        assert_eq!(keys_of("user:?"), vec![b"user:1".to_vec()]);
+        assert_eq!(
+            keys_of("[ou]*:1"),
+            vec![b"order:1".to_vec(), b"user:1".to_vec()]
+        );
+        assert!(keys_of("user\\*").is_empty());
     }
 
+    #[ignore]
     #[test]
-    fn test_type_on_empty_key() {
+    fn test_touch_deletes_expired_key() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "key".to_string();
-        let type_method = redis.execute(Command::Type { key });
-
-        assert!(eq_response(
-            Re::String("none".to_string()),
-            type_method.unwrap(),
-        ));
-    }
-
-    #[test]
-    fn test_type_on_list() {
-        let mut redis: Redis = Redis::new_for_test();
+        let key = "key".into();
+        let value = "value".into();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            param: SetParam::default(),
+        });
 
-        let key = "key".to_string();
-        let value = vec!["value".to_string()];
-        let _lpush = redis.execute(Command::Lpush { key, value });
+        let key = "key".into();
+        let ttl = Duration::from_secs(1);
+        let _expire = redis.execute(Command::Expire { key, ttl });
 
-        let key = "key".to_string();
-        let type_method = redis.execute(Command::Type { key });
-        assert!(eq_response(
-            Re::String("list".to_string()),
-            type_method.unwrap(),
-        ));
-    }
+        thread::sleep(Duration::from_secs(1));
 
-    #[test]
-    fn test_type_on_set() {
-        let mut redis: Redis = Redis::new_for_test();
+        let keys = vec!["key".into()];
+        let touch = redis.execute(Command::Touch { keys });
 
-        let key = "key".to_string();
-        let mut values = HashSet::new();
-        values.insert("value".to_string());
-        let _sadd = redis.execute(Command::Sadd { key, values });
+        let pattern = "*".into();
+        let keys = redis.execute(Command::Keys { pattern });
 
-        let key = "key".to_string();
-        let type_method = redis.execute(Command::Type { key });
-        assert!(eq_response(
-            Re::String("set".to_string()),
-            type_method.unwrap(),
-        ));
+        assert!(eq_response(Reply::Integer(0), touch.unwrap()));
+        assert!(eq_response(Reply::bulk_strings(Vec::new()), keys.unwrap()));
     }
 
     #[test]
-    fn test_lindex_with_key_used_err() {
+    fn test_touch_returns_number_of_keys_touched() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "key".to_string();
-        let value = "value".to_string();
-        let _set = redis.execute(Command::Set { key, value });
-
-        let key = "key".to_string();
-        let index = 1;
-        let lindex = redis.execute(Command::Lindex { key, index });
-
-        assert!(lindex.is_err());
-    }
-
-    #[test]
-    fn test_lindex_ok() {
-        let mut redis: Redis = Redis::new_for_test();
+        let key = "key1".into();
+        let value = "value".into();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            param: SetParam::default(),
+        });
 
-        let key = "key".to_string();
-        let value = vec!["value".to_string(), "value2".to_string()];
-        let _lpush = redis.execute(Command::Lpush { key, value });
+        let key = "key2".into();
+        let value = "value".into();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            param: SetParam::default(),
+        });
 
-        let key = "key".to_string();
-        let index = 0;
-        let lindex = redis.execute(Command::Lindex { key, index });
+        let keys = vec!["key1".into(), "key2".into()];
+        let touch = redis.execute(Command::Touch { keys });
 
-        assert!(lindex.is_ok());
-        assert!(eq_response(
-            Re::String("value2".to_string()),
-            lindex.unwrap(),
-        ));
+        assert!(eq_response(Reply::Integer(2), touch.unwrap()));
     }
 
     #[test]
-    fn test_lindex_negative_index_ok() {
+    fn test_set_element_and_flushdb() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "key".to_string();
-        let value = vec!["value".to_string(), "value2".to_string()];
-        let _lpush = redis.execute(Command::Lpush { key, value });
+        let value = "value".into();
+        let key = "key".into();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            param: SetParam::default(),
+        });
 
-        let key = "key".to_string();
-        let index = -1;
-        let lindex = redis.execute(Command::Lindex { key, index });
+        let key = "key".into();
+        let get = redis.execute(Command::Get { key });
+        assert!(eq_response(Reply::BulkString("value".into()), get.unwrap()));
 
-        assert!(lindex.is_ok());
-        assert!(eq_response(
-            Re::String("value".to_string()),
-            lindex.unwrap(),
-        ));
+        let flushdb = redis.execute(Command::Flushdb);
+        assert!(flushdb.is_ok());
+
+        let key = "key".into();
+        let get = redis.execute(Command::Get { key });
+        assert!(eq_response(Reply::NullBulk, get.unwrap()));
     }
 
     #[test]
-    fn test_lindex_negative_index_result_nil_ok() {
+    fn test_bgsave_then_lastsave_and_info() {
         let mut redis: Redis = Redis::new_for_test();
+        let path = "test_bgsave_then_lastsave_and_info.rdb".to_string();
+        redis.config.lock().unwrap().set_dbfilename(path.clone());
+        let _set = redis.execute(Command::Set {
+            key: "key".into(),
+            value: "value".into(),
+            param: SetParam::default(),
+        });
 
-        let key = "key".to_string();
-        let value = vec!["value".to_string(), "value2".to_string()];
-        let _lpush = redis.execute(Command::Lpush { key, value });
-
-        let key = "key".to_string();
-        let index = -3;
-        let lindex = redis.execute(Command::Lindex { key, index });
+        let bgsave = redis.execute(Command::Bgsave);
+        let _set = redis.execute(Command::Set {
+            key: "other".into(),
+            value: "value".into(),
+            param: SetParam::default(),
+        });
+        while redis.rdb.bgsave_in_progress() {
+            sleep(Duration::from_millis(10));
+        }
+        let lastsave = redis.execute(Command::Lastsave);
+        let info = redis.execute(Command::Info {
+            param: InfoParam::Persistence,
+        });
+        let mut loaded = TtlHashMap::deserialize(fs::read(&path).unwrap()).unwrap();
+        let _ = fs::remove_file(&path);
 
-        assert!(lindex.is_ok());
-        assert!(eq_response(Re::Nil, lindex.unwrap()));
+        assert!(matches!(
+            bgsave,
+            Ok(Response::Normal(Reply::SimpleString(msg))) if msg == "Background saving started"
+        ));
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+        assert!(matches!(
+            lastsave,
+            Ok(Response::Normal(Reply::Integer(time))) if now - time <= 1
+        ));
+        let info = match info {
+            Ok(Response::Normal(Reply::BulkString(info))) => String::from_utf8(info).unwrap(),
+            _ => panic!("unexpected info response"),
+        };
+        assert!(info.contains("rdb_bgsave_in_progress:0\r\n"));
+        assert!(info.contains("rdb_last_save_time:"));
+        assert!(info.contains("rdb_last_bgsave_status:ok\r\n"));
+        assert_eq!(loaded.len(), 1);
+        assert!(loaded.contains_key(&b"key".to_vec()));
     }
 
     #[test]
-    fn test_llen_key_saved_as_string_ok() {
+    fn test_commands_are_served_while_large_bgsave_runs() {
         let mut redis: Redis = Redis::new_for_test();
+        let path = "test_commands_are_served_while_large_bgsave_runs.rdb".to_string();
+        redis.config.lock().unwrap().set_dbfilename(path.clone());
+        let elements: Vec<Vec<u8>> = (0..10_000).map(|i| i.to_string().into_bytes()).collect();
+        for i in 0..100 {
+            let _rpush = redis.execute(Command::Rpush {
+                key: format!("list{}", i).into_bytes(),
+                value: elements.clone(),
+            });
+        }
 
-        let key = "key".to_string();
-        let value = "value".to_string();
-        let _set = redis.execute(Command::Set { key, value });
-
-        let key = "key".to_string();
-        let llen = redis.execute(Command::Llen { key });
-
-        assert!(llen.is_err());
+        let bgsave = redis.execute(Command::Bgsave);
+        let lpush = redis.execute(Command::Lpush {
+            key: "list0".into(),
+            value: vec!["new".into()],
+        });
+        let set = redis.execute(Command::Set {
+            key: "other".into(),
+            value: "value".into(),
+            param: SetParam::default(),
+        });
+        let served_while_saving = redis.rdb.bgsave_in_progress();
+        while redis.rdb.bgsave_in_progress() {
+            sleep(Duration::from_millis(10));
+        }
+        let mut loaded = TtlHashMap::deserialize(fs::read(&path).unwrap()).unwrap();
+        let _ = fs::remove_file(&path);
+
+        assert!(bgsave.is_ok());
+        assert!(eq_response(Reply::Integer(10_001), lpush.unwrap()));
+        assert!(set.is_ok());
+        assert!(served_while_saving);
+        assert_eq!(loaded.len(), 100);
+        assert!(!loaded.contains_key(&b"other".to_vec()));
+        assert!(matches!(
+            loaded.get(&b"list0".to_vec()),
+            Some(Re::List(list)) if list.len() == 10_000
+        ));
     }
 
     #[test]
-    fn test_llen_key_not_found_ok() {
+    fn test_save_writes_dbfilename() {
         let mut redis: Redis = Redis::new_for_test();
+        let path = "test_save_writes_dbfilename.rdb".to_string();
+        redis.config.lock().unwrap().set_dbfilename(path.clone());
+        let _set = redis.execute(Command::Set {
+            key: "key".into(),
+            value: "value".into(),
+            param: SetParam::default(),
+        });
 
-        let key = "key".to_string();
-        let llen = redis.execute(Command::Llen { key });
+        let save = redis.execute(Command::Save);
+        let mut loaded = TtlHashMap::deserialize(fs::read(&path).unwrap()).unwrap();
+        let _ = fs::remove_file(&path);
 
-        assert!(llen.is_ok());
-        assert!(eq_response(Re::String("0".to_string()), llen.unwrap()));
+        assert!(matches!(save, Ok(Response::Normal(Reply::SimpleString(ok))) if ok == "OK"));
+        assert!(loaded.contains_key(&b"key".to_vec()));
     }
 
     #[test]
-    fn test_llen_key_used_twice_ok() {
+    fn test_check_save_points_saves_only_when_rule_is_satisfied() {
         let mut redis: Redis = Redis::new_for_test();
+        let path = "test_check_save_points.rdb".to_string();
+        redis.config.lock().unwrap().set_dbfilename(path.clone());
+        let _config_set = redis.execute(Command::ConfigSet {
+            parameter: "save".to_string(),
+            value: "0 2".to_string(),
+        });
 
-        let key = "key".to_string();
-        let value = vec!["value".to_string(), "value2".to_string()];
-        let _lpush = redis.execute(Command::Lpush { key, value });
-
-        let key = "key".to_string();
-        let value = vec!["value".to_string(), "value2".to_string()];
-        let _lpush = redis.execute(Command::Lpush { key, value });
+        let _set = redis.execute(Command::Set {
+            key: "key".into(),
+            value: "value".into(),
+            param: SetParam::default(),
+        });
+        let _get = redis.execute(Command::Get { key: "key".into() });
+        let _check = redis.execute(Command::CheckSavePoints);
+        assert!(!redis.rdb.bgsave_in_progress());
+        assert!(fs::metadata(&path).is_err());
 
-        let key = "key".to_string();
-        let llen = redis.execute(Command::Llen { key });
+        let _set = redis.execute(Command::Set {
+            key: "other".into(),
+            value: "value".into(),
+            param: SetParam::default(),
+        });
+        let _check = redis.execute(Command::CheckSavePoints);
+        while redis.rdb.bgsave_in_progress() {
+            sleep(Duration::from_millis(10));
+        }
+        let saved = fs::metadata(&path).is_ok();
+        let _ = fs::remove_file(&path);
+        redis.update_dirty();
 
-        assert!(eq_response(Re::String("4".to_string()), llen.unwrap()));
+        assert!(saved);
+        assert_eq!(redis.dirty, 0);
     }
 
     #[test]
-    fn test_lpop_without_count_ok() {
+    fn test_config_set_save_invalid_err() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "key".to_string();
-        let value = vec!["value".to_string(), "value2".to_string()];
-        let _lpush = redis.execute(Command::Lpush { key, value });
-
-        let key = "key".to_string();
-        let lpop = redis.execute(Command::Lpop { key, count: 0 });
-        assert!(lpop.is_ok());
-        assert!(eq_response(Re::String("value2".to_string()), lpop.unwrap()));
+        let config_set = redis.execute(Command::ConfigSet {
+            parameter: "save".to_string(),
+            value: "900".to_string(),
+        });
 
-        let key = "key".to_string();
-        let llen = redis.execute(Command::Llen { key });
-        assert!(llen.is_ok());
-        assert!(eq_response(Re::String("1".to_string()), llen.unwrap()));
+        assert!(config_set.is_err());
+        assert_eq!(redis.config.lock().unwrap().get_save().len(), 3);
     }
 
     #[test]
-    fn test_lpop_with_count_ok() {
+    fn test_store_then_load() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "key".to_string();
-        let value = vec![
-            "value".to_string(),
-            "value2".to_string(),
-            "value3".to_string(),
-            "value4".to_string(),
-        ];
-        let _lpush = redis.execute(Command::Lpush { key, value });
+        let key1: Vec<u8> = "key1".into();
+        let value1: Vec<u8> = "value1".into();
+        let _set = redis.execute(Command::Set {
+            key: key1.clone(),
+            value: value1.clone(),
+            param: SetParam::default(),
+        });
+        let key2: Vec<u8> = "key2".into();
+        let value2: Vec<u8> = "value2".into();
+        let _set = redis.execute(Command::Set {
+            key: key2.clone(),
+            value: value2.clone(),
+            param: SetParam::default(),
+        });
+        let expire = Duration::from_secs(2);
+        let _ttl = redis.execute(Command::Expire {
+            key: key2.clone(),
+            ttl: expire,
+        });
 
-        let key = "key".to_string();
-        let lpop = redis.execute(Command::Lpop { key, count: 2 });
-        assert!(lpop.is_ok());
+        let path = "test_store_then_load.rdb".to_string();
+        let _store = redis.execute(Command::Store { path: path.clone() });
+
+        let _content = fs::read(path.clone()).unwrap();
+        let mut redis_new: Redis = Redis::new_for_test();
+        let _load = redis_new.execute(Command::Load { path });
+
+        let get = redis_new.execute(Command::Get { key: key1 });
+        assert!(eq_response(Reply::BulkString(value1), get.unwrap()));
+
+        let get = redis_new.execute(Command::Get { key: key2.clone() });
+        assert!(eq_response(Reply::BulkString(value2), get.unwrap()));
+
+        let ttl = redis_new.execute(Command::Ttl { key: key2 });
         assert!(eq_response(
-            Re::List(vec!["value4".to_string(), "value3".to_string()]),
-            lpop.unwrap(),
+            Reply::Integer((expire.as_secs() - 1) as i64),
+            ttl.unwrap(),
         ));
 
-        let key = "key".to_string();
-        let llen = redis.execute(Command::Llen { key });
-        assert!(llen.is_ok());
-        assert!(eq_response(Re::String("2".to_string()), llen.unwrap()));
+        fs::remove_file("test_store_then_load.rdb").unwrap();
     }
 
     #[test]
-    fn test_lpop_with_count_major_than_len_ok() {
+    fn test_load_corrupt_file_returns_err() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "key".to_string();
-        let value = vec![
-            "value".to_string(),
-            "value2".to_string(),
-            "value3".to_string(),
-            "value4".to_string(),
-        ];
-        let _lpush = redis.execute(Command::Lpush { key, value });
+        let path = "test_load_empy_file_returns_err.rdb".to_string();
+        let mut file = fs::File::create(path.clone()).unwrap();
 
-        let key = "key".to_string();
-        let lpop = redis.execute(Command::Lpop { key, count: 5 });
-        assert!(lpop.is_ok());
-        assert!(eq_response(
-            Re::List(vec![
-                "value4".to_string(),
-                "value3".to_string(),
-                "value2".to_string(),
-                "value".to_string()
-            ]),
-            lpop.unwrap(),
-        ));
+        let op_resizedb = 0xfb;
+        let mut store_len = TtlHashMap::length_encode(1);
+        let mut ttl_len = TtlHashMap::length_encode(0);
+        let byte_value_type = TtlHashMap::value_type_encode(&Re::String("value".into()));
+        let key = b"key".to_vec();
+        let mut key_encoded = TtlHashMap::string_encode(key.clone());
+        let op_eof = 0xff;
 
-        let key = "key".to_string();
-        let llen = redis.execute(Command::Llen { key });
-        assert!(llen.is_ok());
-        assert!(eq_response(Re::String("0".to_string()), llen.unwrap()));
+        let mut bytes = ["REDIS".as_bytes(), "0001".as_bytes()].concat();
+        bytes.push(op_resizedb);
+        bytes.append(&mut store_len);
+        bytes.append(&mut ttl_len);
+        bytes.push(byte_value_type);
+        bytes.append(&mut key_encoded);
+        bytes.push(op_eof);
 
-        let key = "key".to_string();
-        let lpop = redis.execute(Command::Lpop { key, count: 5 });
-        assert!(lpop.is_ok());
-        assert!(eq_response(Re::Nil, lpop.unwrap()));
+        let _ = file.write_all(&bytes);
+
+        let load = redis.execute(Command::Load { path });
+
+        assert!(load.is_err());
+        fs::remove_file("test_load_empy_file_returns_err.rdb").unwrap();
     }
 
     #[test]
-    fn test_lpop_with_saved_string_err() {
+    fn test_config_get_ok() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "key".to_string();
-        let value = "value".to_string();
-        let _set = redis.execute(Command::Set { key, value });
+        let config_get = redis.execute(Command::ConfigGet);
+        let conf = vec![
+            "dump.rdb".into(),
+            "log.log".into(),
+            "8080".into(),
+            "0".into(),
+            "0".into(),
+            "3600 1 300 100 60 10000".into(),
+        ];
 
-        let key = "key".to_string();
-        let lpop = redis.execute(Command::Lpop { key, count: 5 });
-        assert!(lpop.is_err());
+        assert!(eq_response(Reply::bulk_strings(conf), config_get.unwrap()));
     }
 
     #[test]
-    fn test_lrange_ok() {
+    fn test_config_set_verbose() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "key".to_string();
-        let value = vec![
-            "value1".to_string(),
-            "value2".to_string(),
-            "value3".to_string(),
-            "value4".to_string(),
-        ];
-
-        let _lpush = redis.execute(Command::Lpush { key, value });
-        let key = "key".to_string();
-        let lrange = redis.execute(Command::Lrange {
-            key,
-            begin: 0,
-            end: -1,
-        });
+        let parameter = "verbose".into();
+        let value = "1".into();
+        let _config_set = redis.execute(Command::ConfigSet { parameter, value });
 
-        assert!(lrange.is_ok());
-        assert!(eq_response(
-            Re::List(vec![
-                "value4".to_string(),
-                "value3".to_string(),
-                "value2".to_string(),
-                "value1".to_string()
-            ]),
-            lrange.unwrap(),
-        ));
+        assert_eq!("1", redis.config.lock().unwrap().get_verbose());
     }
 
     #[test]
-    fn test_lrange_ranges_incorrect_return_empty_vec_ok() {
+    fn test_config_set_dbfilename() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "key".to_string();
-        let value = vec![
-            "value1".to_string(),
-            "value2".to_string(),
-            "value3".to_string(),
-            "value4".to_string(),
-        ];
-
-        let _lpush = redis.execute(Command::Lpush { key, value });
-
-        let key = "key".to_string();
-        let lrange = redis.execute(Command::Lrange {
-            key,
-            begin: -1,
-            end: 0,
-        });
+        let parameter = "dbfilename".into();
+        let value = "new_dump.rdb".into();
+        let _config_set = redis.execute(Command::ConfigSet { parameter, value });
 
-        assert!(lrange.is_ok());
-        assert!(eq_response(Re::List(vec![]), lrange.unwrap()));
+        assert_eq!(
+            "new_dump.rdb",
+            redis.config.lock().unwrap().get_dbfilename()
+        );
     }
 
     #[test]
-    fn test_lrange_using_ranges_ok() {
+    fn test_config_set_logfile() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "key".to_string();
-        let value = vec![
-            "value1".to_string(),
-            "value2".to_string(),
-            "value3".to_string(),
-            "value4".to_string(),
-        ];
-
-        let _lpush = redis.execute(Command::Lpush { key, value });
-
-        let key = "key".to_string();
-        let lrange = redis.execute(Command::Lrange {
-            key,
-            begin: 2,
-            end: 4,
-        });
+        let parameter = "logfile".into();
+        let value = "new_log.log".into();
+        let _config_set = redis.execute(Command::ConfigSet { parameter, value });
 
-        assert!(lrange.is_ok());
-        assert!(eq_response(
-            Re::List(vec!["value2".to_string(), "value1".to_string(),]),
-            lrange.unwrap(),
-        ));
+        assert_eq!("new_log.log", redis.config.lock().unwrap().get_logfile());
     }
 
     #[test]
-    fn test_lrange_for_string_value_err() {
+    fn test_config_set_wrong_parameter() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "key".to_string();
-        let value = "value1".to_string();
-
-        let _set = redis.execute(Command::Set { key, value });
-
-        let key = "key".to_string();
-        let lrange = redis.execute(Command::Lrange {
-            key,
-            begin: 2,
-            end: 4,
-        });
+        let parameter = "timeout".into();
+        let value = "1".into();
+        let config_set = redis.execute(Command::ConfigSet { parameter, value });
 
-        assert!(lrange.is_err());
+        assert!(config_set.is_err());
+        assert_ne!(1, redis.config.lock().unwrap().get_timeout());
     }
 
     #[test]
-    fn test_lset_ok() {
+    fn test_info_connected_clients_ok() {
         let mut redis: Redis = Redis::new_for_test();
-
-        let key = "key".to_string();
-        let value = vec!["value".to_string(), "value2".to_string()];
-        let _lpush = redis.execute(Command::Lpush { key, value });
-
-        let key = "key".to_string();
-        let index = -1;
-        let element = "Nuevos".to_string();
-        let lset = redis.execute(Command::Lset {
-            key,
-            index,
-            element,
+        let info = redis.execute(Command::Info {
+            param: InfoParam::ConnectedClients,
         });
 
-        assert!(lset.is_ok());
-        assert!(eq_response(
-            Re::SimpleString("OK".to_string()),
-            lset.unwrap(),
-        ));
+        assert!(info.is_ok());
+        assert!(eq_response(Reply::BulkString("0".into()), info.unwrap()));
 
-        let key = "key".to_string();
-        let lrange = redis.execute(Command::Lrange {
-            key,
-            begin: 0,
-            end: -1,
+        let _ = redis.execute(Command::AddClient);
+        let info = redis.execute(Command::Info {
+            param: InfoParam::ConnectedClients,
         });
+        assert!(info.is_ok());
+        assert!(eq_response(Reply::BulkString("1".into()), info.unwrap()));
 
-        assert!(lrange.is_ok());
-        assert!(eq_response(
-            Re::List(vec!["value2".to_string(), "Nuevos".to_string(),]),
-            lrange.unwrap(),
-        ));
+        let _ = redis.execute(Command::RemoveClient);
+        let info = redis.execute(Command::Info {
+            param: InfoParam::ConnectedClients,
+        });
+        assert!(info.is_ok());
+        assert!(eq_response(Reply::BulkString("0".into()), info.unwrap()));
     }
 
     #[test]
-    fn test_lset_out_of_range_err() {
+    fn test_info_port_ok() {
         let mut redis: Redis = Redis::new_for_test();
-
-        let key = "key".to_string();
-        let value = vec!["value".to_string(), "value2".to_string()];
-        let _lpush = redis.execute(Command::Lpush { key, value });
-
-        let key = "key".to_string();
-        let index = -50;
-        let element = "Nuevos".to_string();
-        let lset = redis.execute(Command::Lset {
-            key,
-            index,
-            element,
+        let info = redis.execute(Command::Info {
+            param: InfoParam::Port,
         });
 
-        assert!(lset.is_err());
+        assert!(info.is_ok());
+        assert!(eq_response(Reply::BulkString("8080".into()), info.unwrap()));
     }
 
     #[test]
-    fn test_lset_out_of_range_upper_err() {
+    fn test_info_config_file_ok() {
         let mut redis: Redis = Redis::new_for_test();
-
-        let key = "key".to_string();
-        let value = vec!["value".to_string(), "value2".to_string()];
-        let _lpush = redis.execute(Command::Lpush { key, value });
-
-        let key = "key".to_string();
-        let index = 70;
-        let element = "Nuevos".to_string();
-        let lset = redis.execute(Command::Lset {
-            key,
-            index,
-            element,
+        let info = redis.execute(Command::Info {
+            param: InfoParam::ConfigFile,
         });
 
-        assert!(lset.is_err());
+        assert!(info.is_ok());
+        assert!(eq_response(
+            Reply::BulkString(redis.config.lock().unwrap().get_configfile().into_bytes()),
+            info.unwrap()
+        ));
     }
 
     #[test]
-    fn test_lset_key_not_found_err() {
+    fn test_info_process_id_ok() {
         let mut redis: Redis = Redis::new_for_test();
-
-        let key = "key".to_string();
-        let index = 70;
-        let element = "Nuevos".to_string();
-        let lset = redis.execute(Command::Lset {
-            key,
-            index,
-            element,
+        let info = redis.execute(Command::Info {
+            param: InfoParam::ProcessId,
         });
 
-        assert!(lset.is_err());
+        assert!(info.is_ok());
+        assert!(eq_response(
+            Reply::BulkString(process::id().to_string().into_bytes()),
+            info.unwrap()
+        ));
     }
 
     #[test]
-    fn test_lset_value_saved_was_string_err() {
+    fn test_hset_and_hget() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let value = "value".to_string();
-        let key = "key".to_string();
-        let _set = redis.execute(Command::Set { key, value });
+        let key = "hash".into();
+        let field_values = vec![
+            ("field1".into(), "value1".into()),
+            ("field2".into(), "value2".into()),
+        ];
+        let hset = redis.execute(Command::Hset { key, field_values });
+        assert!(eq_response(Reply::Integer(2), hset.unwrap()));
 
-        let key = "key".to_string();
-        let index = 70;
-        let element = "Nuevos".to_string();
-        let lset = redis.execute(Command::Lset {
-            key,
-            index,
-            element,
-        });
+        let key = "hash".into();
+        let field_values = vec![
+            ("field2".into(), "new_value".into()),
+            ("field3".into(), "value3".into()),
+        ];
+        let hset = redis.execute(Command::Hset { key, field_values });
+        assert!(eq_response(Reply::Integer(1), hset.unwrap()));
 
-        assert!(lset.is_err());
+        let key = "hash".into();
+        let field = "field2".into();
+        let hget = redis.execute(Command::Hget { key, field });
+        assert!(eq_response(
+            Reply::BulkString("new_value".into()),
+            hget.unwrap()
+        ));
+
+        let key = "hash".into();
+        let field = "field4".into();
+        let hget = redis.execute(Command::Hget { key, field });
+        assert!(eq_response(Reply::NullBulk, hget.unwrap()));
     }
 
     #[test]
-    fn test_rpop_without_count_ok() {
+    fn test_hset_on_string_err() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "key".to_string();
-        let value = vec!["value".to_string(), "value2".to_string()];
-        let _lpush = redis.execute(Command::Lpush { key, value });
-
-        let key = "key".to_string();
-        let rpop = redis.execute(Command::Rpop { key, count: 0 });
-        assert!(rpop.is_ok());
-        assert!(eq_response(Re::String("value".to_string()), rpop.unwrap()));
+        let key = "key".into();
+        let value = "value".into();
+        let _set = redis.execute(Command::Set {
+            key,
+            value,
+            param: SetParam::default(),
+        });
 
-        let key = "key".to_string();
-        let llen = redis.execute(Command::Llen { key });
-        assert!(llen.is_ok());
-        assert!(eq_response(Re::String("1".to_string()), llen.unwrap()));
+        let key = "key".into();
+        let field_values = vec![("field".into(), "value".into())];
+        let hset = redis.execute(Command::Hset { key, field_values });
+        assert!(hset.is_err());
     }
 
     #[test]
-    fn test_rpop_with_count_ok() {
+    fn test_hgetall() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "key".to_string();
-        let value = vec![
-            "value".to_string(),
-            "value2".to_string(),
-            "value3".to_string(),
-            "value4".to_string(),
+        let key = "hash".into();
+        let field_values = vec![
+            ("field1".into(), "value1".into()),
+            ("field2".into(), "value2".into()),
         ];
-        let _lpush = redis.execute(Command::Lpush { key, value });
+        let _hset = redis.execute(Command::Hset { key, field_values });
 
-        let key = "key".to_string();
-        let rpop = redis.execute(Command::Rpop { key, count: 2 });
-        assert!(rpop.is_ok());
-        assert!(eq_response(
-            Re::List(vec!["value".to_string(), "value2".to_string(),]),
-            rpop.unwrap(),
-        ));
+        let mut hash = HashMap::new();
+        hash.insert("field1".into(), "value1".into());
+        hash.insert("field2".into(), "value2".into());
 
-        let key = "key".to_string();
-        let llen = redis.execute(Command::Llen { key });
-        assert!(llen.is_ok());
-        assert!(eq_response(Re::String("2".to_string()), llen.unwrap()));
+        let key = "hash".into();
+        let hgetall = redis.execute(Command::Hgetall { key });
+        assert!(eq_fields(hash, hgetall.unwrap()));
+
+        let key = "missing".into();
+        let hgetall = redis.execute(Command::Hgetall { key });
+        assert!(eq_fields(HashMap::new(), hgetall.unwrap()));
     }
 
     #[test]
-    fn test_rpop_with_count_major_than_len_ok() {
+    fn test_hdel_removes_key_when_empty() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "key".to_string();
-        let value = vec![
-            "value".to_string(),
-            "value2".to_string(),
-            "value3".to_string(),
-            "value4".to_string(),
+        let key = "hash".into();
+        let field_values = vec![
+            ("field1".into(), "value1".into()),
+            ("field2".into(), "value2".into()),
         ];
-        let _lpush = redis.execute(Command::Lpush { key, value });
+        let _hset = redis.execute(Command::Hset { key, field_values });
 
-        let key = "key".to_string();
-        let rpop = redis.execute(Command::Rpop { key, count: 5 });
-        assert!(rpop.is_ok());
-        assert!(eq_response(
-            Re::List(vec![
-                "value".to_string(),
-                "value2".to_string(),
-                "value3".to_string(),
-                "value4".to_string()
-            ]),
-            rpop.unwrap(),
-        ));
+        let key = "hash".into();
+        let fields = vec!["field1".into(), "field3".into()];
+        let hdel = redis.execute(Command::Hdel { key, fields });
+        assert!(eq_response(Reply::Integer(1), hdel.unwrap()));
 
-        let key = "key".to_string();
-        let llen = redis.execute(Command::Llen { key });
-        assert!(llen.is_ok());
-        assert!(eq_response(Re::String("0".to_string()), llen.unwrap()));
+        let key = "hash".into();
+        let fields = vec!["field2".into()];
+        let hdel = redis.execute(Command::Hdel { key, fields });
+        assert!(eq_response(Reply::Integer(1), hdel.unwrap()));
 
-        let key = "key".to_string();
-        let rpop = redis.execute(Command::Rpop { key, count: 5 });
-        assert!(rpop.is_ok());
-        assert!(eq_response(Re::Nil, rpop.unwrap()));
+        let keys = vec!["hash".into()];
+        let exists = redis.execute(Command::Exists { keys });
+        assert!(eq_response(Reply::Integer(0), exists.unwrap()));
     }
 
     #[test]
-    fn test_rpop_with_saved_string_err() {
+    fn test_hincrby() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "key".to_string();
-        let value = "value".to_string();
-        let _set = redis.execute(Command::Set { key, value });
+        let key = "hash".into();
+        let field = "counter".into();
+        let hincrby = redis.execute(Command::Hincrby {
+            key,
+            field,
+            increment: 5,
+        });
+        assert!(eq_response(Reply::Integer(5), hincrby.unwrap()));
 
-        let key = "key".to_string();
-        let rpop = redis.execute(Command::Rpop { key, count: 5 });
-        assert!(rpop.is_err());
+        let key = "hash".into();
+        let field = "counter".into();
+        let hincrby = redis.execute(Command::Hincrby {
+            key,
+            field,
+            increment: -8,
+        });
+        assert!(eq_response(Reply::Integer(-3), hincrby.unwrap()));
     }
 
     #[test]
-    fn test_lpush_ok() {
+    fn test_hincrby_not_integer_and_overflow_err() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "key".to_string();
-        let value = vec!["value".to_string(), "value2".to_string()];
-        let lpush = redis.execute(Command::Lpush { key, value });
+        let key = "hash".into();
+        let field_values = vec![
+            ("text".into(), "value".into()),
+            ("max".into(), i64::MAX.to_string().into_bytes()),
+        ];
+        let _hset = redis.execute(Command::Hset { key, field_values });
 
-        assert!(lpush.is_ok());
-        assert!(eq_response(Re::String("2".to_string()), lpush.unwrap()));
+        let key = "hash".into();
+        let field = "text".into();
+        let hincrby = redis.execute(Command::Hincrby {
+            key,
+            field,
+            increment: 1,
+        });
+        assert!(hincrby.is_err());
+
+        let key = "hash".into();
+        let field = "max".into();
+        let hincrby = redis.execute(Command::Hincrby {
+            key,
+            field,
+            increment: 1,
+        });
+        assert!(hincrby.is_err());
     }
 
     #[test]
-    fn test_lpush_with_key_used_err() {
+    fn test_hincrbyfloat() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "key".to_string();
-        let value = "value".to_string();
-        let _set = redis.execute(Command::Set { key, value });
+        let key = "hash".into();
+        let field_values = vec![("text".into(), "value".into())];
+        let _hset = redis.execute(Command::Hset { key, field_values });
+        let mut hincrbyfloat = |field: &str, increment| {
+            redis.execute(Command::Hincrbyfloat {
+                key: "hash".into(),
+                field: field.into(),
+                increment,
+            })
+        };
 
-        let key = "key".to_string();
-        let value = vec!["value".to_string(), "value2".to_string()];
-        let lpush = redis.execute(Command::Lpush { key, value });
+        let new_field = hincrbyfloat("counter", 1.25);
+        let exponent = hincrbyfloat("counter", 1e2);
+        let not_float = hincrbyfloat("text", 1.0);
+        let infinite = hincrbyfloat("counter", f64::NEG_INFINITY);
 
-        assert!(lpush.is_err());
+        assert!(eq_response(
+            Reply::BulkString("1.25".into()),
+            new_field.unwrap()
+        ));
+        assert!(eq_response(
+            Reply::BulkString("101.25".into()),
+            exponent.unwrap()
+        ));
+        assert_eq!(not_float.unwrap_err(), "ERR hash value is not a float");
+        assert!(infinite.is_err());
     }
 
     #[test]
-    fn test_lpush_key_used_ok() {
+    fn test_hsetnx_hexists_hlen() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "key".to_string();
-        let value = vec!["value".to_string(), "value2".to_string()];
-        let lpush = redis.execute(Command::Lpush { key, value });
+        let key = "hash".into();
+        let field = "field".into();
+        let value = "value".into();
+        let hsetnx = redis.execute(Command::Hsetnx { key, field, value });
+        assert!(eq_response(Reply::Integer(1), hsetnx.unwrap()));
 
-        assert!(lpush.is_ok());
-        assert!(eq_response(Re::String("2".to_string()), lpush.unwrap()));
+        let key = "hash".into();
+        let field = "field".into();
+        let value = "other".into();
+        let hsetnx = redis.execute(Command::Hsetnx { key, field, value });
+        assert!(eq_response(Reply::Integer(0), hsetnx.unwrap()));
 
-        let key = "key".to_string();
-        let value = vec!["value".to_string(), "value2".to_string()];
-        let lpush = redis.execute(Command::Lpush { key, value });
+        let key = "hash".into();
+        let field = "field".into();
+        let hexists = redis.execute(Command::Hexists { key, field });
+        assert!(eq_response(Reply::Integer(1), hexists.unwrap()));
 
-        assert!(lpush.is_ok());
-        assert!(eq_response(Re::String("4".to_string()), lpush.unwrap()));
+        let key = "hash".into();
+        let hlen = redis.execute(Command::Hlen { key });
+        assert!(eq_response(Reply::Integer(1), hlen.unwrap()));
+    }
+
+    #[allow(dead_code)]
+    fn scan_page(response: Result<Response, String>) -> (u64, Vec<Vec<u8>>) {
+        if let Ok(Response::Normal(Reply::Array(frame))) = response {
+            if let [Reply::BulkString(cursor), Reply::Array(elements)] = frame.as_slice() {
+                let elements = elements.iter().filter_map(|element| match element {
+                    Reply::BulkString(bytes) => Some(bytes.clone()),
+                    _ => None,
+                });
+                return (to_text(cursor).parse().unwrap(), elements.collect());
+            }
+        }
+        panic!("scan should return a cursor and elements")
+    }
+
+    #[allow(dead_code)]
+    fn scan_until_done(redis: &mut Redis, command: impl Fn(u64) -> Command) -> Vec<Vec<u8>> {
+        let mut cursor = 0;
+        let mut elements = vec![];
+        loop {
+            let (next_cursor, mut page) = scan_page(redis.execute(command(cursor)));
+            elements.append(&mut page);
+            cursor = next_cursor;
+            if cursor == 0 {
+                return elements;
+            }
+        }
     }
 
     #[test]
-    fn test_lpush_key_used_check_ok() {
+    fn test_hscan_iterates_all_fields() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "key".to_string();
-        let value = vec!["1".to_string(), "2".to_string()];
-        let _lpush = redis.execute(Command::Lpush { key, value });
+        let key = "hash".into();
+        let field_values = vec![
+            ("a".into(), "1".into()),
+            ("b".into(), "2".into()),
+            ("c".into(), "3".into()),
+        ];
+        let _hset = redis.execute(Command::Hset { key, field_values });
 
-        let key = "key".to_string();
-        let value = vec!["3".to_string(), "4".to_string()];
-        let _lpush = redis.execute(Command::Lpush { key, value });
+        let elements = scan_until_done(&mut redis, |cursor| Command::Hscan {
+            key: "hash".into(),
+            cursor,
+            pattern: None,
+            count: 2,
+        });
+        let mut pairs: Vec<(Vec<u8>, Vec<u8>)> = elements
+            .chunks(2)
+            .map(|pair| (pair[0].clone(), pair[1].clone()))
+            .collect();
+        pairs.sort();
 
-        let key = "key".to_string();
-        let index = -1;
-        let lindex = redis.execute(Command::Lindex { key, index });
-        assert!(lindex.is_ok());
-        assert!(eq_response(Re::String("1".to_string()), lindex.unwrap()));
-        let key = "key".to_string();
-        let index = -2;
-        let lindex = redis.execute(Command::Lindex { key, index });
-        assert!(lindex.is_ok());
-        assert!(eq_response(Re::String("2".to_string()), lindex.unwrap()));
-        let key = "key".to_string();
-        let index = -3;
-        let lindex = redis.execute(Command::Lindex { key, index });
-        assert!(lindex.is_ok());
-        assert!(eq_response(Re::String("3".to_string()), lindex.unwrap()));
-        let key = "key".to_string();
-        let index = -4;
-        let lindex = redis.execute(Command::Lindex { key, index });
-        assert!(lindex.is_ok());
-        assert!(eq_response(Re::String("4".to_string()), lindex.unwrap()));
+        assert_eq!(
+            pairs,
+            vec![
+                ("a".into(), "1".into()),
+                ("b".into(), "2".into()),
+                ("c".into(), "3".into()),
+            ]
+        );
     }
 
     #[test]
-    fn test_rpush_ok() {
+    fn test_scan_returns_keys_present_during_whole_scan() {
         let mut redis: Redis = Redis::new_for_test();
+        for index in 0..30 {
+            let _set = redis.execute(Command::Set {
+                key: format!("key{}", index).into_bytes(),
+                value: "value".into(),
+                param: SetParam::default(),
+            });
+        }
 
-        let key = "key".to_string();
-        let value = vec!["value".to_string(), "value2".to_string()];
-        let rpush = redis.execute(Command::Rpush { key, value });
+        let first_page = scan_page(redis.execute(Command::Scan {
+            cursor: 0,
+            pattern: None,
+            count: 10,
+            value_type: None,
+        }));
+        for index in 30..60 {
+            let _set = redis.execute(Command::Set {
+                key: format!("key{}", index).into_bytes(),
+                value: "value".into(),
+                param: SetParam::default(),
+            });
+        }
+        let (first_cursor, first_keys): (u64, Vec<Vec<u8>>) = first_page;
+        let rest = scan_until_done(&mut redis, |cursor| Command::Scan {
+            cursor: if cursor == 0 { first_cursor } else { cursor },
+            pattern: None,
+            count: 10,
+            value_type: None,
+        });
 
-        assert!(rpush.is_ok());
-        assert!(eq_response(Re::String("2".to_string()), rpush.unwrap()));
+        let scanned: HashSet<Vec<u8>> = first_keys.into_iter().chain(rest).collect();
+        for index in 0..30 {
+            assert!(scanned.contains(&format!("key{}", index).into_bytes()));
+        }
     }
 
     #[test]
-    fn test_rpush_with_key_used_err() {
+    fn test_scan_with_match_and_type() {
         let mut redis: Redis = Redis::new_for_test();
+        let _set = redis.execute(Command::Set {
+            key: "user:1".into(),
+            value: "value".into(),
+            param: SetParam::default(),
+        });
+        let _hset = redis.execute(Command::Hset {
+            key: "user:2".into(),
+            field_values: vec![("field".into(), "value".into())],
+        });
+        let _other = redis.execute(Command::Set {
+            key: "order:1".into(),
+            value: "value".into(),
+            param: SetParam::default(),
+        });
 
-        let key = "key".to_string();
-        let value = "value".to_string();
-        let _set = redis.execute(Command::Set { key, value });
-
-        let key = "key".to_string();
-        let value = vec!["value".to_string(), "value2".to_string()];
-        let rpush = redis.execute(Command::Rpush { key, value });
+        let mut users = scan_until_done(&mut redis, |cursor| Command::Scan {
+            cursor,
+            pattern: Some("user:*".into()),
+            count: 1,
+            value_type: None,
+        });
+        users.sort();
+        let hashes = scan_until_done(&mut redis, |cursor| Command::Scan {
+            cursor,
+            pattern: None,
+            count: 10,
+            value_type: Some("hash".to_string()),
+        });
 
-        assert!(rpush.is_err());
+        assert_eq!(users, vec![b"user:1".to_vec(), b"user:2".to_vec()]);
+        assert_eq!(hashes, vec![b"user:2".to_vec()]);
     }
 
     #[test]
-    fn test_rpush_key_used_ok() {
+    fn test_sscan_iterates_all_members() {
         let mut redis: Redis = Redis::new_for_test();
+        let members: HashSet<Vec<u8>> = (0..20).map(|i| format!("m{}", i).into_bytes()).collect();
+        let _sadd = redis.execute(Command::Sadd {
+            key: "set".into(),
+            values: members.clone(),
+        });
+        let _string = redis.execute(Command::Set {
+            key: "string".into(),
+            value: "value".into(),
+            param: SetParam::default(),
+        });
 
-        let key = "key".to_string();
-        let value = vec!["value".to_string(), "value2".to_string()];
-        let rpush = redis.execute(Command::Rpush { key, value });
-
-        assert!(rpush.is_ok());
-        assert!(eq_response(Re::String("2".to_string()), rpush.unwrap()));
-
-        let key = "key".to_string();
-        let value = vec!["value".to_string(), "value2".to_string()];
-        let rpush = redis.execute(Command::Rpush { key, value });
+        let scanned = scan_until_done(&mut redis, |cursor| Command::Sscan {
+            key: "set".into(),
+            cursor,
+            pattern: None,
+            count: 3,
+        });
+        let matched = scan_until_done(&mut redis, |cursor| Command::Sscan {
+            key: "set".into(),
+            cursor,
+            pattern: Some("m1?".into()),
+            count: 3,
+        });
+        let wrong_type = redis.execute(Command::Sscan {
+            key: "string".into(),
+            cursor: 0,
+            pattern: None,
+            count: 3,
+        });
 
-        assert!(rpush.is_ok());
-        assert!(eq_response(Re::String("4".to_string()), rpush.unwrap()));
+        assert_eq!(scanned.len(), 20);
+        assert_eq!(scanned.into_iter().collect::<HashSet<Vec<u8>>>(), members);
+        assert_eq!(matched.len(), 10);
+        assert!(wrong_type.is_err());
     }
 
     #[test]
-    fn test_rpush_key_used_check_ok() {
+    fn test_type_hash() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "key".to_string();
-        let value = vec!["1".to_string(), "2".to_string()];
-        let _rpush = redis.execute(Command::Rpush { key, value });
+        let key = "hash".into();
+        let field_values = vec![("field".into(), "value".into())];
+        let _hset = redis.execute(Command::Hset { key, field_values });
 
-        let key = "key".to_string();
-        let value = vec!["3".to_string(), "4".to_string()];
-        let _rpush = redis.execute(Command::Rpush { key, value });
+        let key = "hash".into();
+        let type_method = redis.execute(Command::Type { key });
+        assert!(eq_response(
+            Reply::SimpleString("hash".into()),
+            type_method.unwrap()
+        ));
+    }
 
-        let key = "key".to_string();
-        let index = -1;
-        let lindex = redis.execute(Command::Lindex { key, index });
-        assert!(lindex.is_ok());
-        assert!(eq_response(Re::String("4".to_string()), lindex.unwrap()));
-        let key = "key".to_string();
-        let index = -2;
-        let lindex = redis.execute(Command::Lindex { key, index });
-        assert!(lindex.is_ok());
-        assert!(eq_response(Re::String("3".to_string()), lindex.unwrap()));
-        let key = "key".to_string();
-        let index = -3;
-        let lindex = redis.execute(Command::Lindex { key, index });
-        assert!(lindex.is_ok());
-        assert!(eq_response(Re::String("2".to_string()), lindex.unwrap()));
-        let key = "key".to_string();
-        let index = -4;
-        let lindex = redis.execute(Command::Lindex { key, index });
-        assert!(lindex.is_ok());
-        assert!(eq_response(Re::String("1".to_string()), lindex.unwrap()));
+    #[allow(dead_code)]
+    fn zadd(redis: &mut Redis, key: &str, members: Vec<(f64, &str)>) -> Result<Response, String> {
+        redis.execute(Command::Zadd {
+            key: key.into(),
+            members: members
+                .into_iter()
+                .map(|(score, member)| (score, member.into()))
+                .collect(),
+            nx: false,
+            xx: false,
+            gt: false,
+            lt: false,
+            ch: false,
+            incr: false,
+        })
+    }
+
+    #[allow(dead_code)]
+    fn list(elements: Vec<&str>) -> Reply {
+        Reply::bulk_strings(elements.into_iter().map(|e| e.into()))
     }
 
     #[test]
-    fn test_sadd() {
+    fn test_zadd_and_zrange_with_scores() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "key".to_string();
-        let mut values = HashSet::new();
-        values.insert("value1".to_string());
-        values.insert("value2".to_string());
-        values.insert("value3".to_string());
-        let sadd = redis.execute(Command::Sadd { key, values });
+        let zadd = zadd(&mut redis, "zset", vec![(2.0, "b"), (1.0, "a"), (3.0, "c")]);
+        assert!(eq_response(Reply::Integer(3), zadd.unwrap()));
 
-        assert!(eq_response(Re::String("3".to_string()), sadd.unwrap()));
+        let zrange = redis.execute(Command::Zrange {
+            key: "zset".into(),
+            param: ZRangeParam::Rank { start: 0, stop: -1 },
+            rev: false,
+            limit: None,
+            with_scores: true,
+        });
+        assert!(eq_response(
+            list(vec!["a", "1", "b", "2", "c", "3"]),
+            zrange.unwrap()
+        ));
     }
 
     #[test]
-    fn test_sadd_with_existing_key() {
+    fn test_scores_are_formatted_like_redis() {
         let mut redis: Redis = Redis::new_for_test();
+        let _zadd = zadd(
+            &mut redis,
+            "zset",
+            vec![(1.5e21, "big"), (0.1 + 0.2, "small")],
+        );
 
-        let key = "set".to_string();
-        let mut values = HashSet::new();
-        values.insert("value1".to_string());
-        values.insert("value2".to_string());
-        values.insert("value3".to_string());
-        let sadd = redis.execute(Command::Sadd { key, values });
-
-        assert!(eq_response(Re::String("3".to_string()), sadd.unwrap()));
-
-        let key = "set".to_string();
-        let mut values = HashSet::new();
-        values.insert("value3".to_string());
-        values.insert("value4".to_string());
+        let zscore = redis.execute(Command::Zscore {
+            key: "zset".into(),
+            member: "big".into(),
+        });
+        assert!(eq_response(
+            Reply::BulkString("1.5e+21".into()),
+            zscore.unwrap()
+        ));
 
-        let sadd2 = redis.execute(Command::Sadd { key, values });
-        assert!(eq_response(Re::String("1".to_string()), sadd2.unwrap()));
+        let zrange = redis.execute(Command::Zrange {
+            key: "zset".into(),
+            param: ZRangeParam::Rank { start: 0, stop: -1 },
+            rev: false,
+            limit: None,
+            with_scores: true,
+        });
+        assert!(eq_response(
+            list(vec!["small", "0.30000000000000004", "big", "1.5e+21"]),
+            zrange.unwrap()
+        ));
     }
 
     #[test]
-    fn test_sadd_error() {
+    fn test_zadd_options() {
         let mut redis: Redis = Redis::new_for_test();
+        let _zadd = zadd(&mut redis, "zset", vec![(5.0, "a")]);
+
+        let zadd = redis.execute(Command::Zadd {
+            key: "zset".into(),
+            members: vec![(1.0, "a".into()), (1.0, "b".into())],
+            nx: false,
+            xx: true,
+            gt: false,
+            lt: false,
+            ch: true,
+            incr: false,
+        });
+        assert!(eq_response(Reply::Integer(1), zadd.unwrap()));
+
+        let zadd = redis.execute(Command::Zadd {
+            key: "zset".into(),
+            members: vec![(0.0, "a".into())],
+            nx: false,
+            xx: false,
+            gt: true,
+            lt: false,
+            ch: true,
+            incr: false,
+        });
+        assert!(eq_response(Reply::Integer(0), zadd.unwrap()));
 
-        let key = "set".to_string();
-        let value = "value".to_string();
-        let _set = redis.execute(Command::Set { key, value });
-
-        let key = "set".to_string();
-        let mut values = HashSet::new();
-        values.insert("value1".to_string());
-        values.insert("value2".to_string());
-        values.insert("value3".to_string());
-        let sadd = redis.execute(Command::Sadd { key, values });
+        let zscore = redis.execute(Command::Zscore {
+            key: "zset".into(),
+            member: "a".into(),
+        });
+        assert!(eq_response(Reply::BulkString("1".into()), zscore.unwrap()));
 
-        assert_eq!(
-            "WRONGTYPE A hashset data type expected".to_string(),
-            sadd.err().unwrap()
-        )
+        let zcard = redis.execute(Command::Zcard { key: "zset".into() });
+        assert!(eq_response(Reply::Integer(1), zcard.unwrap()));
     }
 
     #[test]
-    fn test_scard() {
+    fn test_zincrby_and_zrank() {
         let mut redis: Redis = Redis::new_for_test();
+        let _zadd = zadd(&mut redis, "zset", vec![(1.0, "a"), (2.0, "b")]);
 
-        let key = "key".to_string();
-        let mut values = HashSet::new();
-        values.insert("value1".to_string());
-        values.insert("value2".to_string());
-        values.insert("value3".to_string());
-        let _sadd = redis.execute(Command::Sadd { key, values });
+        let zincrby = redis.execute(Command::Zincrby {
+            key: "zset".into(),
+            increment: 2.5,
+            member: "a".into(),
+        });
+        assert!(eq_response(
+            Reply::BulkString("3.5".into()),
+            zincrby.unwrap()
+        ));
 
-        let key = "key".to_string();
-        let scard = redis.execute(Command::Scard { key });
+        let zrank = redis.execute(Command::Zrank {
+            key: "zset".into(),
+            member: "a".into(),
+        });
+        assert!(eq_response(Reply::Integer(1), zrank.unwrap()));
+
+        let zrevrank = redis.execute(Command::Zrevrank {
+            key: "zset".into(),
+            member: "a".into(),
+        });
+        assert!(eq_response(Reply::Integer(0), zrevrank.unwrap()));
 
-        assert!(eq_response(Re::String("3".to_string()), scard.unwrap()));
+        let zrank = redis.execute(Command::Zrank {
+            key: "zset".into(),
+            member: "c".into(),
+        });
+        assert!(eq_response(Reply::NullBulk, zrank.unwrap()));
     }
 
     #[test]
-    fn test_scard_error() {
+    fn test_zrange_by_score_rev_with_limit() {
         let mut redis: Redis = Redis::new_for_test();
+        let _zadd = zadd(
+            &mut redis,
+            "zset",
+            vec![(1.0, "a"), (2.0, "b"), (3.0, "c"), (4.0, "d")],
+        );
 
-        let key = "set".to_string();
-        let value = "value".to_string();
-        let _set = redis.execute(Command::Set { key, value });
-
-        let key = "set".to_string();
-        let scard = redis.execute(Command::Scard { key });
-
-        assert_eq!(
-            "WRONGTYPE A hashset data type expected".to_string(),
-            scard.err().unwrap()
-        )
+        let zrange = redis.execute(Command::Zrange {
+            key: "zset".into(),
+            param: ZRangeParam::Score {
+                min: ScoreBound::Exclusive(1.0),
+                max: ScoreBound::Inclusive(f64::INFINITY),
+            },
+            rev: true,
+            limit: Some((1, 2)),
+            with_scores: false,
+        });
+        assert!(eq_response(list(vec!["c", "b"]), zrange.unwrap()));
     }
 
     #[test]
-    fn test_sismember() {
+    fn test_zrange_by_lex() {
         let mut redis: Redis = Redis::new_for_test();
+        let _zadd = zadd(
+            &mut redis,
+            "zset",
+            vec![(0.0, "apple"), (0.0, "banana"), (0.0, "cherry")],
+        );
 
-        let key = "key".to_string();
-        let mut values = HashSet::new();
-        values.insert("value1".to_string());
-        values.insert("value2".to_string());
-        values.insert("value3".to_string());
-        let _sadd = redis.execute(Command::Sadd { key, values });
-
-        let key = "key".to_string();
-        let value = "value1".to_string();
-        let sismember = redis.execute(Command::Sismember { key, value });
-
-        assert!(eq_response(Re::String("1".to_string()), sismember.unwrap()));
-
-        let key = "key".to_string();
-        let value = "value".to_string();
-        let sismember = redis.execute(Command::Sismember { key, value });
-
-        assert!(eq_response(Re::String("0".to_string()), sismember.unwrap()));
+        let zrange = redis.execute(Command::Zrange {
+            key: "zset".into(),
+            param: ZRangeParam::Lex {
+                min: LexBound::Inclusive("b".into()),
+                max: LexBound::Max,
+            },
+            rev: false,
+            limit: None,
+            with_scores: false,
+        });
+        assert!(eq_response(list(vec!["banana", "cherry"]), zrange.unwrap()));
     }
 
     #[test]
-    fn test_sismember_error() {
+    fn test_zpopmin_and_zpopmax_remove_key_when_empty() {
         let mut redis: Redis = Redis::new_for_test();
+        let _zadd = zadd(&mut redis, "zset", vec![(1.0, "a"), (2.0, "b"), (3.0, "c")]);
 
-        let key = "key".to_string();
-        let mut values = HashSet::new();
-        values.insert("value1".to_string());
-        values.insert("value2".to_string());
-        values.insert("value3".to_string());
-        let _sadd = redis.execute(Command::Sadd { key, values });
-
-        let key = "key1".to_string();
-        let value = "value1".to_string();
-        let sismember = redis.execute(Command::Sismember { key, value });
-
-        assert_eq!(
-            "The key doesn't exist".to_string(),
-            sismember.err().unwrap()
-        );
+        let zpopmax = redis.execute(Command::Zpopmax {
+            key: "zset".into(),
+            count: 1,
+        });
+        assert!(eq_response(list(vec!["c", "3"]), zpopmax.unwrap()));
 
-        let key = "set".to_string();
-        let value = "value".to_string();
-        let _set = redis.execute(Command::Set { key, value });
+        let zpopmin = redis.execute(Command::Zpopmin {
+            key: "zset".into(),
+            count: 5,
+        });
+        assert!(eq_response(
+            list(vec!["a", "1", "b", "2"]),
+            zpopmin.unwrap()
+        ));
 
-        let key = "set".to_string();
-        let value = "value".to_string();
-        let sismember = redis.execute(Command::Sismember { key, value });
+        let keys = vec!["zset".into()];
+        let exists = redis.execute(Command::Exists { keys });
+        assert!(eq_response(Reply::Integer(0), exists.unwrap()));
+    }
 
-        assert_eq!(
-            "WRONGTYPE A hashset data type expected".to_string(),
-            sismember.err().unwrap()
-        );
+    #[test]
+    fn test_zunionstore_with_weights() {
+        let mut redis: Redis = Redis::new_for_test();
+        let _zadd = zadd(&mut redis, "zset1", vec![(1.0, "a"), (2.0, "b")]);
+        let _zadd = zadd(&mut redis, "zset2", vec![(3.0, "b"), (4.0, "c")]);
+
+        let zunionstore = redis.execute(Command::Zunionstore {
+            destination: "out".into(),
+            keys: vec!["zset1".into(), "zset2".into()],
+            weights: vec![1.0, 2.0],
+            aggregate: AggregateParam::Sum,
+        });
+        assert!(eq_response(Reply::Integer(3), zunionstore.unwrap()));
+
+        let zrange = redis.execute(Command::Zrange {
+            key: "out".into(),
+            param: ZRangeParam::Rank { start: 0, stop: -1 },
+            rev: false,
+            limit: None,
+            with_scores: true,
+        });
+        assert!(eq_response(
+            list(vec!["a", "1", "b", "8", "c", "8"]),
+            zrange.unwrap()
+        ));
     }
 
     #[test]
-    fn test_srem() {
+    fn test_zinterstore_with_aggregate_max() {
         let mut redis: Redis = Redis::new_for_test();
+        let _zadd = zadd(&mut redis, "zset1", vec![(1.0, "a"), (5.0, "b")]);
+        let _zadd = zadd(&mut redis, "zset2", vec![(3.0, "b"), (4.0, "c")]);
+
+        let zinterstore = redis.execute(Command::Zinterstore {
+            destination: "out".into(),
+            keys: vec!["zset1".into(), "zset2".into()],
+            weights: vec![1.0, 1.0],
+            aggregate: AggregateParam::Max,
+        });
+        assert!(eq_response(Reply::Integer(1), zinterstore.unwrap()));
 
-        let key = "key".to_string();
-        let mut values = HashSet::new();
-        values.insert("value1".to_string());
-        values.insert("value2".to_string());
-        values.insert("value3".to_string());
-        let _sadd = redis.execute(Command::Sadd { key, values });
+        let zscore = redis.execute(Command::Zscore {
+            key: "out".into(),
+            member: "b".into(),
+        });
+        assert!(eq_response(Reply::BulkString("5".into()), zscore.unwrap()));
+    }
 
-        let key = "key".to_string();
-        let mut values = HashSet::new();
-        values.insert("value1".to_string());
-        let srem = redis.execute(Command::Srem { key, values });
+    #[test]
+    fn test_zadd_on_list_err() {
+        let mut redis: Redis = Redis::new_for_test();
 
-        assert!(eq_response(Re::String("1".to_string()), srem.unwrap()));
+        let key = "list".into();
+        let value = vec!["value".into()];
+        let _lpush = redis.execute(Command::Lpush { key, value });
 
-        let key = "key_inexistente".to_string();
-        let mut values = HashSet::new();
-        values.insert("value2".to_string());
-        let srem = redis.execute(Command::Srem { key, values });
+        let zadd_list = zadd(&mut redis, "list", vec![(1.0, "a")]);
+        assert!(zadd_list.is_err());
 
-        assert!(eq_response(Re::String("0".to_string()), srem.unwrap()));
+        let key = "zset".into();
+        let _zadd = zadd(&mut redis, "zset", vec![(1.0, "a")]);
+        let type_method = redis.execute(Command::Type { key });
+        assert!(eq_response(
+            Reply::SimpleString("zset".into()),
+            type_method.unwrap()
+        ));
     }
 
     #[test]
-    fn test_srem_value_two_times() {
+    fn test_exec_runs_queued_commands() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "key".to_string();
-        let mut values = HashSet::new();
-        values.insert("value1".to_string());
-        values.insert("value2".to_string());
-        values.insert("value3".to_string());
-        let _sadd = redis.execute(Command::Sadd { key, values });
-
-        let key = "key".to_string();
-        let mut values = HashSet::new();
-        values.insert("value1".to_string());
-        let srem = redis.execute(Command::Srem { key, values });
-
-        assert!(eq_response(Re::String("1".to_string()), srem.unwrap()));
-
-        let key = "key".to_string();
-        let mut values = HashSet::new();
-        values.insert("value1".to_string());
-        let srem = redis.execute(Command::Srem { key, values });
+        let commands = vec![
+            Command::Set {
+                key: "key".into(),
+                value: "value".into(),
+                param: SetParam::default(),
+            },
+            Command::Get { key: "key".into() },
+            Command::Lpush {
+                key: "key".into(),
+                value: vec!["value".into()],
+            },
+        ];
+        let exec = redis.execute(Command::Exec {
+            commands: Some(commands),
+            client_id: "client".into(),
+        });
 
-        assert!(eq_response(Re::String("0".to_string()), srem.unwrap()));
+        match exec.unwrap() {
+            Response::Transaction(responses) => {
+                assert_eq!(responses.len(), 3);
+                assert!(
+                    matches!(&responses[1], Response::Normal(Reply::BulkString(value)) if value == b"value")
+                );
+                assert!(matches!(&responses[2], Response::Error(_)));
+            }
+            _ => panic!("EXEC must reply with a transaction response"),
+        }
     }
 
     #[test]
-    fn test_srem_error() {
+    fn test_exec_aborted_by_queue_errors() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "set".to_string();
-        let value = "value".to_string();
-        let _set = redis.execute(Command::Set { key, value });
-
-        let key = "set".to_string();
-        let mut values = HashSet::new();
-        values.insert("value1".to_string());
-        let srem = redis.execute(Command::Srem { key, values });
-
-        assert_eq!(
-            "WRONGTYPE A hashset data type expected".to_string(),
-            srem.err().unwrap()
-        );
+        let exec = redis.execute(Command::Exec {
+            commands: None,
+            client_id: "client".into(),
+        });
+        assert!(exec.unwrap_err().starts_with("EXECABORT"));
     }
 
     #[test]
-    fn test_smembers() {
+    fn test_exec_fails_when_watched_key_changes() {
         let mut redis: Redis = Redis::new_for_test();
+        let client_id = "client".to_string();
 
-        let key = "key".to_string();
-        let mut values = HashSet::new();
-        values.insert("value1".to_string());
-        values.insert("value2".to_string());
-        values.insert("value3".to_string());
-        let _sadd = redis.execute(Command::Sadd { key, values });
+        let _watch = redis.execute(Command::Watch {
+            keys: vec!["key".into()],
+            client_id: client_id.clone(),
+        });
+        let _set = redis.execute(Command::Set {
+            key: "key".into(),
+            value: "other".into(),
+            param: SetParam::default(),
+        });
 
-        let key = "key".to_string();
-        let mut values = HashSet::new();
-        values.insert("value1".to_string());
-        values.insert("value2".to_string());
-        values.insert("value3".to_string());
-        let smembers = redis.execute(Command::Smembers { key });
+        let exec = redis.execute(Command::Exec {
+            commands: Some(vec![Command::Ping]),
+            client_id: client_id.clone(),
+        });
+        assert!(eq_response(Reply::NullArray, exec.unwrap()));
 
-        assert!(eq_response(Re::Set(values), smembers.unwrap()));
+        let exec = redis.execute(Command::Exec {
+            commands: Some(vec![Command::Ping]),
+            client_id,
+        });
+        assert!(matches!(exec.unwrap(), Response::Transaction(_)));
     }
 
     #[test]
-    fn test_lpushx_not_pre_save_return_0() {
+    fn test_exec_runs_when_watched_key_only_read() {
         let mut redis: Redis = Redis::new_for_test();
+        let client_id = "client".to_string();
 
-        let key = "key".to_string();
-        let value = vec!["value".to_string(), "value2".to_string()];
-        let lpushx = redis.execute(Command::Lpushx { key, value });
+        let key = "key".into();
+        let value = vec!["value".into()];
+        let _rpush = redis.execute(Command::Rpush { key, value });
+        let _watch = redis.execute(Command::Watch {
+            keys: vec!["key".into()],
+            client_id: client_id.clone(),
+        });
+        let key = "key".into();
+        let _llen = redis.execute(Command::Llen { key });
 
-        assert!(lpushx.is_ok());
-        assert!(eq_response(Re::String("0".to_string()), lpushx.unwrap()));
+        let exec = redis.execute(Command::Exec {
+            commands: Some(vec![Command::Ping]),
+            client_id,
+        });
+        assert!(matches!(exec.unwrap(), Response::Transaction(_)));
     }
 
     #[test]
-    fn test_lpushx_with_key_used_with_string_err() {
+    fn test_exec_runs_after_reads_and_no_op_writes() {
         let mut redis: Redis = Redis::new_for_test();
+        let client_id = "client".to_string();
 
-        let key = "key".to_string();
-        let value = "value".to_string();
-        let _set = redis.execute(Command::Set { key, value });
-
-        let key = "key".to_string();
-        let value = vec!["value".to_string(), "value2".to_string()];
-        let lpushx = redis.execute(Command::Lpushx { key, value });
+        let _rpush = redis.execute(Command::Rpush {
+            key: "list".into(),
+            value: vec!["value".into()],
+        });
+        let _hset = redis.execute(Command::Hset {
+            key: "hash".into(),
+            field_values: vec![("field".into(), "value".into())],
+        });
+        sadd_members(&mut redis, "set", &["member"]);
+        let _watch = redis.execute(Command::Watch {
+            keys: vec!["list".into(), "hash".into(), "set".into()],
+            client_id: client_id.clone(),
+        });
+        let _lrange = redis.execute(Command::Lrange {
+            key: "list".into(),
+            begin: 0,
+            end: -1,
+        });
+        let _lpos = redis.execute(Command::Lpos {
+            key: "list".into(),
+            element: "value".into(),
+            rank: 1,
+            count: None,
+            maxlen: 0,
+        });
+        let _lrem = redis.execute(Command::Lrem {
+            key: "list".into(),
+            count: 0,
+            element: "missing".into(),
+        });
+        let _hsetnx = redis.execute(Command::Hsetnx {
+            key: "hash".into(),
+            field: "field".into(),
+            value: "other".into(),
+        });
+        let _hdel = redis.execute(Command::Hdel {
+            key: "hash".into(),
+            fields: vec!["missing".into()],
+        });
+        sadd_members(&mut redis, "set", &["member"]);
 
-        assert!(lpushx.is_err());
+        let exec = redis.execute(Command::Exec {
+            commands: Some(vec![Command::Ping]),
+            client_id,
+        });
+        assert!(matches!(exec.unwrap(), Response::Transaction(_)));
     }
 
     #[test]
-    fn test_lpushx_after_lpush_ok() {
+    fn test_exec_fails_when_watched_collection_changes_in_place() {
         let mut redis: Redis = Redis::new_for_test();
+        let writes = vec![
+            Command::Rpush {
+                key: "list".into(),
+                value: vec!["other".into()],
+            },
+            Command::Lset {
+                key: "list".into(),
+                index: 0,
+                element: "other".into(),
+            },
+            Command::Hset {
+                key: "hash".into(),
+                field_values: vec![("field".into(), "other".into())],
+            },
+            Command::Hdel {
+                key: "hash".into(),
+                fields: vec!["field".into()],
+            },
+        ];
 
-        let key = "key".to_string();
-        let value = vec!["value".to_string(), "value2".to_string()];
-        let lpush = redis.execute(Command::Lpush { key, value });
-
-        assert!(lpush.is_ok());
-        assert!(eq_response(Re::String("2".to_string()), lpush.unwrap()));
-
-        let key = "key".to_string();
-        let value = vec!["value".to_string(), "value2".to_string()];
-        let lpush = redis.execute(Command::Lpushx { key, value });
-
-        assert!(lpush.is_ok());
-        assert!(eq_response(Re::String("4".to_string()), lpush.unwrap()));
+        for write in writes {
+            let _rpush = redis.execute(Command::Rpush {
+                key: "list".into(),
+                value: vec!["value".into()],
+            });
+            let _hset = redis.execute(Command::Hset {
+                key: "hash".into(),
+                field_values: vec![
+                    ("field".into(), "value".into()),
+                    ("other".into(), "value".into()),
+                ],
+            });
+            let _watch = redis.execute(Command::Watch {
+                keys: vec!["list".into(), "hash".into()],
+                client_id: "client".into(),
+            });
+            let _write = redis.execute(write);
+
+            let exec = redis.execute(Command::Exec {
+                commands: Some(vec![Command::Ping]),
+                client_id: "client".into(),
+            });
+            assert!(eq_response(Reply::NullArray, exec.unwrap()));
+        }
     }
 
     #[test]
-    fn test_rpushx_not_pre_save_return_0() {
+    fn test_unwatch_forgets_watched_keys() {
         let mut redis: Redis = Redis::new_for_test();
+        let client_id = "client".to_string();
 
-        let key = "key".to_string();
-        let value = vec!["value".to_string(), "value2".to_string()];
-        let rpushx = redis.execute(Command::Rpushx { key, value });
+        let _watch = redis.execute(Command::Watch {
+            keys: vec!["key".into()],
+            client_id: client_id.clone(),
+        });
+        let _unwatch = redis.execute(Command::Unwatch {
+            client_id: client_id.clone(),
+        });
+        let _flushdb = redis.execute(Command::Flushdb);
 
-        assert!(rpushx.is_ok());
-        assert!(eq_response(Re::String("0".to_string()), rpushx.unwrap()));
+        let exec = redis.execute(Command::Exec {
+            commands: Some(vec![Command::Ping]),
+            client_id,
+        });
+        assert!(matches!(exec.unwrap(), Response::Transaction(_)));
     }
 
     #[test]
-    fn test_rpushx_with_key_used_with_string_err() {
+    fn test_versions_of_deleted_keys_are_kept_only_while_watched() {
         let mut redis: Redis = Redis::new_for_test();
+        let client_id = "client".to_string();
 
-        let key = "key".to_string();
-        let value = "value".to_string();
-        let _set = redis.execute(Command::Set { key, value });
-
-        let key = "key".to_string();
-        let value = vec!["value".to_string(), "value2".to_string()];
-        let rpushx = redis.execute(Command::Rpushx { key, value });
+        let _watch = redis.execute(Command::Watch {
+            keys: vec!["watched".into()],
+            client_id: client_id.clone(),
+        });
+        for key in &["watched", "other"] {
+            let _set = redis.execute(Command::Set {
+                key: key.to_string().into_bytes(),
+                value: "value".into(),
+                param: SetParam::default(),
+            });
+            let _del = redis.execute(Command::Del {
+                keys: vec![key.to_string().into_bytes()],
+            });
+        }
+        assert!(redis.db.get_version(&b"watched".to_vec()) > 0);
+        assert_eq!(redis.db.get_version(&b"other".to_vec()), 0);
 
-        assert!(rpushx.is_err());
+        let exec = redis.execute(Command::Exec {
+            commands: Some(vec![Command::Ping]),
+            client_id,
+        });
+        assert!(eq_response(Reply::NullArray, exec.unwrap()));
+        assert_eq!(redis.db.get_version(&b"watched".to_vec()), 0);
     }
 
     #[test]
-    fn test_rpushx_after_rpush_ok() {
+    fn test_set_and_get_invalid_utf8() {
         let mut redis: Redis = Redis::new_for_test();
+        let key = vec![0xff, 0x00, 0xfe];
+        let value = vec![0xc3, 0x28, 0x80, b'\r', b'\n'];
 
-        let key = "key".to_string();
-        let value = vec!["value".to_string(), "value2".to_string()];
-        let rpushx = redis.execute(Command::Rpush { key, value });
-
-        assert!(rpushx.is_ok());
-        assert!(eq_response(Re::String("2".to_string()), rpushx.unwrap()));
-
-        let key = "key".to_string();
-        let value = vec!["value".to_string(), "value2".to_string()];
-        let rpushx = redis.execute(Command::Rpushx { key, value });
+        let _set = redis.execute(Command::Set {
+            key: key.clone(),
+            value: value.clone(),
+            param: SetParam::default(),
+        });
 
-        assert!(rpushx.is_ok());
-        assert!(eq_response(Re::String("4".to_string()), rpushx.unwrap()));
+        let get = redis.execute(Command::Get { key });
+        assert!(eq_response(Reply::BulkString(value), get.unwrap()));
     }
 
     #[test]
-    fn test_rpush_and_check_elements_ok() {
+    fn test_rpush_and_lrange_invalid_utf8() {
         let mut redis: Redis = Redis::new_for_test();
+        let value = vec![vec![0xff], vec![0xe2, 0x82], vec![0x00]];
 
-        let key = "key".to_string();
-        let value = vec![
-            "1".to_string(),
-            "2".to_string(),
-            "3".to_string(),
-            "4".to_string(),
-        ];
-        let rpushx = redis.execute(Command::Rpush { key, value });
-
-        assert!(rpushx.is_ok());
-        assert!(eq_response(Re::String("4".to_string()), rpushx.unwrap()));
+        let _rpush = redis.execute(Command::Rpush {
+            key: vec![0x80],
+            value: value.clone(),
+        });
 
-        let key = "key".to_string();
-        let value = vec![
-            "1".to_string(),
-            "2".to_string(),
-            "3".to_string(),
-            "4".to_string(),
-        ];
-        let rpushx = redis.execute(Command::Lrange {
-            key,
+        let lrange = redis.execute(Command::Lrange {
+            key: vec![0x80],
             begin: 0,
             end: -1,
         });
-
-        assert!(rpushx.is_ok());
-        assert!(eq_response(Re::List(value), rpushx.unwrap()));
+        assert!(eq_response(Reply::bulk_strings(value), lrange.unwrap()));
     }
 
     #[test]
-    fn test_rpush_rpushx_and_check_elements_ok() {
+    fn test_sadd_and_smembers_invalid_utf8() {
         let mut redis: Redis = Redis::new_for_test();
+        let values: HashSet<Vec<u8>> = vec![vec![0xfa], vec![0xfb, 0xfc]].into_iter().collect();
 
-        let key = "key".to_string();
-        let value = vec!["1".to_string(), "2".to_string()];
-        let rpush = redis.execute(Command::Rpush { key, value });
+        let _sadd = redis.execute(Command::Sadd {
+            key: vec![0xff],
+            values: values.clone(),
+        });
 
-        assert!(rpush.is_ok());
-        assert!(eq_response(Re::String("2".to_string()), rpush.unwrap()));
+        let smembers = redis.execute(Command::Smembers { key: vec![0xff] });
+        assert!(eq_members(values, smembers.unwrap()));
+    }
 
-        let key = "key".to_string();
-        let value = vec!["3".to_string(), "4".to_string()];
-        let rpushx = redis.execute(Command::Rpushx { key, value });
+    #[test]
+    fn test_load_aof_replays_write_commands() {
+        let path = "test_load_aof_replays_write_commands.aof".to_string();
+        let _ = fs::remove_file(&path);
 
-        assert!(rpushx.is_ok());
-        assert!(eq_response(Re::String("4".to_string()), rpushx.unwrap()));
+        let mut redis: Redis = Redis::new_for_test();
+        let _load = redis.execute(Command::LoadAof { path: path.clone() });
+        let _set = redis.execute(Command::Set {
+            key: "key".into(),
+            value: "1".into(),
+            param: SetParam::default(),
+        });
+        let _incrby = redis.execute(Command::Incrby {
+            key: "key".into(),
+            increment: 2,
+        });
+        let _expire = redis.execute(Command::Expire {
+            key: "key".into(),
+            ttl: Duration::from_secs(100),
+        });
+        let _rpush = redis.execute(Command::Rpush {
+            key: "list".into(),
+            value: vec!["a".into(), "b".into()],
+        });
+        let _get = redis.execute(Command::Get { key: "key".into() });
+        let _lpush_err = redis.execute(Command::Lpush {
+            key: "key".into(),
+            value: vec!["a".into()],
+        });
 
-        let key = "key".to_string();
-        let rpushx = redis.execute(Command::Lrange {
-            key,
+        let mut redis_new: Redis = Redis::new_for_test();
+        let _load = redis_new.execute(Command::LoadAof { path: path.clone() });
+        let content = fs::read(&path).unwrap();
+        let _ = fs::remove_file(&path);
+
+        let get = redis_new.execute(Command::Get { key: "key".into() });
+        assert!(eq_response(Reply::BulkString("3".into()), get.unwrap()));
+        let ttl = redis_new.execute(Command::Ttl { key: "key".into() });
+        assert!(matches!(ttl, Ok(Response::Normal(Reply::Integer(ttl))) if ttl > 90));
+        let lrange = redis_new.execute(Command::Lrange {
+            key: "list".into(),
             begin: 0,
             end: -1,
         });
-
-        assert!(rpushx.is_ok());
         assert!(eq_response(
-            Re::List(vec![
-                "1".to_string(),
-                "2".to_string(),
-                "3".to_string(),
-                "4".to_string()
-            ]),
-            rpushx.unwrap(),
+            Reply::bulk_strings(vec!["a".into(), "b".into()]),
+            lrange.unwrap()
         ));
+        assert!(!String::from_utf8_lossy(&content).contains("get"));
+        assert!(!String::from_utf8_lossy(&content).contains("lpush"));
     }
 
     #[test]
-    fn test_lrem_ok() {
+    fn test_exec_is_logged_in_aof_between_multi_and_exec() {
+        let path = "test_exec_is_logged_in_aof_between_multi_and_exec.aof".to_string();
+        let _ = fs::remove_file(&path);
+
         let mut redis: Redis = Redis::new_for_test();
+        let _load = redis.execute(Command::LoadAof { path: path.clone() });
+        let _exec = redis.execute(Command::Exec {
+            commands: Some(vec![
+                Command::Set {
+                    key: "key".into(),
+                    value: "1".into(),
+                    param: SetParam::default(),
+                },
+                Command::Get { key: "key".into() },
+                Command::Incrby {
+                    key: "key".into(),
+                    increment: 2,
+                },
+            ]),
+            client_id: "client".to_string(),
+        });
+        let _exec_read_only = redis.execute(Command::Exec {
+            commands: Some(vec![Command::Get { key: "key".into() }]),
+            client_id: "client".to_string(),
+        });
+        let commands = Aof::read(&path).unwrap();
+        assert_eq!(
+            commands,
+            vec![
+                vec![b"select".to_vec(), b"0".to_vec()],
+                vec![b"multi".to_vec()],
+                vec![b"set".to_vec(), b"key".to_vec(), b"1".to_vec()],
+                vec![b"incrby".to_vec(), b"key".to_vec(), b"2".to_vec()],
+                vec![b"exec".to_vec()],
+            ]
+        );
 
-        let key = "key".to_string();
-        let value = vec![
-            "value".to_string(),
-            "value1".to_string(),
-            "value2".to_string(),
-            "value".to_string(),
-            "value".to_string(),
-        ];
-        let _lpush = redis.execute(Command::Lpush { key, value });
+        // Una transacción a la que le falta el EXEC, como si el servidor se hubiera caído al
+        // escribirla, se descarta.
+        let mut aof = Aof::open(path.clone(), AppendFsync::Always).unwrap();
+        aof.append(0, vec![b"multi".to_vec()]).unwrap();
+        aof.append(0, vec![b"set".to_vec(), b"key".to_vec(), b"10".to_vec()])
+            .unwrap();
+        let mut redis_new: Redis = Redis::new_for_test();
+        let _load = redis_new.execute(Command::LoadAof { path: path.clone() });
+        let _ = fs::remove_file(&path);
 
-        let key = "key".to_string();
+        let get = redis_new.execute(Command::Get { key: "key".into() });
+        assert!(eq_response(Reply::BulkString("3".into()), get.unwrap()));
+    }
 
-        let lrem = redis.execute(Command::Lrem {
-            key,
-            count: 2,
-            element: "value".to_string(),
+    #[test]
+    fn test_bgrewriteaof_compacts_log() {
+        let path = "test_bgrewriteaof_compacts_log.aof".to_string();
+        let _ = fs::remove_file(&path);
+
+        let mut redis: Redis = Redis::new_for_test();
+        let _load = redis.execute(Command::LoadAof { path: path.clone() });
+        for _ in 0..3 {
+            let _incrby = redis.execute(Command::Incrby {
+                key: "key".into(),
+                increment: 1,
+            });
+        }
+        let _set = redis.execute(Command::Set {
+            key: "deleted".into(),
+            value: "value".into(),
+            param: SetParam::default(),
+        });
+        let _del = redis.execute(Command::Del {
+            keys: vec!["deleted".into()],
         });
-        assert!(lrem.is_ok());
-        assert!(eq_response(Re::String("2".to_string()), lrem.unwrap()));
 
-        let key = "key".to_string();
+        let rewrite = redis.execute(Command::Bgrewriteaof);
+        assert!(rewrite.is_ok());
+        while redis.aof_rewrite_in_progress() {
+            sleep(Duration::from_millis(10));
+        }
+        let commands = Aof::read(&path).unwrap();
+        assert_eq!(
+            commands,
+            vec![vec![b"set".to_vec(), b"key".to_vec(), b"3".to_vec()]]
+        );
 
-        let lrange = redis.execute(Command::Lrange {
-            key,
-            begin: 0,
-            end: -1,
+        let _set = redis.execute(Command::Set {
+            key: "other".into(),
+            value: "value".into(),
+            param: SetParam::default(),
         });
+        let mut redis_new: Redis = Redis::new_for_test();
+        let _load = redis_new.execute(Command::LoadAof { path: path.clone() });
+        let _ = fs::remove_file(&path);
 
-        let mut vector = vec![
-            "value1".to_string(),
-            "value2".to_string(),
-            "value".to_string(),
-        ];
-        vector.reverse();
-        assert!(eq_response(Re::List(vector), lrange.unwrap()));
+        let dbsize = redis_new.execute(Command::Dbsize);
+        assert!(eq_response(Reply::Integer(2), dbsize.unwrap()));
     }
 
     #[test]
-    fn test_lrem_reverse_ok() {
+    fn test_replica_rejects_writes_but_applies_master_commands() {
         let mut redis: Redis = Redis::new_for_test();
-
-        let key = "key".to_string();
-        let value = vec![
-            "value".to_string(),
-            "value".to_string(),
-            "value2".to_string(),
-            "value3".to_string(),
-            "value1".to_string(),
-            "value".to_string(),
-        ];
-        let _lpush = redis.execute(Command::Lpush { key, value });
-
-        let key = "key".to_string();
-
-        let lrem = redis.execute(Command::Lrem {
-            key,
-            count: -2,
-            element: "value".to_string(),
+        let (db_sender, _db_receiver) = std::sync::mpsc::channel();
+        redis.set_db_sender(db_sender);
+        let _replicaof = redis.execute(Command::Replicaof {
+            master: Some(("127.0.0.1".to_string(), 1)),
         });
-        assert!(lrem.is_ok());
-        assert!(eq_response(Re::String("2".to_string()), lrem.unwrap()));
-
-        let key = "key".to_string();
+        let link_id = redis.replication.last_link_id;
 
-        let lrange = redis.execute(Command::Lrange {
-            key,
-            begin: 0,
-            end: -1,
+        let set = redis.execute(Command::Set {
+            key: "key".into(),
+            value: "value".into(),
+            param: SetParam::default(),
+        });
+        let master_set = redis.execute(Command::MasterCommand {
+            args: vec!["SET".into(), "key".into(), "master".into()],
+            link_id,
+        });
+        let old_link = redis.execute(Command::MasterCommand {
+            args: vec!["SET".into(), "key".into(), "old".into()],
+            link_id: link_id - 1,
+        });
+        let role = redis.execute(Command::Role);
+        let get = redis.execute(Command::Get { key: "key".into() });
+        let _replicaof = redis.execute(Command::Replicaof { master: None });
+        let set_after = redis.execute(Command::Set {
+            key: "key".into(),
+            value: "value".into(),
+            param: SetParam::default(),
         });
 
-        let mut vector = vec![
-            "value".to_string(),
-            "value2".to_string(),
-            "value3".to_string(),
-            "value1".to_string(),
-        ];
-
-        vector.reverse();
-
-        assert!(eq_response(Re::List(vector), lrange.unwrap()));
+        assert!(matches!(set, Err(msg) if msg.starts_with("READONLY")));
+        assert!(matches!(master_set, Ok(Response::Normal(_))));
+        assert!(matches!(old_link, Ok(Response::Error(_))));
+        assert!(matches!(role, Ok(Response::Normal(Reply::Array(role)))
+            if role[0] == Reply::BulkString(b"slave".to_vec())));
+        assert!(eq_response(
+            Reply::BulkString("master".into()),
+            get.unwrap()
+        ));
+        assert!(set_after.is_ok());
     }
 
     #[test]
-    fn test_lrem_all_ok() {
+    fn test_replica_applies_master_transaction_on_exec() {
         let mut redis: Redis = Redis::new_for_test();
-
-        let key = "key".to_string();
-        let value = vec![
-            "value".to_string(),
-            "value2".to_string(),
-            "value3".to_string(),
-            "value1".to_string(),
-            "value".to_string(),
-        ];
-        let _lpush = redis.execute(Command::Lpush { key, value });
-
-        let key = "key".to_string();
-
-        let lrem = redis.execute(Command::Lrem {
-            key,
-            count: 0,
-            element: "value".to_string(),
+        let (db_sender, _db_receiver) = std::sync::mpsc::channel();
+        redis.set_db_sender(db_sender);
+        let _replicaof = redis.execute(Command::Replicaof {
+            master: Some(("127.0.0.1".to_string(), 1)),
         });
-        assert!(lrem.is_ok());
-        assert!(eq_response(Re::String("2".to_string()), lrem.unwrap()));
-
-        let key = "key".to_string();
+        let link_id = redis.replication.last_link_id;
 
-        let lrange = redis.execute(Command::Lrange {
-            key,
-            begin: 0,
-            end: -1,
+        let _multi = redis.execute(Command::MasterCommand {
+            args: vec!["MULTI".into()],
+            link_id,
+        });
+        let _set = redis.execute(Command::MasterCommand {
+            args: vec!["SET".into(), "key".into(), "value".into()],
+            link_id,
+        });
+        let get_queued = redis.execute(Command::Get { key: "key".into() });
+        let _exec = redis.execute(Command::MasterCommand {
+            args: vec!["EXEC".into()],
+            link_id,
         });
+        let get = redis.execute(Command::Get { key: "key".into() });
 
-        let mut vector = vec![
-            "value2".to_string(),
-            "value3".to_string(),
-            "value1".to_string(),
-        ];
+        assert!(eq_response(Reply::NullBulk, get_queued.unwrap()));
+        assert!(eq_response(Reply::BulkString("value".into()), get.unwrap()));
+    }
 
-        vector.reverse();
+    #[test]
+    fn test_replica_full_sync_replaces_db() {
+        let mut redis: Redis = Redis::new_for_test();
+        let (db_sender, _db_receiver) = std::sync::mpsc::channel();
+        redis.set_db_sender(db_sender);
+        let _set = redis.execute(Command::Set {
+            key: "old".into(),
+            value: "value".into(),
+            param: SetParam::default(),
+        });
+        let _replicaof = redis.execute(Command::Replicaof {
+            master: Some(("127.0.0.1".to_string(), 1)),
+        });
+        let mut master_db: TtlHashMap<Vec<u8>, Re> = TtlHashMap::new();
+        master_db.insert("new".into(), Re::String("value".into()));
+
+        let sync = redis.execute(Command::ReplicaFullSync {
+            rdb: master_db.serialize(),
+            replid: "a".repeat(40),
+            offset: 10,
+            link_id: redis.replication.last_link_id,
+        });
+        let exists_old = redis.execute(Command::Exists {
+            keys: vec!["old".into()],
+        });
+        let exists_new = redis.execute(Command::Exists {
+            keys: vec!["new".into()],
+        });
+        let _replicaof = redis.execute(Command::Replicaof { master: None });
 
-        assert!(eq_response(Re::List(vector), lrange.unwrap()));
+        assert!(sync.is_ok());
+        assert!(eq_response(Reply::Integer(0), exists_old.unwrap()));
+        assert!(eq_response(Reply::Integer(1), exists_new.unwrap()));
     }
 
     #[test]
-    fn test_lrem_invalid_key_ok() {
+    fn test_dump_and_restore() {
         let mut redis: Redis = Redis::new_for_test();
+        let _rpush = redis.execute(Command::Rpush {
+            key: "list".into(),
+            value: vec!["a".into(), "b".into()],
+        });
 
-        let key = "key".to_string();
-
-        let lrem = redis.execute(Command::Lrem {
-            key,
-            count: 0,
-            element: "value".to_string(),
+        let payload = match redis.execute(Command::Dump { key: "list".into() }) {
+            Ok(Response::Normal(Reply::BulkString(payload))) => payload,
+            _ => panic!("DUMP should return the serialized value"),
+        };
+        let busy = redis.execute(Command::Restore {
+            key: "list".into(),
+            ttl: 0,
+            payload: payload.clone(),
+            replace: false,
+            absttl: false,
+            asking: false,
+        });
+        let restore = redis.execute(Command::Restore {
+            key: "copy".into(),
+            ttl: 10000,
+            payload,
+            replace: false,
+            absttl: false,
+            asking: false,
+        });
+        let lrange = redis.execute(Command::Lrange {
+            key: "copy".into(),
+            begin: 0,
+            end: -1,
         });
-        assert!(lrem.is_ok());
-        assert!(eq_response(Re::String("0".to_string()), lrem.unwrap()));
+        let ttl = redis.execute(Command::Ttl { key: "copy".into() });
+        let missing = redis.execute(Command::Dump {
+            key: "missing".into(),
+        });
+
+        assert!(matches!(busy, Err(msg) if msg.starts_with("BUSYKEY")));
+        assert!(restore.is_ok());
+        assert!(eq_response(
+            Reply::bulk_strings(vec!["a".into(), "b".into()]),
+            lrange.unwrap()
+        ));
+        assert!(matches!(ttl, Ok(Response::Normal(Reply::Integer(ttl))) if ttl > 0));
+        assert!(eq_response(Reply::NullBulk, missing.unwrap()));
     }
 
     #[test]
-    fn test_keys_ok() {
+    fn test_cluster_disabled() {
         let mut redis: Redis = Redis::new_for_test();
 
-        let key = "key".to_string();
-        let value = "value".to_string();
-        let _set = redis.execute(Command::Set { key, value });
-
-        let key = "key1".to_string();
-        let value = "value".to_string();
-        let _set = redis.execute(Command::Set { key, value });
-
-        let pattern: String = "/*".to_string();
-
-        let keys = redis.execute(Command::Keys { pattern });
-
-        assert!(keys.is_ok());
-
-        let pattern: String = "k**".to_string();
-
-        let keys = redis.execute(Command::Keys { pattern });
+        let keyslot = redis.execute(Command::Cluster {
+            param: ClusterParam::Keyslot("foo".into()),
+        });
 
-        assert!(keys.is_ok());
+        assert!(matches!(keyslot, Err(msg) if msg.contains("cluster support disabled")));
     }
 
-    #[ignore]
     #[test]
-    fn test_touch_deletes_expired_key() {
+    fn test_cluster_redirects_keys_of_other_slots() {
         let mut redis: Redis = Redis::new_for_test();
+        redis.cluster = Cluster::new(true, "127.0.0.1".to_string(), 7000);
+        let slot = key_hash_slot(b"foo");
+        let _addslots = redis.execute(Command::Cluster {
+            param: ClusterParam::Addslots(vec![slot]),
+        });
 
-        let key = "key".to_string();
-        let value = "value".to_string();
-        let _set = redis.execute(Command::Set { key, value });
-
-        let key = "key".to_string();
-        let ttl = Duration::from_secs(1);
-        let _expire = redis.execute(Command::Expire { key, ttl });
+        let set = redis.execute(Command::Set {
+            key: "foo".into(),
+            value: "value".into(),
+            param: SetParam::default(),
+        });
+        let other = redis.execute(Command::Get { key: "bar".into() });
+        let keyslot = redis.execute(Command::Cluster {
+            param: ClusterParam::Keyslot("foo".into()),
+        });
+        let countkeys = redis.execute(Command::Cluster {
+            param: ClusterParam::Countkeysinslot(slot),
+        });
+        let ping = redis.execute(Command::Ping);
 
-        thread::sleep(Duration::from_secs(1));
+        assert!(set.is_ok());
+        assert!(matches!(other, Err(msg) if msg.starts_with("CLUSTERDOWN")));
+        assert!(eq_response(Reply::Integer(slot as i64), keyslot.unwrap()));
+        assert!(eq_response(Reply::Integer(1), countkeys.unwrap()));
+        assert!(ping.is_ok());
+    }
 
-        let keys = vec!["key".to_string()];
-        let touch = redis.execute(Command::Touch { keys });
+    #[test]
+    fn test_cluster_node_replays_aof_after_restart() {
+        let path = "test_cluster_node_replays_aof_after_restart.aof".to_string();
+        let nodes_conf = "test_cluster_node_replays_aof_after_restart.conf".to_string();
+        let _ = fs::remove_file(&path);
+        let _ = fs::remove_file(&nodes_conf);
 
-        let pattern = "*".to_string();
-        let keys = redis.execute(Command::Keys { pattern });
+        let mut redis: Redis = Redis::new_for_test();
+        redis.cluster = Cluster::new(true, "127.0.0.1".to_string(), 7000);
+        redis.cluster.load_config(nodes_conf.clone()).unwrap();
+        let _addslots = redis.execute(Command::Cluster {
+            param: ClusterParam::Addslots(vec![key_hash_slot(b"foo")]),
+        });
+        let _load = redis.execute(Command::LoadAof { path: path.clone() });
+        let _set = redis.execute(Command::Set {
+            key: "foo".into(),
+            value: "value".into(),
+            param: SetParam::default(),
+        });
 
-        assert!(eq_response(Re::String("0".to_string()), touch.unwrap()));
-        assert!(eq_response(Re::List(Vec::new()), keys.unwrap()));
+        let mut redis_new: Redis = Redis::new_for_test();
+        redis_new.cluster = Cluster::new(true, "127.0.0.1".to_string(), 7000);
+        redis_new.cluster.load_config(nodes_conf.clone()).unwrap();
+        let _load = redis_new.execute(Command::LoadAof { path: path.clone() });
+        // Sin la configuración del cluster el nodo no atiende ningún slot, pero igual se cargan
+        // los datos del AOF.
+        let mut redis_without_slots: Redis = Redis::new_for_test();
+        redis_without_slots.cluster = Cluster::new(true, "127.0.0.1".to_string(), 7000);
+        let _load = redis_without_slots.execute(Command::LoadAof { path: path.clone() });
+        let _ = fs::remove_file(&path);
+        let _ = fs::remove_file(&nodes_conf);
+
+        let get = redis_new.execute(Command::Get { key: "foo".into() });
+        assert!(eq_response(Reply::BulkString("value".into()), get.unwrap()));
+        assert_eq!(redis_new.cluster.myid(), redis.cluster.myid());
+        let dbsize = redis_without_slots.execute(Command::Dbsize);
+        assert!(eq_response(Reply::Integer(1), dbsize.unwrap()));
     }
 
     #[test]
-    fn test_touch_returns_number_of_keys_touched() {
+    fn test_psubscribe_receives_matching_publishes() {
         let mut redis: Redis = Redis::new_for_test();
+        let psubscribe = redis.execute(Command::Psubscribe {
+            patterns: vec!["news.*".to_string()],
+            client_id: "client".to_string(),
+        });
+        let numpat = redis.execute(Command::Pubsub {
+            param: PubSubParam::Numpat,
+        });
+        let matching = redis.execute(Command::Publish {
+            channel: "news.sports".to_string(),
+            message: "goal".into(),
+        });
+        let other = redis.execute(Command::Publish {
+            channel: "weather".to_string(),
+            message: "rain".into(),
+        });
 
-        let key = "key1".to_string();
-        let value = "value".to_string();
-        let _set = redis.execute(Command::Set { key, value });
-
-        let key = "key2".to_string();
-        let value = "value".to_string();
-        let _set = redis.execute(Command::Set { key, value });
-
-        let keys = vec!["key1".to_string(), "key2".to_string()];
-        let touch = redis.execute(Command::Touch { keys });
-
-        assert!(eq_response(Re::String("2".to_string()), touch.unwrap()));
+        let receiver = match psubscribe {
+            Ok(Response::Stream(receiver)) => receiver,
+            _ => panic!("psubscribe should return a stream"),
+        };
+        assert_eq!(
+            receiver.try_recv().unwrap(),
+            Reply::Array(vec![
+                Reply::BulkString(b"psubscribe".to_vec()),
+                Reply::BulkString(b"news.*".to_vec()),
+                Reply::Integer(1),
+            ])
+        );
+        assert_eq!(
+            receiver.try_recv().unwrap(),
+            Reply::Array(vec![
+                Reply::BulkString(b"pmessage".to_vec()),
+                Reply::BulkString(b"news.*".to_vec()),
+                Reply::BulkString(b"news.sports".to_vec()),
+                Reply::BulkString(b"goal".to_vec()),
+            ])
+        );
+        assert!(receiver.try_recv().is_err());
+        assert!(matches!(numpat, Ok(Response::Normal(Reply::Integer(1)))));
+        assert!(matches!(matching, Ok(Response::Normal(Reply::Integer(1)))));
+        assert!(matches!(other, Ok(Response::Normal(Reply::Integer(0)))));
     }
 
     #[test]
-    fn test_set_element_and_flushdb() {
+    fn test_pubsub_channels_with_glob_pattern() {
         let mut redis: Redis = Redis::new_for_test();
+        let _subscribe = redis.execute(Command::Subscribe {
+            channels: vec![
+                "news.sports".to_string(),
+                "news.weather".to_string(),
+                "newsletter".to_string(),
+            ],
+            client_id: "client".to_string(),
+        });
 
-        let value = "value".to_string();
-        let key = "key".to_string();
-        let _set = redis.execute(Command::Set { key, value });
-
-        let key = "key".to_string();
-        let get = redis.execute(Command::Get { key });
-        assert!(eq_response(Re::String("value".to_string()), get.unwrap()));
-
-        let flushdb = redis.execute(Command::Flushdb);
-        assert!(flushdb.is_ok());
+        let channels = redis.execute(Command::Pubsub {
+            param: PubSubParam::ChannelsWithPattern("news.*".to_string()),
+        });
+        let class = redis.execute(Command::Pubsub {
+            param: PubSubParam::ChannelsWithPattern("news[.]s*".to_string()),
+        });
 
-        let key = "key".to_string();
-        let get = redis.execute(Command::Get { key });
-        assert!(eq_response(Re::Nil, get.unwrap()));
+        let mut channels = bulk_strings(channels);
+        channels.sort();
+        assert_eq!(
+            channels,
+            vec![b"news.sports".to_vec(), b"news.weather".to_vec()]
+        );
+        assert!(eq_response(
+            Reply::bulk_strings(vec![b"news.sports".to_vec()]),
+            class.unwrap()
+        ));
     }
 
     #[test]
-    fn test_store_then_load() {
+    fn test_punsubscribe_removes_patterns() {
         let mut redis: Redis = Redis::new_for_test();
-
-        let key1 = "key1".to_string();
-        let value1 = "value1".to_string();
-        let _set = redis.execute(Command::Set {
-            key: key1.clone(),
-            value: value1.clone(),
+        let receiver = match redis.execute(Command::Psubscribe {
+            patterns: vec!["a*".to_string(), "b*".to_string()],
+            client_id: "client".to_string(),
+        }) {
+            Ok(Response::Stream(receiver)) => receiver,
+            _ => panic!("psubscribe should return a stream"),
+        };
+        let _psubscribe = receiver.try_iter().count();
+        let punsubscribe = redis.execute(Command::Punsubscribe {
+            patterns: vec!["a*".to_string()],
+            client_id: "client".to_string(),
         });
-        let key2 = "key2".to_string();
-        let value2 = "value2".to_string();
-        let _set = redis.execute(Command::Set {
-            key: key2.clone(),
-            value: value2.clone(),
+        let numpat = redis.execute(Command::Pubsub {
+            param: PubSubParam::Numpat,
         });
-        let expire = Duration::from_secs(2);
-        let _ttl = redis.execute(Command::Expire {
-            key: key2.clone(),
-            ttl: expire.clone(),
+        let punsubscribe_all = redis.execute(Command::Punsubscribe {
+            patterns: vec![],
+            client_id: "client".to_string(),
+        });
+        let publish = redis.execute(Command::Publish {
+            channel: "bar".to_string(),
+            message: "msg".into(),
         });
 
-        let path = "test_store_then_load.rdb".to_string();
-        let _store = redis.execute(Command::Store { path: path.clone() });
-
-        let _content = fs::read(path.clone()).unwrap();
-        let mut redis_new: Redis = Redis::new_for_test();
-        let _load = redis_new.execute(Command::Load { path: path });
-
-        let get = redis_new.execute(Command::Get { key: key1 });
-        assert!(eq_response(Re::String(value1), get.unwrap()));
+        let frames = |response: Result<Response, String>| match response {
+            Ok(Response::Pushed) => receiver.try_recv().unwrap(),
+            _ => panic!("punsubscribe should reply through the client stream"),
+        };
+        assert_eq!(
+            frames(punsubscribe),
+            Reply::Array(vec![
+                Reply::BulkString(b"punsubscribe".to_vec()),
+                Reply::BulkString(b"a*".to_vec()),
+                Reply::Integer(1),
+            ])
+        );
+        assert!(matches!(numpat, Ok(Response::Normal(Reply::Integer(1)))));
+        assert_eq!(
+            frames(punsubscribe_all),
+            Reply::Array(vec![
+                Reply::BulkString(b"punsubscribe".to_vec()),
+                Reply::BulkString(b"b*".to_vec()),
+                Reply::Integer(0),
+            ])
+        );
+        assert!(receiver.try_recv().is_err());
+        assert!(matches!(publish, Ok(Response::Normal(Reply::Integer(0)))));
+    }
 
-        let get = redis_new.execute(Command::Get { key: key2.clone() });
-        assert!(eq_response(Re::String(value2), get.unwrap()));
+    #[test]
+    fn test_unsubscribe_counts_remaining_subscriptions() {
+        let mut redis: Redis = Redis::new_for_test();
+        let receiver = match redis.execute(Command::Subscribe {
+            channels: vec!["a".to_string(), "b".to_string(), "a".to_string()],
+            client_id: "client".to_string(),
+        }) {
+            Ok(Response::Stream(receiver)) => receiver,
+            _ => panic!("subscribe should return a stream"),
+        };
+        let _psubscribe = redis.execute(Command::Psubscribe {
+            patterns: vec!["c*".to_string()],
+            client_id: "client".to_string(),
+        });
+        let unsubscribe = redis.execute(Command::Unsubscribe {
+            channels: vec![],
+            client_id: "client".to_string(),
+        });
+        let channels = redis.execute(Command::Pubsub {
+            param: PubSubParam::Channels,
+        });
+        let unsubscribe_again = redis.execute(Command::Unsubscribe {
+            channels: vec!["a".to_string()],
+            client_id: "client".to_string(),
+        });
 
-        let ttl = redis_new.execute(Command::Ttl { key: key2 });
-        assert!(eq_response(
-            Re::String((expire.as_secs() - 1).to_string()),
-            ttl.unwrap(),
+        let frames: Vec<Reply> = receiver.try_iter().skip(4).collect();
+        assert!(matches!(unsubscribe, Ok(Response::Pushed)));
+        assert_eq!(
+            frames,
+            vec![
+                Reply::Array(vec![
+                    Reply::BulkString(b"unsubscribe".to_vec()),
+                    Reply::BulkString(b"a".to_vec()),
+                    Reply::Integer(2),
+                ]),
+                Reply::Array(vec![
+                    Reply::BulkString(b"unsubscribe".to_vec()),
+                    Reply::BulkString(b"b".to_vec()),
+                    Reply::Integer(1),
+                ]),
+                Reply::Array(vec![
+                    Reply::BulkString(b"unsubscribe".to_vec()),
+                    Reply::NullBulk,
+                    Reply::Integer(1),
+                ]),
+            ]
+        );
+        assert!(matches!(
+            channels,
+            Ok(Response::Normal(Reply::Array(channels))) if channels.is_empty()
         ));
-
-        fs::remove_file("test_store_then_load.rdb").unwrap();
+        assert!(matches!(unsubscribe_again, Ok(Response::Pushed)));
     }
 
     #[test]
-    fn test_load_corrupt_file_returns_err() {
+    fn test_subscribed_client_receives_messages_in_publish_order() {
         let mut redis: Redis = Redis::new_for_test();
+        let receiver = match redis.execute(Command::Subscribe {
+            channels: vec!["a".to_string()],
+            client_id: "client".to_string(),
+        }) {
+            Ok(Response::Stream(receiver)) => receiver,
+            _ => panic!("subscribe should return a stream"),
+        };
+        let subscribe_b = redis.execute(Command::Subscribe {
+            channels: vec!["b".to_string()],
+            client_id: "client".to_string(),
+        });
+        let psubscribe = redis.execute(Command::Psubscribe {
+            patterns: vec!["c*".to_string()],
+            client_id: "client".to_string(),
+        });
+        for (channel, message) in &[("b", "1"), ("a", "2"), ("c1", "3"), ("b", "4"), ("a", "5")] {
+            let _publish = redis.execute(Command::Publish {
+                channel: channel.to_string(),
+                message: message.as_bytes().to_vec(),
+            });
+        }
 
-        let path = "test_load_empy_file_returns_err.rdb".to_string();
-        let mut file = fs::File::create(path.clone()).unwrap();
+        assert!(matches!(subscribe_b, Ok(Response::Pushed)));
+        assert!(matches!(psubscribe, Ok(Response::Pushed)));
+        let messages: Vec<Reply> = receiver
+            .try_iter()
+            .skip(3)
+            .map(|frame| match frame {
+                Reply::Array(frame) => frame.last().unwrap().clone(),
+                _ => panic!("unexpected frame"),
+            })
+            .collect();
+        assert_eq!(
+            messages,
+            ["1", "2", "3", "4", "5"]
+                .iter()
+                .map(|message| Reply::BulkString(message.as_bytes().to_vec()))
+                .collect::<Vec<Reply>>()
+        );
+    }
 
-        let op_resizedb = 0xfb;
-        let mut store_len = TtlHashMap::length_encode(1);
-        let mut ttl_len = TtlHashMap::length_encode(0);
-        let byte_value_type = TtlHashMap::value_type_encode(&Re::String("value".to_string()));
-        let key = "key".to_string();
-        let mut key_encoded = TtlHashMap::string_encode(key.clone());
-        let op_eof = 0xff;
+    #[test]
+    fn test_keyspace_notifications_for_writes() {
+        let mut redis: Redis = Redis::new_for_test();
+        let _config = redis.execute(Command::ConfigSet {
+            parameter: "notify-keyspace-events".to_string(),
+            value: "KEgh".to_string(),
+        });
+        let receiver = match redis.execute(Command::Psubscribe {
+            patterns: vec!["__key*".to_string()],
+            client_id: "client".to_string(),
+        }) {
+            Ok(Response::Stream(receiver)) => receiver,
+            _ => panic!("psubscribe should return a stream"),
+        };
+        let _psubscribe = receiver.try_recv();
 
-        let mut bytes = ["REDIS".as_bytes(), "0001".as_bytes()].concat();
-        bytes.push(op_resizedb);
-        bytes.append(&mut store_len);
-        bytes.append(&mut ttl_len);
-        bytes.push(byte_value_type);
-        bytes.append(&mut key_encoded);
-        bytes.push(op_eof);
+        let _set = redis.execute(Command::Set {
+            key: "string".into(),
+            value: "value".into(),
+            param: SetParam::default(),
+        });
+        let _hset = redis.execute(Command::Hset {
+            key: "hash".into(),
+            field_values: vec![("field".into(), "value".into())],
+        });
+        let _hdel = redis.execute(Command::Hdel {
+            key: "hash".into(),
+            fields: vec!["field".into()],
+        });
+        let _del = redis.execute(Command::Del {
+            keys: vec!["missing".into()],
+        });
 
-        let _ = file.write_all(&bytes);
+        let channels: Vec<(Reply, Reply)> = receiver
+            .try_iter()
+            .map(|frame| match frame {
+                Reply::Array(frame) => (frame[2].clone(), frame[3].clone()),
+                _ => panic!("unexpected frame"),
+            })
+            .collect();
+        let expected: Vec<(Reply, Reply)> = [
+            ("__keyspace@0__:hash", "hset"),
+            ("__keyevent@0__:hset", "hash"),
+            ("__keyspace@0__:hash", "hdel"),
+            ("__keyevent@0__:hdel", "hash"),
+            ("__keyspace@0__:hash", "del"),
+            ("__keyevent@0__:del", "hash"),
+        ]
+        .iter()
+        .map(|(channel, message)| {
+            (
+                Reply::BulkString(channel.as_bytes().to_vec()),
+                Reply::BulkString(message.as_bytes().to_vec()),
+            )
+        })
+        .collect();
+        assert_eq!(channels, expected);
+    }
 
-        let load = redis.execute(Command::Load { path });
+    #[test]
+    fn test_keyspace_notifications_for_expired_keys() {
+        let mut redis: Redis = Redis::new_for_test();
+        let invalid = redis.execute(Command::ConfigSet {
+            parameter: "notify-keyspace-events".to_string(),
+            value: "Kw".to_string(),
+        });
+        let _config = redis.execute(Command::ConfigSet {
+            parameter: "notify-keyspace-events".to_string(),
+            value: "Ex".to_string(),
+        });
+        let receiver = match redis.execute(Command::Subscribe {
+            channels: vec!["__keyevent@0__:expired".to_string()],
+            client_id: "client".to_string(),
+        }) {
+            Ok(Response::Stream(receiver)) => receiver,
+            _ => panic!("subscribe should return a stream"),
+        };
+        let _subscribe = receiver.try_recv();
+        let _set = redis.execute(Command::Set {
+            key: "key".into(),
+            value: "value".into(),
+            param: SetParam::default(),
+        });
+        let _expire = redis.execute(Command::Expireat {
+            key: "key".into(),
+            ttl: SystemTime::now(),
+        });
+        let _get = redis.execute(Command::Get { key: "key".into() });
 
-        assert!(load.is_err());
-        fs::remove_file("test_load_empy_file_returns_err.rdb").unwrap();
+        assert!(invalid.is_err());
+        assert_eq!(
+            receiver.try_iter().collect::<Vec<Reply>>(),
+            vec![Reply::Array(vec![
+                Reply::BulkString(b"message".to_vec()),
+                Reply::BulkString(b"__keyevent@0__:expired".to_vec()),
+                Reply::BulkString(b"key".to_vec()),
+            ])]
+        );
     }
 
     #[test]
-    fn test_config_get_ok() {
+    fn test_active_expire_cycle_removes_unread_keys() {
         let mut redis: Redis = Redis::new_for_test();
+        for index in 0..100 {
+            let key: Vec<u8> = format!("key{}", index).into_bytes();
+            let _set = redis.execute(Command::Set {
+                key: key.clone(),
+                value: "value".into(),
+                param: SetParam::default(),
+            });
+            let ttl = if index < 90 {
+                SystemTime::now()
+            } else {
+                SystemTime::now() + Duration::from_secs(100)
+            };
+            let _expireat = redis.execute(Command::Expireat { key, ttl });
+        }
 
-        let config_get = redis.execute(Command::ConfigGet);
-        let conf = vec![
-            "dump.rdb".to_string(),
-            "log.log".to_string(),
-            "8080".to_string(),
-            "0".to_string(),
-            "0".to_string(),
-        ];
+        let _cycle = redis.execute(Command::ActiveExpireCycle);
+        let stats = redis.execute(Command::Info {
+            param: InfoParam::Stats,
+        });
 
-        assert!(eq_response(Re::List(conf), config_get.unwrap()));
+        let stats = match stats {
+            Ok(Response::Normal(Reply::BulkString(stats))) => String::from_utf8(stats).unwrap(),
+            _ => panic!("info stats should return a string"),
+        };
+        assert_eq!(redis.db.len(), 10);
+        assert!(stats.contains("expired_keys:90\r\n"));
+        assert!(!stats.contains("expired_stale_perc:0.00"));
     }
 
     #[test]
-    fn test_config_set_verbose() {
+    fn test_noeviction_rejects_writes_over_maxmemory() {
         let mut redis: Redis = Redis::new_for_test();
+        let _set = redis.execute(Command::Set {
+            key: "key".into(),
+            value: "value".repeat(100).into(),
+            param: SetParam::default(),
+        });
+        redis
+            .config
+            .lock()
+            .unwrap()
+            .set_maxmemory("100".to_string());
+
+        let set = redis.execute(Command::Set {
+            key: "other".into(),
+            value: "value".into(),
+            param: SetParam::default(),
+        });
+        let del = redis.execute(Command::Del {
+            keys: vec!["key".into()],
+        });
 
-        let parameter = "verbose".to_string();
-        let value = "1".to_string();
-        let _config_set = redis.execute(Command::ConfigSet { parameter, value });
-
-        assert_eq!("1", redis.config.lock().unwrap().get_verbose());
+        assert!(set.is_err_and(|error| error.starts_with("OOM")));
+        assert!(del.is_ok());
+        assert!(redis
+            .execute(Command::Set {
+                key: "other".into(),
+                value: "value".into(),
+                param: SetParam::default()
+            })
+            .is_ok());
     }
 
     #[test]
-    fn test_config_set_dbfilename() {
+    fn test_allkeys_lru_evicts_keys_over_maxmemory() {
         let mut redis: Redis = Redis::new_for_test();
+        redis
+            .config
+            .lock()
+            .unwrap()
+            .set_maxmemory("2kb".to_string());
+        redis
+            .config
+            .lock()
+            .unwrap()
+            .set_maxmemory_policy("allkeys-lru".to_string());
+
+        for index in 0..100 {
+            let set = redis.execute(Command::Set {
+                key: format!("key{}", index).into_bytes(),
+                value: "value".repeat(10).into(),
+                param: SetParam::default(),
+            });
+            assert!(set.is_ok());
+        }
+        let _set = redis.execute(Command::Set {
+            key: "last".into(),
+            value: "value".into(),
+            param: SetParam::default(),
+        });
 
-        let parameter = "dbfilename".to_string();
-        let value = "new_dump.rdb".to_string();
-        let _config_set = redis.execute(Command::ConfigSet { parameter, value });
-
-        assert_eq!(
-            "new_dump.rdb",
-            redis.config.lock().unwrap().get_dbfilename()
-        );
+        let stats = match redis.execute(Command::Info {
+            param: InfoParam::Stats,
+        }) {
+            Ok(Response::Normal(Reply::BulkString(stats))) => String::from_utf8(stats).unwrap(),
+            _ => panic!("info stats should return a string"),
+        };
+        assert!(redis.db.len() < 100);
+        assert!(redis.db.used_memory() <= 2048);
+        assert!(!stats.contains("evicted_keys:0\r\n"));
     }
 
     #[test]
-    fn test_config_set_logfile() {
+    fn test_random_eviction_picks_keys_uniformly_across_databases() {
         let mut redis: Redis = Redis::new_for_test();
+        for index in 0..100 {
+            let db = if index < 10 { 0 } else { 1 };
+            let _set = redis.execute(selected(
+                db,
+                Command::Set {
+                    key: format!("key{}", index).into_bytes(),
+                    value: "value".into(),
+                    param: SetParam::default(),
+                },
+            ));
+        }
+        let _expire = redis.execute(selected(
+            1,
+            Command::Expire {
+                key: "key50".into(),
+                ttl: Duration::from_secs(100),
+            },
+        ));
 
-        let parameter = "logfile".to_string();
-        let value = "new_log.log".to_string();
-        let _config_set = redis.execute(Command::ConfigSet { parameter, value });
+        let mut counts = [0; 2];
+        for _ in 0..1000 {
+            let (index, _) = redis
+                .eviction_candidate(MaxmemoryPolicy::AllkeysRandom)
+                .unwrap();
+            counts[index] += 1;
+        }
+        let volatile = redis.eviction_candidate(MaxmemoryPolicy::VolatileRandom);
 
-        assert_eq!("new_log.log", redis.config.lock().unwrap().get_logfile());
+        assert!(counts[0] > 50 && counts[0] < 200, "{:?}", counts);
+        assert_eq!(volatile, Some((1, b"key50".to_vec())));
+    }
+
+    #[allow(dead_code)]
+    fn selected(db: usize, command: Command) -> Command {
+        Command::SelectedCommand {
+            db,
+            command: Box::new(command),
+        }
     }
 
     #[test]
-    fn test_config_set_wrong_parameter() {
+    fn test_select_keeps_databases_apart() {
         let mut redis: Redis = Redis::new_for_test();
+        let _set = redis.execute(selected(
+            1,
+            Command::Set {
+                key: "key".into(),
+                value: "one".into(),
+                param: SetParam::default(),
+            },
+        ));
 
-        let parameter = "timeout".to_string();
-        let value = "1".to_string();
-        let config_set = redis.execute(Command::ConfigSet { parameter, value });
+        let in_db0 = redis.execute(selected(0, Command::Get { key: "key".into() }));
+        let in_db1 = redis.execute(selected(1, Command::Get { key: "key".into() }));
+        let out_of_range = redis.execute(Command::Select { index: 16 });
 
-        assert!(config_set.is_err());
-        assert_ne!(1, redis.config.lock().unwrap().get_timeout());
+        assert!(matches!(in_db0, Ok(Response::Normal(Reply::NullBulk))));
+        assert!(matches!(
+            in_db1,
+            Ok(Response::Normal(Reply::BulkString(value))) if value == b"one".to_vec()
+        ));
+        assert_eq!(out_of_range.unwrap_err(), "ERR DB index is out of range");
     }
 
     #[test]
-    fn test_info_connected_clients_ok() {
+    fn test_move_key_to_other_database() {
         let mut redis: Redis = Redis::new_for_test();
-        let info = redis.execute(Command::Info {
-            param: InfoParam::ConnectedClients,
+        let _set = redis.execute(Command::Set {
+            key: "key".into(),
+            value: "value".into(),
+            param: SetParam::default(),
         });
-
-        assert!(info.is_ok());
-        assert!(eq_response(Re::String("0".to_string()), info.unwrap()));
-
-        let _ = redis.execute(Command::AddClient);
-        let info = redis.execute(Command::Info {
-            param: InfoParam::ConnectedClients,
+        let _expire = redis.execute(Command::Expire {
+            key: "key".into(),
+            ttl: Duration::from_secs(100),
         });
-        assert!(info.is_ok());
-        assert!(eq_response(Re::String("1".to_string()), info.unwrap()));
 
-        let _ = redis.execute(Command::RemoveClient);
-        let info = redis.execute(Command::Info {
-            param: InfoParam::ConnectedClients,
+        let moved = redis.execute(Command::Move {
+            key: "key".into(),
+            db: 2,
         });
-        assert!(info.is_ok());
-        assert!(eq_response(Re::String("0".to_string()), info.unwrap()));
-    }
-
-    #[test]
-    fn test_info_port_ok() {
-        let mut redis: Redis = Redis::new_for_test();
-        let info = redis.execute(Command::Info {
-            param: InfoParam::Port,
+        let missing = redis.execute(Command::Move {
+            key: "key".into(),
+            db: 2,
         });
+        let same = redis.execute(Command::Move {
+            key: "key".into(),
+            db: 0,
+        });
+        let ttl = redis.execute(selected(2, Command::Ttl { key: "key".into() }));
 
-        assert!(info.is_ok());
-        assert!(eq_response(Re::String("8080".to_string()), info.unwrap()));
+        assert!(matches!(moved, Ok(Response::Normal(Reply::Integer(1)))));
+        assert!(matches!(missing, Ok(Response::Normal(Reply::Integer(0)))));
+        assert_eq!(
+            same.unwrap_err(),
+            "ERR source and destination objects are the same"
+        );
+        assert!(matches!(ttl, Ok(Response::Normal(Reply::Integer(ttl))) if ttl > 0));
     }
 
     #[test]
-    fn test_info_config_file_ok() {
+    fn test_swapdb_and_flushall() {
         let mut redis: Redis = Redis::new_for_test();
-        let info = redis.execute(Command::Info {
-            param: InfoParam::ConfigFile,
+        let _set = redis.execute(selected(
+            1,
+            Command::Set {
+                key: "key".into(),
+                value: "value".into(),
+                param: SetParam::default(),
+            },
+        ));
+        let _watch = redis.execute(selected(
+            0,
+            Command::Watch {
+                keys: vec!["other".into()],
+                client_id: "client".to_string(),
+            },
+        ));
+
+        let swapdb = redis.execute(Command::Swapdb {
+            index1: 0,
+            index2: 1,
         });
+        let in_db0 = redis.execute(selected(0, Command::Get { key: "key".into() }));
+        let exec = redis.execute(Command::Exec {
+            commands: Some(vec![]),
+            client_id: "client".to_string(),
+        });
+        let _flushall = redis.execute(selected(3, Command::Flushall));
+        let after_flush = redis.execute(selected(0, Command::Get { key: "key".into() }));
 
-        assert!(info.is_ok());
-        assert!(eq_response(
-            Re::String(redis.config.lock().unwrap().get_configfile()),
-            info.unwrap()
+        assert!(swapdb.is_ok());
+        assert!(matches!(
+            in_db0,
+            Ok(Response::Normal(Reply::BulkString(value))) if value == b"value".to_vec()
         ));
+        assert!(matches!(exec, Ok(Response::Normal(Reply::NullArray))));
+        assert!(matches!(after_flush, Ok(Response::Normal(Reply::NullBulk))));
     }
 
     #[test]
-    fn test_info_process_id_ok() {
+    fn test_info_keyspace_reports_each_database() {
         let mut redis: Redis = Redis::new_for_test();
-        let info = redis.execute(Command::Info {
-            param: InfoParam::ProcessId,
+        let _set = redis.execute(Command::Set {
+            key: "a".into(),
+            value: "1".into(),
+            param: SetParam::default(),
         });
-
-        assert!(info.is_ok());
-        assert!(eq_response(
-            Re::String(process::id().to_string()),
-            info.unwrap()
+        for key in ["b", "c"] {
+            let _set = redis.execute(selected(
+                5,
+                Command::Set {
+                    key: key.into(),
+                    value: "1".into(),
+                    param: SetParam::default(),
+                },
+            ));
+        }
+        let _expire = redis.execute(selected(
+            5,
+            Command::Expire {
+                key: "b".into(),
+                ttl: Duration::from_secs(100),
+            },
         ));
+
+        let info = match redis.execute(Command::Info {
+            param: InfoParam::Keyspace,
+        }) {
+            Ok(Response::Normal(Reply::BulkString(info))) => String::from_utf8(info).unwrap(),
+            _ => panic!("info keyspace should return a string"),
+        };
+
+        assert!(info.starts_with("# Keyspace\r\ndb0:keys=1,expires=0,avg_ttl=0\r\n"));
+        assert!(info.contains("db5:keys=2,expires=1,avg_ttl="));
+        assert!(!info.contains("db1:"));
     }
 }
@@ -0,0 +1,672 @@
+use crate::entities::command::Command;
+use crate::entities::log::Log;
+use crate::entities::log_level::LogLevel;
+use crate::entities::redis_element::RedisElement as Re;
+use crate::entities::reply::Reply;
+use crate::entities::response::Response;
+use crate::entities::ttl_hash_map::TtlHashMap;
+use crate::protocol::decode::decode;
+use crate::protocol::parse_data::{parse_command, parse_response_ok};
+use std::collections::hash_map::{DefaultHasher, RandomState};
+use std::collections::{HashMap, VecDeque};
+use std::hash::{BuildHasher, Hasher};
+use std::io;
+use std::io::{BufRead, BufReader, Error, ErrorKind, Read, Write};
+use std::net::{Shutdown, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{Receiver, Sender};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::thread::JoinHandle;
+use std::time::{Duration, SystemTime};
+
+/// Cantidad máxima de bytes de comandos que se guardan para las resincronizaciones parciales.
+const BACKLOG_SIZE: u64 = 1024 * 1024;
+/// Tiempo de espera antes de reintentar la conexión con el master.
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// Tipo de dato definido para el canal de envío de comandos al hilo de la base de datos.
+type DbSender = Sender<(Command, Sender<Response>)>;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// Estado de la conexión de una réplica con su master.
+enum LinkState {
+    /// Conectándose con el master.
+    Connect,
+    /// Recibiendo la copia completa de la base de datos.
+    Sync,
+    /// Recibiendo los comandos de escritura del master.
+    Connected,
+}
+
+impl LinkState {
+    fn as_str(&self) -> &'static str {
+        match self {
+            LinkState::Connect => "connect",
+            LinkState::Sync => "sync",
+            LinkState::Connected => "connected",
+        }
+    }
+}
+
+#[derive(Debug)]
+/// Conexión de una réplica con su master, atendida desde un hilo aparte.
+struct MasterLink {
+    /// Identificador de la conexión. Los comandos recibidos por conexiones anteriores se ignoran.
+    id: u64,
+    host: String,
+    port: u16,
+    /// Indica que la conexión debe cerrarse.
+    stop: Arc<AtomicBool>,
+    /// Socket abierto con el master, para poder cerrarlo desde el hilo de la base de datos.
+    stream: Arc<Mutex<Option<TcpStream>>>,
+    state: Arc<Mutex<LinkState>>,
+}
+
+impl MasterLink {
+    /// Cierra la conexión con el master, terminando el hilo que la atiende.
+    fn close(&self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Ok(stream) = self.stream.lock() {
+            if let Some(stream) = stream.as_ref() {
+                let _ = stream.shutdown(Shutdown::Both);
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+/// Réplica conectada a este servidor.
+struct ReplicaInfo {
+    /// Identificador del cliente de la réplica (su dirección).
+    client_id: String,
+    /// Puerto en el que la réplica atiende a sus clientes.
+    listening_port: u16,
+    /// Canal por el que se le envían los comandos de escritura.
+    sender: Sender<Reply>,
+}
+
+#[derive(Debug)]
+/// Estado de la replicación del servidor.
+///
+/// Como master, guarda los comandos de escritura ejecutados en un backlog y se los envía a las
+/// réplicas conectadas. Como réplica, mantiene la conexión con su master y aplica sus comandos.
+///
+/// El offset es la cantidad de bytes de comandos de escritura generados desde que se creó el
+/// identificador de replicación (replid), y permite saber desde dónde continuar la sincronización.
+pub struct Replication {
+    replid: String,
+    offset: u64,
+    /// Últimos comandos de escritura, junto al offset en el que comienza cada uno.
+    backlog: VecDeque<(u64, Vec<Vec<u8>>)>,
+    /// Cantidad de bytes de los comandos guardados en el backlog.
+    backlog_bytes: u64,
+    /// Base de datos seleccionada con el último SELECT enviado a las réplicas, si lo hay.
+    selected_db: Option<usize>,
+    replicas: Vec<ReplicaInfo>,
+    /// Mapa en donde se guarda {Id_cliente, puerto informado con REPLCONF listening-port}.
+    listening_ports: HashMap<String, u16>,
+    /// Conexión con el master, si el servidor es una réplica.
+    master: Option<MasterLink>,
+    /// Identificador de la última conexión con un master. Los comandos de conexiones anteriores
+    /// se descartan.
+    pub(crate) last_link_id: u64,
+}
+
+impl Replication {
+    /// Constructor de la replicación de un servidor que es master, con un replid nuevo.
+    pub fn new() -> Self {
+        Self {
+            replid: new_replid(),
+            offset: 0,
+            backlog: VecDeque::new(),
+            backlog_bytes: 0,
+            selected_db: None,
+            replicas: Vec::new(),
+            listening_ports: HashMap::new(),
+            master: None,
+            last_link_id: 0,
+        }
+    }
+
+    /// Indica si el servidor es una réplica de otro.
+    pub fn is_replica(&self) -> bool {
+        self.master.is_some()
+    }
+
+    /// Indica si los comandos recibidos por la conexión indicada deben aplicarse, es decir, si es
+    /// la conexión actual con el master.
+    pub fn is_current_link(&self, link_id: u64) -> bool {
+        self.master.as_ref().map(|master| master.id) == Some(link_id)
+    }
+
+    /// Registra un comando de escritura: lo guarda en el backlog y se lo envía a las réplicas.
+    pub fn feed(&mut self, args: Vec<Vec<u8>>) {
+        let len = parse_response_ok(Reply::bulk_strings(args.clone())).len() as u64;
+        self.replicas.retain(|replica| {
+            replica
+                .sender
+                .send(Reply::bulk_strings(args.clone()))
+                .is_ok()
+        });
+
+        self.backlog.push_back((self.offset, args));
+        self.offset += len;
+        self.backlog_bytes += len;
+        while self.backlog_bytes > BACKLOG_SIZE {
+            match self.backlog.pop_front() {
+                Some((start, _)) => {
+                    let end = self.backlog.front().map_or(self.offset, |(next, _)| *next);
+                    self.backlog_bytes -= end - start;
+                }
+                None => break,
+            }
+        }
+    }
+
+    /// Registra un comando de escritura de la base de datos indicada. Si la base es distinta a la
+    /// del comando anterior, se registra antes un SELECT.
+    pub fn feed_db(&mut self, db: usize, args: Vec<Vec<u8>>) {
+        if self.selected_db != Some(db) {
+            self.feed(vec![b"select".to_vec(), db.to_string().into_bytes()]);
+            self.selected_db = Some(db);
+        }
+        self.feed(args);
+    }
+
+    /// Devuelve los comandos del backlog a partir del offset indicado, o None si no están todos.
+    fn backlog_since(&self, offset: u64) -> Option<Vec<Vec<Vec<u8>>>> {
+        if offset == self.offset {
+            return Some(vec![]);
+        }
+        let position = self
+            .backlog
+            .iter()
+            .position(|(start, _)| *start == offset)?;
+        Some(
+            self.backlog
+                .iter()
+                .skip(position)
+                .map(|(_, args)| args.clone())
+                .collect(),
+        )
+    }
+
+    /// Guarda el puerto en el que atiende la réplica conectada como el cliente indicado.
+    pub fn set_listening_port(&mut self, client_id: String, port: u16) {
+        self.listening_ports.insert(client_id, port);
+    }
+
+    /// Atiende el pedido de sincronización (PSYNC) de una réplica y devuelve el canal por el que
+    /// se le envían los datos.
+    ///
+    /// Si la réplica ya tiene los datos de este replid y los comandos que le faltan siguen en el
+    /// backlog, se le envían sólo esos comandos (+CONTINUE). Sino, se le envía una copia completa
+    /// de las bases de datos (+FULLRESYNC), serializada desde un hilo aparte. En ambos casos, luego
+    /// se le envían los comandos de escritura que se ejecuten.
+    pub fn psync(
+        &mut self,
+        replid: String,
+        offset: i64,
+        client_id: String,
+        dbs: &[&TtlHashMap<Vec<u8>, Re>],
+    ) -> Receiver<Reply> {
+        let (sender, receiver): (Sender<Reply>, Receiver<Reply>) = mpsc::channel();
+        let listening_port = self.listening_ports.remove(&client_id).unwrap_or(0);
+
+        let backlog = match offset {
+            offset if replid == self.replid && offset > 0 => self.backlog_since(offset as u64 - 1),
+            _ => None,
+        };
+        if let Some(commands) = backlog {
+            let _ = sender.send(Reply::SimpleString(format!("CONTINUE {}", self.replid)));
+            for args in commands {
+                let _ = sender.send(Reply::bulk_strings(args));
+            }
+            self.replicas.push(ReplicaInfo {
+                client_id,
+                listening_port,
+                sender,
+            });
+            return receiver;
+        }
+
+        let header = format!("FULLRESYNC {} {}", self.replid, self.offset);
+        let snapshot: Vec<TtlHashMap<Vec<u8>, Re>> = dbs.iter().map(|db| db.snapshot()).collect();
+        // La réplica carga la copia con la base 0 seleccionada, por lo que el próximo comando
+        // tiene que indicar su base.
+        self.selected_db = None;
+        let (feed_sender, feed_receiver): (Sender<Reply>, Receiver<Reply>) = mpsc::channel();
+        self.replicas.push(ReplicaInfo {
+            client_id,
+            listening_port,
+            sender: feed_sender,
+        });
+        let _: JoinHandle<()> = thread::spawn(move || {
+            let _ = sender.send(Reply::SimpleString(header));
+            let dbs: Vec<&TtlHashMap<Vec<u8>, Re>> = snapshot.iter().collect();
+            let _ = sender.send(Reply::BulkString(TtlHashMap::serialize_databases(&dbs)));
+            while let Ok(element) = feed_receiver.recv() {
+                if sender.send(element).is_err() {
+                    break;
+                }
+            }
+        });
+        receiver
+    }
+
+    /// Reemplaza el replid y el offset por los del master, luego de recibir su copia completa de
+    /// la base de datos.
+    pub fn full_sync(&mut self, replid: String, offset: u64) {
+        self.replid = replid;
+        self.offset = offset;
+        self.backlog.clear();
+        self.backlog_bytes = 0;
+        self.selected_db = None;
+    }
+
+    /// Convierte al servidor en réplica del master indicado, lanzando el hilo que se conecta con él.
+    /// Si ya era réplica, se cierra la conexión anterior.
+    pub fn start_link(
+        &mut self,
+        host: String,
+        port: u16,
+        listening_port: u16,
+        db_sender: DbSender,
+        log_sender: Sender<Log>,
+    ) {
+        if let Some(master) = self.master.take() {
+            master.close();
+        }
+        self.last_link_id += 1;
+        let master = MasterLink {
+            id: self.last_link_id,
+            host,
+            port,
+            stop: Arc::new(AtomicBool::new(false)),
+            stream: Arc::new(Mutex::new(None)),
+            state: Arc::new(Mutex::new(LinkState::Connect)),
+        };
+        let link = LinkThread {
+            id: master.id,
+            address: format!("{}:{}", master.host, master.port),
+            listening_port,
+            stop: Arc::clone(&master.stop),
+            stream: Arc::clone(&master.stream),
+            state: Arc::clone(&master.state),
+            db_sender,
+            log_sender,
+        };
+        let _: JoinHandle<()> = thread::spawn(move || link.run());
+        self.master = Some(master);
+    }
+
+    /// Deja de replicar al master, convirtiendo al servidor en master con un replid nuevo. Los
+    /// datos se conservan.
+    pub fn stop_link(&mut self) {
+        if let Some(master) = self.master.take() {
+            master.close();
+            self.replid = new_replid();
+            self.selected_db = None;
+        }
+    }
+
+    /// Devuelve la respuesta del comando ROLE.
+    ///
+    /// * Master: `master`, offset y la lista de réplicas (ip, puerto, offset).
+    /// * Réplica: `slave`, host y puerto del master, estado de la conexión y offset.
+    pub fn role(&self) -> Reply {
+        match &self.master {
+            Some(master) => {
+                let state = master.state.lock().map_or(LinkState::Connect, |s| *s);
+                Reply::Array(vec![
+                    Reply::BulkString(b"slave".to_vec()),
+                    Reply::BulkString(master.host.clone().into_bytes()),
+                    Reply::Integer(master.port as i64),
+                    Reply::BulkString(state.as_str().as_bytes().to_vec()),
+                    Reply::Integer(self.offset as i64),
+                ])
+            }
+            None => Reply::Array(vec![
+                Reply::BulkString(b"master".to_vec()),
+                Reply::Integer(self.offset as i64),
+                Reply::Array(
+                    self.replicas
+                        .iter()
+                        .map(|replica| {
+                            let ip = replica
+                                .client_id
+                                .rsplit_once(':')
+                                .map_or(replica.client_id.as_str(), |(ip, _)| ip);
+                            Reply::Array(vec![
+                                Reply::BulkString(ip.as_bytes().to_vec()),
+                                Reply::BulkString(replica.listening_port.to_string().into_bytes()),
+                                Reply::BulkString(self.offset.to_string().into_bytes()),
+                            ])
+                        })
+                        .collect(),
+                ),
+            ]),
+        }
+    }
+}
+
+/// Genera un identificador de replicación nuevo: 40 caracteres hexadecimales pseudo aleatorios.
+pub fn new_replid() -> String {
+    let nanos = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_nanos());
+    (0..3)
+        .map(|part| {
+            let mut hasher: DefaultHasher = RandomState::new().build_hasher();
+            hasher.write_u128(nanos);
+            hasher.write_u32(part);
+            format!("{:016x}", hasher.finish())
+        })
+        .collect::<String>()[..40]
+        .to_string()
+}
+
+/// Hilo de una réplica que se conecta con su master, recibe sus datos y se los envía al hilo de la
+/// base de datos. Si se corta la conexión, se reconecta intentando continuar desde el último
+/// comando recibido.
+struct LinkThread {
+    id: u64,
+    address: String,
+    listening_port: u16,
+    stop: Arc<AtomicBool>,
+    stream: Arc<Mutex<Option<TcpStream>>>,
+    state: Arc<Mutex<LinkState>>,
+    db_sender: DbSender,
+    log_sender: Sender<Log>,
+}
+
+impl LinkThread {
+    fn run(self) {
+        let mut replid = "?".to_string();
+        let mut offset: i64 = -1;
+
+        while !self.stop.load(Ordering::Relaxed) {
+            self.set_state(LinkState::Connect);
+            if let Err(e) = self.sync(&mut replid, &mut offset) {
+                let _ = self.log_sender.send(Log::new(
+                    LogLevel::Error,
+                    line!(),
+                    column!(),
+                    file!().to_string(),
+                    format!("Replication link with {} lost: {}", self.address, e),
+                ));
+            }
+            if !self.stop.load(Ordering::Relaxed) {
+                thread::sleep(RECONNECT_DELAY);
+            }
+        }
+    }
+
+    fn set_state(&self, state: LinkState) {
+        if let Ok(mut current) = self.state.lock() {
+            *current = state;
+        }
+    }
+
+    /// Se sincroniza con el master y aplica sus comandos hasta que se corte la conexión.
+    fn sync(&self, replid: &mut String, offset: &mut i64) -> io::Result<()> {
+        let stream = TcpStream::connect(&self.address)?;
+        *self
+            .stream
+            .lock()
+            .map_err(|_| Error::other("Replication lock error"))? = Some(stream.try_clone()?);
+        if self.stop.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+        let mut output = stream.try_clone()?;
+        let mut input = BufReader::new(stream);
+
+        send_command(&mut output, &["PING"])?;
+        expect_reply(&mut input)?;
+        let port = self.listening_port.to_string();
+        send_command(&mut output, &["REPLCONF", "listening-port", &port])?;
+        expect_reply(&mut input)?;
+        let psync_offset = (*offset + 1).to_string();
+        send_command(&mut output, &["PSYNC", replid, &psync_offset])?;
+
+        let reply = read_line(&mut input)?;
+        if let Some(full_resync) = reply.strip_prefix("+FULLRESYNC ") {
+            self.set_state(LinkState::Sync);
+            let mut parts = full_resync.split_whitespace();
+            let new_replid = parts.next().map(str::to_string);
+            let new_offset = parts.next().and_then(|offset| offset.parse::<u64>().ok());
+            let (new_replid, new_offset) = match (new_replid, new_offset) {
+                (Some(new_replid), Some(new_offset)) => (new_replid, new_offset),
+                _ => return Err(Error::new(ErrorKind::InvalidData, reply)),
+            };
+            let rdb = read_bulk(&mut input)?;
+            self.apply(Command::ReplicaFullSync {
+                rdb,
+                replid: new_replid.clone(),
+                offset: new_offset,
+                link_id: self.id,
+            })?;
+            *replid = new_replid;
+            *offset = new_offset as i64;
+        } else if !reply.starts_with("+CONTINUE") {
+            return Err(Error::new(ErrorKind::InvalidData, reply));
+        }
+        self.set_state(LinkState::Connected);
+
+        loop {
+            let (args, len) = read_command(&mut input)?;
+            if self.stop.load(Ordering::Relaxed) {
+                return Ok(());
+            }
+            self.apply(Command::MasterCommand {
+                args,
+                link_id: self.id,
+            })?;
+            *offset += len as i64;
+        }
+    }
+
+    /// Envía el comando al hilo de la base de datos y espera a que se ejecute.
+    fn apply(&self, command: Command) -> io::Result<()> {
+        let (sender, receiver): (Sender<Response>, Receiver<Response>) = mpsc::channel();
+        self.db_sender
+            .send((command, sender))
+            .map_err(|_| Error::new(ErrorKind::ConnectionAborted, "Db Sender error"))?;
+        receiver
+            .recv()
+            .map_err(|_| Error::new(ErrorKind::ConnectionAborted, "Db receiver error"))?;
+        Ok(())
+    }
+}
+
+/// Envía un comando al master, codificado como un array RESP de bulk strings.
+fn send_command(output: &mut TcpStream, args: &[&str]) -> io::Result<()> {
+    let args = args.iter().map(|arg| arg.as_bytes().to_vec());
+    output.write_all(&parse_response_ok(Reply::bulk_strings(args)))
+}
+
+/// Lee una línea de respuesta del master, sin el `\r\n` final.
+fn read_line(input: &mut BufReader<TcpStream>) -> io::Result<String> {
+    let mut line = Vec::new();
+    if input.read_until(b'\n', &mut line)? == 0 {
+        return Err(Error::new(
+            ErrorKind::UnexpectedEof,
+            "Master closed the link",
+        ));
+    }
+    Ok(String::from_utf8_lossy(&line).trim_end().to_string())
+}
+
+/// Lee una respuesta del master, fallando si es un error.
+fn expect_reply(input: &mut BufReader<TcpStream>) -> io::Result<()> {
+    let reply = read_line(input)?;
+    if reply.starts_with('-') {
+        return Err(Error::new(ErrorKind::InvalidData, reply));
+    }
+    Ok(())
+}
+
+/// Lee la copia de la base de datos enviada por el master: `$<largo>\r\n` seguido del archivo
+/// RDB. El `\r\n` final es opcional, ya que Redis no lo envía.
+fn read_bulk(input: &mut BufReader<TcpStream>) -> io::Result<Vec<u8>> {
+    let header = read_line(input)?;
+    let len: usize = header
+        .strip_prefix('$')
+        .and_then(|len| len.parse().ok())
+        .ok_or_else(|| Error::new(ErrorKind::InvalidData, header.clone()))?;
+    let mut bytes = vec![0; len];
+    input.read_exact(&mut bytes)?;
+    if input.fill_buf()?.starts_with(b"\r\n") {
+        input.consume(2);
+    }
+    Ok(bytes)
+}
+
+/// Lee un comando enviado por el master. Devuelve sus argumentos y la cantidad de bytes leídos.
+fn read_command(input: &mut BufReader<TcpStream>) -> io::Result<(Vec<Vec<u8>>, usize)> {
+    let mut buf: Vec<u8> = Vec::new();
+    while input.read_until(b'\n', &mut buf)? != 0 {
+        if let Ok((data, len)) = decode(&buf, 0) {
+            return Ok((parse_command(data), len));
+        }
+    }
+    Err(Error::new(
+        ErrorKind::UnexpectedEof,
+        "Master closed the link",
+    ))
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::entities::reply::Reply;
+    use crate::entities::ttl_hash_map::TtlHashMap;
+    use crate::service::replication::{new_replid, Replication, BACKLOG_SIZE};
+
+    #[allow(dead_code)]
+    fn command(args: &[&str]) -> Vec<Vec<u8>> {
+        args.iter().map(|arg| arg.as_bytes().to_vec()).collect()
+    }
+
+    #[test]
+    fn test_new_replid() {
+        let replid = new_replid();
+
+        assert_eq!(replid.len(), 40);
+        assert!(replid.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_ne!(replid, new_replid());
+    }
+
+    #[test]
+    fn test_feed_updates_offset_and_replicas() {
+        let mut replication = Replication::new();
+        let db = TtlHashMap::new();
+        let replid = replication.replid.clone();
+        let receiver = replication.psync("?".to_string(), -1, "client".to_string(), &[&db]);
+
+        replication.feed(command(&["set", "key", "value"]));
+
+        assert_eq!(replication.offset, 33);
+        assert_eq!(
+            receiver.recv().unwrap(),
+            Reply::SimpleString(format!("FULLRESYNC {} 0", replid))
+        );
+        assert!(matches!(receiver.recv().unwrap(), Reply::BulkString(_)));
+        assert_eq!(
+            receiver.recv().unwrap(),
+            Reply::bulk_strings(command(&["set", "key", "value"]))
+        );
+    }
+
+    #[test]
+    fn test_feed_db_selects_only_when_db_changes() {
+        let mut replication = Replication::new();
+        let db = TtlHashMap::new();
+        let receiver = replication.psync("?".to_string(), -1, "client".to_string(), &[&db]);
+        let _fullresync = receiver.recv();
+        let _rdb = receiver.recv();
+
+        replication.feed_db(0, command(&["set", "a", "1"]));
+        replication.feed_db(0, command(&["set", "b", "2"]));
+        replication.feed_db(3, command(&["del", "a"]));
+
+        assert_eq!(
+            (0..5)
+                .map(|_| receiver.recv().unwrap())
+                .collect::<Vec<Reply>>(),
+            vec![
+                Reply::bulk_strings(command(&["select", "0"])),
+                Reply::bulk_strings(command(&["set", "a", "1"])),
+                Reply::bulk_strings(command(&["set", "b", "2"])),
+                Reply::bulk_strings(command(&["select", "3"])),
+                Reply::bulk_strings(command(&["del", "a"])),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_psync_continues_from_backlog() {
+        let mut replication = Replication::new();
+        let db = TtlHashMap::new();
+        replication.feed(command(&["set", "a", "1"]));
+        let offset = replication.offset;
+        replication.feed(command(&["set", "b", "2"]));
+        let replid = replication.replid.clone();
+
+        let receiver =
+            replication.psync(replid.clone(), offset as i64 + 1, "c".to_string(), &[&db]);
+
+        assert_eq!(
+            receiver.recv().unwrap(),
+            Reply::SimpleString(format!("CONTINUE {}", replid))
+        );
+        assert_eq!(
+            receiver.recv().unwrap(),
+            Reply::bulk_strings(command(&["set", "b", "2"]))
+        );
+    }
+
+    #[test]
+    fn test_psync_with_other_replid_or_old_offset_is_full() {
+        let mut replication = Replication::new();
+        let db = TtlHashMap::new();
+        let big_value = "x".repeat(1024);
+        for _ in 0..(BACKLOG_SIZE / 1024 + 10) {
+            replication.feed(command(&["set", "key", &big_value]));
+        }
+        let replid = replication.replid.clone();
+
+        let other = replication.psync("other".to_string(), 1, "a".to_string(), &[&db]);
+        let old = replication.psync(replid, 1, "b".to_string(), &[&db]);
+
+        assert!(
+            matches!(other.recv().unwrap(), Reply::SimpleString(s) if s.starts_with("FULLRESYNC"))
+        );
+        assert!(
+            matches!(old.recv().unwrap(), Reply::SimpleString(s) if s.starts_with("FULLRESYNC"))
+        );
+        assert!(replication.backlog_bytes <= BACKLOG_SIZE);
+    }
+
+    #[test]
+    fn test_role_of_master() {
+        let mut replication = Replication::new();
+        let db = TtlHashMap::new();
+        replication.set_listening_port("127.0.0.1:5000".to_string(), 6380);
+        let _receiver =
+            replication.psync("?".to_string(), -1, "127.0.0.1:5000".to_string(), &[&db]);
+
+        assert_eq!(
+            replication.role(),
+            Reply::Array(vec![
+                Reply::BulkString(b"master".to_vec()),
+                Reply::Integer(0),
+                Reply::Array(vec![Reply::Array(vec![
+                    Reply::BulkString(b"127.0.0.1".to_vec()),
+                    Reply::BulkString(b"6380".to_vec()),
+                    Reply::BulkString(b"0".to_vec()),
+                ])]),
+            ])
+        );
+    }
+}
@@ -2,7 +2,9 @@ use crate::config::server_config::Config;
 use crate::entities::command::Command;
 use crate::entities::log::Log;
 use crate::entities::log_level::LogLevel;
+use crate::entities::reply::Reply;
 use crate::entities::response::Response;
+use crate::entities::transaction::{Transaction, TransactionStep};
 use crate::service::command_generator::generate;
 use crate::service::logger::Logger;
 use crate::service::redis::Redis;
@@ -10,7 +12,7 @@ use std::io;
 use std::io::{BufReader, Error, ErrorKind, Read, Write};
 use std::net::{TcpListener, TcpStream};
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::mpsc::{Receiver, Sender};
+use std::sync::mpsc::{Receiver, RecvTimeoutError, Sender, TryRecvError};
 use std::sync::{mpsc, Arc, Mutex};
 use std::thread;
 
@@ -18,13 +20,24 @@ use crate::protocol::http::html::Html;
 use crate::protocol::http::parse_request::{parse_command_rest, HttpMethod};
 use crate::protocol::http::parse_response::parse_response_rest;
 use crate::protocol::lines_iterator::LinesIterator;
-use crate::protocol::parse_data::{parse_command, parse_response_error, parse_response_ok};
+use crate::protocol::parse_data::{
+    parse_command, parse_response_error, parse_response_ok, parse_response_transaction,
+};
 use std::thread::JoinHandle;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 /// Tiempo de ejecución entre un ciclo y el siguiente, en el hilo de Mantenimiento.
 /// Este valor está representado en Segundos.
-static STORE_TIME_SEC: u64 = 120;
+static SAVE_CHECK_TIME_SEC: u64 = 1;
+/// Tiempo entre un ciclo de expiración activa de claves y el siguiente.
+/// Este valor está representado en Milisegundos.
+static ACTIVE_EXPIRE_TIME_MS: u64 = 100;
+/// Tiempo entre una revisión y la siguiente de si un cliente bloqueado cerró la conexión.
+/// Este valor está representado en Milisegundos.
+static BLOCKED_CHECK_TIME_MS: u64 = 100;
+/// Tiempo máximo de espera de mensajes de los Streams de un cliente antes de revisar si envió un
+/// comando. Este valor está representado en Milisegundos.
+static STREAM_CHECK_TIME_MS: u64 = 10;
 
 /// Tipo de dato definido para guardar las conecciones de los usuarios y su estado en uso.
 type VecHandler = Vec<(JoinHandle<Result<(), io::Error>>, Arc<AtomicBool>)>;
@@ -69,8 +82,17 @@ impl Server {
 
     /// Methodo del Server para ponerlo operativo.
     pub fn serve(mut self) -> Result<(), Box<dyn std::error::Error>> {
-        let command = Command::Load {
-            path: self.config.lock().unwrap().get_dbfilename(),
+        let command = {
+            let config = self.config.lock().unwrap();
+            if config.get_appendonly() {
+                Command::LoadAof {
+                    path: config.get_appendfilename(),
+                }
+            } else {
+                Command::Load {
+                    path: config.get_dbfilename(),
+                }
+            }
         };
         let _ = self.redis.execute(command);
 
@@ -102,25 +124,56 @@ impl Server {
         Ok(())
     }
 
-    fn server_run(self, address: &str, address_rest: &str) -> io::Result<()> {
+    fn server_run(mut self, address: &str, address_rest: &str) -> io::Result<()> {
         let listener = TcpListener::bind(address)?;
-        let rest_listener = TcpListener::bind(address_rest)?;
         let (db_sender, db_receiver): (DbSender, DbReceiver) = mpsc::channel();
 
         let log_sender = self.log_sender.clone();
         let timeout = self.config.lock().unwrap().get_timeout();
+        let replicaof = self.config.lock().unwrap().get_replicaof();
+
+        self.redis.set_db_sender(db_sender.clone());
+        if let Some(master) = replicaof {
+            let _ = self.redis.execute(Command::Replicaof {
+                master: Some(master),
+            });
+        }
 
-        let db_filename = self.config.lock().unwrap().get_dbfilename();
         let db_sender_maintenance = db_sender.clone();
 
         let _: JoinHandle<Result<(), io::Error>> = thread::spawn(move || {
-            Server::maintenance_thread(db_filename, db_sender_maintenance)?;
+            Server::maintenance_thread(db_sender_maintenance)?;
+            Ok(())
+        });
+
+        let db_sender_expire = db_sender.clone();
+        let _: JoinHandle<Result<(), io::Error>> = thread::spawn(move || {
+            Server::active_expire_thread(db_sender_expire)?;
             Ok(())
         });
 
         self.db_thread(db_receiver);
 
-        let _ = Server::accepter_rest_thread(rest_listener, db_sender.clone(), log_sender.clone());
+        // Si el puerto REST está ocupado (por ejemplo, por otra instancia en el mismo equipo) el
+        // servidor sigue atendiendo sólo a los clientes de Redis.
+        match TcpListener::bind(address_rest) {
+            Ok(rest_listener) => {
+                let _ = Server::accepter_rest_thread(
+                    rest_listener,
+                    db_sender.clone(),
+                    log_sender.clone(),
+                );
+            }
+            Err(e) => {
+                let _ = log_sender.send(Log::new(
+                    LogLevel::Error,
+                    line!(),
+                    column!(),
+                    file!().to_string(),
+                    format!("REST listener could not be started: {}", e),
+                ));
+            }
+        }
         Server::receive_connections(listener, db_sender, log_sender, timeout)?;
 
         Ok(())
@@ -254,18 +307,34 @@ impl Server {
     ) -> io::Result<()> {
         let (client_sndr, client_rcvr): (Sender<Response>, Receiver<Response>) = mpsc::channel();
         let help_msg = "I'm sorry, I don't recognize that command. Please type HELP for one of \
-        these commands: DECRBY, DEL, EXISTS, EXPIRE, GET, GETSET, INCRBY, KEYS, LINDEX, LLEN, LPOP, \
-         LPUSH, LRANGE, LREM, LSET, LTRIM, MGET, MSET, RENAME, RPOP, RPUSH, SADD, SCARD, SET, SORT, \
-         TTL, TYPE";
+        these commands: DECRBY, DEL, EXISTS, EXPIRE, GET, GETSET, HDEL, HGET, HGETALL, HINCRBY, \
+         HSET, INCRBY, KEYS, LINDEX, LLEN, LPOP, LPUSH, LRANGE, LREM, LSET, LTRIM, MGET, MSET, \
+         RENAME, RPOP, RPUSH, SADD, SCARD, SET, SORT, TTL, TYPE, ZADD, ZINCRBY, ZRANGE, ZRANK, \
+         ZSCORE";
 
         html.append_input(&command.join(" "));
-        match generate(command, "REST".to_string()) {
+        let params = command.into_iter().map(String::into_bytes).collect();
+        match generate(params, "REST".to_string()) {
             Ok(Command::Monitor) => html.append_error(help_msg),
             Ok(Command::Publish { .. }) => html.append_error(help_msg),
             Ok(Command::Command) => html.append_error(help_msg),
             Ok(Command::Subscribe { .. }) => html.append_error(help_msg),
             Ok(Command::Unsubscribe { .. }) => html.append_error(help_msg),
+            Ok(Command::Psubscribe { .. }) => html.append_error(help_msg),
+            Ok(Command::Punsubscribe { .. }) => html.append_error(help_msg),
+            Ok(Command::Multi) => html.append_error(help_msg),
+            Ok(Command::Exec { .. }) => html.append_error(help_msg),
+            Ok(Command::Discard { .. }) => html.append_error(help_msg),
+            Ok(Command::Watch { .. }) => html.append_error(help_msg),
+            Ok(Command::Psync { .. }) => html.append_error(help_msg),
+            Ok(Command::Blpop { .. }) => html.append_error(help_msg),
+            Ok(Command::Brpop { .. }) => html.append_error(help_msg),
+            Ok(Command::Blmove { .. }) => html.append_error(help_msg),
             Ok(command) => {
+                let command = Command::SelectedCommand {
+                    db: 0,
+                    command: Box::new(command),
+                };
                 db_sender_clone
                     .send((command, client_sndr))
                     .map_err(|_| Error::new(ErrorKind::ConnectionAborted, "Db Sender error"))?;
@@ -279,7 +348,9 @@ impl Server {
                         html.append_response(&parse_response_rest(redis_string));
                     }
                     Response::Error(msg) => html.append_error(&msg),
-                    Response::Stream(_) => html.append_error(help_msg),
+                    Response::Stream(_) | Response::Pushed => html.append_error(help_msg),
+                    Response::Blocked(..) => html.append_error(help_msg),
+                    Response::Transaction(_) => html.append_error(help_msg),
                 }
             }
             Err(err) => html.append_error(&err),
@@ -341,8 +412,20 @@ impl Server {
         Ok(())
     }
 
-    #[allow(clippy::while_let_on_iterator)]
     /// Metodo encargado de capturar los eventos de cada cliente.
+    ///
+    /// Las respuestas se escriben desde un hilo aparte. Los mensajes que la DB envía al cliente por
+    /// su cuenta (PubSub y MONITOR) llegan por los Streams del cliente, que este mismo hilo reenvía
+    /// mientras espera el próximo comando, por lo que se escriben en el orden en que se enviaron.
+    /// De esta forma el cliente puede seguir enviando comandos mientras está suscripto, con las
+    /// restricciones de Redis: mientras tenga suscripciones sólo puede usar (P)SUBSCRIBE,
+    /// (P)UNSUBSCRIBE, PING y QUIT.
+    ///
+    /// La base de datos seleccionada con SELECT es propia de la conexión, y se envía junto a cada
+    /// comando.
+    ///
+    /// Mientras el cliente está bloqueado por BLPOP, BRPOP o BLMOVE no se leen sus comandos
+    /// siguientes, igual que en Redis.
     fn client_handler(
         client: TcpStream,
         db_sender_clone: Sender<(Command, Sender<Response>)>,
@@ -352,23 +435,103 @@ impl Server {
         let client_input: TcpStream = client.try_clone()?;
         let client_output: TcpStream = client;
         let mut input = BufReader::new(client_input);
-        let mut output = client_output;
 
-        let client_id = output.try_clone()?.local_addr()?.to_string();
+        let client_id = client_output.peer_addr()?.to_string();
+        let output = Server::output_writer(client_output);
+        let mut transaction = Transaction::new();
+        // Indica si el cliente envió ASKING, que sólo aplica al comando siguiente.
+        let mut asking = false;
+        // Cantidad de canales y patrones a los que está suscripto el cliente.
+        let mut subscriptions = 0;
+        // Base de datos seleccionada por el cliente con SELECT.
+        let mut db = 0;
+        // Streams por los que la DB envía mensajes al cliente.
+        let mut streams: Vec<Receiver<Reply>> = Vec::new();
 
         Server::connected_user(&db_sender_clone);
 
         // iteramos las lineas que recibimos de nuestro cliente
-        'principal: while let Some(line) = LinesIterator::new(&mut input).next() {
+        loop {
+            Server::wait_command(&input, &mut streams, &output, &mut subscriptions)?;
+            let line = match LinesIterator::new(&mut input).next() {
+                Some(line) => line,
+                None => break,
+            };
             let (client_sndr, client_rcvr): (Sender<Response>, Receiver<Response>) =
                 mpsc::channel();
 
             let vector = parse_command(line);
 
             let command = generate(vector, client_id.clone());
-
-            match command {
-                Ok(command) => {
+            if subscriptions > 0 {
+                match &command {
+                    Ok(Command::Subscribe { .. })
+                    | Ok(Command::Unsubscribe { .. })
+                    | Ok(Command::Psubscribe { .. })
+                    | Ok(Command::Punsubscribe { .. })
+                    | Ok(Command::Quit) => {}
+                    Ok(Command::Ping) => {
+                        Server::write(
+                            &output,
+                            parse_response_ok(Reply::Array(vec![
+                                Reply::BulkString(b"pong".to_vec()),
+                                Reply::BulkString(vec![]),
+                            ])),
+                        )?;
+                        continue;
+                    }
+                    Ok(command) => {
+                        Server::write(
+                            &output,
+                            parse_response_error(format!(
+                                "ERR Can't execute '{}': only (P)SUBSCRIBE / (P)UNSUBSCRIBE / \
+                                 PING / QUIT are allowed in this context",
+                                command.as_str()
+                            )),
+                        )?;
+                        continue;
+                    }
+                    Err(_) => {}
+                }
+            }
+            if let Ok(Command::Asking) = command {
+                asking = true;
+                Server::write(
+                    &output,
+                    parse_response_ok(Reply::SimpleString("OK".to_string())),
+                )?;
+                continue;
+            }
+            if let Ok(Command::Quit) = command {
+                Server::write(
+                    &output,
+                    parse_response_ok(Reply::SimpleString("OK".to_string())),
+                )?;
+                break;
+            }
+            let asked = std::mem::replace(&mut asking, false);
+
+            match transaction.handle(command) {
+                TransactionStep::Execute(command) => {
+                    let requested_db = Server::requested_db(&command);
+                    let queued_dbs: Vec<Option<usize>> = match &command {
+                        Command::Exec {
+                            commands: Some(commands),
+                            ..
+                        } => commands.iter().map(Server::requested_db).collect(),
+                        _ => vec![],
+                    };
+                    let command = if asked {
+                        Command::AskingCommand {
+                            command: Box::new(command),
+                        }
+                    } else {
+                        command
+                    };
+                    let command = Command::SelectedCommand {
+                        db,
+                        command: Box::new(command),
+                    };
                     db_sender_clone
                         .send((command, client_sndr))
                         .map_err(|_| Error::new(ErrorKind::ConnectionAborted, "Db Sender error"))?;
@@ -376,27 +539,54 @@ impl Server {
                     let response = client_rcvr.recv().map_err(|_| {
                         Error::new(ErrorKind::ConnectionAborted, "Client receiver error")
                     })?;
+                    let response = match response {
+                        Response::Blocked(receiver, timeout) => {
+                            match Server::wait_unblocked(
+                                receiver,
+                                timeout,
+                                &db_sender_clone,
+                                &client_id,
+                                input.get_ref(),
+                            )? {
+                                Some(response) => response,
+                                None => break,
+                            }
+                        }
+                        response => response,
+                    };
 
                     match response {
                         Response::Normal(redis_string) => {
-                            output.write_all(&parse_response_ok(redis_string))?;
+                            db = requested_db.unwrap_or(db);
+                            Server::write(&output, parse_response_ok(redis_string))?;
                         }
                         Response::Stream(rec) => {
-                            'inner: while let Ok(redis_element) = rec.recv() {
-                                if output.write_all(&parse_response_ok(redis_element)).is_err() {
-                                    break 'inner;
-                                }
-                            }
-
-                            std::mem::drop(rec);
-                            break 'principal;
+                            streams.push(rec);
+                            Server::drain_streams(&mut streams, &output, &mut subscriptions)?;
+                        }
+                        Response::Pushed => {
+                            Server::drain_streams(&mut streams, &output, &mut subscriptions)?;
                         }
                         Response::Error(msg) => {
-                            output.write_all(&parse_response_error(msg))?;
+                            Server::write(&output, parse_response_error(msg))?;
+                        }
+                        Response::Transaction(responses) => {
+                            db = queued_dbs
+                                .iter()
+                                .zip(responses.iter())
+                                .filter(|(_, response)| matches!(response, Response::Normal(_)))
+                                .filter_map(|(index, _)| *index)
+                                .next_back()
+                                .unwrap_or(db);
+                            Server::write(&output, parse_response_transaction(responses))?;
                         }
+                        Response::Blocked(..) => {}
                     }
                 }
-                Err(err) => {
+                TransactionStep::Reply(reply) => {
+                    Server::write(&output, parse_response_ok(reply))?;
+                }
+                TransactionStep::Error(err) => {
                     logger
                         .send(Log::new(
                             LogLevel::Error,
@@ -408,17 +598,197 @@ impl Server {
                         .map_err(|_| {
                             Error::new(ErrorKind::ConnectionAborted, "Log Sender error")
                         })?;
-                    output.write_all(&parse_response_error(err))?;
+                    Server::write(&output, parse_response_error(err))?;
                 }
             };
         }
 
         used.swap(false, Ordering::Relaxed);
-        Server::disconnected_user(&db_sender_clone);
+        Server::disconnected_user(&db_sender_clone, client_id);
+
+        Ok(())
+    }
+
+    /// Espera la respuesta de un comando bloqueante. Si vence el timeout, se pide a la DB que
+    /// desbloquee al cliente, tras lo cual la respuesta (la de timeout, o la del comando si la DB
+    /// lo atendió antes) ya está en el canal. Mientras espera, revisa periódicamente si el cliente
+    /// cerró la conexión, en cuyo caso devuelve None.
+    fn wait_unblocked(
+        receiver: Receiver<Response>,
+        timeout: Option<Duration>,
+        db_sender: &DbSender,
+        client_id: &str,
+        input: &TcpStream,
+    ) -> io::Result<Option<Response>> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        let check_time = Duration::from_millis(BLOCKED_CHECK_TIME_MS);
+        loop {
+            let wait = deadline.map_or(check_time, |deadline| {
+                check_time.min(deadline.saturating_duration_since(Instant::now()))
+            });
+            match receiver.recv_timeout(wait) {
+                Ok(response) => return Ok(Some(response)),
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => {
+                    return Err(Error::new(
+                        ErrorKind::ConnectionAborted,
+                        "Client receiver error",
+                    ))
+                }
+            }
+
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                let (client_sndr, client_rcvr): (Sender<Response>, Receiver<Response>) =
+                    mpsc::channel();
+                let command = Command::UnblockClient {
+                    client_id: client_id.to_string(),
+                };
+                db_sender
+                    .send((command, client_sndr))
+                    .map_err(|_| Error::new(ErrorKind::ConnectionAborted, "Db Sender error"))?;
+                let _ = client_rcvr.recv();
+                return receiver.recv().map(Some).map_err(|_| {
+                    Error::new(ErrorKind::ConnectionAborted, "Client receiver error")
+                });
+            }
+            if Server::connection_closed(input)? {
+                return Ok(None);
+            }
+        }
+    }
+
+    /// Indica si el cliente cerró la conexión, sin consumir lo que haya enviado.
+    fn connection_closed(input: &TcpStream) -> io::Result<bool> {
+        Ok(matches!(Server::peek_input(input), Ok(Some(0)) | Err(_)))
+    }
+
+    /// Revisa, sin consumirlo, si el cliente envió algo. Devuelve la cantidad de bytes disponibles
+    /// (0 si cerró la conexión), o None si todavía no envió nada. Al terminar se restaura el timeout
+    /// de lectura del cliente (la opción `timeout`).
+    fn peek_input(input: &TcpStream) -> io::Result<Option<usize>> {
+        let timeout = input.read_timeout()?;
+        input.set_read_timeout(Some(Duration::from_millis(1)))?;
+        let peeked = input.peek(&mut [0; 1]);
+        input.set_read_timeout(timeout)?;
+        match peeked {
+            Ok(read) => Ok(Some(read)),
+            Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Devuelve la base de datos que selecciona el comando, si es un SELECT.
+    fn requested_db(command: &Command) -> Option<usize> {
+        match command {
+            Command::Select { index } => Some(*index),
+            _ => None,
+        }
+    }
+
+    /// Lanza el hilo que escribe en la conexión del cliente todo lo que recibe por el canal
+    /// devuelto. El hilo termina cuando se cierra la conexión o se descartan todos los Senders.
+    fn output_writer(mut output: TcpStream) -> Sender<Vec<u8>> {
+        let (sender, receiver): (Sender<Vec<u8>>, Receiver<Vec<u8>>) = mpsc::channel();
+        thread::spawn(move || {
+            for bytes in receiver {
+                if output.write_all(&bytes).is_err() {
+                    break;
+                }
+            }
+        });
+        sender
+    }
+
+    /// Envía los bytes al hilo que escribe en la conexión del cliente.
+    fn write(output: &Sender<Vec<u8>>, bytes: Vec<u8>) -> io::Result<()> {
+        output
+            .send(bytes)
+            .map_err(|_| Error::new(ErrorKind::ConnectionAborted, "Client writer error"))
+    }
+
+    /// Espera a que el cliente envíe su próximo comando (o cierre la conexión), reenviándole
+    /// mientras tanto los mensajes de sus Streams a medida que llegan. Si el cliente no tiene
+    /// Streams, vuelve enseguida y el comando se espera al leerlo.
+    fn wait_command(
+        input: &BufReader<TcpStream>,
+        streams: &mut Vec<Receiver<Reply>>,
+        output: &Sender<Vec<u8>>,
+        subscriptions: &mut i64,
+    ) -> io::Result<()> {
+        let check_time = Duration::from_millis(STREAM_CHECK_TIME_MS);
+        loop {
+            Server::drain_streams(streams, output, subscriptions)?;
+            if streams.is_empty() || !input.buffer().is_empty() {
+                return Ok(());
+            }
+            match streams[0].recv_timeout(check_time) {
+                Ok(reply) => Server::forward_reply(reply, output, subscriptions)?,
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => {
+                    streams.remove(0);
+                }
+            }
+            if !matches!(Server::peek_input(input.get_ref()), Ok(None)) {
+                return Ok(());
+            }
+        }
+    }
 
+    /// Escribe los mensajes que ya están disponibles en los Streams del cliente, en el orden en
+    /// que la DB los envió, y descarta los Streams por los que la DB ya no puede enviar más.
+    fn drain_streams(
+        streams: &mut Vec<Receiver<Reply>>,
+        output: &Sender<Vec<u8>>,
+        subscriptions: &mut i64,
+    ) -> io::Result<()> {
+        let mut index = 0;
+        while index < streams.len() {
+            match streams[index].try_recv() {
+                Ok(reply) => Server::forward_reply(reply, output, subscriptions)?,
+                Err(TryRecvError::Empty) => index += 1,
+                Err(TryRecvError::Disconnected) => {
+                    streams.remove(index);
+                }
+            }
+        }
         Ok(())
     }
 
+    /// Escribe un mensaje de un Stream, actualizando la cantidad de suscripciones del cliente con
+    /// las confirmaciones de (P)SUBSCRIBE y (P)UNSUBSCRIBE.
+    fn forward_reply(
+        reply: Reply,
+        output: &Sender<Vec<u8>>,
+        subscriptions: &mut i64,
+    ) -> io::Result<()> {
+        if let Some(count) = Server::subscription_count(&reply) {
+            *subscriptions = count;
+        }
+        Server::write(output, parse_response_ok(reply))
+    }
+
+    /// Devuelve la cantidad de suscripciones informada en una confirmación de (P)SUBSCRIBE o
+    /// (P)UNSUBSCRIBE, o None si el mensaje es de otro tipo.
+    fn subscription_count(reply: &Reply) -> Option<i64> {
+        match reply {
+            Reply::Array(frame) if frame.len() == 3 => match (&frame[0], &frame[2]) {
+                (Reply::BulkString(kind), Reply::Integer(count))
+                    if [
+                        &b"subscribe"[..],
+                        b"unsubscribe",
+                        b"psubscribe",
+                        b"punsubscribe",
+                    ]
+                    .contains(&kind.as_slice()) =>
+                {
+                    Some(*count)
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
     /// Metodo encargado de Enviarle una señal a la DB indicando que se ha conectado otro usuario.
     fn connected_user(db_sender_clone: &Sender<(Command, Sender<Response>)>) {
         let (client_sndr, client_rcvr): (Sender<Response>, Receiver<Response>) = mpsc::channel();
@@ -426,8 +796,30 @@ impl Server {
         let _ = client_rcvr.recv();
     }
 
-    /// Metodo encargado de Enviarle una señal a la DB indicando que se ha desconectado un usuario.
-    fn disconnected_user(db_sender_clone: &Sender<(Command, Sender<Response>)>) {
+    /// Metodo encargado de Enviarle una señal a la DB indicando que se ha desconectado un usuario,
+    /// dejando de observar las claves que haya observado con WATCH, quitando sus suscripciones y
+    /// desbloqueándolo si estaba bloqueado.
+    fn disconnected_user(db_sender_clone: &Sender<(Command, Sender<Response>)>, client_id: String) {
+        for command in [
+            Command::Unsubscribe {
+                channels: vec![],
+                client_id: client_id.clone(),
+            },
+            Command::Punsubscribe {
+                patterns: vec![],
+                client_id: client_id.clone(),
+            },
+            Command::Unwatch {
+                client_id: client_id.clone(),
+            },
+            Command::UnblockClient { client_id },
+        ] {
+            let (client_sndr, client_rcvr): (Sender<Response>, Receiver<Response>) =
+                mpsc::channel();
+            let _ = db_sender_clone.send((command, client_sndr));
+            let _ = client_rcvr.recv();
+        }
+
         let (client_sndr, client_rcvr): (Sender<Response>, Receiver<Response>) = mpsc::channel();
         let _ = db_sender_clone.send((Command::RemoveClient, client_sndr));
         let _ = client_rcvr.recv();
@@ -480,25 +872,38 @@ impl Server {
 
     /// Metodo ejecutado en el hilo de mantenimiento el cual se encarga de ejecutar acciones dentro
     /// del server que sean de Mantenimiento. Como por ejemplo persistir la base de datos en caso de
-    /// fallas.
-    fn maintenance_thread(
-        file: String,
-        db_receiver: Sender<(Command, Sender<Response>)>,
-    ) -> io::Result<()> {
+    /// fallas. En cada ciclo se pide a la base de datos que se guarde en segundo plano si se cumple
+    /// alguna de las reglas `save` configuradas, y que envíe su estado a los demás nodos del
+    /// cluster.
+    fn maintenance_thread(db_receiver: Sender<(Command, Sender<Response>)>) -> io::Result<()> {
         loop {
             let (client_sndr, client_rcvr): (Sender<Response>, Receiver<Response>) =
                 mpsc::channel();
-            let command = Command::Store {
-                path: file.to_string(),
-            };
+            for command in [Command::CheckSavePoints, Command::ClusterCron] {
+                db_receiver
+                    .send((command, client_sndr.clone()))
+                    .map_err(|_| Error::new(ErrorKind::ConnectionAborted, "DB receiver error"))?;
+                client_rcvr
+                    .recv()
+                    .map_err(|_| Error::new(ErrorKind::ConnectionAborted, "DB sender error"))?;
+            }
+            thread::sleep(Duration::from_secs(SAVE_CHECK_TIME_SEC));
+        }
+    }
 
-            db_receiver
-                .send((command, client_sndr))
+    /// Metodo ejecutado en el hilo de expiración activa, que pide periódicamente a la base de datos
+    /// que borre las claves expiradas que nadie volvió a leer.
+    fn active_expire_thread(db_sender: Sender<(Command, Sender<Response>)>) -> io::Result<()> {
+        loop {
+            let (client_sndr, client_rcvr): (Sender<Response>, Receiver<Response>) =
+                mpsc::channel();
+            db_sender
+                .send((Command::ActiveExpireCycle, client_sndr))
                 .map_err(|_| Error::new(ErrorKind::ConnectionAborted, "DB receiver error"))?;
             client_rcvr
                 .recv()
                 .map_err(|_| Error::new(ErrorKind::ConnectionAborted, "DB sender error"))?;
-            thread::sleep(Duration::from_secs(STORE_TIME_SEC));
+            thread::sleep(Duration::from_millis(ACTIVE_EXPIRE_TIME_MS));
         }
     }
 }
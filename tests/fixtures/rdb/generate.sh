@@ -0,0 +1,61 @@
+#!/bin/sh
+# Genera un archivo RDB de prueba con un redis-server real, para verificar que el servidor carga
+# las codificaciones compactas que usa Redis: ziplist, listpack, intset, quicklist y strings
+# comprimidos con LZF.
+#
+# Uso: generate.sh <redis-server> <archivo.rdb>
+#
+# Los tests de integración esperan estos archivos, generados con cada versión:
+#
+#   generate.sh /ruta/a/redis-6.2/redis-server tests/fixtures/rdb/redis-6.2.rdb
+#   generate.sh /ruta/a/redis-7.2/redis-server tests/fixtures/rdb/redis-7.2.rdb
+#
+# Redis 6.2 guarda las listas como quicklist de ziplists y los hash y sorted sets chicos como
+# ziplist. Redis 7.2 usa listpack en su lugar, también para los sets chicos de strings. En ambas
+# versiones los sets de enteros se guardan como intset, y los strings largos (y los nodos de las
+# listas, con list-compress-depth) se comprimen con LZF.
+set -e
+
+if [ $# -ne 2 ]; then
+    echo "Uso: $0 <redis-server> <archivo.rdb>" >&2
+    exit 1
+fi
+
+server=$1
+output=$(cd "$(dirname "$2")" && pwd)/$(basename "$2")
+cli="$(dirname "$server")/redis-cli"
+port=6399
+dir=$(mktemp -d)
+
+"$server" --port "$port" --dir "$dir" --save "" --appendonly no --rdbcompression yes \
+    --list-max-ziplist-size 4 --list-compress-depth 1 --daemonize yes \
+    --pidfile "$dir/redis.pid" --logfile "$dir/redis.log"
+trap '"$cli" -p "$port" shutdown nosave >/dev/null 2>&1 || true; rm -rf "$dir"' EXIT
+until "$cli" -p "$port" ping >/dev/null 2>&1; do
+    sleep 0.1
+done
+
+run() {
+    "$cli" -p "$port" "$@" >/dev/null
+}
+
+long=$(printf 'a%.0s' $(seq 1 200))
+
+run set string value
+run set compressed "$long"
+run set volatile value
+run expireat volatile 4102444800
+run rpush list a b c 1 2
+for i in $(seq 1 20); do
+    run rpush quicklist "element-$i-$long"
+done
+run sadd intset 1 2 3 -5 100000
+run sadd set a b c
+run hset hash field value number 10
+run zadd zset 1 a 2.5 b -3 c
+run select 1
+run set other_db value
+
+run save
+cp "$dir/dump.rdb" "$output"
+echo "RDB generado en $output"
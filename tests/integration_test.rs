@@ -1,4 +1,9 @@
 use proyecto_taller_1::run_redis;
+use std::collections::HashMap;
+use std::fs;
+use std::io::{ErrorKind, Read, Write};
+use std::net::TcpStream;
+use std::path::Path;
 use std::thread;
 use std::time::Duration;
 
@@ -150,3 +155,593 @@ fn test_info_shows_connected_clients() {
         .unwrap();
     assert_eq!(info, 1);
 }
+
+#[ignore]
+#[test]
+fn test_replica_receives_master_writes() {
+    fs::write(
+        "test_master.conf",
+        "port 8081\ndbfilename test_master.rdb\nlogfile test_master.log\nsave \"\"\n",
+    )
+    .unwrap();
+    fs::write(
+        "test_replica.conf",
+        "port 8082\ndbfilename test_replica.rdb\nlogfile test_replica.log\nsave \"\"\n\
+         replicaof 127.0.0.1 8081\n",
+    )
+    .unwrap();
+
+    let _master_thread = thread::spawn(move || {
+        run_redis(vec!["test_master.conf".to_string()]).unwrap();
+    });
+    thread::sleep(Duration::from_millis(500));
+
+    let master = redis::Client::open("redis://localhost:8081/").unwrap();
+    let mut master_con = master.get_connection().unwrap();
+    let _set: () = redis::cmd("SET")
+        .arg("before")
+        .arg(1)
+        .query(&mut master_con)
+        .unwrap();
+
+    let _replica_thread = thread::spawn(move || {
+        run_redis(vec!["test_replica.conf".to_string()]).unwrap();
+    });
+    thread::sleep(Duration::from_secs(2));
+
+    let _set: () = redis::cmd("SET")
+        .arg("after")
+        .arg(2)
+        .query(&mut master_con)
+        .unwrap();
+    let _transaction: () = redis::pipe()
+        .atomic()
+        .cmd("SET")
+        .arg("in_transaction")
+        .arg(3)
+        .ignore()
+        .cmd("INCR")
+        .arg("in_transaction")
+        .ignore()
+        .query(&mut master_con)
+        .unwrap();
+    thread::sleep(Duration::from_millis(500));
+
+    let replica = redis::Client::open("redis://localhost:8082/").unwrap();
+    let mut replica_con = replica.get_connection().unwrap();
+    let before: i32 = redis::cmd("GET")
+        .arg("before")
+        .query(&mut replica_con)
+        .unwrap();
+    let after: i32 = redis::cmd("GET")
+        .arg("after")
+        .query(&mut replica_con)
+        .unwrap();
+    let in_transaction: i32 = redis::cmd("GET")
+        .arg("in_transaction")
+        .query(&mut replica_con)
+        .unwrap();
+    let write: Result<(), _> = redis::cmd("SET").arg("key").arg(3).query(&mut replica_con);
+
+    for file in &[
+        "test_master.conf",
+        "test_replica.conf",
+        "test_master.log",
+        "test_replica.log",
+    ] {
+        let _ = fs::remove_file(file);
+    }
+
+    assert_eq!(before, 1);
+    assert_eq!(after, 2);
+    assert_eq!(in_transaction, 4);
+    assert!(write.is_err());
+}
+
+#[ignore]
+#[test]
+fn test_cluster_redirects_and_migrates_slot() {
+    for (name, port) in &[("test_node_a", 8083), ("test_node_b", 8084)] {
+        fs::write(
+            format!("{}.conf", name),
+            format!(
+                "port {}\ndbfilename {}.rdb\nlogfile {}.log\nsave \"\"\ncluster-enabled yes\n\
+                 cluster-config-file {}-nodes.conf\n",
+                port, name, name, name
+            ),
+        )
+        .unwrap();
+        let conf = format!("{}.conf", name);
+        let _node_thread = thread::spawn(move || {
+            run_redis(vec![conf]).unwrap();
+        });
+    }
+    thread::sleep(Duration::from_millis(500));
+
+    let mut node_a = redis::Client::open("redis://localhost:8083/")
+        .unwrap()
+        .get_connection()
+        .unwrap();
+    let mut node_b = redis::Client::open("redis://localhost:8084/")
+        .unwrap()
+        .get_connection()
+        .unwrap();
+    let _addslots: () = redis::cmd("CLUSTER")
+        .arg("ADDSLOTSRANGE")
+        .arg(0)
+        .arg(16383)
+        .query(&mut node_a)
+        .unwrap();
+    let _meet: () = redis::cmd("CLUSTER")
+        .arg("MEET")
+        .arg("127.0.0.1")
+        .arg(8084)
+        .query(&mut node_a)
+        .unwrap();
+    thread::sleep(Duration::from_secs(3));
+
+    let id_a: String = redis::cmd("CLUSTER")
+        .arg("MYID")
+        .query(&mut node_a)
+        .unwrap();
+    let id_b: String = redis::cmd("CLUSTER")
+        .arg("MYID")
+        .query(&mut node_b)
+        .unwrap();
+    let slot: u16 = redis::cmd("CLUSTER")
+        .arg("KEYSLOT")
+        .arg("key")
+        .query(&mut node_a)
+        .unwrap();
+    let _set: () = redis::cmd("SET")
+        .arg("key")
+        .arg(42)
+        .query(&mut node_a)
+        .unwrap();
+    let moved: Result<i32, _> = redis::cmd("GET").arg("key").query(&mut node_b);
+
+    let _importing: () = redis::cmd("CLUSTER")
+        .arg("SETSLOT")
+        .arg(slot)
+        .arg("IMPORTING")
+        .arg(&id_a)
+        .query(&mut node_b)
+        .unwrap();
+    let _migrating: () = redis::cmd("CLUSTER")
+        .arg("SETSLOT")
+        .arg(slot)
+        .arg("MIGRATING")
+        .arg(&id_b)
+        .query(&mut node_a)
+        .unwrap();
+    let _migrate: () = redis::cmd("MIGRATE")
+        .arg("127.0.0.1")
+        .arg(8084)
+        .arg("key")
+        .arg(0)
+        .arg(1000)
+        .query(&mut node_a)
+        .unwrap();
+    let ask: Result<i32, _> = redis::cmd("GET").arg("key").query(&mut node_a);
+    for node in &mut [&mut node_b, &mut node_a] {
+        let _node: () = redis::cmd("CLUSTER")
+            .arg("SETSLOT")
+            .arg(slot)
+            .arg("NODE")
+            .arg(&id_b)
+            .query(*node)
+            .unwrap();
+    }
+    let get: i32 = redis::cmd("GET").arg("key").query(&mut node_b).unwrap();
+    let moved_to_b: Result<i32, _> = redis::cmd("GET").arg("key").query(&mut node_a);
+
+    for name in &["test_node_a", "test_node_b"] {
+        let _ = fs::remove_file(format!("{}.conf", name));
+        let _ = fs::remove_file(format!("{}.log", name));
+        let _ = fs::remove_file(format!("{}-nodes.conf", name));
+    }
+
+    assert!(moved.unwrap_err().to_string().contains("8083"));
+    assert!(ask.unwrap_err().to_string().contains("8084"));
+    assert_eq!(get, 42);
+    assert!(moved_to_b.unwrap_err().to_string().contains("8084"));
+}
+
+/// Envía un comando RESP por la conexión y lee la cantidad de bytes de la respuesta esperada.
+#[allow(dead_code)]
+fn send_and_read(stream: &mut TcpStream, args: &[&str], expected: &[u8]) -> Vec<u8> {
+    let mut command = format!("*{}\r\n", args.len());
+    for arg in args {
+        command.push_str(&format!("${}\r\n{}\r\n", arg.len(), arg));
+    }
+    stream.write_all(command.as_bytes()).unwrap();
+    read_bytes(stream, expected.len())
+}
+
+#[allow(dead_code)]
+fn read_bytes(stream: &mut TcpStream, len: usize) -> Vec<u8> {
+    let mut buffer = vec![0; len];
+    stream.read_exact(&mut buffer).unwrap();
+    buffer
+}
+
+#[ignore]
+#[test]
+fn test_subscribed_client_keeps_issuing_commands() {
+    fs::write(
+        "test_pubsub.conf",
+        "port 8085\ndbfilename test_pubsub.rdb\nlogfile test_pubsub.log\nsave \"\"\n",
+    )
+    .unwrap();
+    let _redis_thread = thread::spawn(move || {
+        run_redis(vec!["test_pubsub.conf".to_string()]).unwrap();
+    });
+    thread::sleep(Duration::from_millis(500));
+
+    let mut publisher = redis::Client::open("redis://localhost:8085/")
+        .unwrap()
+        .get_connection()
+        .unwrap();
+    let mut subscriber = TcpStream::connect("127.0.0.1:8085").unwrap();
+    subscriber
+        .set_read_timeout(Some(Duration::from_secs(2)))
+        .unwrap();
+
+    let subscribe = b"*3\r\n$9\r\nsubscribe\r\n$4\r\nnews\r\n:1\r\n";
+    let psubscribe = b"*3\r\n$10\r\npsubscribe\r\n$8\r\nalerts.*\r\n:2\r\n";
+    let not_allowed = b"-ERR Can't execute 'get'";
+    let pong = b"*2\r\n$4\r\npong\r\n$0\r\n\r\n";
+    let message = b"*3\r\n$7\r\nmessage\r\n$4\r\nnews\r\n$5\r\nhello\r\n";
+    let pmessage = b"*4\r\n$8\r\npmessage\r\n$8\r\nalerts.*\r\n$11\r\nalerts.fire\r\n$3\r\nhot\r\n";
+    let unsubscribe = b"*3\r\n$11\r\nunsubscribe\r\n$4\r\nnews\r\n:1\r\n";
+    let punsubscribe = b"*3\r\n$12\r\npunsubscribe\r\n$8\r\nalerts.*\r\n:0\r\n";
+    let nil = b"$-1\r\n";
+
+    let subscribe_reply = send_and_read(&mut subscriber, &["SUBSCRIBE", "news"], subscribe);
+    let psubscribe_reply = send_and_read(&mut subscriber, &["PSUBSCRIBE", "alerts.*"], psubscribe);
+    let get_reply = send_and_read(&mut subscriber, &["GET", "key"], not_allowed);
+    let mut rest_of_error = vec![];
+    while !rest_of_error.ends_with(b"\r\n") {
+        rest_of_error.append(&mut read_bytes(&mut subscriber, 1));
+    }
+    let ping_reply = send_and_read(&mut subscriber, &["PING"], pong);
+
+    let news: i32 = redis::cmd("PUBLISH")
+        .arg("news")
+        .arg("hello")
+        .query(&mut publisher)
+        .unwrap();
+    let message_reply = read_bytes(&mut subscriber, message.len());
+    let alerts: i32 = redis::cmd("PUBLISH")
+        .arg("alerts.fire")
+        .arg("hot")
+        .query(&mut publisher)
+        .unwrap();
+    let pmessage_reply = read_bytes(&mut subscriber, pmessage.len());
+
+    let unsubscribe_reply = send_and_read(&mut subscriber, &["UNSUBSCRIBE"], unsubscribe);
+    let punsubscribe_reply = send_and_read(&mut subscriber, &["PUNSUBSCRIBE"], punsubscribe);
+    let get_after = send_and_read(&mut subscriber, &["GET", "key"], nil);
+    let _psubscribe = send_and_read(&mut subscriber, &["PSUBSCRIBE", "alerts.*"], psubscribe);
+    drop(subscriber);
+    thread::sleep(Duration::from_millis(200));
+    let numpat: i32 = redis::cmd("PUBSUB")
+        .arg("NUMPAT")
+        .query(&mut publisher)
+        .unwrap();
+
+    let _ = fs::remove_file("test_pubsub.conf");
+    let _ = fs::remove_file("test_pubsub.log");
+
+    assert_eq!(subscribe_reply, subscribe);
+    assert_eq!(psubscribe_reply, psubscribe);
+    assert_eq!(get_reply, not_allowed);
+    assert_eq!(ping_reply, pong);
+    assert_eq!((news, alerts), (1, 1));
+    assert_eq!(message_reply, message);
+    assert_eq!(pmessage_reply, pmessage);
+    assert_eq!(unsubscribe_reply, unsubscribe);
+    assert_eq!(punsubscribe_reply, punsubscribe);
+    assert_eq!(get_after, nil);
+    assert_eq!(numpat, 0);
+}
+
+#[ignore]
+#[test]
+fn test_select_is_kept_per_connection() {
+    fs::write(
+        "test_select.conf",
+        "port 8086\ndbfilename test_select.rdb\nlogfile test_select.log\nsave \"\"\ndatabases 4\n",
+    )
+    .unwrap();
+    let _redis_thread = thread::spawn(move || {
+        run_redis(vec!["test_select.conf".to_string()]).unwrap();
+    });
+    thread::sleep(Duration::from_millis(500));
+
+    let client = redis::Client::open("redis://localhost:8086/").unwrap();
+    let mut first = client.get_connection().unwrap();
+    let mut second = client.get_connection().unwrap();
+
+    let _select: () = redis::cmd("SELECT").arg(2).query(&mut first).unwrap();
+    let _set: () = redis::cmd("SET")
+        .arg("key")
+        .arg(42)
+        .query(&mut first)
+        .unwrap();
+    let in_db0: Option<i32> = redis::cmd("GET").arg("key").query(&mut second).unwrap();
+    let in_db2: i32 = redis::cmd("GET").arg("key").query(&mut first).unwrap();
+    let out_of_range = redis::cmd("SELECT").arg(4).query::<()>(&mut second);
+    let moved: i32 = redis::cmd("MOVE")
+        .arg("key")
+        .arg(0)
+        .query(&mut first)
+        .unwrap();
+    let after_move: i32 = redis::cmd("GET").arg("key").query(&mut second).unwrap();
+
+    let _ = fs::remove_file("test_select.conf");
+    let _ = fs::remove_file("test_select.log");
+
+    assert_eq!(in_db0, None);
+    assert_eq!(in_db2, 42);
+    assert!(out_of_range.is_err());
+    assert_eq!(moved, 1);
+    assert_eq!(after_move, 42);
+}
+
+#[ignore]
+#[test]
+fn test_blocking_pop_waits_for_push() {
+    fs::write(
+        "test_blocking.conf",
+        "port 8087\ndbfilename test_blocking.rdb\nlogfile test_blocking.log\nsave \"\"\n",
+    )
+    .unwrap();
+    let _redis_thread = thread::spawn(move || {
+        run_redis(vec!["test_blocking.conf".to_string()]).unwrap();
+    });
+    thread::sleep(Duration::from_millis(500));
+
+    let client = redis::Client::open("redis://localhost:8087/").unwrap();
+    let mut pusher = client.get_connection().unwrap();
+    let mut waiter = client.get_connection().unwrap();
+    let waiter_thread = thread::spawn(move || {
+        let popped: (String, String) = redis::cmd("BLPOP")
+            .arg("jobs")
+            .arg(0)
+            .query(&mut waiter)
+            .unwrap();
+        popped
+    });
+
+    let mut abandoned = TcpStream::connect("127.0.0.1:8087").unwrap();
+    let _blpop = abandoned.write_all(b"*3\r\n$5\r\nBLPOP\r\n$6\r\nqueued\r\n$1\r\n0\r\n");
+    thread::sleep(Duration::from_millis(200));
+    drop(abandoned);
+    thread::sleep(Duration::from_millis(300));
+
+    let timed_out: Option<(String, String)> = redis::cmd("BRPOP")
+        .arg("empty")
+        .arg(0.2)
+        .query(&mut pusher)
+        .unwrap();
+    let _rpush: () = redis::cmd("RPUSH")
+        .arg("jobs")
+        .arg("job")
+        .query(&mut pusher)
+        .unwrap();
+    let popped = waiter_thread.join().unwrap();
+    let _rpush: () = redis::cmd("RPUSH")
+        .arg("queued")
+        .arg("job")
+        .query(&mut pusher)
+        .unwrap();
+    let queued: i32 = redis::cmd("LLEN").arg("queued").query(&mut pusher).unwrap();
+
+    let _ = fs::remove_file("test_blocking.conf");
+    let _ = fs::remove_file("test_blocking.log");
+
+    assert_eq!(timed_out, None);
+    assert_eq!(popped, ("jobs".to_string(), "job".to_string()));
+    assert_eq!(queued, 1);
+}
+
+#[ignore]
+#[test]
+fn test_blocked_client_keeps_idle_timeout() {
+    fs::write(
+        "test_timeout.conf",
+        "port 8088\ndbfilename test_timeout.rdb\nlogfile test_timeout.log\nsave \"\"\ntimeout 1\n",
+    )
+    .unwrap();
+    let _redis_thread = thread::spawn(move || {
+        run_redis(vec!["test_timeout.conf".to_string()]).unwrap();
+    });
+    thread::sleep(Duration::from_millis(500));
+
+    let mut client = TcpStream::connect("127.0.0.1:8088").unwrap();
+    let timed_out = send_and_read(&mut client, &["BLPOP", "empty", "0.5"], b"*-1\r\n");
+    thread::sleep(Duration::from_millis(2500));
+    client
+        .set_read_timeout(Some(Duration::from_secs(3)))
+        .unwrap();
+    let mut buffer = [0; 1];
+    let closed = client.read(&mut buffer);
+
+    let _ = fs::remove_file("test_timeout.conf");
+    let _ = fs::remove_file("test_timeout.log");
+
+    assert_eq!(timed_out, b"*-1\r\n");
+    match closed {
+        Ok(read) => assert_eq!(read, 0),
+        Err(e) => assert!(!matches!(
+            e.kind(),
+            ErrorKind::WouldBlock | ErrorKind::TimedOut
+        )),
+    }
+}
+
+#[ignore]
+#[test]
+fn test_subscriber_receives_messages_in_publish_order() {
+    fs::write(
+        "test_pubsub_order.conf",
+        "port 8089\ndbfilename test_pubsub_order.rdb\nlogfile test_pubsub_order.log\nsave \"\"\n",
+    )
+    .unwrap();
+    let _redis_thread = thread::spawn(move || {
+        run_redis(vec!["test_pubsub_order.conf".to_string()]).unwrap();
+    });
+    thread::sleep(Duration::from_millis(500));
+
+    let mut publisher = redis::Client::open("redis://localhost:8089/")
+        .unwrap()
+        .get_connection()
+        .unwrap();
+    let mut subscriber = TcpStream::connect("127.0.0.1:8089").unwrap();
+    subscriber
+        .set_read_timeout(Some(Duration::from_secs(2)))
+        .unwrap();
+    let subscribe_a = b"*3\r\n$9\r\nsubscribe\r\n$1\r\na\r\n:1\r\n";
+    let subscribe_b = b"*3\r\n$9\r\nsubscribe\r\n$1\r\nb\r\n:2\r\n";
+    let subscribe_a_reply = send_and_read(&mut subscriber, &["SUBSCRIBE", "a"], subscribe_a);
+    let subscribe_b_reply = send_and_read(&mut subscriber, &["SUBSCRIBE", "b"], subscribe_b);
+
+    // Cada mensaje se publica en un canal distinto al anterior, todos en un mismo pipeline.
+    let messages: Vec<(&str, String)> = (0..1000)
+        .map(|i| (if i % 2 == 0 { "a" } else { "b" }, format!("{:03}", i)))
+        .collect();
+    let mut pipe = redis::pipe();
+    for (channel, message) in &messages {
+        pipe.cmd("PUBLISH").arg(*channel).arg(message).ignore();
+    }
+    let _: () = pipe.query(&mut publisher).unwrap();
+    let expected: Vec<u8> = messages
+        .iter()
+        .flat_map(|(channel, message)| {
+            format!(
+                "*3\r\n$7\r\nmessage\r\n$1\r\n{}\r\n$3\r\n{}\r\n",
+                channel, message
+            )
+            .into_bytes()
+        })
+        .collect();
+    let received = read_bytes(&mut subscriber, expected.len());
+
+    let _ = fs::remove_file("test_pubsub_order.conf");
+    let _ = fs::remove_file("test_pubsub_order.log");
+
+    assert_eq!(subscribe_a_reply, subscribe_a);
+    assert_eq!(subscribe_b_reply, subscribe_b);
+    assert_eq!(
+        String::from_utf8_lossy(&received),
+        String::from_utf8_lossy(&expected)
+    );
+}
+
+/// Levanta un servidor que carga el RDB de prueba indicado, generado con un redis-server real por
+/// `tests/fixtures/rdb/generate.sh`, y verifica su contenido. Si el archivo no se generó, no
+/// verifica nada.
+#[allow(dead_code)]
+fn assert_loads_rdb_fixture(fixture: &str, port: u16) {
+    let path = format!("tests/fixtures/rdb/{}", fixture);
+    if !Path::new(&path).exists() {
+        eprintln!(
+            "{} not found, generate it with tests/fixtures/rdb/generate.sh",
+            path
+        );
+        return;
+    }
+    let name = format!("test_fixture_{}", port);
+    fs::copy(&path, format!("{}.rdb", name)).unwrap();
+    fs::write(
+        format!("{}.conf", name),
+        format!(
+            "port {}\ndbfilename {}.rdb\nlogfile {}.log\nsave \"\"\n",
+            port, name, name
+        ),
+    )
+    .unwrap();
+    let conf = format!("{}.conf", name);
+    let _redis_thread = thread::spawn(move || {
+        run_redis(vec![conf]).unwrap();
+    });
+    thread::sleep(Duration::from_millis(500));
+
+    let client = redis::Client::open(format!("redis://localhost:{}/", port)).unwrap();
+    let mut con = client.get_connection().unwrap();
+    let string: String = redis::cmd("GET").arg("string").query(&mut con).unwrap();
+    let compressed: String = redis::cmd("GET").arg("compressed").query(&mut con).unwrap();
+    let ttl: i64 = redis::cmd("TTL").arg("volatile").query(&mut con).unwrap();
+    let list: Vec<String> = redis::cmd("LRANGE")
+        .arg("list")
+        .arg(0)
+        .arg(-1)
+        .query(&mut con)
+        .unwrap();
+    let quicklist: Vec<String> = redis::cmd("LRANGE")
+        .arg("quicklist")
+        .arg(0)
+        .arg(-1)
+        .query(&mut con)
+        .unwrap();
+    let mut intset: Vec<i64> = redis::cmd("SMEMBERS")
+        .arg("intset")
+        .query(&mut con)
+        .unwrap();
+    intset.sort_unstable();
+    let mut set: Vec<String> = redis::cmd("SMEMBERS").arg("set").query(&mut con).unwrap();
+    set.sort();
+    let hash: HashMap<String, String> = redis::cmd("HGETALL").arg("hash").query(&mut con).unwrap();
+    let zset: Vec<(String, f64)> = redis::cmd("ZRANGE")
+        .arg("zset")
+        .arg(0)
+        .arg(-1)
+        .arg("WITHSCORES")
+        .query(&mut con)
+        .unwrap();
+    let _select: () = redis::cmd("SELECT").arg(1).query(&mut con).unwrap();
+    let other_db: String = redis::cmd("GET").arg("other_db").query(&mut con).unwrap();
+
+    for extension in &["rdb", "conf", "log"] {
+        let _ = fs::remove_file(format!("{}.{}", name, extension));
+    }
+
+    let long = "a".repeat(200);
+    assert_eq!(string, "value");
+    assert_eq!(compressed, long);
+    assert!(ttl > 0);
+    assert_eq!(list, vec!["a", "b", "c", "1", "2"]);
+    assert_eq!(
+        quicklist,
+        (1..=20)
+            .map(|i| format!("element-{}-{}", i, long))
+            .collect::<Vec<String>>()
+    );
+    assert_eq!(intset, vec![-5, 1, 2, 3, 100000]);
+    assert_eq!(set, vec!["a", "b", "c"]);
+    assert_eq!(hash.len(), 2);
+    assert_eq!(hash["field"], "value");
+    assert_eq!(hash["number"], "10");
+    assert_eq!(
+        zset,
+        vec![
+            ("c".to_string(), -3.0),
+            ("a".to_string(), 1.0),
+            ("b".to_string(), 2.5)
+        ]
+    );
+    assert_eq!(other_db, "value");
+}
+
+#[ignore]
+#[test]
+fn test_loads_rdb_generated_by_redis_6() {
+    assert_loads_rdb_fixture("redis-6.2.rdb", 8091);
+}
+
+#[ignore]
+#[test]
+fn test_loads_rdb_generated_by_redis_7() {
+    assert_loads_rdb_fixture("redis-7.2.rdb", 8092);
+}